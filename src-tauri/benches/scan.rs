@@ -0,0 +1,61 @@
+//! Criterion benchmarks for the installed scan, manifest cache build and search.
+//!
+//! By default they run against a synthetic Scoop root. Set `SCOOP_BENCH_ROOT` to a real
+//! Scoop directory to measure against an actual install instead.
+use criterion::{criterion_group, criterion_main, Criterion};
+use scoopmeta_lib::bench;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SYNTHETIC_APPS: usize = 200;
+const SYNTHETIC_MANIFESTS: usize = 2000;
+
+fn write_synthetic_root(root: &Path) {
+    let bucket = root.join("buckets").join("main").join("bucket");
+    fs::create_dir_all(&bucket).unwrap();
+    for i in 0..SYNTHETIC_MANIFESTS {
+        let manifest = format!(
+            r#"{{"version": "1.{i}.0", "description": "Package {i}", "bin": ["tool{i}.exe"]}}"#
+        );
+        fs::write(bucket.join(format!("package{}.json", i)), manifest).unwrap();
+    }
+
+    for i in 0..SYNTHETIC_APPS {
+        let version_dir = root
+            .join("apps")
+            .join(format!("package{}", i))
+            .join("current");
+        fs::create_dir_all(&version_dir).unwrap();
+        fs::write(
+            version_dir.join("manifest.json"),
+            format!(r#"{{"version": "1.{i}.0", "description": "Package {i}"}}"#),
+        )
+        .unwrap();
+        fs::write(version_dir.join("install.json"), r#"{"bucket": "main"}"#).unwrap();
+    }
+}
+
+fn benchmarks(c: &mut Criterion) {
+    let synthetic = tempfile::tempdir().unwrap();
+    let root: PathBuf = match std::env::var_os("SCOOP_BENCH_ROOT") {
+        Some(path) => PathBuf::from(path),
+        None => {
+            write_synthetic_root(synthetic.path());
+            synthetic.path().to_path_buf()
+        }
+    };
+    let manifests = bench::manifest_cache_build(&root);
+
+    c.bench_function("installed_scan", |b| {
+        b.iter(|| bench::installed_scan(&root))
+    });
+    c.bench_function("manifest_cache_build", |b| {
+        b.iter(|| bench::manifest_cache_build(&root))
+    });
+    c.bench_function("search", |b| {
+        b.iter(|| bench::search(&manifests, bench::DEFAULT_SEARCH_TERM))
+    });
+}
+
+criterion_group!(benches, benchmarks);
+criterion_main!(benches);
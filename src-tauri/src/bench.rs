@@ -0,0 +1,124 @@
+//! Timing harness for the installed scan, manifest cache build and search.
+//!
+//! The same entry points back the criterion benches in `benches/` and the hidden
+//! `bench` command, which runs them against the user's own Scoop directory and keeps
+//! the last report so it shows up in debug info.
+use crate::commands::installed::{list_app_dirs, load_packages};
+use crate::commands::search::{build_manifest_index, match_manifests, IndexedManifest};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Search term used when none is given.
+pub const DEFAULT_SEARCH_TERM: &str = "git";
+
+static LAST_REPORT: Lazy<Mutex<Option<BenchReport>>> = Lazy::new(|| Mutex::new(None));
+
+/// Timings for one measured operation, in milliseconds.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchTiming {
+    pub name: String,
+    pub iterations: u32,
+    /// Number of items the operation produced (packages, manifests or matches).
+    pub items: usize,
+    pub min_ms: f64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Results of a full benchmark run.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchReport {
+    pub scoop_path: String,
+    pub search_term: String,
+    pub ran_at: String,
+    pub timings: Vec<BenchTiming>,
+}
+
+/// Scans and loads every installed package, returning the number loaded.
+pub fn installed_scan(scoop_path: &Path) -> usize {
+    list_app_dirs(&scoop_path.join("apps"))
+        .map(|dirs| load_packages(&dirs, scoop_path, "=== BENCH ===").len())
+        .unwrap_or(0)
+}
+
+/// Parses every bucket manifest into the search index, as the search cache warm-up does.
+pub fn manifest_cache_build(scoop_path: &Path) -> Vec<IndexedManifest> {
+    build_manifest_index(scoop_path).unwrap_or_default()
+}
+
+/// Runs a search against an already built manifest cache, returning the match count.
+pub fn search(manifests: &[IndexedManifest], term: &str) -> usize {
+    match_manifests(manifests, term)
+        .map(|packages| packages.len())
+        .unwrap_or(0)
+}
+
+fn measure(name: &str, iterations: u32, mut op: impl FnMut() -> usize) -> BenchTiming {
+    let iterations = iterations.max(1);
+    let mut samples = Vec::with_capacity(iterations as usize);
+    let mut items = 0;
+    for _ in 0..iterations {
+        let start = Instant::now();
+        items = op();
+        samples.push(start.elapsed().as_secs_f64() * 1000.0);
+    }
+
+    BenchTiming {
+        name: name.to_string(),
+        iterations,
+        items,
+        min_ms: samples.iter().cloned().fold(f64::INFINITY, f64::min),
+        mean_ms: samples.iter().sum::<f64>() / samples.len() as f64,
+        max_ms: samples.iter().cloned().fold(0.0, f64::max),
+    }
+}
+
+/// Runs every benchmark against `scoop_path` and stores the report for debug info.
+pub fn run(scoop_path: &Path, iterations: u32, search_term: &str) -> BenchReport {
+    log::info!(
+        "Running benchmarks against {} ({} iterations)",
+        scoop_path.display(),
+        iterations
+    );
+
+    let manifests = manifest_cache_build(scoop_path);
+    let timings = vec![
+        measure("installedScan", iterations, || installed_scan(scoop_path)),
+        measure("manifestCacheBuild", iterations, || {
+            manifest_cache_build(scoop_path).len()
+        }),
+        measure("search", iterations, || search(&manifests, search_term)),
+    ];
+
+    for timing in &timings {
+        log::info!(
+            "Benchmark {}: mean {:.2}ms (min {:.2}ms, max {:.2}ms, {} items)",
+            timing.name,
+            timing.mean_ms,
+            timing.min_ms,
+            timing.max_ms,
+            timing.items
+        );
+    }
+
+    let report = BenchReport {
+        scoop_path: scoop_path.display().to_string(),
+        search_term: search_term.to_string(),
+        ran_at: chrono::Local::now().to_rfc3339(),
+        timings,
+    };
+    if let Ok(mut last) = LAST_REPORT.lock() {
+        *last = Some(report.clone());
+    }
+    report
+}
+
+/// Returns the report of the most recent run in this session.
+pub fn last_report() -> Option<BenchReport> {
+    LAST_REPORT.lock().ok().and_then(|last| last.clone())
+}
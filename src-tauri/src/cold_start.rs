@@ -35,7 +35,7 @@ pub fn run_cold_start<R: Runtime>(app: AppHandle<R>) {
         let state = app.state::<AppState>();
         log::info!("Getting AppState for cold start initialization");
         
-        match crate::commands::installed::get_installed_packages_full(app.clone(), state).await {
+        match crate::commands::installed::warm_installed_packages(app.clone(), &state).await {
             Ok(pkgs) => {
                 log::info!("Prefetched {} installed packages", pkgs.len());
 
@@ -47,6 +47,19 @@ pub fn run_cold_start<R: Runtime>(app: AppHandle<R>) {
                     log::info!("Search manifest cache warmed successfully");
                 }
 
+                // Prewarm the bucket search cache without delaying the ready events.
+                let prewarm_app = app.clone();
+                crate::supervisor::spawn_supervised("bucketCachePrewarm", move || {
+                    crate::commands::bucket_parser::prewarm_bucket_cache(prewarm_app.clone())
+                });
+
+                // Look for broken junctions and missing buckets without delaying the ready events.
+                let check_app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    crate::commands::broken_installs::check_broken_installs(&check_app).await;
+                    crate::commands::bucket_rehome::check_orphaned_installs(&check_app).await;
+                });
+
                 // Emit events with retry logic
                 log::info!("Emitting cold start success events");
                 emit_ready_events_with_retry(&app, true).await;
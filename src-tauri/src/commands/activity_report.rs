@@ -0,0 +1,256 @@
+//! Weekly summary of what Scoop did on this machine.
+//!
+//! Composed from the update log (applied updates) and the operation history
+//! (installs, cleanups and failures); nothing is tracked specifically for it.
+use crate::commands::operation_log::{list_operation_logs, OperationLogSummary};
+use crate::commands::powershell::OperationClass;
+use crate::commands::update_log::{read_update_log, UpdateLogEntry};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Emitted with the [`ActivityReport`] when it is requested as a notification.
+pub const EVENT_ACTIVITY_REPORT: &str = "activity-report";
+const DEFAULT_PERIOD_DAYS: u32 = 7;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatedPackage {
+    pub package: String,
+    pub from_version: String,
+    pub to_version: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedOperation {
+    pub log_id: String,
+    pub operation_name: String,
+    pub started_at: String,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityReport {
+    pub period_start: String,
+    pub period_end: String,
+    /// Newest version per package when it was updated several times.
+    pub updated_packages: Vec<UpdatedPackage>,
+    pub new_installs: Vec<String>,
+    pub reclaimed_bytes: u64,
+    pub total_operations: usize,
+    pub failed_operations: Vec<FailedOperation>,
+}
+
+/// Package names an install command was run for, without bucket prefixes.
+fn installed_packages(command: &str) -> Vec<String> {
+    let mut words = command.split_whitespace();
+    if !words.by_ref().any(|w| w.eq_ignore_ascii_case("install")) {
+        return Vec::new();
+    }
+    words
+        .filter(|w| !w.starts_with('-'))
+        .map(|w| w.rsplit('/').next().unwrap_or(w).to_string())
+        .collect()
+}
+
+fn collapse_updates(entries: Vec<UpdateLogEntry>) -> Vec<UpdatedPackage> {
+    let mut updated: Vec<UpdatedPackage> = Vec::new();
+    // The log is oldest first, so later entries extend earlier ones
+    for entry in entries {
+        match updated.iter_mut().find(|u| u.package == entry.package) {
+            Some(existing) => existing.to_version = entry.to_version,
+            None => updated.push(UpdatedPackage {
+                package: entry.package,
+                from_version: entry.from_version,
+                to_version: entry.to_version,
+            }),
+        }
+    }
+    updated.sort_by(|a, b| a.package.cmp(&b.package));
+    updated
+}
+
+/// Builds the report for the operations and updates that started after `since`.
+pub fn build_report(
+    since: DateTime<Utc>,
+    now: DateTime<Utc>,
+    updates: Vec<UpdateLogEntry>,
+    operations: &[OperationLogSummary],
+) -> ActivityReport {
+    let since_secs = since.timestamp().max(0) as u64;
+    let updates = updates
+        .into_iter()
+        .filter(|entry| entry.timestamp >= since_secs)
+        .collect();
+
+    let recent: Vec<&OperationLogSummary> = operations
+        .iter()
+        .filter(|op| {
+            DateTime::parse_from_rfc3339(&op.started_at)
+                .map(|started| started >= since)
+                .unwrap_or(false)
+        })
+        .collect();
+
+    let mut new_installs: Vec<String> = recent
+        .iter()
+        .filter(|op| op.class == Some(OperationClass::Install) && op.success == Some(true))
+        .flat_map(|op| installed_packages(&op.command))
+        .collect();
+    new_installs.sort();
+    new_installs.dedup();
+
+    let mut failed_operations: Vec<FailedOperation> = recent
+        .iter()
+        .filter(|op| op.success == Some(false))
+        .map(|op| FailedOperation {
+            log_id: op.log_id.clone(),
+            operation_name: op.operation_name.clone(),
+            started_at: op.started_at.clone(),
+            message: op.message.clone(),
+        })
+        .collect();
+    failed_operations.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+
+    ActivityReport {
+        period_start: since.to_rfc3339(),
+        period_end: now.to_rfc3339(),
+        updated_packages: collapse_updates(updates),
+        new_installs,
+        reclaimed_bytes: recent.iter().filter_map(|op| op.freed_bytes).sum(),
+        total_operations: recent.len(),
+        failed_operations,
+    }
+}
+
+/// Summarizes the last `days` (7 by default) of activity.
+///
+/// With `notify` the report is also emitted as [`EVENT_ACTIVITY_REPORT`] so the UI can
+/// show it as a toast, unless do-not-disturb is active.
+#[tauri::command]
+pub async fn generate_activity_report<R: Runtime>(
+    app: AppHandle<R>,
+    days: Option<u32>,
+    notify: Option<bool>,
+) -> Result<ActivityReport, String> {
+    let now = Utc::now();
+    let since = now - Duration::days(days.unwrap_or(DEFAULT_PERIOD_DAYS).max(1) as i64);
+
+    let report = tokio::task::spawn_blocking(move || {
+        let operations = list_operation_logs().unwrap_or_default();
+        build_report(since, now, read_update_log(), &operations)
+    })
+    .await
+    .map_err(|e| format!("Failed to build activity report: {}", e))?;
+
+    log::info!(
+        "Activity report: {} updated, {} installed, {} failed, {} bytes reclaimed",
+        report.updated_packages.len(),
+        report.new_installs.len(),
+        report.failed_operations.len(),
+        report.reclaimed_bytes
+    );
+
+    if notify.unwrap_or(false) && !crate::quiet::is_active() {
+        if let Err(e) = app.emit(EVENT_ACTIVITY_REPORT, &report) {
+            log::warn!("Failed to emit {} event: {}", EVENT_ACTIVITY_REPORT, e);
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operation(
+        started_at: DateTime<Utc>,
+        class: OperationClass,
+        command: &str,
+        success: bool,
+        freed_bytes: Option<u64>,
+    ) -> OperationLogSummary {
+        OperationLogSummary {
+            log_id: format!("{}-op", started_at.timestamp_millis()),
+            operation_id: None,
+            operation_name: command.to_string(),
+            command: command.to_string(),
+            started_at: started_at.to_rfc3339(),
+            finished_at: None,
+            success: Some(success),
+            timed_out: false,
+            message: None,
+            line_count: 0,
+            env_changes: Vec::new(),
+            class: Some(class),
+            freed_bytes,
+            uninstall_verification: None,
+            milestones: Vec::new(),
+        }
+    }
+
+    fn update(package: &str, from: &str, to: &str, at: DateTime<Utc>) -> UpdateLogEntry {
+        UpdateLogEntry {
+            package: package.to_string(),
+            from_version: from.to_string(),
+            to_version: to.to_string(),
+            timestamp: at.timestamp() as u64,
+            duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn summarizes_the_period() {
+        let now = Utc::now();
+        let since = now - Duration::days(7);
+        let old = now - Duration::days(10);
+        let recent = now - Duration::days(1);
+
+        let operations = [
+            operation(
+                recent,
+                OperationClass::Install,
+                "scoop install extras/vlc",
+                true,
+                None,
+            ),
+            operation(
+                old,
+                OperationClass::Install,
+                "scoop install git",
+                true,
+                None,
+            ),
+            operation(
+                recent,
+                OperationClass::Cleanup,
+                "scoop cleanup --all",
+                true,
+                Some(300),
+            ),
+            operation(
+                recent,
+                OperationClass::Update,
+                "scoop update node",
+                false,
+                None,
+            ),
+        ];
+        let updates = vec![
+            update("node", "20.0.0", "20.1.0", old),
+            update("python", "3.12.0", "3.12.1", recent),
+            update("python", "3.12.1", "3.12.2", now),
+        ];
+
+        let report = build_report(since, now, updates, &operations);
+        assert_eq!(report.new_installs, vec!["vlc"]);
+        assert_eq!(report.reclaimed_bytes, 300);
+        assert_eq!(report.total_operations, 3);
+        assert_eq!(report.failed_operations.len(), 1);
+        assert_eq!(report.updated_packages.len(), 1);
+        assert_eq!(report.updated_packages[0].from_version, "3.12.0");
+        assert_eq!(report.updated_packages[0].to_version, "3.12.2");
+    }
+}
@@ -1,8 +1,106 @@
+use crate::engine::manifest::locate_package_manifest;
+use crate::engine::version::compare_versions;
+use crate::models::SelfUpdateInfo;
+use crate::state::AppState;
 use crate::utils;
+use std::cmp::Ordering;
+use std::path::{Component, Path, PathBuf};
 use tauri;
+use tauri::{AppHandle, State};
 
 /// Checks if the application is installed via Scoop package manager
 #[tauri::command]
 pub fn is_scoop_installation() -> bool {
     utils::is_scoop_installation()
 }
+
+/// Splits an executable path inside a Scoop install into the package name and its
+/// version directory (`apps/<name>/<version>`).
+fn scoop_install_of(exe_path: &Path) -> Option<(String, PathBuf)> {
+    let components: Vec<Component> = exe_path.components().collect();
+    let apps_index = components
+        .iter()
+        .rposition(|c| c.as_os_str().eq_ignore_ascii_case("apps"))?;
+    let name = components.get(apps_index + 1)?.as_os_str();
+    components.get(apps_index + 2)?;
+
+    let version_dir: PathBuf = components[..apps_index + 3].iter().collect();
+    Some((name.to_string_lossy().to_string(), version_dir))
+}
+
+/// Looks up this app's manifest in the bucket it was installed from and reports a
+/// newer version, if any.
+///
+/// The built-in updater is disabled for Scoop installs, so this is the only way such
+/// users learn about new releases.
+pub fn find_self_update(
+    exe_path: &Path,
+    scoop_path: &Path,
+    current_version: &str,
+) -> Option<SelfUpdateInfo> {
+    let (package_name, version_dir) = scoop_install_of(exe_path)?;
+    let install: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(version_dir.join("install.json")).ok()?)
+            .ok()?;
+    let bucket = install.get("bucket")?.as_str()?.to_string();
+
+    let (manifest_path, _) =
+        locate_package_manifest(scoop_path, &package_name, Some(bucket.clone())).ok()?;
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(manifest_path).ok()?).ok()?;
+    let latest_version = manifest.get("version")?.as_str()?.to_string();
+
+    if compare_versions(&latest_version, current_version) != Ordering::Greater {
+        return None;
+    }
+    log::info!(
+        "A newer {} ({}) is available in bucket '{}'",
+        package_name,
+        latest_version,
+        bucket
+    );
+    Some(SelfUpdateInfo {
+        package_name,
+        current_version: current_version.to_string(),
+        latest_version,
+        bucket,
+    })
+}
+
+/// Reports a newer version of this app in its Scoop bucket; `None` when up to date or
+/// not installed through Scoop.
+#[tauri::command]
+pub fn check_self_manifest_update(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Option<SelfUpdateInfo> {
+    if !utils::is_scoop_installation() {
+        return None;
+    }
+    let exe_path = std::env::current_exe().ok()?;
+    find_self_update(
+        &exe_path,
+        &state.scoop_path(),
+        &app.package_info().version.to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScoopFixture;
+
+    #[test]
+    fn reports_newer_version_in_bucket() {
+        let fixture = ScoopFixture::new();
+        fixture
+            .bucket_manifest("extras", "pailer", "1.2.0")
+            .install("pailer", "1.1.0", "extras");
+        let exe = fixture.app_dir("pailer").join("1.1.0").join("pailer.exe");
+
+        let update = find_self_update(&exe, fixture.root(), "1.1.0").unwrap();
+        assert_eq!(update.latest_version, "1.2.0");
+        assert_eq!(update.bucket, "extras");
+        assert!(find_self_update(&exe, fixture.root(), "1.2.0").is_none());
+    }
+}
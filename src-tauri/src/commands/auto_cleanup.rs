@@ -1,8 +1,10 @@
 //! Commands for automatic cleanup based on user settings.
-use crate::commands::installed::get_installed_packages_full;
+use crate::commands::installed::get_installed_packages;
 use crate::commands::powershell;
 use crate::commands::settings;
+use crate::commands::version_pin::load_version_pins;
 use crate::state::AppState;
+use crate::utils::ScoopDirs;
 use serde::Deserialize;
 use std::path::PathBuf;
 use tauri::{AppHandle, Runtime, State};
@@ -38,12 +40,15 @@ pub async fn run_auto_cleanup<R: Runtime>(
     log::info!("Running auto cleanup with settings: {:?}", settings);
 
     // Get all installed packages to identify versioned installs
-    let installed_packages = get_installed_packages_full(app.clone(), state.clone()).await?;
+    let installed_packages = get_installed_packages(app.clone(), state.clone()).await?;
 
-    // Separate regular packages from versioned installs
+    // Separate regular packages from versioned installs; broken installs are left alone,
+    // and so are pinned packages, whose pinned version may not be the newest
+    let pins = load_version_pins(&app);
     let regular_packages: Vec<String> = installed_packages
         .iter()
-        .filter(|pkg| !pkg.is_versioned_install)
+        .filter(|pkg| !pkg.is_versioned_install && !pkg.broken)
+        .filter(|pkg| !pins.contains_key(&pkg.name))
         .map(|pkg| pkg.name.clone())
         .collect();
 
@@ -93,7 +98,7 @@ async fn cleanup_old_versions_smart(
     packages: &[String],
     keep_count: usize,
 ) -> Result<(), String> {
-    let apps_path = scoop_path.join("apps");
+    let apps_path = ScoopDirs::resolve(scoop_path).apps;
 
     for package_name in packages {
         let package_path = apps_path.join(package_name);
@@ -151,13 +156,13 @@ fn get_versions_to_remove(
 }
 
 async fn remove_specific_versions(scoop_path: &PathBuf, package_name: &str, versions: &[String]) {
-    let package_dir = scoop_path.join("apps").join(package_name);
+    let package_dir = ScoopDirs::resolve(scoop_path).apps.join(package_name);
 
     for version in versions {
         let version_dir = package_dir.join(version);
         log::info!("Removing old version directory: {}", version_dir.display());
 
-        if let Err(e) = std::fs::remove_dir_all(&version_dir) {
+        if let Err(e) = crate::recycle::remove_path(&version_dir, crate::recycle::is_enabled()) {
             log::warn!(
                 "Failed to remove version directory {}: {}",
                 version_dir.display(),
@@ -300,3 +305,32 @@ fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
     
     std::cmp::Ordering::Equal
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScoopFixture;
+
+    #[test]
+    fn keeps_newest_versions_and_ignores_current_link() {
+        let fixture = ScoopFixture::new();
+        for version in ["1.9.0", "1.10.0", "1.2.0", "2.0.0"] {
+            fixture.app_version("ripgrep", version, Some("main"));
+        }
+        fixture.link_current("ripgrep", "2.0.0");
+
+        let mut removed = get_versions_to_remove(&fixture.app_dir("ripgrep"), 2).unwrap();
+        removed.sort();
+        assert_eq!(removed, ["1.2.0", "1.9.0"]);
+    }
+
+    #[test]
+    fn nothing_removed_within_retention() {
+        let fixture = ScoopFixture::new();
+        fixture.install("ripgrep", "14.1.0", "main");
+
+        assert!(get_versions_to_remove(&fixture.app_dir("ripgrep"), 1)
+            .unwrap()
+            .is_empty());
+    }
+}
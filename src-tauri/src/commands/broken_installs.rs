@@ -0,0 +1,181 @@
+//! Detecting packages whose `current` junction points nowhere.
+//!
+//! Disk cleanup tools and manual deletes of version directories leave `current`
+//! dangling, after which the package's shims fail with obscure errors. The junctions
+//! are checked once during cold start, [`EVENT_BROKEN_INSTALLS_DETECTED`] lists the
+//! broken ones, and [`repair_broken_installs`] relinks them through the linker to the
+//! newest version directory still on disk.
+use crate::commands::installed::invalidate_installed_cache;
+use crate::commands::linker::switch_to_version;
+use crate::engine::fingerprint::find_latest_version_dir;
+use crate::state::AppState;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Emitted with the [`BrokenInstall`] list when dangling junctions are found.
+pub const EVENT_BROKEN_INSTALLS_DETECTED: &str = "broken-installs-detected";
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenInstall {
+    pub package: String,
+    /// Where `current` points, when the link can be read.
+    pub target: Option<String>,
+    /// The version directory a repair would link to; none when nothing is left.
+    pub repair_version: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallRepair {
+    pub package: String,
+    pub version: Option<String>,
+    pub repaired: bool,
+    pub error: Option<String>,
+}
+
+/// Lists the packages in `apps_dir` whose `current` link exists but whose target does not.
+pub fn find_broken_installs(apps_dir: &Path) -> Vec<BrokenInstall> {
+    let Ok(entries) = fs::read_dir(apps_dir) else {
+        return Vec::new();
+    };
+    let mut broken: Vec<BrokenInstall> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|package_dir| {
+            let current = package_dir.join("current");
+            // `exists` follows the link, `symlink_metadata` does not
+            if fs::symlink_metadata(&current).is_err() || current.exists() {
+                return None;
+            }
+            Some(BrokenInstall {
+                package: package_dir.file_name()?.to_string_lossy().to_string(),
+                target: fs::read_link(&current)
+                    .ok()
+                    .map(|target| target.display().to_string()),
+                repair_version: find_latest_version_dir(&package_dir)
+                    .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().to_string())),
+            })
+        })
+        .collect();
+    broken.sort_by(|a, b| a.package.cmp(&b.package));
+    broken
+}
+
+/// Checks the `current` junctions and emits the broken ones; run during cold start.
+pub async fn check_broken_installs<R: Runtime>(app: &AppHandle<R>) -> Vec<BrokenInstall> {
+    let apps_dir = app.state::<AppState>().scoop_dirs().apps;
+    let broken = tokio::task::spawn_blocking(move || find_broken_installs(&apps_dir))
+        .await
+        .unwrap_or_default();
+
+    if !broken.is_empty() {
+        log::warn!(
+            "Found {} packages with a dangling current junction: {}",
+            broken.len(),
+            broken
+                .iter()
+                .map(|b| b.package.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        if let Err(e) = app.emit(EVENT_BROKEN_INSTALLS_DETECTED, &broken) {
+            log::warn!(
+                "Failed to emit {} event: {}",
+                EVENT_BROKEN_INSTALLS_DETECTED,
+                e
+            );
+        }
+    }
+    broken
+}
+
+#[tauri::command]
+pub async fn get_broken_installs(app: AppHandle) -> Result<Vec<BrokenInstall>, String> {
+    let apps_dir = app.state::<AppState>().scoop_dirs().apps;
+    tokio::task::spawn_blocking(move || find_broken_installs(&apps_dir))
+        .await
+        .map_err(|e| format!("Failed to check installs: {}", e))
+}
+
+/// Relinks broken packages to their newest remaining version; all of them when
+/// `packages` is not given.
+#[tauri::command]
+pub async fn repair_broken_installs(
+    state: State<'_, AppState>,
+    packages: Option<Vec<String>>,
+) -> Result<Vec<InstallRepair>, String> {
+    let mut repairs = Vec::new();
+    {
+        // Wait for a running Scoop operation, which may be relinking the package itself
+        let _queue = state.operation_queue.lock().await;
+        let apps_dir = state.scoop_dirs().apps;
+        let scan_dir = apps_dir.clone();
+        let broken = tokio::task::spawn_blocking(move || find_broken_installs(&scan_dir))
+            .await
+            .map_err(|e| format!("Failed to check installs: {}", e))?;
+
+        for install in broken {
+            if packages
+                .as_ref()
+                .is_some_and(|names| !names.contains(&install.package))
+            {
+                continue;
+            }
+            let result = match &install.repair_version {
+                Some(version) => {
+                    let package_dir = apps_dir.join(&install.package);
+                    switch_to_version(&package_dir, &install.package, version).await
+                }
+                None => Err("No installed version left to link to".to_string()),
+            };
+            match &result {
+                Ok(()) => log::info!(
+                    "Relinked '{}' to version {}",
+                    install.package,
+                    install.repair_version.as_deref().unwrap_or("?")
+                ),
+                Err(e) => log::warn!("Could not repair '{}': {}", install.package, e),
+            }
+            repairs.push(InstallRepair {
+                package: install.package,
+                version: install.repair_version,
+                repaired: result.is_ok(),
+                error: result.err(),
+            });
+        }
+    }
+
+    if repairs.iter().any(|r| r.repaired) {
+        invalidate_installed_cache(state).await;
+    }
+    Ok(repairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScoopFixture;
+
+    #[test]
+    fn finds_dangling_current_junctions() {
+        let fixture = ScoopFixture::new();
+        let apps = fixture.apps_dir();
+        fixture.install("git", "2.44.0", "main");
+        fixture.install("node", "20.11.0", "main");
+        fixture.app_version("node", "18.19.0", Some("main"));
+        fixture.install("vlc", "3.0.20", "extras");
+        fs::remove_dir_all(apps.join("node").join("20.11.0")).unwrap();
+        fs::remove_dir_all(apps.join("vlc").join("3.0.20")).unwrap();
+
+        let broken = find_broken_installs(&apps);
+        assert_eq!(broken.len(), 2);
+        assert_eq!(broken[0].package, "node");
+        assert_eq!(broken[0].repair_version.as_deref(), Some("18.19.0"));
+        assert_eq!(broken[1].package, "vlc");
+        assert_eq!(broken[1].repair_version, None);
+    }
+}
@@ -0,0 +1,292 @@
+//! Filters for the community bucket directory.
+//!
+//! Besides a minimum star count, buckets can be hidden by the writing system of their
+//! description and by user-defined regex rules. Filters can be saved as named presets
+//! in the settings store.
+use super::bucket_search::SearchableBucket;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+/// Scripts that [`detect_script`] can report.
+pub const KNOWN_SCRIPTS: &[&str] = &[
+    "latin",
+    "han",
+    "japanese",
+    "hangul",
+    "cyrillic",
+    "greek",
+    "arabic",
+    "hebrew",
+    "thai",
+    "devanagari",
+];
+
+/// Part of a bucket a filter rule is matched against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterField {
+    Name,
+    Description,
+    Owner,
+    /// Name, description or owner.
+    Any,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    /// When any include rule exists, only buckets matching one of them are kept.
+    Include,
+    Exclude,
+}
+
+/// A user-defined rule; patterns are case-insensitive regular expressions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketFilterRule {
+    pub field: FilterField,
+    pub pattern: String,
+    pub action: FilterAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketFilterOptions {
+    /// Hides buckets with any Chinese characters; kept for older clients.
+    #[serde(default)]
+    pub disable_chinese_buckets: bool,
+    #[serde(default)]
+    pub minimum_stars: u32,
+    /// Scripts (see [`KNOWN_SCRIPTS`]) whose buckets are hidden.
+    #[serde(default)]
+    pub excluded_scripts: Vec<String>,
+    #[serde(default)]
+    pub rules: Vec<BucketFilterRule>,
+}
+
+impl Default for BucketFilterOptions {
+    fn default() -> Self {
+        Self {
+            disable_chinese_buckets: false,
+            minimum_stars: 2,
+            excluded_scripts: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+fn script_of(c: char) -> Option<&'static str> {
+    match c {
+        '\u{3040}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}' => Some("japanese"),
+        '\u{4E00}'..='\u{9FFF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{20000}'..='\u{2CEAF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{2F800}'..='\u{2FA1F}' => Some("han"),
+        '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' => {
+            Some("hangul")
+        }
+        '\u{0400}'..='\u{052F}' => Some("cyrillic"),
+        '\u{0370}'..='\u{03FF}' => Some("greek"),
+        '\u{0600}'..='\u{06FF}' | '\u{0750}'..='\u{077F}' => Some("arabic"),
+        '\u{0590}'..='\u{05FF}' => Some("hebrew"),
+        '\u{0E00}'..='\u{0E7F}' => Some("thai"),
+        '\u{0900}'..='\u{097F}' => Some("devanagari"),
+        c if c.is_ascii_alphabetic() || ('\u{00C0}'..='\u{024F}').contains(&c) => Some("latin"),
+        _ => None,
+    }
+}
+
+/// Detects the dominant writing system of `text`.
+///
+/// Ideographic and syllabic characters carry a whole word or syllable, so they are
+/// weighted higher than alphabetic letters; a mostly-English description with a few
+/// Chinese words still counts as `han`. Any kana marks the text as `japanese`.
+pub fn detect_script(text: &str) -> Option<&'static str> {
+    let mut counts: Vec<(&'static str, usize)> = Vec::new();
+    for script in text.chars().filter_map(script_of) {
+        let weight = match script {
+            "han" | "japanese" | "hangul" => 3,
+            _ => 1,
+        };
+        match counts.iter_mut().find(|(s, _)| *s == script) {
+            Some((_, count)) => *count += weight,
+            None => counts.push((script, weight)),
+        }
+    }
+
+    if counts.iter().any(|(s, _)| *s == "japanese") {
+        return Some("japanese");
+    }
+    counts
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(script, _)| script)
+}
+
+fn contains_chinese_characters(text: &str) -> bool {
+    text.chars().any(|c| script_of(c) == Some("han"))
+}
+
+/// Bucket filters with their rule patterns compiled.
+pub struct CompiledBucketFilters {
+    options: BucketFilterOptions,
+    rules: Vec<(FilterField, FilterAction, Regex)>,
+}
+
+impl CompiledBucketFilters {
+    /// Compiles the rules in `options`, failing on the first invalid pattern or
+    /// unknown script.
+    pub fn new(options: BucketFilterOptions) -> Result<Self, String> {
+        if let Some(script) = options
+            .excluded_scripts
+            .iter()
+            .find(|s| !KNOWN_SCRIPTS.iter().any(|k| k.eq_ignore_ascii_case(s)))
+        {
+            return Err(format!(
+                "Unknown script '{}', expected one of: {}",
+                script,
+                KNOWN_SCRIPTS.join(", ")
+            ));
+        }
+
+        let rules = options
+            .rules
+            .iter()
+            .map(|rule| {
+                RegexBuilder::new(&rule.pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .map(|regex| (rule.field, rule.action, regex))
+                    .map_err(|e| format!("Invalid filter pattern '{}': {}", rule.pattern, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { options, rules })
+    }
+
+    /// Returns `true` if the bucket passes every filter.
+    pub fn matches(&self, bucket: &SearchableBucket) -> bool {
+        if bucket.stars < self.options.minimum_stars {
+            return false;
+        }
+
+        if self.options.disable_chinese_buckets
+            && (contains_chinese_characters(&bucket.name)
+                || contains_chinese_characters(&bucket.description)
+                || contains_chinese_characters(&bucket.full_name))
+        {
+            return false;
+        }
+
+        if !self.options.excluded_scripts.is_empty() {
+            let text = if bucket.description.trim().is_empty() {
+                &bucket.name
+            } else {
+                &bucket.description
+            };
+            if let Some(script) = detect_script(text) {
+                if self
+                    .options
+                    .excluded_scripts
+                    .iter()
+                    .any(|s| s.eq_ignore_ascii_case(script))
+                {
+                    return false;
+                }
+            }
+        }
+
+        let owner = bucket.full_name.split('/').next().unwrap_or_default();
+        let rule_matches = |field: FilterField, regex: &Regex| match field {
+            FilterField::Name => regex.is_match(&bucket.name),
+            FilterField::Description => regex.is_match(&bucket.description),
+            FilterField::Owner => regex.is_match(owner),
+            FilterField::Any => {
+                regex.is_match(&bucket.name)
+                    || regex.is_match(&bucket.description)
+                    || regex.is_match(owner)
+            }
+        };
+
+        let mut has_include = false;
+        let mut included = false;
+        for (field, action, regex) in &self.rules {
+            match action {
+                FilterAction::Exclude if rule_matches(*field, regex) => return false,
+                FilterAction::Exclude => {}
+                FilterAction::Include => {
+                    has_include = true;
+                    included = included || rule_matches(*field, regex);
+                }
+            }
+        }
+        !has_include || included
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bucket(full_name: &str, description: &str, stars: u32) -> SearchableBucket {
+        SearchableBucket {
+            name: full_name.split('/').nth(1).unwrap_or_default().to_string(),
+            full_name: full_name.to_string(),
+            description: description.to_string(),
+            url: format!("https://github.com/{}", full_name),
+            stars,
+            forks: 0,
+            apps: 1,
+            last_updated: "Unknown".to_string(),
+            is_verified: false,
+            duplicate_of: None,
+        }
+    }
+
+    #[test]
+    fn detects_dominant_script() {
+        assert_eq!(detect_script("A bucket for dev tools"), Some("latin"));
+        assert_eq!(detect_script("Scoop bucket 国内软件"), Some("han"));
+        assert_eq!(detect_script("日本語のアプリ"), Some("japanese"));
+        assert_eq!(detect_script("Пакеты для Scoop"), Some("cyrillic"));
+        assert_eq!(detect_script("1.0 - 2.0"), None);
+    }
+
+    #[test]
+    fn applies_scripts_and_rules() {
+        let filters = CompiledBucketFilters::new(BucketFilterOptions {
+            minimum_stars: 0,
+            excluded_scripts: vec!["cyrillic".to_string()],
+            rules: vec![
+                BucketFilterRule {
+                    field: FilterField::Any,
+                    pattern: "font".to_string(),
+                    action: FilterAction::Include,
+                },
+                BucketFilterRule {
+                    field: FilterField::Owner,
+                    pattern: "^spam".to_string(),
+                    action: FilterAction::Exclude,
+                },
+            ],
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert!(filters.matches(&bucket("alice/scoop-fonts", "Fonts", 5)));
+        assert!(!filters.matches(&bucket("spammer/fonts", "Fonts", 5)));
+        assert!(!filters.matches(&bucket("bob/tools", "Dev tools", 5)));
+        assert!(!filters.matches(&bucket("ivan/fonts", "Шрифты", 5)));
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        let result = CompiledBucketFilters::new(BucketFilterOptions {
+            rules: vec![BucketFilterRule {
+                field: FilterField::Name,
+                pattern: "(".to_string(),
+                action: FilterAction::Exclude,
+            }],
+            ..Default::default()
+        });
+        assert!(result.is_err());
+    }
+}
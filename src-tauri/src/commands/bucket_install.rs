@@ -1,9 +1,13 @@
 use git2::{Cred, CredentialType, FetchOptions, RemoteCallbacks, Repository};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
-use tauri::command;
+use tauri::{command, Emitter, Manager, Window};
 
+use crate::commands::bucket::invalidate_bucket_info_cache;
+use crate::commands::operation_log::OperationLog;
+use crate::commands::powershell::OperationClass;
 use crate::commands::search::invalidate_manifest_cache;
 use crate::utils;
 
@@ -23,6 +27,38 @@ pub struct BucketInstallResult {
     pub manifest_count: Option<u32>,
 }
 
+/// Emitted with a [`BatchBucketProgress`] as a batch bucket operation works through its list.
+pub const EVENT_BATCH_BUCKET_PROGRESS: &str = "batch-bucket-progress";
+
+#[derive(Debug, Clone)]
+enum BucketAction {
+    /// Clones each bucket from its URL in the map.
+    Add(BTreeMap<String, String>),
+    Update,
+    Remove,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchBucketProgress {
+    pub bucket: String,
+    /// 1-based position in the list.
+    pub index: usize,
+    pub total: usize,
+    /// `started`, `succeeded` or `failed`.
+    pub status: String,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchBucketResult {
+    pub succeeded: Vec<String>,
+    /// Bucket name and error of each failed bucket.
+    pub failed: Vec<(String, String)>,
+    pub results: Vec<BucketInstallResult>,
+}
+
 // Get the buckets directory path
 fn get_buckets_dir() -> Result<PathBuf, String> {
     // Use fallback method to get scoop directory
@@ -120,6 +156,7 @@ async fn install_bucket_internal(
     } else {
         utils::extract_bucket_name_from_url(&normalized_url, Some(&name))?
     };
+    crate::policy::check_bucket_allowed(&bucket_name)?;
 
     // Check if bucket already exists
     if bucket_exists(&bucket_name)? && !force {
@@ -163,6 +200,7 @@ async fn install_bucket_internal(
 
             // Invalidate search cache so new bucket's packages are searchable
             invalidate_manifest_cache().await;
+            invalidate_bucket_info_cache(Some(&bucket_name));
 
             log::info!(
                 "Successfully installed bucket '{}' with {} manifests",
@@ -302,28 +340,13 @@ pub async fn update_bucket(_app: tauri::AppHandle, bucket_name: String) -> Resul
         return Ok(result);
     }
 
-    // Check if it's a git repository
-    if !bucket_path.join(".git").exists() {
-        let result = BucketInstallResult {
-            success: false,
-            message: format!(
-                "Bucket '{}' is not a git repository and cannot be updated",
-                bucket_name
-            ),
-            bucket_name: bucket_name.clone(),
-            bucket_path: Some(bucket_path.to_string_lossy().to_string()),
-            manifest_count: None,
-        };
-
-        return Ok(result);
-    }
-
     let bucket_name_clone = bucket_name.clone();
     let bucket_path_clone = bucket_path.clone();
 
     let result = tokio::task::spawn_blocking(move || update_bucket_sync(&bucket_name_clone, &bucket_path_clone))
         .await
         .map_err(|e| e.to_string())??;
+    invalidate_bucket_info_cache(Some(&bucket_name));
 
     Ok(result)
 }
@@ -332,6 +355,21 @@ fn update_bucket_sync(
     bucket_name: &str,
     bucket_path: &Path,
 ) -> Result<BucketInstallResult, String> {
+    // Buckets added from a folder or archive have no remote to pull from
+    if !bucket_path.join(".git").exists() {
+        log::info!("Bucket '{}' is not a git repository; skipping update", bucket_name);
+        return Ok(BucketInstallResult {
+            success: true,
+            message: format!(
+                "Bucket '{}' is managed manually (not a git repository) and was not updated",
+                bucket_name
+            ),
+            bucket_name: bucket_name.to_string(),
+            bucket_path: Some(bucket_path.to_string_lossy().to_string()),
+            manifest_count: Some(utils::count_manifests(bucket_path)),
+        });
+    }
+
     // Try to update the repository using git2
     match Repository::open(bucket_path) {
         Ok(repo) => {
@@ -368,7 +406,9 @@ fn update_bucket_sync(
             fetch_options.remote_callbacks(callbacks);
 
             // Fetch latest changes
-            match remote.fetch(&[] as &[&str], Some(&mut fetch_options), None) {
+            let fetched = remote.fetch(&[] as &[&str], Some(&mut fetch_options), None);
+            crate::commands::bucket::record_fetch(bucket_path, fetched.as_ref().copied());
+            match fetched {
                 Ok(_) => {
                     // Get current branch
                     let head = match repo.head() {
@@ -551,6 +591,7 @@ pub async fn update_all_buckets() -> Result<Vec<BucketInstallResult>, String> {
     }
 
     log::info!("Completed updating {} buckets", results.len());
+    invalidate_bucket_info_cache(None);
     
     // Clear the scoop root cache after batch update to allow for fresh detection next time
     crate::utils::clear_scoop_root_cache();
@@ -558,9 +599,12 @@ pub async fn update_all_buckets() -> Result<Vec<BucketInstallResult>, String> {
     Ok(results)
 }
 
-// Command to remove a bucket
+// Command to remove a bucket; `permanent` skips the Recycle Bin regardless of the setting
 #[command]
-pub async fn remove_bucket(bucket_name: String) -> Result<BucketInstallResult, String> {
+pub async fn remove_bucket(
+    bucket_name: String,
+    permanent: Option<bool>,
+) -> Result<BucketInstallResult, String> {
     log::info!("Removing bucket: {}", bucket_name);
 
     let bucket_path = get_bucket_path(&bucket_name)?;
@@ -575,10 +619,12 @@ pub async fn remove_bucket(bucket_name: String) -> Result<BucketInstallResult, S
         });
     }
 
-    match remove_bucket_directory(&bucket_path) {
+    let recycle = crate::recycle::should_recycle(permanent);
+    match crate::recycle::remove_path(&bucket_path, recycle) {
         Ok(_) => {
             // Invalidate search cache so removed bucket's packages are no longer searchable
             invalidate_manifest_cache().await;
+            invalidate_bucket_info_cache(Some(&bucket_name));
 
             log::info!("Successfully removed bucket '{}'", bucket_name);
             Ok(BucketInstallResult {
@@ -601,3 +647,370 @@ pub async fn remove_bucket(bucket_name: String) -> Result<BucketInstallResult, S
         }
     }
 }
+
+async fn run_bucket_batch(
+    window: Window,
+    action: BucketAction,
+    bucket_names: Vec<String>,
+) -> Result<BatchBucketResult, String> {
+    let (verb, done) = match &action {
+        BucketAction::Add(_) => ("add", "Added"),
+        BucketAction::Update => ("update", "Updated"),
+        BucketAction::Remove => ("remove", "Removed"),
+    };
+    let total = bucket_names.len();
+    log::info!("Batch {} of {} buckets: {:?}", verb, total, bucket_names);
+
+    let operation_id = format!(
+        "bucket-{}-{}",
+        verb,
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    );
+    let op_log = OperationLog::start(
+        Some(&operation_id),
+        &format!("{} {} buckets", done, total),
+        &format!("bucket {} {}", verb, bucket_names.join(" ")),
+        OperationClass::Bucket,
+    );
+
+    let emit = |bucket: &str, index: usize, status: &str, message: Option<String>| {
+        let progress = BatchBucketProgress {
+            bucket: bucket.to_string(),
+            index,
+            total,
+            status: status.to_string(),
+            message,
+        };
+        if let Err(e) = window.emit(EVENT_BATCH_BUCKET_PROGRESS, progress) {
+            log::warn!("Failed to emit {} event: {}", EVENT_BATCH_BUCKET_PROGRESS, e);
+        }
+    };
+
+    let mut batch = BatchBucketResult::default();
+    for (i, name) in bucket_names.iter().enumerate() {
+        let index = i + 1;
+        emit(name, index, "started", None);
+        let outcome = match &action {
+            BucketAction::Add(urls) => {
+                install_bucket(BucketInstallOptions {
+                    name: name.clone(),
+                    url: urls.get(name).cloned().unwrap_or_default(),
+                    force: false,
+                })
+                .await
+            }
+            BucketAction::Update => {
+                update_bucket(window.app_handle().clone(), name.clone()).await
+            }
+            BucketAction::Remove => remove_bucket(name.clone(), None).await,
+        };
+        let result = outcome.unwrap_or_else(|e| BucketInstallResult {
+            success: false,
+            message: e,
+            bucket_name: name.clone(),
+            bucket_path: None,
+            manifest_count: None,
+        });
+
+        if let Some(op_log) = &op_log {
+            let source = if result.success { "stdout" } else { "stderr" };
+            op_log.append(&result.message, source);
+        }
+        if result.success {
+            emit(name, index, "succeeded", Some(result.message.clone()));
+            batch.succeeded.push(name.clone());
+        } else {
+            log::warn!("Batch {} of bucket '{}' failed: {}", verb, name, result.message);
+            emit(name, index, "failed", Some(result.message.clone()));
+            batch.failed.push((name.clone(), result.message.clone()));
+        }
+        batch.results.push(result);
+    }
+
+    let summary = format!("{} {} of {} buckets", done, batch.succeeded.len(), total);
+    if let Some(op_log) = &op_log {
+        let outcome = if batch.failed.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "{}; failed: {}",
+                summary,
+                batch
+                    .failed
+                    .iter()
+                    .map(|(name, _)| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+        };
+        op_log.finish(&outcome, &summary, false);
+    }
+    log::info!("{}", summary);
+    Ok(batch)
+}
+
+/// Updates several buckets in one go, recorded as a single history entry.
+///
+/// Progress is emitted as [`EVENT_BATCH_BUCKET_PROGRESS`]; a failed bucket does not
+/// stop the others.
+#[command]
+pub async fn update_buckets(
+    window: Window,
+    bucket_names: Vec<String>,
+) -> Result<BatchBucketResult, String> {
+    run_bucket_batch(window, BucketAction::Update, bucket_names).await
+}
+
+/// Clones several buckets one after another, recorded as a single history entry, with
+/// progress emitted as [`EVENT_BATCH_BUCKET_PROGRESS`]. `buckets` maps each bucket name
+/// to its repository URL.
+pub async fn add_buckets(
+    window: Window,
+    buckets: BTreeMap<String, String>,
+) -> Result<BatchBucketResult, String> {
+    let names = buckets.keys().cloned().collect();
+    run_bucket_batch(window, BucketAction::Add(buckets), names).await
+}
+
+/// Removes several buckets in one go, recorded as a single history entry.
+#[command]
+pub async fn remove_buckets(
+    window: Window,
+    bucket_names: Vec<String>,
+) -> Result<BatchBucketResult, String> {
+    run_bucket_batch(window, BucketAction::Remove, bucket_names).await
+}
+
+// Quote a path for a single-quoted PowerShell string
+fn ps_quote(path: &Path) -> String {
+    super::powershell::quote_literal(&path.to_string_lossy())
+}
+
+async fn run_powershell(command: &str) -> Result<(), String> {
+    let output = crate::commands::powershell::create_powershell_command(command)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run PowerShell: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+// Whether a directory looks like a bucket: manifests in `bucket/` or at the top level
+fn looks_like_bucket(path: &Path) -> bool {
+    path.join("bucket").is_dir() || utils::count_manifests(path) > 0
+}
+
+// Archives often wrap everything in one folder (e.g. `Main-master/`); descend into it
+fn archive_root(extracted: &Path) -> PathBuf {
+    let entries: Vec<PathBuf> = fs::read_dir(extracted)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    match entries.as_slice() {
+        [only] if only.is_dir() && !looks_like_bucket(extracted) => only.clone(),
+        _ => extracted.to_path_buf(),
+    }
+}
+
+/// Exports a bucket as a zip archive, leaving out `.git` unless `include_git` is set.
+#[command]
+pub async fn export_bucket_archive(
+    bucket_name: String,
+    destination: String,
+    include_git: Option<bool>,
+) -> Result<String, String> {
+    let bucket_path = get_bucket_path(&bucket_name)?;
+    if !bucket_path.is_dir() {
+        return Err(format!("Bucket '{}' does not exist", bucket_name));
+    }
+    let mut destination = PathBuf::from(destination);
+    if destination.is_dir() {
+        destination = destination.join(format!("{}.zip", bucket_name));
+    }
+    log::info!(
+        "Exporting bucket '{}' to {}",
+        bucket_name,
+        destination.display()
+    );
+
+    let filter = if include_git.unwrap_or(false) {
+        ""
+    } else {
+        " | Where-Object { $_.Name -ne '.git' }"
+    };
+    let command = format!(
+        "$items = Get-ChildItem -LiteralPath {} -Force{}; Compress-Archive -LiteralPath $items.FullName -DestinationPath {} -Force",
+        ps_quote(&bucket_path),
+        filter,
+        ps_quote(&destination)
+    );
+    run_powershell(&command)
+        .await
+        .map_err(|e| format!("Failed to export bucket '{}': {}", bucket_name, e))?;
+    Ok(destination.to_string_lossy().to_string())
+}
+
+// Moves a staged bucket into place. An existing bucket is set aside first and only
+// deleted once the new one is in place, so a failed move leaves it untouched.
+fn replace_bucket_directory(staged: &Path, bucket_path: &Path) -> Result<(), String> {
+    if !bucket_path.exists() {
+        return fs::rename(staged, bucket_path)
+            .map_err(|e| format!("Failed to move bucket into place: {}", e));
+    }
+    let name = bucket_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let previous = bucket_path.with_file_name(format!(".{}.previous", name));
+    remove_bucket_directory(&previous)?;
+    fs::rename(bucket_path, &previous)
+        .map_err(|e| format!("Failed to set the existing bucket aside: {}", e))?;
+    if let Err(e) = fs::rename(staged, bucket_path) {
+        let _ = fs::rename(&previous, bucket_path);
+        return Err(format!("Failed to move bucket into place: {}", e));
+    }
+    if let Err(e) = remove_bucket_directory(&previous) {
+        log::warn!(
+            "Failed to remove the replaced bucket {}: {}",
+            previous.display(),
+            e
+        );
+    }
+    Ok(())
+}
+
+/// Adds a bucket from a local zip archive or folder instead of a git remote.
+///
+/// The bucket is not a git repository, so it is reported as manually managed by
+/// `update_bucket` and only changes when it is added again with `force`. With `force`,
+/// the existing bucket is only replaced once the new source has been staged and found
+/// to contain manifests.
+#[command]
+pub async fn add_local_bucket(
+    name: String,
+    source_path: String,
+    force: bool,
+) -> Result<BucketInstallResult, String> {
+    let source = PathBuf::from(source_path.trim());
+    let bucket_name = if name.trim().is_empty() {
+        let stem = source
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        stem.trim_start_matches("scoop-").to_string()
+    } else {
+        name.trim().to_lowercase()
+    };
+    crate::utils::validate_dir_name("bucket", &bucket_name)?;
+    crate::policy::check_bucket_allowed(&bucket_name)?;
+
+    let bucket_path = get_bucket_path(&bucket_name)?;
+    if bucket_path.exists() {
+        if !force {
+            return Ok(BucketInstallResult {
+                success: false,
+                message: format!(
+                    "Bucket '{}' already exists. Use force=true to replace it.",
+                    bucket_name
+                ),
+                bucket_name,
+                bucket_path: Some(bucket_path.to_string_lossy().to_string()),
+                manifest_count: None,
+            });
+        }
+    }
+    log::info!(
+        "Adding local bucket '{}' from {}",
+        bucket_name,
+        source.display()
+    );
+
+    // Stage next to the target so the final move is a rename on the same volume
+    let staging = get_buckets_dir()?.join(format!(".{}.import", bucket_name));
+    remove_bucket_directory(&staging)?;
+    let staged = if source.is_dir() {
+        let (from, to) = (source.clone(), staging.clone());
+        tokio::task::spawn_blocking(move || utils::copy_dir_recursive(&from, &to))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("Failed to copy bucket folder: {}", e))
+            .map(|_| staging.clone())
+    } else if source
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+    {
+        let command = format!(
+            "Expand-Archive -LiteralPath {} -DestinationPath {} -Force",
+            ps_quote(&source),
+            ps_quote(&staging)
+        );
+        run_powershell(&command)
+            .await
+            .map_err(|e| format!("Failed to extract bucket archive: {}", e))
+            .map(|_| archive_root(&staging))
+    } else {
+        Err(format!(
+            "'{}' is neither a folder nor a zip archive",
+            source.display()
+        ))
+    };
+
+    let result = staged.and_then(|root| {
+        if !looks_like_bucket(&root) {
+            return Err(format!("'{}' does not contain any manifests", source.display()));
+        }
+        replace_bucket_directory(&root, &bucket_path)
+    });
+    let _ = remove_bucket_directory(&staging);
+    result?;
+
+    invalidate_manifest_cache().await;
+    invalidate_bucket_info_cache(Some(&bucket_name));
+    let manifest_count = utils::count_manifests(&bucket_path);
+    log::info!(
+        "Added local bucket '{}' with {} manifests",
+        bucket_name,
+        manifest_count
+    );
+    Ok(BucketInstallResult {
+        success: true,
+        message: format!(
+            "Added bucket '{}' with {} manifests",
+            bucket_name, manifest_count
+        ),
+        bucket_name,
+        bucket_path: Some(bucket_path.to_string_lossy().to_string()),
+        manifest_count: Some(manifest_count),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replacing_a_bucket_keeps_it_until_the_new_one_is_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let bucket = dir.path().join("extras");
+        fs::create_dir_all(bucket.join("bucket")).unwrap();
+        fs::write(bucket.join("bucket").join("old.json"), "{}").unwrap();
+
+        // A missing staging directory fails the move and restores the old bucket
+        let missing = dir.path().join(".extras.import");
+        assert!(replace_bucket_directory(&missing, &bucket).is_err());
+        assert!(bucket.join("bucket").join("old.json").is_file());
+
+        let staged = dir.path().join("staged");
+        fs::create_dir_all(staged.join("bucket")).unwrap();
+        fs::write(staged.join("bucket").join("new.json"), "{}").unwrap();
+        replace_bucket_directory(&staged, &bucket).unwrap();
+        assert!(bucket.join("bucket").join("new.json").is_file());
+        assert!(!bucket.join("bucket").join("old.json").exists());
+        assert!(!dir.path().join(".extras.previous").exists());
+    }
+}
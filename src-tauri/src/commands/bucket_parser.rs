@@ -4,25 +4,16 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Runtime};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
+use super::bucket_filter::{BucketFilterOptions, CompiledBucketFilters};
 use super::bucket_search::SearchableBucket;
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BucketFilterOptions {
-    pub disable_chinese_buckets: bool,
-    pub minimum_stars: u32,
-}
-
-impl Default for BucketFilterOptions {
-    fn default() -> Self {
-        Self {
-            disable_chinese_buckets: false,
-            minimum_stars: 2,
-        }
-    }
-}
+use super::milestones::{emit_milestone, MilestoneStage, OperationMilestone};
+use super::operation_log::OperationLog;
+use super::powershell::OperationClass;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
@@ -41,12 +32,17 @@ struct BucketCsvRecord {
 static BUCKET_CACHE: Lazy<tokio::sync::RwLock<HashMap<String, SearchableBucket>>> =
     Lazy::new(|| tokio::sync::RwLock::new(HashMap::new()));
 
-// Get the cache file path in the app data directory
-fn get_cache_file_path() -> Result<PathBuf, String> {
+/// Lowercase app name -> full names (`owner/repo`) of the buckets shipping it.
+pub type PackageIndex = HashMap<String, Vec<String>>;
+
+static PACKAGE_INDEX: Lazy<tokio::sync::RwLock<PackageIndex>> =
+    Lazy::new(|| tokio::sync::RwLock::new(HashMap::new()));
+
+// Get the cache directory in the app data directory
+pub fn get_cache_dir() -> Result<PathBuf, String> {
     // Try to use the correct app data directory
-    let app_data_dir = if let Some(data_dir) = dirs::data_dir() {
-        // Try Tauri app directory first (com.pailer.ks)
-        let tauri_dir = data_dir.join("com.pailer.ks");
+    let app_data_dir = if let Some(tauri_dir) = crate::portable::app_data_dir() {
+        // Try Tauri app directory first (com.pailer.ks, or the portable data directory)
         if tauri_dir.exists() {
             tauri_dir.join("cache")
         } else {
@@ -66,7 +62,35 @@ fn get_cache_file_path() -> Result<PathBuf, String> {
     std::fs::create_dir_all(&app_data_dir)
         .map_err(|e| format!("Failed to create cache directory: {}", e))?;
 
-    Ok(app_data_dir.join("bucket_cache.csv"))
+    Ok(app_data_dir)
+}
+
+// Get the cache file path in the app data directory
+fn get_cache_file_path() -> Result<PathBuf, String> {
+    Ok(get_cache_dir()?.join("bucket_cache.csv"))
+}
+
+fn get_package_index_path() -> Result<PathBuf, String> {
+    Ok(get_cache_dir()?.join("bucket_packages.json"))
+}
+
+async fn save_package_index(index: &PackageIndex) -> Result<(), String> {
+    let content = serde_json::to_vec(index)
+        .map_err(|e| format!("Failed to serialize package index: {}", e))?;
+    fs::write(get_package_index_path()?, content)
+        .await
+        .map_err(|e| format!("Failed to write package index: {}", e))
+}
+
+async fn load_package_index() -> Result<PackageIndex, String> {
+    let path = get_package_index_path()?;
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let content = fs::read(&path)
+        .await
+        .map_err(|e| format!("Failed to read package index: {}", e))?;
+    serde_json::from_slice(&content).map_err(|e| format!("Failed to parse package index: {}", e))
 }
 
 // Save bucket cache to disk
@@ -156,21 +180,38 @@ async fn load_cache_from_disk() -> Result<HashMap<String, SearchableBucket>, Str
 }
 
 // Convert markdown table to CSV format with file cleanup
-pub async fn fetch_and_parse_bucket_directory(
-    filters: Option<BucketFilterOptions>,
-) -> Result<HashMap<String, SearchableBucket>, String> {
-    let filters = filters.unwrap_or_default();
+pub async fn fetch_and_parse_bucket_directory() -> Result<HashMap<String, SearchableBucket>, String>
+{
+    fetch_and_parse_bucket_directory_with_progress(|_, _| {}).await
+}
+
+// Same as `fetch_and_parse_bucket_directory`, reporting (downloaded, total) bytes while downloading
+async fn fetch_and_parse_bucket_directory_with_progress<F>(
+    on_progress: F,
+) -> Result<HashMap<String, SearchableBucket>, String>
+where
+    F: Fn(u64, Option<u64>) + Send,
+{
     let url = "https://github.com/rasa/scoop-directory/raw/refs/heads/master/by-stars.md";
 
     log::info!("Fetching bucket directory from: {}", url);
 
-    let response = reqwest::get(url)
+    let mut response = crate::http::get(url)
         .await
         .map_err(|e| format!("Failed to fetch bucket directory: {}", e))?;
 
-    let content = response
-        .text()
+    let total_bytes = response.content_length();
+    let mut body: Vec<u8> = Vec::with_capacity(total_bytes.unwrap_or(0) as usize);
+    while let Some(chunk) = response
+        .chunk()
         .await
+        .map_err(|e| format!("Failed to read response: {}", e))?
+    {
+        body.extend_from_slice(&chunk);
+        on_progress(body.len() as u64, total_bytes);
+    }
+
+    let content = String::from_utf8(body)
         .map_err(|e| format!("Failed to read response: {}", e))?;
 
     let original_size_mb = content.len() as f64 / (1024.0 * 1024.0);
@@ -180,34 +221,24 @@ pub async fn fetch_and_parse_bucket_directory(
     );
 
     let buckets = parse_markdown_to_buckets(&content)?;
-
-    log::info!("Parsed {} buckets from directory", buckets.len());
-
-    // Convert to HashMap keyed by full_name (owner/repo) to avoid deduplication of bucket names
-    let mut bucket_map = HashMap::new();
-    let mut filtered_count = 0;
-    let original_count = buckets.len();
-
-    for bucket in buckets {
-        if apply_bucket_filters(&bucket, &filters) {
-            bucket_map.insert(bucket.full_name.clone(), bucket);
-        } else {
-            filtered_count += 1;
-        }
-    }
+    let package_index = parse_package_index(&content);
 
     log::info!(
-        "Applied filters: {} buckets filtered out, {} remaining (original: {})",
-        filtered_count,
-        bucket_map.len(),
-        original_count
+        "Parsed {} buckets and {} app names from directory",
+        buckets.len(),
+        package_index.len()
     );
-    if filters.disable_chinese_buckets {
-        log::info!("Chinese bucket filtering was enabled");
-    }
-    if filters.minimum_stars > 0 {
-        log::info!("Minimum star filter: {} stars", filters.minimum_stars);
+    if let Err(e) = save_package_index(&package_index).await {
+        log::warn!("{}", e);
     }
+    *PACKAGE_INDEX.write().await = package_index;
+
+    // Convert to HashMap keyed by full_name (owner/repo) to avoid deduplication of bucket names.
+    // The cache keeps every bucket; filters are applied when it is read.
+    let bucket_map: HashMap<String, SearchableBucket> = buckets
+        .into_iter()
+        .map(|bucket| (bucket.full_name.clone(), bucket))
+        .collect();
 
     // Save optimized cache to disk
     save_cache_to_disk(&bucket_map).await?;
@@ -239,6 +270,49 @@ static BASIC_REGEX: Lazy<Regex> = Lazy::new(|| {
     ).expect("Failed to compile basic regex")
 });
 
+/// Manifest links in the app tables, e.g. `(https://github.com/owner/repo/blob/master/bucket/app.json)`.
+static MANIFEST_LINK_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\(https://github\.com/([^/)\s]+)/([^/)\s]+)/blob/[^)\s]*?([^/)\s]+)\.json\)"#)
+        .expect("Failed to compile manifest link regex")
+});
+
+/// Maps every app listed in the directory to the buckets whose manifest links name it.
+fn parse_package_index(content: &str) -> PackageIndex {
+    let mut index: PackageIndex = HashMap::new();
+    for captures in MANIFEST_LINK_REGEX.captures_iter(content) {
+        let full_name = format!("{}/{}", &captures[1], &captures[2]);
+        let buckets = index.entry(captures[3].to_lowercase()).or_default();
+        if !buckets.iter().any(|b| b.eq_ignore_ascii_case(&full_name)) {
+            buckets.push(full_name);
+        }
+    }
+    index
+}
+
+/// Gets the app-to-bucket index, fetching the directory if it was never parsed.
+pub async fn get_package_index() -> Result<PackageIndex, String> {
+    {
+        let index = PACKAGE_INDEX.read().await;
+        if !index.is_empty() {
+            return Ok(index.clone());
+        }
+    }
+
+    match load_package_index().await {
+        Ok(index) if !index.is_empty() => {
+            *PACKAGE_INDEX.write().await = index.clone();
+            return Ok(index);
+        }
+        Ok(_) => log::info!("Package index is empty or doesn't exist"),
+        Err(e) => log::warn!("Failed to load package index: {}", e),
+    }
+
+    // Caches written before the index existed only hold the bucket list
+    let buckets = fetch_and_parse_bucket_directory().await?;
+    *BUCKET_CACHE.write().await = buckets;
+    Ok(PACKAGE_INDEX.read().await.clone())
+}
+
 fn try_parse_complex(line: &str) -> Option<SearchableBucket> {
     let captures = COMPLEX_REGEX.captures(line)?;
     let owner = captures.get(1).map_or("", |m| m.as_str()).trim();
@@ -276,6 +350,7 @@ fn try_parse_complex(line: &str) -> Option<SearchableBucket> {
             apps,
             last_updated,
             is_verified: false,
+            duplicate_of: None,
         })
     } else {
         None
@@ -325,6 +400,7 @@ fn try_parse_simple(line: &str) -> Option<SearchableBucket> {
             apps: 1,
             last_updated: "Unknown".to_string(),
             is_verified: false,
+            duplicate_of: None,
         })
     } else {
         None
@@ -373,6 +449,7 @@ fn try_parse_basic(line: &str) -> Option<SearchableBucket> {
             apps: 1,
             last_updated: "Unknown".to_string(),
             is_verified: false,
+            duplicate_of: None,
         })
     } else {
         None
@@ -415,41 +492,27 @@ fn extract_bucket_name(repo: &str) -> String {
         .to_lowercase()
 }
 
-// Check if text contains Chinese characters
-fn contains_chinese_characters(text: &str) -> bool {
-    text.chars().any(|c| {
-        // Check for CJK Unified Ideographs (common Chinese characters)
-        matches!(c,
-            '\u{4E00}'..='\u{9FFF}' |  // CJK Unified Ideographs
-            '\u{3400}'..='\u{4DBF}' |  // CJK Extension A
-            '\u{20000}'..='\u{2A6DF}' | // CJK Extension B
-            '\u{2A700}'..='\u{2B73F}' | // CJK Extension C
-            '\u{2B740}'..='\u{2B81F}' | // CJK Extension D
-            '\u{2B820}'..='\u{2CEAF}' | // CJK Extension E
-            '\u{F900}'..='\u{FAFF}' |  // CJK Compatibility Ideographs
-            '\u{2F800}'..='\u{2FA1F}'  // CJK Compatibility Supplement
-        )
-    })
-}
-
-// Apply filters to a bucket
-fn apply_bucket_filters(bucket: &SearchableBucket, filters: &BucketFilterOptions) -> bool {
-    // Filter by minimum stars
-    if bucket.stars < filters.minimum_stars {
-        return false;
-    }
-
-    // Filter Chinese buckets if requested
-    if filters.disable_chinese_buckets {
-        if contains_chinese_characters(&bucket.name)
-            || contains_chinese_characters(&bucket.description)
-            || contains_chinese_characters(&bucket.full_name)
-        {
-            return false;
-        }
-    }
+/// Keeps the buckets that pass `filters`, or all of them when no filters are given.
+fn filter_bucket_map(
+    buckets: &HashMap<String, SearchableBucket>,
+    filters: Option<&CompiledBucketFilters>,
+) -> HashMap<String, SearchableBucket> {
+    let Some(filters) = filters else {
+        return buckets.clone();
+    };
 
-    true
+    let filtered: HashMap<String, SearchableBucket> = buckets
+        .iter()
+        .filter(|(_, bucket)| filters.matches(bucket))
+        .map(|(key, bucket)| (key.clone(), bucket.clone()))
+        .collect();
+    log::info!(
+        "Applied filters: {} buckets filtered out, {} remaining (original: {})",
+        buckets.len() - filtered.len(),
+        filtered.len(),
+        buckets.len()
+    );
+    filtered
 }
 
 fn parse_encoded_date(date_str: &str) -> String {
@@ -480,16 +543,18 @@ fn parse_encoded_date(date_str: &str) -> String {
     "Unknown".to_string()
 }
 
-// Get cached buckets or fetch if not cached
+// Get cached buckets or fetch if not cached, applying `filters` to the result
 pub async fn get_cached_buckets(
     filters: Option<BucketFilterOptions>,
 ) -> Result<HashMap<String, SearchableBucket>, String> {
+    let filters = filters.map(CompiledBucketFilters::new).transpose()?;
+
     // First check memory cache
     {
         let cache = (*BUCKET_CACHE).read().await;
         if !cache.is_empty() {
             log::debug!("Returning {} cached buckets from memory", cache.len());
-            return Ok(cache.clone());
+            return Ok(filter_bucket_map(&cache, filters.as_ref()));
         }
     }
 
@@ -497,59 +562,189 @@ pub async fn get_cached_buckets(
     match load_cache_from_disk().await {
         Ok(disk_cache) if !disk_cache.is_empty() => {
             log::info!("Loaded {} buckets from disk cache", disk_cache.len());
+            let filtered = filter_bucket_map(&disk_cache, filters.as_ref());
+            *BUCKET_CACHE.write().await = disk_cache;
+            return Ok(filtered);
+        }
+        Ok(_) => log::info!("Disk cache is empty or doesn't exist"),
+        Err(e) => log::warn!("Failed to load disk cache: {}", e),
+    }
 
-            // If filters are provided, apply them to cached data
-            let filtered_cache = if let Some(ref filter_opts) = filters {
-                if filter_opts.disable_chinese_buckets || filter_opts.minimum_stars > 0 {
-                    log::info!("Applying filters to cached data");
-                    let mut filtered = HashMap::new();
-                    let mut filtered_count = 0;
-                    let original_count = disk_cache.len();
-
-                    for (key, bucket) in disk_cache {
-                        if apply_bucket_filters(&bucket, filter_opts) {
-                            filtered.insert(key, bucket);
-                        } else {
-                            filtered_count += 1;
-                        }
-                    }
-
-                    log::info!(
-                        "Filtered cache: {} buckets filtered out, {} remaining (original: {})",
-                        filtered_count,
-                        filtered.len(),
-                        original_count
-                    );
-                    filtered
-                } else {
-                    disk_cache
-                }
-            } else {
-                disk_cache
+    log::info!("No cache found, fetching bucket directory...");
+    let buckets = fetch_and_parse_bucket_directory().await?;
+    let filtered = filter_bucket_map(&buckets, filters.as_ref());
+    *BUCKET_CACHE.write().await = buckets;
+
+    Ok(filtered)
+}
+
+/// Progress of the background bucket cache prewarm, emitted as `bucket-cache-progress`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketCachePrewarmProgress {
+    /// One of `loading`, `downloading` or `done`.
+    pub stage: String,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+}
+
+/// Payload of the `bucket-cache-ready` event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketCacheReady {
+    pub success: bool,
+    pub bucket_count: usize,
+    pub from_disk: bool,
+    pub error: Option<String>,
+}
+
+static PREWARM_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Operation id of the milestones the prewarm emits.
+pub const PREWARM_OPERATION_ID: &str = "bucket-prewarm";
+
+/// Clears `PREWARM_RUNNING` when the prewarm ends, including by panic, so a restart
+/// by the task supervisor is not mistaken for a concurrent run.
+struct PrewarmGuard;
+
+impl Drop for PrewarmGuard {
+    fn drop(&mut self) {
+        PREWARM_RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Populates the bucket search cache in the background so the first expanded search
+/// does not have to download the bucket directory.
+///
+/// Emits `bucket-cache-progress` while working and `bucket-cache-ready` when done. Its
+/// milestones are also saved in an operation log, when logs can be written.
+pub async fn prewarm_bucket_cache<R: Runtime>(app: AppHandle<R>) {
+    if PREWARM_RUNNING.swap(true, Ordering::SeqCst) {
+        log::debug!("Bucket cache prewarm already running");
+        return;
+    }
+    let _guard = PrewarmGuard;
+
+    let op_log = OperationLog::start(
+        Some(PREWARM_OPERATION_ID),
+        "Prewarm bucket directory",
+        "bucket prewarm",
+        OperationClass::Bucket,
+    );
+    // Milestones are only emitted per stage, and per quarter while downloading
+    let last_milestone = std::sync::Mutex::new(None::<(String, u64)>);
+    let emit_progress = |stage: &str, downloaded_bytes: u64, total_bytes: Option<u64>| {
+        let percent = total_bytes
+            .filter(|total| *total > 0)
+            .map(|total| downloaded_bytes.min(total) * 100 / total);
+        let step = (stage.to_string(), percent.map_or(0, |p| p / 25));
+        let is_new = last_milestone
+            .lock()
+            .map(|mut last| last.replace(step.clone()) != Some(step))
+            .unwrap_or(false);
+        if is_new {
+            let (milestone_stage, summary) = match (stage, percent) {
+                ("loading", _) => (
+                    MilestoneStage::Loading,
+                    "Loading the bucket directory".to_string(),
+                ),
+                ("downloading", Some(percent)) => (
+                    MilestoneStage::Downloading,
+                    format!("Downloading the bucket directory: {}%", percent),
+                ),
+                ("downloading", None) => (
+                    MilestoneStage::Downloading,
+                    "Downloading the bucket directory".to_string(),
+                ),
+                _ => (MilestoneStage::Done, "Bucket directory ready".to_string()),
             };
+            let milestone = OperationMilestone::new(
+                Some(PREWARM_OPERATION_ID.to_string()),
+                milestone_stage,
+                summary,
+            );
+            if let Some(op_log) = &op_log {
+                op_log.record_milestone(&milestone);
+            }
+            emit_milestone(&app, &milestone);
+        }
+
+        let progress = BucketCachePrewarmProgress {
+            stage: stage.to_string(),
+            downloaded_bytes,
+            total_bytes,
+        };
+        if let Err(e) = app.emit("bucket-cache-progress", progress) {
+            log::warn!("Failed to emit bucket-cache-progress: {}", e);
+        }
+    };
+
+    let already_cached = !BUCKET_CACHE.read().await.is_empty();
+    let on_disk = cache_exists().await.unwrap_or(false);
 
-            // Update memory cache
+    let result = if already_cached || on_disk {
+        emit_progress("loading", 0, None);
+        get_cached_buckets(None).await.map(|b| (b.len(), true))
+    } else {
+        log::info!("Prewarming bucket search cache in the background");
+        let last_reported = std::sync::atomic::AtomicU64::new(0);
+        let fetched = fetch_and_parse_bucket_directory_with_progress(|downloaded, total| {
+            // Throttle events to roughly every 512 KB
+            if downloaded.saturating_sub(last_reported.load(Ordering::Relaxed)) >= 512 * 1024
+                || Some(downloaded) == total
             {
-                let mut cache = BUCKET_CACHE.write().await;
-                *cache = filtered_cache.clone();
+                last_reported.store(downloaded, Ordering::Relaxed);
+                emit_progress("downloading", downloaded, total);
             }
+        })
+        .await;
 
-            return Ok(filtered_cache);
+        match fetched {
+            Ok(buckets) => {
+                let count = buckets.len();
+                *BUCKET_CACHE.write().await = buckets;
+                Ok((count, false))
+            }
+            Err(e) => Err(e),
         }
-        Ok(_) => log::info!("Disk cache is empty or doesn't exist"),
-        Err(e) => log::warn!("Failed to load disk cache: {}", e),
-    }
+    };
 
-    log::info!("No cache found, fetching bucket directory...");
-    let buckets = fetch_and_parse_bucket_directory(filters).await?;
+    let ready = match result {
+        Ok((bucket_count, from_disk)) => {
+            emit_progress("done", 0, None);
+            log::info!(
+                "Bucket search cache ready with {} buckets (from disk: {})",
+                bucket_count,
+                from_disk
+            );
+            BucketCacheReady {
+                success: true,
+                bucket_count,
+                from_disk,
+                error: None,
+            }
+        }
+        Err(e) => {
+            log::warn!("Failed to prewarm bucket search cache: {}", e);
+            BucketCacheReady {
+                success: false,
+                bucket_count: 0,
+                from_disk: false,
+                error: Some(e),
+            }
+        }
+    };
 
-    // Update memory cache
-    {
-        let mut cache = (*BUCKET_CACHE).write().await;
-        *cache = buckets.clone();
+    if let Some(op_log) = &op_log {
+        let outcome = match &ready.error {
+            Some(e) => Err(e.clone()),
+            None => Ok(()),
+        };
+        op_log.finish(&outcome, "Bucket directory ready", false);
     }
 
-    Ok(buckets)
+    if let Err(e) = app.emit("bucket-cache-ready", ready) {
+        log::warn!("Failed to emit bucket-cache-ready: {}", e);
+    }
 }
 
 // Check if cache file exists
@@ -563,6 +758,10 @@ pub async fn clear_cache() {
     // Clear memory cache
     let mut cache = (*BUCKET_CACHE).write().await;
     cache.clear();
+    PACKAGE_INDEX.write().await.clear();
+    if let Ok(index_file) = get_package_index_path() {
+        let _ = fs::remove_file(index_file).await;
+    }
 
     // Clear disk cache
     if let Ok(cache_file) = get_cache_file_path() {
@@ -577,3 +776,23 @@ pub async fn clear_cache() {
 
     log::info!("Bucket cache cleared (memory and disk)");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexes_apps_by_manifest_links() {
+        let content = "\
+|[7-Zip](https://www.7-zip.org/)|[23.01](https://github.com/ScoopInstaller/Main/blob/master/bucket/7zip.json)|A file archiver|
+|[7-Zip](https://www.7-zip.org/)|[23.01](https://github.com/someone/scoop-stuff/blob/main/7zip.json)|Same app|
+|[Git](https://git-scm.com/)|[2.44](https://github.com/ScoopInstaller/Main/blob/master/bucket/git.json)|VCS|
+";
+        let index = parse_package_index(content);
+        assert_eq!(
+            index["7zip"],
+            vec!["ScoopInstaller/Main", "someone/scoop-stuff"]
+        );
+        assert_eq!(index["git"], vec!["ScoopInstaller/Main"]);
+    }
+}
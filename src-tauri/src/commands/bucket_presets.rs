@@ -0,0 +1,136 @@
+//! Curated bucket presets for first-run setup.
+//!
+//! A preset is a named set of buckets, such as "Developer essentials", added in one go.
+//! The list is read from `docs/bucket-presets.json` in the app repository so presets can
+//! change without a release; the copy bundled at build time is used when it cannot be
+//! fetched.
+use crate::commands::bucket_install::{add_buckets, BatchBucketResult};
+use crate::state::AppState;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::{State, Window};
+use tokio::sync::Mutex;
+
+const BUCKET_PRESETS_URL: &str =
+    "https://raw.githubusercontent.com/Kwensiu/Pailer/refs/heads/main/docs/bucket-presets.json";
+const BUNDLED_PRESETS: &str = include_str!("../../../docs/bucket-presets.json");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketPreset {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Bucket name to repository URL.
+    pub buckets: BTreeMap<String, String>,
+}
+
+/// Presets by id.
+type BucketPresets = BTreeMap<String, BucketPreset>;
+
+static PRESETS: Lazy<Mutex<Option<BucketPresets>>> = Lazy::new(|| Mutex::new(None));
+
+fn parse_presets(content: &str) -> Result<BucketPresets, String> {
+    let presets: BucketPresets = serde_json::from_str(content)
+        .map_err(|e| format!("Failed to parse bucket presets: {}", e))?;
+    if presets.is_empty() {
+        return Err("Bucket preset list is empty".to_string());
+    }
+    Ok(presets)
+}
+
+/// Lowercases `name` and joins its words with dashes, the form preset ids take.
+fn normalize(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Finds a preset by id or display name, ignoring case and separators, so
+/// "developer essentials" finds `developer-essentials`.
+pub fn find_preset<'a>(presets: &'a BucketPresets, name: &str) -> Option<&'a BucketPreset> {
+    let wanted = normalize(name);
+    presets
+        .iter()
+        .find(|(id, preset)| normalize(id) == wanted || normalize(&preset.name) == wanted)
+        .map(|(_, preset)| preset)
+}
+
+async fn fetch_presets() -> Result<BucketPresets, String> {
+    let content = crate::http::get(BUCKET_PRESETS_URL)
+        .await
+        .and_then(|response| response.error_for_status().map_err(|e| e.to_string()))
+        .map_err(|e| format!("Failed to fetch bucket presets: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read bucket presets: {}", e))?;
+    parse_presets(&content)
+}
+
+/// Returns the presets, fetched once per run and bundled when that fails.
+pub async fn load_presets() -> BucketPresets {
+    let mut cached = PRESETS.lock().await;
+    if let Some(presets) = cached.as_ref() {
+        return presets.clone();
+    }
+    let presets = match fetch_presets().await {
+        Ok(presets) => presets,
+        Err(e) => {
+            log::warn!("{}; using bundled bucket presets", e);
+            parse_presets(BUNDLED_PRESETS).unwrap_or_default()
+        }
+    };
+    *cached = Some(presets.clone());
+    presets
+}
+
+#[tauri::command]
+pub async fn get_bucket_presets() -> Result<BucketPresets, String> {
+    Ok(load_presets().await)
+}
+
+/// Adds every bucket of the named preset that is not added yet, one after another.
+///
+/// Progress is emitted as batch bucket progress events over the buckets to clone.
+#[tauri::command]
+pub async fn install_bucket_preset(
+    window: Window,
+    state: State<'_, AppState>,
+    preset: String,
+) -> Result<BatchBucketResult, String> {
+    let presets = load_presets().await;
+    let found = find_preset(&presets, &preset)
+        .ok_or_else(|| format!("Unknown bucket preset '{}'", preset))?;
+
+    let buckets_dir = state.scoop_path().join("buckets");
+    let (existing, missing): (Vec<_>, Vec<_>) = found
+        .buckets
+        .clone()
+        .into_iter()
+        .partition(|(name, _)| buckets_dir.join(name).exists());
+    log::info!(
+        "Installing bucket preset '{}': {} to add, {} already added",
+        found.name,
+        missing.len(),
+        existing.len()
+    );
+
+    add_buckets(window, missing.into_iter().collect()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_bundled_presets_by_name() {
+        let presets = parse_presets(BUNDLED_PRESETS).unwrap();
+        let preset = find_preset(&presets, "Developer Essentials").unwrap();
+        assert!(preset.buckets.contains_key("main"));
+        assert_eq!(find_preset(&presets, "gaming"), presets.get("gaming"));
+        assert!(find_preset(&presets, "unknown").is_none());
+    }
+}
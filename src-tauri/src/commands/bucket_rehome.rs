@@ -0,0 +1,293 @@
+//! Re-homing packages whose bucket is gone.
+//!
+//! A package installed from a bucket that was later removed or renamed resolves to
+//! its installed manifest only (the "Installed (Bucket missing)" case), so it never
+//! gets updates. Other local buckets with a manifest of the same name, and community
+//! buckets from the cached scoop-directory index, are offered as new homes. A single
+//! package moves with `change_package_bucket`; [`rehome_orphaned_installs`] moves all
+//! of them to their best local candidate at once.
+use crate::commands::bucket_parser::{self, PackageIndex};
+use crate::commands::installed::invalidate_installed_cache;
+use crate::commands::linker::rewrite_install_bucket;
+use crate::state::AppState;
+use crate::utils::find_manifest_candidates;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Emitted with the [`OrphanedInstall`] list when installs with a missing bucket are found.
+pub const EVENT_ORPHANED_INSTALLS_DETECTED: &str = "orphaned-installs-detected";
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RehomeCandidate {
+    pub bucket: String,
+    pub version: Option<String>,
+    /// The manifest is at the installed version.
+    pub same_version: bool,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedInstall {
+    pub package: String,
+    pub version: Option<String>,
+    /// Bucket recorded in `install.json`; none when it records no bucket at all.
+    pub missing_bucket: Option<String>,
+    /// Added buckets providing the package, installed version first.
+    pub candidates: Vec<RehomeCandidate>,
+    /// Repositories (`owner/repo`) from the scoop-directory index that ship the package
+    /// and would have to be added first.
+    pub community_buckets: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Rehome {
+    pub package: String,
+    pub bucket: Option<String>,
+    pub rehomed: bool,
+    pub error: Option<String>,
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn manifest_version(path: &Path) -> Option<String> {
+    read_json(path)?
+        .get("version")
+        .and_then(Value::as_str)
+        .map(String::from)
+}
+
+/// Lists the installed packages whose recorded bucket is no longer added, with the
+/// local buckets that could take them over.
+///
+/// Packages installed from a URL or file are not tied to a bucket and are skipped.
+pub fn find_orphaned_installs(scoop_dir: &Path) -> Vec<OrphanedInstall> {
+    let Ok(entries) = fs::read_dir(scoop_dir.join("apps")) else {
+        return Vec::new();
+    };
+    let buckets_dir = scoop_dir.join("buckets");
+
+    let mut orphaned: Vec<OrphanedInstall> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let package = entry.file_name().to_string_lossy().to_string();
+            let current = entry.path().join("current");
+            let install = read_json(&current.join("install.json"))?;
+            if install.get("url").is_some() {
+                return None;
+            }
+            let bucket = install
+                .get("bucket")
+                .and_then(Value::as_str)
+                .filter(|b| !b.is_empty())
+                .map(String::from);
+            if bucket
+                .as_deref()
+                .is_some_and(|b| buckets_dir.join(b).is_dir())
+            {
+                return None;
+            }
+
+            let version = manifest_version(&current.join("manifest.json"));
+            let mut candidates: Vec<RehomeCandidate> =
+                find_manifest_candidates(scoop_dir, &package)
+                    .into_iter()
+                    .map(|(path, bucket)| {
+                        let candidate_version = manifest_version(&path);
+                        RehomeCandidate {
+                            bucket,
+                            same_version: candidate_version.is_some()
+                                && candidate_version == version,
+                            version: candidate_version,
+                        }
+                    })
+                    .collect();
+            candidates.sort_by_key(|c| !c.same_version);
+
+            Some(OrphanedInstall {
+                package,
+                version,
+                missing_bucket: bucket,
+                candidates,
+                community_buckets: Vec::new(),
+            })
+        })
+        .collect();
+    orphaned.sort_by(|a, b| a.package.cmp(&b.package));
+    orphaned
+}
+
+/// Fills in the community buckets shipping each orphaned package.
+pub fn attach_community_buckets(orphaned: &mut [OrphanedInstall], index: &PackageIndex) {
+    for install in orphaned {
+        install.community_buckets = index
+            .get(&install.package.to_lowercase())
+            .cloned()
+            .unwrap_or_default();
+    }
+}
+
+/// Picks the bucket to move `install` to; a `batch` run only takes one at the
+/// installed version.
+fn rehome_target(install: &OrphanedInstall, batch: bool) -> Result<String, String> {
+    match install.candidates.first() {
+        Some(candidate) if !batch || candidate.same_version => Ok(candidate.bucket.clone()),
+        Some(_) => Err(
+            "No added bucket provides the installed version; re-home it individually".to_string(),
+        ),
+        None => Err("No added bucket provides the package".to_string()),
+    }
+}
+
+async fn orphaned_installs(scoop_dir: &Path) -> Result<Vec<OrphanedInstall>, String> {
+    let scan_dir = scoop_dir.to_path_buf();
+    let mut orphaned = tokio::task::spawn_blocking(move || find_orphaned_installs(&scan_dir))
+        .await
+        .map_err(|e| format!("Failed to check installs: {}", e))?;
+    if !orphaned.is_empty() {
+        match bucket_parser::get_package_index().await {
+            Ok(index) => attach_community_buckets(&mut orphaned, &index),
+            Err(e) => log::warn!("Scoop directory index unavailable: {}", e),
+        }
+    }
+    Ok(orphaned)
+}
+
+/// Looks for installs with a missing bucket and emits them; run during cold start.
+pub async fn check_orphaned_installs<R: Runtime>(app: &AppHandle<R>) -> Vec<OrphanedInstall> {
+    let scoop_dir = app.state::<AppState>().scoop_path();
+    let orphaned = orphaned_installs(&scoop_dir).await.unwrap_or_default();
+
+    if !orphaned.is_empty() {
+        log::warn!(
+            "Found {} packages whose bucket is missing: {}",
+            orphaned.len(),
+            orphaned
+                .iter()
+                .map(|o| o.package.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        if let Err(e) = app.emit(EVENT_ORPHANED_INSTALLS_DETECTED, &orphaned) {
+            log::warn!(
+                "Failed to emit {} event: {}",
+                EVENT_ORPHANED_INSTALLS_DETECTED,
+                e
+            );
+        }
+    }
+    orphaned
+}
+
+#[tauri::command]
+pub async fn get_orphaned_installs(
+    state: State<'_, AppState>,
+) -> Result<Vec<OrphanedInstall>, String> {
+    orphaned_installs(&state.scoop_path()).await
+}
+
+/// Moves orphaned packages to their first candidate bucket, preferring one at the
+/// installed version. Without `packages`, every orphaned package is moved, but only to
+/// a bucket at its installed version; the others are left for the user to pick.
+#[tauri::command]
+pub async fn rehome_orphaned_installs(
+    state: State<'_, AppState>,
+    packages: Option<Vec<String>>,
+) -> Result<Vec<Rehome>, String> {
+    let mut rehomes = Vec::new();
+    {
+        let _queue = state.operation_queue.lock().await;
+        let scoop_dir = state.scoop_path();
+        let scan_dir = scoop_dir.clone();
+        let orphaned = tokio::task::spawn_blocking(move || find_orphaned_installs(&scan_dir))
+            .await
+            .map_err(|e| format!("Failed to check installs: {}", e))?;
+
+        for install in orphaned {
+            if packages
+                .as_ref()
+                .is_some_and(|names| !names.contains(&install.package))
+            {
+                continue;
+            }
+            let target = rehome_target(&install, packages.is_none());
+            let bucket = target.as_ref().ok().cloned();
+            let result = target.and_then(|bucket| {
+                rewrite_install_bucket(&scoop_dir.join("apps").join(&install.package), &bucket)
+                    .map(|_| ())
+            });
+            match &result {
+                Ok(()) => log::info!(
+                    "Re-homed '{}' to bucket '{}'",
+                    install.package,
+                    bucket.as_deref().unwrap_or("?")
+                ),
+                Err(e) => log::warn!("Could not re-home '{}': {}", install.package, e),
+            }
+            rehomes.push(Rehome {
+                package: install.package,
+                bucket,
+                rehomed: result.is_ok(),
+                error: result.err(),
+            });
+        }
+    }
+
+    if rehomes.iter().any(|r| r.rehomed) {
+        invalidate_installed_cache(state).await;
+    }
+    Ok(rehomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScoopFixture;
+
+    #[test]
+    fn finds_installs_with_missing_bucket() {
+        let fixture = ScoopFixture::new();
+        fixture
+            .install("git", "2.44.0", "main")
+            .install("foo", "1.2.0", "old-bucket")
+            .bucket_manifest("main", "git", "2.44.0")
+            .bucket_manifest("fork", "foo", "1.3.0")
+            .bucket_manifest("mirror", "foo", "1.2.0");
+        let mut index = PackageIndex::new();
+        index.insert("foo".to_string(), vec!["someone/scoop-foo".to_string()]);
+
+        let mut orphaned = find_orphaned_installs(fixture.root());
+        attach_community_buckets(&mut orphaned, &index);
+        assert_eq!(orphaned.len(), 1);
+        let foo = &orphaned[0];
+        assert_eq!(foo.package, "foo");
+        assert_eq!(foo.missing_bucket.as_deref(), Some("old-bucket"));
+        assert_eq!(foo.candidates[0].bucket, "mirror");
+        assert!(foo.candidates[0].same_version);
+        assert_eq!(foo.candidates[1].bucket, "fork");
+        assert_eq!(foo.community_buckets, vec!["someone/scoop-foo"]);
+
+        rewrite_install_bucket(&fixture.app_dir("foo"), "mirror").unwrap();
+        assert!(find_orphaned_installs(fixture.root()).is_empty());
+    }
+
+    #[test]
+    fn batch_rehome_requires_the_installed_version() {
+        let fixture = ScoopFixture::new();
+        fixture
+            .install("foo", "1.2.0", "old-bucket")
+            .bucket_manifest("fork", "foo", "1.3.0");
+        let orphaned = find_orphaned_installs(fixture.root());
+        let foo = &orphaned[0];
+
+        assert!(rehome_target(foo, true).is_err());
+        assert_eq!(rehome_target(foo, false).unwrap(), "fork");
+    }
+}
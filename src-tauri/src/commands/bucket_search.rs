@@ -1,7 +1,13 @@
-use super::bucket_parser::{self, BucketFilterOptions};
+use super::bucket_filter::{BucketFilterOptions, BucketFilterRule, CompiledBucketFilters};
+use super::bucket_parser;
+use super::known_buckets;
+use super::settings;
+use crate::config_keys::{BUCKET_ACTIVE_FILTER_PRESET, BUCKET_FILTER_PRESETS};
 use crate::state::AppState;
+use crate::utils;
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use std::collections::{BTreeMap, HashMap};
+use tauri::{AppHandle, State};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchableBucket {
@@ -14,6 +20,9 @@ pub struct SearchableBucket {
     pub apps: u32,
     pub last_updated: String,
     pub is_verified: bool,
+    /// Full name of the bucket this entry mirrors, if it points at the same repository.
+    #[serde(default)]
+    pub duplicate_of: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,6 +33,13 @@ pub struct BucketSearchRequest {
     pub sort_by: Option<String>, // "stars", "apps", "name", "relevance"
     pub disable_chinese_buckets: Option<bool>,
     pub minimum_stars: Option<u32>,
+    #[serde(default)]
+    pub excluded_scripts: Option<Vec<String>>,
+    #[serde(default)]
+    pub rules: Option<Vec<BucketFilterRule>>,
+    /// Name of a saved filter preset; takes precedence over the individual filters.
+    #[serde(default)]
+    pub filter_preset: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -41,7 +57,8 @@ pub struct BucketSearchResponse {
 //     pub description: String,
 // }
 
-// Default verified buckets - these show automatically
+// Bundled metadata for the verified buckets. Used as-is when the official list cannot be
+// fetched, and to fill in descriptions and counts for buckets on the fetched list.
 static VERIFIED_BUCKETS_DATA: &[(&str, &str, &str, &str, u32, u32, u32, &str)] = &[
     (
         "main",
@@ -145,7 +162,7 @@ static VERIFIED_BUCKETS_DATA: &[(&str, &str, &str, &str, u32, u32, u32, &str)] =
     ),
 ];
 
-fn get_verified_buckets() -> Vec<SearchableBucket> {
+pub fn get_bundled_verified_buckets() -> Vec<SearchableBucket> {
     VERIFIED_BUCKETS_DATA
         .iter()
         .map(
@@ -160,12 +177,46 @@ fn get_verified_buckets() -> Vec<SearchableBucket> {
                     apps,
                     last_updated: last_updated.to_string(),
                     is_verified: true,
+                    duplicate_of: None,
                 }
             },
         )
         .collect()
 }
 
+/// Builds the verified bucket list from Scoop's official `buckets.json`, falling back
+/// to the bundled snapshot when the list is unavailable.
+async fn get_verified_buckets() -> Vec<SearchableBucket> {
+    let bundled = get_bundled_verified_buckets();
+    let Some(known) = known_buckets::get_known_buckets().await else {
+        return bundled;
+    };
+
+    known
+        .into_iter()
+        .map(|(name, url)| {
+            match bundled.iter().find(|b| b.name.eq_ignore_ascii_case(&name)) {
+                Some(bundled_bucket) => SearchableBucket {
+                    url,
+                    ..bundled_bucket.clone()
+                },
+                None => SearchableBucket {
+                    full_name: known_buckets::repo_full_name(&url),
+                    description: format!("Official Scoop bucket '{}'", name),
+                    name,
+                    url,
+                    stars: 0,
+                    forks: 0,
+                    apps: 0,
+                    last_updated: String::new(),
+                    is_verified: true,
+                    duplicate_of: None,
+                },
+            }
+        })
+        .collect()
+}
+
 // Parse the massive bucket list from GitHub using efficient parser
 async fn fetch_expanded_bucket_list(
     filters: Option<BucketFilterOptions>,
@@ -179,6 +230,40 @@ async fn fetch_expanded_bucket_list(
     Ok(buckets)
 }
 
+/// Marks buckets that point at the same repository as another, higher-ranked entry.
+/// Verified buckets win, then the one with more stars.
+fn mark_duplicate_buckets(buckets: &mut [SearchableBucket]) {
+    let mut keepers: HashMap<String, (bool, u32, String)> = HashMap::new();
+    for bucket in buckets.iter() {
+        let key = utils::canonical_repo_key(&bucket.url);
+        if key.is_empty() {
+            continue;
+        }
+        let candidate = (bucket.is_verified, bucket.stars, bucket.full_name.clone());
+        match keepers.get(&key) {
+            Some(existing) if (existing.0, existing.1) >= (candidate.0, candidate.1) => {}
+            _ => {
+                keepers.insert(key, candidate);
+            }
+        }
+    }
+
+    let mut duplicate_count = 0;
+    for bucket in buckets.iter_mut() {
+        let key = utils::canonical_repo_key(&bucket.url);
+        if let Some((_, _, keeper)) = keepers.get(&key) {
+            if *keeper != bucket.full_name {
+                bucket.duplicate_of = Some(keeper.clone());
+                duplicate_count += 1;
+            }
+        }
+    }
+
+    if duplicate_count > 0 {
+        log::info!("Marked {} buckets as duplicates of another repository", duplicate_count);
+    }
+}
+
 fn filter_buckets(buckets: &[SearchableBucket], query: &str) -> Vec<SearchableBucket> {
     if query.is_empty() {
         return buckets.to_vec();
@@ -215,36 +300,81 @@ fn sort_buckets(buckets: &mut [SearchableBucket], sort_by: &str) {
     }
 }
 
+fn load_filter_presets(app: &AppHandle) -> Result<BTreeMap<String, BucketFilterOptions>, String> {
+    match settings::get_config_value(app.clone(), BUCKET_FILTER_PRESETS.to_string())? {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| format!("Failed to read bucket filter presets: {}", e)),
+        None => Ok(BTreeMap::new()),
+    }
+}
+
+fn save_filter_presets(
+    app: &AppHandle,
+    presets: &BTreeMap<String, BucketFilterOptions>,
+) -> Result<(), String> {
+    let value = serde_json::to_value(presets)
+        .map_err(|e| format!("Failed to serialize bucket filter presets: {}", e))?;
+    settings::set_config_value(app.clone(), BUCKET_FILTER_PRESETS.to_string(), value)
+}
+
+/// Resolves the filters for a search: a named preset, the individual filters in the
+/// request, or the active preset from settings, in that order.
+fn resolve_filters(
+    app: &AppHandle,
+    request: &BucketSearchRequest,
+) -> Result<Option<BucketFilterOptions>, String> {
+    if let Some(name) = &request.filter_preset {
+        return load_filter_presets(app)?
+            .remove(name)
+            .map(Some)
+            .ok_or_else(|| format!("Bucket filter preset '{}' does not exist", name));
+    }
+
+    let excluded_scripts = request.excluded_scripts.clone().unwrap_or_default();
+    let rules = request.rules.clone().unwrap_or_default();
+    if request.disable_chinese_buckets.unwrap_or(false)
+        || request.minimum_stars.unwrap_or(0) > 0
+        || !excluded_scripts.is_empty()
+        || !rules.is_empty()
+    {
+        return Ok(Some(BucketFilterOptions {
+            disable_chinese_buckets: request.disable_chinese_buckets.unwrap_or(false),
+            minimum_stars: request.minimum_stars.unwrap_or(2),
+            excluded_scripts,
+            rules,
+        }));
+    }
+
+    let active = settings::get_config_value(app.clone(), BUCKET_ACTIVE_FILTER_PRESET.to_string())?
+        .and_then(|value| value.as_str().map(String::from));
+    match active {
+        Some(name) => Ok(load_filter_presets(app)?.remove(&name)),
+        None => Ok(None),
+    }
+}
+
 #[tauri::command]
 pub async fn search_buckets(
+    app: AppHandle,
     request: BucketSearchRequest,
     _state: State<'_, AppState>,
 ) -> Result<BucketSearchResponse, String> {
     let mut buckets = if request.include_expanded {
         log::info!("Performing expanded search including all community buckets");
 
-        // Create filter options from request
-        let filters = if request.disable_chinese_buckets.unwrap_or(false)
-            || request.minimum_stars.unwrap_or(0) > 0
-        {
-            Some(BucketFilterOptions {
-                disable_chinese_buckets: request.disable_chinese_buckets.unwrap_or(false),
-                minimum_stars: request.minimum_stars.unwrap_or(2),
-            })
-        } else {
-            None
-        };
-
+        let filters = resolve_filters(&app, &request)?;
         if let Some(ref filter_opts) = filters {
             log::info!(
-                "Applying filters - Chinese buckets disabled: {}, Minimum stars: {}",
+                "Applying filters - Chinese buckets disabled: {}, Minimum stars: {}, Excluded scripts: {:?}, Rules: {}",
                 filter_opts.disable_chinese_buckets,
-                filter_opts.minimum_stars
+                filter_opts.minimum_stars,
+                filter_opts.excluded_scripts,
+                filter_opts.rules.len()
             );
         }
 
         // Get verified buckets
-        let verified_buckets = get_verified_buckets();
+        let verified_buckets = get_verified_buckets().await;
         let verified_names: std::collections::HashSet<String> =
             verified_buckets.iter().map(|b| b.name.clone()).collect();
 
@@ -266,11 +396,12 @@ pub async fn search_buckets(
             }
         }
 
+        mark_duplicate_buckets(&mut all_buckets);
         all_buckets
     } else {
         log::info!("Performing default search with verified buckets only");
         // Only return verified buckets for default search
-        get_verified_buckets()
+        get_verified_buckets().await
     };
 
     // Apply search filter if query is provided
@@ -316,6 +447,181 @@ pub async fn search_buckets(
     })
 }
 
+/// A community bucket that ships a manifest for a requested app.
+#[derive(Debug, Clone, Serialize)]
+pub struct BucketContainingPackage {
+    pub bucket: SearchableBucket,
+    /// Where the match came from: `directory` or `github`.
+    pub source: String,
+    /// A local bucket already points at this repository.
+    pub already_added: bool,
+}
+
+fn normalize_repo_url(url: &str) -> String {
+    url.trim()
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .to_lowercase()
+}
+
+/// Searches GitHub for `bucket/<app>.json`, returning the repositories' full names.
+///
+/// Code search needs authentication, so this only runs with a `GITHUB_TOKEN`.
+async fn search_github_manifests(app_name: &str) -> Result<Vec<(String, String)>, String> {
+    let token = std::env::var("GITHUB_TOKEN")
+        .map_err(|_| "GITHUB_TOKEN is not set; GitHub code search needs a token".to_string())?;
+    let url = format!(
+        "https://api.github.com/search/code?q=filename:{}.json+extension:json&per_page=100",
+        app_name
+    );
+    let response = crate::http::client()?
+        .get(&url)
+        .bearer_auth(token)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| format!("GitHub code search failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("GitHub code search returned {}", response.status()));
+    }
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse GitHub search results: {}", e))?;
+
+    let expected = [
+        format!("bucket/{}.json", app_name),
+        format!("{}.json", app_name),
+    ];
+    Ok(body["items"]
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter(|item| {
+                    item["path"]
+                        .as_str()
+                        .is_some_and(|path| expected.iter().any(|e| e.eq_ignore_ascii_case(path)))
+                })
+                .filter_map(|item| {
+                    let repo = &item["repository"];
+                    Some((
+                        repo["full_name"].as_str()?.to_string(),
+                        repo["description"].as_str().unwrap_or_default().to_string(),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Finds community buckets that ship a manifest named `package_name`, most starred first.
+///
+/// Uses the cached scoop-directory listing, plus GitHub code search when
+/// `search_github` is set and a token is available.
+#[tauri::command]
+pub async fn find_buckets_containing(
+    state: State<'_, AppState>,
+    package_name: String,
+    search_github: Option<bool>,
+) -> Result<Vec<BucketContainingPackage>, String> {
+    let app_name = package_name.trim().to_lowercase();
+    if app_name.is_empty() {
+        return Err("Package name is empty".to_string());
+    }
+    log::info!("Looking for community buckets containing '{}'", app_name);
+
+    let index = bucket_parser::get_package_index().await?;
+    let known = bucket_parser::get_cached_buckets(None)
+        .await
+        .unwrap_or_default();
+    let verified: HashMap<String, SearchableBucket> = get_verified_buckets()
+        .await
+        .into_iter()
+        .map(|b| (b.full_name.to_lowercase(), b))
+        .collect();
+
+    let lookup = |full_name: &str, description: &str| -> SearchableBucket {
+        let key = full_name.to_lowercase();
+        if let Some(bucket) = verified.get(&key) {
+            return bucket.clone();
+        }
+        known
+            .iter()
+            .find(|(name, _)| name.to_lowercase() == key)
+            .map(|(_, bucket)| bucket.clone())
+            .unwrap_or_else(|| {
+                let repo = full_name.rsplit('/').next().unwrap_or(full_name);
+                SearchableBucket {
+                    name: repo
+                        .trim_start_matches("scoop-")
+                        .trim_start_matches("Scoop-")
+                        .to_lowercase(),
+                    full_name: full_name.to_string(),
+                    description: description.to_string(),
+                    url: format!("https://github.com/{}", full_name),
+                    stars: 0,
+                    forks: 0,
+                    apps: 0,
+                    last_updated: "Unknown".to_string(),
+                    is_verified: false,
+                    duplicate_of: None,
+                }
+            })
+    };
+
+    let mut results: Vec<BucketContainingPackage> = index
+        .get(&app_name)
+        .into_iter()
+        .flatten()
+        .map(|full_name| BucketContainingPackage {
+            bucket: lookup(full_name, ""),
+            source: "directory".to_string(),
+            already_added: false,
+        })
+        .collect();
+
+    if search_github.unwrap_or(false) {
+        match search_github_manifests(&app_name).await {
+            Ok(repos) => {
+                for (full_name, description) in repos {
+                    if results
+                        .iter()
+                        .any(|r| r.bucket.full_name.eq_ignore_ascii_case(&full_name))
+                    {
+                        continue;
+                    }
+                    results.push(BucketContainingPackage {
+                        bucket: lookup(&full_name, &description),
+                        source: "github".to_string(),
+                        already_added: false,
+                    });
+                }
+            }
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+
+    let local_urls: Vec<String> = super::bucket::list_local_buckets(&state.scoop_path())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|b| b.git_url.map(|url| normalize_repo_url(&url)))
+        .collect();
+    for result in &mut results {
+        result.already_added = local_urls.contains(&normalize_repo_url(&result.bucket.url));
+    }
+
+    results.sort_by(|a, b| {
+        b.bucket
+            .is_verified
+            .cmp(&a.bucket.is_verified)
+            .then(b.bucket.stars.cmp(&a.bucket.stars))
+            .then(a.bucket.full_name.cmp(&b.bucket.full_name))
+    });
+    log::info!("Found {} buckets containing '{}'", results.len(), app_name);
+    Ok(results)
+}
+
 // #[tauri::command]
 // pub async fn get_expanded_search_info() -> Result<ExpandedSearchInfo, String> {
 //     Ok(ExpandedSearchInfo {
@@ -327,15 +633,81 @@ pub async fn search_buckets(
 
 #[tauri::command]
 pub async fn get_default_buckets() -> Result<Vec<SearchableBucket>, String> {
-    let mut buckets = get_verified_buckets();
+    let mut buckets = get_verified_buckets().await;
     sort_buckets(&mut buckets, "stars"); // Sort by stars by default
     Ok(buckets)
 }
 
+/// Lists the saved bucket filter presets by name.
+#[tauri::command]
+pub fn get_bucket_filter_presets(
+    app: AppHandle,
+) -> Result<BTreeMap<String, BucketFilterOptions>, String> {
+    load_filter_presets(&app)
+}
+
+/// Saves a bucket filter preset, replacing any preset with the same name.
+#[tauri::command]
+pub fn save_bucket_filter_preset(
+    app: AppHandle,
+    name: String,
+    options: BucketFilterOptions,
+) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Preset name cannot be empty".to_string());
+    }
+    // Reject invalid patterns before they are persisted
+    CompiledBucketFilters::new(options.clone())?;
+
+    let mut presets = load_filter_presets(&app)?;
+    presets.insert(name.clone(), options);
+    save_filter_presets(&app, &presets)?;
+    log::info!("Saved bucket filter preset '{}'", name);
+    Ok(())
+}
+
+/// Deletes a bucket filter preset, clearing it as the active preset if needed.
+#[tauri::command]
+pub fn delete_bucket_filter_preset(app: AppHandle, name: String) -> Result<(), String> {
+    let mut presets = load_filter_presets(&app)?;
+    if presets.remove(&name).is_none() {
+        return Err(format!("Bucket filter preset '{}' does not exist", name));
+    }
+    save_filter_presets(&app, &presets)?;
+
+    let active = settings::get_config_value(app.clone(), BUCKET_ACTIVE_FILTER_PRESET.to_string())?;
+    if active.as_ref().and_then(|v| v.as_str()) == Some(name.as_str()) {
+        settings::set_config_value(
+            app,
+            BUCKET_ACTIVE_FILTER_PRESET.to_string(),
+            serde_json::Value::Null,
+        )?;
+    }
+    Ok(())
+}
+
+/// Sets the preset applied to searches that do not specify their own filters.
+#[tauri::command]
+pub fn set_active_bucket_filter_preset(app: AppHandle, name: Option<String>) -> Result<(), String> {
+    if let Some(name) = &name {
+        if !load_filter_presets(&app)?.contains_key(name) {
+            return Err(format!("Bucket filter preset '{}' does not exist", name));
+        }
+    }
+    settings::set_config_value(
+        app,
+        BUCKET_ACTIVE_FILTER_PRESET.to_string(),
+        name.map(serde_json::Value::String)
+            .unwrap_or(serde_json::Value::Null),
+    )
+}
+
 #[tauri::command]
 pub async fn clear_bucket_cache() -> Result<(), String> {
     log::info!("Clearing bucket cache as requested");
     bucket_parser::clear_cache().await;
+    known_buckets::clear_known_buckets_cache().await;
     Ok(())
 }
 
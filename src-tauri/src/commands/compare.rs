@@ -0,0 +1,277 @@
+//! Side-by-side comparison of two package manifests.
+//!
+//! Either side is a bucket manifest (the latest version a bucket offers) or an
+//! installed version, so the same command serves "which of these tools should I
+//! install" and "what changes if I update".
+use crate::commands::installed_search::bin_names;
+use crate::commands::statistics::dir_size;
+use crate::state::AppState;
+use crate::utils;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// One side of a comparison.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageRef {
+    pub name: String,
+    /// Bucket to take the manifest from; any bucket when unset.
+    pub bucket: Option<String>,
+    /// An installed version directory, `current` included, instead of a bucket manifest.
+    pub version: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestSummary {
+    pub name: String,
+    pub bucket: Option<String>,
+    pub manifest_path: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+    pub license: Option<String>,
+    pub bins: Vec<String>,
+    pub dependencies: Vec<String>,
+    /// Download URLs of every architecture.
+    pub urls: Vec<String>,
+    /// Disk usage, known for installed versions only.
+    pub installed_size: Option<u64>,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDiff {
+    pub only_left: Vec<String>,
+    pub only_right: Vec<String>,
+    pub common: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageComparison {
+    pub left: ManifestSummary,
+    pub right: ManifestSummary,
+    /// Names of the fields whose values differ.
+    pub changed_fields: Vec<String>,
+    pub bins: ListDiff,
+    pub dependencies: ListDiff,
+    pub urls: ListDiff,
+}
+
+/// Strings of a field that is either a string or an array of strings.
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(s)) => vec![s.clone()],
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn manifest_urls(manifest: &Value) -> Vec<String> {
+    let mut urls = string_list(manifest.get("url"));
+    if let Some(Value::Object(architectures)) = manifest.get("architecture") {
+        for arch in architectures.values() {
+            urls.extend(string_list(arch.get("url")));
+        }
+    }
+    urls.sort();
+    urls.dedup();
+    urls
+}
+
+/// `license` is either an SPDX string or an object with an `identifier`.
+fn license(manifest: &Value) -> Option<String> {
+    match manifest.get("license")? {
+        Value::String(s) => Some(s.clone()),
+        Value::Object(obj) => obj.get("identifier")?.as_str().map(String::from),
+        _ => None,
+    }
+}
+
+pub fn summarize(
+    name: &str,
+    bucket: Option<String>,
+    manifest_path: &Path,
+    manifest: &Value,
+    installed_size: Option<u64>,
+) -> ManifestSummary {
+    let text = |key: &str| manifest.get(key).and_then(Value::as_str).map(String::from);
+    let mut dependencies = string_list(manifest.get("depends"));
+    dependencies.sort();
+    ManifestSummary {
+        name: name.to_string(),
+        bucket,
+        manifest_path: manifest_path.display().to_string(),
+        version: text("version"),
+        description: text("description"),
+        homepage: text("homepage"),
+        license: license(manifest),
+        bins: bin_names(manifest),
+        dependencies,
+        urls: manifest_urls(manifest),
+        installed_size,
+    }
+}
+
+fn list_diff(left: &[String], right: &[String]) -> ListDiff {
+    let left: BTreeSet<&String> = left.iter().collect();
+    let right: BTreeSet<&String> = right.iter().collect();
+    let owned = |set: Vec<&&String>| set.into_iter().map(|s| s.to_string()).collect();
+    ListDiff {
+        only_left: owned(left.difference(&right).collect()),
+        only_right: owned(right.difference(&left).collect()),
+        common: owned(left.intersection(&right).collect()),
+    }
+}
+
+pub fn compare(left: ManifestSummary, right: ManifestSummary) -> PackageComparison {
+    let fields: [(&str, bool); 9] = [
+        ("name", left.name != right.name),
+        ("version", left.version != right.version),
+        ("description", left.description != right.description),
+        ("homepage", left.homepage != right.homepage),
+        ("license", left.license != right.license),
+        ("bins", left.bins != right.bins),
+        ("dependencies", left.dependencies != right.dependencies),
+        ("urls", left.urls != right.urls),
+        ("installedSize", left.installed_size != right.installed_size),
+    ];
+    PackageComparison {
+        changed_fields: fields
+            .iter()
+            .filter(|(_, changed)| *changed)
+            .map(|(field, _)| field.to_string())
+            .collect(),
+        bins: list_diff(&left.bins, &right.bins),
+        dependencies: list_diff(&left.dependencies, &right.dependencies),
+        urls: list_diff(&left.urls, &right.urls),
+        left,
+        right,
+    }
+}
+
+fn load_side(
+    scoop_path: &Path,
+    apps_dir: &Path,
+    side: &PackageRef,
+) -> Result<ManifestSummary, String> {
+    utils::validate_dir_name("package", &side.name)?;
+    let (path, bucket, installed_dir): (PathBuf, Option<String>, Option<PathBuf>) =
+        match side.version.as_deref().filter(|v| !v.is_empty()) {
+            Some(version) => {
+                utils::validate_dir_name("version", version)?;
+                let dir = apps_dir.join(&side.name).join(version);
+                if !dir.is_dir() {
+                    return Err(format!(
+                        "Version '{}' of '{}' is not installed",
+                        version, side.name
+                    ));
+                }
+                let bucket = std::fs::read_to_string(dir.join("install.json"))
+                    .ok()
+                    .and_then(|c| serde_json::from_str::<Value>(&c).ok())
+                    .and_then(|j| j.get("bucket")?.as_str().map(String::from));
+                (dir.join("manifest.json"), bucket, Some(dir))
+            }
+            None => {
+                let (path, bucket) =
+                    utils::locate_package_manifest(scoop_path, &side.name, side.bucket.clone())?;
+                (path, Some(bucket), None)
+            }
+        };
+
+    let manifest: Value = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read manifest {}: {}", path.display(), e))
+        .and_then(|c| {
+            serde_json::from_str(&c)
+                .map_err(|e| format!("Invalid manifest {}: {}", path.display(), e))
+        })?;
+    let installed_size = installed_dir.map(|dir| dir_size(&dir));
+    Ok(summarize(
+        &side.name,
+        bucket,
+        &path,
+        &manifest,
+        installed_size,
+    ))
+}
+
+/// Compares two packages, or two versions of one package.
+#[tauri::command]
+pub async fn compare_packages(
+    state: State<'_, AppState>,
+    left: PackageRef,
+    right: PackageRef,
+) -> Result<PackageComparison, String> {
+    let scoop_path = state.scoop_path();
+    let apps_dir = state.scoop_dirs().apps;
+    tokio::task::spawn_blocking(move || {
+        let left = load_side(&scoop_path, &apps_dir, &left)?;
+        let right = load_side(&scoop_path, &apps_dir, &right)?;
+        Ok(compare(left, right))
+    })
+    .await
+    .map_err(|e| format!("Failed to compare packages: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn diffs_two_versions() {
+        let old = json!({
+            "version": "1.0.0",
+            "license": "MIT",
+            "bin": ["tool.exe"],
+            "depends": "vcredist2022",
+            "architecture": { "64bit": { "url": "https://example.com/tool-1.0.0.zip" } }
+        });
+        let new = json!({
+            "version": "2.0.0",
+            "license": { "identifier": "MIT" },
+            "bin": ["tool.exe", ["tool.exe", "t"]],
+            "architecture": { "64bit": { "url": "https://example.com/tool-2.0.0.zip" } }
+        });
+        let path = Path::new("manifest.json");
+        let comparison = compare(
+            summarize("tool", None, path, &old, Some(10)),
+            summarize("tool", Some("main".to_string()), path, &new, None),
+        );
+
+        assert_eq!(
+            comparison.changed_fields,
+            vec!["version", "bins", "dependencies", "urls", "installedSize"]
+        );
+        assert_eq!(comparison.bins.only_right, vec!["t"]);
+        assert_eq!(comparison.bins.common, vec!["tool"]);
+        assert_eq!(comparison.dependencies.only_left, vec!["vcredist2022"]);
+        assert_eq!(comparison.urls.only_left.len(), 1);
+    }
+
+    #[test]
+    fn refuses_paths_outside_apps() {
+        let root = Path::new("scoop");
+        let side = |name: &str, version: &str| PackageRef {
+            name: name.to_string(),
+            bucket: None,
+            version: Some(version.to_string()),
+        };
+        for side in [
+            side("..", "persist"),
+            side("tool", "../../.."),
+            side("tool", ".."),
+        ] {
+            let error = load_side(root, &root.join("apps"), &side).unwrap_err();
+            assert!(error.starts_with("Invalid"), "{}", error);
+        }
+    }
+}
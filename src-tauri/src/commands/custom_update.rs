@@ -1,7 +1,15 @@
+use base64::{engine::general_purpose, Engine as _};
+use minisign_verify::{PublicKey, Signature};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use tauri::AppHandle;
-use crate::commands::update_config::get_update_channel;
+use crate::commands::settings;
+use crate::commands::update_config::{get_update_channel, set_update_channel};
+use crate::config_keys::UPDATE_INSTALLER_TYPE;
+use crate::engine::version::compare_versions;
 
 /// Represents update information from GitHub API
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -13,6 +21,31 @@ pub struct CustomUpdateInfo {
     pub notes: String,
     pub body: Option<String>,
     pub channel: String,
+    /// Name of the asset behind `download_url`.
+    #[serde(default)]
+    pub asset_name: String,
+    /// `msi` or `nsis`.
+    #[serde(default)]
+    pub installer_type: Option<String>,
+    /// Digest GitHub reports for the asset, e.g. `sha256:...`.
+    #[serde(default)]
+    pub digest: Option<String>,
+    /// Every asset of the release, so the user can pick another one.
+    #[serde(default)]
+    pub assets: Vec<CustomUpdateAsset>,
+}
+
+/// A release asset with the platform details read from its file name
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CustomUpdateAsset {
+    pub name: String,
+    pub download_url: String,
+    pub size: u64,
+    pub digest: Option<String>,
+    /// Rust target arch: `x86_64`, `aarch64` or `i686`.
+    pub arch: Option<String>,
+    /// `msi` or `nsis`; `None` for files that are not installers.
+    pub installer_type: Option<String>,
 }
 
 /// Represents a GitHub release
@@ -29,6 +62,164 @@ struct GitHubRelease {
 struct GitHubAsset {
     name: String,
     browser_download_url: String,
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+impl From<GitHubAsset> for CustomUpdateAsset {
+    fn from(asset: GitHubAsset) -> Self {
+        let lower = asset.name.to_lowercase();
+        CustomUpdateAsset {
+            arch: asset_arch(&lower).map(String::from),
+            installer_type: asset_installer_type(&lower).map(String::from),
+            name: asset.name,
+            download_url: asset.browser_download_url,
+            size: asset.size,
+            digest: asset.digest,
+        }
+    }
+}
+
+/// Architecture named in a lowercase asset file name.
+fn asset_arch(name: &str) -> Option<&'static str> {
+    if ["x64", "x86_64", "amd64"].iter().any(|a| name.contains(a)) {
+        Some("x86_64")
+    } else if ["arm64", "aarch64"].iter().any(|a| name.contains(a)) {
+        Some("aarch64")
+    } else if ["x86", "i686", "ia32"].iter().any(|a| name.contains(a)) {
+        Some("i686")
+    } else {
+        None
+    }
+}
+
+/// Installer type of a lowercase asset file name.
+fn asset_installer_type(name: &str) -> Option<&'static str> {
+    if name.ends_with(".msi") {
+        Some("msi")
+    } else if name.ends_with(".exe") {
+        Some("nsis")
+    } else {
+        None
+    }
+}
+
+/// Architecture of this build, named the way release assets are.
+fn current_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86" => "i686",
+        arch => arch,
+    }
+}
+
+/// Picks the installer for `arch`, preferring `preferred_type` when both kinds exist.
+///
+/// Assets built for another architecture are never picked; an installer that does not
+/// name an architecture is only used when no exact match exists.
+fn select_asset<'a>(
+    assets: &'a [CustomUpdateAsset],
+    arch: &str,
+    preferred_type: Option<&str>,
+) -> Option<&'a CustomUpdateAsset> {
+    assets
+        .iter()
+        .filter(|asset| asset.installer_type.is_some())
+        .filter_map(|asset| {
+            let arch_score = match asset.arch.as_deref() {
+                Some(a) if a == arch => 2,
+                None => 0,
+                Some(_) => return None,
+            };
+            let type_score = (asset.installer_type.as_deref() == preferred_type) as u8;
+            Some((arch_score + type_score, asset))
+        })
+        // max_by_key keeps the last of equal scores, so reverse to keep release order
+        .rev()
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, asset)| asset)
+}
+
+/// Checks downloaded bytes against a GitHub digest such as `sha256:abc...`.
+fn verify_digest(bytes: &[u8], digest: Option<&str>) -> Result<(), String> {
+    let Some(digest) = digest else {
+        log::warn!("Release asset has no digest, skipping verification");
+        return Ok(());
+    };
+    let Some(expected) = digest.strip_prefix("sha256:") else {
+        log::warn!("Unsupported asset digest '{}', skipping verification", digest);
+        return Ok(());
+    };
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Installer digest mismatch: expected {}, got sha256:{}",
+            digest, actual
+        ))
+    }
+}
+
+/// Checks downloaded bytes against a signature made with the updater key.
+///
+/// Both are base64 as Tauri writes them: `signature` is a `.sig` file or the
+/// `signature` of `update.json`, `pubkey` the `pubkey` of `tauri.conf.json`. Test
+/// builds are held to the same check as stable ones.
+fn verify_signature(bytes: &[u8], signature: &str, pubkey: &str) -> Result<(), String> {
+    let decode = |value: &str| {
+        general_purpose::STANDARD
+            .decode(value.trim())
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+    };
+    let key = decode(pubkey)
+        .and_then(|key| PublicKey::decode(&key).ok())
+        .ok_or("The updater public key is invalid")?;
+    let signature = decode(signature)
+        .and_then(|signature| Signature::decode(&signature).ok())
+        .ok_or("The installer signature is missing or invalid")?;
+    key.verify(bytes, &signature, true)
+        .map_err(|e| format!("Installer signature verification failed: {}", e))
+}
+
+/// The updater public key from `tauri.conf.json`.
+fn updater_pubkey(app_handle: &AppHandle) -> Result<String, String> {
+    app_handle
+        .config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("pubkey"))
+        .and_then(|key| key.as_str())
+        .map(String::from)
+        .ok_or_else(|| "No updater public key is configured".to_string())
+}
+
+/// Refuses to install `version` unless it is newer than the running `current` one.
+/// `allow_downgrade` is only set when the user deliberately returns to stable.
+fn check_not_downgrade(version: &str, current: &str, allow_downgrade: bool) -> Result<(), String> {
+    if allow_downgrade || compare_versions(version, current) == Ordering::Greater {
+        return Ok(());
+    }
+    Err(format!(
+        "Refusing to install {}: it is not newer than the running version {}",
+        version, current
+    ))
+}
+
+/// Downloads the `.sig` file the release publishes next to `asset_name`, if any.
+async fn fetch_asset_signature(assets: &[CustomUpdateAsset], asset_name: &str) -> Option<String> {
+    let sig_name = format!("{}.sig", asset_name);
+    let sig_asset = assets.iter().find(|asset| asset.name == sig_name)?;
+    let response = crate::http::get_direct(&sig_asset.download_url)
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.text().await.ok().map(|text| text.trim().to_string())
 }
 
 /// Check for updates using GitHub API directly
@@ -39,6 +230,11 @@ pub async fn check_for_custom_update(app_handle: AppHandle) -> Result<CustomUpda
     
     // Get the current channel
     let channel = get_update_channel(app_handle.clone()).await?;
+    find_update(&app_handle, channel).await
+}
+
+/// Finds the newest release of `channel` and its installer for this machine.
+async fn find_update(app_handle: &AppHandle, channel: String) -> Result<CustomUpdateInfo, String> {
     log::info!("Checking for updates on channel: {}", channel);
     
     // Determine the repository based on channel
@@ -60,11 +256,7 @@ pub async fn check_for_custom_update(app_handle: AppHandle) -> Result<CustomUpda
     log::debug!("Fetching release info from: {}", api_url);
     
     // Make HTTP request to GitHub API
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&api_url)
-        .header("User-Agent", "Pailer-Updater")
-        .send()
+    let response = crate::http::get_direct(&api_url)
         .await
         .map_err(|e| format!("Failed to fetch release info: {}", e))?;
     
@@ -101,33 +293,61 @@ pub async fn check_for_custom_update(app_handle: AppHandle) -> Result<CustomUpda
     // Extract version from tag (remove 'v' prefix if present)
     let version = release.tag_name.strip_prefix('v').unwrap_or(&release.tag_name).to_string();
     
-    // Find the Windows installer asset
-    let windows_asset = release.assets.into_iter()
-        .find(|asset| asset.name.contains("x64-setup.exe") || asset.name.contains("windows"))
-        .ok_or("Windows installer not found in release assets")?;
-    
-    log::info!("Found update: {} from {}", version, release.published_at);
-    
-    // For the signature, we'll need to get it from the update.json file
-    // This is a limitation of using GitHub API directly
-    let signature = get_signature_for_version(&version, &channel).await?;
+    // Find the installer for this architecture, honouring the preferred installer type
+    let assets: Vec<CustomUpdateAsset> = release.assets.into_iter().map(Into::into).collect();
+    let preferred_type = settings::get_config_value(app_handle.clone(), UPDATE_INSTALLER_TYPE.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_str().map(str::to_lowercase));
+    let arch = current_arch();
+    let windows_asset = select_asset(&assets, arch, preferred_type.as_deref())
+        .cloned()
+        .ok_or_else(|| format!("No {} installer found in release assets", arch))?;
+    
+    log::info!(
+        "Found update: {} from {} ({})",
+        version, release.published_at, windows_asset.name
+    );
+    
+    // Prefer the .sig asset of the installer, falling back to the update.json entry
+    let signature = match fetch_asset_signature(&assets, &windows_asset.name).await {
+        Some(signature) => signature,
+        None => {
+            get_signature_for_version(
+                &version,
+                &channel,
+                arch,
+                windows_asset.installer_type.as_deref(),
+            )
+            .await?
+        }
+    };
     
     // Create update info
     let update_info = CustomUpdateInfo {
         version: version.clone(),
         pub_date: release.published_at,
-        download_url: windows_asset.browser_download_url,
+        download_url: windows_asset.download_url,
         signature,
         notes: format!("Update available for {} channel", channel),
         body: release.body,
         channel,
+        asset_name: windows_asset.name,
+        installer_type: windows_asset.installer_type,
+        digest: windows_asset.digest,
+        assets,
     };
     
     Ok(update_info)
 }
 
 /// Get signature for a specific version from the update.json file
-async fn get_signature_for_version(_version: &str, channel: &str) -> Result<String, String> {
+async fn get_signature_for_version(
+    _version: &str,
+    channel: &str,
+    arch: &str,
+    installer_type: Option<&str>,
+) -> Result<String, String> {
     let update_json_url = if channel == "test" {
         format!("https://raw.githubusercontent.com/Kwensiu/Pailer/refs/heads/test/docs/test-update.json")
     } else {
@@ -136,58 +356,124 @@ async fn get_signature_for_version(_version: &str, channel: &str) -> Result<Stri
     
     log::debug!("Fetching signature from: {}", update_json_url);
     
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&update_json_url)
-        .header("User-Agent", "Pailer-Updater")
-        .send()
+    let response = crate::http::get_direct(&update_json_url)
         .await
         .map_err(|e| format!("Failed to fetch update.json: {}", e))?;
     
     if !response.status().is_success() {
-        // If we can't get the signature, return a placeholder
-        log::warn!("Could not fetch signature, using placeholder");
-        return Ok("signature-unavailable".to_string());
+        return Err(format!(
+            "Could not fetch the update signature: {} returned {}",
+            update_json_url,
+            response.status()
+        ));
     }
     
     let update_data: serde_json::Value = response.json()
         .await
         .map_err(|e| format!("Failed to parse update.json: {}", e))?;
     
-    // Extract signature for Windows x64 platform
+    // Extract the signature for this platform, preferring the installer-specific entry
+    let platform = format!("windows-{}", arch);
+    let mut keys = Vec::new();
+    if let Some(installer_type) = installer_type {
+        keys.push(format!("{}-{}", platform, installer_type));
+    }
+    keys.push(platform);
     if let Some(platforms) = update_data.get("platforms") {
-        if let Some(windows_platform) = platforms.get("windows-x86_64") {
-            if let Some(signature) = windows_platform.get("signature") {
-                if let Some(sig_str) = signature.as_str() {
-                    return Ok(sig_str.to_string());
-                }
+        for key in &keys {
+            if let Some(sig_str) = platforms
+                .get(key)
+                .and_then(|p| p.get("signature"))
+                .and_then(|s| s.as_str())
+            {
+                return Ok(sig_str.to_string());
             }
         }
     }
     
-    log::warn!("Signature not found in update.json");
-    Ok("signature-not-found".to_string())
+    Err(format!("No signature for {} published in update.json", keys.join(" or ")))
 }
 
 /// Download and install the custom update
+///
+/// `asset_name` selects another installer from `update_info.assets`; by default the
+/// asset picked by `check_for_custom_update` is used.
 #[tauri::command]
 pub async fn download_and_install_custom_update(
     app_handle: AppHandle,
     update_info: CustomUpdateInfo,
+    asset_name: Option<String>,
 ) -> Result<(), String> {
     log::info!("Starting custom update download and installation");
+    let (installer_path, is_msi) =
+        download_installer(&app_handle, &update_info, asset_name, false).await?;
+    launch_installer(&app_handle, &installer_path, is_msi)
+}
+
+/// Leaves the test channel: reinstalls the latest stable release, although it is older
+/// than the running test build, and switches the update channel back to stable.
+#[tauri::command]
+pub async fn return_to_stable(app_handle: AppHandle) -> Result<(), String> {
+    log::info!("Returning to the stable update channel");
+    let update_info = find_update(&app_handle, "stable".to_string()).await?;
+    let (installer_path, is_msi) =
+        download_installer(&app_handle, &update_info, None, true).await?;
+    // Only leave the test channel once the stable installer is actually running
+    start_installer(&installer_path, is_msi)?;
+    if let Err(e) = set_update_channel(&app_handle, "stable") {
+        log::warn!("Failed to switch the update channel to stable: {}", e);
+    }
+    exit_for_installer(&app_handle);
+    Ok(())
+}
+
+/// Downloads the installer of `update_info` and checks its digest and signature,
+/// returning where it was saved and whether it is an MSI.
+async fn download_installer(
+    app_handle: &AppHandle,
+    update_info: &CustomUpdateInfo,
+    asset_name: Option<String>,
+    allow_downgrade: bool,
+) -> Result<(PathBuf, bool), String> {
+    check_not_downgrade(
+        &update_info.version,
+        env!("CARGO_PKG_VERSION"),
+        allow_downgrade,
+    )?;
+    let pubkey = updater_pubkey(app_handle)?;
+
+    let (download_url, installer_type, digest, signature) = match asset_name {
+        Some(name) => {
+            let asset = update_info.assets.iter()
+                .find(|asset| asset.name == name)
+                .ok_or_else(|| format!("Asset '{}' not found in release", name))?;
+            if asset.installer_type.is_none() {
+                return Err(format!("Asset '{}' is not an installer", name));
+            }
+            let signature = match fetch_asset_signature(&update_info.assets, &name).await {
+                Some(signature) => signature,
+                None if name == update_info.asset_name => update_info.signature.clone(),
+                None => return Err(format!("Asset '{}' has no published signature", name)),
+            };
+            (asset.download_url.clone(), asset.installer_type.clone(), asset.digest.clone(), signature)
+        }
+        None => (
+            update_info.download_url.clone(),
+            update_info.installer_type.clone(),
+            update_info.digest.clone(),
+            update_info.signature.clone(),
+        ),
+    };
+    let is_msi = installer_type.as_deref() == Some("msi");
     
     // Create a temporary directory for the download
     let temp_dir = std::env::temp_dir();
-    let installer_path = temp_dir.join(format!("scoopmeta_update_{}.exe", update_info.version));
+    let extension = if is_msi { "msi" } else { "exe" };
+    let installer_path = temp_dir.join(format!("scoopmeta_update_{}.{}", update_info.version, extension));
     
     // Download the installer
-    log::info!("Downloading installer from: {}", update_info.download_url);
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&update_info.download_url)
-        .header("User-Agent", "Pailer-Updater")
-        .send()
+    log::info!("Downloading installer from: {}", download_url);
+    let response = crate::http::get_direct(&download_url)
         .await
         .map_err(|e| format!("Failed to download installer: {}", e))?;
     
@@ -199,23 +485,43 @@ pub async fn download_and_install_custom_update(
         .await
         .map_err(|e| format!("Failed to read installer bytes: {}", e))?;
     
+    verify_digest(&installer_bytes, digest.as_deref())?;
+    verify_signature(&installer_bytes, &signature, &pubkey)?;
+    
     // Write installer to disk
     std::fs::write(&installer_path, &installer_bytes)
         .map_err(|e| format!("Failed to write installer: {}", e))?;
     
     log::info!("Installer downloaded to: {}", installer_path.display());
-    
+    Ok((installer_path, is_msi))
+}
+
+/// Starts the installer detached and exits the app so it can replace the files.
+fn launch_installer(app_handle: &AppHandle, installer_path: &Path, is_msi: bool) -> Result<(), String> {
+    start_installer(installer_path, is_msi)?;
+    exit_for_installer(app_handle);
+    Ok(())
+}
+
+/// Starts the installer detached from the app.
+fn start_installer(installer_path: &Path, is_msi: bool) -> Result<(), String> {
     // Execute the installer with the same arguments as in tauri.conf.json
-    let args = if cfg!(windows) {
-        vec!["/CURRENTUSER", "/MERGETASKS=!desktopicon,!quicklaunchicon"]
+    let mut cmd = if is_msi {
+        let mut cmd = Command::new("msiexec");
+        cmd.arg("/i").arg(installer_path).arg("/passive");
+        cmd
     } else {
-        vec![]
+        let args = if cfg!(windows) {
+            vec!["/CURRENTUSER", "/MERGETASKS=!desktopicon,!quicklaunchicon"]
+        } else {
+            vec![]
+        };
+        let mut cmd = Command::new(installer_path);
+        cmd.args(args);
+        cmd
     };
     
-    log::info!("Starting installer with args: {:?}", args);
-    
-    let mut cmd = Command::new(installer_path);
-    cmd.args(args);
+    log::info!("Starting installer: {:?}", cmd);
     
     #[cfg(windows)]
     {
@@ -228,16 +534,67 @@ pub async fn download_and_install_custom_update(
         .map_err(|e| format!("Failed to start installer: {}", e))?;
     
     log::info!("Installer started with PID: {}", child.id());
-    
-    // Exit the current application
+    Ok(())
+}
+
+/// Exits the app so the running installer can replace it.
+fn exit_for_installer(app_handle: &AppHandle) {
     std::thread::sleep(std::time::Duration::from_secs(1));
     app_handle.exit(0);
-    
-    Ok(())
 }
 
 /// Get current app version
 #[tauri::command]
 pub async fn get_current_version() -> Result<String, String> {
     Ok(env!("CARGO_PKG_VERSION").to_string())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str) -> CustomUpdateAsset {
+        GitHubAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.com/{}", name),
+            size: 0,
+            digest: None,
+        }
+        .into()
+    }
+
+    #[test]
+    fn selects_installer_by_arch_and_type() {
+        let assets = vec![
+            asset("Pailer_1.0.0_x64_en-US.msi"),
+            asset("Pailer_1.0.0_x64_en-US.msi.sig"),
+            asset("Pailer_1.0.0_arm64-setup.exe"),
+            asset("Pailer_1.0.0_x64-setup.exe"),
+            asset("update.json"),
+        ];
+        let pick = |arch, kind| select_asset(&assets, arch, kind).map(|a| a.name.as_str());
+
+        assert_eq!(pick("x86_64", Some("nsis")), Some("Pailer_1.0.0_x64-setup.exe"));
+        assert_eq!(pick("x86_64", Some("msi")), Some("Pailer_1.0.0_x64_en-US.msi"));
+        assert_eq!(pick("x86_64", None), Some("Pailer_1.0.0_x64_en-US.msi"));
+        assert_eq!(pick("aarch64", Some("msi")), Some("Pailer_1.0.0_arm64-setup.exe"));
+        assert_eq!(pick("i686", None), None);
+
+        assert!(verify_digest(b"hello", Some("sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824")).is_ok());
+        assert!(verify_digest(b"hello", Some("sha256:00")).is_err());
+    }
+
+    #[test]
+    fn rejects_unsigned_installers_and_downgrades() {
+        let pubkey = "dW50cnVzdGVkIGNvbW1lbnQ6IG1pbmlzaWduIHB1YmxpYyBrZXkgREUzNDBBOUZCRkZGMzg5Qg0KUldTYk9QKy9ud28wM21SRUdEd25sNXdtNXZyZVFZcWNFUDN3MDVUdEhGSHRKVXowVmVQbHVCUVcNCg==";
+        for signature in ["signature-not-found", "", pubkey] {
+            assert!(verify_signature(b"installer", signature, pubkey).is_err());
+        }
+        assert!(verify_signature(b"installer", pubkey, "not a key").is_err());
+
+        assert!(check_not_downgrade("1.2.0", "1.1.9", false).is_ok());
+        assert!(check_not_downgrade("1.2.0-test.2", "1.2.0-test.1", false).is_ok());
+        assert!(check_not_downgrade("1.2.0", "1.2.0", false).is_err());
+        assert!(check_not_downgrade("1.2.0", "1.3.0-test.1", false).is_err());
+        assert!(check_not_downgrade("1.2.0", "1.3.0-test.1", true).is_ok());
+    }
+}
@@ -1,4 +1,5 @@
 //! Commands for retrieving diagnostic information about the application.
+use crate::models::{CrashReport, PortableInfo};
 use crate::state::AppState;
 use chrono::Local;
 use std::fs;
@@ -8,8 +9,7 @@ use tauri::State;
 // Note: Retry logic constants are defined locally in functions as needed
 
 // Application identifiers
-const TAURI_APP_ID: &str = "com.pailer.ks";
-const OLD_APP_DIR: &str = "pailer";
+pub const OLD_APP_DIR: &str = "pailer";
 
 // Store data file names (new unified format)
 const FRONTEND_STORE_FILE: &str = "settings.json";
@@ -45,8 +45,7 @@ const WEBVIEW_LOCKED_DIRS: &[&str] = &[
 #[tauri::command]
 pub fn get_app_data_dir() -> Result<String, String> {
     // First try to get the Tauri app data directory
-    if let Some(app_data_dir) = dirs::data_dir() {
-        let app_data_dir = app_data_dir.join(TAURI_APP_ID);
+    if let Some(app_data_dir) = crate::portable::app_data_dir() {
         if app_data_dir.exists() {
             return Ok(app_data_dir.to_string_lossy().to_string());
         }
@@ -60,6 +59,43 @@ pub fn get_app_data_dir() -> Result<String, String> {
     Ok(data_dir.to_string_lossy().to_string())
 }
 
+/// Reports whether the app runs in portable mode and where its data is kept.
+#[tauri::command]
+pub fn get_portable_info() -> PortableInfo {
+    PortableInfo {
+        portable: crate::portable::is_portable(),
+        data_dir: crate::portable::app_data_dir().map(|dir| dir.to_string_lossy().to_string()),
+    }
+}
+
+/// Returns the newest crash report if the user has not seen it yet, so the UI can
+/// offer it after a crash.
+#[tauri::command]
+pub fn get_pending_crash_report() -> Option<CrashReport> {
+    crate::crash::list_reports()
+        .into_iter()
+        .next()
+        .filter(|report| !report.acknowledged)
+}
+
+/// Lists stored crash reports, newest first.
+#[tauri::command]
+pub fn list_crash_reports() -> Vec<CrashReport> {
+    crate::crash::list_reports()
+}
+
+#[tauri::command]
+pub fn acknowledge_crash_report(id: String) -> Result<(), String> {
+    crate::crash::acknowledge(&id)
+}
+
+/// Deletes all crash reports and minidumps.
+#[tauri::command]
+pub fn clear_crash_reports() {
+    crate::crash::clear_reports();
+    log::info!("Cleared crash reports");
+}
+
 /// Gets the log directory
 #[tauri::command]
 pub fn get_log_dir_cmd() -> Result<String, String> {
@@ -172,8 +208,7 @@ fn is_webview_locked_dir(dir_path: &std::path::Path) -> bool {
 #[tauri::command]
 pub fn clear_application_data() -> Result<(), String> {
     // First try to get the Tauri app data directory
-    let data_dir = if let Some(app_data_dir) = dirs::data_dir() {
-        let app_data_dir = app_data_dir.join("com.pailer.ks");
+    let data_dir = if let Some(app_data_dir) = crate::portable::app_data_dir() {
         if app_data_dir.exists() {
             app_data_dir
         } else {
@@ -265,22 +300,40 @@ pub async fn get_debug_info(state: State<'_, AppState>) -> Result<serde_json::Va
         serde_json::json!({
             "cached_count": cache.packages.len(),
             "fingerprint": cache.fingerprint,
+            "generation": state.installed_cache_info(),
         })
     } else {
         log::info!("=== DEBUG INFO === No cache found (None)");
         serde_json::json!({
             "cached_count": 0,
             "fingerprint": null,
+            "generation": state.installed_cache_info(),
         })
     };
     drop(cache_guard); // Explicitly drop guard
 
+    let environment = crate::env_report::collect(&scoop_path).await;
+
     let debug_result = serde_json::json!({
         "timestamp": Local::now().to_rfc3339(),
         "scoop_path": scoop_path.display().to_string(),
         "apps_dir_exists": apps_dir_exists,
         "app_count": app_count,
         "cache_info": cache_info,
+        "bench": crate::bench::last_report(),
+        "crash_reports": crate::crash::list_reports()
+            .iter()
+            .map(|r| serde_json::json!({
+                "id": r.id,
+                "timestamp": r.timestamp,
+                "kind": r.kind,
+                "message": r.message,
+                "location": r.location,
+                "has_minidump": r.minidump_path.is_some(),
+            }))
+            .collect::<Vec<_>>(),
+        "environment": environment,
+        "command_stats": state.command_stats(),
     });
 
     log::info!(
@@ -292,6 +345,25 @@ pub async fn get_debug_info(state: State<'_, AppState>) -> Result<serde_json::Va
     Ok(debug_result)
 }
 
+/// Runs the scan and search benchmarks against the configured Scoop directory.
+///
+/// Not exposed in the UI; invoke it from the devtools console. The report is also
+/// included in `get_debug_info` until the app restarts.
+#[tauri::command]
+pub async fn bench(
+    state: State<'_, AppState>,
+    iterations: Option<u32>,
+    term: Option<String>,
+) -> Result<crate::bench::BenchReport, String> {
+    let scoop_path = state.scoop_path();
+    let iterations = iterations.unwrap_or(5);
+    let term = term.unwrap_or_else(|| crate::bench::DEFAULT_SEARCH_TERM.to_string());
+
+    tokio::task::spawn_blocking(move || crate::bench::run(&scoop_path, iterations, &term))
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Gets the current application logs from the logging system
 #[tauri::command]
 pub fn get_app_logs() -> Result<String, String> {
@@ -379,8 +451,8 @@ pub fn get_app_logs() -> Result<String, String> {
 #[tauri::command]
 pub fn read_app_log_file() -> Result<String, String> {
     // Determine log file path - use APPDATA\com.pailer.ks\logs\pailer.log on Windows
-    let log_file = if let Some(data_dir) = dirs::data_dir() {
-        data_dir.join("com.pailer.ks").join("logs").join("pailer.log")
+    let log_file = if let Some(log_dir) = crate::portable::log_dir() {
+        log_dir.join("pailer.log")
     } else {
         PathBuf::from("./logs/pailer.log")
     };
@@ -441,8 +513,8 @@ fn read_last_n_bytes(file_path: &PathBuf, n: usize) -> Result<String, String> {
 /// Checks if factory reset marker exists
 #[tauri::command]
 pub fn check_factory_reset_marker() -> Result<bool, String> {
-    if let Some(app_data_dir) = dirs::data_dir() {
-        let marker_file = app_data_dir.join(TAURI_APP_ID).join(FACTORY_RESET_MARKER);
+    if let Some(app_data_dir) = crate::portable::app_data_dir() {
+        let marker_file = app_data_dir.join(FACTORY_RESET_MARKER);
         if marker_file.exists() {
             // Remove the marker after checking
             let _ = fs::remove_file(&marker_file);
@@ -460,16 +532,16 @@ pub fn clear_store_data() -> Result<(), String> {
     // Create list of files to clear using defined constants
     let store_files = vec![
         // New unified store files
-        dirs::data_dir().map(|d| d.join(TAURI_APP_ID).join(FRONTEND_STORE_FILE)),
-        dirs::data_dir().map(|d| d.join(TAURI_APP_ID).join(BACKEND_STORE_FILE)),
-        dirs::data_dir().map(|d| d.join(TAURI_APP_ID).join(VERSION_FILE)),
+        crate::portable::app_data_dir().map(|d| d.join(FRONTEND_STORE_FILE)),
+        crate::portable::app_data_dir().map(|d| d.join(BACKEND_STORE_FILE)),
+        crate::portable::app_data_dir().map(|d| d.join(VERSION_FILE)),
         // Backup files in new directory
-        dirs::data_dir().map(|d| d.join(TAURI_APP_ID).join(format!("{}{}", FRONTEND_STORE_FILE, BACKUP_EXT))),
-        dirs::data_dir().map(|d| d.join(TAURI_APP_ID).join(format!("{}{}", BACKEND_STORE_FILE, BACKUP_EXT))),
+        crate::portable::app_data_dir().map(|d| d.join(format!("{}{}", FRONTEND_STORE_FILE, BACKUP_EXT))),
+        crate::portable::app_data_dir().map(|d| d.join(format!("{}{}", BACKEND_STORE_FILE, BACKUP_EXT))),
         // Legacy files for migration cleanup
-        dirs::data_dir().map(|d| d.join(TAURI_APP_ID).join(LEGACY_SETTINGS_FILE)),
-        dirs::data_dir().map(|d| d.join(TAURI_APP_ID).join(LEGACY_SIGNALS_FILE)),
-        dirs::data_dir().map(|d| d.join(TAURI_APP_ID).join(LEGACY_STORE_FILE)),
+        crate::portable::app_data_dir().map(|d| d.join(LEGACY_SETTINGS_FILE)),
+        crate::portable::app_data_dir().map(|d| d.join(LEGACY_SIGNALS_FILE)),
+        crate::portable::app_data_dir().map(|d| d.join(LEGACY_STORE_FILE)),
         // Old directory - main files
         dirs::data_local_dir().map(|d| d.join(OLD_APP_DIR).join(LEGACY_SETTINGS_FILE)),
         dirs::data_local_dir().map(|d| d.join(OLD_APP_DIR).join(LEGACY_SIGNALS_FILE)),
@@ -498,8 +570,8 @@ pub fn clear_store_data() -> Result<(), String> {
     
     // Create a marker file to indicate factory reset
     let mut marker_created = false;
-    if let Some(app_data_dir) = dirs::data_dir() {
-        let marker_file = app_data_dir.join(TAURI_APP_ID).join(FACTORY_RESET_MARKER);
+    if let Some(app_data_dir) = crate::portable::app_data_dir() {
+        let marker_file = app_data_dir.join(FACTORY_RESET_MARKER);
         if let Some(parent) = marker_file.parent() {
             match fs::create_dir_all(parent) {
                 Ok(_) => {
@@ -588,7 +660,7 @@ pub fn clear_webview_cache() -> Result<(), String> {
     
     // Try to clear cache from both new and old locations
     let cache_dirs = vec![
-        dirs::data_dir().map(|d| d.join(TAURI_APP_ID)),
+        crate::portable::app_data_dir(),
         dirs::data_local_dir().map(|d| d.join(OLD_APP_DIR)),
     ];
     
@@ -616,8 +688,8 @@ pub fn clear_webview_cache() -> Result<(), String> {
 /// Schedules WebView cache cleanup for next startup
 #[tauri::command]
 pub fn schedule_webview_cleanup() -> Result<(), String> {
-    if let Some(app_data_dir) = dirs::data_dir() {
-        let marker_file = app_data_dir.join(TAURI_APP_ID).join(WEBVIEW_CLEANUP_MARKER);
+    if let Some(app_data_dir) = crate::portable::app_data_dir() {
+        let marker_file = app_data_dir.join(WEBVIEW_CLEANUP_MARKER);
         if let Some(parent) = marker_file.parent() {
             match fs::create_dir_all(parent) {
                 Ok(_) => {
@@ -638,8 +710,8 @@ pub fn schedule_webview_cleanup() -> Result<(), String> {
 /// Checks if WebView cleanup is scheduled
 #[tauri::command]
 pub fn is_webview_cleanup_scheduled() -> Result<bool, String> {
-    if let Some(app_data_dir) = dirs::data_dir() {
-        let marker_file = app_data_dir.join(TAURI_APP_ID).join(WEBVIEW_CLEANUP_MARKER);
+    if let Some(app_data_dir) = crate::portable::app_data_dir() {
+        let marker_file = app_data_dir.join(WEBVIEW_CLEANUP_MARKER);
         Ok(marker_file.exists())
     } else {
         Ok(false)
@@ -660,8 +732,8 @@ pub fn perform_scheduled_webview_cleanup() -> Result<(), String> {
     clear_webview_cache()?;
     
     // Remove the marker
-    if let Some(app_data_dir) = dirs::data_dir() {
-        let marker_file = app_data_dir.join(TAURI_APP_ID).join(WEBVIEW_CLEANUP_MARKER);
+    if let Some(app_data_dir) = crate::portable::app_data_dir() {
+        let marker_file = app_data_dir.join(WEBVIEW_CLEANUP_MARKER);
         if marker_file.exists() {
             let _ = fs::remove_file(&marker_file);
         }
@@ -681,8 +753,8 @@ pub fn final_cleanup_on_exit() -> Result<(), String> {
     
     // Try to remove any remaining configuration files
     let final_cleanup_files = vec![
-        dirs::data_dir().map(|d| d.join(TAURI_APP_ID).join(FRONTEND_STORE_FILE)),
-        dirs::data_dir().map(|d| d.join(TAURI_APP_ID).join(BACKEND_STORE_FILE)),
+        crate::portable::app_data_dir().map(|d| d.join(FRONTEND_STORE_FILE)),
+        crate::portable::app_data_dir().map(|d| d.join(BACKEND_STORE_FILE)),
         dirs::data_local_dir().map(|d| d.join(OLD_APP_DIR).join(LEGACY_SETTINGS_FILE)),
         dirs::data_local_dir().map(|d| d.join(OLD_APP_DIR).join(LEGACY_SIGNALS_FILE)),
     ];
@@ -705,8 +777,7 @@ pub fn final_cleanup_on_exit() -> Result<(), String> {
 
 fn get_log_dir() -> Option<PathBuf> {
     // First try to get the Tauri app data directory
-    if let Some(app_data_dir) = dirs::data_dir() {
-        let app_data_dir = app_data_dir.join(TAURI_APP_ID);
+    if let Some(app_data_dir) = crate::portable::app_data_dir() {
         if app_data_dir.exists() {
             return Some(app_data_dir.join("logs"));
         }
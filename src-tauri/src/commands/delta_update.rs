@@ -0,0 +1,329 @@
+//! Differential downloads before package updates.
+//!
+//! When a new archive is published with a `.zsync` control file next to it and the
+//! previous version's archive is still in the Scoop cache, the new archive is rebuilt
+//! from the old one plus the changed byte ranges and placed in the cache, so `scoop
+//! update` finds it there instead of downloading it in full. Any failure just leaves
+//! the cache untouched and Scoop falls back to the normal download.
+use crate::commands::hold::find_held_packages;
+use crate::commands::package_source::get_installed_source;
+use crate::commands::repair::host_architecture;
+use crate::commands::settings;
+use crate::config_keys::UPDATE_DELTA_DOWNLOADS;
+use crate::engine::cache_import::{cache_file_name, check_hash, manifest_downloads, HashCheck};
+use crate::engine::manifest::locate_package_manifest;
+use crate::engine::mirror::download_urls;
+use crate::engine::zsync::ControlFile;
+use crate::state::AppState;
+use crate::utils::ScoopDirs;
+use serde_json::Value;
+use std::fs;
+use std::io::{Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Skip the delta when more than this share of the archive would be downloaded anyway.
+const MAX_MISSING_RATIO: f64 = 0.8;
+/// A new archive may be at most this many times the size of the one it is rebuilt from.
+const MAX_GROWTH: u64 = 4;
+const MAX_TARGET_LENGTH: u64 = 4 * 1024 * 1024 * 1024;
+/// Attempts per byte range, including the first.
+const RANGE_ATTEMPTS: u32 = 3;
+const RANGE_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Bytes saved by differential downloads for one update run.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DeltaSummary {
+    pub files: usize,
+    pub downloaded_bytes: u64,
+    pub reused_bytes: u64,
+}
+
+/// One archive that can be rebuilt from its previous version.
+struct DeltaCandidate {
+    url: String,
+    hash: String,
+    seed: PathBuf,
+    target: PathBuf,
+}
+
+fn read_manifest(path: &Path) -> Option<Value> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+/// Pairs each download of the new manifest with the cached archive of the installed one.
+fn delta_candidates(dirs: &ScoopDirs, package: &str) -> Vec<DeltaCandidate> {
+    let Some(installed) = read_manifest(
+        &dirs
+            .apps
+            .join(package)
+            .join("current")
+            .join("manifest.json"),
+    ) else {
+        return Vec::new();
+    };
+    let source = get_installed_source(&dirs.root, package);
+    let Some(latest) = locate_package_manifest(&dirs.root, package, source)
+        .ok()
+        .and_then(|(path, _)| read_manifest(&path))
+    else {
+        return Vec::new();
+    };
+
+    let version = |m: &Value| m.get("version").and_then(Value::as_str).map(String::from);
+    let (Some(old_version), Some(new_version)) = (version(&installed), version(&latest)) else {
+        return Vec::new();
+    };
+    if old_version == new_version {
+        return Vec::new();
+    }
+
+    let arch = host_architecture();
+    let old_urls = download_urls(&installed, arch);
+    let hashes = manifest_downloads(package, &latest);
+    download_urls(&latest, arch)
+        .into_iter()
+        .zip(old_urls)
+        .filter_map(|(url, old_url)| {
+            let target = dirs
+                .cache
+                .join(cache_file_name(package, &new_version, &url));
+            let seed = dirs
+                .cache
+                .join(cache_file_name(package, &old_version, &old_url));
+            if target.exists() || !seed.is_file() {
+                return None;
+            }
+            // Without a hash the rebuilt archive could not be trusted
+            let hash = hashes.iter().find(|d| d.url == url)?.hash.clone()?;
+            Some(DeltaCandidate {
+                url,
+                hash,
+                seed,
+                target,
+            })
+        })
+        .collect()
+}
+
+/// Downloads `range` of `url` into `out` at the same offset.
+async fn fetch_range(url: &str, range: &Range<u64>, out: &mut fs::File) -> Result<(), String> {
+    let mut response = crate::http::client()?
+        .get(crate::http::mirror_url(url))
+        .header(
+            reqwest::header::RANGE,
+            format!("bytes={}-{}", range.start, range.end - 1),
+        )
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        return Err(format!(
+            "server does not support ranges ({})",
+            response.status()
+        ));
+    }
+    out.seek(SeekFrom::Start(range.start))
+        .map_err(|e| e.to_string())?;
+    let mut received = 0u64;
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        received += chunk.len() as u64;
+        if received > range.end - range.start {
+            break;
+        }
+        out.write_all(&chunk).map_err(|e| e.to_string())?;
+    }
+    if received != range.end - range.start {
+        return Err(format!(
+            "range {}-{} returned {} bytes",
+            range.start, range.end, received
+        ));
+    }
+    Ok(())
+}
+
+/// [`fetch_range`] with retries, since a delta fetches many ranges and one dropped
+/// connection should not waste the others.
+async fn fetch_range_with_retry(
+    url: &str,
+    range: &Range<u64>,
+    out: &mut fs::File,
+) -> Result<(), String> {
+    let mut backoff = RANGE_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        match fetch_range(url, range, out).await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < RANGE_ATTEMPTS && !e.starts_with("server does not support") => {
+                log::debug!(
+                    "Range {}-{} of {} failed: {} (attempt {}/{})",
+                    range.start,
+                    range.end,
+                    url,
+                    e,
+                    attempt,
+                    RANGE_ATTEMPTS
+                );
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Rebuilds one archive into the cache, returning the bytes downloaded and reused.
+///
+/// The archive is assembled in a file next to the target rather than in memory, and a
+/// control file claiming a target much larger than the seed is rejected up front.
+async fn apply_delta(candidate: DeltaCandidate) -> Result<(u64, u64), String> {
+    let response = crate::http::get(&format!("{}.zsync", candidate.url)).await?;
+    if !response.status().is_success() {
+        return Err(format!("no zsync file ({})", response.status()));
+    }
+    let control_bytes = response.bytes().await.map_err(|e| e.to_string())?;
+
+    let seed_path = candidate.seed.clone();
+    let partial = candidate.target.with_extension("delta");
+    let partial_path = partial.clone();
+    let prepared = tokio::task::spawn_blocking(move || {
+        let control = ControlFile::parse(&control_bytes)?;
+        let seed = fs::read(&seed_path).map_err(|e| e.to_string())?;
+        let limit = (seed.len() as u64)
+            .saturating_mul(MAX_GROWTH)
+            .min(MAX_TARGET_LENGTH);
+        if control.length > limit {
+            return Err(format!(
+                "target of {} bytes is too large for a {} byte seed",
+                control.length,
+                seed.len()
+            ));
+        }
+        let matches = control.match_seed(&seed);
+        let missing = control.missing_ranges(&matches);
+        let missing_bytes: u64 = missing.iter().map(|r| r.end - r.start).sum();
+        if missing_bytes as f64 > control.length as f64 * MAX_MISSING_RATIO {
+            return Err(format!(
+                "{} of {} bytes changed, not worth a delta",
+                missing_bytes, control.length
+            ));
+        }
+
+        let mut out = fs::File::create(&partial_path).map_err(|e| e.to_string())?;
+        out.set_len(control.length).map_err(|e| e.to_string())?;
+        control
+            .write_matched(&seed, &matches, &mut out)
+            .map_err(|e| e.to_string())?;
+        Ok::<_, String>((control.length, missing, missing_bytes, out))
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let rebuilt = match prepared {
+        Ok((length, missing, missing_bytes, mut out)) => {
+            let mut fetched = Ok(());
+            for range in &missing {
+                fetched = fetch_range_with_retry(&candidate.url, range, &mut out).await;
+                if fetched.is_err() {
+                    break;
+                }
+            }
+            fetched
+                .and_then(|()| out.sync_all().map_err(|e| e.to_string()))
+                .map(|()| (length, missing_bytes))
+        }
+        Err(e) => Err(e),
+    };
+    let (length, missing_bytes) = match rebuilt {
+        Ok(sizes) => sizes,
+        Err(e) => {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+    };
+
+    match check_hash(&partial, Some(&candidate.hash)) {
+        Ok(HashCheck::Match) => {
+            fs::rename(&partial, &candidate.target).map_err(|e| e.to_string())?;
+            Ok((missing_bytes, length - missing_bytes))
+        }
+        Ok(_) => {
+            let _ = fs::remove_file(&partial);
+            Err("rebuilt archive does not match the manifest hash".to_string())
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&partial);
+            Err(e)
+        }
+    }
+}
+
+/// Seeds the Scoop cache for the given packages (all installed ones when `None`).
+///
+/// Does nothing when differential downloads are turned off in the settings. Holds the
+/// operation queue while it runs, so it must not be called with the queue held.
+pub async fn prepare_delta_updates<R: Runtime>(
+    app: &AppHandle<R>,
+    scoop_path: &Path,
+    packages: Option<Vec<String>>,
+) -> DeltaSummary {
+    let mut summary = DeltaSummary::default();
+    let enabled = settings::get_config_value(app.clone(), UPDATE_DELTA_DOWNLOADS.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    if !enabled {
+        return summary;
+    }
+
+    // Archives are written into Scoop's cache, so wait for any running operation
+    let state = app.state::<AppState>();
+    let _queue = state.operation_queue.lock().await;
+
+    let dirs = ScoopDirs::resolve(scoop_path);
+    let packages = packages.unwrap_or_else(|| {
+        // Held packages are not updated by `scoop update *`
+        let held = find_held_packages(&dirs.root);
+        fs::read_dir(&dirs.apps)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .filter(|name| name != "scoop" && !held.contains(name))
+                    .collect()
+            })
+            .unwrap_or_default()
+    });
+
+    for package in packages {
+        let dirs = dirs.clone();
+        let lookup = package.clone();
+        let candidates = tokio::task::spawn_blocking(move || delta_candidates(&dirs, &lookup))
+            .await
+            .unwrap_or_default();
+        for candidate in candidates {
+            let url = candidate.url.clone();
+            match apply_delta(candidate).await {
+                Ok((downloaded, reused)) => {
+                    log::info!(
+                        "Delta update for '{}': downloaded {} bytes, reused {} bytes",
+                        package,
+                        downloaded,
+                        reused
+                    );
+                    summary.files += 1;
+                    summary.downloaded_bytes += downloaded;
+                    summary.reused_bytes += reused;
+                }
+                Err(e) => log::debug!("No delta update for {}: {}", url, e),
+            }
+        }
+    }
+    summary
+}
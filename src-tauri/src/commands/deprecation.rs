@@ -0,0 +1,374 @@
+//! Detection of deprecated manifests and their successor packages, and a command
+//! for migrating an installed package to its successor.
+//!
+//! Buckets mark deprecation in several ways: a `deprecated` field, a `##` comment
+//! such as "Deprecated, use `foo` instead", or by moving the manifest into the
+//! bucket's `deprecated` directory.
+use crate::commands::auto_cleanup::trigger_auto_cleanup;
+use crate::commands::installed::invalidate_installed_cache;
+use crate::commands::scoop::{self, ScoopOp};
+use crate::commands::search::invalidate_manifest_cache;
+use crate::state::AppState;
+use crate::utils::{locate_package_manifest, validate_dir_name};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State, Window};
+
+static REPLACEMENT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(?:use|replaced\s+by|renamed\s+to|moved\s+to|superseded\s+by|successor\s*(?:is|:)|migrate\s+to|switch\s+to)\s+[`'\x22]?([A-Za-z0-9][A-Za-z0-9._/-]*)",
+    )
+    .unwrap()
+});
+
+/// A `##` comment announcing deprecation opens with it, as in "Deprecated, use ..." or
+/// "This package is deprecated". Comments merely mentioning a deprecated option do not.
+static DEPRECATION_NOTE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)^\W*(?:(?:this|the)\s+(?:app|application|package|manifest)\s+(?:is|has\s+been|was)\s+(?:now\s+)?)?deprecated\b",
+    )
+    .unwrap()
+});
+
+/// Deprecation details extracted from a manifest.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeprecationInfo {
+    pub is_deprecated: bool,
+    /// Name of the successor package, optionally prefixed with its bucket (`bucket/name`).
+    pub replacement: Option<String>,
+    pub message: Option<String>,
+}
+
+/// Collects the free-form text of the `##` comment field, one line per entry.
+fn comment_text(manifest: &Value) -> Option<String> {
+    match manifest.get("##")? {
+        Value::String(s) => Some(s.clone()),
+        Value::Array(lines) => Some(
+            lines
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+        _ => None,
+    }
+}
+
+/// Extracts a successor package name from a deprecation message.
+fn parse_replacement(text: &str, package_name: &str) -> Option<String> {
+    REPLACEMENT_RE
+        .captures_iter(text)
+        .filter_map(|c| c.get(1))
+        .map(|m| m.as_str().trim_end_matches(['.', ',']).to_string())
+        .find(|name| {
+            !name.eq_ignore_ascii_case(package_name)
+                && !name.contains("://")
+                && !["the", "a", "an", "this", "it", "instead"]
+                    .contains(&name.to_lowercase().as_str())
+        })
+}
+
+/// Inspects a manifest (and where it lives) for deprecation markers.
+pub fn detect_deprecation(
+    manifest: &Value,
+    manifest_path: &Path,
+    package_name: &str,
+) -> DeprecationInfo {
+    let in_deprecated_dir = manifest_path
+        .components()
+        .any(|c| c.as_os_str().eq_ignore_ascii_case("deprecated"));
+
+    let deprecated_field = manifest.get("deprecated").and_then(|v| match v {
+        Value::Bool(false) | Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        _ => Some(String::new()),
+    });
+
+    let comment = comment_text(manifest);
+    let comment_announces_deprecation = comment
+        .as_ref()
+        .is_some_and(|c| c.lines().any(|line| DEPRECATION_NOTE_RE.is_match(line)));
+
+    if deprecated_field.is_none() && !in_deprecated_dir && !comment_announces_deprecation {
+        return DeprecationInfo::default();
+    }
+
+    let message = deprecated_field
+        .filter(|s| !s.is_empty())
+        .or(comment)
+        .filter(|s| !s.is_empty());
+    let replacement = message
+        .as_deref()
+        .and_then(|text| parse_replacement(text, package_name));
+
+    DeprecationInfo {
+        is_deprecated: true,
+        replacement,
+        message,
+    }
+}
+
+/// Finds a manifest that was moved into a bucket's `deprecated` directory.
+pub fn find_deprecated_manifest(scoop_dir: &Path, package_name: &str) -> Option<(PathBuf, String)> {
+    let manifest_filename = format!("{}.json", package_name);
+
+    fs::read_dir(scoop_dir.join("buckets"))
+        .ok()?
+        .flatten()
+        .find_map(|bucket| {
+            let bucket_name = bucket.file_name().to_string_lossy().to_string();
+            [
+                bucket.path().join("deprecated").join(&manifest_filename),
+                bucket
+                    .path()
+                    .join("bucket")
+                    .join("deprecated")
+                    .join(&manifest_filename),
+            ]
+            .into_iter()
+            .find(|path| path.is_file())
+            .map(|path| (path, bucket_name))
+        })
+}
+
+/// Reads the deprecation status of a package from its bucket manifest.
+pub fn get_package_deprecation(
+    scoop_dir: &Path,
+    package_name: &str,
+    bucket: Option<String>,
+) -> DeprecationInfo {
+    let manifest_path = match locate_package_manifest(scoop_dir, package_name, bucket) {
+        Ok((path, bucket))
+            if !bucket.ends_with("(missing)") && !bucket.contains("Bucket missing") =>
+        {
+            path
+        }
+        _ => match find_deprecated_manifest(scoop_dir, package_name) {
+            Some((path, _)) => path,
+            None => return DeprecationInfo::default(),
+        },
+    };
+
+    fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .map(|manifest| detect_deprecation(&manifest, &manifest_path, package_name))
+        .unwrap_or_default()
+}
+
+/// Installs `package_name` again after a failed migration: from its bucket, or from the
+/// bucket's `deprecated` directory when the manifest was moved there.
+async fn reinstall_original(
+    window: Window,
+    scoop_path: &Path,
+    package_name: &str,
+    bucket: Option<&str>,
+) -> Result<(), String> {
+    let operation_id = Some(format!("migrate-restore-{}", package_name));
+    match find_deprecated_manifest(scoop_path, package_name) {
+        Some((manifest, _)) => {
+            scoop::execute_scoop(
+                window,
+                ScoopOp::InstallManifest,
+                Some(&manifest.to_string_lossy()),
+                None,
+                operation_id,
+            )
+            .await
+        }
+        None => {
+            scoop::execute_scoop(
+                window,
+                ScoopOp::Install,
+                Some(package_name),
+                bucket,
+                operation_id,
+            )
+            .await
+        }
+    }
+}
+
+/// Splits a replacement such as `extras/newtool` into its bucket and package name.
+///
+/// Replacements may come from a manifest comment and name directories under `apps` and
+/// `persist`, so both parts must be plain names.
+fn split_migration_target(target: &str) -> Result<(Option<String>, String), String> {
+    let (bucket, name) = match target.split_once('/') {
+        Some((bucket, name)) => {
+            validate_dir_name("bucket", bucket)?;
+            (Some(bucket.to_string()), name.to_string())
+        }
+        None => (None, target.to_string()),
+    };
+    validate_dir_name("package", &name)?;
+    Ok((bucket, name))
+}
+
+/// Migrates a deprecated package to its successor.
+///
+/// The old package is uninstalled without purging its persisted data, and its
+/// `persist` directory is copied to the successor before it is installed so Scoop
+/// links the existing data instead of creating defaults. The old package goes first
+/// because both usually declare the same shims; if the successor then fails to
+/// install, the old package is installed again and the copied data is removed.
+#[tauri::command]
+pub async fn migrate_package(
+    window: Window,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    package_name: String,
+    replacement: Option<String>,
+) -> Result<String, String> {
+    validate_dir_name("package", &package_name)?;
+    let scoop_path = state.scoop_path();
+    let package_dir = scoop_path.join("apps").join(&package_name);
+    if !package_dir.exists() {
+        return Err(format!("Package '{}' is not installed", package_name));
+    }
+
+    let deprecation = get_package_deprecation(&scoop_path, &package_name, None);
+    let target = replacement
+        .filter(|r| !r.trim().is_empty())
+        .or(deprecation.replacement)
+        .ok_or_else(|| format!("No replacement package is known for '{}'", package_name))?;
+
+    let (target_bucket, target_name) = split_migration_target(&target)?;
+
+    if target_name.eq_ignore_ascii_case(&package_name) {
+        return Err(
+            "The replacement package must differ from the package being migrated".to_string(),
+        );
+    }
+
+    log::info!("Migrating '{}' to '{}'", package_name, target);
+    let original_bucket = fs::read_to_string(package_dir.join("current").join("install.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|install| install.get("bucket")?.as_str().map(String::from));
+
+    let persist_dir = state.scoop_dirs().persist;
+    let old_persist = persist_dir.join(&package_name);
+    let new_persist = persist_dir.join(&target_name);
+    let copied_persist = old_persist.is_dir() && !new_persist.exists();
+    if copied_persist {
+        crate::utils::copy_dir_recursive(&old_persist, &new_persist)
+            .map_err(|e| format!("Failed to copy persisted data to '{}': {}", target_name, e))?;
+        log::info!(
+            "Copied persisted data from {} to {}",
+            old_persist.display(),
+            new_persist.display()
+        );
+    }
+
+    let uninstalled = scoop::execute_scoop(
+        window.clone(),
+        ScoopOp::Uninstall,
+        Some(&package_name),
+        None,
+        Some(format!("migrate-uninstall-{}", package_name)),
+    )
+    .await;
+    let result = match uninstalled {
+        Ok(()) => scoop::execute_scoop(
+            window.clone(),
+            ScoopOp::Install,
+            Some(&target_name),
+            target_bucket.as_deref(),
+            Some(format!("migrate-install-{}", target_name)),
+        )
+        .await
+        .map_err(|install_error| (install_error, true)),
+        Err(e) => Err((e, false)),
+    };
+
+    if let Err((error, uninstalled)) = result {
+        if copied_persist {
+            let _ = fs::remove_dir_all(&new_persist);
+        }
+        if uninstalled {
+            log::warn!(
+                "Installing '{}' failed, reinstalling '{}': {}",
+                target_name,
+                package_name,
+                error
+            );
+            if let Err(restore_error) = reinstall_original(
+                window,
+                &scoop_path,
+                &package_name,
+                original_bucket.as_deref(),
+            )
+            .await
+            {
+                invalidate_installed_cache(state).await;
+                return Err(format!(
+                    "Installing '{}' failed ({}), and reinstalling '{}' failed too: {}",
+                    target_name, error, package_name, restore_error
+                ));
+            }
+        }
+        invalidate_installed_cache(state).await;
+        return Err(error);
+    }
+
+    invalidate_manifest_cache().await;
+    invalidate_installed_cache(state.clone()).await;
+    trigger_auto_cleanup(app, state).await;
+
+    Ok(target_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn detects_replacement_from_comment() {
+        let manifest = json!({ "version": "1.0", "##": "Deprecated, use `newtool` instead." });
+        let info = detect_deprecation(&manifest, Path::new("bucket/oldtool.json"), "oldtool");
+        assert!(info.is_deprecated);
+        assert_eq!(info.replacement.as_deref(), Some("newtool"));
+    }
+
+    #[test]
+    fn ignores_regular_manifests() {
+        let manifest = json!({ "version": "1.0", "##": "Use the portable build" });
+        let info = detect_deprecation(&manifest, Path::new("bucket/tool.json"), "tool");
+        assert_eq!(info, DeprecationInfo::default());
+    }
+
+    #[test]
+    fn mentions_of_deprecated_options_are_not_deprecation() {
+        let manifest = json!({
+            "version": "1.0",
+            "##": ["The deprecated --legacy flag is no longer passed.", "Use the portable build"]
+        });
+        let info = detect_deprecation(&manifest, Path::new("bucket/tool.json"), "tool");
+        assert!(!info.is_deprecated);
+
+        let manifest = json!({ "version": "1.0", "##": ["Build notes", "This package is deprecated; switch to newtool"] });
+        let info = detect_deprecation(&manifest, Path::new("bucket/tool.json"), "tool");
+        assert_eq!(info.replacement.as_deref(), Some("newtool"));
+    }
+
+    #[test]
+    fn migration_targets_must_be_plain_names() {
+        assert_eq!(
+            split_migration_target("extras/newtool").unwrap(),
+            (Some("extras".to_string()), "newtool".to_string())
+        );
+        assert_eq!(
+            split_migration_target("newtool").unwrap(),
+            (None, "newtool".to_string())
+        );
+        for target in ["extras/../../x", "../x", "extras/", "a\\b"] {
+            assert!(split_migration_target(target).is_err(), "{}", target);
+        }
+    }
+}
@@ -0,0 +1,230 @@
+//! Checks of the app's own runtime: the web view, plugins and persisted files.
+//!
+//! A missing or outdated WebView2 runtime, an unwritable web view profile or a
+//! corrupt store file all end in a blank or unresponsive window. These checks turn
+//! those cases into items with repair steps users can follow themselves.
+use super::checkup::CheckupItem;
+use crate::commands::settings::STORE_PATH;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_store::StoreExt;
+
+/// Oldest WebView2 release the frontend runs on: Vite's default build target is
+/// Chrome 107.
+#[cfg(windows)]
+const MIN_WEBVIEW2_MAJOR: u32 = 107;
+const WEBVIEW2_DOWNLOAD_URL: &str = "https://developer.microsoft.com/microsoft-edge/webview2/";
+
+fn item(key: &str, status: bool, params: serde_json::Value, suggestion: String) -> CheckupItem {
+    CheckupItem {
+        id: None,
+        status,
+        key: key.to_string(),
+        params: Some(params),
+        suggestion: if status { None } else { Some(suggestion) },
+    }
+}
+
+/// Checks the web view runtime version against `min_major`, when one applies.
+pub fn check_webview_runtime(
+    version: Result<String, String>,
+    min_major: Option<u32>,
+) -> CheckupItem {
+    match version {
+        Ok(version) => {
+            let major = version
+                .split('.')
+                .next()
+                .and_then(|m| m.parse::<u32>().ok());
+            let supported = match (min_major, major) {
+                (Some(min), Some(major)) => major >= min,
+                _ => true,
+            };
+            item(
+                "webviewRuntime",
+                supported,
+                serde_json::json!({ "version": version, "minimum": min_major }),
+                format!(
+                    "WebView2 {} is too old for this app. Update Microsoft Edge WebView2 Runtime from {}",
+                    version, WEBVIEW2_DOWNLOAD_URL
+                ),
+            )
+        }
+        Err(e) => item(
+            "webviewRuntime",
+            false,
+            serde_json::json!({ "error": e }),
+            format!(
+                "The WebView2 runtime could not be found ({}). Install the Evergreen runtime from {}",
+                e, WEBVIEW2_DOWNLOAD_URL
+            ),
+        ),
+    }
+}
+
+/// Creates and removes a probe file to tell whether `dir` is writable.
+fn is_writable(dir: &Path) -> bool {
+    if fs::create_dir_all(dir).is_err() {
+        return false;
+    }
+    let probe = dir.join(".pailer-write-probe");
+    let writable = fs::write(&probe, b"").is_ok();
+    let _ = fs::remove_file(&probe);
+    writable
+}
+
+/// Folder of the web view profile: `WEBVIEW2_USER_DATA_FOLDER` when set, which portable
+/// mode does, else where Tauri puts it in the local app data directory.
+fn webview_profile_dir<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("WEBVIEW2_USER_DATA_FOLDER") {
+        return Some(PathBuf::from(dir));
+    }
+    let local = app.path().app_local_data_dir().ok()?;
+    if cfg!(windows) {
+        Some(local.join("EBWebView"))
+    } else {
+        Some(local)
+    }
+}
+
+/// Checks that the web view profile folder, which holds local storage, is writable.
+pub fn check_webview_profile(dir: &Path) -> CheckupItem {
+    item(
+        "webviewProfileWritable",
+        is_writable(dir),
+        serde_json::json!({ "path": dir.display().to_string() }),
+        format!(
+            "The web view profile folder {} is not writable, which leaves the window blank. Close the app, check the folder's permissions or delete it to reset the web view (saved UI state is lost)",
+            dir.display()
+        ),
+    )
+}
+
+/// Lists the JSON files directly in `dir` that do not parse, such as a settings store
+/// cut short by a crash.
+pub fn find_corrupt_json_files(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut corrupt: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+        .filter(|path| {
+            fs::read_to_string(path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+                .is_none()
+        })
+        .filter_map(|path| path.file_name().map(|n| n.to_string_lossy().to_string()))
+        .collect();
+    corrupt.sort();
+    corrupt
+}
+
+/// Checks the settings store and other persisted state files in the app data directory.
+pub fn check_persisted_files(dir: &Path) -> CheckupItem {
+    let corrupt = find_corrupt_json_files(dir);
+    item(
+        "persistedFilesValid",
+        corrupt.is_empty(),
+        serde_json::json!({ "path": dir.display().to_string(), "files": corrupt }),
+        format!(
+            "These files in {} are corrupt and are ignored or reset on load: {}. Close the app and delete or rename them; settings and the saved window state fall back to defaults",
+            dir.display(),
+            corrupt.join(", ")
+        ),
+    )
+}
+
+/// Checks the plugins whose setup can still fail after the window is up. The others
+/// (opener, dialog, process, single instance) cannot fail once the app is running.
+fn check_plugins<R: Runtime>(app: &AppHandle<R>) -> Vec<CheckupItem> {
+    let mut items = Vec::new();
+
+    let store = app.store(crate::portable::store_path(STORE_PATH));
+    items.push(item(
+        "pluginStore",
+        store.is_ok(),
+        serde_json::json!({ "error": store.as_ref().err().map(|e| e.to_string()) }),
+        "The settings store could not be opened, so settings are not saved. Check the persisted files below".to_string(),
+    ));
+
+    if let Some(log_dir) = crate::portable::log_dir() {
+        items.push(item(
+            "pluginLog",
+            is_writable(&log_dir),
+            serde_json::json!({ "path": log_dir.display().to_string() }),
+            format!(
+                "The log folder {} is not writable, so no logs are kept for support reports",
+                log_dir.display()
+            ),
+        ));
+    }
+
+    #[cfg(windows)]
+    if !crate::utils::is_scoop_installation() {
+        use tauri_plugin_updater::UpdaterExt;
+        let updater = app.updater();
+        items.push(item(
+            "pluginUpdater",
+            updater.is_ok(),
+            serde_json::json!({ "error": updater.as_ref().err().map(|e| e.to_string()) }),
+            "The updater could not be set up; download new releases manually from the project page"
+                .to_string(),
+        ));
+    }
+
+    items
+}
+
+/// Runs the web view and app runtime checks.
+#[tauri::command]
+pub async fn run_app_runtime_checkup<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<CheckupItem>, String> {
+    log::info!("Running app runtime checkup");
+    let mut items = Vec::new();
+
+    #[cfg(windows)]
+    let min_major = Some(MIN_WEBVIEW2_MAJOR);
+    #[cfg(not(windows))]
+    let min_major = None;
+    items.push(check_webview_runtime(
+        tauri::webview_version().map_err(|e| e.to_string()),
+        min_major,
+    ));
+
+    if let Some(profile) = webview_profile_dir(&app) {
+        items.push(check_webview_profile(&profile));
+    }
+
+    items.extend(check_plugins(&app));
+
+    let data_dir = crate::portable::app_data_dir().or_else(|| app.path().app_data_dir().ok());
+    if let Some(data_dir) = data_dir {
+        items.push(check_persisted_files(&data_dir));
+    }
+
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_old_webview_and_corrupt_json() {
+        assert!(check_webview_runtime(Ok("120.0.2210.91".to_string()), Some(107)).status);
+        assert!(!check_webview_runtime(Ok("98.0.1108.43".to_string()), Some(107)).status);
+        assert!(!check_webview_runtime(Err("not found".to_string()), None).status);
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("settings.json"), r#"{"theme":"dark"}"#).unwrap();
+        fs::write(dir.path().join("metrics.json"), r#"{"calls":"#).unwrap();
+        fs::write(dir.path().join("notes.txt"), "not json").unwrap();
+        assert_eq!(find_corrupt_json_files(dir.path()), vec!["metrics.json"]);
+        assert!(!check_persisted_files(dir.path()).status);
+    }
+}
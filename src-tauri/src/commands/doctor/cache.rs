@@ -1,11 +1,16 @@
 //! Commands for managing the Scoop cache.
-use crate::commands::installed::get_installed_packages_full;
+use crate::commands::installed::get_installed_packages;
+use crate::engine::cache_import::{
+    cache_file_name, check_hash, manifest_downloads, parse_cache_name, suffix_matches,
+    url_file_name, HashCheck, ManifestDownload,
+};
+use crate::engine::manifest::find_manifest_candidates;
 use crate::state::AppState;
 use rayon::prelude::*;
 use serde::Serialize;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Runtime, State};
 
 /// Represents a single entry in the Scoop cache.
@@ -64,8 +69,7 @@ pub async fn list_cache_contents<R: Runtime>(
 ) -> Result<Vec<CacheEntry>, String> {
     log::info!("Listing cache contents from filesystem with version-awareness");
 
-    let scoop_path = state.scoop_path();
-    let cache_path = scoop_path.join("cache");
+    let cache_path = state.scoop_dirs().cache;
 
     if !cache_path.is_dir() {
         log::warn!("Scoop cache directory not found at: {:?}", cache_path);
@@ -73,7 +77,7 @@ pub async fn list_cache_contents<R: Runtime>(
     }
 
     // Get all installed packages to identify versioned installs
-    let installed_packages = get_installed_packages_full(app, state).await?;
+    let installed_packages = get_installed_packages(app, state).await?;
     let versioned_packages: HashSet<String> = installed_packages
         .iter()
         .filter(|pkg| pkg.is_versioned_install)
@@ -104,37 +108,39 @@ pub async fn list_cache_contents<R: Runtime>(
 ///
 /// # Arguments
 /// * `files` - An optional vector of file names to remove. If `None`, only non-versioned cache is cleared.
+/// * `permanent` - Erase instead of using the Recycle Bin; defaults to the setting.
 #[tauri::command]
 pub async fn clear_cache<R: Runtime>(
     app: AppHandle<R>,
     state: State<'_, AppState>,
     files: Option<Vec<String>>,
+    permanent: Option<bool>,
 ) -> Result<(), String> {
     log::info!(
         "Clearing cache from filesystem with version-awareness. Files: {:?}",
         &files
     );
 
-    let scoop_path = state.scoop_path();
-    let cache_path = scoop_path.join("cache");
+    let cache_path = state.scoop_dirs().cache;
 
     if !cache_path.is_dir() {
         return Ok(());
     }
 
     // Get versioned packages to avoid deleting their cache
-    let installed_packages = get_installed_packages_full(app, state).await?;
+    let installed_packages = get_installed_packages(app, state).await?;
     let versioned_packages: HashSet<String> = installed_packages
         .iter()
         .filter(|pkg| pkg.is_versioned_install)
         .map(|pkg| pkg.name.clone())
         .collect();
 
+    let recycle = crate::recycle::should_recycle(permanent);
     match files {
         Some(files_to_delete) if !files_to_delete.is_empty() => {
-            clear_specific_files_safe(&cache_path, &files_to_delete, &versioned_packages)
+            clear_specific_files_safe(&cache_path, &files_to_delete, &versioned_packages, recycle)
         }
-        _ => clear_safe_cache(&cache_path, &versioned_packages),
+        _ => clear_safe_cache(&cache_path, &versioned_packages, recycle),
     }
 }
 
@@ -143,70 +149,297 @@ fn clear_specific_files_safe(
     cache_path: &Path,
     files_to_delete: &[String],
     versioned_packages: &HashSet<String>,
+    recycle: bool,
 ) -> Result<(), String> {
     log::info!(
         "Clearing {} specified cache files (avoiding versioned installs).",
         files_to_delete.len()
     );
 
-    files_to_delete.par_iter().for_each(|file_name| {
-        // Parse the package name from the cache file name (format: name#version#hash.ext)
-        if let Some(package_name) = file_name.split('#').next() {
+    let paths: Vec<PathBuf> = files_to_delete
+        .iter()
+        .filter(|file_name| {
+            // Parse the package name from the cache file name (format: name#version#hash.ext)
+            let package_name = file_name.split('#').next().unwrap_or_default();
             if versioned_packages.contains(package_name) {
                 log::info!("Skipping cache file for versioned install: {}", file_name);
-                return; // Skip this file
-            }
-        }
-
-        let file_path = cache_path.join(file_name);
-        if file_path.is_file() {
-            match fs::remove_file(&file_path) {
-                Ok(()) => {
-                    log::debug!("Deleted cache file: {}", file_name);
-                }
-                Err(e) => {
-                    log::error!("Failed to delete cache file {}: {}", file_name, e);
-                }
+                return false;
             }
-        }
-    });
+            true
+        })
+        .map(|file_name| cache_path.join(file_name))
+        .filter(|file_path| file_path.is_file())
+        .collect();
 
-    Ok(())
+    delete_cache_files(&paths, recycle)
 }
 
 /// Removes all non-versioned files from the cache directory.
-fn clear_safe_cache(cache_path: &Path, versioned_packages: &HashSet<String>) -> Result<(), String> {
+fn clear_safe_cache(
+    cache_path: &Path,
+    versioned_packages: &HashSet<String>,
+    recycle: bool,
+) -> Result<(), String> {
     log::info!("Clearing cache directory (avoiding versioned installs).");
 
     let dir_entries =
         fs::read_dir(cache_path).map_err(|e| format!("Failed to read cache directory: {}", e))?;
 
-    dir_entries
-        .par_bridge()
+    let paths: Vec<PathBuf> = dir_entries
         .filter_map(Result::ok)
-        .for_each(|entry| {
-            let path = entry.path();
-            if path.is_file() {
-                if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-                    // Parse the package name from the cache file name
-                    if let Some(package_name) = file_name.split('#').next() {
-                        if versioned_packages.contains(package_name) {
-                            log::debug!("Skipping cache file for versioned install: {}", file_name);
-                            return; // Skip this file
-                        }
-                    }
-                }
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+                return true;
+            };
+            // Parse the package name from the cache file name
+            let package_name = file_name.split('#').next().unwrap_or_default();
+            if versioned_packages.contains(package_name) {
+                log::debug!("Skipping cache file for versioned install: {}", file_name);
+                return false;
+            }
+            true
+        })
+        .collect();
+
+    delete_cache_files(&paths, recycle)
+}
+
+/// Deletes cache files, in one Recycle Bin operation or permanently in parallel.
+fn delete_cache_files(paths: &[PathBuf], recycle: bool) -> Result<(), String> {
+    if recycle {
+        return crate::recycle::remove_paths(paths, true);
+    }
+
+    paths.par_iter().for_each(|path| match fs::remove_file(path) {
+        Ok(()) => {
+            log::debug!("Deleted cache file: {:?}", path.file_name());
+        }
+        Err(e) => {
+            log::error!("Failed to remove cache file {:?}: {}", path.file_name(), e);
+        }
+    });
+
+    Ok(())
+}
+
+/// Result of importing one file into the cache.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheImportEntry {
+    pub file_name: String,
+    /// `imported`, `exists`, `noMatch`, `hashMismatch`, `unverifiable` or `failed`.
+    pub status: String,
+    pub package: Option<String>,
+    pub version: Option<String>,
+    pub cache_file_name: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheImportResult {
+    pub imported: usize,
+    pub entries: Vec<CacheImportEntry>,
+}
+
+fn read_downloads(app: &str, manifest_path: &Path) -> Vec<ManifestDownload> {
+    fs::read_to_string(manifest_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .map(|manifest| manifest_downloads(app, &manifest))
+        .unwrap_or_default()
+}
+
+/// Downloads of `app` from its bucket manifests and every installed version.
+fn downloads_for_app(scoop_dir: &Path, app: &str) -> Vec<ManifestDownload> {
+    let mut downloads: Vec<ManifestDownload> = find_manifest_candidates(scoop_dir, app)
+        .iter()
+        .flat_map(|(path, _)| read_downloads(app, path))
+        .collect();
+    if let Ok(versions) = fs::read_dir(scoop_dir.join("apps").join(app)) {
+        for version in versions.flatten() {
+            downloads.extend(read_downloads(app, &version.path().join("manifest.json")));
+        }
+    }
+    downloads
+}
 
-                match fs::remove_file(&path) {
-                    Ok(()) => {
-                        log::debug!("Deleted cache file: {:?}", path.file_name());
-                    }
-                    Err(e) => {
-                        log::error!("Failed to remove cache file {:?}: {}", path.file_name(), e);
-                    }
+/// Downloads of every bucket manifest, keyed by the lowercase file name they save as.
+fn downloads_by_file_name(scoop_dir: &Path) -> HashMap<String, Vec<ManifestDownload>> {
+    let mut index: HashMap<String, Vec<ManifestDownload>> = HashMap::new();
+    let Ok(buckets) = fs::read_dir(scoop_dir.join("buckets")) else {
+        return index;
+    };
+    for bucket in buckets.flatten() {
+        for dir in [bucket.path(), bucket.path().join("bucket")] {
+            let Ok(manifests) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for manifest in manifests.flatten() {
+                let path = manifest.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                    continue;
+                }
+                let Some(app) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                for download in read_downloads(app, &path) {
+                    index
+                        .entry(url_file_name(&download.url).to_lowercase())
+                        .or_default()
+                        .push(download);
                 }
             }
-        });
+        }
+    }
+    index
+}
 
-    Ok(())
+/// Picks the download whose hash matches the file.
+///
+/// Returns the status to report when none does: `hashMismatch` if any candidate had
+/// a hash we could check, `unverifiable` otherwise.
+fn match_download(
+    path: &Path,
+    candidates: &[ManifestDownload],
+) -> Result<ManifestDownload, &'static str> {
+    if candidates.is_empty() {
+        return Err("noMatch");
+    }
+    let mut checked = false;
+    for candidate in candidates {
+        match check_hash(path, candidate.hash.as_deref()) {
+            Ok(HashCheck::Match) => return Ok(candidate.clone()),
+            Ok(HashCheck::Mismatch) => checked = true,
+            Ok(HashCheck::Unverifiable) => {}
+            Err(e) => log::warn!("Failed to hash {}: {}", path.display(), e),
+        }
+    }
+    Err(if checked { "hashMismatch" } else { "unverifiable" })
+}
+
+fn import_file(
+    scoop_dir: &Path,
+    cache_dir: &Path,
+    path: &Path,
+    index: &mut Option<HashMap<String, Vec<ManifestDownload>>>,
+) -> CacheImportEntry {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut entry = CacheImportEntry {
+        file_name: file_name.clone(),
+        status: String::new(),
+        package: None,
+        version: None,
+        cache_file_name: None,
+        message: None,
+    };
+
+    // Files copied from another cache carry their app and version in the name
+    let candidates: Vec<ManifestDownload> = match parse_cache_name(&file_name) {
+        Some((app, version, rest)) => {
+            let same_version: Vec<ManifestDownload> = downloads_for_app(scoop_dir, app)
+                .into_iter()
+                .filter(|d| d.version == version)
+                .collect();
+            let by_name: Vec<ManifestDownload> = same_version
+                .iter()
+                .filter(|d| suffix_matches(rest, app, version, &d.url))
+                .cloned()
+                .collect();
+            if by_name.is_empty() {
+                same_version
+            } else {
+                by_name
+            }
+        }
+        None => index
+            .get_or_insert_with(|| downloads_by_file_name(scoop_dir))
+            .get(&file_name.to_lowercase())
+            .cloned()
+            .unwrap_or_default(),
+    };
+
+    let download = match match_download(path, &candidates) {
+        Ok(download) => download,
+        Err(status) => {
+            entry.status = status.to_string();
+            return entry;
+        }
+    };
+    let target_name = cache_file_name(&download.app, &download.version, &download.url);
+    entry.package = Some(download.app);
+    entry.version = Some(download.version);
+    entry.cache_file_name = Some(target_name.clone());
+
+    let target = cache_dir.join(&target_name);
+    if target.is_file() {
+        entry.status = "exists".to_string();
+        return entry;
+    }
+    match fs::copy(path, &target) {
+        Ok(_) => entry.status = "imported".to_string(),
+        Err(e) => {
+            entry.status = "failed".to_string();
+            entry.message = Some(e.to_string());
+        }
+    }
+    entry
+}
+
+/// Imports downloaded files from a folder into the Scoop cache for offline installs.
+///
+/// Files may carry a Scoop cache name (`app#version#…`, e.g. copied from another
+/// machine's cache) or the plain name they were downloaded as. A file is only imported
+/// when its hash matches a manifest, and is renamed the way Scoop expects.
+#[tauri::command]
+pub async fn import_cache_files(
+    state: State<'_, AppState>,
+    source_dir: String,
+) -> Result<CacheImportResult, String> {
+    let source = PathBuf::from(&source_dir);
+    if !source.is_dir() {
+        return Err(format!("'{}' is not a directory", source_dir));
+    }
+    let scoop_dir = state.scoop_path();
+    let cache_dir = state.scoop_dirs().cache;
+    log::info!(
+        "Importing cache files from {} into {}",
+        source.display(),
+        cache_dir.display()
+    );
+
+    tokio::task::spawn_blocking(move || {
+        fs::create_dir_all(&cache_dir)
+            .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+        let mut files: Vec<PathBuf> = fs::read_dir(&source)
+            .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .collect();
+        files.sort();
+
+        let mut index = None;
+        let mut result = CacheImportResult::default();
+        for file in files {
+            let entry = import_file(&scoop_dir, &cache_dir, &file, &mut index);
+            if entry.status == "imported" {
+                result.imported += 1;
+            }
+            result.entries.push(entry);
+        }
+        log::info!(
+            "Imported {} of {} files into the cache",
+            result.imported,
+            result.entries.len()
+        );
+        Ok(result)
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
@@ -0,0 +1,305 @@
+//! Sharing downloads between the user and global Scoop caches.
+//!
+//! A machine with both a user and a global (admin) Scoop keeps two download caches,
+//! `<root>\cache` and `<global>\cache`, and the same archive is often downloaded into
+//! both. Scoop names cache files `app#version#hash.ext` after the download URL, so files
+//! of the same name are the same download; they are also compared by content before
+//! one is replaced by a hard link to the other. Hard links only work on one volume, so
+//! caches on different drives are reported but left alone.
+//!
+//! Links only ever point from the user cache into the global one. A user's download
+//! linked into the global cache would stay writable by that user after an elevated
+//! Scoop had checked its hash, so downloads only in the user cache are not shared.
+use crate::engine::cache_import::file_digest;
+use crate::state::AppState;
+use serde::Serialize;
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateCacheFile {
+    pub file_name: String,
+    pub size: u64,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheDedupReport {
+    pub user_cache: String,
+    /// `None` when there is no global cache separate from the user one.
+    pub global_cache: Option<String>,
+    /// Files in both caches that are already hard links to one file.
+    pub linked: usize,
+    /// Space the existing links save.
+    pub saved_bytes: u64,
+    /// Identical files stored twice.
+    pub duplicates: Vec<DuplicateCacheFile>,
+    /// Space linking the duplicates would free.
+    pub reclaimable_bytes: u64,
+    /// Downloads only in the user cache.
+    pub user_only: usize,
+    /// Downloads only in the global cache.
+    pub global_only: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheShareResult {
+    /// Duplicates replaced by hard links.
+    pub linked: usize,
+    /// Downloads linked into the cache that lacked them.
+    pub shared: usize,
+    pub bytes_saved: u64,
+    pub errors: Vec<String>,
+    /// The caches after the changes.
+    pub report: CacheDedupReport,
+}
+
+/// Whether two paths are hard links to the same file.
+#[cfg(windows)]
+fn same_file(a: &Path, b: &Path) -> bool {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION,
+    };
+
+    let identity = |path: &Path| {
+        let file = fs::File::open(path).ok()?;
+        // SAFETY: the handle belongs to `file`, which is open for the duration of the
+        // call, and `info` is a plain struct the call fills in.
+        let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+        let ok = unsafe { GetFileInformationByHandle(file.as_raw_handle() as _, &mut info) };
+        (ok != 0).then_some((
+            info.dwVolumeSerialNumber,
+            info.nFileIndexHigh,
+            info.nFileIndexLow,
+        ))
+    };
+    matches!((identity(a), identity(b)), (Some(x), Some(y)) if x == y)
+}
+
+#[cfg(not(windows))]
+fn same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(x), Ok(y)) => x.dev() == y.dev() && x.ino() == y.ino(),
+        _ => false,
+    }
+}
+
+/// Downloads in a cache directory by file name, with their sizes. Partial downloads
+/// are left out.
+fn cache_files(dir: &Path) -> BTreeMap<String, u64> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return BTreeMap::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let metadata = entry.metadata().ok()?;
+            (metadata.is_file() && name.contains('#') && !name.ends_with(".download"))
+                .then_some((name, metadata.len()))
+        })
+        .collect()
+}
+
+fn same_content(a: &Path, b: &Path) -> bool {
+    match (file_digest::<Sha256>(a), file_digest::<Sha256>(b)) {
+        (Ok(x), Ok(y)) => x == y,
+        _ => false,
+    }
+}
+
+/// The global cache when it exists and is not the user cache.
+fn global_cache(state: &AppState) -> (PathBuf, Option<PathBuf>) {
+    let dirs = state.scoop_dirs();
+    let global = dirs.global.join("cache");
+    let separate = global.is_dir()
+        && match (fs::canonicalize(&dirs.cache), fs::canonicalize(&global)) {
+            (Ok(user), Ok(global)) => user != global,
+            _ => true,
+        };
+    (dirs.cache, separate.then_some(global))
+}
+
+/// Compares the downloads of the user and global caches.
+pub fn build_dedup_report(user_cache: &Path, global_cache: &Path) -> CacheDedupReport {
+    let user = cache_files(user_cache);
+    let global = cache_files(global_cache);
+    let mut report = CacheDedupReport {
+        user_cache: user_cache.to_string_lossy().to_string(),
+        global_cache: Some(global_cache.to_string_lossy().to_string()),
+        global_only: global
+            .keys()
+            .filter(|name| !user.contains_key(*name))
+            .count(),
+        ..Default::default()
+    };
+
+    for (name, &size) in &user {
+        let Some(&global_size) = global.get(name) else {
+            report.user_only += 1;
+            continue;
+        };
+        let (user_path, global_path) = (user_cache.join(name), global_cache.join(name));
+        if same_file(&user_path, &global_path) {
+            report.linked += 1;
+            report.saved_bytes += size;
+        } else if size == global_size && same_content(&user_path, &global_path) {
+            report.reclaimable_bytes += size;
+            report.duplicates.push(DuplicateCacheFile {
+                file_name: name.clone(),
+                size,
+            });
+        }
+    }
+    report
+}
+
+/// Replaces `target` with a hard link to `source`. The link is made beside it and
+/// renamed over it, so a failure leaves the original in place.
+fn link_over(source: &Path, target: &Path) -> Result<(), String> {
+    let partial = target.with_extension("link.partial");
+    let _ = fs::remove_file(&partial);
+    fs::hard_link(source, &partial)
+        .and_then(|_| fs::rename(&partial, target))
+        .map_err(|e| {
+            let _ = fs::remove_file(&partial);
+            format!("Failed to link {}: {}", target.display(), e)
+        })
+}
+
+/// Replaces user cache duplicates with hard links to the global copies, which the
+/// user cannot always write to. With `share_missing`, downloads only in the global
+/// cache are also linked into the user cache; never the other way round.
+pub fn share_caches(
+    user_cache: &Path,
+    global_cache: &Path,
+    share_missing: bool,
+) -> CacheShareResult {
+    let before = build_dedup_report(user_cache, global_cache);
+    let mut result = CacheShareResult::default();
+
+    for duplicate in &before.duplicates {
+        let source = global_cache.join(&duplicate.file_name);
+        match link_over(&source, &user_cache.join(&duplicate.file_name)) {
+            Ok(()) => {
+                result.linked += 1;
+                result.bytes_saved += duplicate.size;
+            }
+            Err(e) => result.errors.push(e),
+        }
+    }
+
+    if share_missing {
+        let user = cache_files(user_cache);
+        let global = cache_files(global_cache);
+        let missing = global.keys().filter(|name| !user.contains_key(*name));
+        for name in missing {
+            match fs::hard_link(global_cache.join(name), user_cache.join(name)) {
+                Ok(()) => result.shared += 1,
+                Err(e) => result.errors.push(format!(
+                    "Failed to link {} into {}: {}",
+                    name,
+                    user_cache.display(),
+                    e
+                )),
+            }
+        }
+    }
+
+    log::info!(
+        "Shared cache downloads: {} duplicates linked ({} bytes), {} downloads shared, {} errors",
+        result.linked,
+        result.bytes_saved,
+        result.shared,
+        result.errors.len()
+    );
+    result.report = build_dedup_report(user_cache, global_cache);
+    result
+}
+
+/// Reports downloads stored in both the user and global caches, and the space linking
+/// them saves or would save.
+#[tauri::command]
+pub async fn get_cache_dedup_report(
+    state: State<'_, AppState>,
+) -> Result<CacheDedupReport, String> {
+    match global_cache(&state) {
+        (user, Some(global)) => {
+            tokio::task::spawn_blocking(move || build_dedup_report(&user, &global))
+                .await
+                .map_err(|e| format!("Failed to compare caches: {}", e))
+        }
+        (user, None) => Ok(CacheDedupReport {
+            user_cache: user.to_string_lossy().to_string(),
+            ..Default::default()
+        }),
+    }
+}
+
+/// Hard-links identical downloads of the user and global caches, and with
+/// `share_missing` links the downloads only the global cache has into the user cache,
+/// so they are not downloaded again.
+#[tauri::command]
+pub async fn share_cache_files(
+    state: State<'_, AppState>,
+    share_missing: Option<bool>,
+) -> Result<CacheShareResult, String> {
+    let (user, Some(global)) = global_cache(&state) else {
+        return Err("No global Scoop cache to share downloads with".to_string());
+    };
+    let share_missing = share_missing.unwrap_or(false);
+    tokio::task::spawn_blocking(move || share_caches(&user, &global, share_missing))
+        .await
+        .map_err(|e| format!("Failed to share cache files: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_identical_downloads() {
+        let dir = tempfile::tempdir().unwrap();
+        let (user, global) = (dir.path().join("user"), dir.path().join("global"));
+        fs::create_dir_all(&user).unwrap();
+        fs::create_dir_all(&global).unwrap();
+        for cache in [&user, &global] {
+            fs::write(cache.join("git#2.45.0#abc.7z"), "archive").unwrap();
+        }
+        fs::write(user.join("rg#14.1.0#def.zip"), "old").unwrap();
+        fs::write(global.join("rg#14.1.0#def.zip"), "new").unwrap();
+        fs::write(global.join("jq#1.7#123.exe"), "jq").unwrap();
+        fs::write(global.join("fd#9.0#456.zip.download"), "partial").unwrap();
+        fs::write(user.join("bat#0.24#789.zip"), "bat").unwrap();
+
+        let report = build_dedup_report(&user, &global);
+        assert_eq!(
+            report.duplicates,
+            vec![DuplicateCacheFile {
+                file_name: "git#2.45.0#abc.7z".to_string(),
+                size: 7,
+            }]
+        );
+        assert_eq!(report.reclaimable_bytes, 7);
+        assert_eq!((report.user_only, report.global_only), (1, 1));
+
+        let result = share_caches(&user, &global, true);
+        assert!(result.errors.is_empty());
+        assert_eq!((result.linked, result.shared), (1, 1));
+        assert!(!global.join("bat#0.24#789.zip").exists());
+        assert_eq!(result.report.linked, 2);
+        assert_eq!(result.report.saved_bytes, 9);
+        assert!(result.report.duplicates.is_empty());
+        assert_eq!(
+            fs::read_to_string(user.join("rg#14.1.0#def.zip")).unwrap(),
+            "old"
+        );
+    }
+}
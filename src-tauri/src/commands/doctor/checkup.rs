@@ -6,6 +6,7 @@
 
 use crate::commands::powershell::create_powershell_command;
 use crate::state::AppState;
+use crate::utils::ScoopDirs;
 use serde::Serialize;
 use std::path::Path;
 use tauri::State;
@@ -74,7 +75,7 @@ fn check_main_bucket_installed(scoop_path: &Path) -> CheckupItem {
 /// Checks for missing recommended helper packages.
 fn check_missing_helpers(scoop_path: &Path) -> Vec<CheckupItem> {
     const HELPERS: &[&str] = &["7zip", "dark", "innounp", "lessmsi"];
-    let apps_path = scoop_path.join("apps");
+    let apps_path = ScoopDirs::resolve(scoop_path).apps;
 
     HELPERS
         .iter()
@@ -124,7 +125,11 @@ pub async fn run_scoop_checkup(state: State<'_, AppState>) -> Result<Vec<Checkup
         items.push(windows_checks::check_scoop_on_ntfs(&scoop_path));
     }
 
+    items.extend(super::shims_path::check_shims_path(&scoop_path));
     items.extend(check_missing_helpers(&scoop_path));
+    items.extend(super::runtimes::check_suggested_runtimes(
+        &ScoopDirs::resolve(&scoop_path).apps,
+    ));
 
     // Await the async check and prepend its result to the list.
     let git_check_result = git_check_future.await;
@@ -1,9 +1,122 @@
 //! Commands for cleaning up Scoop apps and cache.
-use crate::commands::installed::get_installed_packages_full;
-use crate::commands::powershell;
+use crate::commands::installed::get_installed_packages;
+use crate::commands::statistics::dir_size;
+use crate::commands::version_pin::load_version_pins;
+use crate::commands::{powershell, scoop};
+use crate::models::{InstallScope, ScoopPackage};
 use crate::state::AppState;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
 use tauri::{AppHandle, Runtime, State, Window};
 
+/// A version directory or cache file a cleanup would remove.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupItem {
+    /// Empty for partial downloads, which belong to no package.
+    pub package: String,
+    pub version: String,
+    pub path: String,
+    pub size: u64,
+}
+
+/// What [`cleanup_all_apps`] and [`cleanup_outdated_cache`] would remove.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupPreview {
+    /// Version directories other than the installed one.
+    pub versions: Vec<CleanupItem>,
+    /// Downloads of other than the installed versions, and partial downloads.
+    pub cache_files: Vec<CleanupItem>,
+    pub versions_bytes: u64,
+    pub cache_bytes: u64,
+    pub total_bytes: u64,
+    /// Versioned installs and pinned packages, which both cleanups leave alone.
+    pub skipped: Vec<String>,
+}
+
+/// Lists what `scoop cleanup <packages> [--cache]` removes for the user's installed
+/// `packages`: every entry of an app directory but `current` and the installed version,
+/// cached downloads of other versions, and `*.download` leftovers.
+pub fn build_cleanup_preview(
+    apps_dir: &Path,
+    cache_dir: &Path,
+    packages: &[ScoopPackage],
+    pins: &BTreeMap<String, String>,
+) -> CleanupPreview {
+    let mut preview = CleanupPreview::default();
+    let regular: Vec<&ScoopPackage> = packages
+        .iter()
+        .filter(|pkg| pkg.scope == InstallScope::User && !pkg.broken)
+        .filter(|pkg| {
+            let skip = pkg.is_versioned_install || pins.contains_key(&pkg.name);
+            if skip {
+                preview.skipped.push(pkg.name.clone());
+            }
+            !skip
+        })
+        .collect();
+
+    for pkg in &regular {
+        let Ok(entries) = fs::read_dir(apps_dir.join(&pkg.name)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let version = entry.file_name().to_string_lossy().to_string();
+            if version == "current" || version == pkg.version {
+                continue;
+            }
+            let path = entry.path();
+            let size = match fs::symlink_metadata(&path) {
+                Ok(meta) if meta.is_dir() => dir_size(&path),
+                Ok(meta) => meta.len(),
+                Err(_) => 0,
+            };
+            preview.versions.push(CleanupItem {
+                package: pkg.name.clone(),
+                version,
+                path: path.display().to_string(),
+                size,
+            });
+        }
+    }
+
+    for entry in fs::read_dir(cache_dir).into_iter().flatten().flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let lower = file_name.to_lowercase();
+        let mut parts = file_name.splitn(3, '#');
+        let (package, version) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+        let outdated = regular.iter().any(|pkg| {
+            package.eq_ignore_ascii_case(&pkg.name) && !version.eq_ignore_ascii_case(&pkg.version)
+        });
+        let partial = lower.ends_with(".download");
+        if !outdated && !partial {
+            continue;
+        }
+        preview.cache_files.push(CleanupItem {
+            package: if partial && !outdated {
+                String::new()
+            } else {
+                package.to_string()
+            },
+            version: version.to_string(),
+            path: entry.path().display().to_string(),
+            size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+        });
+    }
+
+    preview
+        .versions
+        .sort_by(|a, b| (&a.package, &a.version).cmp(&(&b.package, &b.version)));
+    preview.cache_files.sort_by(|a, b| a.path.cmp(&b.path));
+    preview.versions_bytes = preview.versions.iter().map(|i| i.size).sum();
+    preview.cache_bytes = preview.cache_files.iter().map(|i| i.size).sum();
+    preview.total_bytes = preview.versions_bytes + preview.cache_bytes;
+    preview
+}
+
 /// Runs a specific Scoop cleanup command and streams its output.
 ///
 /// # Arguments
@@ -19,14 +132,12 @@ async fn run_cleanup_command(
 ) -> Result<(), String> {
     log::info!("Executing cleanup command: {}", command);
     
-    let result = powershell::run_and_stream_command(
+    let result = scoop::run_queued_command(
         window,
         command.to_string(),
         operation_name.to_string(),
-        powershell::EVENT_OUTPUT,
-        powershell::EVENT_FINISHED,
-        powershell::EVENT_CANCEL,
         Some(operation_id.to_string()),
+        powershell::OperationClass::Cleanup,
     )
     .await;
     
@@ -38,6 +149,32 @@ async fn run_cleanup_command(
     result
 }
 
+/// Lists the old versions and cache files the cleanup commands would remove, with the
+/// space each frees, without removing anything.
+#[tauri::command]
+pub async fn preview_cleanup<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+) -> Result<CleanupPreview, String> {
+    let pins = load_version_pins(&app);
+    let packages = get_installed_packages(app, state.clone()).await?;
+    let dirs = state.scoop_dirs();
+    let preview = tokio::task::spawn_blocking(move || {
+        build_cleanup_preview(&dirs.apps, &dirs.cache, &packages, &pins)
+    })
+    .await
+    .map_err(|e| format!("Failed to preview cleanup: {}", e))?;
+
+    log::info!(
+        "Cleanup preview: {} old versions ({} bytes), {} cache files ({} bytes)",
+        preview.versions.len(),
+        preview.versions_bytes,
+        preview.cache_files.len(),
+        preview.cache_bytes
+    );
+    Ok(preview)
+}
+
 /// Cleans up old versions of all installed apps, with an option to include/exclude versioned installs.
 #[tauri::command]
 pub async fn cleanup_all_apps<R: Runtime>(
@@ -46,9 +183,10 @@ pub async fn cleanup_all_apps<R: Runtime>(
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     log::info!("Running cleanup of old app versions");
+    let pins = load_version_pins(&app);
 
     // Get all installed packages to identify versioned installs
-    let installed_packages_result = get_installed_packages_full(app, state.clone()).await;
+    let installed_packages_result = get_installed_packages(app, state.clone()).await;
     
     let installed_packages = match installed_packages_result {
         Ok(packages) => {
@@ -61,22 +199,22 @@ pub async fn cleanup_all_apps<R: Runtime>(
         }
     };
 
-    // Count versioned installs for logging
+    // Count versioned installs and pinned packages for logging
     let versioned_count = installed_packages
         .iter()
-        .filter(|pkg| pkg.is_versioned_install)
+        .filter(|pkg| pkg.is_versioned_install || pins.contains_key(&pkg.name))
         .count();
 
     if versioned_count > 0 {
         log::warn!(
-            "Found {} versioned installs. These will be EXCLUDED from cleanup to preserve specific versions.", 
+            "Found {} versioned installs or pinned packages. These will be EXCLUDED from cleanup to preserve specific versions.",
             versioned_count
         );
 
         // Get only regular packages (non-versioned installs)
         let regular_packages: Vec<String> = installed_packages
             .iter()
-            .filter(|pkg| !pkg.is_versioned_install)
+            .filter(|pkg| !pkg.is_versioned_install && !pins.contains_key(&pkg.name))
             .map(|pkg| pkg.name.clone())
             .collect();
 
@@ -122,9 +260,10 @@ pub async fn cleanup_outdated_cache<R: Runtime>(
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     log::info!("Running version-aware cleanup of outdated app caches");
+    let pins = load_version_pins(&app);
 
     // Get all installed packages to identify versioned installs
-    let installed_packages_result = get_installed_packages_full(app, state.clone()).await;
+    let installed_packages_result = get_installed_packages(app, state.clone()).await;
     
     let installed_packages = match installed_packages_result {
         Ok(packages) => {
@@ -137,10 +276,10 @@ pub async fn cleanup_outdated_cache<R: Runtime>(
         }
     };
 
-    // Collect packages that are NOT versioned installs (safe to clean cache)
+    // Collect packages that are NOT versioned installs or pinned (safe to clean cache)
     let safe_packages: Vec<String> = installed_packages
         .iter()
-        .filter(|pkg| !pkg.is_versioned_install)
+        .filter(|pkg| !pkg.is_versioned_install && !pins.contains_key(&pkg.name))
         .map(|pkg| pkg.name.clone())
         .collect();
 
@@ -155,4 +294,65 @@ pub async fn cleanup_outdated_cache<R: Runtime>(
 
     log::info!("Running cache cleanup for packages: {}", packages_str);
     run_cleanup_command(window, &command, "Cleanup Outdated App Caches", "cleanup-cache").await
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScoopFixture;
+
+    #[test]
+    fn previews_old_versions_and_cache_files() {
+        let fixture = ScoopFixture::new();
+        fixture.install("git", "2.45.0", "main");
+        fixture.app_version("git", "2.44.0", Some("main"));
+        fixture.install("node", "18.0.0", "main");
+        fixture.app_version("node", "20.0.0", Some("main"));
+        fixture.install("python", "3.12.0", "main");
+        fixture.app_version("python", "3.11.0", Some("main"));
+        fixture.cache_file("git", "2.44.0", "abc1234.7z");
+        fixture.cache_file("git", "2.45.0", "def5678.7z");
+        fixture.cache_file("node", "20.0.0", "0123456.zip");
+        fs::write(
+            fixture.cache_dir().join("tool#1.0#7654321.zip.download"),
+            b"part",
+        )
+        .unwrap();
+
+        let package = |name: &str, version: &str, versioned: bool| ScoopPackage {
+            name: name.to_string(),
+            version: version.to_string(),
+            is_versioned_install: versioned,
+            ..Default::default()
+        };
+        let preview = build_cleanup_preview(
+            &fixture.apps_dir(),
+            &fixture.cache_dir(),
+            &[
+                package("git", "2.45.0", false),
+                package("node", "18.0.0", true),
+                package("python", "3.12.0", false),
+            ],
+            &BTreeMap::from([("python".to_string(), "3.11.0".to_string())]),
+        );
+
+        let versions: Vec<_> = preview
+            .versions
+            .iter()
+            .map(|i| (i.package.as_str(), i.version.as_str()))
+            .collect();
+        assert_eq!(versions, vec![("git", "2.44.0")]);
+        let cached: Vec<_> = preview
+            .cache_files
+            .iter()
+            .map(|i| i.package.as_str())
+            .collect();
+        assert_eq!(cached, vec!["git", ""]);
+        assert_eq!(preview.skipped, vec!["node", "python"]);
+        assert_eq!(preview.cache_bytes, 10);
+        assert_eq!(
+            preview.total_bytes,
+            preview.versions_bytes + preview.cache_bytes
+        );
+    }
+}
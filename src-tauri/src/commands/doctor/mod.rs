@@ -1,5 +1,9 @@
+pub mod app_runtime;
 pub mod cache;
+pub mod cache_sharing;
 pub mod checkup;
 pub mod cleanup;
+pub mod runtimes;
 pub mod shim;
+pub mod shims_path;
 pub mod windows_checks;
@@ -0,0 +1,219 @@
+//! Detection of system runtimes that installed packages suggest.
+//!
+//! Scoop manifests list runtimes such as the Visual C++ Redistributable or the .NET
+//! Desktop Runtime under `suggest`. Scoop only prints these once at install time, so
+//! this check re-reads the installed manifests and reports runtimes that are still
+//! missing.
+use super::checkup::CheckupItem;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// Runtimes with a system-wide install check.
+#[derive(Clone, Copy)]
+enum RuntimeKind {
+    VcRedist,
+    DotnetDesktop,
+    WebView2,
+}
+
+/// A runtime the doctor knows how to detect.
+struct Runtime {
+    kind: RuntimeKind,
+    /// Display name used in the checkup item.
+    name: &'static str,
+    /// Lowercase fragments matched against `suggest` keys and values.
+    patterns: &'static [&'static str],
+    /// Scoop packages that provide the runtime; the first is offered for install.
+    packages: &'static [&'static str],
+    /// Manual install hint when no Scoop package is available.
+    manual_hint: Option<&'static str>,
+}
+
+const RUNTIMES: &[Runtime] = &[
+    Runtime {
+        kind: RuntimeKind::VcRedist,
+        name: "Visual C++ Redistributable",
+        patterns: &["vcredist", "visual c++", "vc++"],
+        packages: &["vcredist-aio", "vcredist2022", "vcredist2019", "vcredist2015"],
+        manual_hint: None,
+    },
+    Runtime {
+        kind: RuntimeKind::DotnetDesktop,
+        name: ".NET Desktop Runtime",
+        patterns: &["windowsdesktop-runtime", ".net desktop", "dotnet-desktop"],
+        packages: &["windowsdesktop-runtime", "windowsdesktop-runtime-lts"],
+        manual_hint: None,
+    },
+    Runtime {
+        kind: RuntimeKind::WebView2,
+        name: "WebView2 Runtime",
+        patterns: &["webview2"],
+        packages: &[],
+        manual_hint: Some(
+            "Install the Evergreen WebView2 Runtime from https://developer.microsoft.com/microsoft-edge/webview2/",
+        ),
+    },
+];
+
+/// Collects the lowercase `suggest` keys and values of every installed manifest,
+/// grouped by package name.
+fn installed_suggestions(apps_path: &Path) -> Vec<(String, Vec<String>)> {
+    let Ok(entries) = fs::read_dir(apps_path) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let manifest_path = entry.path().join("current").join("manifest.json");
+            let manifest: Value =
+                serde_json::from_str(&fs::read_to_string(manifest_path).ok()?).ok()?;
+            let suggest = manifest.get("suggest")?.as_object()?;
+
+            let mut terms = Vec::new();
+            for (key, value) in suggest {
+                terms.push(key.to_lowercase());
+                match value {
+                    Value::String(s) => terms.push(s.to_lowercase()),
+                    Value::Array(list) => {
+                        terms.extend(list.iter().filter_map(Value::as_str).map(str::to_lowercase))
+                    }
+                    _ => {}
+                }
+            }
+            Some((entry.file_name().to_string_lossy().to_string(), terms))
+        })
+        .collect()
+}
+
+/// Returns `true` if the runtime is present, either via Scoop or system-wide.
+fn is_runtime_available(runtime: &Runtime, apps_path: &Path) -> bool {
+    let via_scoop = runtime
+        .packages
+        .iter()
+        .any(|package| apps_path.join(package).join("current").exists());
+    via_scoop || is_installed_system_wide(runtime.kind)
+}
+
+#[cfg(windows)]
+fn is_installed_system_wide(kind: RuntimeKind) -> bool {
+    use super::windows_checks;
+    match kind {
+        RuntimeKind::VcRedist => windows_checks::is_vcredist_installed(),
+        RuntimeKind::DotnetDesktop => windows_checks::is_dotnet_desktop_runtime_installed(),
+        RuntimeKind::WebView2 => windows_checks::is_webview2_installed(),
+    }
+}
+
+#[cfg(not(windows))]
+fn is_installed_system_wide(_kind: RuntimeKind) -> bool {
+    false
+}
+
+/// Reports each runtime suggested by at least one installed package.
+pub fn check_suggested_runtimes(apps_path: &Path) -> Vec<CheckupItem> {
+    let suggestions = installed_suggestions(apps_path);
+
+    RUNTIMES
+        .iter()
+        .filter_map(|runtime| {
+            let apps: BTreeSet<&str> = suggestions
+                .iter()
+                .filter(|(_, terms)| {
+                    terms
+                        .iter()
+                        .any(|term| runtime.patterns.iter().any(|p| term.contains(p)))
+                })
+                .map(|(app, _)| app.as_str())
+                .collect();
+            if apps.is_empty() {
+                return None;
+            }
+
+            let status = is_runtime_available(runtime, apps_path);
+            let install_package = runtime.packages.first().copied();
+            let suggestion = match (install_package, runtime.manual_hint) {
+                _ if status => None,
+                (Some(package), _) => Some(format!(
+                    "Required by installed apps. Install it from the extras bucket with: scoop install {}",
+                    package
+                )),
+                (None, Some(hint)) => Some(hint.to_string()),
+                (None, None) => None,
+            };
+
+            Some(CheckupItem {
+                id: if status {
+                    None
+                } else {
+                    install_package.map(String::from)
+                },
+                status,
+                key: "runtimeInstalled".to_string(),
+                params: Some(serde_json::json!({
+                    "name": runtime.name,
+                    "apps": apps.into_iter().collect::<Vec<_>>().join(", "),
+                })),
+                suggestion,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScoopFixture;
+
+    fn set_suggest(fixture: &ScoopFixture, package: &str, suggest: Value) {
+        fixture.install(package, "1.0", "extras");
+        fixture.installed_manifest(
+            package,
+            "1.0",
+            serde_json::json!({ "version": "1.0", "suggest": suggest }),
+        );
+    }
+
+    #[test]
+    fn reports_only_suggested_runtimes() {
+        let fixture = ScoopFixture::new();
+        set_suggest(
+            &fixture,
+            "game",
+            serde_json::json!({ "vcredist": "extras/vcredist2022" }),
+        );
+        set_suggest(
+            &fixture,
+            "editor",
+            serde_json::json!({ "Visual C++ Redistributable": ["extras/vcredist-aio"] }),
+        );
+        if !fixture.app_dir("game").join("current").exists() {
+            return;
+        }
+
+        let items = check_suggested_runtimes(&fixture.apps_dir());
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].params.as_ref().unwrap()["apps"], "editor, game");
+    }
+
+    #[test]
+    fn runtime_installed_through_scoop_passes() {
+        let fixture = ScoopFixture::new();
+        set_suggest(
+            &fixture,
+            "tool",
+            serde_json::json!({ ".NET Desktop Runtime": "extras/windowsdesktop-runtime" }),
+        );
+        fixture.install("windowsdesktop-runtime", "8.0.1", "extras");
+        if !fixture.app_dir("tool").join("current").exists() {
+            return;
+        }
+
+        let items = check_suggested_runtimes(&fixture.apps_dir());
+        assert_eq!(items.len(), 1);
+        assert!(items[0].status);
+        assert!(items[0].id.is_none());
+    }
+}
@@ -1,5 +1,6 @@
 //! Commands for managing Scoop shims.
 use crate::state::AppState;
+use crate::utils::ScoopDirs;
 use once_cell::sync::Lazy;
 use rayon::prelude::*;
 use regex::Regex;
@@ -120,10 +121,10 @@ fn process_shim_dir(dir: &Path, is_global: bool) -> Result<Vec<Shim>, String> {
 #[tauri::command]
 pub fn list_shims(state: State<'_, AppState>) -> Result<Vec<Shim>, String> {
     log::info!("Listing shims from filesystem");
-    let scoop_path = state.scoop_path();
+    let dirs = state.scoop_dirs();
 
-    let local_shims = process_shim_dir(&scoop_path.join("shims"), false)?;
-    let global_shims = process_shim_dir(&scoop_path.join("global").join("shims"), true)?;
+    let local_shims = process_shim_dir(&dirs.shims, false)?;
+    let global_shims = process_shim_dir(&dirs.global_shims, true)?;
 
     let mut shim_set: HashSet<Shim> = local_shims.into_iter().collect();
     shim_set.extend(global_shims);
@@ -138,7 +139,7 @@ pub fn list_shims(state: State<'_, AppState>) -> Result<Vec<Shim>, String> {
 #[tauri::command]
 pub fn alter_shim(state: State<'_, AppState>, shim_name: String) -> Result<(), String> {
     log::info!("Altering shim '{}' on filesystem", shim_name);
-    let scoop_path = state.scoop_path();
+    let dirs = state.scoop_dirs();
 
     let attempt_rename = |dir: &Path| -> Result<bool, String> {
         if !dir.is_dir() {
@@ -158,8 +159,7 @@ pub fn alter_shim(state: State<'_, AppState>, shim_name: String) -> Result<(), S
         }
     };
 
-    let was_altered = attempt_rename(&scoop_path.join("shims"))?
-        || attempt_rename(&scoop_path.join("global").join("shims"))?;
+    let was_altered = attempt_rename(&dirs.shims)? || attempt_rename(&dirs.global_shims)?;
 
     if was_altered {
         Ok(())
@@ -172,12 +172,9 @@ pub fn alter_shim(state: State<'_, AppState>, shim_name: String) -> Result<(), S
 }
 
 /// Finds all files associated with a given shim name in both local and global paths.
-fn find_shim_files(scoop_path: &Path, shim_name: &str) -> Result<Vec<PathBuf>, String> {
+fn find_shim_files(dirs: &ScoopDirs, shim_name: &str) -> Result<Vec<PathBuf>, String> {
     let mut files = Vec::new();
-    let shim_dirs = [
-        scoop_path.join("shims"),
-        scoop_path.join("global").join("shims"),
-    ];
+    let shim_dirs = [&dirs.shims, &dirs.global_shims];
 
     for dir in shim_dirs.iter().filter(|d| d.is_dir()) {
         let entries = fs::read_dir(dir).map_err(|e| e.to_string())?;
@@ -194,9 +191,7 @@ fn find_shim_files(scoop_path: &Path, shim_name: &str) -> Result<Vec<PathBuf>, S
 #[tauri::command]
 pub fn remove_shim(state: State<'_, AppState>, shim_name: String) -> Result<(), String> {
     log::info!("Removing shim '{}' from filesystem", shim_name);
-    let scoop_path = state.scoop_path();
-
-    let files_to_remove = find_shim_files(&scoop_path, &shim_name)?;
+    let files_to_remove = find_shim_files(&state.scoop_dirs(), &shim_name)?;
 
     if files_to_remove.is_empty() {
         return Err(format!("Shim '{}' not found.", shim_name));
@@ -211,24 +206,17 @@ pub fn remove_shim(state: State<'_, AppState>, shim_name: String) -> Result<(),
     Ok(())
 }
 
-/// Adds a new shim for a given executable path.
-#[tauri::command]
-pub fn add_shim(state: State<'_, AppState>, args: AddShimArgs) -> Result<(), String> {
-    log::info!("Adding shim '{}' for path '{}'", args.name, args.path);
-    let scoop_path = state.scoop_path();
-
-    let shims_dir = if args.global {
-        scoop_path.join("global").join("shims")
-    } else {
-        scoop_path.join("shims")
-    };
-
-    fs::create_dir_all(&shims_dir)
-        .map_err(|e| format!("Failed to create shims directory: {}", e))?;
-
-    let shim_file_path = shims_dir.join(format!("{}.shim", args.name));
-    let mut shim_content = format!("path = \"{}\"\n", args.path.replace('\\', "\\\\"));
-    if let Some(shim_args) = &args.args {
+/// Writes `<name>.shim` and a copy of Scoop's `shim.exe` into `shims_dir`.
+pub fn write_shim(
+    dirs: &ScoopDirs,
+    shims_dir: &Path,
+    name: &str,
+    target: &str,
+    args: Option<&str>,
+) -> Result<(), String> {
+    let shim_file_path = shims_dir.join(format!("{}.shim", name));
+    let mut shim_content = format!("path = \"{}\"\n", target.replace('\\', "\\\\"));
+    if let Some(shim_args) = args {
         if !shim_args.is_empty() {
             shim_content.push_str(&format!("args = {}", shim_args));
         }
@@ -236,15 +224,35 @@ pub fn add_shim(state: State<'_, AppState>, args: AddShimArgs) -> Result<(), Str
     fs::write(&shim_file_path, shim_content)
         .map_err(|e| format!("Failed to write .shim file: {}", e))?;
 
-    let shim_template_path = scoop_path.join("apps/scoop/current/shim.exe");
+    let shim_template_path = dirs.apps.join("scoop").join("current").join("shim.exe");
     if !shim_template_path.exists() {
         return Err(
             "Scoop's shim.exe template not found. Is Scoop installed correctly?".to_string(),
         );
     }
-    let new_shim_exe_path = shims_dir.join(format!("{}.exe", args.name));
+    let new_shim_exe_path = shims_dir.join(format!("{}.exe", name));
     fs::copy(&shim_template_path, &new_shim_exe_path)
         .map_err(|e| format!("Failed to copy shim executable: {}", e))?;
 
     Ok(())
 }
+
+/// Adds a new shim for a given executable path.
+#[tauri::command]
+pub fn add_shim(state: State<'_, AppState>, args: AddShimArgs) -> Result<(), String> {
+    log::info!("Adding shim '{}' for path '{}'", args.name, args.path);
+    let dirs = state.scoop_dirs();
+
+    let shims_dir = if args.global {
+        dirs.global_shims.clone()
+    } else {
+        dirs.shims.clone()
+    };
+
+    fs::create_dir_all(&shims_dir)
+        .map_err(|e| format!("Failed to create shims directory: {}", e))?;
+
+    write_shim(&dirs, &shims_dir, &args.name, &args.path, args.args.as_deref())?;
+
+    Ok(())
+}
@@ -0,0 +1,260 @@
+//! Checks that the Scoop shims directory is on `PATH` and not shadowed.
+//!
+//! Without the shims entry every installed command-line tool fails to start, and a
+//! directory earlier on `PATH` that ships the same executable (Git for Windows, a
+//! system Node.js, ...) silently wins over the Scoop version. Windows searches the
+//! machine `PATH` before the user `PATH`.
+use super::checkup::CheckupItem;
+use crate::commands::env_changes::{self, broadcast_change, write_value};
+use crate::state::AppState;
+use crate::utils::ScoopDirs;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Executable extensions a shim can shadow or be shadowed by.
+const EXECUTABLE_EXTENSIONS: &[&str] = &["exe", "cmd", "bat", "ps1"];
+
+/// Where the shims directory stands on the effective `PATH`.
+#[derive(Debug, Default, PartialEq)]
+pub struct ShimsPathStatus {
+    pub on_path: bool,
+    /// Directories before the shims entry that contain a same-named executable.
+    pub conflicts: Vec<String>,
+    /// Moving the entry to the front of the user `PATH` would resolve the problem.
+    pub fixable: bool,
+}
+
+/// Expands `%NAME%` references the way the registry's `REG_EXPAND_SZ` values do.
+fn expand_vars(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('%') {
+        let Some(len) = rest[start + 1..].find('%') else {
+            break;
+        };
+        let name = &rest[start + 1..start + 1 + len];
+        out.push_str(&rest[..start]);
+        match std::env::var(name) {
+            Ok(expanded) if !name.is_empty() => out.push_str(&expanded),
+            _ => out.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The `Path` variable of a scope, whatever its capitalisation.
+fn path_var(vars: &BTreeMap<String, String>) -> Option<(&String, &String)> {
+    vars.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("Path"))
+}
+
+fn split_path(var: Option<(&String, &String)>) -> Vec<String> {
+    var.map(|(_, v)| {
+        v.split(';')
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .map(String::from)
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+fn same_dir(entry: &str, dir: &Path) -> bool {
+    expand_vars(entry)
+        .trim_end_matches(['\\', '/'])
+        .eq_ignore_ascii_case(dir.to_string_lossy().trim_end_matches(['\\', '/']))
+}
+
+/// Names of the commands provided by the shims directory, lowercase.
+fn shim_names(shims: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(shims)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.extension()
+                        .and_then(|e| e.to_str())
+                        .is_some_and(|e| EXECUTABLE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+                })
+                .filter_map(|p| Some(p.file_stem()?.to_string_lossy().to_lowercase()))
+                .filter(|name| name != "scoop")
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn provides_any(dir: &Path, names: &[String]) -> bool {
+    names.iter().any(|name| {
+        EXECUTABLE_EXTENSIONS
+            .iter()
+            .any(|ext| dir.join(format!("{}.{}", name, ext)).is_file())
+    })
+}
+
+/// Evaluates the shims entry given the machine and user `PATH` entries.
+///
+/// `shadows` tells whether an earlier directory provides one of the shimmed commands.
+pub fn evaluate(
+    shims: &Path,
+    machine_path: &[String],
+    user_path: &[String],
+    shadows: impl Fn(&Path) -> bool,
+) -> ShimsPathStatus {
+    let effective: Vec<(bool, &String)> = machine_path
+        .iter()
+        .map(|e| (true, e))
+        .chain(user_path.iter().map(|e| (false, e)))
+        .collect();
+    let Some(position) = effective.iter().position(|(_, e)| same_dir(e, shims)) else {
+        return ShimsPathStatus {
+            on_path: false,
+            conflicts: Vec::new(),
+            fixable: true,
+        };
+    };
+
+    let conflicting: Vec<(bool, String)> = effective[..position]
+        .iter()
+        .map(|(machine, e)| (*machine, expand_vars(e)))
+        .filter(|(_, dir)| !same_dir(dir, shims) && shadows(Path::new(dir)))
+        .collect();
+    ShimsPathStatus {
+        on_path: true,
+        // A user entry can be moved ahead of; a machine entry cannot
+        fixable: !conflicting.is_empty() && conflicting.iter().all(|(machine, _)| !machine),
+        conflicts: conflicting.into_iter().map(|(_, dir)| dir).collect(),
+    }
+}
+
+fn current_status(shims: &Path) -> ShimsPathStatus {
+    let env = env_changes::capture();
+    let names = shim_names(shims);
+    evaluate(
+        shims,
+        &split_path(path_var(&env.machine)),
+        &split_path(path_var(&env.user)),
+        |dir| provides_any(dir, &names),
+    )
+}
+
+/// Builds the checkup items for the shims directory.
+pub fn check_shims_path(scoop_path: &Path) -> Vec<CheckupItem> {
+    // PATH is read from the registry
+    if cfg!(not(windows)) {
+        return Vec::new();
+    }
+    let shims = ScoopDirs::resolve(scoop_path).shims;
+    let status = current_status(&shims);
+    let shims_display = shims.display().to_string();
+
+    let mut items = vec![CheckupItem {
+        id: (!status.on_path).then(|| "shimsPath".to_string()),
+        status: status.on_path,
+        key: "shimsOnPath".to_string(),
+        params: Some(serde_json::json!({ "path": shims_display })),
+        suggestion: (!status.on_path).then(|| {
+            format!(
+                "Installed commands cannot be found. Add '{}' to your user PATH.",
+                shims_display
+            )
+        }),
+    }];
+    if status.on_path {
+        let ok = status.conflicts.is_empty();
+        items.push(CheckupItem {
+            id: status.fixable.then(|| "shimsPath".to_string()),
+            status: ok,
+            key: "shimsPathPriority".to_string(),
+            params: Some(serde_json::json!({ "conflicts": status.conflicts.join(", ") })),
+            suggestion: (!ok).then(|| {
+                if status.fixable {
+                    format!(
+                        "These directories shadow Scoop apps: {}. Move '{}' to the front of your user PATH.",
+                        status.conflicts.join(", "),
+                        shims_display
+                    )
+                } else {
+                    format!(
+                        "These directories on the system PATH shadow Scoop apps: {}. Remove the conflicting programs or their system PATH entries.",
+                        status.conflicts.join(", ")
+                    )
+                }
+            }),
+        });
+    }
+    items
+}
+
+/// Puts the shims directory at the front of the user `PATH`.
+#[tauri::command]
+pub fn fix_shims_path(state: State<'_, AppState>) -> Result<(), String> {
+    let shims: PathBuf = state.scoop_dirs().shims;
+    let env = env_changes::capture();
+    let user_path = path_var(&env.user);
+    let name = user_path
+        .map(|(name, _)| name.clone())
+        .unwrap_or_else(|| "Path".to_string());
+    let mut entries: Vec<String> = split_path(user_path)
+        .into_iter()
+        .filter(|e| !same_dir(e, &shims))
+        .collect();
+    entries.insert(0, shims.display().to_string());
+
+    write_value("user", &name, Some(&entries.join(";")))?;
+    broadcast_change();
+    log::info!("Moved {} to the front of the user PATH", shims.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn finds_missing_and_shadowed_shims() {
+        let shims = Path::new(r"C:\scoop\shims");
+        let git = |dir: &Path| dir == Path::new(r"C:\Program Files\Git\cmd");
+
+        let missing = evaluate(shims, &entries(&[r"C:\Windows"]), &[], git);
+        assert!(!missing.on_path && missing.fixable);
+
+        let shadowed_by_user = evaluate(
+            shims,
+            &entries(&[r"C:\Windows"]),
+            &entries(&[r"C:\Program Files\Git\cmd", r"C:\scoop\shims\"]),
+            git,
+        );
+        assert!(shadowed_by_user.on_path && shadowed_by_user.fixable);
+        assert_eq!(
+            shadowed_by_user.conflicts,
+            entries(&[r"C:\Program Files\Git\cmd"])
+        );
+
+        let shadowed_by_machine = evaluate(
+            shims,
+            &entries(&[r"C:\Program Files\Git\cmd"]),
+            &entries(&[r"C:\scoop\shims"]),
+            git,
+        );
+        assert!(!shadowed_by_machine.fixable);
+        assert_eq!(
+            evaluate(shims, &[], &entries(&[r"C:\scoop\shims"]), git),
+            ShimsPathStatus {
+                on_path: true,
+                conflicts: Vec::new(),
+                fixable: false,
+            }
+        );
+    }
+}
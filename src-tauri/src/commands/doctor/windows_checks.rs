@@ -146,3 +146,56 @@ pub fn check_scoop_on_ntfs(scoop_path: &Path) -> CheckupItem {
         },
     }
 }
+
+/// Checks for the Visual C++ 2015-2022 Redistributable matching the OS architecture.
+#[cfg(windows)]
+pub fn is_vcredist_installed() -> bool {
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let arch = if cfg!(target_arch = "aarch64") { "arm64" } else { "x64" };
+    [
+        format!(r"SOFTWARE\Microsoft\VisualStudio\14.0\VC\Runtimes\{}", arch),
+        format!(
+            r"SOFTWARE\WOW6432Node\Microsoft\VisualStudio\14.0\VC\Runtimes\{}",
+            arch
+        ),
+    ]
+    .iter()
+    .any(|path| {
+        hklm.open_subkey(path)
+            .and_then(|key| key.get_value::<u32, _>("Installed"))
+            .map_or(false, |v| v == 1)
+    })
+}
+
+/// Checks for any installed .NET Desktop Runtime (`Microsoft.WindowsDesktop.App`).
+#[cfg(windows)]
+pub fn is_dotnet_desktop_runtime_installed() -> bool {
+    let program_files =
+        std::env::var("ProgramFiles").unwrap_or_else(|_| r"C:\Program Files".to_string());
+    std::fs::read_dir(
+        Path::new(&program_files)
+            .join("dotnet")
+            .join("shared")
+            .join("Microsoft.WindowsDesktop.App"),
+    )
+    .map(|mut entries| entries.any(|e| e.map_or(false, |e| e.path().is_dir())))
+    .unwrap_or(false)
+}
+
+/// Checks for the Evergreen WebView2 Runtime, installed per machine or per user.
+#[cfg(windows)]
+pub fn is_webview2_installed() -> bool {
+    const CLIENT: &str = r"Microsoft\EdgeUpdate\Clients\{F3017226-FE2A-4295-8BDF-00C3A9A7E4C5}";
+    [
+        (HKEY_LOCAL_MACHINE, format!(r"SOFTWARE\WOW6432Node\{}", CLIENT)),
+        (HKEY_LOCAL_MACHINE, format!(r"SOFTWARE\{}", CLIENT)),
+        (HKEY_CURRENT_USER, format!(r"Software\{}", CLIENT)),
+    ]
+    .iter()
+    .any(|(hive, path)| {
+        RegKey::predef(*hive)
+            .open_subkey(path)
+            .and_then(|key| key.get_value::<String, _>("pv"))
+            .map_or(false, |version| !version.is_empty() && version != "0.0.0.0")
+    })
+}
@@ -0,0 +1,156 @@
+//! Environment changes made by Scoop operations.
+//!
+//! The persistent user and machine variables are read from the registry before and
+//! after every streamed operation, and the difference is stored in its operation log.
+//! [`revert_env_changes`] undoes them, e.g. when an uninstall left a `PATH` entry or
+//! variable behind.
+use crate::commands::operation_log::read_operation_summary;
+use crate::engine::env_diff::{revert_value, EnvSnapshot};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[cfg(windows)]
+use winreg::{enums::*, RegKey, RegValue};
+
+#[cfg(windows)]
+const MACHINE_ENV_KEY: &str = r"SYSTEM\CurrentControlSet\Control\Session Manager\Environment";
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvRevertResult {
+    /// `scope:name` of each variable that was restored.
+    pub reverted: Vec<String>,
+    /// `scope:name` and reason of each change left as is.
+    pub skipped: Vec<(String, String)>,
+}
+
+#[cfg(windows)]
+fn env_key(scope: &str, flags: u32) -> std::io::Result<RegKey> {
+    match scope {
+        "machine" => {
+            RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey_with_flags(MACHINE_ENV_KEY, flags)
+        }
+        _ => RegKey::predef(HKEY_CURRENT_USER).open_subkey_with_flags("Environment", flags),
+    }
+}
+
+#[cfg(windows)]
+fn read_scope(scope: &str) -> BTreeMap<String, String> {
+    use winreg::types::FromRegValue;
+
+    let Ok(key) = env_key(scope, KEY_READ) else {
+        return BTreeMap::new();
+    };
+    key.enum_values()
+        .flatten()
+        .filter_map(|(name, value)| String::from_reg_value(&value).ok().map(|v| (name, v)))
+        .collect()
+}
+
+#[cfg(not(windows))]
+fn read_scope(_scope: &str) -> BTreeMap<String, String> {
+    BTreeMap::new()
+}
+
+/// Reads the persistent user and machine variables.
+pub fn capture() -> EnvSnapshot {
+    EnvSnapshot {
+        user: read_scope("user"),
+        machine: read_scope("machine"),
+    }
+}
+
+/// Tells running programs, Explorer in particular, that the environment changed.
+#[cfg(windows)]
+pub fn broadcast_change() {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        SendMessageTimeoutW, HWND_BROADCAST, SMTO_ABORTIFHUNG, WM_SETTINGCHANGE,
+    };
+
+    let param: Vec<u16> = "Environment".encode_utf16().chain(Some(0)).collect();
+    let mut result = 0usize;
+    unsafe {
+        SendMessageTimeoutW(
+            HWND_BROADCAST,
+            WM_SETTINGCHANGE,
+            0,
+            param.as_ptr() as isize,
+            SMTO_ABORTIFHUNG,
+            5000,
+            &mut result,
+        );
+    }
+}
+
+#[cfg(not(windows))]
+pub fn broadcast_change() {}
+
+/// Sets or (with `None`) deletes a variable, keeping its registry value type.
+#[cfg(windows)]
+pub fn write_value(scope: &str, name: &str, value: Option<&str>) -> Result<(), String> {
+    let key = env_key(scope, KEY_READ | KEY_WRITE)
+        .map_err(|e| format!("Cannot open {} environment: {}", scope, e))?;
+    match value {
+        Some(value) => {
+            let vtype =
+                key.get_raw_value(name)
+                    .map(|v| v.vtype)
+                    .unwrap_or(if value.contains('%') {
+                        REG_EXPAND_SZ
+                    } else {
+                        REG_SZ
+                    });
+            let bytes = value
+                .encode_utf16()
+                .chain(Some(0))
+                .flat_map(u16::to_le_bytes)
+                .collect();
+            key.set_raw_value(name, &RegValue { bytes, vtype })
+        }
+        None => key.delete_value(name),
+    }
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(not(windows))]
+pub fn write_value(_scope: &str, _name: &str, _value: Option<&str>) -> Result<(), String> {
+    Err("Environment variables can only be changed on Windows".to_string())
+}
+
+/// Undoes the environment changes recorded in an operation log.
+///
+/// Changes that were already undone, or were modified again since, are skipped.
+/// Machine variables need the app to run elevated.
+#[tauri::command]
+pub fn revert_env_changes(log_id: String) -> Result<EnvRevertResult, String> {
+    let summary = read_operation_summary(&log_id)?;
+    let current = capture();
+    let mut result = EnvRevertResult::default();
+
+    for change in &summary.env_changes {
+        let label = format!("{}:{}", change.scope, change.name);
+        let scope_vars = if change.scope == "machine" {
+            &current.machine
+        } else {
+            &current.user
+        };
+        let value = scope_vars.get(&change.name).map(String::as_str);
+        match revert_value(change, value)
+            .and_then(|reverted| write_value(&change.scope, &change.name, reverted.as_deref()))
+        {
+            Ok(()) => {
+                log::info!("Reverted environment change {}", label);
+                result.reverted.push(label);
+            }
+            Err(reason) => {
+                log::warn!("Not reverting {}: {}", label, reason);
+                result.skipped.push((label, reason));
+            }
+        }
+    }
+
+    if !result.reverted.is_empty() {
+        broadcast_change();
+    }
+    Ok(result)
+}
@@ -0,0 +1,169 @@
+//! Exporting the installation as a `scoop export` file.
+//!
+//! The file reads back with `scoop import` and the onboarding import. With
+//! `embed_manifests` every app also carries the manifest of its installed version under
+//! `Manifest`, so the onboarding import can reinstall exactly that version after its
+//! bucket has moved on or dropped the manifest; `scoop import` ignores the extra field.
+use crate::commands::bucket::list_local_buckets;
+use crate::commands::installed::get_installed_packages;
+use crate::models::{BucketInfo, InstallScope, ScoopPackage};
+use crate::state::AppState;
+use crate::utils::{locate_package_manifest, ScoopDirs};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+fn read_json(path: &Path) -> Option<Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Builds the export document for `packages` and `buckets`.
+pub fn build_export(
+    dirs: &ScoopDirs,
+    packages: &[ScoopPackage],
+    buckets: &[BucketInfo],
+    embed_manifests: bool,
+) -> Value {
+    let buckets: Vec<Value> = buckets
+        .iter()
+        .map(|bucket| {
+            json!({
+                "Name": bucket.name,
+                "Source": bucket.git_url.clone().unwrap_or_else(|| bucket.path.clone()),
+                "Updated": bucket.last_updated,
+                "Manifests": bucket.manifest_count,
+            })
+        })
+        .collect();
+
+    let apps: Vec<Value> = packages
+        .iter()
+        .filter(|package| !package.broken)
+        .map(|package| {
+            let global = package.scope == InstallScope::Global;
+            let mut app = json!({
+                "Name": package.name,
+                "Version": package.version,
+                "Source": package.source,
+                "Updated": package.updated,
+                "Info": if global { "Global install" } else { "" },
+            });
+            if embed_manifests {
+                let apps_dir = if global {
+                    &dirs.global_apps
+                } else {
+                    &dirs.apps
+                };
+                let manifest = apps_dir
+                    .join(&package.name)
+                    .join("current")
+                    .join("manifest.json");
+                if let Some(manifest) = read_json(&manifest) {
+                    app["Manifest"] = manifest;
+                }
+            }
+            app
+        })
+        .collect();
+
+    json!({ "buckets": buckets, "apps": apps })
+}
+
+/// The embedded manifest to install an exported app from: set when no added bucket
+/// provides the exported version any more, so installing from the bucket would get
+/// another version or fail.
+pub fn pinned_manifest<'a>(
+    scoop_dir: &Path,
+    name: &str,
+    source: Option<&str>,
+    version: Option<&str>,
+    manifest: Option<&'a Value>,
+) -> Option<&'a Value> {
+    let manifest = manifest?;
+    let bucket_version = locate_package_manifest(scoop_dir, name, source.map(String::from))
+        .ok()
+        .and_then(|(path, _)| read_json(&path))
+        .and_then(|m| m.get("version").and_then(Value::as_str).map(String::from));
+    let exported_version = version.or_else(|| manifest.get("version").and_then(Value::as_str));
+    match (bucket_version, exported_version) {
+        (Some(bucket_version), Some(exported)) if bucket_version == exported => None,
+        _ => Some(manifest),
+    }
+}
+
+/// Writes the installed packages and added buckets to `path` in `scoop export` format,
+/// returning the number of apps written.
+#[tauri::command]
+pub async fn export_installed_packages(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    embed_manifests: Option<bool>,
+) -> Result<usize, String> {
+    let embed_manifests = embed_manifests.unwrap_or(false);
+    let packages = get_installed_packages(app, state.clone()).await?;
+    let dirs = state.scoop_dirs();
+    let buckets = list_local_buckets(&dirs.root)?;
+
+    let export = build_export(&dirs, &packages, &buckets, embed_manifests);
+    let count = export["apps"].as_array().map_or(0, Vec::len);
+    let content = serde_json::to_string_pretty(&export)
+        .map_err(|e| format!("Failed to serialize export: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    log::info!(
+        "Exported {} apps and {} buckets to {}{}",
+        count,
+        buckets.len(),
+        path,
+        if embed_manifests {
+            " with embedded manifests"
+        } else {
+            ""
+        }
+    );
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScoopFixture;
+
+    #[test]
+    fn embeds_manifests_and_pins_versions_the_bucket_lost() {
+        let fixture = ScoopFixture::new();
+        fixture
+            .install("git", "2.44.0", "main")
+            .bucket_manifest("main", "git", "2.45.0")
+            .bucket_manifest("main", "7zip", "24.09");
+        let dirs = ScoopDirs::resolve(fixture.root());
+        let package = ScoopPackage {
+            name: "git".to_string(),
+            version: "2.44.0".to_string(),
+            source: "main".to_string(),
+            ..Default::default()
+        };
+
+        let export = build_export(&dirs, &[package], &[], true);
+        let git = &export["apps"][0];
+        assert_eq!(git["Name"], "git");
+        assert_eq!(git["Manifest"]["version"], "2.44.0");
+
+        let manifest = &git["Manifest"];
+        let pinned = pinned_manifest(
+            fixture.root(),
+            "git",
+            Some("main"),
+            Some("2.44.0"),
+            Some(manifest),
+        );
+        assert_eq!(pinned, Some(manifest));
+        let current = json!({ "version": "24.09" });
+        assert_eq!(
+            pinned_manifest(fixture.root(), "7zip", Some("main"), None, Some(&current)),
+            None
+        );
+    }
+}
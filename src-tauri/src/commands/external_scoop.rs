@@ -0,0 +1,348 @@
+//! Detection of Scoop runs started outside the app.
+//!
+//! A `scoop update` in a terminal and an operation started here fight over the same
+//! junctions, shims and cache files. Before a Scoop operation starts, running `scoop`
+//! processes that were not spawned by the app, and lock files left by an in-progress
+//! git pull or download, are looked for. Depending on the `operations.externalScoopMode`
+//! setting the operation is refused (`warn`, the default), waits until they are gone
+//! (`wait`) or starts anyway (`off`).
+use crate::commands::settings;
+use crate::config_keys::OPERATIONS_EXTERNAL_SCOOP_MODE;
+use crate::state::AppState;
+use crate::utils::ScoopDirs;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Runtime, State};
+use tokio::sync::Mutex;
+
+/// Emitted with the [`ExternalScoopActivity`] while an operation waits for it to end.
+pub const EVENT_EXTERNAL_SCOOP_WAIT: &str = "external-scoop-wait";
+const WAIT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const MAX_WAIT: Duration = Duration::from_secs(30 * 60);
+/// Partial downloads untouched for longer than this are leftovers, not a running download.
+const STALE_DOWNLOAD_AGE: Duration = Duration::from_secs(10 * 60);
+/// Git holds `index.lock` for seconds; older ones were left by a git that crashed.
+const STALE_GIT_LOCK_AGE: Duration = Duration::from_secs(5 * 60);
+/// How long a process listing is reused by the operations that follow it.
+const PROCESS_LIST_TTL: Duration = Duration::from_secs(10);
+
+/// The last process listing and when it was taken.
+static PROCESS_LIST: Lazy<Mutex<Option<(Instant, Vec<ScoopProcess>)>>> =
+    Lazy::new(|| Mutex::new(None));
+
+static SCOOP_COMMAND_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)(^|[\s\\/&;'\x22])scoop(\.ps1|\.cmd)?($|[\s'\x22])").unwrap());
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoopProcess {
+    pub pid: u32,
+    pub parent_pid: u32,
+    pub name: String,
+    pub command_line: String,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalScoopActivity {
+    pub processes: Vec<ScoopProcess>,
+    pub lock_files: Vec<String>,
+}
+
+impl ExternalScoopActivity {
+    pub fn is_busy(&self) -> bool {
+        !self.processes.is_empty() || !self.lock_files.is_empty()
+    }
+
+    fn describe(&self) -> String {
+        let mut parts: Vec<String> = self
+            .processes
+            .iter()
+            .map(|p| format!("{} (pid {})", p.name, p.pid))
+            .collect();
+        parts.extend(self.lock_files.iter().cloned());
+        parts.join(", ")
+    }
+}
+
+/// How operations react to an external Scoop run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExternalScoopMode {
+    Warn,
+    Wait,
+    Off,
+}
+
+fn read_mode<R: Runtime>(app: &AppHandle<R>) -> ExternalScoopMode {
+    let key = OPERATIONS_EXTERNAL_SCOOP_MODE.to_string();
+    let value = settings::get_config_value(app.clone(), key).ok().flatten();
+    match value.as_ref().and_then(|v| v.as_str()) {
+        Some("wait") => ExternalScoopMode::Wait,
+        Some("off") => ExternalScoopMode::Off,
+        _ => ExternalScoopMode::Warn,
+    }
+}
+
+/// Picks the processes running Scoop that are not descendants of `own_pid`.
+pub fn find_external_scoop(processes: &[ScoopProcess], own_pid: u32) -> Vec<ScoopProcess> {
+    let parents: HashMap<u32, u32> = processes.iter().map(|p| (p.pid, p.parent_pid)).collect();
+    let is_own = |pid: u32| {
+        let mut current = pid;
+        // Bounded walk, parent ids can form cycles after pid reuse
+        for _ in 0..32 {
+            match parents.get(&current) {
+                Some(&parent) if parent == own_pid => return true,
+                Some(&parent) => current = parent,
+                None => return false,
+            }
+        }
+        false
+    };
+
+    processes
+        .iter()
+        .filter(|p| SCOOP_COMMAND_RE.is_match(&p.command_line) && !is_own(p.pid))
+        .cloned()
+        .collect()
+}
+
+#[cfg(windows)]
+async fn list_shell_processes() -> Vec<ScoopProcess> {
+    use crate::commands::powershell::create_powershell_command;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct CimProcess {
+        process_id: u32,
+        parent_process_id: u32,
+        name: String,
+        command_line: Option<String>,
+    }
+
+    let query = "Get-CimInstance Win32_Process -Filter \"Name='powershell.exe' OR Name='pwsh.exe' OR Name='cmd.exe'\" | Select-Object ProcessId,ParentProcessId,Name,CommandLine | ConvertTo-Json -Compress";
+    let output = match create_powershell_command(query).output().await {
+        Ok(output) => output,
+        Err(e) => {
+            log::warn!("Failed to list PowerShell processes: {}", e);
+            return Vec::new();
+        }
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let value: serde_json::Value = serde_json::from_str(stdout.trim()).unwrap_or_default();
+    // A single result is not wrapped in an array
+    let items = match value {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Null => Vec::new(),
+        other => vec![other],
+    };
+    items
+        .into_iter()
+        .filter_map(|item| serde_json::from_value::<CimProcess>(item).ok())
+        .map(|p| ScoopProcess {
+            pid: p.process_id,
+            parent_pid: p.parent_process_id,
+            name: p.name,
+            command_line: p.command_line.unwrap_or_default(),
+        })
+        .collect()
+}
+
+#[cfg(not(windows))]
+async fn list_shell_processes() -> Vec<ScoopProcess> {
+    Vec::new()
+}
+
+/// The shell processes, listed again only when the last listing is older than `max_age`.
+/// Listing through CIM takes a PowerShell start, too slow to repeat for every package
+/// of a batch.
+async fn cached_shell_processes(max_age: Duration) -> Vec<ScoopProcess> {
+    let mut cached = PROCESS_LIST.lock().await;
+    if let Some((taken_at, processes)) = cached.as_ref() {
+        if taken_at.elapsed() < max_age {
+            return processes.clone();
+        }
+    }
+    let processes = list_shell_processes().await;
+    *cached = Some((Instant::now(), processes.clone()));
+    processes
+}
+
+fn modified_within(path: &Path, max_age: Duration) -> bool {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age < max_age)
+}
+
+/// Recent git index locks in the Scoop and bucket repositories and partial downloads in
+/// the cache. Older ones are leftovers that would otherwise block every operation.
+fn find_lock_files(dirs: &ScoopDirs) -> Vec<String> {
+    let mut repos: Vec<PathBuf> = vec![dirs.apps.join("scoop").join("current")];
+    if let Ok(entries) = std::fs::read_dir(&dirs.buckets) {
+        repos.extend(entries.flatten().map(|e| e.path()));
+    }
+    let mut locks: Vec<PathBuf> = repos
+        .into_iter()
+        .map(|repo| repo.join(".git").join("index.lock"))
+        .filter(|lock| lock.is_file())
+        .filter(|lock| {
+            let recent = modified_within(lock, STALE_GIT_LOCK_AGE);
+            if !recent {
+                log::info!("Ignoring stale git lock {}", lock.display());
+            }
+            recent
+        })
+        .collect();
+
+    if let Ok(entries) = std::fs::read_dir(&dirs.cache) {
+        locks.extend(
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.extension()
+                        .is_some_and(|ext| ext == "download" || ext == "aria2")
+                })
+                .filter(|p| modified_within(p, STALE_DOWNLOAD_AGE)),
+        );
+    }
+    locks.iter().map(|p| p.display().to_string()).collect()
+}
+
+/// Looks for Scoop runs that were not started by the app, reusing a process listing up
+/// to `max_age` old.
+async fn detect_with_max_age(scoop_path: &Path, max_age: Duration) -> ExternalScoopActivity {
+    let dirs = ScoopDirs::resolve(scoop_path);
+    let lock_files = tokio::task::spawn_blocking(move || find_lock_files(&dirs))
+        .await
+        .unwrap_or_default();
+    let processes = find_external_scoop(&cached_shell_processes(max_age).await, std::process::id());
+    ExternalScoopActivity {
+        processes,
+        lock_files,
+    }
+}
+
+/// Looks for Scoop runs that were not started by the app.
+pub async fn detect_external_scoop(scoop_path: &Path) -> ExternalScoopActivity {
+    detect_with_max_age(scoop_path, PROCESS_LIST_TTL).await
+}
+
+/// Checks for an external Scoop run before an operation, according to the settings.
+///
+/// Returns an error when the operation should not start.
+pub async fn guard_external_scoop<R: Runtime>(
+    app: &AppHandle<R>,
+    scoop_path: &Path,
+) -> Result<(), String> {
+    let mode = read_mode(app);
+    if mode == ExternalScoopMode::Off {
+        return Ok(());
+    }
+
+    let started = Instant::now();
+    // While waiting, every poll has to see the processes as they are now
+    let mut max_age = PROCESS_LIST_TTL;
+    loop {
+        let activity = detect_with_max_age(scoop_path, max_age).await;
+        if !activity.is_busy() {
+            return Ok(());
+        }
+        if mode == ExternalScoopMode::Warn {
+            return Err(format!(
+                "Scoop is already running outside the app: {}. Wait for it to finish and try again.",
+                activity.describe()
+            ));
+        }
+        if started.elapsed() >= MAX_WAIT {
+            return Err(format!(
+                "Gave up waiting for Scoop running outside the app: {}",
+                activity.describe()
+            ));
+        }
+
+        log::info!(
+            "Waiting for external Scoop run to finish: {}",
+            activity.describe()
+        );
+        if let Err(e) = app.emit(EVENT_EXTERNAL_SCOOP_WAIT, &activity) {
+            log::warn!("Failed to emit {} event: {}", EVENT_EXTERNAL_SCOOP_WAIT, e);
+        }
+        tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+        max_age = Duration::ZERO;
+    }
+}
+
+/// Reports Scoop runs outside the app, so the UI can warn before starting an operation.
+#[tauri::command]
+pub async fn check_external_scoop(
+    state: State<'_, AppState>,
+) -> Result<ExternalScoopActivity, String> {
+    Ok(detect_external_scoop(&state.scoop_path()).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, parent_pid: u32, command_line: &str) -> ScoopProcess {
+        ScoopProcess {
+            pid,
+            parent_pid,
+            name: "pwsh.exe".to_string(),
+            command_line: command_line.to_string(),
+        }
+    }
+
+    #[test]
+    fn ignores_own_and_unrelated_processes() {
+        let own = 100;
+        let processes = [
+            process(200, own, "pwsh -NoProfile -Command ...; scoop update git"),
+            process(
+                201,
+                200,
+                r"powershell -File C:\scoop\shims\scoop.ps1 update",
+            ),
+            process(
+                300,
+                1,
+                r"powershell -File C:\Users\me\scoop\shims\scoop.ps1 update *",
+            ),
+            process(301, 1, r"cmd /c C:\Users\me\scoop\shims\scoop.cmd status"),
+            process(400, 1, "pwsh -Command Get-ChildItem scoopy"),
+        ];
+        let pids: Vec<u32> = find_external_scoop(&processes, own)
+            .iter()
+            .map(|p| p.pid)
+            .collect();
+        assert_eq!(pids, vec![300, 301]);
+    }
+
+    #[test]
+    fn ignores_stale_git_locks() {
+        let fixture = crate::test_support::ScoopFixture::new();
+        let git_lock = |bucket: &str| {
+            let lock = fixture.bucket_dir(bucket).join(".git").join("index.lock");
+            crate::test_support::write_file(&lock, b"");
+            lock
+        };
+        let fresh = git_lock("main");
+        let stale = git_lock("extras");
+        std::fs::File::options()
+            .write(true)
+            .open(&stale)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(60 * 60))
+            .unwrap();
+
+        let locks = find_lock_files(&ScoopDirs::resolve(fixture.root()));
+        assert_eq!(locks, vec![fresh.display().to_string()]);
+    }
+}
@@ -0,0 +1,349 @@
+//! Command for computing a combined health record for installed Scoop packages.
+//!
+//! Aggregates the signals the UI would otherwise have to collect from several
+//! commands (status, shims, cache, VirusTotal) into a single structure per package.
+use crate::commands::installed::get_installed_packages;
+use crate::commands::quarantine::is_quarantined;
+use crate::commands::settings;
+use crate::models::ScoopPackage;
+use crate::state::AppState;
+use crate::utils::{locate_package_manifest, ScoopDirs};
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Runtime, State};
+
+/// Store key holding the VirusTotal detections recorded by `scan_package`.
+pub const VIRUSTOTAL_DETECTIONS_KEY: &str = "virustotal.detections";
+
+/// Cache files of non-installed versions above this size are reported as stale.
+const STALE_CACHE_THRESHOLD_BYTES: u64 = 100 * 1024 * 1024;
+
+static SHIM_PATH_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"path\s*=\s*['"](.*?)['"]"#).unwrap());
+static SHIM_SOURCE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[\\/]apps[\\/]([^\\/]+)[\\/]").unwrap());
+
+/// Combined health information for a single installed package.
+#[derive(Serialize, Debug, Clone)]
+pub struct PackageHealth {
+    pub name: String,
+    pub installed_version: String,
+    pub latest_version: Option<String>,
+    /// Score from 0 (broken) to 100 (no issues detected).
+    pub score: u8,
+    /// One of `healthy`, `warning` or `critical`.
+    pub status: String,
+    pub is_outdated: bool,
+    pub is_deprecated: bool,
+    pub is_failed: bool,
+    pub is_manifest_missing: bool,
+    pub is_vulnerable: bool,
+    pub is_held: bool,
+    pub broken_shims: Vec<String>,
+    pub stale_cache_bytes: u64,
+    pub issues: Vec<String>,
+}
+
+impl PackageHealth {
+    fn new(name: String, installed_version: String) -> Self {
+        Self {
+            name,
+            installed_version,
+            latest_version: None,
+            score: 100,
+            status: "healthy".to_string(),
+            is_outdated: false,
+            is_deprecated: false,
+            is_failed: false,
+            is_manifest_missing: false,
+            is_vulnerable: false,
+            is_held: false,
+            broken_shims: Vec::new(),
+            stale_cache_bytes: 0,
+            issues: Vec::new(),
+        }
+    }
+
+    /// Derives the score and status from the individual signals.
+    fn finalize(mut self) -> Self {
+        let mut penalty: u8 = 0;
+        if self.is_failed {
+            penalty += 50;
+        }
+        if self.is_vulnerable {
+            penalty += 40;
+        }
+        if self.is_manifest_missing {
+            penalty += 30;
+        }
+        if self.is_deprecated {
+            penalty += 20;
+        }
+        if !self.broken_shims.is_empty() {
+            penalty += 15;
+        }
+        if self.is_outdated && !self.is_held {
+            penalty += 10;
+        }
+        if self.stale_cache_bytes > STALE_CACHE_THRESHOLD_BYTES {
+            penalty += 5;
+        }
+
+        self.score = 100u8.saturating_sub(penalty);
+        self.status = match self.score {
+            80..=100 => "healthy",
+            50..=79 => "warning",
+            _ => "critical",
+        }
+        .to_string();
+        self
+    }
+}
+
+/// Collects shims whose target executable no longer exists, grouped by owning package.
+fn collect_broken_shims(scoop_path: &Path) -> HashMap<String, Vec<String>> {
+    let mut broken: HashMap<String, Vec<String>> = HashMap::new();
+
+    let entries = match fs::read_dir(ScoopDirs::resolve(scoop_path).shims) {
+        Ok(entries) => entries,
+        Err(_) => return broken,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("shim") {
+            continue;
+        }
+
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        let target = match SHIM_PATH_RE.captures(&content).and_then(|c| c.get(1)) {
+            Some(m) => m.as_str().to_string(),
+            None => continue,
+        };
+
+        let owner = match SHIM_SOURCE_RE.captures(&target).and_then(|c| c.get(1)) {
+            Some(m) => m.as_str().to_lowercase(),
+            None => continue,
+        };
+
+        if !Path::new(&target).exists() {
+            let shim_name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            broken.entry(owner).or_default().push(shim_name);
+        }
+    }
+
+    broken
+}
+
+/// Sums the size of cached downloads that do not belong to the installed version.
+fn collect_stale_cache(
+    scoop_path: &Path,
+    installed: &HashMap<String, String>,
+) -> HashMap<String, u64> {
+    let mut stale: HashMap<String, u64> = HashMap::new();
+
+    let entries = match fs::read_dir(ScoopDirs::resolve(scoop_path).cache) {
+        Ok(entries) => entries,
+        Err(_) => return stale,
+    };
+
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let parts: Vec<&str> = file_name.split('#').collect();
+        if parts.len() < 2 {
+            continue;
+        }
+
+        let name = parts[0].to_lowercase();
+        if installed.get(&name).map(|v| v == parts[1]).unwrap_or(false) {
+            continue;
+        }
+
+        if let Ok(metadata) = entry.metadata() {
+            if metadata.is_file() {
+                *stale.entry(name).or_insert(0) += metadata.len();
+            }
+        }
+    }
+
+    stale
+}
+
+/// Reads the bucket manifest for an installed package and records outdated, deprecated
+/// and missing manifest signals.
+fn check_manifest(scoop_path: &Path, package: &ScoopPackage, health: &mut PackageHealth) {
+    match locate_package_manifest(scoop_path, &package.name, Some(package.source.clone())) {
+        Ok((manifest_path, bucket))
+            if !bucket.ends_with("(missing)") && !bucket.contains("Bucket missing") =>
+        {
+            let manifest = fs::read_to_string(&manifest_path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<Value>(&content).ok());
+
+            let manifest = match manifest {
+                Some(manifest) => manifest,
+                None => {
+                    health.is_failed = true;
+                    health
+                        .issues
+                        .push("Manifest could not be parsed".to_string());
+                    return;
+                }
+            };
+
+            if let Some(version) = manifest.get("version").and_then(Value::as_str) {
+                health.latest_version = Some(version.to_string());
+                if !package.is_versioned_install && version != package.version {
+                    health.is_outdated = true;
+                    health.issues.push(format!("Update available: {}", version));
+                }
+            }
+
+            let in_deprecated_dir = manifest_path
+                .components()
+                .any(|c| c.as_os_str().eq_ignore_ascii_case("deprecated"));
+            if manifest.get("deprecated").is_some() || in_deprecated_dir {
+                health.is_deprecated = true;
+                health.issues.push("Deprecated".to_string());
+            }
+        }
+        _ => {
+            health.is_manifest_missing = true;
+            health
+                .issues
+                .push("Manifest removed from bucket".to_string());
+        }
+    }
+}
+
+/// Returns a combined health record for installed packages.
+///
+/// When `package_name` is provided only that package is returned.
+#[tauri::command]
+pub async fn get_package_health<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+    package_name: Option<String>,
+) -> Result<Vec<PackageHealth>, String> {
+    log::info!("Computing package health (filter: {:?})", package_name);
+
+    let installed_packages = get_installed_packages(app.clone(), state.clone()).await?;
+    let scoop_path = state.scoop_path();
+
+    let held_packages: HashSet<String> =
+        crate::commands::hold::list_held_packages(app.clone(), state.clone())
+            .await?
+            .into_iter()
+            .collect();
+
+    let detections = settings::get_config_value(app, VIRUSTOTAL_DETECTIONS_KEY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_object().cloned())
+        .unwrap_or_default();
+
+    let filter = package_name.map(|n| n.to_lowercase());
+
+    let results = tokio::task::spawn_blocking(move || {
+        let installed_versions: HashMap<String, String> = installed_packages
+            .iter()
+            .map(|p| (p.name.to_lowercase(), p.version.clone()))
+            .collect();
+        let broken_shims = collect_broken_shims(&scoop_path);
+        let stale_cache = collect_stale_cache(&scoop_path, &installed_versions);
+
+        let matches_filter = |name: &str| {
+            filter
+                .as_ref()
+                .map(|f| f == &name.to_lowercase())
+                .unwrap_or(true)
+        };
+
+        let mut results: Vec<PackageHealth> = installed_packages
+            .par_iter()
+            .filter(|p| matches_filter(&p.name))
+            .map(|package| {
+                let key = package.name.to_lowercase();
+                let mut health = PackageHealth::new(package.name.clone(), package.version.clone());
+                health.is_held = held_packages.contains(&package.name);
+                if package.broken {
+                    health.is_failed = true;
+                    health.issues.push(
+                        package
+                            .broken_reason
+                            .clone()
+                            .unwrap_or_else(|| "Install metadata missing".to_string()),
+                    );
+                }
+
+                check_manifest(&scoop_path, package, &mut health);
+
+                if let Some(shims) = broken_shims.get(&key) {
+                    health.broken_shims = shims.clone();
+                    health
+                        .issues
+                        .push(format!("Broken shims: {}", shims.join(", ")));
+                }
+
+                if let Some(size) = stale_cache.get(&key) {
+                    health.stale_cache_bytes = *size;
+                    if *size > STALE_CACHE_THRESHOLD_BYTES {
+                        health.issues.push(format!(
+                            "Stale cache: {:.1} MB",
+                            *size as f64 / (1024.0 * 1024.0)
+                        ));
+                    }
+                }
+
+                let detected_version = detections
+                    .get(&package.name)
+                    .and_then(|d| d.get("version"))
+                    .and_then(Value::as_str);
+                if detected_version == Some(package.version.as_str()) {
+                    health.is_vulnerable = true;
+                    health
+                        .issues
+                        .push("VirusTotal detections for installed version".to_string());
+                }
+
+                health.finalize()
+            })
+            .collect();
+
+        // App directories that could not be loaded by the installed scan are failed installs.
+        if let Ok(entries) = fs::read_dir(scoop_path.join("apps")) {
+            for entry in entries.flatten() {
+                if !entry.path().is_dir() || is_quarantined(&entry.path()) {
+                    continue;
+                }
+                let name = entry.file_name().to_string_lossy().to_string();
+                if installed_versions.contains_key(&name.to_lowercase()) || !matches_filter(&name) {
+                    continue;
+                }
+                let mut health = PackageHealth::new(name, String::new());
+                health.is_failed = true;
+                health.issues.push(
+                    "Install failed or incomplete (missing manifest.json or install.json)"
+                        .to_string(),
+                );
+                results.push(health.finalize());
+            }
+        }
+
+        results.sort_by(|a, b| a.score.cmp(&b.score).then_with(|| a.name.cmp(&b.name)));
+        results
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    log::info!("Computed health for {} packages", results.len());
+    Ok(results)
+}
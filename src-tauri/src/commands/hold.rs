@@ -1,4 +1,7 @@
 //! Commands for holding and unholding Scoop packages.
+use crate::commands::package_state::emit_package_state;
+use crate::commands::resolver::resolve_installed_package;
+use crate::models::{PackageActivity, PackageState};
 use crate::state::AppState;
 use rayon::prelude::*;
 use serde_json::Value;
@@ -42,7 +45,7 @@ fn get_current_install_json_path(
 }
 
 /// Checks if a specific package is currently on hold.
-fn is_package_held(scoop_dir: &std::path::Path, package_name: &str) -> Result<bool, String> {
+pub fn is_package_held(scoop_dir: &std::path::Path, package_name: &str) -> Result<bool, String> {
     let install_json_path = get_current_install_json_path(scoop_dir, package_name)?;
     let content = fs::read_to_string(&install_json_path).map_err(|e| e.to_string())?;
     let value: Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
@@ -50,7 +53,7 @@ fn is_package_held(scoop_dir: &std::path::Path, package_name: &str) -> Result<bo
 }
 
 /// Modifies the hold status of a package by updating its `install.json`.
-fn modify_hold_status(scoop_dir: &Path, package_name: &str, hold: bool) -> Result<(), String> {
+pub fn modify_hold_status(scoop_dir: &Path, package_name: &str, hold: bool) -> Result<(), String> {
     let install_json_path = get_current_install_json_path(scoop_dir, package_name)?;
     let content = fs::read_to_string(&install_json_path).map_err(|e| e.to_string())?;
 
@@ -73,6 +76,33 @@ fn modify_hold_status(scoop_dir: &Path, package_name: &str, hold: bool) -> Resul
     }
 }
 
+/// Returns the names of the held packages among `app_dirs`.
+fn held_among(scoop_dir: &Path, app_dirs: &[fs::DirEntry]) -> Vec<String> {
+    app_dirs
+        .par_iter()
+        .filter_map(|entry| {
+            let package_name = entry.file_name().to_string_lossy().to_string();
+            match is_package_held(scoop_dir, &package_name) {
+                Ok(true) => Some(package_name),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Scans the apps directory for held packages.
+pub fn find_held_packages(scoop_dir: &Path) -> Vec<String> {
+    let app_dirs = fs::read_dir(scoop_dir.join("apps"))
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().is_dir())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    held_among(scoop_dir, &app_dirs)
+}
+
 /// Lists all packages that are currently on hold.
 /// Uses a memoized approach by checking the installed packages cache first,
 /// then only scanning directories if needed.
@@ -113,41 +143,56 @@ pub async fn list_held_packages<R: Runtime>(
         }
     }
 
-    let held_packages = app_dirs
-        .par_iter()
-        .filter_map(|entry| {
-            let package_name = entry.file_name().to_string_lossy().to_string();
-            match is_package_held(&scoop_path, &package_name) {
-                Ok(true) => Some(package_name),
-                _ => None,
-            }
-        })
-        .collect::<Vec<String>>();
+    let held_packages = held_among(&scoop_path, &app_dirs);
 
     log::info!("Found {} held packages", held_packages.len());
     Ok(held_packages)
 }
 
+/// Tells the UI about a hold change. Holds are read from disk, so nothing is stored,
+/// and a package with a queued, running or failed operation keeps reporting that, as
+/// `get_package_states` does.
+fn emit_hold_change<R: Runtime>(app: &AppHandle<R>, state: &AppState, name: String, held: bool) {
+    let package_state = state.package_state(&name).unwrap_or_else(|| PackageState {
+        name,
+        state: if held {
+            PackageActivity::Held
+        } else {
+            PackageActivity::Idle
+        },
+        operation_id: None,
+        message: None,
+        updated_at: chrono::Utc::now().timestamp_millis() as u64,
+    });
+    emit_package_state(app, &package_state);
+}
+
 /// Places a hold on a package to prevent it from being updated.
 #[tauri::command]
 pub async fn hold_package<R: Runtime>(
-    _app: AppHandle<R>,
+    app: AppHandle<R>,
     state: State<'_, AppState>,
     package_name: String,
 ) -> Result<(), String> {
     log::info!("Placing a hold on: {}", package_name);
     let scoop_path = state.scoop_path();
-    modify_hold_status(&scoop_path, &package_name, true)
+    let package_name = resolve_installed_package(&scoop_path, &package_name)?;
+    modify_hold_status(&scoop_path, &package_name, true)?;
+    emit_hold_change(&app, &state, package_name, true);
+    Ok(())
 }
 
 /// Removes the hold on a package, allowing it to be updated.
 #[tauri::command]
 pub async fn unhold_package<R: Runtime>(
-    _app: AppHandle<R>,
+    app: AppHandle<R>,
     state: State<'_, AppState>,
     package_name: String,
 ) -> Result<(), String> {
     log::info!("Removing hold from: {}", package_name);
     let scoop_path = state.scoop_path();
-    modify_hold_status(&scoop_path, &package_name, false)
+    let package_name = resolve_installed_package(&scoop_path, &package_name)?;
+    modify_hold_status(&scoop_path, &package_name, false)?;
+    emit_hold_change(&app, &state, package_name, false);
+    Ok(())
 }
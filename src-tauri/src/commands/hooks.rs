@@ -0,0 +1,327 @@
+//! User-configured programs run around Scoop operations.
+//!
+//! A hook is a PowerShell script or an executable run before or after installs,
+//! updates and uninstalls, e.g. to sync dotfiles after a tool updates. The package is
+//! passed in `PAILER_*` environment variables and the hook's output is streamed to the
+//! operation's window as it is printed and added to the operation's log. A failing
+//! `pre-*` hook cancels the operation; failing `post-*` hooks are only logged. Hooks
+//! run outside the operation queue, so a slow hook does not hold up other operations.
+use crate::commands::operation_log::{self, OperationLogLine};
+use crate::commands::powershell::{
+    create_powershell_command, quote_literal, StreamOutput, EVENT_OUTPUT,
+};
+use crate::commands::settings;
+use crate::config_keys::OPERATION_HOOKS;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+
+/// Hooks still running after this long are killed.
+const HOOK_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookEvent {
+    PreInstall,
+    PostInstall,
+    PreUpdate,
+    PostUpdate,
+    PreUpdateAll,
+    PostUpdateAll,
+    PreUninstall,
+    PostUninstall,
+}
+
+impl HookEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            HookEvent::PreInstall => "pre-install",
+            HookEvent::PostInstall => "post-install",
+            HookEvent::PreUpdate => "pre-update",
+            HookEvent::PostUpdate => "post-update",
+            HookEvent::PreUpdateAll => "pre-update-all",
+            HookEvent::PostUpdateAll => "post-update-all",
+            HookEvent::PreUninstall => "pre-uninstall",
+            HookEvent::PostUninstall => "post-uninstall",
+        }
+    }
+
+    fn is_pre(self) -> bool {
+        self.name().starts_with("pre-")
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationHook {
+    pub event: HookEvent,
+    /// A `.ps1` script or an executable.
+    pub path: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// What a hook is told about the operation it runs around.
+#[derive(Debug, Clone, Default)]
+pub struct HookContext {
+    pub package: Option<String>,
+    pub bucket: Option<String>,
+    /// The installed version: before the operation for `pre-*` hooks, after it for
+    /// `post-*` hooks.
+    pub version: Option<String>,
+    pub operation_id: Option<String>,
+}
+
+/// The environment variables a hook runs with.
+pub fn hook_env(event: HookEvent, context: &HookContext) -> Vec<(&'static str, String)> {
+    let mut env = vec![("PAILER_HOOK_EVENT", event.name().to_string())];
+    let optional = [
+        ("PAILER_PACKAGE", &context.package),
+        ("PAILER_BUCKET", &context.bucket),
+        ("PAILER_VERSION", &context.version),
+        ("PAILER_OPERATION_ID", &context.operation_id),
+    ];
+    env.extend(
+        optional
+            .into_iter()
+            .filter_map(|(name, value)| value.clone().map(|value| (name, value))),
+    );
+    env
+}
+
+/// Reads the saved hooks.
+pub fn load_hooks<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<OperationHook>, String> {
+    match settings::get_config_value(app.clone(), OPERATION_HOOKS.to_string())? {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| format!("Failed to read operation hooks: {}", e)),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn hook_command(hook: &OperationHook) -> Command {
+    let is_script = Path::new(&hook.path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ps1"));
+    if is_script {
+        let args: Vec<String> = hook.args.iter().map(|a| quote_literal(a)).collect();
+        return create_powershell_command(&format!(
+            "& {} {}",
+            quote_literal(&hook.path),
+            args.join(" ")
+        ));
+    }
+    let mut cmd = Command::new(&hook.path);
+    cmd.args(&hook.args)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    #[cfg(windows)]
+    cmd.creation_flags(0x0800_0000); // CREATE_NO_WINDOW
+    cmd
+}
+
+/// Sends each line `reader` produces to `sender`, tagged with `source`.
+fn forward_lines<T>(
+    reader: Option<T>,
+    source: &'static str,
+    sender: &mpsc::UnboundedSender<(String, &'static str)>,
+) where
+    T: AsyncRead + Unpin + Send + 'static,
+{
+    let Some(reader) = reader else {
+        return;
+    };
+    let sender = sender.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut reader = BufReader::new(reader);
+        let mut buffer = Vec::new();
+        while matches!(reader.read_until(b'\n', &mut buffer).await, Ok(n) if n > 0) {
+            let text = String::from_utf8_lossy(&buffer).trim_end().to_string();
+            buffer.clear();
+            if sender.send((text, source)).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Runs one hook, streaming each output line to `emit` as it is printed. Returns the
+/// output lines and whether the hook succeeded.
+async fn run_hook(
+    hook: &OperationHook,
+    event: HookEvent,
+    context: &HookContext,
+    emit: impl Fn(&OperationLogLine),
+) -> (Vec<OperationLogLine>, bool) {
+    let mut cmd = hook_command(hook);
+    cmd.envs(hook_env(event, context))
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+    let prefix = format!("[{} hook]", event.name());
+    let line = |text: &str, source: &str| OperationLogLine {
+        line: format!("{} {}", prefix, text),
+        source: source.to_string(),
+    };
+    let mut lines = Vec::new();
+    let finish = |lines: &mut Vec<OperationLogLine>, message: String| {
+        let failure = line(&message, "stderr");
+        emit(&failure);
+        lines.push(failure);
+    };
+
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            finish(&mut lines, format!("Failed to run {}: {}", hook.path, e));
+            return (lines, false);
+        }
+    };
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    forward_lines(child.stdout.take(), "stdout", &sender);
+    forward_lines(child.stderr.take(), "stderr", &sender);
+    drop(sender);
+
+    let finished = tokio::time::timeout(HOOK_TIMEOUT, async {
+        while let Some((text, source)) = receiver.recv().await {
+            if !text.trim().is_empty() {
+                let output = line(&text, source);
+                emit(&output);
+                lines.push(output);
+            }
+        }
+        child.wait().await
+    })
+    .await;
+
+    let success = match finished {
+        Ok(Ok(status)) if status.success() => true,
+        Ok(Ok(status)) => {
+            finish(&mut lines, format!("{} exited with {}", hook.path, status));
+            false
+        }
+        Ok(Err(e)) => {
+            finish(&mut lines, format!("Failed to run {}: {}", hook.path, e));
+            false
+        }
+        Err(_) => {
+            let _ = child.kill().await;
+            finish(
+                &mut lines,
+                format!("{} timed out after {}s", hook.path, HOOK_TIMEOUT.as_secs()),
+            );
+            false
+        }
+    };
+    (lines, success)
+}
+
+/// Runs the enabled hooks for `event` one after another, streaming their output to the
+/// operation's window. Returns their output for the operation log, or an error when a
+/// `pre-*` hook fails, which stops the remaining hooks.
+pub async fn run_hooks<R: Runtime>(
+    app: &AppHandle<R>,
+    event: HookEvent,
+    context: &HookContext,
+) -> Result<Vec<OperationLogLine>, String> {
+    let hooks = load_hooks(app).unwrap_or_else(|e| {
+        log::warn!("{}", e);
+        Vec::new()
+    });
+    let emit = |line: &OperationLogLine| {
+        let _ = app.emit(
+            EVENT_OUTPUT,
+            StreamOutput {
+                line: line.line.clone(),
+                source: line.source.clone(),
+                operation_id: context.operation_id.clone(),
+            },
+        );
+    };
+    let mut output = Vec::new();
+    for hook in hooks.iter().filter(|h| h.enabled && h.event == event) {
+        log::info!("Running {} hook {}", event.name(), hook.path);
+        let (lines, success) = run_hook(hook, event, context, emit).await;
+        output.extend(lines);
+        if !success {
+            log::warn!("{} hook {} failed", event.name(), hook.path);
+            if event.is_pre() {
+                return Err(format!(
+                    "The {} hook {} failed, so the operation was not started",
+                    event.name(),
+                    hook.path
+                ));
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// Adds hook output to the log of the operation it ran around, if it has one.
+pub fn record_hook_output(context: &HookContext, lines: &[OperationLogLine]) {
+    if let (Some(operation_id), false) = (&context.operation_id, lines.is_empty()) {
+        operation_log::append_to_operation(operation_id, lines);
+    }
+}
+
+#[tauri::command]
+pub fn get_operation_hooks(app: AppHandle) -> Result<Vec<OperationHook>, String> {
+    load_hooks(&app)
+}
+
+/// Replaces the operation hooks. Every hook must name an existing program by its full
+/// path.
+#[tauri::command]
+pub fn set_operation_hooks(app: AppHandle, hooks: Vec<OperationHook>) -> Result<(), String> {
+    for hook in &hooks {
+        let path = Path::new(&hook.path);
+        if !path.is_absolute() || !path.is_file() {
+            return Err(format!("Hook program not found: {}", hook.path));
+        }
+    }
+    let value = serde_json::to_value(&hooks)
+        .map_err(|e| format!("Failed to serialize operation hooks: {}", e))?;
+    settings::set_config_value(app, OPERATION_HOOKS.to_string(), value)?;
+    log::info!("Saved {} operation hook(s)", hooks.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_hooks_and_builds_their_environment() {
+        let hooks: Vec<OperationHook> = serde_json::from_value(json!([
+            { "event": "post-update-all", "path": "C:\\hooks\\sync.ps1" },
+            { "event": "pre-uninstall", "path": "C:\\hooks\\backup.exe", "args": ["--quiet"], "enabled": false }
+        ]))
+        .unwrap();
+        assert_eq!(hooks[0].event, HookEvent::PostUpdateAll);
+        assert!(hooks[0].enabled);
+        assert!(!hooks[1].enabled && hooks[1].event.is_pre());
+
+        let context = HookContext {
+            package: Some("git".to_string()),
+            version: Some("2.45.0".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            hook_env(HookEvent::PostUpdate, &context),
+            vec![
+                ("PAILER_HOOK_EVENT", "post-update".to_string()),
+                ("PAILER_PACKAGE", "git".to_string()),
+                ("PAILER_VERSION", "2.45.0".to_string()),
+            ]
+        );
+    }
+}
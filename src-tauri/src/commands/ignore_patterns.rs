@@ -0,0 +1,43 @@
+//! Commands for managing the patterns that hide packages from search and update checks.
+use crate::commands::settings;
+use crate::config_keys::SEARCH_IGNORE_PATTERNS;
+use crate::engine::ignore::IgnoreRules;
+use crate::models::IgnorePattern;
+use tauri::{AppHandle, Runtime};
+
+/// Reads the saved ignore patterns.
+pub fn load_ignore_patterns<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<IgnorePattern>, String> {
+    match settings::get_config_value(app.clone(), SEARCH_IGNORE_PATTERNS.to_string())? {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| format!("Failed to read ignore patterns: {}", e)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Compiles the saved ignore patterns; unreadable settings ignore nothing.
+pub fn load_ignore_rules<R: Runtime>(app: &AppHandle<R>) -> IgnoreRules {
+    load_ignore_patterns(app)
+        .and_then(|patterns| IgnoreRules::compile(&patterns))
+        .unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid ignore patterns: {}", e);
+            IgnoreRules::default()
+        })
+}
+
+#[tauri::command]
+pub fn get_ignore_patterns(app: AppHandle) -> Result<Vec<IgnorePattern>, String> {
+    load_ignore_patterns(&app)
+}
+
+/// Replaces the ignore patterns; they apply to the next search or update check.
+#[tauri::command]
+pub fn set_ignore_patterns(app: AppHandle, patterns: Vec<IgnorePattern>) -> Result<(), String> {
+    // Reject invalid patterns before they are persisted
+    IgnoreRules::compile(&patterns)?;
+
+    let value = serde_json::to_value(&patterns)
+        .map_err(|e| format!("Failed to serialize ignore patterns: {}", e))?;
+    settings::set_config_value(app, SEARCH_IGNORE_PATTERNS.to_string(), value)?;
+    log::info!("Saved {} ignore pattern(s)", patterns.len());
+    Ok(())
+}
@@ -1,10 +1,11 @@
 //! Command for fetching detailed information about a Scoop package.
+use crate::commands::{deprecation, package_source, resolver};
 use crate::state::AppState;
 use crate::utils;
 use serde::Serialize;
 use serde_json::Value;
 use std::fs;
-use tauri::State;
+use tauri::{AppHandle, Runtime, State};
 
 /// Represents the structured information for a Scoop package, suitable for frontend display.
 #[derive(Serialize, Debug, Clone, Default)]
@@ -13,6 +14,10 @@ pub struct ScoopInfo {
     pub details: Vec<(String, String)>,
     /// Optional installation notes provided by the package manifest.
     pub notes: Option<String>,
+    /// Whether the manifest is marked as deprecated.
+    pub is_deprecated: bool,
+    /// Successor package suggested by a deprecated manifest.
+    pub replacement: Option<String>,
 }
 
 /// Formats a JSON key for display, capitalizing it and handling special cases.
@@ -104,20 +109,23 @@ fn parse_manifest_details(json_value: &Value) -> (Vec<(String, String)>, Option<
 
 /// Fetches and formats information about a specific Scoop package.
 #[tauri::command]
-pub fn get_package_info(
+pub fn get_package_info<R: Runtime>(
+    app: AppHandle<R>,
     state: State<'_, AppState>,
     package_name: String,
 ) -> Result<ScoopInfo, String> {
     log::info!("Fetching info for package: {}", package_name);
 
     let scoop_dir = state.scoop_path();
+    let package_name = resolver::resolve_any_package(&scoop_dir, &package_name)?;
     
-    // Try to get bucket info from install.json first (for installed packages)
+    // Honor pinned, installed and preferred sources before searching all buckets
     let installed_bucket = get_installed_package_bucket(&scoop_dir, &package_name);
+    let selected_bucket = package_source::select_source(&app, &scoop_dir, &package_name)
+        .or_else(|| installed_bucket.clone());
     
-    // Use the installed bucket if available, otherwise search all buckets
-    let (manifest_path, bucket_name) = if let Some(ref bucket) = installed_bucket {
-        // Try to find the package manifest in the installed bucket first
+    let (manifest_path, bucket_name) = if let Some(ref bucket) = selected_bucket {
+        // Try to find the package manifest in the selected bucket first
         match utils::locate_package_manifest(&scoop_dir, &package_name, Some(bucket.clone())) {
             Ok(result) => result,
             // If not found in the installed bucket, fall back to searching all buckets
@@ -139,8 +147,12 @@ pub fn get_package_info(
     // Remove the "Version" entry from details since we'll add more specific version info
     details.retain(|(key, _)| key != "Version");
 
-    // Add bucket information - prefer installed bucket if available
-    let display_bucket = installed_bucket.unwrap_or(bucket_name);
+    // Add bucket information - show the source the manifest was read from, falling back
+    // to the installed bucket when the manifest only exists in the app directory
+    let display_bucket = match installed_bucket {
+        Some(bucket) if bucket_name.ends_with("(missing)") => bucket,
+        _ => bucket_name,
+    };
     details.push(("Bucket".to_string(), display_bucket));
 
     let installed_dir = scoop_dir.join("apps").join(&package_name).join("current");
@@ -167,6 +179,17 @@ pub fn get_package_info(
         }
     }
 
+    let deprecation = deprecation::detect_deprecation(&json_value, &manifest_path, &package_name);
+    if deprecation.is_deprecated {
+        details.push((
+            "Deprecated".to_string(),
+            deprecation.message.clone().unwrap_or_else(|| "Yes".to_string()),
+        ));
+        if let Some(replacement) = &deprecation.replacement {
+            details.push(("Replacement".to_string(), replacement.clone()));
+        }
+    }
+
     details.sort_by(|a, b| a.0.cmp(&b.0));
 
     // Prepend the package name to the details list for consistent display order.
@@ -177,11 +200,13 @@ pub fn get_package_info(
     Ok(ScoopInfo {
         details: ordered_details,
         notes,
+        is_deprecated: deprecation.is_deprecated,
+        replacement: deprecation.replacement,
     })
 }
 
 /// Gets the installed version of a package by reading its manifest file.
-fn get_installed_version(scoop_dir: &std::path::Path, package_name: &str) -> Option<String> {
+pub fn get_installed_version(scoop_dir: &std::path::Path, package_name: &str) -> Option<String> {
     let installed_manifest_path = scoop_dir
         .join("apps")
         .join(package_name)
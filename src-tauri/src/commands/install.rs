@@ -1,6 +1,9 @@
 //! Command for installing Scoop packages.
 use crate::commands::auto_cleanup::trigger_auto_cleanup;
+use crate::commands::install_preflight::check_system_installer;
 use crate::commands::installed::invalidate_installed_cache;
+use crate::commands::package_source::select_source;
+use crate::commands::powershell::fail_unstarted_operation;
 use crate::commands::scoop::{self, ScoopOp};
 use crate::commands::search::invalidate_manifest_cache;
 use crate::state::AppState;
@@ -11,7 +14,12 @@ use tauri::{AppHandle, State, Window};
 /// # Arguments
 /// * `window` - The Tauri window to emit events to.
 /// * `package_name` - The name of package to install.
-/// * `bucket` - The name of bucket to install from. If empty or "None", default buckets are used.
+/// * `bucket` - The name of bucket to install from. If empty or "None", the pinned or
+///   preferred source is used when the package exists in several buckets.
+/// * `skip_installer_checks` - Install even while Windows Installer is busy or a
+///   reboot is pending.
+/// * `operation_id` - Id to run the operation under, so the caller can pass it to
+///   `wait_for_operation`; one is generated when not given.
 #[tauri::command]
 pub async fn install_package(
     window: Window,
@@ -19,19 +27,35 @@ pub async fn install_package(
     state: State<'_, AppState>,
     package_name: String,
     bucket: String,
+    skip_installer_checks: Option<bool>,
+    operation_id: Option<String>,
 ) -> Result<(), String> {
-    let bucket_opt =
-        (!bucket.is_empty() && !bucket.eq_ignore_ascii_case("none")).then(|| bucket.as_str());
+    let selected_source = if bucket.is_empty() || bucket.eq_ignore_ascii_case("none") {
+        // Resolve pinned/preferred buckets explicitly instead of letting scoop pick one
+        select_source(&app, &state.scoop_path(), &package_name)
+    } else {
+        Some(bucket)
+    };
+    let bucket_opt = selected_source.as_deref();
 
     log::info!(
         "Installing package '{}' from bucket '{}'",
         package_name,
         bucket_opt.unwrap_or("default")
     );
+    let operation_id = operation_id.or_else(|| Some(format!("install-{}-{}", package_name, std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs())));
 
-    let operation_id = Some(format!("install-{}-{}", package_name, std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs()));
+    if !skip_installer_checks.unwrap_or(false) {
+        check_system_installer(&state.scoop_path(), &package_name, bucket_opt)
+            .map_err(|e| fail_unstarted_operation(operation_id.as_deref(), e))?;
+    }
 
-    scoop::execute_scoop(window, ScoopOp::Install, Some(&package_name), bucket_opt, operation_id).await?;
+    if let Err(e) =
+        scoop::execute_scoop(window, ScoopOp::Install, Some(&package_name), bucket_opt, operation_id).await
+    {
+        invalidate_installed_cache(state.clone()).await;
+        return Err(e);
+    }
     invalidate_manifest_cache().await;
     invalidate_installed_cache(state.clone()).await;
 
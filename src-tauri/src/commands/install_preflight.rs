@@ -0,0 +1,184 @@
+//! Checks made before installing packages that run a system installer.
+//!
+//! MSI downloads, which Scoop extracts with `msiexec` unless `use_lessmsi` is set, and
+//! manifests with an `installer` fail half-way while Windows has a reboot pending or
+//! another Windows Installer transaction holds the `_MSIExecute` mutex, often leaving
+//! a broken install behind. Such installs and updates are refused up front with the
+//! reason, unless the caller asks to skip the check. Installers that only run a
+//! PowerShell script are not affected.
+use crate::commands::repair::host_architecture;
+use crate::commands::settings;
+use crate::engine::cache_import::{architecture_downloads, url_file_name};
+use crate::utils::locate_package_manifest;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+#[cfg(windows)]
+use winreg::{enums::*, RegKey};
+
+/// Whether a manifest's `installer` runs a program rather than only a script. Without a
+/// `file` or a `script`, Scoop runs the download itself.
+fn runs_setup_program(installer: &Value) -> bool {
+    match installer.get("file").and_then(Value::as_str) {
+        Some(file) => !file.to_lowercase().ends_with(".ps1"),
+        None => installer.get("script").is_none(),
+    }
+}
+
+/// Whether installing `manifest` on `architecture` runs Windows Installer or a setup
+/// program. `use_lessmsi` is Scoop's setting of that name.
+pub fn runs_system_installer(manifest: &Value, architecture: &str, use_lessmsi: bool) -> bool {
+    let arch = manifest
+        .get("architecture")
+        .and_then(|archs| archs.get(architecture));
+    if arch
+        .and_then(|a| a.get("installer"))
+        .or_else(|| manifest.get("installer"))
+        .is_some_and(runs_setup_program)
+    {
+        return true;
+    }
+    !use_lessmsi
+        && architecture_downloads("", manifest, architecture)
+            .iter()
+            .any(|d| url_file_name(&d.url).to_lowercase().ends_with(".msi"))
+}
+
+/// Why Windows is waiting for a reboot, empty when it is not.
+#[cfg(windows)]
+pub fn pending_reboot_reasons() -> Vec<String> {
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let has_key = |path: &str| hklm.open_subkey(path).is_ok();
+    let mut reasons = Vec::new();
+
+    if has_key(r"SOFTWARE\Microsoft\Windows\CurrentVersion\Component Based Servicing\RebootPending")
+    {
+        reasons.push("Windows component servicing".to_string());
+    }
+    if has_key(
+        r"SOFTWARE\Microsoft\Windows\CurrentVersion\WindowsUpdate\Auto Update\RebootRequired",
+    ) {
+        reasons.push("Windows Update".to_string());
+    }
+    let pending_renames = hklm
+        .open_subkey(r"SYSTEM\CurrentControlSet\Control\Session Manager")
+        .and_then(|key| key.get_value::<Vec<String>, _>("PendingFileRenameOperations"))
+        .is_ok_and(|renames| renames.iter().any(|r| !r.is_empty()));
+    if pending_renames {
+        reasons.push("pending file renames".to_string());
+    }
+    reasons
+}
+
+#[cfg(not(windows))]
+pub fn pending_reboot_reasons() -> Vec<String> {
+    Vec::new()
+}
+
+/// Whether a Windows Installer transaction is running, which holds the global
+/// `_MSIExecute` mutex for its duration.
+#[cfg(windows)]
+pub fn msi_busy() -> bool {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{OpenMutexW, SYNCHRONIZATION_SYNCHRONIZE};
+
+    let name: Vec<u16> = "Global\\_MSIExecute"
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    // SAFETY: `name` is a NUL-terminated UTF-16 string that outlives the call, and the
+    // handle is closed right after.
+    unsafe {
+        let handle = OpenMutexW(SYNCHRONIZATION_SYNCHRONIZE, 0, name.as_ptr());
+        if handle.is_null() {
+            return false;
+        }
+        CloseHandle(handle);
+    }
+    true
+}
+
+#[cfg(not(windows))]
+pub fn msi_busy() -> bool {
+    false
+}
+
+/// Refuses to install or update `package` while its system installer would fail: when
+/// another installation is running or a reboot is pending. Packages without a system
+/// installer, and ones whose manifest cannot be found, are let through.
+pub fn check_system_installer(
+    scoop_dir: &Path,
+    package: &str,
+    bucket: Option<&str>,
+) -> Result<(), String> {
+    let Some(manifest) = locate_package_manifest(scoop_dir, package, bucket.map(String::from))
+        .ok()
+        .and_then(|(path, _)| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+    else {
+        return Ok(());
+    };
+    let use_lessmsi = settings::read_scoop_config()
+        .ok()
+        .and_then(|config| config.get("use_lessmsi").and_then(Value::as_bool))
+        .unwrap_or(false);
+    if !runs_system_installer(&manifest, host_architecture(), use_lessmsi) {
+        return Ok(());
+    }
+
+    if msi_busy() {
+        return Err(format!(
+            "Installer busy: another Windows Installer setup is running. Wait for it to finish, then try {} again.",
+            package
+        ));
+    }
+    let reasons = pending_reboot_reasons();
+    if !reasons.is_empty() {
+        return Err(format!(
+            "Reboot required: Windows has a restart pending ({}). Restart, then try {} again.",
+            reasons.join(", "),
+            package
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn detects_msi_downloads_and_installers() {
+        let msi = json!({
+            "version": "1.0",
+            "architecture": { "64bit": { "url": "https://example.com/tool-x64.msi" } }
+        });
+        assert!(runs_system_installer(&msi, "64bit", false));
+        assert!(!runs_system_installer(&msi, "64bit", true));
+        assert!(!runs_system_installer(&msi, "32bit", false));
+
+        let renamed = json!({ "version": "1.0", "url": "https://example.com/dl?id=1#/setup.msi" });
+        assert!(runs_system_installer(&renamed, "64bit", false));
+
+        let installer = json!({
+            "version": "1.0",
+            "url": "https://example.com/setup.exe",
+            "installer": { "args": ["/S"] }
+        });
+        assert!(runs_system_installer(&installer, "64bit", true));
+
+        let script_only = json!({
+            "version": "1.0",
+            "url": "https://example.com/tool.zip",
+            "installer": { "script": "Move-Item \"$dir\\tool\\*\" \"$dir\"" }
+        });
+        assert!(!runs_system_installer(&script_only, "64bit", false));
+        let ps1 = json!({ "version": "1.0", "installer": { "file": "install.ps1" } });
+        assert!(!runs_system_installer(&ps1, "64bit", false));
+
+        let portable = json!({ "version": "1.0", "url": "https://example.com/tool.zip" });
+        assert!(!runs_system_installer(&portable, "64bit", false));
+    }
+}
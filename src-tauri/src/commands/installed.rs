@@ -1,37 +1,103 @@
 //! Command for fetching all installed Scoop packages from the filesystem.
-use crate::models::{InstallManifest, PackageManifest, ScoopPackage};
+use crate::commands::quarantine::is_quarantined;
+use crate::commands::search::invalidate_manifest_cache;
+use crate::commands::settings;
+use crate::config_keys::INSTALLED_SCAN_EXCLUSIONS;
+use crate::engine::fingerprint::{compute_apps_fingerprint, find_latest_version_dir};
+use crate::models::{
+    InstallManifest, InstallScope, InstalledCacheInfo, InstalledPackagesResult, PackageManifest,
+    ScoopPackage,
+};
 use crate::state::{AppState, InstalledPackagesCache};
+use crate::utils::ScoopDirs;
 use chrono::{DateTime, Utc};
 use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::time::UNIX_EPOCH;
-use tauri::{AppHandle, Runtime, State};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Runtime, State};
+
+/// Emitted when a scan finds that the set of installed packages changed on disk.
+pub const EVENT_INSTALLED_CHANGED: &str = "installed-packages-changed";
+/// Emitted with [`ExternalChanges`] when packages were installed, removed or updated
+/// outside the app, e.g. with the Scoop CLI.
+pub const EVENT_EXTERNAL_CHANGES: &str = "external-changes";
+/// Emitted with [`WarmupProgress`] while the cold start scan parses app directories.
+pub const EVENT_WARMUP_PROGRESS: &str = "warmup-progress";
+/// Minimum time between two progress events; the final count is always sent.
+const WARMUP_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WarmupProgress {
+    /// App directories parsed so far.
+    pub parsed: usize,
+    pub total: usize,
+    /// The result came from the installed packages cache without parsing.
+    pub cached: bool,
+}
 
-/// Helper to get modification time of a path (file or directory) in milliseconds.
-fn get_path_modification_time(path: &Path) -> u128 {
-    fs::metadata(path)
-        .and_then(|meta| meta.modified())
-        .ok()
-        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
-        .map(|duration| duration.as_millis())
-        .unwrap_or(0)
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionChange {
+    pub name: String,
+    pub from: String,
+    pub to: String,
 }
 
-/// Helper to get modification time of an installation directory.
-/// Checks install.json, then manifest.json, then the directory itself.
-fn get_install_modification_time(install_dir: &Path) -> u128 {
-    let install_manifest = install_dir.join("install.json");
-    let manifest_path = install_dir.join("manifest.json");
+/// Differences between two scans of the installed packages.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalChanges {
+    pub installed: Vec<String>,
+    pub removed: Vec<String>,
+    pub updated: Vec<VersionChange>,
+}
 
-    fs::metadata(&install_manifest)
-        .or_else(|_| fs::metadata(&manifest_path))
-        .or_else(|_| fs::metadata(install_dir))
-        .and_then(|meta| meta.modified())
-        .ok()
-        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
-        .map(|duration| duration.as_millis())
-        .unwrap_or(0)
+impl ExternalChanges {
+    pub fn is_empty(&self) -> bool {
+        self.installed.is_empty() && self.removed.is_empty() && self.updated.is_empty()
+    }
+}
+
+/// Installed version by package name.
+fn installed_versions(packages: &[ScoopPackage]) -> HashMap<String, String> {
+    packages
+        .iter()
+        .map(|pkg| (pkg.name.clone(), pkg.version.clone()))
+        .collect()
+}
+
+/// Compares the installed versions of two scans.
+pub fn diff_installed(
+    previous: &HashMap<String, String>,
+    current: &HashMap<String, String>,
+) -> ExternalChanges {
+    let mut changes = ExternalChanges::default();
+    for (name, version) in current {
+        match previous.get(name) {
+            None => changes.installed.push(name.clone()),
+            Some(old) if old != version => changes.updated.push(VersionChange {
+                name: name.clone(),
+                from: old.clone(),
+                to: version.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    changes.removed = previous
+        .keys()
+        .filter(|name| !current.contains_key(*name))
+        .cloned()
+        .collect();
+    changes.installed.sort();
+    changes.removed.sort();
+    changes.updated.sort_by(|a, b| a.name.cmp(&b.name));
+    changes
 }
 
 /// Searches for a package manifest in all bucket directories to determine the bucket.
@@ -72,104 +138,6 @@ fn find_package_bucket(scoop_path: &Path, package_name: &str) -> Option<String>
     None
 }
 
-/// Returns the most recently updated version directory for a package when the
-/// `current` link is missing.
-fn find_latest_version_dir(package_path: &Path) -> Option<PathBuf> {
-    let mut candidates: Vec<(u128, PathBuf)> = Vec::new();
-
-    log::info!(
-        "Finding latest version directory for package: {}",
-        package_path.display()
-    );
-
-    if let Ok(entries) = fs::read_dir(package_path) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-
-            if !path.is_dir() {
-                continue;
-            }
-
-            if path
-                .file_name()
-                .and_then(|name| name.to_str())
-                .map(|name| name.eq_ignore_ascii_case("current"))
-                .unwrap_or(false)
-            {
-                continue;
-            }
-
-            let install_manifest = path.join("install.json");
-            let manifest_path = path.join("manifest.json");
-
-            if !install_manifest.exists() && !manifest_path.exists() {
-                continue;
-            }
-
-            let modified = fs::metadata(&install_manifest)
-                .or_else(|_| fs::metadata(&manifest_path))
-                .or_else(|_| fs::metadata(&path))
-                .and_then(|meta| meta.modified())
-                .ok()
-                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
-                .map(|duration| duration.as_millis())
-                .unwrap_or(0);
-
-            candidates.push((modified, path));
-        }
-    }
-
-    candidates.sort_by(|a, b| b.0.cmp(&a.0));
-    let result = candidates.into_iter().map(|(_, path)| path).next();
-    log::info!(
-        "Latest version directory found: {:?}",
-        result.as_ref().map(|p| p.display().to_string())
-    );
-    result
-}
-
-fn locate_install_dir(package_path: &Path) -> Option<PathBuf> {
-    let current_path = package_path.join("current");
-    log::debug!(
-        "Locating install directory for package: {}, checking current path: {}",
-        package_path.display(),
-        current_path.display()
-    );
-
-    if current_path.is_dir() {
-        log::debug!("Found current directory: {}", current_path.display());
-        Some(current_path)
-    } else {
-        log::debug!("Current directory not found, searching for latest version directory");
-        find_latest_version_dir(package_path)
-    }
-}
-
-fn compute_apps_fingerprint(app_dirs: &[PathBuf]) -> String {
-    log::debug!(
-        "Computing apps fingerprint for {} app directories",
-        app_dirs.len()
-    );
-    let entries: Vec<String> = app_dirs
-        .iter()
-        .filter_map(|path| {
-            path.file_name().and_then(|n| n.to_str()).map(|name| {
-                let modified_stamp = locate_install_dir(path)
-                    .map(|install_dir| get_install_modification_time(&install_dir))
-                    .unwrap_or_else(|| get_path_modification_time(path));
-                
-                format!("{}:{}", name.to_ascii_lowercase(), modified_stamp)
-            })
-        })
-        .collect();
-
-    let mut sorted_entries = entries;
-    sorted_entries.sort();
-    let fingerprint = format!("{}|{}", app_dirs.len(), sorted_entries.join(";"));
-    log::debug!("Computed apps fingerprint: {}", fingerprint);
-    fingerprint
-}
-
 /// Loads the details for a single installed package from its directory.
 /// Uses quick synchronous checks without blocking retries; the frontend handles
 /// refresh after cold-start if any packages are not yet ready on fresh .msi installs.
@@ -182,6 +150,10 @@ fn load_package_details(package_path: &Path, scoop_path: &Path) -> Result<ScoopP
 
     log::debug!("Loading package details for: {}", package_name);
 
+    if is_quarantined(package_path) {
+        return Err(format!("{} is quarantined after a failed install", package_name));
+    }
+
     let current_path = package_path.join("current");
 
     let install_root = if current_path.is_dir() {
@@ -212,11 +184,12 @@ fn load_package_details(package_path: &Path, scoop_path: &Path) -> Result<ScoopP
         package_name,
         manifest_path.display()
     );
-    let manifest_content = fs::read_to_string(&manifest_path)
-        .map_err(|e| format!("Failed to read manifest.json for {}: {}", package_name, e))?;
-
-    let manifest: PackageManifest = serde_json::from_str(&manifest_content)
-        .map_err(|e| format!("Failed to parse manifest.json for {}: {}", package_name, e))?;
+    let manifest: Result<PackageManifest, String> = fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("Failed to read manifest.json for {}: {}", package_name, e))
+        .and_then(|content| {
+            serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse manifest.json for {}: {}", package_name, e))
+        });
 
     // install.json might not exist for versioned installs
     let install_manifest_path = install_root.join("install.json");
@@ -225,15 +198,30 @@ fn load_package_details(package_path: &Path, scoop_path: &Path) -> Result<ScoopP
         package_name,
         install_manifest_path.display()
     );
-    let install_manifest_content = fs::read_to_string(&install_manifest_path)
-        .map_err(|e| format!("Failed to read install.json for {}: {}", package_name, e))?;
-    let install_manifest: InstallManifest = serde_json::from_str(&install_manifest_content)
-        .map_err(|e| format!("Failed to parse install.json for {}: {}", package_name, e))?;
+    let install_manifest: Result<InstallManifest, String> =
+        fs::read_to_string(&install_manifest_path)
+            .map_err(|e| format!("Failed to read install.json for {}: {}", package_name, e))
+            .and_then(|content| {
+                serde_json::from_str(&content).map_err(|e| {
+                    format!("Failed to parse install.json for {}: {}", package_name, e)
+                })
+            });
+
+    // Keep packages with unreadable metadata visible so they can be repaired or removed
+    let broken_reason = match (&manifest, &install_manifest) {
+        (Err(e), _) | (_, Err(e)) => {
+            log::warn!("=== INSTALLED SCAN === Loading degraded package: {}", e);
+            Some(e.clone())
+        }
+        _ => None,
+    };
+    let manifest = manifest.ok();
+    let install_manifest = install_manifest.ok();
 
     // Determine bucket - either from install.json or by searching buckets
     let bucket = install_manifest
-        .bucket
-        .clone()
+        .as_ref()
+        .and_then(|m| m.bucket.clone())
         .or_else(|| find_package_bucket(scoop_path, &package_name))
         .unwrap_or_else(|| {
             log::debug!("Using default bucket 'main' for package: {}", package_name);
@@ -244,13 +232,26 @@ fn load_package_details(package_path: &Path, scoop_path: &Path) -> Result<ScoopP
 
     // Check if this is a versioned install - versioned installs don't have a bucket field in install.json
     // AND cannot be found in any bucket directory (indicating custom/generated manifest)
-    let is_versioned_install = install_manifest.bucket.is_none();
+    let is_versioned_install = install_manifest
+        .as_ref()
+        .map(|m| m.bucket.is_none())
+        .unwrap_or(false);
     log::debug!(
         "Is versioned install for {}: {}",
         package_name,
         is_versioned_install
     );
 
+    // Without a manifest the version can still be read from the version directory name
+    let version = match &manifest {
+        Some(manifest) => manifest.version.clone(),
+        None => fs::canonicalize(&install_root)
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .filter(|name| !name.eq_ignore_ascii_case("current"))
+            .unwrap_or_default(),
+    };
+
     // Get the last modified time of the installation folder as install date
     let updated_time = fs::metadata(&install_root)
         .and_then(|m| m.modified())
@@ -261,12 +262,14 @@ fn load_package_details(package_path: &Path, scoop_path: &Path) -> Result<ScoopP
 
     Ok(ScoopPackage {
         name: package_name,
-        version: manifest.version,
+        version,
         source: bucket,
         updated: updated_time,
         is_installed: true,
-        info: manifest.description.unwrap_or_default(),
+        info: manifest.and_then(|m| m.description).unwrap_or_default(),
         is_versioned_install,
+        broken: broken_reason.is_some(),
+        broken_reason,
         ..Default::default()
     })
 }
@@ -284,7 +287,7 @@ async fn refresh_scoop_path_if_needed<R: Runtime>(
         reason
     );
 
-    match crate::utils::resolve_scoop_root(app) {
+    match crate::utils::resolve_scoop_root(app.clone()) {
         Ok(new_path) => {
             if current_path != new_path {
                 log::info!(
@@ -294,8 +297,10 @@ async fn refresh_scoop_path_if_needed<R: Runtime>(
                     reason
                 );
                 state.set_scoop_path(new_path.clone());
+                state.watch_scoop_dirs(&app);
                 let mut cache_guard = state.installed_packages.lock().await;
                 *cache_guard = None;
+                state.bump_installed_cache_generation();
                 return Some(new_path);
             }
             Some(current_path)
@@ -307,12 +312,211 @@ async fn refresh_scoop_path_if_needed<R: Runtime>(
     }
 }
 
+/// Updates the scan-in-progress flag and notifies the frontend.
+fn set_scan_in_progress<R: Runtime>(app: &AppHandle<R>, state: &AppState, in_progress: bool) {
+    state.set_installed_scan_in_progress(in_progress);
+    if let Err(e) = app.emit("installed-scan-state", in_progress) {
+        log::warn!("Failed to emit installed-scan-state event: {}", e);
+    }
+}
+
 /// Internal method to perform the actual installed packages scan.
 /// Separated from the public command to support both warm-up and user-initiated refresh paths.
+///
+/// Only one scan runs at a time. Callers arriving while a scan is in progress wait for it
+/// and reuse its result instead of scanning again.
 async fn scan_installed_packages_internal<R: Runtime>(
     app: AppHandle<R>,
     state: &AppState,
     is_warmup: bool,
+) -> Result<Vec<ScoopPackage>, String> {
+    let observed_generation = state.installed_scan_generation();
+    let _scan_guard = state.installed_scan_lock.lock().await;
+
+    if state.installed_scan_generation() != observed_generation {
+        // A scan finished while we were waiting; reuse its result unless it was invalidated since
+        if let Some(cache) = state.installed_packages.lock().await.as_ref() {
+            log::debug!("Reusing result of concurrent installed packages scan");
+            return Ok(cache.packages.clone());
+        }
+    }
+
+    set_scan_in_progress(&app, state, true);
+    let result = perform_installed_scan(app.clone(), state, is_warmup).await;
+    state.finish_installed_scan();
+    set_scan_in_progress(&app, state, false);
+    result
+}
+
+/// Lists the package directories under `apps_path`, skipping quarantined installs.
+pub fn list_app_dirs(apps_path: &Path) -> Result<Vec<PathBuf>, String> {
+    Ok(fs::read_dir(apps_path)
+        .map_err(|e| format!("Failed to read apps directory: {}", e))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && !is_quarantined(path))
+        .collect())
+}
+
+/// Reads the app directory names excluded from the installed scan.
+pub fn load_scan_exclusions<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    settings::get_config_value(app.clone(), INSTALLED_SCAN_EXCLUSIONS.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_array().cloned())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|v| v.as_str().map(|s| s.trim().to_string()))
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Drops the directories whose name matches one of `exclusions`, ignoring case.
+pub fn apply_scan_exclusions(app_dirs: Vec<PathBuf>, exclusions: &[String]) -> Vec<PathBuf> {
+    if exclusions.is_empty() {
+        return app_dirs;
+    }
+    app_dirs
+        .into_iter()
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            !exclusions.iter().any(|e| e.eq_ignore_ascii_case(&name))
+        })
+        .collect()
+}
+
+/// Loads package details for every directory in `app_dirs` in parallel.
+pub fn load_packages(
+    app_dirs: &[PathBuf],
+    scoop_path: &Path,
+    log_prefix: &str,
+) -> Vec<ScoopPackage> {
+    load_packages_with_progress(app_dirs, scoop_path, log_prefix, None)
+}
+
+/// Like [`load_packages`], sending the number of directories parsed so far to
+/// `progress` after each one. Counts can arrive slightly out of order.
+pub fn load_packages_with_progress(
+    app_dirs: &[PathBuf],
+    scoop_path: &Path,
+    log_prefix: &str,
+    progress: Option<mpsc::Sender<usize>>,
+) -> Vec<ScoopPackage> {
+    let parsed = AtomicUsize::new(0);
+    crate::scan_pool::install(|| {
+        app_dirs
+            .par_iter()
+            .filter_map(|path| {
+                let result = load_package_details(path.as_path(), scoop_path);
+                if let Some(progress) = &progress {
+                    let _ = progress.send(parsed.fetch_add(1, Ordering::Relaxed) + 1);
+                }
+                match result {
+                    Ok(package) => {
+                        log::debug!("Successfully loaded package: {}", package.name);
+                        Some(package)
+                    }
+                    Err(e) => {
+                        log::warn!(
+                            "{} Skipping package at '{}': {}",
+                            log_prefix,
+                            path.display(),
+                            e
+                        );
+                        None
+                    }
+                }
+            })
+            .collect()
+    })
+}
+
+fn emit_warmup_progress<R: Runtime>(app: &AppHandle<R>, progress: WarmupProgress) {
+    if let Err(e) = app.emit(EVENT_WARMUP_PROGRESS, progress) {
+        log::warn!("Failed to emit {} event: {}", EVENT_WARMUP_PROGRESS, e);
+    }
+}
+
+/// Emits [`EVENT_WARMUP_PROGRESS`] for the counts sent to the returned channel, at most
+/// every [`WARMUP_PROGRESS_INTERVAL`], until the channel is dropped.
+fn spawn_warmup_reporter<R: Runtime>(
+    app: AppHandle<R>,
+    total: usize,
+) -> (mpsc::Sender<usize>, std::thread::JoinHandle<()>) {
+    let (sender, receiver) = mpsc::channel::<usize>();
+    let reporter = std::thread::spawn(move || {
+        let progress = |parsed| WarmupProgress {
+            parsed,
+            total,
+            cached: false,
+        };
+        emit_warmup_progress(&app, progress(0));
+        let mut parsed = 0;
+        let mut emitted = 0;
+        let mut last_emit = Instant::now();
+        for count in receiver {
+            parsed = parsed.max(count);
+            if parsed > emitted
+                && (parsed == total || last_emit.elapsed() >= WARMUP_PROGRESS_INTERVAL)
+            {
+                emit_warmup_progress(&app, progress(parsed));
+                emitted = parsed;
+                last_emit = Instant::now();
+            }
+        }
+        if parsed > emitted {
+            emit_warmup_progress(&app, progress(parsed));
+        }
+    });
+    (sender, reporter)
+}
+
+/// Lists the apps installed with `--global`, marked with their scope.
+///
+/// They are not part of the installed packages cache, since the commands that act on
+/// installed packages only manage the user's own installs.
+pub fn load_global_packages(dirs: &ScoopDirs, exclusions: &[String]) -> Vec<ScoopPackage> {
+    if !dirs.global_apps.is_dir() || dirs.global_apps == dirs.apps {
+        return Vec::new();
+    }
+    let app_dirs = match list_app_dirs(&dirs.global_apps) {
+        Ok(app_dirs) => apply_scan_exclusions(app_dirs, exclusions),
+        Err(e) => {
+            log::warn!("Failed to list global apps: {}", e);
+            return Vec::new();
+        }
+    };
+    load_packages(&app_dirs, &dirs.root, "=== GLOBAL SCAN ===")
+        .into_iter()
+        .map(|package| ScoopPackage {
+            scope: InstallScope::Global,
+            ..package
+        })
+        .collect()
+}
+
+/// Appends the global installs to a list of the user's installed packages.
+pub async fn with_global_packages<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &AppState,
+    mut packages: Vec<ScoopPackage>,
+) -> Vec<ScoopPackage> {
+    let dirs = state.scoop_dirs();
+    let exclusions = load_scan_exclusions(app);
+    match tokio::task::spawn_blocking(move || load_global_packages(&dirs, &exclusions)).await {
+        Ok(global) => packages.extend(global),
+        Err(e) => log::warn!("Failed to load global packages: {}", e),
+    }
+    packages
+}
+
+async fn perform_installed_scan<R: Runtime>(
+    app: AppHandle<R>,
+    state: &AppState,
+    is_warmup: bool,
 ) -> Result<Vec<ScoopPackage>, String> {
     let log_prefix = if is_warmup {
         "=== INSTALLED WARMUP ==="
@@ -340,27 +544,50 @@ async fn scan_installed_packages_internal<R: Runtime>(
         apps_path.display()
     );
 
-    let app_dirs: Vec<PathBuf> = fs::read_dir(&apps_path)
-        .map_err(|e| format!("Failed to read apps directory: {}", e))?
-        .filter_map(Result::ok)
-        .map(|entry| entry.path())
-        .filter(|path| path.is_dir())
-        .collect();
+    let all_dirs = list_app_dirs(&apps_path)?;
+    let found = all_dirs.len();
+    let app_dirs = apply_scan_exclusions(all_dirs, &load_scan_exclusions(&app));
 
     log::debug!(
-        "{} Found {} app directories in apps path",
+        "{} Found {} app directories in apps path ({} excluded)",
         log_prefix,
-        app_dirs.len()
+        app_dirs.len(),
+        found - app_dirs.len()
     );
 
     let fingerprint = compute_apps_fingerprint(&app_dirs);
     log::debug!("{} Computed fingerprint: {}", log_prefix, fingerprint);
+    state.update_last_scan_time();
+
+    let previous_fingerprint = state
+        .last_scan_fingerprint
+        .lock()
+        .await
+        .replace(fingerprint.clone());
+    let own_changes = state.take_own_changes();
+    let changed = previous_fingerprint.is_some_and(|previous| previous != fingerprint);
+    if changed {
+        log::info!("{} Installed packages changed since last scan", log_prefix);
+        if let Err(e) = app.emit(EVENT_INSTALLED_CHANGED, ()) {
+            log::warn!("Failed to emit {} event: {}", EVENT_INSTALLED_CHANGED, e);
+        }
+    }
 
     // Get scoop path for use in package loading
     let scoop_path = state.scoop_path();
 
     // Check cache
     if let Some(cached_packages) = check_cache(state, &fingerprint, log_prefix).await {
+        if is_warmup {
+            emit_warmup_progress(
+                &app,
+                WarmupProgress {
+                    parsed: app_dirs.len(),
+                    total: app_dirs.len(),
+                    cached: true,
+                },
+            );
+        }
         return Ok(cached_packages);
     }
 
@@ -370,26 +597,15 @@ async fn scan_installed_packages_internal<R: Runtime>(
         app_dirs.len()
     );
 
-    let packages: Vec<ScoopPackage> = app_dirs
-        .par_iter()
-        .filter_map(
-            |path| match load_package_details(path.as_path(), &scoop_path) {
-                Ok(package) => {
-                    log::debug!("Successfully loaded package: {}", package.name);
-                    Some(package)
-                }
-                Err(e) => {
-                    log::warn!(
-                        "{} Skipping package at '{}': {}",
-                        log_prefix,
-                        path.display(),
-                        e
-                    );
-                    None
-                }
-            },
-        )
-        .collect();
+    let packages = if is_warmup {
+        let (progress, reporter) = spawn_warmup_reporter(app.clone(), app_dirs.len());
+        let packages =
+            load_packages_with_progress(&app_dirs, &scoop_path, log_prefix, Some(progress));
+        let _ = reporter.join();
+        packages
+    } else {
+        load_packages(&app_dirs, &scoop_path, log_prefix)
+    };
 
     log::info!(
         "{} ✓ Scanned {} packages, found {} valid packages",
@@ -398,6 +614,16 @@ async fn scan_installed_packages_internal<R: Runtime>(
         packages.len()
     );
 
+    let versions = installed_versions(&packages);
+    let previous_versions = state
+        .last_scan_versions
+        .lock()
+        .await
+        .replace(versions.clone());
+    if let (true, false, Some(previous)) = (changed, own_changes, previous_versions) {
+        report_external_changes(&app, &previous, &versions).await;
+    }
+
     // Update cache
     update_cache(state, packages.clone(), fingerprint.clone(), log_prefix).await;
 
@@ -409,29 +635,123 @@ async fn scan_installed_packages_internal<R: Runtime>(
     Ok(packages)
 }
 
-#[tauri::command]
-pub async fn get_installed_packages_full<R: Runtime>(
+/// Emits [`EVENT_EXTERNAL_CHANGES`] for packages changed outside the app. A CLI update
+/// usually pulled the buckets too, so the search cache is dropped as well.
+async fn report_external_changes<R: Runtime>(
+    app: &AppHandle<R>,
+    previous: &HashMap<String, String>,
+    current: &HashMap<String, String>,
+) {
+    let changes = diff_installed(previous, current);
+    if changes.is_empty() {
+        return;
+    }
+    log::info!(
+        "Packages changed outside the app: {} installed, {} removed, {} updated",
+        changes.installed.len(),
+        changes.removed.len(),
+        changes.updated.len()
+    );
+    invalidate_manifest_cache().await;
+    if let Err(e) = app.emit(EVENT_EXTERNAL_CHANGES, &changes) {
+        log::warn!("Failed to emit {} event: {}", EVENT_EXTERNAL_CHANGES, e);
+    }
+}
+
+/// Runs the cold start scan of installed packages, which reports its progress with
+/// [`EVENT_WARMUP_PROGRESS`] events.
+pub async fn warm_installed_packages<R: Runtime>(
+    app: AppHandle<R>,
+    state: &AppState,
+) -> Result<Vec<ScoopPackage>, String> {
+    scan_installed_packages_internal(app, state, true).await
+}
+
+/// Lists installed packages, reusing the cache when the apps directory is unchanged.
+pub async fn get_installed_packages<R: Runtime>(
     app: AppHandle<R>,
     state: State<'_, AppState>,
 ) -> Result<Vec<ScoopPackage>, String> {
-    log::info!("=== INSTALLED SCAN === get_installed_packages_full called");
+    log::info!("=== INSTALLED SCAN === get_installed_packages called");
 
     // Perform the scan (cache is checked inside)
     let result = scan_installed_packages_internal(app, &state, false).await;
     log::info!(
-        "=== INSTALLED SCAN === get_installed_packages_full completed, result: {:?}",
+        "=== INSTALLED SCAN === get_installed_packages completed, result: {:?}",
         result.as_ref().map(|pkgs| pkgs.len())
     );
     result
 }
 
+/// Returns the app directory names the installed scan skips.
+#[tauri::command]
+pub fn get_scan_exclusions(app: AppHandle) -> Vec<String> {
+    load_scan_exclusions(&app)
+}
+
+/// Replaces the excluded app directories and drops the installed packages cache so
+/// the next scan reflects the change.
+#[tauri::command]
+pub async fn set_scan_exclusions(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    exclusions: Vec<String>,
+) -> Result<(), String> {
+    let mut exclusions: Vec<String> = exclusions
+        .into_iter()
+        .map(|e| e.trim().to_string())
+        .filter(|e| !e.is_empty())
+        .collect();
+    exclusions.sort_by_key(|e| e.to_lowercase());
+    exclusions.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+
+    settings::set_config_value(
+        app,
+        INSTALLED_SCAN_EXCLUSIONS.to_string(),
+        serde_json::json!(exclusions),
+    )?;
+    invalidate_installed_cache(state).await;
+    log::info!("Saved {} installed scan exclusion(s)", exclusions.len());
+    Ok(())
+}
+
+/// Lists installed packages along with the cache generation and last scan time,
+/// so the frontend can tell how fresh its view is.
+#[tauri::command]
+pub async fn get_installed_packages_full<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+) -> Result<InstalledPackagesResult, String> {
+    let packages = get_installed_packages(app.clone(), state.clone()).await?;
+    Ok(InstalledPackagesResult {
+        packages: with_global_packages(&app, &state, packages).await,
+        cache_info: state.installed_cache_info(),
+    })
+}
+
+/// Returns the installed packages cache generation and the time of the last scan.
+#[tauri::command]
+pub fn get_installed_cache_info(state: State<'_, AppState>) -> InstalledCacheInfo {
+    state.installed_cache_info()
+}
+
 /// Invalidates the cached list of installed packages in AppState.
 /// This should be called after operations that change the installed packages,
 /// such as installing or uninstalling a package.
 pub async fn invalidate_installed_cache(state: State<'_, AppState>) {
+    state.note_own_changes();
+    clear_installed_cache(&state).await;
+}
+
+/// Drops the installed packages and versions caches without treating the next change
+/// of the installs as the app's own.
+pub async fn clear_installed_cache(state: &AppState) {
     let mut cache_guard = state.installed_packages.lock().await;
     let was_cached = cache_guard.is_some();
     *cache_guard = None;
+    if was_cached {
+        state.bump_installed_cache_generation();
+    }
 
     // Also invalidate the versions cache since it depends on installed packages
     let mut versions_guard = state.package_versions.lock().await;
@@ -461,7 +781,9 @@ pub async fn refresh_installed_packages<R: Runtime>(
         let cache_guard = state.installed_packages.lock().await;
         if let Some(cache) = cache_guard.as_ref() {
             log::info!("=== INSTALLED REFRESH === Returning cached packages due to debounce");
-            return Ok(cache.packages.clone());
+            let packages = cache.packages.clone();
+            drop(cache_guard);
+            return Ok(with_global_packages(&app, &state, packages).await);
         }
     }
 
@@ -469,13 +791,19 @@ pub async fn refresh_installed_packages<R: Runtime>(
 
     // First invalidate the cache
     log::info!("=== INSTALLED REFRESH === Invalidating cache");
-    invalidate_installed_cache(state.clone()).await;
+    clear_installed_cache(&state).await;
 
     // Then fetch fresh data
     log::info!("=== INSTALLED REFRESH === Fetching fresh data");
-    let result = scan_installed_packages_internal(app, &state, false).await;
+    let packages = scan_installed_packages_internal(app.clone(), &state, false).await?;
     log::info!("=== INSTALLED REFRESH === refresh_installed_packages completed");
-    result
+    Ok(with_global_packages(&app, &state, packages).await)
+}
+
+/// Returns `true` while an installed packages scan is running.
+#[tauri::command]
+pub fn is_installed_scan_in_progress(state: State<'_, AppState>) -> bool {
+    state.is_installed_scan_in_progress()
 }
 
 /// Gets the installation path for a specific package.
@@ -485,11 +813,11 @@ pub async fn get_package_path<R: Runtime>(
     state: State<'_, AppState>,
     package_name: String,
 ) -> Result<String, String> {
-    let package_path = state.scoop_path().join("apps").join(&package_name);
-
-    if !package_path.exists() {
-        return Err(format!("Package '{}' is not installed", package_name));
-    }
+    let dirs = state.scoop_dirs();
+    let package_path = [dirs.apps.join(&package_name), dirs.global_apps.join(&package_name)]
+        .into_iter()
+        .find(|path| path.exists())
+        .ok_or_else(|| format!("Package '{}' is not installed", package_name))?;
 
     Ok(package_path.to_string_lossy().to_string())
 }
@@ -499,8 +827,7 @@ async fn ensure_apps_path<R: Runtime>(
     state: &AppState,
     log_prefix: &str,
 ) -> Option<PathBuf> {
-    let mut scoop_path = state.scoop_path();
-    let mut apps_path = scoop_path.join("apps");
+    let mut apps_path = state.scoop_dirs().apps;
 
     if !apps_path.is_dir() {
         log::warn!(
@@ -512,8 +839,7 @@ async fn ensure_apps_path<R: Runtime>(
         if let Some(updated_path) =
             refresh_scoop_path_if_needed(app, state, "apps path missing").await
         {
-            scoop_path = updated_path;
-            apps_path = scoop_path.join("apps");
+            apps_path = ScoopDirs::resolve(&updated_path).apps;
             log::info!("{} Path refreshed to: {}", log_prefix, apps_path.display());
         }
     }
@@ -564,9 +890,114 @@ async fn update_cache(
         packages: packages.clone(),
         fingerprint,
     });
+    state.bump_installed_cache_generation();
     log::info!(
         "{} ✓ Cache updated with {} packages",
         log_prefix,
         packages.len()
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScoopFixture;
+
+    #[test]
+    fn loads_package_from_current_install() {
+        let fixture = ScoopFixture::new();
+        fixture
+            .bucket_manifest("main", "git", "2.44.0")
+            .install("git", "2.44.0", "main");
+
+        let package = load_package_details(&fixture.app_dir("git"), fixture.root()).unwrap();
+        assert_eq!(package.name, "git");
+        assert_eq!(package.version, "2.44.0");
+        assert_eq!(package.source, "main");
+        assert!(!package.broken);
+        assert!(!package.is_versioned_install);
+    }
+
+    #[test]
+    fn falls_back_to_version_dir_without_current_link() {
+        let fixture = ScoopFixture::new();
+        fixture.app_version("7zip", "23.01", Some("main"));
+
+        let package = load_package_details(&fixture.app_dir("7zip"), fixture.root()).unwrap();
+        assert_eq!(package.version, "23.01");
+    }
+
+    #[test]
+    fn keeps_broken_and_versioned_installs_visible() {
+        let fixture = ScoopFixture::new();
+        fixture.app_version("node", "20.11.0", Some("main"));
+        fixture.corrupt_install_info("node", "20.11.0");
+        fixture.app_version("python", "3.12.1", None);
+
+        let node = load_package_details(&fixture.app_dir("node"), fixture.root()).unwrap();
+        assert!(node.broken);
+        assert_eq!(node.version, "20.11.0");
+
+        let python = load_package_details(&fixture.app_dir("python"), fixture.root()).unwrap();
+        assert!(python.is_versioned_install);
+    }
+
+    #[test]
+    fn skips_quarantined_installs() {
+        let fixture = ScoopFixture::new();
+        fixture.app_version("vlc.quarantined-1", "3.0.20", Some("extras"));
+
+        let dir = fixture.app_dir("vlc.quarantined-1");
+        assert!(load_package_details(&dir, fixture.root()).is_err());
+    }
+
+    #[test]
+    fn reports_progress_for_every_app_dir() {
+        let fixture = ScoopFixture::new();
+        fixture
+            .install("git", "2.44.0", "main")
+            .install("node", "20.11.0", "main")
+            .install("vlc", "3.0.20", "extras");
+        let app_dirs = list_app_dirs(&fixture.apps_dir()).unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let packages = load_packages_with_progress(&app_dirs, fixture.root(), "", Some(sender));
+        let mut counts: Vec<usize> = receiver.iter().collect();
+        counts.sort_unstable();
+        assert_eq!(packages.len(), 3);
+        assert_eq!(counts, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn excludes_app_dirs_by_name() {
+        let apps = Path::new("apps");
+        let dirs = vec![apps.join("git"), apps.join("Junk")];
+        let kept = apply_scan_exclusions(dirs, &["junk".to_string()]);
+        assert_eq!(kept, vec![apps.join("git")]);
+    }
+
+    #[test]
+    fn diffs_installed_versions() {
+        let versions = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(name, version)| (name.to_string(), version.to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+        let previous = versions(&[("git", "2.44.0"), ("7zip", "23.01"), ("nodejs", "20.0")]);
+        let current = versions(&[("git", "2.45.0"), ("nodejs", "20.0"), ("rg", "14.1.0")]);
+
+        let changes = diff_installed(&previous, &current);
+        assert_eq!(changes.installed, vec!["rg"]);
+        assert_eq!(changes.removed, vec!["7zip"]);
+        assert_eq!(
+            changes.updated,
+            vec![VersionChange {
+                name: "git".to_string(),
+                from: "2.44.0".to_string(),
+                to: "2.45.0".to_string(),
+            }]
+        );
+        assert!(diff_installed(&current, &current).is_empty());
+    }
+}
@@ -0,0 +1,314 @@
+//! Filtering the installed packages list.
+//!
+//! Searches only what is installed, using the installed packages cache plus the
+//! commands each app provides, read once from its `manifest.json`. The index is
+//! rebuilt when the cache generation changes, so typing in the filter box never
+//! touches the buckets.
+use crate::commands::installed::{get_installed_packages, with_global_packages};
+use crate::commands::search::{IndexedManifest, SearchQuery};
+use crate::models::{InstallScope, MatchSource, ScoopPackage};
+use crate::state::AppState;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use tauri::{AppHandle, Runtime, State};
+use tokio::sync::Mutex;
+
+const DEFAULT_LIMIT: usize = 50;
+/// Score of an installed package found by its bucket name.
+const BUCKET_MATCH_SCORE: u32 = 15;
+
+struct IndexedPackage {
+    package: ScoopPackage,
+    /// Lowercase names of the commands the package provides.
+    bins: Vec<String>,
+}
+
+struct InstalledSearchIndex {
+    generation: u64,
+    entries: Vec<IndexedPackage>,
+}
+
+static INDEX: Lazy<Mutex<Option<InstalledSearchIndex>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledSearchHit {
+    pub package: ScoopPackage,
+    pub score: u32,
+    /// `name`, `bin`, `description` or `bucket`, for each field that matched.
+    pub matched: Vec<String>,
+}
+
+/// Command names declared in a manifest's `bin` field: the alias when one is given,
+/// else the file name without extension.
+pub fn bin_names(manifest: &Value) -> Vec<String> {
+    let stem = |path: &str| {
+        let file = path.rsplit(['\\', '/']).next().unwrap_or(path);
+        Path::new(file)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+    };
+    let entry_name = |entry: &Value| match entry {
+        Value::String(path) => Some(stem(path)),
+        Value::Array(parts) => match (parts.first(), parts.get(1)) {
+            (_, Some(Value::String(alias))) => Some(alias.to_lowercase()),
+            (Some(Value::String(path)), _) => Some(stem(path)),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    let mut names: Vec<String> = match manifest.get("bin") {
+        Some(Value::Array(entries)) => entries.iter().filter_map(entry_name).collect(),
+        Some(entry) => entry_name(entry).into_iter().collect(),
+        None => Vec::new(),
+    };
+    names.retain(|n| !n.is_empty());
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Whether the characters of `query` appear in `text` in order.
+fn is_subsequence(query: &str, text: &str) -> bool {
+    let mut chars = text.chars();
+    query.chars().all(|q| chars.any(|c| c == q))
+}
+
+/// Scores one lowercase query term against a package; `None` when nothing matched.
+fn score_term(term: &str, entry: &IndexedPackage, matched: &mut Vec<&'static str>) -> Option<u32> {
+    let name = entry.package.name.to_lowercase();
+    let mut best = 0;
+    let mut hit = |field: &'static str, score: u32| {
+        if score > 0 {
+            best = best.max(score);
+            if !matched.contains(&field) {
+                matched.push(field);
+            }
+        }
+    };
+
+    hit(
+        "name",
+        if name == term {
+            100
+        } else if name.starts_with(term) {
+            80
+        } else if name.contains(term) {
+            60
+        } else if term.len() >= 2 && is_subsequence(term, &name) {
+            30
+        } else {
+            0
+        },
+    );
+    hit(
+        "bin",
+        if entry.bins.iter().any(|b| b == term) {
+            70
+        } else if entry.bins.iter().any(|b| b.starts_with(term)) {
+            50
+        } else {
+            0
+        },
+    );
+    if entry.package.info.to_lowercase().contains(term) {
+        hit("description", 20);
+    }
+    if entry.package.source.eq_ignore_ascii_case(term) {
+        hit("bucket", 15);
+    }
+    (best > 0).then_some(best)
+}
+
+/// Ranks the indexed packages against `query`; every term has to match somewhere.
+fn rank(query: &str, entries: &[IndexedPackage], limit: usize) -> Vec<InstalledSearchHit> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    let mut hits: Vec<InstalledSearchHit> = entries
+        .iter()
+        .filter_map(|entry| {
+            let mut matched = Vec::new();
+            let score = terms
+                .iter()
+                .map(|term| score_term(term, entry, &mut matched))
+                .sum::<Option<u32>>()?;
+            Some(InstalledSearchHit {
+                package: entry.package.clone(),
+                score,
+                matched: matched.into_iter().map(String::from).collect(),
+            })
+        })
+        .collect();
+    hits.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.package.name.cmp(&b.package.name))
+    });
+    hits.truncate(limit);
+    hits
+}
+
+fn read_bins(apps_dir: &Path, global_apps_dir: &Path, package: &ScoopPackage) -> Vec<String> {
+    let apps = match package.scope {
+        InstallScope::User => apps_dir,
+        InstallScope::Global => global_apps_dir,
+    };
+    std::fs::read_to_string(
+        apps.join(&package.name)
+            .join("current")
+            .join("manifest.json"),
+    )
+    .ok()
+    .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+    .map(|manifest| bin_names(&manifest))
+    .unwrap_or_default()
+}
+
+/// Runs `f` on the installed packages index, rebuilding it first when the installed
+/// packages cache changed since.
+async fn with_index<R: Runtime, T>(
+    app: &AppHandle<R>,
+    state: &State<'_, AppState>,
+    f: impl FnOnce(&[IndexedPackage]) -> T,
+) -> Result<T, String> {
+    let packages = get_installed_packages(app.clone(), state.clone()).await?;
+    let generation = state.installed_cache_info().generation;
+
+    let mut index = INDEX.lock().await;
+    if index.as_ref().is_none_or(|i| i.generation != generation) {
+        let packages = with_global_packages(app, state, packages).await;
+        let dirs = state.scoop_dirs();
+        let entries = tokio::task::spawn_blocking(move || {
+            packages
+                .into_iter()
+                .map(|package| IndexedPackage {
+                    bins: read_bins(&dirs.apps, &dirs.global_apps, &package),
+                    package,
+                })
+                .collect::<Vec<_>>()
+        })
+        .await
+        .map_err(|e| format!("Failed to index installed packages: {}", e))?;
+        log::debug!("Indexed {} installed packages for search", entries.len());
+        *index = Some(InstalledSearchIndex {
+            generation,
+            entries,
+        });
+    }
+
+    Ok(f(index
+        .as_ref()
+        .map(|i| i.entries.as_slice())
+        .unwrap_or_default()))
+}
+
+/// Matches installed packages against `query` the way bucket manifests are matched,
+/// and by their bucket name.
+fn match_entries(query: &SearchQuery, entries: &[IndexedPackage]) -> Vec<ScoopPackage> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let manifest = IndexedManifest {
+                name: entry.package.name.clone(),
+                version: entry.package.version.clone(),
+                bucket: entry.package.source.clone(),
+                bins: entry.bins.clone(),
+                description: entry.package.info.clone(),
+                ..Default::default()
+            };
+            let (match_source, score) = query.match_manifest(&manifest).or_else(|| {
+                query
+                    .is_text_match(&entry.package.source)
+                    .then_some((MatchSource::Bucket, BUCKET_MATCH_SCORE))
+            })?;
+            Some(ScoopPackage {
+                match_source,
+                score: Some(score),
+                is_installed: true,
+                ..entry.package.clone()
+            })
+        })
+        .collect()
+}
+
+/// The installed packages matching `query`, for `search_scoop` limited to them.
+pub async fn match_installed<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &State<'_, AppState>,
+    query: &SearchQuery,
+) -> Result<Vec<ScoopPackage>, String> {
+    with_index(app, state, |entries| match_entries(query, entries)).await
+}
+
+/// Searches the installed packages by name, command, description and bucket.
+///
+/// An empty query lists every installed package. Results are best match first.
+#[tauri::command]
+pub async fn search_installed<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<InstalledSearchHit>, String> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    with_index(&app, &state, |entries| rank(&query, entries, limit)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(name: &str, bucket: &str, info: &str, manifest: Value) -> IndexedPackage {
+        IndexedPackage {
+            package: ScoopPackage {
+                name: name.to_string(),
+                source: bucket.to_string(),
+                info: info.to_string(),
+                ..Default::default()
+            },
+            bins: bin_names(&manifest),
+        }
+    }
+
+    #[test]
+    fn ranks_name_bin_and_description_matches() {
+        let entries = [
+            entry(
+                "ripgrep",
+                "main",
+                "Recursively search directories",
+                json!({ "bin": "rg.exe" }),
+            ),
+            entry(
+                "git",
+                "main",
+                "Distributed version control",
+                json!({ "bin": ["bin\\git.exe", ["usr\\bin\\bash.exe", "git-bash"]] }),
+            ),
+            entry("grepwin", "extras", "Search with regex", json!({})),
+        ];
+
+        let names = |query: &str| -> Vec<String> {
+            rank(query, &entries, 10)
+                .into_iter()
+                .map(|hit| hit.package.name)
+                .collect()
+        };
+        assert_eq!(names("rg"), vec!["ripgrep"]);
+        assert_eq!(names("grep"), vec!["grepwin", "ripgrep"]);
+        assert_eq!(names("git-bash"), vec!["git"]);
+        assert_eq!(names("search extras"), vec!["grepwin"]);
+        assert_eq!(names("").len(), 3);
+
+        let found = match_entries(&SearchQuery::new("extras", false).unwrap(), &entries);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].match_source, MatchSource::Bucket);
+        let found = match_entries(&SearchQuery::new("\"git\"", false).unwrap(), &entries);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "git");
+    }
+}
@@ -0,0 +1,241 @@
+//! Verifying installed packages against their manifest hashes.
+//!
+//! Manifests only carry hashes of the downloads, not of extracted content, so two kinds
+//! of files can be checked: downloads Scoop installs as they are (single executables
+//! and the like, which stay in the version directory under their download name), and
+//! archives still in the download cache. Anything else is reported as unverifiable.
+use crate::commands::installed::list_app_dirs;
+use crate::engine::cache_import::{
+    architecture_downloads, cache_file_name, check_hash, url_file_name, HashCheck,
+};
+use crate::models::InstallScope;
+use crate::state::AppState;
+use rayon::prelude::*;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileCheck {
+    Match,
+    Mismatch,
+    Unverifiable,
+    Error,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IntegrityStatus {
+    /// Every checked file matches its hash.
+    Verified,
+    /// At least one file differs from its hash.
+    Modified,
+    /// Nothing could be checked against a hash.
+    Unverifiable,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FileIntegrity {
+    pub url: String,
+    pub path: String,
+    /// The file is a cached download rather than an installed one.
+    pub cached: bool,
+    pub result: FileCheck,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageIntegrity {
+    pub package: String,
+    pub version: String,
+    pub scope: InstallScope,
+    pub status: IntegrityStatus,
+    pub files: Vec<FileIntegrity>,
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn check_file(url: &str, path: &Path, cached: bool, hash: Option<&str>) -> FileIntegrity {
+    let (result, error) = match check_hash(path, hash) {
+        Ok(HashCheck::Match) => (FileCheck::Match, None),
+        Ok(HashCheck::Mismatch) => (FileCheck::Mismatch, None),
+        Ok(HashCheck::Unverifiable) => (FileCheck::Unverifiable, None),
+        Err(e) => (FileCheck::Error, Some(e)),
+    };
+    FileIntegrity {
+        url: url.to_string(),
+        path: path.display().to_string(),
+        cached,
+        result,
+        error,
+    }
+}
+
+/// Checks the installed version of the package in `package_dir`.
+///
+/// Returns `None` when the package has no readable installed manifest.
+pub fn verify_package(
+    package_dir: &Path,
+    cache_dir: &Path,
+    default_architecture: &str,
+) -> Option<PackageIntegrity> {
+    let package = package_dir.file_name()?.to_string_lossy().to_string();
+    let current = package_dir.join("current");
+    let manifest = read_json(&current.join("manifest.json"))?;
+    let architecture = read_json(&current.join("install.json"))
+        .and_then(|install| {
+            install
+                .get("architecture")
+                .and_then(Value::as_str)
+                .map(String::from)
+        })
+        .unwrap_or_else(|| default_architecture.to_string());
+
+    let downloads = architecture_downloads(&package, &manifest, &architecture);
+    let version = downloads
+        .first()
+        .map(|d| d.version.clone())
+        .or_else(|| {
+            manifest
+                .get("version")
+                .and_then(Value::as_str)
+                .map(String::from)
+        })
+        .unwrap_or_default();
+
+    let mut files = Vec::new();
+    for download in &downloads {
+        let hash = download.hash.as_deref();
+        let installed = current.join(url_file_name(&download.url));
+        if installed.is_file() {
+            files.push(check_file(&download.url, &installed, false, hash));
+        }
+        let cached = cache_dir.join(cache_file_name(&package, &version, &download.url));
+        if cached.is_file() {
+            files.push(check_file(&download.url, &cached, true, hash));
+        }
+    }
+
+    let status = if files.iter().any(|f| f.result == FileCheck::Mismatch) {
+        IntegrityStatus::Modified
+    } else if files.iter().any(|f| f.result == FileCheck::Match) {
+        IntegrityStatus::Verified
+    } else {
+        IntegrityStatus::Unverifiable
+    };
+    Some(PackageIntegrity {
+        package,
+        version,
+        scope: InstallScope::User,
+        status,
+        files,
+    })
+}
+
+/// Re-hashes installed downloads and cached archives of installed packages and reports
+/// the ones that no longer match their manifest; all packages when `packages` is not given.
+#[tauri::command]
+pub async fn verify_package_integrity(
+    state: State<'_, AppState>,
+    packages: Option<Vec<String>>,
+) -> Result<Vec<PackageIntegrity>, String> {
+    let dirs = state.scoop_dirs();
+    log::info!("Verifying integrity of installed packages");
+
+    let reports = tokio::task::spawn_blocking(move || {
+        let mut scopes = vec![(dirs.apps.clone(), InstallScope::User)];
+        if dirs.global_apps != dirs.apps && dirs.global_apps.is_dir() {
+            scopes.push((dirs.global_apps.clone(), InstallScope::Global));
+        }
+
+        let mut package_dirs = Vec::new();
+        for (apps, scope) in scopes {
+            for dir in list_app_dirs(&apps).unwrap_or_default() {
+                let name = dir
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                if packages
+                    .as_ref()
+                    .is_none_or(|names| names.iter().any(|n| n.eq_ignore_ascii_case(&name)))
+                {
+                    package_dirs.push((dir, scope));
+                }
+            }
+        }
+
+        let architecture = crate::commands::repair::host_architecture();
+        let mut reports: Vec<PackageIntegrity> = crate::scan_pool::install(|| {
+            package_dirs
+                .par_iter()
+                .filter_map(|(dir, scope)| {
+                    let mut report = verify_package(dir, &dirs.cache, architecture)?;
+                    report.scope = *scope;
+                    Some(report)
+                })
+                .collect()
+        });
+        reports.sort_by(|a, b| a.package.to_lowercase().cmp(&b.package.to_lowercase()));
+        reports
+    })
+    .await
+    .map_err(|e| format!("Failed to verify packages: {}", e))?;
+
+    let modified: Vec<&str> = reports
+        .iter()
+        .filter(|r| r.status == IntegrityStatus::Modified)
+        .map(|r| r.package.as_str())
+        .collect();
+    if modified.is_empty() {
+        log::info!("Verified {} packages, none modified", reports.len());
+    } else {
+        log::warn!(
+            "{} of {} packages differ from their manifest hashes: {}",
+            modified.len(),
+            reports.len(),
+            modified.join(", ")
+        );
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScoopFixture;
+
+    #[test]
+    fn detects_modified_installed_and_cached_files() {
+        let fixture = ScoopFixture::new();
+        let sha = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let url = "https://example.com/tool.exe";
+        fixture.install("tool", "1.0", "main");
+        let version_dir = fixture.installed_manifest(
+            "tool",
+            "1.0",
+            serde_json::json!({ "version": "1.0", "url": url, "hash": sha }),
+        );
+        fs::write(version_dir.join("tool.exe"), b"hello").unwrap();
+        let cache = fixture.cache_dir();
+        fs::write(cache.join(cache_file_name("tool", "1.0", url)), b"hello").unwrap();
+
+        let report = verify_package(&fixture.app_dir("tool"), &cache, "64bit").unwrap();
+        assert_eq!(report.status, IntegrityStatus::Verified);
+        assert_eq!(report.files.len(), 2);
+
+        fs::write(version_dir.join("tool.exe"), b"tampered").unwrap();
+        let report = verify_package(&fixture.app_dir("tool"), &cache, "64bit").unwrap();
+        assert_eq!(report.status, IntegrityStatus::Modified);
+        assert!(report
+            .files
+            .iter()
+            .any(|f| !f.cached && f.result == FileCheck::Mismatch));
+    }
+}
@@ -0,0 +1,192 @@
+//! Scoop's list of official ("known") buckets.
+//!
+//! The list is read from `buckets.json` in the Scoop repository so new official buckets
+//! show up without an app release. It is cached in memory and on disk, and a stale copy
+//! is preferred over nothing when GitHub cannot be reached. A failed download is not
+//! retried for a few minutes, so callers offline do not each wait for it to time out.
+use super::bucket_parser::get_cache_dir;
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+const KNOWN_BUCKETS_URL: &str =
+    "https://raw.githubusercontent.com/ScoopInstaller/Scoop/master/buckets.json";
+/// How long a downloaded list is used before it is fetched again.
+const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// How long after a failed download no new one is attempted.
+const FAILURE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Bucket name to repository URL, along with when it was fetched.
+type KnownBuckets = (SystemTime, BTreeMap<String, String>);
+
+#[derive(Default)]
+struct KnownBucketsCache {
+    list: Option<KnownBuckets>,
+    /// Whether the copy on disk has been read into `list`.
+    loaded: bool,
+    /// When the last download failed.
+    failed_at: Option<Instant>,
+}
+
+/// Only held briefly, never across the download.
+static KNOWN_BUCKETS: Lazy<Mutex<KnownBucketsCache>> =
+    Lazy::new(|| Mutex::new(KnownBucketsCache::default()));
+
+fn lock_cache() -> std::sync::MutexGuard<'static, KnownBucketsCache> {
+    KNOWN_BUCKETS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn get_cache_file_path() -> Result<PathBuf, String> {
+    Ok(get_cache_dir()?.join("known_buckets.json"))
+}
+
+fn is_fresh(fetched_at: SystemTime) -> bool {
+    fetched_at
+        .elapsed()
+        .map(|age| age < CACHE_TTL)
+        .unwrap_or(false)
+}
+
+fn parse_known_buckets(content: &str) -> Result<BTreeMap<String, String>, String> {
+    let buckets: BTreeMap<String, String> = serde_json::from_str(content)
+        .map_err(|e| format!("Failed to parse known buckets list: {}", e))?;
+    if buckets.is_empty() {
+        return Err("Known buckets list is empty".to_string());
+    }
+    Ok(buckets)
+}
+
+async fn load_from_disk() -> Option<KnownBuckets> {
+    let path = get_cache_file_path().ok()?;
+    let fetched_at = tokio::fs::metadata(&path).await.ok()?.modified().ok()?;
+    let content = tokio::fs::read_to_string(&path).await.ok()?;
+    match parse_known_buckets(&content) {
+        Ok(buckets) => Some((fetched_at, buckets)),
+        Err(e) => {
+            log::warn!("Ignoring cached known buckets list: {}", e);
+            None
+        }
+    }
+}
+
+async fn fetch_known_buckets() -> Result<BTreeMap<String, String>, String> {
+    log::info!("Fetching known buckets list from {}", KNOWN_BUCKETS_URL);
+    let content = crate::http::get(KNOWN_BUCKETS_URL)
+        .await
+        .and_then(|response| response.error_for_status().map_err(|e| e.to_string()))
+        .map_err(|e| format!("Failed to fetch known buckets list: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read known buckets list: {}", e))?;
+
+    let buckets = parse_known_buckets(&content)?;
+    match get_cache_file_path() {
+        Ok(path) => {
+            if let Err(e) = tokio::fs::write(&path, &content).await {
+                log::warn!("Failed to cache known buckets list: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Failed to cache known buckets list: {}", e),
+    }
+    Ok(buckets)
+}
+
+/// Returns the official buckets as a map of name to repository URL.
+///
+/// Returns `None` only when the list has never been downloaded and cannot be fetched
+/// now; callers then fall back to their bundled list.
+pub async fn get_known_buckets() -> Option<BTreeMap<String, String>> {
+    if !lock_cache().loaded {
+        let from_disk = load_from_disk().await;
+        let mut cache = lock_cache();
+        if !cache.loaded {
+            cache.list = cache.list.take().or(from_disk);
+            cache.loaded = true;
+        }
+    }
+
+    let stale = {
+        let cache = lock_cache();
+        if let Some((fetched_at, buckets)) = &cache.list {
+            if is_fresh(*fetched_at) {
+                return Some(buckets.clone());
+            }
+        }
+        let stale = cache.list.as_ref().map(|(_, buckets)| buckets.clone());
+        if cache.failed_at.is_some_and(|at| at.elapsed() < FAILURE_TTL) {
+            return stale;
+        }
+        stale
+    };
+
+    match fetch_known_buckets().await {
+        Ok(buckets) => {
+            log::info!("Fetched {} known buckets", buckets.len());
+            let mut cache = lock_cache();
+            cache.list = Some((SystemTime::now(), buckets.clone()));
+            cache.failed_at = None;
+            Some(buckets)
+        }
+        Err(e) => {
+            log::warn!("{}; using cached or bundled known buckets", e);
+            lock_cache().failed_at = Some(Instant::now());
+            stale
+        }
+    }
+}
+
+/// Drops the cached list so the next call downloads it again.
+pub async fn clear_known_buckets_cache() {
+    *lock_cache() = KnownBucketsCache {
+        loaded: true,
+        ..KnownBucketsCache::default()
+    };
+    if let Ok(path) = get_cache_file_path() {
+        if path.exists() {
+            if let Err(e) = tokio::fs::remove_file(&path).await {
+                log::warn!("Failed to remove known buckets cache: {}", e);
+            }
+        }
+    }
+}
+
+/// Derives the `owner/repo` name from a repository URL.
+pub fn repo_full_name(url: &str) -> String {
+    let path = url
+        .trim()
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .splitn(4, '/')
+        .nth(3)
+        .unwrap_or_default();
+    path.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_buckets_json() {
+        let buckets = parse_known_buckets(
+            r#"{"main": "https://github.com/ScoopInstaller/Main", "extras": "https://github.com/ScoopInstaller/Extras"}"#,
+        )
+        .unwrap();
+        assert_eq!(buckets.len(), 2);
+        assert!(parse_known_buckets("{}").is_err());
+    }
+
+    #[test]
+    fn derives_full_name_from_url() {
+        assert_eq!(
+            repo_full_name("https://github.com/niheaven/scoop-sysinternals.git"),
+            "niheaven/scoop-sysinternals"
+        );
+        assert_eq!(
+            repo_full_name("https://github.com/ScoopInstaller/Main/"),
+            "ScoopInstaller/Main"
+        );
+    }
+}
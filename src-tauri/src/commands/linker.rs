@@ -1,7 +1,10 @@
+use crate::commands::quarantine::is_quarantined;
+use crate::engine::manifest::find_manifest_candidates;
 use crate::state::AppState;
+use crate::utils::validate_dir_name;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tauri::State;
 use tokio::time::{sleep, Duration};
 
@@ -205,8 +208,21 @@ pub async fn switch_package_version(
         scoop_path.join("apps")
     };
 
-    let package_dir = apps_dir.join(&package_name);
-    let target_version_dir = package_dir.join(&target_version);
+    switch_to_version(&apps_dir.join(&package_name), &package_name, &target_version).await?;
+
+    Ok(format!(
+        "Successfully switched '{}' to version '{}'",
+        package_name, target_version
+    ))
+}
+
+/// Points the `current` junction in `package_dir` at an installed version.
+pub async fn switch_to_version(
+    package_dir: &Path,
+    package_name: &str,
+    target_version: &str,
+) -> Result<(), String> {
+    let target_version_dir = package_dir.join(target_version);
     let current_link = package_dir.join("current");
 
     // Validate that the package exists
@@ -227,17 +243,13 @@ pub async fn switch_package_version(
     if let Err(e) = result {
         return Err(format!("Failed to switch version junction: {}", e));
     }
-
-    Ok(format!(
-        "Successfully switched '{}' to version '{}'",
-        package_name, target_version
-    ))
+    Ok(())
 }
 
 /// Use direct Windows commands to switch junctions efficiently
 async fn switch_junction_direct(current_link: &Path, target_dir: &Path) -> Result<(), String> {
-    // Remove existing junction if it exists
-    if current_link.exists() {
+    // Remove existing junction if it exists, dangling ones included
+    if fs::symlink_metadata(current_link).is_ok() {
         remove_junction(current_link).await?;
     }
 
@@ -251,8 +263,8 @@ async fn switch_junction_direct(current_link: &Path, target_dir: &Path) -> Resul
 async fn remove_junction(junction_path: &Path) -> Result<(), String> {
     let junction_str = junction_path.to_string_lossy().replace('/', "\\");
 
-    // First check if the path exists
-    if !junction_path.exists() {
+    // First check if the path exists; a dangling junction still has to be removed
+    if fs::symlink_metadata(junction_path).is_err() {
         log::info!(
             "Junction {} does not exist, nothing to remove",
             junction_str
@@ -366,7 +378,7 @@ async fn remove_junction(junction_path: &Path) -> Result<(), String> {
 }
 
 /// Create a directory junction using Windows mklink command
-async fn create_junction(junction_path: &Path, target_path: &Path) -> Result<(), String> {
+pub async fn create_junction(junction_path: &Path, target_path: &Path) -> Result<(), String> {
     let junction_str = junction_path.to_string_lossy().replace('/', "\\");
     let target_str = target_path.to_string_lossy().replace('/', "\\");
 
@@ -585,7 +597,94 @@ pub async fn debug_package_structure(
     Ok(debug_info.join("\n"))
 }
 
+/// Lists the version directories of a package that carry an `install.json`.
+///
+/// Falls back to `current` when it is a plain directory rather than a link, which
+/// happens on file systems without junction support.
+fn install_json_dirs(package_dir: &Path) -> Vec<std::path::PathBuf> {
+    let mut dirs: Vec<std::path::PathBuf> = fs::read_dir(package_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_dir()
+                        && path.file_name().map_or(false, |n| n != "current")
+                        && !is_quarantined(path)
+                        && path.join("install.json").is_file()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if dirs.is_empty() && package_dir.join("current").join("install.json").is_file() {
+        dirs.push(package_dir.join("current"));
+    }
+    dirs.sort();
+    dirs
+}
+
+/// Sets the `bucket` field in every `install.json` of a package.
+///
+/// Each original file is kept as `install.json.bak`. All files are parsed before any is
+/// written, and files already rewritten are restored if a later write fails, so the
+/// version directories never disagree about the bucket.
+pub fn rewrite_install_bucket(package_dir: &Path, new_bucket: &str) -> Result<usize, String> {
+    let dirs = install_json_dirs(package_dir);
+    if dirs.is_empty() {
+        return Err(format!(
+            "No install.json found under '{}'",
+            package_dir.display()
+        ));
+    }
+
+    let mut rewrites = Vec::with_capacity(dirs.len());
+    for dir in &dirs {
+        let path = dir.join("install.json");
+        let original = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut install_data: serde_json::Value = serde_json::from_str(&original)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        let obj = install_data
+            .as_object_mut()
+            .ok_or_else(|| format!("{} is not a valid JSON object", path.display()))?;
+        obj.insert(
+            "bucket".to_string(),
+            serde_json::Value::String(new_bucket.to_string()),
+        );
+        let updated = serde_json::to_string_pretty(&install_data)
+            .map_err(|e| format!("Failed to serialize updated install.json: {}", e))?;
+        rewrites.push((path, original, updated));
+    }
+
+    for (index, (path, original, updated)) in rewrites.iter().enumerate() {
+        let written = fs::write(path.with_extension("json.bak"), original)
+            .map_err(|e| format!("Failed to back up {}: {}", path.display(), e))
+            .and_then(|_| {
+                fs::write(path, updated)
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+            });
+        if let Err(e) = written {
+            for (restore_path, restore_content, _) in &rewrites[..index] {
+                if let Err(restore_err) = fs::write(restore_path, restore_content) {
+                    log::error!(
+                        "Failed to restore {}: {}",
+                        restore_path.display(),
+                        restore_err
+                    );
+                }
+            }
+            return Err(e);
+        }
+    }
+
+    Ok(rewrites.len())
+}
+
 /// Change the bucket of an installed package by modifying its install.json
+///
+/// The new bucket must provide a manifest for the package. Every installed version is
+/// updated so switching versions later keeps the new bucket.
 #[tauri::command]
 pub async fn change_package_bucket(
     state: State<'_, AppState>,
@@ -593,81 +692,261 @@ pub async fn change_package_bucket(
     new_bucket: String,
 ) -> Result<String, String> {
     let scoop_path = state.scoop_path();
-    let apps_dir = scoop_path.join("apps");
-    let package_dir = apps_dir.join(&package_name);
+    let package_dir = scoop_path.join("apps").join(&package_name);
 
     if !package_dir.exists() {
         return Err(format!("Package '{}' is not installed", package_name));
     }
 
-    // Find the current installation directory (either "current" or latest version)
-    let install_dir = {
-        let current_path = package_dir.join("current");
-        if current_path.exists() && current_path.is_dir() {
-            current_path
+    let bucket = find_manifest_candidates(&scoop_path, &package_name)
+        .into_iter()
+        .map(|(_, bucket)| bucket)
+        .find(|bucket| bucket.eq_ignore_ascii_case(&new_bucket))
+        .ok_or_else(|| {
+            format!(
+                "Bucket '{}' does not provide a manifest for '{}'",
+                new_bucket, package_name
+            )
+        })?;
+
+    let updated = rewrite_install_bucket(&package_dir, &bucket)?;
+    log::info!(
+        "Changed bucket of '{}' to '{}' in {} install.json file(s)",
+        package_name,
+        bucket,
+        updated
+    );
+    crate::commands::installed::invalidate_installed_cache(state).await;
+
+    Ok(format!(
+        "Successfully changed bucket for '{}' to '{}'",
+        package_name, bucket
+    ))
+}
+
+/// Copies a version directory without following the links inside it.
+///
+/// Directory links, which is how Scoop persists folders, are not copied but returned
+/// as `(link, target)` pairs to be recreated as junctions. Files that also exist under
+/// `persist_dir` are hard linked to the persisted file again, so the copy shares the
+/// persisted data instead of forking it.
+fn copy_version_tree(
+    source: &Path,
+    destination: &Path,
+    persist_dir: &Path,
+) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    let mut junctions = Vec::new();
+    fs::create_dir_all(destination)
+        .map_err(|e| format!("Failed to create {}: {}", destination.display(), e))?;
+    let entries =
+        fs::read_dir(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let target = destination.join(entry.file_name());
+        let persisted = persist_dir.join(entry.file_name());
+        let file_type = entry
+            .file_type()
+            .map_err(|e| format!("Failed to inspect {}: {}", path.display(), e))?;
+
+        if file_type.is_symlink() && path.is_dir() {
+            let link_target = fs::read_link(&path)
+                .map_err(|e| format!("Failed to read link {}: {}", path.display(), e))?;
+            junctions.push((target, link_target));
+        } else if path.is_dir() {
+            junctions.extend(copy_version_tree(&path, &target, &persisted)?);
+        } else if persisted.is_file() {
+            fs::hard_link(&persisted, &target)
+                .map_err(|e| format!("Failed to link {}: {}", target.display(), e))?;
         } else {
-            // Find the latest version directory
-            let mut candidates = Vec::new();
-            
-            if let Ok(entries) = fs::read_dir(&package_dir) {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-                    if path.is_dir() {
-                        if let Some(name) = path.file_name() {
-                            // Skip "current" directory
-                            if name.to_string_lossy() == "current" {
-                                continue;
-                            }
-                            
-                            // Check if it's a version directory (has install.json or manifest.json)
-                            let install_json = path.join("install.json");
-                            let manifest_json = path.join("manifest.json");
-                            
-                            if install_json.exists() || manifest_json.exists() {
-                                if let Ok(metadata) = fs::metadata(&path) {
-                                    if let Ok(modified) = metadata.modified() {
-                                        candidates.push((modified, path));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            
-            // Sort by modification time and get the latest
-            candidates.sort_by(|a, b| b.0.cmp(&a.0));
-            candidates.into_iter().next().map(|(_, path)| path)
-                .ok_or_else(|| format!("Could not find installation directory for package '{}'", package_name))?
+            fs::copy(&path, &target)
+                .map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
         }
-    };
+    }
+    Ok(junctions)
+}
 
-    // Read the install.json file
-    let install_json_path = install_dir.join("install.json");
-    if !install_json_path.exists() {
-        return Err(format!("install.json not found for package '{}'", package_name));
+/// Copies an installed version of a package to a new version directory.
+///
+/// Useful to keep a backup before a risky update or to try a modified build; the
+/// copy shows up in the versions list and can be switched to like any other version.
+/// Persisted folders and files are linked to the persist directory again.
+#[tauri::command]
+pub async fn duplicate_version(
+    state: State<'_, AppState>,
+    package_name: String,
+    source_version: String,
+    new_version: String,
+) -> Result<PackageVersion, String> {
+    validate_dir_name("package", &package_name)?;
+    validate_dir_name("version", &source_version)?;
+    validate_dir_name("version", &new_version)?;
+    if new_version.eq_ignore_ascii_case("current") {
+        return Err(format!("Invalid version name '{}'", new_version));
     }
 
-    let install_json_content = fs::read_to_string(&install_json_path)
-        .map_err(|e| format!("Failed to read install.json: {}", e))?;
+    // Don't copy while a Scoop operation may be changing the package, and check the
+    // destination under the lock so two copies cannot race for it
+    let _queue = state.operation_queue.lock().await;
+    let dirs = state.scoop_dirs();
+    let package_dir = dirs.apps.join(&package_name);
+    let source = package_dir.join(&source_version);
+    let destination = package_dir.join(&new_version);
+    if !source.is_dir() {
+        return Err(format!(
+            "Version '{}' of package '{}' is not installed",
+            source_version, package_name
+        ));
+    }
+    if fs::symlink_metadata(&destination).is_ok() {
+        return Err(format!(
+            "Version '{}' of package '{}' already exists",
+            new_version, package_name
+        ));
+    }
 
-    // Parse the JSON
-    let mut install_data: serde_json::Value = serde_json::from_str(&install_json_content)
-        .map_err(|e| format!("Failed to parse install.json: {}", e))?;
+    let persist_dir = dirs.persist.join(&package_name);
+    let copy_from = source.clone();
+    let copy_to = destination.clone();
+    let mut result =
+        tokio::task::spawn_blocking(move || copy_version_tree(&copy_from, &copy_to, &persist_dir))
+            .await
+            .map_err(|e| format!("Failed to copy version: {}", e))?;
+    if let Ok(junctions) = &result {
+        for (link, target) in junctions {
+            if let Err(e) = create_junction(link, target).await {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+    if let Err(e) = result {
+        log::warn!(
+            "Duplicating '{}' {} failed, removing the partial copy: {}",
+            package_name,
+            source_version,
+            e
+        );
+        let _ = fs::remove_dir_all(&destination);
+        return Err(e);
+    }
 
-    // Update the bucket field
-    if let Some(obj) = install_data.as_object_mut() {
-        obj.insert("bucket".to_string(), serde_json::Value::String(new_bucket.clone()));
-    } else {
-        return Err("install.json is not a valid JSON object".to_string());
+    *state.package_versions.lock().await = None;
+    log::info!(
+        "Duplicated '{}' version {} as {}",
+        package_name,
+        source_version,
+        new_version
+    );
+    Ok(PackageVersion {
+        version: new_version,
+        is_current: false,
+        install_path: destination.to_string_lossy().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{create_dir_link, read_json, write_file, ScoopFixture};
+
+    #[test]
+    fn version_directories_need_scoop_metadata() {
+        let fixture = ScoopFixture::new();
+        let version_dir = fixture.app_version("git", "2.44.0", Some("main"));
+        let stray_dir = fixture.app_dir("git").join("logs");
+        fs::create_dir_all(&stray_dir).unwrap();
+
+        assert!(is_version_directory(&version_dir));
+        assert!(!is_version_directory(&stray_dir));
+    }
+
+    #[tokio::test]
+    async fn versioned_info_reports_linked_version_first() {
+        let fixture = ScoopFixture::new();
+        fixture.app_version("python", "3.11.8", None);
+        fixture.app_version("python", "3.12.1", None);
+        fixture.app_version("python", "3.10.11", None);
+        if !fixture.link_current("python", "3.11.8") {
+            return;
+        }
+
+        let versions = vec![
+            "3.10.11".to_string(),
+            "3.11.8".to_string(),
+            "3.12.1".to_string(),
+        ];
+        let info = build_versioned_package_info(fixture.root(), "python", versions)
+            .await
+            .unwrap();
+
+        assert_eq!(info.current_version, "3.11.8");
+        let order: Vec<&str> = info
+            .available_versions
+            .iter()
+            .map(|v| v.version.as_str())
+            .collect();
+        assert_eq!(order, ["3.11.8", "3.12.1", "3.10.11"]);
+        assert!(info.available_versions[0].is_current);
     }
 
-    // Write back to the file
-    let updated_content = serde_json::to_string_pretty(&install_data)
-        .map_err(|e| format!("Failed to serialize updated install.json: {}", e))?;
+    #[test]
+    fn bucket_change_rewrites_every_version_with_backup() {
+        let fixture = ScoopFixture::new();
+        fixture.app_version("git", "2.43.0", Some("main"));
+        fixture.install("git", "2.44.0", "main");
+
+        let updated = rewrite_install_bucket(&fixture.app_dir("git"), "versions").unwrap();
+        assert_eq!(updated, 2);
+
+        for version in ["2.43.0", "2.44.0"] {
+            let dir = fixture.app_dir("git").join(version);
+            let install = read_json(&dir.join("install.json"));
+            assert_eq!(install["bucket"], "versions");
+            assert_eq!(install["architecture"], "64bit");
+            assert_eq!(read_json(&dir.join("install.json.bak"))["bucket"], "main");
+        }
+    }
 
-    fs::write(&install_json_path, updated_content)
-        .map_err(|e| format!("Failed to write updated install.json: {}", e))?;
+    #[test]
+    fn bucket_change_leaves_files_untouched_on_invalid_json() {
+        let fixture = ScoopFixture::new();
+        let good = fixture.app_version("git", "2.43.0", Some("main"));
+        fixture.app_version("git", "2.44.0", Some("main"));
+        fixture.corrupt_install_info("git", "2.44.0");
+
+        assert!(rewrite_install_bucket(&fixture.app_dir("git"), "versions").is_err());
+        assert!(fs::read_to_string(good.join("install.json"))
+            .unwrap()
+            .contains("\"main\""));
+        assert!(!good.join("install.json.bak").exists());
+    }
 
-    Ok(format!("Successfully changed bucket for '{}' to '{}'", package_name, new_bucket))
-}
\ No newline at end of file
+    #[test]
+    fn duplicating_a_version_keeps_persisted_data_linked() {
+        let fixture = ScoopFixture::new();
+        let source = fixture.app_version("notepad3", "6.23.0", Some("extras"));
+        let persist = fixture.persist_dir("notepad3");
+        write_file(&source.join("themes").join("dark.ini"), "dark");
+        fs::create_dir_all(persist.join("data")).unwrap();
+        write_file(&persist.join("Notepad3.ini"), "settings");
+        write_file(&source.join("Notepad3.ini"), "settings");
+        let linked = create_dir_link(&source.join("data"), &persist.join("data"));
+
+        let copy = fixture.app_dir("notepad3").join("6.23.0-backup");
+        let junctions = copy_version_tree(&source, &copy, &persist).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(copy.join("themes").join("dark.ini")).unwrap(),
+            "dark"
+        );
+        fs::write(persist.join("Notepad3.ini"), "changed").unwrap();
+        assert_eq!(
+            fs::read_to_string(copy.join("Notepad3.ini")).unwrap(),
+            "changed"
+        );
+        if linked {
+            assert_eq!(junctions, vec![(copy.join("data"), persist.join("data"))]);
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! Commands for viewing, exporting and clearing the local operation metrics.
+use crate::metrics;
+use crate::models::{CommandTiming, MetricsSnapshot};
+use crate::state::AppState;
+use std::time::Duration;
+use tauri::State;
+
+#[tauri::command]
+pub fn get_metrics() -> MetricsSnapshot {
+    metrics::snapshot()
+}
+
+/// Writes the collected metrics as JSON to `path`, for attaching to a bug report.
+#[tauri::command]
+pub fn export_metrics(path: String) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(&metrics::snapshot())
+        .map_err(|e| format!("Failed to serialize metrics: {}", e))?;
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    log::info!("Exported metrics to {}", path);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn clear_metrics() {
+    metrics::clear();
+    log::info!("Cleared local metrics");
+}
+
+/// Records IPC calls timed by the frontend, from `invoke` until the promise settled.
+#[tauri::command]
+pub fn record_command_timings(timings: Vec<CommandTiming>, state: State<'_, AppState>) {
+    for timing in timings {
+        let duration = Duration::try_from_secs_f64(timing.duration_ms / 1000.0).unwrap_or_default();
+        state.record_command(&timing.command, duration, timing.success);
+    }
+}
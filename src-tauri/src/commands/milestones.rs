@@ -0,0 +1,267 @@
+//! Coarse progress milestones of long operations.
+//!
+//! Raw Scoop output is too noisy to announce, so it is condensed into milestones such
+//! as "Updating 3 of 12: git" or "Extracting git (3/12 packages)", emitted as
+//! `operation-milestone` next to the output lines and saved in the operation's log
+//! summary. The UI and screen-reader summaries describe progress from these.
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Runtime};
+
+pub const EVENT_MILESTONE: &str = "operation-milestone";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum MilestoneStage {
+    /// Work on the next package began.
+    Started,
+    Loading,
+    Downloading,
+    Verifying,
+    Extracting,
+    /// Running an installer or install script.
+    Installing,
+    Linking,
+    CreatingShims,
+    Persisting,
+    Done,
+}
+
+impl MilestoneStage {
+    fn describe(self) -> &'static str {
+        match self {
+            MilestoneStage::Started => "Starting",
+            MilestoneStage::Loading => "Loading",
+            MilestoneStage::Downloading => "Downloading",
+            MilestoneStage::Verifying => "Checking hash of",
+            MilestoneStage::Extracting => "Extracting",
+            MilestoneStage::Installing => "Running installer for",
+            MilestoneStage::Linking => "Linking",
+            MilestoneStage::CreatingShims => "Creating shims for",
+            MilestoneStage::Persisting => "Persisting data for",
+            MilestoneStage::Done => "Finished",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationMilestone {
+    #[serde(default)]
+    pub operation_id: Option<String>,
+    pub stage: MilestoneStage,
+    pub package: Option<String>,
+    /// 1-based position of `package` among the packages of the operation.
+    pub current: Option<usize>,
+    pub total: Option<usize>,
+    /// A plain sentence describing the milestone.
+    pub summary: String,
+    pub at: String,
+}
+
+impl OperationMilestone {
+    pub fn new(operation_id: Option<String>, stage: MilestoneStage, summary: String) -> Self {
+        OperationMilestone {
+            operation_id,
+            stage,
+            package: None,
+            current: None,
+            total: None,
+            summary,
+            at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// What a line of Scoop output says about progress.
+#[derive(Debug, PartialEq)]
+enum ScoopLine<'a> {
+    /// `Updating 12 outdated apps:`
+    Total(usize),
+    /// `Installing 'git' (2.45.0) ...`, with the verb.
+    Package(&'a str, &'a str),
+    Stage(MilestoneStage),
+}
+
+/// The name quoted at the start of `rest`, as in `'git' (2.45.0)`.
+fn quoted_name(rest: &str) -> Option<&str> {
+    let rest = rest.strip_prefix('\'')?;
+    rest.split('\'').next().filter(|name| !name.is_empty())
+}
+
+fn classify(line: &str) -> Option<ScoopLine<'_>> {
+    let line = line.trim();
+    if let Some(count) = line
+        .strip_prefix("Updating ")
+        .and_then(|rest| rest.strip_suffix(':'))
+        .and_then(|rest| rest.split(' ').next())
+        .filter(|_| line.contains(" outdated app"))
+    {
+        let total = if count == "one" {
+            Some(1)
+        } else {
+            count.parse().ok()
+        };
+        return total.map(ScoopLine::Total);
+    }
+    for verb in ["Updating", "Installing", "Uninstalling"] {
+        if let Some(name) = line
+            .strip_prefix(verb)
+            .and_then(|rest| quoted_name(rest.trim_start()))
+        {
+            return Some(ScoopLine::Package(verb, name));
+        }
+    }
+    let stage = if line.starts_with("Downloading ")
+        || (line.starts_with("Loading ") && line.contains("from cache"))
+    {
+        MilestoneStage::Downloading
+    } else if line.starts_with("Checking hash of ") {
+        MilestoneStage::Verifying
+    } else if line.starts_with("Extracting ") {
+        MilestoneStage::Extracting
+    } else if line.starts_with("Running ")
+        && (line.contains("installer") || line.contains("script"))
+    {
+        MilestoneStage::Installing
+    } else if line.starts_with("Linking ") {
+        MilestoneStage::Linking
+    } else if line.starts_with("Creating shim for ") {
+        MilestoneStage::CreatingShims
+    } else if line.starts_with("Persisting ") {
+        MilestoneStage::Persisting
+    } else {
+        return None;
+    };
+    Some(ScoopLine::Stage(stage))
+}
+
+/// Turns the output of one operation into milestones, reporting each stage of each
+/// package once.
+#[derive(Debug, Default)]
+pub struct MilestoneTracker {
+    total: Option<usize>,
+    current: usize,
+    verb: Option<String>,
+    package: Option<String>,
+    last: Option<(MilestoneStage, usize)>,
+}
+
+impl MilestoneTracker {
+    /// The milestone `line` reaches, if it is a new one.
+    pub fn observe(
+        &mut self,
+        line: &str,
+        operation_id: Option<&str>,
+    ) -> Option<OperationMilestone> {
+        let stage = match classify(line)? {
+            ScoopLine::Total(total) => {
+                self.total = Some(total);
+                return None;
+            }
+            ScoopLine::Package(verb, name) => {
+                self.current += 1;
+                self.verb = Some(verb.to_string());
+                self.package = Some(name.to_string());
+                MilestoneStage::Started
+            }
+            ScoopLine::Stage(stage) => stage,
+        };
+        if self.last == Some((stage, self.current)) {
+            return None;
+        }
+        self.last = Some((stage, self.current));
+
+        let package = self.package.as_deref().unwrap_or("package");
+        let summary = match (stage, self.total) {
+            (MilestoneStage::Started, Some(total)) => format!(
+                "{} {} of {}: {}",
+                self.verb.as_deref().unwrap_or("Processing"),
+                self.current,
+                total,
+                package
+            ),
+            (MilestoneStage::Started, None) => {
+                format!(
+                    "{} {}",
+                    self.verb.as_deref().unwrap_or("Processing"),
+                    package
+                )
+            }
+            (stage, Some(total)) => format!(
+                "{} {} ({}/{} packages)",
+                stage.describe(),
+                package,
+                self.current,
+                total
+            ),
+            (stage, None) => format!("{} {}", stage.describe(), package),
+        };
+        Some(OperationMilestone {
+            package: self.package.clone(),
+            current: (self.current > 0).then_some(self.current),
+            total: self.total,
+            ..OperationMilestone::new(operation_id.map(String::from), stage, summary)
+        })
+    }
+}
+
+/// Emits a milestone to the UI.
+pub fn emit_milestone<R: Runtime>(emitter: &impl Emitter<R>, milestone: &OperationMilestone) {
+    log::debug!("Milestone: {}", milestone.summary);
+    if let Err(e) = emitter.emit(EVENT_MILESTONE, milestone) {
+        log::warn!("Failed to emit {} event: {}", EVENT_MILESTONE, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn condenses_update_output_into_milestones() {
+        let output = [
+            "Updating Scoop...",
+            "Updating 2 outdated apps:",
+            "Updating 'git' (2.44.0 -> 2.45.0)",
+            "Downloading new version",
+            "Downloading https://example.com/git.7z (60 MB)...",
+            "Checking hash of git.7z ... ok.",
+            "Extracting git.7z ... done.",
+            "Linking ~\\scoop\\apps\\git\\current => ~\\scoop\\apps\\git\\2.45.0",
+            "Creating shim for 'git'.",
+            "Creating shim for 'git-bash'.",
+            "'git' (2.45.0) was installed successfully!",
+            "Updating 'jq' (1.6 -> 1.7)",
+            "Loading jq.exe from cache",
+        ];
+        let mut tracker = MilestoneTracker::default();
+        let milestones: Vec<OperationMilestone> = output
+            .iter()
+            .filter_map(|line| tracker.observe(line, Some("op-1")))
+            .collect();
+        let summaries: Vec<&str> = milestones.iter().map(|m| m.summary.as_str()).collect();
+        assert_eq!(
+            summaries,
+            vec![
+                "Updating 1 of 2: git",
+                "Downloading git (1/2 packages)",
+                "Checking hash of git (1/2 packages)",
+                "Extracting git (1/2 packages)",
+                "Linking git (1/2 packages)",
+                "Creating shims for git (1/2 packages)",
+                "Updating 2 of 2: jq",
+                "Downloading jq (2/2 packages)",
+            ]
+        );
+        assert_eq!(milestones[6].stage, MilestoneStage::Started);
+        assert_eq!(
+            (milestones[6].current, milestones[6].total),
+            (Some(2), Some(2))
+        );
+        assert_eq!(milestones[0].operation_id.as_deref(), Some("op-1"));
+
+        let mut single = MilestoneTracker::default();
+        let started = single.observe("Installing 'fd' (9.0.0) [64bit] from 'main' bucket", None);
+        assert_eq!(started.unwrap().summary, "Installing fd");
+    }
+}
@@ -0,0 +1,67 @@
+//! Commands for managing download mirror rules.
+//!
+//! Rules rewrite the URLs the app downloads itself and are shown per package so users
+//! can check what a mirror would be asked for. Installs run by the Scoop CLI download
+//! through Scoop and are not rewritten.
+use crate::commands::repair::host_architecture;
+use crate::commands::settings;
+use crate::config_keys::NETWORK_MIRROR_RULES;
+use crate::engine::manifest::locate_package_manifest;
+use crate::engine::mirror::{download_urls, MirrorRules};
+use crate::models::{MirrorRewrite, MirrorRule};
+use crate::state::AppState;
+use serde_json::Value;
+use tauri::{AppHandle, Runtime, State};
+
+/// Reads the saved mirror rules, in the order they are applied.
+pub fn load_mirror_rules<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<MirrorRule>, String> {
+    match settings::get_config_value(app.clone(), NETWORK_MIRROR_RULES.to_string())? {
+        Some(value) => {
+            serde_json::from_value(value).map_err(|e| format!("Failed to read mirror rules: {}", e))
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+#[tauri::command]
+pub fn get_mirror_rules(app: AppHandle) -> Result<Vec<MirrorRule>, String> {
+    load_mirror_rules(&app)
+}
+
+/// Replaces the mirror rules; they take effect for the next download.
+#[tauri::command]
+pub fn set_mirror_rules(app: AppHandle, rules: Vec<MirrorRule>) -> Result<(), String> {
+    // Reject invalid patterns before they are persisted
+    MirrorRules::compile(&rules)?;
+
+    let value = serde_json::to_value(&rules)
+        .map_err(|e| format!("Failed to serialize mirror rules: {}", e))?;
+    settings::set_config_value(app, NETWORK_MIRROR_RULES.to_string(), value)?;
+    log::info!("Saved {} mirror rule(s)", rules.len());
+    Ok(())
+}
+
+/// Shows how the current mirror rules rewrite a package's download URLs for this
+/// machine's architecture.
+#[tauri::command]
+pub fn preview_mirror_rewrites(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    package_name: String,
+    bucket: Option<String>,
+) -> Result<Vec<MirrorRewrite>, String> {
+    let rules = MirrorRules::compile(&load_mirror_rules(&app)?)?;
+    let (manifest_path, _) = locate_package_manifest(&state.scoop_path(), &package_name, bucket)?;
+    let content = std::fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("Failed to read manifest for {}: {}", package_name, e))?;
+    let manifest: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse manifest for {}: {}", package_name, e))?;
+
+    Ok(download_urls(&manifest, host_architecture())
+        .into_iter()
+        .map(|original| MirrorRewrite {
+            rewritten: rules.rewrite(&original),
+            original,
+        })
+        .collect())
+}
@@ -1,28 +1,69 @@
+pub mod activity_report;
 pub mod app_info;
 pub mod auto_cleanup;
+pub mod broken_installs;
 pub mod bucket;
+pub mod bucket_filter;
 pub mod bucket_install;
 pub mod bucket_parser;
+pub mod bucket_presets;
+pub mod bucket_rehome;
 pub mod bucket_search;
+pub mod compare;
 pub mod custom_update;
 pub mod debug;
+pub mod delta_update;
+pub mod deprecation;
 pub mod doctor;
+pub mod env_changes;
+pub mod export;
+pub mod external_scoop;
+pub mod health;
 pub mod hold;
+pub mod hooks;
+pub mod ignore_patterns;
 pub mod info;
 pub mod install;
+pub mod install_preflight;
 pub mod installed;
+pub mod installed_search;
+pub mod integrity;
+pub mod known_buckets;
 pub mod linker;
 pub mod manifest;
+pub mod metrics;
+pub mod milestones;
+pub mod mirror;
+pub mod onboarding;
+pub mod operation_log;
+pub mod package_groups;
+pub mod package_source;
+pub mod package_state;
 pub mod powershell;
+pub mod quarantine;
+pub mod readme;
+pub mod repair;
+pub mod resolver;
 pub mod scoop;
+pub mod scoop_core;
+pub mod scoop_db;
 pub mod search;
+pub mod search_history;
+pub mod search_index;
+pub mod self_cleanup;
 pub mod settings;
 pub mod startup;
+pub mod statistics;
 pub mod status;
+pub mod status_history;
 pub mod uninstall;
+pub mod uninstall_verification;
 pub mod update;
 pub mod update_config;
+pub mod update_estimate;
+pub mod update_log;
 pub mod updates;
 pub mod version;
+pub mod version_pin;
 pub mod virustotal;
 pub mod test_update;
@@ -0,0 +1,529 @@
+//! First-run onboarding.
+//!
+//! Setup is a fixed sequence of steps: detect Scoop, choose (or bootstrap) a root, pick
+//! initial buckets, import an existing `scoop export` file and set update preferences.
+//! Progress is saved after every step so the wizard resumes where the user left off,
+//! and any step except choosing a root can be skipped.
+use crate::commands::bucket_install::{install_bucket, BucketInstallOptions, BucketInstallResult};
+use crate::commands::bucket_search::get_bundled_verified_buckets;
+use crate::commands::export::pinned_manifest;
+use crate::commands::install::install_package;
+use crate::commands::installed::{get_installed_packages, invalidate_installed_cache};
+use crate::commands::known_buckets::get_known_buckets;
+use crate::commands::powershell::{self, OperationClass};
+use crate::commands::scoop::{self, ScoopOp};
+use crate::commands::settings;
+use crate::config_keys::ONBOARDING_STATE;
+use crate::engine::root::{evaluate_candidate, select_best_root};
+use crate::models::{
+    OnboardingImportResult, OnboardingState, OnboardingStep, OnboardingUpdatePreferences,
+    ScoopRootCandidateInfo,
+};
+use crate::state::AppState;
+use crate::utils::validate_dir_name;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use tauri::{AppHandle, State, Window};
+
+const SCOOP_INSTALLER_URL: &str = "https://get.scoop.sh";
+
+/// Steps in the order they are presented; `Done` follows the last one.
+const STEPS: &[OnboardingStep] = &[
+    OnboardingStep::DetectScoop,
+    OnboardingStep::ChooseRoot,
+    OnboardingStep::PickBuckets,
+    OnboardingStep::ImportExport,
+    OnboardingStep::UpdatePreferences,
+];
+
+fn load_state(app: &AppHandle) -> Result<OnboardingState, String> {
+    match settings::get_config_value(app.clone(), ONBOARDING_STATE.to_string())? {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| format!("Failed to read onboarding state: {}", e)),
+        None => Ok(OnboardingState::default()),
+    }
+}
+
+fn save_state(app: &AppHandle, state: &OnboardingState) -> Result<(), String> {
+    let value = serde_json::to_value(state)
+        .map_err(|e| format!("Failed to serialize onboarding state: {}", e))?;
+    settings::set_config_value(app.clone(), ONBOARDING_STATE.to_string(), value)
+}
+
+/// Records `step` as completed or skipped and moves on to the first unfinished step.
+fn finish_step(state: &mut OnboardingState, step: OnboardingStep, skipped: bool) {
+    state.completed_steps.retain(|s| *s != step);
+    state.skipped_steps.retain(|s| *s != step);
+    if skipped {
+        state.skipped_steps.push(step);
+    } else {
+        state.completed_steps.push(step);
+    }
+
+    state.current_step = STEPS
+        .iter()
+        .copied()
+        .find(|s| !state.completed_steps.contains(s) && !state.skipped_steps.contains(s))
+        .unwrap_or(OnboardingStep::Done);
+    if state.current_step == OnboardingStep::Done && state.completed_at.is_none() {
+        state.completed_at = Some(chrono::Utc::now().to_rfc3339());
+    }
+}
+
+/// Loads the state, applies `update` and saves it again.
+fn update_state<F>(app: &AppHandle, update: F) -> Result<OnboardingState, String>
+where
+    F: FnOnce(&mut OnboardingState),
+{
+    let mut state = load_state(app)?;
+    if state.started_at.is_none() {
+        state.started_at = Some(chrono::Utc::now().to_rfc3339());
+    }
+    update(&mut state);
+    save_state(app, &state)?;
+    Ok(state)
+}
+
+#[tauri::command]
+pub fn get_onboarding_state(app: AppHandle) -> Result<OnboardingState, String> {
+    load_state(&app)
+}
+
+/// Lists plausible Scoop roots by score, marking the one that would be picked
+/// automatically.
+#[tauri::command]
+pub fn detect_scoop_candidates(app: AppHandle) -> Result<Vec<ScoopRootCandidateInfo>, String> {
+    let stored = settings::get_scoop_path(app.clone())
+        .ok()
+        .flatten()
+        .map(PathBuf::from);
+    let candidates = crate::utils::build_candidate_list(stored.clone());
+    let best = select_best_root(candidates.clone(), stored.as_ref()).map(|best| best.path);
+
+    let mut found: Vec<ScoopRootCandidateInfo> = candidates
+        .iter()
+        .filter_map(|path| evaluate_candidate(path))
+        .map(|candidate| ScoopRootCandidateInfo {
+            recommended: best.as_ref() == Some(&candidate.path),
+            path: candidate.path.to_string_lossy().to_string(),
+            score: candidate.score,
+            installed_count: candidate.installed_count,
+            has_apps_dir: candidate.has_apps_dir,
+            has_buckets_dir: candidate.has_buckets_dir,
+        })
+        .collect();
+    found.sort_by(|a, b| {
+        b.recommended
+            .cmp(&a.recommended)
+            .then(b.score.cmp(&a.score))
+    });
+
+    update_state(&app, |s| finish_step(s, OnboardingStep::DetectScoop, false))?;
+    Ok(found)
+}
+
+/// Uses `path` as the Scoop root. The directory must already contain a Scoop install;
+/// see [`bootstrap_scoop_root`] for creating one.
+#[tauri::command]
+pub async fn choose_scoop_root(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<OnboardingState, String> {
+    if evaluate_candidate(&PathBuf::from(&path)).is_none() {
+        return Err(format!(
+            "'{}' does not contain a Scoop installation (no apps or buckets directory)",
+            path
+        ));
+    }
+
+    settings::set_scoop_path(app.clone(), path.clone())?;
+    invalidate_installed_cache(state).await;
+    log::info!("Onboarding: using Scoop root {}", path);
+
+    update_state(&app, |s| {
+        s.scoop_root = Some(path);
+        finish_step(s, OnboardingStep::ChooseRoot, false);
+    })
+}
+
+/// Downloads Scoop's installer script to a temporary file.
+async fn download_scoop_installer() -> Result<PathBuf, String> {
+    let script = crate::http::get(SCOOP_INSTALLER_URL)
+        .await
+        .and_then(|response| response.error_for_status().map_err(|e| e.to_string()))
+        .map_err(|e| format!("Failed to download the Scoop installer: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to download the Scoop installer: {}", e))?;
+    let path = std::env::temp_dir().join("pailer-scoop-install.ps1");
+    tokio::fs::write(&path, script)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Installs Scoop into `path` with the official installer, streaming its output like
+/// any other operation. Call [`choose_scoop_root`] once it has finished.
+#[tauri::command]
+pub async fn bootstrap_scoop_root(window: Window, path: String) -> Result<(), String> {
+    let path = path.trim().to_string();
+    if path.is_empty() {
+        return Err("Scoop root path cannot be empty".to_string());
+    }
+    if evaluate_candidate(&PathBuf::from(&path)).is_some() {
+        return Err(format!("'{}' already contains a Scoop installation", path));
+    }
+
+    // The installer runs as a script block, as `irm get.scoop.sh | iex` would, but the
+    // path is passed as an argument instead of being spliced into code
+    let script = download_scoop_installer().await?;
+    let command = format!(
+        "& ([scriptblock]::Create((Get-Content -Raw -LiteralPath {}))) -ScoopDir {}",
+        powershell::quote_literal(&script.to_string_lossy()),
+        powershell::quote_literal(&path)
+    );
+    scoop::run_queued_command(
+        window,
+        command,
+        format!("Installing Scoop to {}", path),
+        Some(format!(
+            "bootstrap-scoop-{}",
+            chrono::Utc::now().timestamp()
+        )),
+        OperationClass::Install,
+    )
+    .await
+}
+
+/// Adds the chosen official buckets, looked up by name in Scoop's known buckets list.
+/// Buckets that already exist are reported as unsuccessful but are not an error.
+#[tauri::command]
+pub async fn pick_initial_buckets(
+    app: AppHandle,
+    buckets: Vec<String>,
+) -> Result<Vec<BucketInstallResult>, String> {
+    // Offline, the bundled official buckets still let `main` and friends be added
+    let known = match get_known_buckets().await {
+        Some(known) => known,
+        None => get_bundled_verified_buckets()
+            .into_iter()
+            .map(|bucket| (bucket.name, bucket.url))
+            .collect(),
+    };
+    let mut results = Vec::new();
+
+    for name in &buckets {
+        let Some(url) = known.get(name) else {
+            results.push(BucketInstallResult {
+                success: false,
+                message: format!("'{}' is not a known bucket", name),
+                bucket_name: name.clone(),
+                bucket_path: None,
+                manifest_count: None,
+            });
+            continue;
+        };
+
+        let options = BucketInstallOptions {
+            name: name.clone(),
+            url: url.clone(),
+            force: false,
+        };
+        results.push(
+            install_bucket(options)
+                .await
+                .unwrap_or_else(|e| BucketInstallResult {
+                    success: false,
+                    message: e,
+                    bucket_name: name.clone(),
+                    bucket_path: None,
+                    manifest_count: None,
+                }),
+        );
+    }
+
+    update_state(&app, |s| {
+        s.selected_buckets = buckets;
+        finish_step(s, OnboardingStep::PickBuckets, false);
+    })?;
+    Ok(results)
+}
+
+/// The parts of a `scoop export` file needed to restore it.
+#[derive(Deserialize)]
+struct ScoopExport {
+    #[serde(default)]
+    buckets: Vec<ExportedBucket>,
+    #[serde(default)]
+    apps: Vec<ExportedApp>,
+}
+
+#[derive(Deserialize)]
+struct ExportedBucket {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Source")]
+    source: String,
+}
+
+#[derive(Deserialize)]
+struct ExportedApp {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Source", default)]
+    source: Option<String>,
+    #[serde(rename = "Version", default)]
+    version: Option<String>,
+    /// Manifest of the exported version, when the export embedded it.
+    #[serde(rename = "Manifest", default)]
+    manifest: Option<serde_json::Value>,
+}
+
+fn parse_scoop_export(content: &str) -> Result<ScoopExport, String> {
+    // `scoop export` writes UTF-8 with a BOM on Windows PowerShell
+    serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("Not a valid scoop export file: {}", e))
+}
+
+/// Where manifests embedded in an import are kept. Scoop records the manifest path of
+/// apps installed from a file, so it has to outlive the install.
+fn pinned_manifest_dir() -> Result<PathBuf, String> {
+    crate::portable::app_data_dir()
+        .map(|dir| dir.join("pinned-manifests"))
+        .ok_or_else(|| "Could not determine the app data directory".to_string())
+}
+
+/// Installs `name` from its embedded `manifest`, saved under [`pinned_manifest_dir`].
+async fn install_pinned(
+    window: Window,
+    name: &str,
+    manifest: &serde_json::Value,
+) -> Result<(), String> {
+    validate_dir_name("package", name)?;
+    let dir = pinned_manifest_dir()?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!("{}.json", name));
+    let content = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize manifest of {}: {}", name, e))?;
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    scoop::execute_scoop(
+        window,
+        ScoopOp::InstallManifest,
+        Some(&path.to_string_lossy()),
+        None,
+        Some(format!(
+            "install-manifest-{}-{}",
+            name,
+            chrono::Utc::now().timestamp()
+        )),
+    )
+    .await
+}
+
+/// Restores a `scoop export` file: adds its buckets, then installs its apps one by one.
+/// Apps that are already installed are skipped. An app with an embedded manifest whose
+/// version no added bucket provides any more is installed from that manifest only with
+/// `allow_embedded_manifests`, since the manifest comes from the file and can run any
+/// script; otherwise it is reported as failed.
+#[tauri::command]
+pub async fn import_scoop_export(
+    window: Window,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    allow_embedded_manifests: Option<bool>,
+) -> Result<OnboardingImportResult, String> {
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read export file {}: {}", path, e))?;
+    let export = parse_scoop_export(&content)?;
+    let mut result = OnboardingImportResult::default();
+
+    let buckets_dir = state.scoop_path().join("buckets");
+    for bucket in export.buckets {
+        if buckets_dir.join(&bucket.name).exists() {
+            continue;
+        }
+        let options = BucketInstallOptions {
+            name: bucket.name.clone(),
+            url: bucket.source,
+            force: false,
+        };
+        match install_bucket(options).await {
+            Ok(installed) if installed.success => result.buckets_added.push(bucket.name),
+            Ok(installed) => result.failed.push((bucket.name, installed.message)),
+            Err(e) => result.failed.push((bucket.name, e)),
+        }
+    }
+
+    let installed: HashSet<String> = get_installed_packages(app.clone(), state.clone())
+        .await?
+        .into_iter()
+        .map(|p| p.name.to_lowercase())
+        .collect();
+    let scoop_dir = state.scoop_path();
+    for export_app in export.apps {
+        // Names from the file end up in paths and Scoop command lines
+        let names_valid = validate_dir_name("package", &export_app.name).and_then(|_| {
+            export_app
+                .source
+                .as_deref()
+                .filter(|source| !source.is_empty())
+                .map_or(Ok(()), |source| validate_dir_name("bucket", source))
+        });
+        if let Err(e) = names_valid {
+            result.failed.push((export_app.name, e));
+            continue;
+        }
+        if installed.contains(&export_app.name.to_lowercase()) {
+            result.apps_skipped.push(export_app.name);
+            continue;
+        }
+        if let Some(manifest) = pinned_manifest(
+            &scoop_dir,
+            &export_app.name,
+            export_app.source.as_deref(),
+            export_app.version.as_deref(),
+            export_app.manifest.as_ref(),
+        ) {
+            if !allow_embedded_manifests.unwrap_or(false) {
+                result.failed.push((
+                    export_app.name,
+                    "The exported version is only available from the manifest embedded in \
+                     the export; allow embedded manifests to install it"
+                        .to_string(),
+                ));
+                continue;
+            }
+            log::info!(
+                "Installing {} {} from its exported manifest",
+                export_app.name,
+                export_app.version.as_deref().unwrap_or("?")
+            );
+            match install_pinned(window.clone(), &export_app.name, manifest).await {
+                Ok(()) => result.apps_pinned.push(export_app.name),
+                Err(e) => result.failed.push((export_app.name, e)),
+            }
+            continue;
+        }
+        let bucket = export_app.source.unwrap_or_default();
+        match install_package(
+            window.clone(),
+            app.clone(),
+            state.clone(),
+            export_app.name.clone(),
+            bucket,
+            None,
+            None,
+        )
+        .await
+        {
+            Ok(()) => result.apps_installed.push(export_app.name),
+            Err(e) => result.failed.push((export_app.name, e)),
+        }
+    }
+
+    log::info!(
+        "Onboarding import from {}: {} bucket(s) added, {} app(s) installed, {} pinned, {} skipped, {} failed",
+        path,
+        result.buckets_added.len(),
+        result.apps_installed.len(),
+        result.apps_pinned.len(),
+        result.apps_skipped.len(),
+        result.failed.len()
+    );
+    if !result.apps_pinned.is_empty() {
+        invalidate_installed_cache(state).await;
+    }
+    update_state(&app, |s| {
+        s.imported_from = Some(path);
+        finish_step(s, OnboardingStep::ImportExport, false);
+    })?;
+    Ok(result)
+}
+
+/// Saves the bucket and package auto-update preferences.
+#[tauri::command]
+pub fn set_onboarding_update_preferences(
+    app: AppHandle,
+    preferences: OnboardingUpdatePreferences,
+) -> Result<OnboardingState, String> {
+    let interval = preferences.bucket_auto_update_interval.trim().to_string();
+    if interval != "off" && crate::scheduler::parse_update_interval(&interval).is_none() {
+        return Err(format!("Invalid auto-update interval '{}'", interval));
+    }
+
+    let preferences = [
+        ("buckets.autoUpdateInterval", serde_json::json!(interval)),
+        (
+            "buckets.autoUpdatePackagesEnabled",
+            serde_json::json!(preferences.auto_update_packages),
+        ),
+    ];
+    // Preferences fixed by the machine policy are kept as they are
+    for (key, value) in preferences {
+        if crate::policy::locked_setting(key).is_none() {
+            settings::set_config_value(app.clone(), key.to_string(), value)?;
+        }
+    }
+
+    update_state(&app, |s| {
+        finish_step(s, OnboardingStep::UpdatePreferences, false)
+    })
+}
+
+#[tauri::command]
+pub fn skip_onboarding_step(
+    app: AppHandle,
+    step: OnboardingStep,
+) -> Result<OnboardingState, String> {
+    // Every later step depends on a configured Scoop root
+    if matches!(step, OnboardingStep::ChooseRoot | OnboardingStep::Done) {
+        return Err(format!("Onboarding step {:?} cannot be skipped", step));
+    }
+    update_state(&app, |s| finish_step(s, step, true))
+}
+
+/// Clears the saved progress so onboarding starts over.
+#[tauri::command]
+pub fn reset_onboarding(app: AppHandle) -> Result<OnboardingState, String> {
+    let state = OnboardingState::default();
+    save_state(&app, &state)?;
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resumes_at_first_unfinished_step() {
+        let mut state = OnboardingState::default();
+        finish_step(&mut state, OnboardingStep::DetectScoop, false);
+        finish_step(&mut state, OnboardingStep::PickBuckets, true);
+        assert_eq!(state.current_step, OnboardingStep::ChooseRoot);
+
+        finish_step(&mut state, OnboardingStep::ChooseRoot, false);
+        assert_eq!(state.current_step, OnboardingStep::ImportExport);
+
+        finish_step(&mut state, OnboardingStep::ImportExport, false);
+        finish_step(&mut state, OnboardingStep::UpdatePreferences, false);
+        assert_eq!(state.current_step, OnboardingStep::Done);
+        assert!(state.completed_at.is_some());
+    }
+
+    #[test]
+    fn parses_scoop_export() {
+        let export = parse_scoop_export(
+            "\u{feff}{\"buckets\":[{\"Name\":\"extras\",\"Source\":\"https://github.com/ScoopInstaller/Extras\",\"Manifests\":2000}],\
+             \"apps\":[{\"Name\":\"7zip\",\"Source\":\"main\",\"Version\":\"24.09\",\"Info\":\"\"}]}",
+        )
+        .unwrap();
+        assert_eq!(export.buckets[0].name, "extras");
+        assert_eq!(export.apps[0].source.as_deref(), Some("main"));
+    }
+}
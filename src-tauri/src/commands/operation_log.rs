@@ -0,0 +1,354 @@
+//! Persistent output logs for streamed operations.
+//!
+//! Every operation run through the PowerShell runner writes its output to a log file
+//! under the app data directory so the history view can show it after a reload.
+//! Each operation has a `<id>.json` summary and a `<id>.log` file with one JSON-encoded
+//! line per output line.
+use crate::commands::milestones::OperationMilestone;
+use crate::commands::powershell::OperationClass;
+use crate::commands::uninstall_verification::UninstallVerification;
+use crate::engine::env_diff::EnvChange;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+const OPERATION_LOG_DIR: &str = "operation_logs";
+/// Number of operation logs kept before the oldest are pruned.
+const MAX_OPERATION_LOGS: usize = 200;
+/// Logs older than this are pruned regardless of count.
+const MAX_OPERATION_LOG_AGE_DAYS: i64 = 30;
+
+/// Summary of a past operation, stored next to its output log.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationLogSummary {
+    pub log_id: String,
+    pub operation_id: Option<String>,
+    pub operation_name: String,
+    pub command: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    /// `None` while the operation is still running (or if the app exited mid-operation).
+    pub success: Option<bool>,
+    #[serde(default)]
+    pub timed_out: bool,
+    pub message: Option<String>,
+    pub line_count: usize,
+    /// Persistent environment variables the operation changed.
+    #[serde(default)]
+    pub env_changes: Vec<EnvChange>,
+    /// Missing for logs written before operations were classified.
+    #[serde(default)]
+    pub class: Option<OperationClass>,
+    /// Disk space released by cleanup and cache operations.
+    #[serde(default)]
+    pub freed_bytes: Option<u64>,
+    /// What an uninstall left behind, checked after it finished.
+    #[serde(default)]
+    pub uninstall_verification: Option<UninstallVerification>,
+    /// Progress milestones reached, in order.
+    #[serde(default)]
+    pub milestones: Vec<OperationMilestone>,
+}
+
+/// A single recorded output line.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OperationLogLine {
+    pub line: String,
+    pub source: String,
+}
+
+/// A past operation together with its full output.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationLogDetail {
+    pub summary: OperationLogSummary,
+    pub lines: Vec<OperationLogLine>,
+}
+
+fn get_operation_log_dir() -> Option<PathBuf> {
+    crate::portable::app_data_dir().map(|dir| dir.join(OPERATION_LOG_DIR))
+}
+
+fn read_summary(path: &PathBuf) -> Option<OperationLogSummary> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+fn write_summary(dir: &PathBuf, summary: &OperationLogSummary) {
+    let path = dir.join(format!("{}.json", summary.log_id));
+    match serde_json::to_string_pretty(summary) {
+        Ok(content) => {
+            if let Err(e) = fs::write(&path, content) {
+                log::warn!("Failed to write operation log summary: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Failed to serialize operation log summary: {}", e),
+    }
+}
+
+/// Reads all operation summaries, newest first.
+fn read_all_summaries(dir: &PathBuf) -> Vec<OperationLogSummary> {
+    let mut summaries: Vec<OperationLogSummary> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().map(|ext| ext == "json").unwrap_or(false))
+                .filter_map(|p| read_summary(&p))
+                .collect()
+        })
+        .unwrap_or_default();
+    summaries.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    summaries
+}
+
+/// Removes logs beyond the retention count or age.
+fn prune_operation_logs(dir: &PathBuf) {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(MAX_OPERATION_LOG_AGE_DAYS);
+
+    for (index, summary) in read_all_summaries(dir).into_iter().enumerate() {
+        let expired = chrono::DateTime::parse_from_rfc3339(&summary.started_at)
+            .map(|started| started < cutoff)
+            .unwrap_or(false);
+        if index >= MAX_OPERATION_LOGS || expired {
+            let _ = fs::remove_file(dir.join(format!("{}.json", summary.log_id)));
+            let _ = fs::remove_file(dir.join(format!("{}.log", summary.log_id)));
+        }
+    }
+}
+
+/// Returns `true` if `log_id` names a log file inside the log directory.
+fn is_valid_log_id(log_id: &str) -> bool {
+    !log_id.is_empty()
+        && log_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+struct OperationLogInner {
+    dir: PathBuf,
+    summary: OperationLogSummary,
+    file: Option<File>,
+}
+
+/// Handle used by the PowerShell runner to record an operation's output.
+///
+/// Cloning is cheap; all clones write to the same log. Failures to write are logged
+/// and otherwise ignored so logging never breaks an operation.
+#[derive(Clone)]
+pub struct OperationLog {
+    inner: Arc<Mutex<OperationLogInner>>,
+}
+
+impl OperationLog {
+    /// Creates the log files for a new operation and prunes old logs.
+    pub fn start(
+        operation_id: Option<&str>,
+        operation_name: &str,
+        command: &str,
+        class: OperationClass,
+    ) -> Option<OperationLog> {
+        let dir = get_operation_log_dir()?;
+        if let Err(e) = fs::create_dir_all(&dir) {
+            log::warn!("Failed to create operation log directory: {}", e);
+            return None;
+        }
+        prune_operation_logs(&dir);
+
+        let now = chrono::Utc::now();
+        let suffix: String = operation_id
+            .unwrap_or("operation")
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .take(64)
+            .collect();
+        let log_id = format!("{}-{}", now.timestamp_millis(), suffix);
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(format!("{}.log", log_id)))
+            .map_err(|e| log::warn!("Failed to open operation log: {}", e))
+            .ok();
+
+        let summary = OperationLogSummary {
+            log_id,
+            operation_id: operation_id.map(String::from),
+            operation_name: operation_name.to_string(),
+            command: command.to_string(),
+            started_at: now.to_rfc3339(),
+            finished_at: None,
+            success: None,
+            timed_out: false,
+            message: None,
+            line_count: 0,
+            env_changes: Vec::new(),
+            class: Some(class),
+            freed_bytes: None,
+            uninstall_verification: None,
+            milestones: Vec::new(),
+        };
+        write_summary(&dir, &summary);
+
+        Some(OperationLog {
+            inner: Arc::new(Mutex::new(OperationLogInner { dir, summary, file })),
+        })
+    }
+
+    /// Appends an output line.
+    pub fn append(&self, line: &str, source: &str) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        let record = OperationLogLine {
+            line: line.to_string(),
+            source: source.to_string(),
+        };
+        let Some(file) = inner.file.as_mut() else {
+            return;
+        };
+        let written = serde_json::to_string(&record)
+            .map_err(|e| e.to_string())
+            .and_then(|json| writeln!(file, "{}", json).map_err(|e| e.to_string()));
+        match written {
+            Ok(_) => inner.summary.line_count += 1,
+            Err(e) => log::warn!("Failed to append to operation log: {}", e),
+        }
+    }
+
+    /// Records a milestone the operation reached.
+    pub fn record_milestone(&self, milestone: &OperationMilestone) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        inner.summary.milestones.push(milestone.clone());
+        write_summary(&inner.dir, &inner.summary);
+    }
+
+    /// Records the environment changes made by the operation; saved by [`Self::finish`].
+    pub fn set_env_changes(&self, changes: Vec<EnvChange>) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.summary.env_changes = changes;
+        }
+    }
+
+    /// Records the disk space the operation released; saved by [`Self::finish`].
+    pub fn set_freed_bytes(&self, bytes: u64) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.summary.freed_bytes = Some(bytes);
+        }
+    }
+
+    /// Records the final result of the operation.
+    pub fn finish(&self, result: &Result<(), String>, success_message: &str, timed_out: bool) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        inner.summary.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        inner.summary.success = Some(result.is_ok());
+        inner.summary.timed_out = timed_out;
+        inner.summary.message = Some(match result {
+            Ok(_) => success_message.to_string(),
+            Err(e) => e.clone(),
+        });
+        write_summary(&inner.dir, &inner.summary);
+    }
+}
+
+/// Stores an uninstall verification on the newest log of `operation_id`.
+pub fn record_uninstall_verification(operation_id: &str, verification: &UninstallVerification) {
+    let Some(dir) = get_operation_log_dir() else {
+        return;
+    };
+    let summary = read_all_summaries(&dir)
+        .into_iter()
+        .find(|s| s.operation_id.as_deref() == Some(operation_id));
+    match summary {
+        Some(mut summary) => {
+            summary.uninstall_verification = Some(verification.clone());
+            write_summary(&dir, &summary);
+        }
+        None => log::warn!("No operation log found for '{}'", operation_id),
+    }
+}
+
+/// Appends lines to the newest log of `operation_id`, e.g. the output of hooks run
+/// around it.
+pub fn append_to_operation(operation_id: &str, lines: &[OperationLogLine]) {
+    let Some(dir) = get_operation_log_dir() else {
+        return;
+    };
+    let Some(mut summary) = read_all_summaries(&dir)
+        .into_iter()
+        .find(|s| s.operation_id.as_deref() == Some(operation_id))
+    else {
+        log::warn!("No operation log found for '{}'", operation_id);
+        return;
+    };
+    let file = OpenOptions::new()
+        .append(true)
+        .open(dir.join(format!("{}.log", summary.log_id)));
+    let mut file = match file {
+        Ok(file) => file,
+        Err(e) => {
+            log::warn!("Failed to open operation log: {}", e);
+            return;
+        }
+    };
+    for line in lines {
+        let written = serde_json::to_string(line)
+            .map_err(|e| e.to_string())
+            .and_then(|json| writeln!(file, "{}", json).map_err(|e| e.to_string()));
+        match written {
+            Ok(_) => summary.line_count += 1,
+            Err(e) => log::warn!("Failed to append to operation log: {}", e),
+        }
+    }
+    write_summary(&dir, &summary);
+}
+
+/// Lists past operations, newest first.
+#[tauri::command]
+pub fn list_operation_logs() -> Result<Vec<OperationLogSummary>, String> {
+    let dir = get_operation_log_dir().ok_or("Could not resolve operation log directory")?;
+    Ok(read_all_summaries(&dir))
+}
+
+/// Reads the summary of a past operation.
+pub fn read_operation_summary(log_id: &str) -> Result<OperationLogSummary, String> {
+    if !is_valid_log_id(log_id) {
+        return Err(format!("Invalid operation log id: {}", log_id));
+    }
+    let dir = get_operation_log_dir().ok_or("Could not resolve operation log directory")?;
+    read_summary(&dir.join(format!("{}.json", log_id)))
+        .ok_or_else(|| format!("Operation log '{}' not found", log_id))
+}
+
+/// Returns the summary and full output of a past operation.
+#[tauri::command]
+pub fn get_operation_log(log_id: String) -> Result<OperationLogDetail, String> {
+    let summary = read_operation_summary(&log_id)?;
+    let dir = get_operation_log_dir().ok_or("Could not resolve operation log directory")?;
+
+    let lines = File::open(dir.join(format!("{}.log", log_id)))
+        .map(|file| {
+            BufReader::new(file)
+                .lines()
+                .map_while(Result::ok)
+                .filter_map(|line| serde_json::from_str::<OperationLogLine>(&line).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(OperationLogDetail { summary, lines })
+}
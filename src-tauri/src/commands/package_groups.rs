@@ -0,0 +1,162 @@
+//! Grouping installed packages into families.
+//!
+//! Runtimes are often installed side by side under variant names, such as `python`,
+//! `python311` and `python-beta`, or `temurin17-jdk` and `temurin21-jre`. Packages
+//! are grouped by the family their name normalizes to, and packages whose manifests
+//! share a homepage join the same group, so the installed list can cluster them.
+use crate::commands::installed::{get_installed_packages, with_global_packages};
+use crate::models::InstallScope;
+use crate::state::AppState;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tauri::{AppHandle, Runtime, State};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageGroup {
+    /// Normalized family name, e.g. `python` or `temurin`.
+    pub family: String,
+    /// Names of the installed packages in the family, sorted.
+    pub packages: Vec<String>,
+}
+
+/// Normalizes a package name to its family: the first `-` separated part, without
+/// the version digits trailing it. Names that would normalize to nothing are kept.
+pub fn family_key(name: &str) -> String {
+    let name = name.to_lowercase();
+    let first = name.split('-').next().unwrap_or(&name);
+    let stem = first.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.' || c == '_');
+    if stem.len() < 2 {
+        name
+    } else {
+        stem.to_string()
+    }
+}
+
+/// Host and path of a homepage, ignoring scheme, `www.` and a trailing slash.
+fn normalize_homepage(url: &str) -> Option<String> {
+    let url = url.trim().to_lowercase();
+    let rest = url.split_once("://").map_or(url.as_str(), |(_, rest)| rest);
+    let rest = rest
+        .strip_prefix("www.")
+        .unwrap_or(rest)
+        .trim_end_matches('/');
+    (!rest.is_empty()).then(|| rest.to_string())
+}
+
+/// Groups `(package, homepage)` pairs; only families with two or more packages are returned.
+pub fn group_packages(packages: &[(String, Option<String>)]) -> Vec<PackageGroup> {
+    let mut families: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut family_of_homepage: BTreeMap<String, String> = BTreeMap::new();
+
+    for (name, homepage) in packages {
+        let mut family = family_key(name);
+        if let Some(homepage) = homepage.as_deref().and_then(normalize_homepage) {
+            match family_of_homepage.get(&homepage) {
+                // A package of another family already has this homepage: join it
+                Some(existing) if !families.contains_key(&family) => family = existing.clone(),
+                Some(_) => {}
+                None => {
+                    family_of_homepage.insert(homepage, family.clone());
+                }
+            }
+        }
+        let members = families.entry(family).or_default();
+        if !members.iter().any(|m| m.eq_ignore_ascii_case(name)) {
+            members.push(name.clone());
+        }
+    }
+
+    families
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(family, mut packages)| {
+            packages.sort_by_key(|p| p.to_lowercase());
+            PackageGroup { family, packages }
+        })
+        .collect()
+}
+
+fn read_homepage(apps_dir: &Path, package: &str) -> Option<String> {
+    let content =
+        std::fs::read_to_string(apps_dir.join(package).join("current").join("manifest.json"))
+            .ok()?;
+    let manifest: Value = serde_json::from_str(&content).ok()?;
+    manifest.get("homepage")?.as_str().map(String::from)
+}
+
+/// Groups the installed packages, user and global, into families of related packages.
+#[tauri::command]
+pub async fn get_package_groups<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+) -> Result<Vec<PackageGroup>, String> {
+    let packages = get_installed_packages(app.clone(), state.clone()).await?;
+    let packages = with_global_packages(&app, &state, packages).await;
+    let dirs = state.scoop_dirs();
+
+    tokio::task::spawn_blocking(move || {
+        let entries: Vec<(String, Option<String>)> = packages
+            .into_iter()
+            .map(|package| {
+                let apps = match package.scope {
+                    InstallScope::User => &dirs.apps,
+                    InstallScope::Global => &dirs.global_apps,
+                };
+                let homepage = read_homepage(apps, &package.name);
+                (package.name, homepage)
+            })
+            .collect();
+        group_packages(&entries)
+    })
+    .await
+    .map_err(|e| format!("Failed to group packages: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_runtime_variants_into_families() {
+        let entry =
+            |name: &str, homepage: Option<&str>| (name.to_string(), homepage.map(String::from));
+        let packages = [
+            entry("python", Some("https://www.python.org/")),
+            entry("python311", Some("https://www.python.org")),
+            entry("temurin17-jdk", Some("https://adoptium.net")),
+            entry("temurin21-jre", Some("https://adoptium.net")),
+            entry("adoptium-installer", Some("https://adoptium.net/")),
+            entry("7zip", Some("https://www.7-zip.org/")),
+            entry("7zip19.00-helper", None),
+            entry("git", Some("https://gitforwindows.org/")),
+        ];
+
+        let groups = group_packages(&packages);
+        assert_eq!(family_key("Temurin17-JDK"), "temurin");
+        assert_eq!(family_key("7zip"), "7zip");
+        assert_eq!(
+            groups,
+            vec![
+                PackageGroup {
+                    family: "7zip".to_string(),
+                    packages: vec!["7zip".to_string(), "7zip19.00-helper".to_string()],
+                },
+                PackageGroup {
+                    family: "python".to_string(),
+                    packages: vec!["python".to_string(), "python311".to_string()],
+                },
+                PackageGroup {
+                    family: "temurin".to_string(),
+                    packages: vec![
+                        "adoptium-installer".to_string(),
+                        "temurin17-jdk".to_string(),
+                        "temurin21-jre".to_string(),
+                    ],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,261 @@
+//! Commands for resolving which bucket a package should come from when several
+//! buckets provide a manifest with the same name.
+//!
+//! The chosen source is, in order: a per-package pin, the bucket the package is
+//! installed from, the user's preferred bucket, and finally the first candidate
+//! according to the bucket priority list.
+use crate::commands::settings;
+use crate::state::AppState;
+use crate::utils::find_manifest_candidates;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Runtime, State};
+
+/// Store key for the bucket preferred when a package exists in several buckets.
+pub const PREFERRED_BUCKET_KEY: &str = "buckets.preferredBucket";
+/// Store key for the map of package name to pinned source bucket.
+pub const PINNED_SOURCES_KEY: &str = "packages.pinnedSources";
+/// Store key for the user-defined bucket priority list (highest priority first).
+pub const BUCKET_PRIORITY_KEY: &str = "buckets.priority";
+
+/// Priority used until the user configures one, so official buckets win over
+/// community buckets that shadow popular package names.
+const DEFAULT_BUCKET_PRIORITY: &[&str] = &["main", "extras", "versions"];
+
+/// A bucket that provides a manifest for the requested package.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestCandidate {
+    pub bucket: String,
+    pub version: Option<String>,
+    pub manifest_path: String,
+    pub is_installed_source: bool,
+    pub is_pinned: bool,
+    pub is_preferred: bool,
+    /// Whether this candidate is the one install/update/info will use.
+    pub is_selected: bool,
+}
+
+/// Reads the per-package source pins from the store.
+pub fn get_pinned_sources<R: Runtime>(app: &AppHandle<R>) -> HashMap<String, String> {
+    settings::get_config_value(app.clone(), PINNED_SOURCES_KEY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Reads the preferred bucket from the store, if one is configured.
+pub fn get_preferred_bucket<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    settings::get_config_value(app.clone(), PREFERRED_BUCKET_KEY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_str().map(String::from))
+        .filter(|s| !s.is_empty())
+}
+
+/// Reads the bucket priority list from the store, falling back to the default order.
+pub fn get_bucket_priority_list<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    settings::get_config_value(app.clone(), BUCKET_PRIORITY_KEY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
+        .unwrap_or_else(|| {
+            DEFAULT_BUCKET_PRIORITY
+                .iter()
+                .map(|s| s.to_string())
+                .collect()
+        })
+}
+
+/// Returns the rank of `bucket` in the priority list; unlisted buckets rank last.
+pub fn bucket_rank(priority: &[String], bucket: &str) -> usize {
+    priority
+        .iter()
+        .position(|b| b.eq_ignore_ascii_case(bucket))
+        .unwrap_or(priority.len())
+}
+
+/// Lists the buckets providing `package_name`, highest priority first.
+///
+/// Buckets with the same rank keep their bucket name order.
+pub fn prioritized_candidates(
+    scoop_dir: &Path,
+    package_name: &str,
+    priority: &[String],
+) -> Vec<(PathBuf, String)> {
+    let mut candidates = find_manifest_candidates(scoop_dir, package_name);
+    candidates.sort_by_key(|(_, bucket)| bucket_rank(priority, bucket));
+    candidates
+}
+
+/// Reads the bucket recorded in the installed package's `install.json`.
+pub fn get_installed_source(scoop_dir: &Path, package_name: &str) -> Option<String> {
+    let install_json_path = scoop_dir
+        .join("apps")
+        .join(package_name)
+        .join("current")
+        .join("install.json");
+
+    fs::read_to_string(install_json_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|json| json.get("bucket").and_then(Value::as_str).map(String::from))
+}
+
+/// Picks the bucket a package should be resolved from.
+///
+/// Returns `None` when no bucket provides the package.
+pub fn select_source<R: Runtime>(
+    app: &AppHandle<R>,
+    scoop_dir: &Path,
+    package_name: &str,
+) -> Option<String> {
+    let priority = get_bucket_priority_list(app);
+    let available: Vec<String> = prioritized_candidates(scoop_dir, package_name, &priority)
+        .into_iter()
+        .map(|(_, bucket)| bucket)
+        .collect();
+    let provides = |bucket: &str| available.iter().any(|b| b.eq_ignore_ascii_case(bucket));
+
+    if let Some(pinned) = get_pinned_sources(app).get(package_name) {
+        if provides(pinned) {
+            return Some(pinned.clone());
+        }
+        log::warn!(
+            "Pinned source '{}' for '{}' no longer provides the package",
+            pinned,
+            package_name
+        );
+    }
+
+    if let Some(installed) = get_installed_source(scoop_dir, package_name) {
+        if provides(&installed) {
+            return Some(installed);
+        }
+    }
+
+    if let Some(preferred) = get_preferred_bucket(app) {
+        if provides(&preferred) {
+            return Some(preferred);
+        }
+    }
+
+    available.into_iter().next()
+}
+
+/// Lists every bucket that provides `package_name`, with the version each one offers.
+#[tauri::command]
+pub fn get_package_candidates<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+    package_name: String,
+) -> Result<Vec<ManifestCandidate>, String> {
+    let scoop_dir = state.scoop_path();
+    let installed_source = get_installed_source(&scoop_dir, &package_name);
+    let pinned_source = get_pinned_sources(&app).get(&package_name).cloned();
+    let preferred_bucket = get_preferred_bucket(&app);
+    let selected = select_source(&app, &scoop_dir, &package_name);
+
+    let matches = |bucket: &str, other: &Option<String>| {
+        other
+            .as_ref()
+            .map(|o| o.eq_ignore_ascii_case(bucket))
+            .unwrap_or(false)
+    };
+
+    let priority = get_bucket_priority_list(&app);
+    let candidates: Vec<ManifestCandidate> =
+        prioritized_candidates(&scoop_dir, &package_name, &priority)
+            .into_iter()
+            .map(|(manifest_path, bucket)| {
+                let version = fs::read_to_string(&manifest_path)
+                    .ok()
+                    .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+                    .and_then(|json| {
+                        json.get("version")
+                            .and_then(Value::as_str)
+                            .map(String::from)
+                    });
+
+                ManifestCandidate {
+                    is_installed_source: matches(&bucket, &installed_source),
+                    is_pinned: matches(&bucket, &pinned_source),
+                    is_preferred: matches(&bucket, &preferred_bucket),
+                    is_selected: matches(&bucket, &selected),
+                    version,
+                    manifest_path: manifest_path.to_string_lossy().to_string(),
+                    bucket,
+                }
+            })
+            .collect();
+
+    log::info!(
+        "Found {} candidate buckets for '{}'",
+        candidates.len(),
+        package_name
+    );
+    Ok(candidates)
+}
+
+/// Pins a package to a specific source bucket, or removes the pin when `bucket` is `None`.
+#[tauri::command]
+pub fn set_package_source_pin(
+    app: AppHandle<tauri::Wry>,
+    state: State<'_, AppState>,
+    package_name: String,
+    bucket: Option<String>,
+) -> Result<(), String> {
+    let mut pins = get_pinned_sources(&app);
+
+    match bucket.filter(|b| !b.is_empty()) {
+        Some(bucket) => {
+            let provides = find_manifest_candidates(&state.scoop_path(), &package_name)
+                .iter()
+                .any(|(_, b)| b.eq_ignore_ascii_case(&bucket));
+            if !provides {
+                return Err(format!(
+                    "Bucket '{}' does not provide package '{}'",
+                    bucket, package_name
+                ));
+            }
+            log::info!("Pinning '{}' to bucket '{}'", package_name, bucket);
+            pins.insert(package_name, bucket);
+        }
+        None => {
+            log::info!("Removing source pin for '{}'", package_name);
+            pins.remove(&package_name);
+        }
+    }
+
+    let value = serde_json::to_value(pins).map_err(|e| e.to_string())?;
+    settings::set_config_value(app, PINNED_SOURCES_KEY.to_string(), value)
+}
+
+/// Returns the bucket priority list, highest priority first.
+#[tauri::command]
+pub fn get_bucket_priority<R: Runtime>(app: AppHandle<R>) -> Result<Vec<String>, String> {
+    Ok(get_bucket_priority_list(&app))
+}
+
+/// Stores the bucket priority list used by search ranking, manifest resolution and
+/// update checks. Duplicate and empty entries are dropped.
+#[tauri::command]
+pub fn set_bucket_priority(app: AppHandle<tauri::Wry>, buckets: Vec<String>) -> Result<(), String> {
+    let mut priority: Vec<String> = Vec::new();
+    for bucket in buckets.into_iter().map(|b| b.trim().to_string()) {
+        if !bucket.is_empty() && !priority.iter().any(|b| b.eq_ignore_ascii_case(&bucket)) {
+            priority.push(bucket);
+        }
+    }
+
+    log::info!("Setting bucket priority: {:?}", priority);
+    settings::set_config_value(
+        app,
+        BUCKET_PRIORITY_KEY.to_string(),
+        serde_json::json!(priority),
+    )
+}
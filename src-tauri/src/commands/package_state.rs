@@ -0,0 +1,102 @@
+//! Per-package operation state shared with the UI.
+//!
+//! Scoop operations mark their package as queued, then installing, updating or
+//! uninstalling, and finally idle or failed. Every change is emitted as
+//! [`EVENT_PACKAGE_STATE_CHANGED`] so the UI no longer has to infer state from output.
+use crate::commands::hold;
+use crate::models::{PackageActivity, PackageState};
+use crate::state::AppState;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Emitted with a [`PackageState`] whenever a package changes state.
+pub const EVENT_PACKAGE_STATE_CHANGED: &str = "package-state-changed";
+
+pub fn emit_package_state<R: Runtime>(app: &AppHandle<R>, package_state: &PackageState) {
+    if let Err(e) = app.emit(EVENT_PACKAGE_STATE_CHANGED, package_state) {
+        log::warn!(
+            "Failed to emit {} event: {}",
+            EVENT_PACKAGE_STATE_CHANGED,
+            e
+        );
+    }
+}
+
+/// Records the new state of a package in `AppState` and notifies the UI.
+pub fn update_package_state<R: Runtime>(
+    app: &AppHandle<R>,
+    package_name: &str,
+    activity: PackageActivity,
+    operation_id: Option<String>,
+    message: Option<String>,
+) {
+    let state = app.state::<AppState>();
+    let entry = state.set_package_state(package_name, activity, operation_id, message);
+    emit_package_state(app, &entry);
+}
+
+/// Packages `scoop update *` works on: every installed one that is not held.
+pub fn update_all_targets(scoop_dir: &Path) -> Vec<String> {
+    let held: HashSet<String> = hold::find_held_packages(scoop_dir)
+        .into_iter()
+        .map(|name| name.to_lowercase())
+        .collect();
+    let mut names: Vec<String> = fs::read_dir(scoop_dir.join("apps"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| !held.contains(&name.to_lowercase()))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Lists every package that is not idle: queued, running and failed operations,
+/// plus held packages with no operation in flight.
+#[tauri::command]
+pub async fn get_package_states(state: State<'_, AppState>) -> Result<Vec<PackageState>, String> {
+    let mut states = state.package_states();
+    let tracked: HashSet<String> = states.iter().map(|s| s.name.to_lowercase()).collect();
+
+    let scoop_path = state.scoop_path();
+    let held = tokio::task::spawn_blocking(move || hold::find_held_packages(&scoop_path))
+        .await
+        .map_err(|e| e.to_string())?;
+    states.extend(
+        held.into_iter()
+            .filter(|name| !tracked.contains(&name.to_lowercase()))
+            .map(|name| PackageState {
+                name,
+                state: PackageActivity::Held,
+                operation_id: None,
+                message: None,
+                updated_at: 0,
+            }),
+    );
+
+    states.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    Ok(states)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScoopFixture;
+
+    #[test]
+    fn update_all_skips_held_packages() {
+        let fixture = ScoopFixture::new();
+        fixture
+            .install("git", "2.44.0", "main")
+            .install("node", "20.0.0", "main");
+        hold::modify_hold_status(fixture.root(), "node", true).unwrap();
+
+        assert_eq!(update_all_targets(fixture.root()), vec!["git"]);
+    }
+}
@@ -1,20 +1,66 @@
-use serde::Serialize;
+use crate::commands::env_changes;
+use crate::commands::milestones::{emit_milestone, MilestoneTracker};
+use crate::commands::operation_log::OperationLog;
+use crate::commands::statistics::{apps_disk_usage, dir_size};
+use crate::engine::env_diff;
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
 use std::process::Stdio;
-use tauri::{Emitter, Listener, Window};
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::{Child, Command};
+use std::time::Duration;
+use tauri::{Emitter, Listener, Manager, Window};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::{Child, ChildStdin, Command};
 use tokio::sync::{mpsc, oneshot};
 
 use lazy_static::lazy_static;
-use std::sync::RwLock;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
 
 lazy_static! {
     pub static ref POWERSHELL_EXE: RwLock<String> = RwLock::new("auto".to_string());
+    /// Stdin handles of running operations, keyed by operation id.
+    static ref OPERATION_STDIN: tokio::sync::Mutex<HashMap<String, ChildStdin>> =
+        tokio::sync::Mutex::new(HashMap::new());
+    /// Patterns of prompts that wait for an answer on the same line.
+    static ref PROMPT_RE: Regex = Regex::new(
+        r"(?i)(\[y/n\]|\(y/n\)|\[yes/no\]|\(yes/no\)|\[y\] yes|press any key|press enter|do you (want|wish|accept|agree)|are you sure|continue\?\s*$)"
+    )
+    .unwrap();
+    /// Final results of tracked operations, keyed by operation id.
+    static ref OPERATION_RESULTS: std::sync::Mutex<HashMap<String, TrackedOperation>> =
+        std::sync::Mutex::new(HashMap::new());
+}
+
+/// Number of finished operation results, and of waits on operations that never
+/// started, kept for `wait_for_operation`.
+const MAX_TRACKED_OPERATIONS: usize = 200;
+/// How long a wait on an operation that never started is kept before it is dropped.
+const PENDING_OPERATION_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Completion state of an operation that callers can wait on.
+struct TrackedOperation {
+    result_tx: tokio::sync::watch::Sender<Option<CommandResult>>,
+    created_at: std::time::Instant,
+    started: bool,
+    finished_at: Option<std::time::Instant>,
+}
+
+impl TrackedOperation {
+    fn new(started: bool) -> Self {
+        TrackedOperation {
+            result_tx: tokio::sync::watch::channel(None).0,
+            created_at: std::time::Instant::now(),
+            started,
+            finished_at: None,
+        }
+    }
 }
 
 pub const EVENT_OUTPUT: &str = "operation-output";
 pub const EVENT_FINISHED: &str = "operation-finished";
 pub const EVENT_CANCEL: &str = "cancel-operation";
+pub const EVENT_NEEDS_INPUT: &str = "operation-needs-input";
 
 /// Represents a line of output from a command, specifying its source (stdout or stderr).
 #[derive(Serialize, Clone)]
@@ -24,12 +70,167 @@ pub struct StreamOutput {
     pub operation_id: Option<String>,
 }
 
-/// Represents the final result of a command, indicating success or failure and a corresponding message.
+/// Emitted when an operation appears to be waiting for an answer on stdin.
 #[derive(Serialize, Clone)]
+pub struct OperationInputRequest {
+    pub prompt: String,
+    pub source: String,
+    pub operation_id: Option<String>,
+}
+
+/// Represents the final result of a command, indicating success or failure and a corresponding message.
+#[derive(Serialize, Clone, Debug)]
 pub struct CommandResult {
     pub success: bool,
     pub message: String,
     pub operation_id: Option<String>,
+    /// Set when the command was terminated because it exceeded its timeout.
+    #[serde(default)]
+    pub timed_out: bool,
+}
+
+/// Why a streamed command failed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandError {
+    /// The process could not be started, so nothing on disk was touched.
+    NotStarted(String),
+    /// The process ran and failed, timed out or was cancelled.
+    Failed(String),
+}
+
+impl From<CommandError> for String {
+    fn from(error: CommandError) -> Self {
+        match error {
+            CommandError::NotStarted(message) | CommandError::Failed(message) => message,
+        }
+    }
+}
+
+/// Classes of operations that share a timeout setting.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum OperationClass {
+    Install,
+    Uninstall,
+    Update,
+    UpdateAll,
+    Cache,
+    Cleanup,
+    /// Batch bucket updates and removals, which run in-process.
+    Bucket,
+    /// Arbitrary user-entered commands.
+    Custom,
+}
+
+impl OperationClass {
+    /// The settings key holding the timeout for this class, in seconds.
+    pub fn timeout_key(self) -> &'static str {
+        match self {
+            OperationClass::Install => "operations.timeouts.install",
+            OperationClass::Uninstall => "operations.timeouts.uninstall",
+            OperationClass::Update => "operations.timeouts.update",
+            OperationClass::UpdateAll => "operations.timeouts.updateAll",
+            OperationClass::Cache => "operations.timeouts.cache",
+            OperationClass::Cleanup => "operations.timeouts.cleanup",
+            OperationClass::Bucket => "operations.timeouts.bucket",
+            OperationClass::Custom => "operations.timeouts.custom",
+        }
+    }
+
+    /// Timeout used when no setting is stored. `0` disables the timeout.
+    pub fn default_timeout_secs(self) -> u64 {
+        match self {
+            OperationClass::Install | OperationClass::Update => 30 * 60,
+            OperationClass::UpdateAll => 2 * 60 * 60,
+            OperationClass::Uninstall | OperationClass::Cache | OperationClass::Bucket => 10 * 60,
+            OperationClass::Cleanup => 30 * 60,
+            OperationClass::Custom => 0,
+        }
+    }
+}
+
+/// Scoop subcommands that install, remove or update packages.
+const INSTALL_CHANGING_SUBCOMMANDS: &[&str] =
+    &["install", "uninstall", "update", "reset", "import"];
+
+/// Whether `command` can change which packages or versions are installed, so those
+/// changes are the app's own rather than external ones. Custom commands count only when
+/// they run such a Scoop subcommand; `scoop status` and the like do not.
+fn changes_installs(class: OperationClass, command: &str) -> bool {
+    match class {
+        OperationClass::Install
+        | OperationClass::Uninstall
+        | OperationClass::Update
+        | OperationClass::UpdateAll => true,
+        OperationClass::Custom => command
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .windows(2)
+            .any(|words| {
+                words[0].eq_ignore_ascii_case("scoop")
+                    && INSTALL_CHANGING_SUBCOMMANDS
+                        .iter()
+                        .any(|sub| words[1].eq_ignore_ascii_case(sub))
+            }),
+        OperationClass::Cache | OperationClass::Cleanup | OperationClass::Bucket => false,
+    }
+}
+
+/// Size of the directories an operation of `class` frees space in, if it is a cleanup.
+fn reclaimable_size(window: &Window, class: OperationClass) -> Option<u64> {
+    let dirs = window.state::<AppState>().scoop_dirs();
+    match class {
+        OperationClass::Cache => Some(dir_size(&dirs.cache)),
+        OperationClass::Cleanup => Some(apps_disk_usage(&dirs.apps) + dir_size(&dirs.cache)),
+        _ => None,
+    }
+}
+
+async fn measure_reclaimable(window: &Window, class: OperationClass) -> Option<u64> {
+    let window = window.clone();
+    tokio::task::spawn_blocking(move || reclaimable_size(&window, class))
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Reads the configured timeout for an operation class; `None` means no timeout.
+fn get_operation_timeout(window: &Window, class: OperationClass) -> Option<Duration> {
+    let secs = crate::commands::settings::get_config_value(
+        window.app_handle().clone(),
+        class.timeout_key().to_string(),
+    )
+    .ok()
+    .flatten()
+    .and_then(|v| v.as_u64())
+    .unwrap_or_else(|| class.default_timeout_secs());
+
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// Kills the child process together with every process it spawned.
+///
+/// Scoop runs installers as grandchildren of PowerShell, so killing only the direct
+/// child would leave a hung installer running.
+async fn kill_process_tree(child: &mut Child) {
+    #[cfg(windows)]
+    if let Some(pid) = child.id() {
+        let mut taskkill = Command::new("taskkill");
+        taskkill
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .creation_flags(0x0800_0000); // CREATE_NO_WINDOW
+        match taskkill.status().await {
+            Ok(status) if status.success() => return,
+            Ok(status) => log::warn!("taskkill exited with {} for pid {}", status, pid),
+            Err(e) => log::warn!("Failed to run taskkill for pid {}: {}", pid, e),
+        }
+    }
+
+    if let Err(e) = child.kill().await {
+        log::error!("Failed to kill child process: {}", e);
+    }
 }
 
 /// Creates a `tokio::process::Command` for running a PowerShell command without a visible window.
@@ -61,6 +262,22 @@ pub fn create_powershell_command(command_str: &str) -> Command {
     cmd
 }
 
+/// Quotes `value` as a PowerShell single-quoted string, in which nothing is expanded.
+/// PowerShell also ends such strings at typographic single quotes, so those are doubled
+/// as well.
+pub fn quote_literal(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('\'');
+    for c in value.chars() {
+        if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}') {
+            quoted.push(c);
+        }
+        quoted.push(c);
+    }
+    quoted.push('\'');
+    quoted
+}
+
 /// Checks if PowerShell Core (pwsh) is available on the system.
 pub fn is_pwsh_available() -> bool {
     std::process::Command::new("pwsh")
@@ -84,64 +301,297 @@ pub fn is_powershell_available() -> bool {
         .unwrap_or(false)
 }
 
+/// Returns `true` if unterminated output looks like a prompt waiting for input.
+fn is_interactive_prompt(text: &str) -> bool {
+    PROMPT_RE.is_match(text)
+}
+
+/// Where the output of one operation is recorded, shared by its stdout and stderr.
+#[derive(Clone)]
+struct OutputRecord {
+    log: Option<OperationLog>,
+    milestones: Arc<Mutex<MilestoneTracker>>,
+}
+
 /// Spawns a task to read lines from a stream (stdout or stderr) and sends them to the frontend.
 ///
-/// It also sends any lines that indicate an error to the `error_tx` channel.
-use tokio::io::AsyncRead;
-
+/// It also sends any lines that indicate an error to the `error_tx` channel. Prompts are
+/// printed without a trailing newline, so unterminated output is checked against known
+/// prompt patterns and reported with an `operation-needs-input` event.
 fn spawn_output_stream_handler(
-    stream: impl AsyncRead + Unpin + Send + 'static,
+    mut stream: impl AsyncRead + Unpin + Send + 'static,
     source: &'static str,
     window: Window,
     output_event: String,
     error_tx: mpsc::Sender<String>,
     operation_id: Option<String>,
+    record: OutputRecord,
 ) {
-    let mut reader = BufReader::new(stream).lines();
-
     tokio::spawn(async move {
-        while let Ok(Some(line)) = reader.next_line().await {
-            // Log each line for debugging
-            log::debug!("Output line [{}]: {}", source, line);
-            
-            // Enhanced error detection for scoop commands
-            let is_error_line = source == "stderr"
-                || line.to_lowercase().contains("error")
-                || line.to_lowercase().contains("failed")
-                || line.to_lowercase().contains("exception")
-                || line.to_lowercase().contains("cannot")
-                || line.to_lowercase().contains("could not")
-                || line.to_lowercase().contains("not found")
-                || line.to_lowercase().contains("access to the path")
-                || line.to_lowercase().contains("denied")
-                || line.contains("Remove-Item")
-                || line.contains("Access to the path")
-                || line.contains("is denied");
-
-            // Send error lines to the error channel for final result display
-            if is_error_line {
-                if let Err(e) = error_tx.send(line.clone()).await {
-                    log::error!("Failed to send error line to error channel: {}", e);
+        let mut buf = [0u8; 4096];
+        let mut pending: Vec<u8> = Vec::new();
+        let mut last_prompt: Option<String> = None;
+
+        loop {
+            let read = match stream.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+            pending.extend_from_slice(&buf[..read]);
+
+            while let Some(pos) = pending.iter().position(|b| *b == b'\n') {
+                let raw: Vec<u8> = pending.drain(..=pos).collect();
+                let line = String::from_utf8_lossy(&raw)
+                    .trim_end_matches(['\r', '\n'])
+                    .to_string();
+                handle_output_line(
+                    line,
+                    source,
+                    &window,
+                    &output_event,
+                    &error_tx,
+                    &operation_id,
+                    &record,
+                )
+                .await;
+                last_prompt = None;
+            }
+
+            if !pending.is_empty() {
+                let partial = String::from_utf8_lossy(&pending).trim().to_string();
+                if operation_id.is_some()
+                    && is_interactive_prompt(&partial)
+                    && last_prompt.as_deref() != Some(partial.as_str())
+                {
+                    log::info!("Operation appears to be waiting for input: {}", partial);
+                    if let Err(e) = window.emit(
+                        EVENT_NEEDS_INPUT,
+                        OperationInputRequest {
+                            prompt: partial.clone(),
+                            source: source.to_string(),
+                            operation_id: operation_id.clone(),
+                        },
+                    ) {
+                        log::error!("Failed to emit needs-input event: {}", e);
+                    }
+                    last_prompt = Some(partial);
                 }
             }
+        }
 
-            // Always send all lines to the frontend for display
-            if let Err(e) = window.emit(
+        if !pending.is_empty() {
+            let line = String::from_utf8_lossy(&pending).trim_end_matches('\r').to_string();
+            handle_output_line(
+                line,
+                source,
+                &window,
                 &output_event,
-                StreamOutput {
-                    line: line.clone(),
-                    source: source.to_string(),
-                    operation_id: operation_id.clone(),
-                },
-            ) {
-                log::error!("Failed to emit output event for line '{}': {}", line, e);
-            }
+                &error_tx,
+                &operation_id,
+                &record,
+            )
+            .await;
         }
-        
+
         log::debug!("Output stream handler for {} ended", source);
     });
 }
 
+/// Logs, classifies and forwards a single line of output.
+async fn handle_output_line(
+    line: String,
+    source: &'static str,
+    window: &Window,
+    output_event: &str,
+    error_tx: &mpsc::Sender<String>,
+    operation_id: &Option<String>,
+    record: &OutputRecord,
+) {
+    // Log each line for debugging
+    log::debug!("Output line [{}]: {}", source, line);
+    if let Some(op_log) = &record.log {
+        op_log.append(&line, source);
+    }
+    let milestone = record
+        .milestones
+        .lock()
+        .ok()
+        .and_then(|mut tracker| tracker.observe(&line, operation_id.as_deref()));
+    if let Some(milestone) = milestone {
+        if let Some(op_log) = &record.log {
+            op_log.record_milestone(&milestone);
+        }
+        emit_milestone(window, &milestone);
+    }
+
+    // Enhanced error detection for scoop commands
+    let is_error_line = source == "stderr"
+        || line.to_lowercase().contains("error")
+        || line.to_lowercase().contains("failed")
+        || line.to_lowercase().contains("exception")
+        || line.to_lowercase().contains("cannot")
+        || line.to_lowercase().contains("could not")
+        || line.to_lowercase().contains("not found")
+        || line.to_lowercase().contains("access to the path")
+        || line.to_lowercase().contains("denied")
+        || line.contains("Remove-Item")
+        || line.contains("Access to the path")
+        || line.contains("is denied");
+
+    // Send error lines to the error channel for final result display
+    if is_error_line {
+        if let Err(e) = error_tx.send(line.clone()).await {
+            log::error!("Failed to send error line to error channel: {}", e);
+        }
+    }
+
+    // Always send all lines to the frontend for display
+    if let Err(e) = window.emit(
+        output_event,
+        StreamOutput {
+            line: line.clone(),
+            source: source.to_string(),
+            operation_id: operation_id.clone(),
+        },
+    ) {
+        log::error!("Failed to emit output event for line '{}': {}", line, e);
+    }
+}
+
+/// Returns a receiver for the result of `operation_id`, creating a pending entry if needed.
+fn subscribe_operation(operation_id: &str) -> tokio::sync::watch::Receiver<Option<CommandResult>> {
+    let mut operations = OPERATION_RESULTS.lock().unwrap();
+    prune_tracked_operations(&mut operations);
+    operations
+        .entry(operation_id.to_string())
+        .or_insert_with(|| TrackedOperation::new(false))
+        .result_tx
+        .subscribe()
+}
+
+/// Drops the oldest finished results beyond the limit, and waits on operations that
+/// never started once they expire or exceed the limit. Dropping an entry ends its waits.
+fn prune_tracked_operations(operations: &mut HashMap<String, TrackedOperation>) {
+    operations.retain(|_, op| {
+        op.started || op.finished_at.is_some() || op.created_at.elapsed() < PENDING_OPERATION_TTL
+    });
+
+    let mut finished: Vec<(String, std::time::Instant)> = Vec::new();
+    let mut pending: Vec<(String, std::time::Instant)> = Vec::new();
+    for (id, op) in operations.iter() {
+        match op.finished_at {
+            Some(at) => finished.push((id.clone(), at)),
+            None if !op.started => pending.push((id.clone(), op.created_at)),
+            None => {}
+        }
+    }
+    for mut entries in [finished, pending] {
+        if entries.len() > MAX_TRACKED_OPERATIONS {
+            entries.sort_by_key(|(_, at)| *at);
+            let excess = entries.len() - MAX_TRACKED_OPERATIONS;
+            for (id, _) in entries.into_iter().take(excess) {
+                operations.remove(&id);
+            }
+        }
+    }
+}
+
+/// Marks `operation_id` as running. A result left over from an earlier run with the same
+/// id is discarded; waiters that subscribed before the start are kept.
+fn begin_tracked_operation(operation_id: &str) {
+    let mut operations = OPERATION_RESULTS.lock().unwrap();
+    match operations.get_mut(operation_id) {
+        Some(op) if op.finished_at.is_none() => op.started = true,
+        _ => {
+            operations.insert(operation_id.to_string(), TrackedOperation::new(true));
+        }
+    }
+}
+
+/// Publishes the final result of an operation to its waiters and prunes old results.
+fn complete_tracked_operation(result: CommandResult) {
+    let Some(operation_id) = result.operation_id.clone() else {
+        return;
+    };
+    let mut operations = OPERATION_RESULTS.lock().unwrap();
+    let entry = operations
+        .entry(operation_id)
+        .or_insert_with(|| TrackedOperation::new(true));
+    entry.result_tx.send_replace(Some(result));
+    entry.finished_at = Some(std::time::Instant::now());
+    prune_tracked_operations(&mut operations);
+}
+
+/// Publishes a failed result for an operation that ended before its command was started,
+/// e.g. on a policy or pre-hook rejection, so its waiters are not left waiting. Returns
+/// `message` for use in `map_err`.
+pub fn fail_unstarted_operation(operation_id: Option<&str>, message: String) -> String {
+    complete_tracked_operation(CommandResult {
+        success: false,
+        message: message.clone(),
+        operation_id: operation_id.map(String::from),
+        timed_out: false,
+    });
+    message
+}
+
+/// Waits until the operation with `operation_id` finishes and returns its final result.
+///
+/// Can be called before the operation starts, so scripts may invoke a command with an
+/// `operationId` of their choosing, as `install_package` and `update_package` accept, and
+/// wait on it concurrently. Operations rejected before their command starts report a
+/// failure too. Results stay available for a while after completion; a wait on an id
+/// that never starts ends with an error after an hour.
+#[tauri::command]
+pub async fn wait_for_operation(
+    operation_id: String,
+    timeout_secs: Option<u64>,
+) -> Result<CommandResult, String> {
+    let mut result_rx = subscribe_operation(&operation_id);
+    let wait = async {
+        result_rx
+            .wait_for(|result| result.is_some())
+            .await
+            .map(|result| result.clone())
+    };
+
+    let result = match timeout_secs {
+        Some(secs) => tokio::time::timeout(Duration::from_secs(secs), wait)
+            .await
+            .map_err(|_| {
+                format!(
+                    "Timed out after {} seconds waiting for operation '{}'",
+                    secs, operation_id
+                )
+            })?,
+        None => wait.await,
+    };
+
+    result
+        .map_err(|_| format!("Operation '{}' is no longer tracked", operation_id))?
+        .ok_or_else(|| format!("Operation '{}' has no result", operation_id))
+}
+
+/// Writes a line of input to the stdin of a running operation, e.g. to answer a prompt.
+/// Only operations started with an id accept input.
+#[tauri::command]
+pub async fn send_operation_input(operation_id: String, input: String) -> Result<(), String> {
+    let mut handles = OPERATION_STDIN.lock().await;
+    let stdin = handles
+        .get_mut(&operation_id)
+        .ok_or_else(|| format!("No running operation '{}' accepts input", operation_id))?;
+
+    log::info!("Sending input to operation '{}'", operation_id);
+    stdin
+        .write_all(format!("{}\n", input).as_bytes())
+        .await
+        .map_err(|e| format!("Failed to write input: {}", e))?;
+    stdin
+        .flush()
+        .await
+        .map_err(|e| format!("Failed to write input: {}", e))
+}
+
 /// Sets up a listener for a cancellation event from the frontend.
 ///
 /// When the event is received, it sends a signal through the `cancel_tx` channel.
@@ -166,6 +616,7 @@ fn setup_cancellation_handler(window: &Window, cancel_event: &str, cancel_tx: on
 /// - Emits `output_event` with `StreamOutput` for each line of output.
 /// - Emits `finished_event` with `CommandResult` when command completes.
 /// - Listens for `cancel_event` to terminate the process.
+/// - Kills the process tree once the timeout configured for `class` elapses.
 pub async fn run_and_stream_command(
     window: Window,
     command_str: String,
@@ -174,12 +625,47 @@ pub async fn run_and_stream_command(
     finished_event: &str,
     cancel_event: &str,
     operation_id: Option<String>,
-) -> Result<(), String> {
+    class: OperationClass,
+) -> Result<(), CommandError> {
     log::info!("Executing streaming command: {}", &command_str);
+    let timeout = get_operation_timeout(&window, class);
+
+    if let Some(id) = &operation_id {
+        begin_tracked_operation(id);
+    }
+
+    // Installs this operation changes are not external changes
+    let own_changes = changes_installs(class, &command_str);
+    if own_changes {
+        window.state::<AppState>().note_own_changes();
+    }
+    let env_before = env_changes::capture();
+    let size_before = measure_reclaimable(&window, class).await;
+    let mut command = create_powershell_command(&command_str);
+    // Input is sent by operation id, so only operations with one get a stdin pipe; the
+    // others see end of input and cannot hang on a prompt no one can answer
+    command.stdin(if operation_id.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    });
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            let message = format!("Failed to spawn command '{}': {}", command_str, e);
+            complete_tracked_operation(CommandResult {
+                success: false,
+                message: message.clone(),
+                operation_id: operation_id.clone(),
+                timed_out: false,
+            });
+            return Err(CommandError::NotStarted(message));
+        }
+    };
 
-    let mut child = create_powershell_command(&command_str)
-        .spawn()
-        .map_err(|e| format!("Failed to spawn command '{}': {}", command_str, e))?;
+    if let (Some(id), Some(stdin)) = (&operation_id, child.stdin.take()) {
+        OPERATION_STDIN.lock().await.insert(id.clone(), stdin);
+    }
 
     let stdout = child
         .stdout
@@ -195,6 +681,17 @@ pub async fn run_and_stream_command(
 
     setup_cancellation_handler(&window, cancel_event, cancel_tx);
 
+    let op_log = OperationLog::start(
+        operation_id.as_deref(),
+        &operation_name,
+        &command_str,
+        class,
+    );
+    let record = OutputRecord {
+        log: op_log.clone(),
+        milestones: Arc::new(Mutex::new(MilestoneTracker::default())),
+    };
+
     spawn_output_stream_handler(
         stdout,
         "stdout",
@@ -202,6 +699,7 @@ pub async fn run_and_stream_command(
         output_event.to_string(),
         error_tx.clone(),
         operation_id.clone(),
+        record.clone(),
     );
     spawn_output_stream_handler(
         stderr,
@@ -210,16 +708,65 @@ pub async fn run_and_stream_command(
         output_event.to_string(),
         error_tx,
         operation_id.clone(),
+        record,
     );
 
-    tokio::select! {
+    let deadline = async {
+        match timeout {
+            Some(duration) => tokio::time::sleep(duration).await,
+            None => std::future::pending::<()>().await,
+        }
+    };
+
+    let (result, timed_out) = tokio::select! {
         status_res = child.wait() => {
-            handle_command_completion(status_res, &operation_name, &window, finished_event, &mut error_rx, operation_id.clone()).await
+            (handle_command_completion(status_res, &operation_name, &window, finished_event, &mut error_rx, operation_id.clone()).await, false)
         },
         _ = cancel_rx => {
-            handle_cancellation(child, &operation_name, &window, finished_event, operation_id.clone()).await
+            (handle_cancellation(child, &operation_name, &window, finished_event, operation_id.clone()).await, false)
+        }
+        _ = deadline => {
+            (handle_timeout(child, &operation_name, timeout.unwrap_or_default(), &window, finished_event, operation_id.clone()).await, true)
+        }
+    };
+
+    if let Some(id) = &operation_id {
+        OPERATION_STDIN.lock().await.remove(id);
+    }
+    if own_changes {
+        window.state::<AppState>().note_own_changes();
+    }
+
+    let success_message = format!("{} completed successfully", operation_name);
+    if let Some(op_log) = &op_log {
+        let changes = env_diff::diff(&env_before, &env_changes::capture());
+        if !changes.is_empty() {
+            log::info!(
+                "{} changed {} environment variable(s)",
+                operation_name,
+                changes.len()
+            );
+            op_log.set_env_changes(changes);
+        }
+        if let Some(before) = size_before {
+            if let Some(after) = measure_reclaimable(&window, class).await {
+                op_log.set_freed_bytes(before.saturating_sub(after));
+            }
         }
+        op_log.finish(&result, &success_message, timed_out);
     }
+
+    complete_tracked_operation(CommandResult {
+        success: result.is_ok(),
+        message: match &result {
+            Ok(_) => success_message,
+            Err(e) => e.clone(),
+        },
+        operation_id: operation_id.clone(),
+        timed_out,
+    });
+
+    result.map_err(CommandError::Failed)
 }
 
 /// Handles the completion of the command, checking for errors and emitting the final result.
@@ -286,6 +833,7 @@ async fn handle_command_completion(
             success: was_successful,
             message: message.clone(),
             operation_id: operation_id.clone(),
+            timed_out: false,
         },
     ) {
         log::error!("Failed to emit finished event: {}", e);
@@ -308,10 +856,7 @@ async fn handle_cancellation(
 ) -> Result<(), String> {
     log::warn!("Cancelling operation: {}", operation_name);
 
-    // Try to kill the process
-    if let Err(e) = child.kill().await {
-        log::error!("Failed to kill child process: {}", e);
-    }
+    kill_process_tree(&mut child).await;
 
     let message = format!("{} was cancelled by user", operation_name);
     if let Err(e) = window.emit(
@@ -320,10 +865,98 @@ async fn handle_cancellation(
             success: false,
             message: message.clone(),
             operation_id: operation_id.clone(),
+            timed_out: false,
         },
     ) {
         log::error!("Failed to emit cancellation event: {}", e);
     }
 
     Err(message)
-}
\ No newline at end of file
+}
+
+/// Handles an operation that exceeded its timeout, killing the process tree and emitting a timed-out result.
+async fn handle_timeout(
+    mut child: Child,
+    operation_name: &str,
+    timeout: Duration,
+    window: &Window,
+    finished_event: &str,
+    operation_id: Option<String>,
+) -> Result<(), String> {
+    log::warn!(
+        "{} timed out after {} seconds, terminating process tree",
+        operation_name,
+        timeout.as_secs()
+    );
+
+    kill_process_tree(&mut child).await;
+
+    let message = format!(
+        "{} timed out after {} seconds and was terminated. It may be waiting on a hidden prompt or installer window.",
+        operation_name,
+        timeout.as_secs()
+    );
+    if let Err(e) = window.emit(
+        finished_event,
+        CommandResult {
+            success: false,
+            message: message.clone(),
+            operation_id: operation_id.clone(),
+            timed_out: true,
+        },
+    ) {
+        log::error!("Failed to emit timeout event: {}", e);
+    }
+
+    Err(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_install_changing_commands_are_own_changes() {
+        let custom = |command| changes_installs(OperationClass::Custom, command);
+        assert!(changes_installs(OperationClass::Install, "anything"));
+        assert!(custom("scoop update git"));
+        assert!(custom("& scoop Uninstall git"));
+        assert!(!custom("scoop status"));
+        assert!(!custom("scoop checkup"));
+        assert!(!changes_installs(OperationClass::Cleanup, "scoop cleanup"));
+    }
+
+    #[test]
+    fn quotes_literals_without_expansion() {
+        assert_eq!(quote_literal(r"C:\scoop"), r"'C:\scoop'");
+        assert_eq!(
+            quote_literal("C:\\it's $(calc) \"x\""),
+            "'C:\\it''s $(calc) \"x\"'"
+        );
+        assert_eq!(quote_literal("a\u{2019}b"), "'a\u{2019}\u{2019}b'");
+    }
+
+    #[tokio::test]
+    async fn unstarted_operations_end_their_waits() {
+        let waiter = tokio::spawn(wait_for_operation("test-rejected".to_string(), None));
+        tokio::task::yield_now().await;
+        fail_unstarted_operation(Some("test-rejected"), "Blocked by policy".to_string());
+        let result = waiter.await.unwrap().unwrap();
+        assert!(!result.success);
+        assert_eq!(result.message, "Blocked by policy");
+
+        // Instants cannot go back past the boot of a freshly started machine
+        let Some(expired) = std::time::Instant::now().checked_sub(PENDING_OPERATION_TTL) else {
+            return;
+        };
+        let mut result_rx = subscribe_operation("test-never-started");
+        {
+            let mut operations = OPERATION_RESULTS.lock().unwrap();
+            let op = operations.get_mut("test-never-started").unwrap();
+            op.created_at = expired;
+            prune_tracked_operations(&mut operations);
+            assert!(!operations.contains_key("test-never-started"));
+        }
+        assert!(result_rx.wait_for(|result| result.is_some()).await.is_err());
+    }
+}
@@ -0,0 +1,254 @@
+//! Tracking and cleanup of failed package installs.
+//!
+//! A failed `scoop install` can leave half-written version directories behind that
+//! the installed scanner would otherwise pick up. Such directories are renamed with
+//! a quarantine suffix so they are ignored until the install is retried.
+use crate::commands::auto_cleanup::trigger_auto_cleanup;
+use crate::commands::installed::invalidate_installed_cache;
+use crate::commands::scoop::{self, ScoopOp};
+use crate::commands::search::invalidate_manifest_cache;
+use crate::models::FailedInstall;
+use crate::state::AppState;
+use crate::utils::validate_dir_name;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State, Window};
+
+/// Marker inserted into the name of quarantined directories.
+pub const QUARANTINE_MARKER: &str = ".quarantined-";
+
+/// Returns `true` if the directory was quarantined after a failed install.
+pub fn is_quarantined(path: &Path) -> bool {
+    path.file_name()
+        .map(|n| n.to_string_lossy().contains(QUARANTINE_MARKER))
+        .unwrap_or(false)
+}
+
+fn quarantine_dir(path: &Path) -> Result<PathBuf, String> {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid directory: {}", path.display()))?;
+    let target = path.with_file_name(format!(
+        "{}{}{}",
+        file_name,
+        QUARANTINE_MARKER,
+        chrono::Utc::now().timestamp()
+    ));
+
+    fs::rename(path, &target)
+        .map_err(|e| format!("Failed to quarantine {}: {}", path.display(), e))?;
+    log::info!("Quarantined {} as {}", path.display(), target.display());
+    Ok(target)
+}
+
+/// Removes the `current` link without following it into the version directory.
+fn remove_current_link(app_dir: &Path) {
+    let current = app_dir.join("current");
+    if fs::symlink_metadata(&current).is_ok() {
+        // Junctions are removed like directories on Windows, symlinks like files elsewhere
+        if fs::remove_dir(&current).is_err() {
+            let _ = fs::remove_file(&current);
+        }
+    }
+}
+
+/// Quarantines version directories left behind by a failed install of `package`.
+///
+/// Version directories without an `install.json` were not completed by Scoop. If no
+/// completed version remains, the whole app directory is quarantined instead.
+pub fn quarantine_install_leftovers(scoop_path: &Path, package: &str) -> Vec<PathBuf> {
+    if let Err(e) = validate_dir_name("package", package) {
+        log::warn!("Not quarantining leftovers: {}", e);
+        return Vec::new();
+    }
+    let app_dir = scoop_path.join("apps").join(package);
+    if !app_dir.is_dir() {
+        return Vec::new();
+    }
+
+    let version_dirs: Vec<PathBuf> = fs::read_dir(&app_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_dir() && !is_quarantined(p))
+                .filter(|p| {
+                    !p.file_name()
+                        .map(|n| n.eq_ignore_ascii_case("current"))
+                        .unwrap_or(false)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let (complete, incomplete): (Vec<PathBuf>, Vec<PathBuf>) = version_dirs
+        .into_iter()
+        .partition(|p| p.join("install.json").is_file());
+
+    if complete.is_empty() {
+        remove_current_link(&app_dir);
+        return match quarantine_dir(&app_dir) {
+            Ok(path) => vec![path],
+            Err(e) => {
+                log::warn!("{}", e);
+                Vec::new()
+            }
+        };
+    }
+
+    incomplete
+        .iter()
+        .filter_map(|dir| match quarantine_dir(dir) {
+            Ok(path) => Some(path),
+            Err(e) => {
+                log::warn!("{}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Deletes every quarantined directory belonging to `package`.
+pub fn remove_quarantined_dirs(scoop_path: &Path, package: &str) -> Result<(), String> {
+    validate_dir_name("package", package)?;
+    let apps_dir = scoop_path.join("apps");
+    let app_prefix = format!("{}{}", package, QUARANTINE_MARKER).to_lowercase();
+
+    let mut targets: Vec<PathBuf> = fs::read_dir(&apps_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| {
+                    e.file_name()
+                        .to_string_lossy()
+                        .to_lowercase()
+                        .starts_with(&app_prefix)
+                })
+                .map(|e| e.path())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if let Ok(entries) = fs::read_dir(apps_dir.join(package)) {
+        targets.extend(
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| is_quarantined(p)),
+        );
+    }
+
+    for target in targets {
+        match fs::remove_dir_all(&target) {
+            Ok(_) => log::info!("Removed quarantined directory {}", target.display()),
+            Err(e) => log::warn!("Failed to remove {}: {}", target.display(), e),
+        }
+    }
+    Ok(())
+}
+
+/// Quarantines the leftovers of a failed install and records the failure in app state.
+///
+/// Only called by [`scoop::execute_scoop`] after Scoop ran, while it still holds the
+/// operation queue.
+pub async fn record_failed_install(
+    state: &AppState,
+    package: &str,
+    bucket: Option<&str>,
+    error: &str,
+) {
+    let quarantined = quarantine_install_leftovers(&state.scoop_path(), package);
+    log::warn!(
+        "Install of '{}' failed; quarantined {} directories",
+        package,
+        quarantined.len()
+    );
+
+    let mut failed = state.failed_installs.lock().await;
+    let mut quarantined_paths: Vec<String> = failed
+        .get(package)
+        .map(|f| f.quarantined_paths.clone())
+        .unwrap_or_default();
+    quarantined_paths.extend(quarantined.iter().map(|p| p.to_string_lossy().to_string()));
+
+    failed.insert(
+        package.to_string(),
+        FailedInstall {
+            package: package.to_string(),
+            bucket: bucket.map(String::from),
+            error: error.to_string(),
+            quarantined_paths,
+            failed_at: chrono::Utc::now().to_rfc3339(),
+        },
+    );
+}
+
+/// Lists installs that failed during this session.
+#[tauri::command]
+pub async fn list_failed_installs(
+    state: State<'_, AppState>,
+) -> Result<Vec<FailedInstall>, String> {
+    let failed = state.failed_installs.lock().await;
+    let mut installs: Vec<FailedInstall> = failed.values().cloned().collect();
+    installs.sort_by(|a, b| b.failed_at.cmp(&a.failed_at));
+    Ok(installs)
+}
+
+/// Removes the quarantined leftovers of a failed install and installs the package again.
+#[tauri::command]
+pub async fn retry_failed_install(
+    window: Window,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    package_name: String,
+) -> Result<(), String> {
+    validate_dir_name("package", &package_name)?;
+    let record = state
+        .failed_installs
+        .lock()
+        .await
+        .get(&package_name)
+        .cloned()
+        .ok_or_else(|| format!("No failed install recorded for '{}'", package_name))?;
+
+    log::info!("Retrying failed install of '{}'", package_name);
+    remove_quarantined_dirs(&state.scoop_path(), &package_name)?;
+    state.failed_installs.lock().await.remove(&package_name);
+
+    let operation_id = Some(format!(
+        "retry-install-{}-{}",
+        package_name,
+        chrono::Utc::now().timestamp()
+    ));
+    let result = scoop::execute_scoop(
+        window,
+        ScoopOp::Install,
+        Some(&package_name),
+        record.bucket.as_deref(),
+        operation_id,
+    )
+    .await;
+
+    if let Err(e) = result {
+        // A failure before Scoop ran records nothing, so keep the install retryable
+        state
+            .failed_installs
+            .lock()
+            .await
+            .entry(package_name.clone())
+            .or_insert(FailedInstall {
+                error: e.clone(),
+                quarantined_paths: Vec::new(),
+                ..record
+            });
+        invalidate_installed_cache(state.clone()).await;
+        return Err(e);
+    }
+
+    invalidate_manifest_cache().await;
+    invalidate_installed_cache(state.clone()).await;
+    trigger_auto_cleanup(app, state).await;
+
+    Ok(())
+}
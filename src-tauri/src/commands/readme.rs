@@ -0,0 +1,277 @@
+//! README previews for GitHub-hosted packages.
+//!
+//! The repository is taken from the manifest's `homepage`, `checkver.github` or download
+//! URLs. READMEs are fetched through the GitHub API, which allows 60 unauthenticated
+//! requests an hour, so they are cached for the session and no request is made while
+//! the limit is exhausted. The markdown is rendered to HTML here and cleaned with an
+//! allowlist of tags, attributes and URL schemes, since the webview showing it can call
+//! into the app.
+use crate::state::AppState;
+use crate::utils;
+use base64::{engine::general_purpose, Engine as _};
+use once_cell::sync::Lazy;
+use pulldown_cmark::{Options, Parser};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::State;
+use tokio::sync::Mutex;
+
+/// How long a fetched README, or the lack of one, is reused.
+const README_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+/// READMEs longer than this are cut off.
+const MAX_README_BYTES: usize = 256 * 1024;
+/// URL schemes links and images may use; relative URLs are kept.
+const URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+static GITHUB_REPO: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^https?://(?:www\.)?github\.com/([\w.-]+)/([\w.-]+)").unwrap());
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageReadme {
+    /// `owner/repo` on GitHub.
+    pub repository: String,
+    /// The README's page on GitHub.
+    pub url: String,
+    /// The README rendered to HTML with only allowlisted markup left, safe to insert.
+    pub html: String,
+    pub truncated: bool,
+}
+
+#[derive(Deserialize)]
+struct ReadmeResponse {
+    content: String,
+    encoding: String,
+    html_url: String,
+}
+
+struct CachedReadme {
+    readme: Option<PackageReadme>,
+    fetched_at: Instant,
+}
+
+/// Fetched READMEs by lowercased `owner/repo`.
+static README_CACHE: Lazy<Mutex<HashMap<String, CachedReadme>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+/// When the exhausted API rate limit resets.
+static RATE_LIMITED_UNTIL: Lazy<Mutex<Option<SystemTime>>> = Lazy::new(|| Mutex::new(None));
+
+/// The GitHub repository a manifest points at: its homepage, `checkver.github` or the
+/// first GitHub download URL.
+pub fn github_repository(manifest: &Value) -> Option<(String, String)> {
+    let mut candidates: Vec<&str> = Vec::new();
+    candidates.extend(manifest.get("homepage").and_then(Value::as_str));
+    candidates.extend(
+        manifest
+            .get("checkver")
+            .and_then(|c| c.get("github"))
+            .and_then(Value::as_str),
+    );
+    let mut urls = vec![manifest.get("url")];
+    if let Some(architectures) = manifest.get("architecture").and_then(Value::as_object) {
+        urls.extend(architectures.values().map(|a| a.get("url")));
+    }
+    for url in urls.into_iter().flatten() {
+        match url {
+            Value::String(s) => candidates.push(s),
+            Value::Array(list) => candidates.extend(list.iter().filter_map(Value::as_str)),
+            _ => {}
+        }
+    }
+
+    candidates.into_iter().find_map(|url| {
+        let captures = GITHUB_REPO.captures(url.trim())?;
+        let repo = captures[2].trim_end_matches(".git");
+        (!repo.is_empty()).then(|| (captures[1].to_string(), repo.to_string()))
+    })
+}
+
+/// Renders README markdown, cut off at [`MAX_README_BYTES`], to HTML and cleans it with
+/// an allowlist: anything not known to be inert, including raw HTML the markdown embeds,
+/// is dropped.
+pub fn render_readme(markdown: &str) -> (String, bool) {
+    let truncated = markdown.len() > MAX_README_BYTES;
+    let mut end = markdown.len().min(MAX_README_BYTES);
+    while !markdown.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, Parser::new_ext(&markdown[..end], options));
+    let clean = ammonia::Builder::default()
+        .url_schemes(URL_SCHEMES.iter().copied().collect::<HashSet<_>>())
+        .clean(&html)
+        .to_string();
+    (clean, truncated)
+}
+
+fn header_u64(response: &reqwest::Response, name: &str) -> Option<u64> {
+    response.headers().get(name)?.to_str().ok()?.parse().ok()
+}
+
+/// Remembers the reset time when the response shows the rate limit is used up.
+async fn note_rate_limit(response: &reqwest::Response) {
+    if header_u64(response, "x-ratelimit-remaining") != Some(0) {
+        return;
+    }
+    let reset = header_u64(response, "x-ratelimit-reset")
+        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+        .unwrap_or_else(|| SystemTime::now() + Duration::from_secs(60 * 60));
+    log::warn!("GitHub API rate limit exhausted; not fetching READMEs until it resets");
+    *RATE_LIMITED_UNTIL.lock().await = Some(reset);
+}
+
+async fn fetch_readme(owner: &str, repo: &str) -> Result<Option<PackageReadme>, String> {
+    if let Some(until) = *RATE_LIMITED_UNTIL.lock().await {
+        if let Ok(wait) = until.duration_since(SystemTime::now()) {
+            return Err(format!(
+                "GitHub API rate limit reached; try again in {} minutes",
+                wait.as_secs().div_ceil(60)
+            ));
+        }
+    }
+
+    let url = format!("https://api.github.com/repos/{}/{}/readme", owner, repo);
+    let response = crate::http::get(&url)
+        .await
+        .map_err(|e| format!("Failed to fetch README of {}/{}: {}", owner, repo, e))?;
+    note_rate_limit(&response).await;
+    match response.status() {
+        reqwest::StatusCode::NOT_FOUND => return Ok(None),
+        status if status.is_success() => {}
+        reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::TOO_MANY_REQUESTS => {
+            return Err("GitHub API rate limit reached".to_string())
+        }
+        status => {
+            return Err(format!(
+                "GitHub API returned {} for the README of {}/{}",
+                status, owner, repo
+            ))
+        }
+    }
+
+    let body: ReadmeResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse README response: {}", e))?;
+    if body.encoding != "base64" {
+        return Err(format!("Unsupported README encoding '{}'", body.encoding));
+    }
+    let encoded: String = body.content.split_whitespace().collect();
+    let bytes = general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("Failed to decode README: {}", e))?;
+    let (html, truncated) = render_readme(&String::from_utf8_lossy(&bytes));
+
+    Ok(Some(PackageReadme {
+        repository: format!("{}/{}", owner, repo),
+        url: body.html_url,
+        html,
+        truncated,
+    }))
+}
+
+/// Returns the README of the package's GitHub repository as cleaned HTML, or
+/// `None` when the package is not hosted on GitHub or its repository has no README.
+#[tauri::command]
+pub async fn get_package_readme(
+    state: State<'_, AppState>,
+    package_name: String,
+    bucket: Option<String>,
+) -> Result<Option<PackageReadme>, String> {
+    let scoop_dir = state.scoop_path();
+    let bucket = bucket.filter(|b| !b.is_empty() && !b.eq_ignore_ascii_case("none"));
+    let (manifest_path, _) = utils::locate_package_manifest(&scoop_dir, &package_name, bucket)?;
+    let manifest: Value = fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .ok_or_else(|| format!("Failed to read manifest for {}", package_name))?;
+
+    let Some((owner, repo)) = github_repository(&manifest) else {
+        return Ok(None);
+    };
+    let key = format!("{}/{}", owner, repo).to_lowercase();
+    if let Some(cached) = README_CACHE.lock().await.get(&key) {
+        if cached.fetched_at.elapsed() < README_TTL {
+            return Ok(cached.readme.clone());
+        }
+    }
+
+    log::info!("Fetching README of {}/{} for {}", owner, repo, package_name);
+    let readme = fetch_readme(&owner, &repo).await?;
+    README_CACHE.lock().await.insert(
+        key,
+        CachedReadme {
+            readme: readme.clone(),
+            fetched_at: Instant::now(),
+        },
+    );
+    Ok(readme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn finds_repository_and_sanitizes_markdown() {
+        let manifest = json!({
+            "homepage": "https://example.com",
+            "url": "https://github.com/BurntSushi/ripgrep/releases/download/14.1.0/rg.zip"
+        });
+        assert_eq!(
+            github_repository(&manifest),
+            Some(("BurntSushi".to_string(), "ripgrep".to_string()))
+        );
+        let manifest = json!({ "homepage": "https://github.com/git-for-windows/git.git" });
+        assert_eq!(
+            github_repository(&manifest).map(|(_, repo)| repo),
+            Some("git".to_string())
+        );
+        assert_eq!(
+            github_repository(&json!({ "homepage": "https://7-zip.org" })),
+            None
+        );
+
+        let (html, truncated) = render_readme(
+            "# Tool\n<!-- hidden --><script>alert(1)</script>\n\n\
+             <img src=\"logo.png\" onerror=\"alert(1)\"> [x](javascript:alert(1))",
+        );
+        assert!(!truncated);
+        assert_eq!(
+            html,
+            "<h1>Tool</h1>\n\n<p><img src=\"logo.png\"> <a rel=\"noopener noreferrer\">x</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn readme_html_survives_filter_bypasses() {
+        let render = |markdown: &str| render_readme(markdown).0;
+        assert_eq!(
+            render("<img/onerror=alert(1) src=x>"),
+            "<p>&lt;img/onerror=alert(1) src=x&gt;</p>\n"
+        );
+        assert_eq!(
+            render("<div><img/onerror=alert(1) src=x></div>"),
+            "<div><img src=\"x\"></div>"
+        );
+        assert_eq!(
+            render("<a href=\"jav&#x61;script:alert(1)\">x</a>"),
+            "<p><a rel=\"noopener noreferrer\">x</a></p>\n"
+        );
+        // Never closed, so it is text rather than a tag
+        let unterminated = render("text <img src=x onerror=alert(1)");
+        assert!(!unterminated.contains("<img"), "{}", unterminated);
+        assert_eq!(
+            render("[ok](https://example.com)"),
+            "<p><a href=\"https://example.com\" rel=\"noopener noreferrer\">ok</a></p>\n"
+        );
+    }
+}
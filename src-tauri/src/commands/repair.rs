@@ -0,0 +1,476 @@
+//! Commands for repairing installs whose Scoop metadata is missing or broken.
+use crate::commands::doctor::shim::write_shim;
+use crate::commands::installed::invalidate_installed_cache;
+use crate::engine::fingerprint::locate_install_dir;
+use crate::engine::manifest::find_manifest_candidates;
+use crate::state::AppState;
+use crate::utils::validate_dir_name;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Architecture name Scoop uses for the running machine.
+pub fn host_architecture() -> &'static str {
+    if cfg!(target_arch = "aarch64") {
+        "arm64"
+    } else if cfg!(target_arch = "x86") {
+        "32bit"
+    } else {
+        "64bit"
+    }
+}
+
+/// Collects every download URL in a manifest, including per-architecture ones.
+fn manifest_urls(manifest: &Value) -> Vec<String> {
+    let mut urls = Vec::new();
+    let mut push = |value: Option<&Value>| match value {
+        Some(Value::String(url)) => urls.push(url.to_lowercase()),
+        Some(Value::Array(list)) => urls.extend(
+            list.iter()
+                .filter_map(Value::as_str)
+                .map(|url| url.to_lowercase()),
+        ),
+        _ => {}
+    };
+    push(manifest.get("url"));
+    if let Some(Value::Object(arches)) = manifest.get("architecture") {
+        for arch in arches.values() {
+            push(arch.get("url"));
+        }
+    }
+    urls
+}
+
+/// Scores how well a bucket manifest matches the installed one.
+fn match_score(installed: &Value, candidate: &Value) -> u32 {
+    let field = |manifest: &Value, key: &str| {
+        manifest
+            .get(key)
+            .and_then(Value::as_str)
+            .map(|s| s.trim().to_lowercase())
+    };
+
+    let mut score = 0;
+    if field(installed, "version").is_some()
+        && field(installed, "version") == field(candidate, "version")
+    {
+        score += 2;
+    }
+    if field(installed, "homepage").is_some()
+        && field(installed, "homepage") == field(candidate, "homepage")
+    {
+        score += 2;
+    }
+    let installed_urls = manifest_urls(installed);
+    if manifest_urls(candidate)
+        .iter()
+        .any(|url| installed_urls.contains(url))
+    {
+        score += 3;
+    }
+    score
+}
+
+/// Picks the bucket whose manifest best matches the installed manifest.
+///
+/// `preferred` (the bucket from a previous install.json) wins ties. A lone candidate is
+/// accepted even without matching fields, since the package name already matches.
+fn select_bucket(
+    candidates: &[(PathBuf, String)],
+    installed: &Value,
+    preferred: Option<&str>,
+) -> Option<String> {
+    let scored: Vec<(u32, &String)> = candidates
+        .iter()
+        .map(|(path, bucket)| {
+            let manifest = fs::read_to_string(path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+                .unwrap_or(Value::Null);
+            let mut score = match_score(installed, &manifest) * 2;
+            if preferred.is_some_and(|p| p.eq_ignore_ascii_case(bucket)) {
+                score += 1;
+            }
+            (score, bucket)
+        })
+        .collect();
+
+    if scored.len() == 1 {
+        return Some(scored[0].1.clone());
+    }
+    scored
+        .into_iter()
+        .filter(|(score, _)| *score > 0)
+        .max_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.cmp(a.1)))
+        .map(|(_, bucket)| bucket.clone())
+}
+
+/// Builds a repaired install.json for the package installed in `install_dir`.
+///
+/// Fields of a still-parseable install.json (such as `hold` or `url`) are kept; only
+/// `bucket` and `architecture` are filled in.
+fn build_install_metadata(
+    scoop_path: &Path,
+    package_name: &str,
+    install_dir: &Path,
+) -> Result<Map<String, Value>, String> {
+    let installed: Value = fs::read_to_string(install_dir.join("manifest.json"))
+        .map_err(|e| {
+            format!(
+                "Failed to read installed manifest for '{}': {}",
+                package_name, e
+            )
+        })
+        .and_then(|content| {
+            serde_json::from_str(&content).map_err(|e| {
+                format!(
+                    "Failed to parse installed manifest for '{}': {}",
+                    package_name, e
+                )
+            })
+        })?;
+
+    let mut metadata = fs::read_to_string(install_dir.join("install.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|value| value.as_object().cloned())
+        .unwrap_or_default();
+
+    let candidates = find_manifest_candidates(scoop_path, package_name);
+    let previous_bucket = metadata.get("bucket").and_then(Value::as_str);
+    let bucket = select_bucket(&candidates, &installed, previous_bucket).ok_or_else(|| {
+        if candidates.is_empty() {
+            format!("No bucket provides a manifest for '{}'", package_name)
+        } else {
+            format!(
+                "Could not determine which bucket '{}' was installed from (candidates: {})",
+                package_name,
+                candidates
+                    .iter()
+                    .map(|(_, b)| b.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        }
+    })?;
+    metadata.insert("bucket".to_string(), Value::String(bucket));
+
+    if !metadata.get("architecture").is_some_and(Value::is_string) {
+        let architecture = match installed.get("architecture") {
+            Some(Value::Object(arches)) if !arches.contains_key(host_architecture()) => arches
+                .keys()
+                .next()
+                .cloned()
+                .unwrap_or_else(|| host_architecture().to_string()),
+            _ => host_architecture().to_string(),
+        };
+        metadata.insert("architecture".to_string(), Value::String(architecture));
+    }
+
+    Ok(metadata)
+}
+
+/// Regenerates a missing or broken install.json by matching the installed manifest
+/// against the available buckets.
+///
+/// The previous file, if any, is kept as `install.json.bak`.
+#[tauri::command]
+pub async fn repair_install_metadata(
+    state: State<'_, AppState>,
+    package_name: String,
+) -> Result<String, String> {
+    let scoop_path = state.scoop_path();
+    let package_dir = scoop_path.join("apps").join(&package_name);
+    let install_dir = locate_install_dir(&package_dir)
+        .ok_or_else(|| format!("Package '{}' is not installed", package_name))?;
+
+    let metadata = build_install_metadata(&scoop_path, &package_name, &install_dir)?;
+
+    let install_json = install_dir.join("install.json");
+    if install_json.exists() {
+        fs::copy(&install_json, install_dir.join("install.json.bak"))
+            .map_err(|e| format!("Failed to back up install.json: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&Value::Object(metadata.clone()))
+        .map_err(|e| format!("Failed to serialize install.json: {}", e))?;
+    fs::write(&install_json, content)
+        .map_err(|e| format!("Failed to write install.json: {}", e))?;
+
+    let bucket = metadata
+        .get("bucket")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    log::info!(
+        "Repaired install.json for '{}' (bucket '{}')",
+        package_name,
+        bucket
+    );
+    invalidate_installed_cache(state).await;
+
+    Ok(format!(
+        "Repaired install metadata for '{}' (bucket '{}')",
+        package_name, bucket
+    ))
+}
+
+/// Version recorded for adopted apps when none is given.
+const ADOPTED_DEFAULT_VERSION: &str = "0.0.0";
+
+/// Returns `true` if a directory looks like a Scoop version directory.
+fn has_scoop_metadata(dir: &Path) -> bool {
+    dir.join("manifest.json").exists() || dir.join("install.json").exists()
+}
+
+/// Moves a manually copied app into a version directory and writes minimal metadata.
+///
+/// If the package directory already holds exactly one subdirectory (besides `current`),
+/// that is used as the version directory; otherwise the loose files are moved into
+/// `<version>`. Returns the version directory and the executables to shim, which are
+/// `bins` if given, or every top-level `.exe` otherwise.
+fn scaffold_adopted_package(
+    package_dir: &Path,
+    version: Option<&str>,
+    bins: Option<Vec<String>>,
+) -> Result<(PathBuf, Vec<String>), String> {
+    let entries: Vec<PathBuf> = fs::read_dir(package_dir)
+        .map_err(|e| format!("Failed to read {}: {}", package_dir.display(), e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().map_or(true, |n| n != "current"))
+        .collect();
+    if entries.is_empty() {
+        return Err(format!("{} is empty", package_dir.display()));
+    }
+    if entries
+        .iter()
+        .any(|path| path.is_dir() && has_scoop_metadata(path))
+    {
+        return Err(format!(
+            "{} is already managed by Scoop",
+            package_dir.display()
+        ));
+    }
+
+    let version_dir = match entries.as_slice() {
+        [single] if single.is_dir() && version.is_none() => single.clone(),
+        _ => {
+            let version_dir = package_dir.join(version.unwrap_or(ADOPTED_DEFAULT_VERSION));
+            if version_dir.exists() {
+                return Err(format!("{} already exists", version_dir.display()));
+            }
+            fs::create_dir(&version_dir)
+                .map_err(|e| format!("Failed to create {}: {}", version_dir.display(), e))?;
+            for entry in &entries {
+                let target = version_dir.join(entry.file_name().unwrap_or_default());
+                fs::rename(entry, &target)
+                    .map_err(|e| format!("Failed to move {}: {}", entry.display(), e))?;
+            }
+            version_dir
+        }
+    };
+    let version = version_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| ADOPTED_DEFAULT_VERSION.to_string());
+
+    let bins = bins.unwrap_or_else(|| {
+        let mut exes: Vec<String> = fs::read_dir(&version_dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .filter(|name| name.to_lowercase().ends_with(".exe"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        exes.sort();
+        exes
+    });
+
+    let manifest = serde_json::json!({
+        "version": version,
+        "description": "Adopted into Scoop by Pailer",
+        "bin": bins,
+    });
+    let install = serde_json::json!({ "architecture": host_architecture() });
+    for (file, value) in [("manifest.json", manifest), ("install.json", install)] {
+        let content = serde_json::to_string_pretty(&value)
+            .map_err(|e| format!("Failed to serialize {}: {}", file, e))?;
+        fs::write(version_dir.join(file), content)
+            .map_err(|e| format!("Failed to write {}: {}", file, e))?;
+    }
+
+    Ok((version_dir, bins))
+}
+
+/// Makes an app that was copied into `apps` by hand manageable through Scoop.
+///
+/// Creates a version directory with a minimal manifest and install.json, links
+/// `current` to it and creates shims for its executables. The app has no bucket, so it
+/// is treated like a versioned install and never offered for update.
+#[tauri::command]
+pub async fn adopt_package(
+    state: State<'_, AppState>,
+    package_name: String,
+    version: Option<String>,
+    bins: Option<Vec<String>>,
+) -> Result<String, String> {
+    validate_dir_name("package", &package_name)?;
+    if let Some(version) = &version {
+        validate_dir_name("version", version)?;
+    }
+    let dirs = state.scoop_dirs();
+    let package_dir = dirs.apps.join(&package_name);
+    if !package_dir.is_dir() {
+        return Err(format!(
+            "'{}' was not found in {}",
+            package_name,
+            dirs.apps.display()
+        ));
+    }
+    if package_dir.join("current").exists() {
+        return Err(format!("'{}' already has a current version", package_name));
+    }
+
+    let (version_dir, bins) = scaffold_adopted_package(&package_dir, version.as_deref(), bins)?;
+    let current = package_dir.join("current");
+    crate::commands::linker::create_junction(&current, &version_dir).await?;
+
+    fs::create_dir_all(&dirs.shims)
+        .map_err(|e| format!("Failed to create shims directory: {}", e))?;
+    let mut shim_errors = Vec::new();
+    for bin in &bins {
+        let name = Path::new(bin)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| bin.clone());
+        let target = current.join(bin).to_string_lossy().to_string();
+        if let Err(e) = write_shim(&dirs, &dirs.shims, &name, &target, None) {
+            log::warn!(
+                "Failed to create shim '{}' for adopted package: {}",
+                name,
+                e
+            );
+            shim_errors.push(format!("{}: {}", name, e));
+        }
+    }
+
+    log::info!(
+        "Adopted '{}' into {} with {} shim(s)",
+        package_name,
+        version_dir.display(),
+        bins.len() - shim_errors.len()
+    );
+    invalidate_installed_cache(state).await;
+
+    if shim_errors.is_empty() {
+        Ok(format!("Adopted '{}'", package_name))
+    } else {
+        Ok(format!(
+            "Adopted '{}', but some shims could not be created: {}",
+            package_name,
+            shim_errors.join("; ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{read_json, write_file, ScoopFixture};
+    use serde_json::json;
+
+    #[test]
+    fn picks_bucket_with_matching_manifest() {
+        let fixture = ScoopFixture::new();
+        fixture
+            .bucket_manifest_json(
+                "extras",
+                "tool",
+                json!({ "version": "1.0", "url": "https://mirror.example/tool.zip" }),
+            )
+            .bucket_manifest_json(
+                "main",
+                "tool",
+                json!({ "version": "2.0", "url": "https://tool.example/tool.zip" }),
+            );
+        fixture.app_version("tool", "1.0", None);
+        fixture.installed_manifest(
+            "tool",
+            "1.0",
+            json!({ "version": "1.0", "url": "https://tool.example/tool.zip" }),
+        );
+        let install_dir = fixture.corrupt_install_info("tool", "1.0");
+
+        let metadata = build_install_metadata(fixture.root(), "tool", &install_dir).unwrap();
+        assert_eq!(metadata["bucket"], "main");
+        assert_eq!(metadata["architecture"], host_architecture());
+    }
+
+    #[test]
+    fn keeps_existing_fields_and_prefers_previous_bucket() {
+        let fixture = ScoopFixture::new();
+        fixture
+            .bucket_manifest("extras", "tool", "1.0")
+            .bucket_manifest("main", "tool", "1.0");
+        fixture.app_version("tool", "1.0", Some("main"));
+        let install_dir =
+            fixture.install_info("tool", "1.0", json!({ "bucket": "main", "hold": true }));
+
+        let metadata = build_install_metadata(fixture.root(), "tool", &install_dir).unwrap();
+        assert_eq!(metadata["bucket"], "main");
+        assert_eq!(metadata["hold"], true);
+    }
+
+    #[test]
+    fn fails_without_any_bucket() {
+        let fixture = ScoopFixture::new();
+        let install_dir = fixture.app_version("orphan", "1.0", None);
+
+        assert!(build_install_metadata(fixture.root(), "orphan", &install_dir).is_err());
+    }
+
+    #[test]
+    fn adoption_moves_loose_files_into_version_dir() {
+        let fixture = ScoopFixture::new();
+        let package_dir = fixture.app_dir("portable");
+        fs::create_dir_all(package_dir.join("data")).unwrap();
+        write_file(&package_dir.join("portable.exe"), b"MZ");
+        write_file(&package_dir.join("readme.txt"), b"hi");
+
+        let (version_dir, bins) =
+            scaffold_adopted_package(&package_dir, Some("2.1"), None).unwrap();
+        assert_eq!(version_dir, package_dir.join("2.1"));
+        assert_eq!(bins, ["portable.exe"]);
+        assert!(version_dir.join("data").is_dir());
+        assert!(version_dir.join("readme.txt").is_file());
+
+        assert_eq!(
+            read_json(&version_dir.join("manifest.json"))["version"],
+            "2.1"
+        );
+        let install = read_json(&version_dir.join("install.json"));
+        assert!(install.get("bucket").is_none());
+    }
+
+    #[test]
+    fn adoption_reuses_single_subdirectory_and_rejects_managed_apps() {
+        let fixture = ScoopFixture::new();
+        let package_dir = fixture.app_dir("tool");
+        write_file(&package_dir.join("1.4.0").join("tool.exe"), b"MZ");
+
+        let (version_dir, _) = scaffold_adopted_package(&package_dir, None, None).unwrap();
+        assert_eq!(version_dir, package_dir.join("1.4.0"));
+
+        assert!(scaffold_adopted_package(&package_dir, None, None).is_err());
+    }
+
+    #[test]
+    fn only_plain_names_are_adopted() {
+        for name in ["..", "../..", "a/b", r"a\b", "C:", ".git", ""] {
+            assert!(validate_dir_name("package", name).is_err(), "{}", name);
+        }
+        assert!(validate_dir_name("package", "7zip").is_ok());
+    }
+}
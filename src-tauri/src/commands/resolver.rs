@@ -0,0 +1,307 @@
+//! Package name resolution that tolerates casing differences, common aliases and typos.
+//!
+//! Scoop stores packages in directories named exactly like their manifest, so commands
+//! that build paths from user input fail on `Git` vs `git` or `7-zip` vs `7zip`. The
+//! helpers here map such input onto a real package name, or return a list of candidates
+//! when the intent is ambiguous.
+use crate::commands::quarantine::is_quarantined;
+use crate::state::AppState;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+/// Well-known alternative names that do not normalize to the real package name.
+static PACKAGE_ALIASES: &[(&str, &str)] = &[
+    ("7z", "7zip"),
+    ("code", "vscode"),
+    ("visualstudiocode", "vscode"),
+    ("node", "nodejs"),
+    ("golang", "go"),
+    ("python3", "python"),
+    ("notepadpp", "notepadplusplus"),
+    ("notepad++", "notepadplusplus"),
+    ("vlcplayer", "vlc"),
+    ("chrome", "googlechrome"),
+];
+
+/// Maximum number of candidates returned for a disambiguation list.
+const MAX_CANDIDATES: usize = 5;
+
+/// The outcome of resolving a user-provided package name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameMatch {
+    Found(String),
+    Ambiguous(Vec<String>),
+    NotFound,
+}
+
+/// Result returned to the frontend by `resolve_package_name`.
+#[derive(Serialize, Debug, Clone)]
+pub struct PackageNameResolution {
+    pub query: String,
+    pub resolved: Option<String>,
+    pub candidates: Vec<String>,
+}
+
+/// Lowercases a name and strips separators so `7-Zip`, `7zip` and `7_zip` compare equal.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '-' | '_' | '.' | ' '))
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b_chars.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1; b_chars.len() + 1];
+        for (j, cb) in b_chars.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+
+    prev[b_chars.len()]
+}
+
+/// Resolves `query` against a list of known package names.
+///
+/// Exact, case-insensitive, separator-insensitive and alias matches resolve directly.
+/// Fuzzy matches are never picked automatically; they are returned as candidates.
+pub fn resolve_name(query: &str, names: &[String]) -> NameMatch {
+    if names.iter().any(|n| n == query) {
+        return NameMatch::Found(query.to_string());
+    }
+
+    let case_matches: Vec<&String> = names
+        .iter()
+        .filter(|n| n.eq_ignore_ascii_case(query))
+        .collect();
+    if case_matches.len() == 1 {
+        return NameMatch::Found(case_matches[0].clone());
+    }
+
+    let normalized_query = normalize(query);
+    let alias_target = PACKAGE_ALIASES
+        .iter()
+        .find(|(alias, _)| normalize(alias) == normalized_query)
+        .map(|(_, target)| normalize(target));
+
+    for target in std::iter::once(normalized_query.clone()).chain(alias_target) {
+        let matches: Vec<String> = names
+            .iter()
+            .filter(|n| normalize(n) == target)
+            .cloned()
+            .collect();
+        match matches.len() {
+            0 => continue,
+            1 => return NameMatch::Found(matches[0].clone()),
+            _ => return NameMatch::Ambiguous(matches),
+        }
+    }
+
+    // Fuzzy fallback: small edit distance or containment
+    let max_distance = (normalized_query.len() / 4).clamp(1, 2);
+    let mut scored: Vec<(usize, &String)> = names
+        .iter()
+        .filter_map(|n| {
+            let normalized = normalize(n);
+            let distance = levenshtein(&normalized_query, &normalized);
+            if distance <= max_distance {
+                Some((distance, n))
+            } else if normalized_query.len() >= 3
+                && (normalized.starts_with(&normalized_query)
+                    || normalized_query.starts_with(&normalized))
+            {
+                Some((max_distance + 1, n))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    if scored.is_empty() {
+        return NameMatch::NotFound;
+    }
+
+    scored.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then_with(|| a.1.len().cmp(&b.1.len()))
+            .then_with(|| a.1.cmp(b.1))
+    });
+    NameMatch::Ambiguous(
+        scored
+            .into_iter()
+            .take(MAX_CANDIDATES)
+            .map(|(_, n)| n.clone())
+            .collect(),
+    )
+}
+
+/// Lists the directory names of all installed packages.
+pub fn list_installed_names(scoop_dir: &Path) -> Vec<String> {
+    fs::read_dir(scoop_dir.join("apps"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir() && !is_quarantined(&e.path()))
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Lists the names of all manifests available in local buckets (deduplicated).
+pub fn list_manifest_names(scoop_dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(scoop_dir.join("buckets"))
+        .map(|buckets| {
+            buckets
+                .flatten()
+                .flat_map(|bucket| {
+                    fs::read_dir(bucket.path().join("bucket"))
+                        .map(|entries| {
+                            entries
+                                .flatten()
+                                .map(|e| e.path())
+                                .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("json"))
+                                .filter_map(|p| {
+                                    p.file_stem().and_then(|s| s.to_str()).map(String::from)
+                                })
+                                .collect::<Vec<_>>()
+                        })
+                        .unwrap_or_default()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn not_found_error(query: &str, candidates: &[String], what: &str) -> String {
+    if candidates.is_empty() {
+        format!("Package '{}' {}.", query, what)
+    } else {
+        format!(
+            "Package '{}' {}. Did you mean: {}?",
+            query,
+            what,
+            candidates.join(", ")
+        )
+    }
+}
+
+/// Resolves a name against installed packages, returning the on-disk directory name.
+pub fn resolve_installed_package(scoop_dir: &Path, query: &str) -> Result<String, String> {
+    match resolve_name(query, &list_installed_names(scoop_dir)) {
+        NameMatch::Found(name) => {
+            if name != query {
+                log::info!("Resolved package name '{}' to installed '{}'", query, name);
+            }
+            Ok(name)
+        }
+        NameMatch::Ambiguous(candidates) => {
+            Err(not_found_error(query, &candidates, "is not installed"))
+        }
+        NameMatch::NotFound => Err(not_found_error(query, &[], "is not installed")),
+    }
+}
+
+/// Resolves a name against installed packages first, then against bucket manifests.
+pub fn resolve_any_package(scoop_dir: &Path, query: &str) -> Result<String, String> {
+    let installed = resolve_name(query, &list_installed_names(scoop_dir));
+    if let NameMatch::Found(name) = installed {
+        return Ok(name);
+    }
+
+    // Avoid listing every manifest when the name already exists verbatim in a bucket
+    if crate::utils::locate_package_manifest(scoop_dir, query, None).is_ok() {
+        return Ok(query.to_string());
+    }
+
+    match resolve_name(query, &list_manifest_names(scoop_dir)) {
+        NameMatch::Found(name) => {
+            log::info!("Resolved package name '{}' to '{}'", query, name);
+            Ok(name)
+        }
+        NameMatch::Ambiguous(candidates) => {
+            Err(not_found_error(query, &candidates, "was not found"))
+        }
+        NameMatch::NotFound => match installed {
+            NameMatch::Ambiguous(candidates) => {
+                Err(not_found_error(query, &candidates, "was not found"))
+            }
+            _ => Err(not_found_error(query, &[], "was not found")),
+        },
+    }
+}
+
+/// Resolves a user-provided package name, returning either the matching package or
+/// a list of candidates to disambiguate between.
+#[tauri::command]
+pub fn resolve_package_name(
+    state: State<'_, AppState>,
+    package_name: String,
+    installed_only: Option<bool>,
+) -> Result<PackageNameResolution, String> {
+    let scoop_dir = state.scoop_path();
+
+    let mut result = resolve_name(&package_name, &list_installed_names(&scoop_dir));
+    if !installed_only.unwrap_or(false) && !matches!(result, NameMatch::Found(_)) {
+        result = resolve_name(&package_name, &list_manifest_names(&scoop_dir));
+    }
+
+    let (resolved, candidates) = match result {
+        NameMatch::Found(name) => (Some(name), Vec::new()),
+        NameMatch::Ambiguous(candidates) => (None, candidates),
+        NameMatch::NotFound => (None, Vec::new()),
+    };
+
+    Ok(PackageNameResolution {
+        query: package_name,
+        resolved,
+        candidates,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn resolves_case_and_separator_differences() {
+        let installed = names(&["git", "7zip", "nodejs"]);
+        assert_eq!(
+            resolve_name("Git", &installed),
+            NameMatch::Found("git".into())
+        );
+        assert_eq!(
+            resolve_name("7-Zip", &installed),
+            NameMatch::Found("7zip".into())
+        );
+        assert_eq!(
+            resolve_name("node", &installed),
+            NameMatch::Found("nodejs".into())
+        );
+    }
+
+    #[test]
+    fn fuzzy_matches_are_only_suggested() {
+        let installed = names(&["python", "pythonw", "git"]);
+        match resolve_name("pyton", &installed) {
+            NameMatch::Ambiguous(candidates) => assert_eq!(candidates[0], "python"),
+            other => panic!("expected candidates, got {:?}", other),
+        }
+        assert_eq!(resolve_name("zzz", &installed), NameMatch::NotFound);
+    }
+}
@@ -1,10 +1,23 @@
-use super::powershell::{self, EVENT_CANCEL, EVENT_FINISHED, EVENT_OUTPUT};
-use tauri::Window;
+use super::external_scoop::guard_external_scoop;
+use super::hooks::{self, HookContext, HookEvent};
+use super::info::get_installed_version;
+use super::package_source::{get_bucket_priority_list, prioritized_candidates, select_source};
+use super::package_state::{update_all_targets, update_package_state};
+use super::powershell::{
+    self, CommandError, OperationClass, EVENT_CANCEL, EVENT_FINISHED, EVENT_OUTPUT,
+};
+use super::quarantine::record_failed_install;
+use crate::models::PackageActivity;
+use crate::state::AppState;
+use std::path::Path;
+use tauri::{Manager, Window};
 
 /// Defines the supported Scoop operations.
 #[derive(Debug, Clone, Copy)]
 pub enum ScoopOp {
     Install,
+    /// Installs from a local manifest file; the package argument is the file's path.
+    InstallManifest,
     Uninstall,
     Update,
     UpdateForce,
@@ -12,6 +25,58 @@ pub enum ScoopOp {
     UpdateAll,
 }
 
+impl ScoopOp {
+    /// Name the operation is recorded under in the local metrics.
+    fn metric_name(self) -> &'static str {
+        match self {
+            ScoopOp::Install | ScoopOp::InstallManifest => "install",
+            ScoopOp::Uninstall => "uninstall",
+            ScoopOp::Update | ScoopOp::UpdateForce => "update",
+            ScoopOp::ClearCache => "clearCache",
+            ScoopOp::UpdateAll => "updateAll",
+        }
+    }
+
+    /// State a package is in while the operation runs on it, if it is tracked.
+    fn activity(self) -> Option<PackageActivity> {
+        match self {
+            ScoopOp::Install => Some(PackageActivity::Installing),
+            ScoopOp::Uninstall => Some(PackageActivity::Uninstalling),
+            ScoopOp::Update | ScoopOp::UpdateForce | ScoopOp::UpdateAll => {
+                Some(PackageActivity::Updating)
+            }
+            ScoopOp::ClearCache => Some(PackageActivity::ClearingCache),
+            ScoopOp::InstallManifest => None,
+        }
+    }
+
+    /// The hooks run before and after the operation, if it has any.
+    fn hook_events(self) -> Option<(HookEvent, HookEvent)> {
+        match self {
+            ScoopOp::Install | ScoopOp::InstallManifest => {
+                Some((HookEvent::PreInstall, HookEvent::PostInstall))
+            }
+            ScoopOp::Uninstall => Some((HookEvent::PreUninstall, HookEvent::PostUninstall)),
+            ScoopOp::Update | ScoopOp::UpdateForce => {
+                Some((HookEvent::PreUpdate, HookEvent::PostUpdate))
+            }
+            ScoopOp::UpdateAll => Some((HookEvent::PreUpdateAll, HookEvent::PostUpdateAll)),
+            ScoopOp::ClearCache => None,
+        }
+    }
+
+    /// The timeout class the operation belongs to.
+    fn class(self) -> OperationClass {
+        match self {
+            ScoopOp::Install | ScoopOp::InstallManifest => OperationClass::Install,
+            ScoopOp::Uninstall => OperationClass::Uninstall,
+            ScoopOp::Update | ScoopOp::UpdateForce => OperationClass::Update,
+            ScoopOp::ClearCache => OperationClass::Cache,
+            ScoopOp::UpdateAll => OperationClass::UpdateAll,
+        }
+    }
+}
+
 /// Builds a Scoop command as a string, returning an error if a required
 /// package name is missing.
 fn build_scoop_cmd(
@@ -27,6 +92,10 @@ fn build_scoop_cmd(
                 None => format!("scoop install {}", pkg),
             }
         }
+        ScoopOp::InstallManifest => {
+            let path = package.ok_or("A manifest path is required to install.")?;
+            format!("scoop install {}", powershell::quote_literal(path))
+        }
         ScoopOp::Uninstall => {
             let pkg = package.ok_or("A package name is required to uninstall.")?;
             format!("scoop uninstall {}", pkg)
@@ -52,7 +121,10 @@ fn build_scoop_cmd(
 /// Executes a Scoop operation and streams the output to the frontend.
 ///
 /// This function builds the Scoop command, creates a human-friendly operation
-/// name for the UI, and then executes it using the PowerShell runner.
+/// name for the UI, and then executes it using the PowerShell runner. When Scoop ran
+/// and failed to install a package, its leftovers are quarantined before the next
+/// operation may start. Every failure before Scoop is started is published as the
+/// result of `operation_id`, for `wait_for_operation`.
 pub async fn execute_scoop(
     window: Window,
     op: ScoopOp,
@@ -60,27 +132,186 @@ pub async fn execute_scoop(
     bucket: Option<&str>,
     operation_id: Option<String>,
 ) -> Result<(), String> {
-    let cmd = build_scoop_cmd(op, package, bucket)?;
+    let reject = |e: String| powershell::fail_unstarted_operation(operation_id.as_deref(), e);
+    crate::policy::ensure_writable().map_err(reject)?;
+    // An unqualified install is pinned to a bucket here, honoring source pins and the
+    // bucket priority but preferring buckets the policy allows, so the policy check and
+    // the install agree on where the package comes from
+    let resolved_bucket = match (op, package, bucket) {
+        (ScoopOp::Install, Some(pkg), None) if !pkg.contains('/') => {
+            let app = window.app_handle();
+            let scoop_path = app.state::<AppState>().scoop_path();
+            let allowed = |bucket: &String| crate::policy::check_bucket_allowed(bucket).is_ok();
+            let selected = select_source(app, &scoop_path, pkg);
+            selected
+                .clone()
+                .filter(allowed)
+                .or_else(|| {
+                    let priority = get_bucket_priority_list(app);
+                    prioritized_candidates(&scoop_path, pkg, &priority)
+                        .into_iter()
+                        .map(|(_, bucket)| bucket)
+                        .find(allowed)
+                })
+                .or(selected)
+        }
+        _ => None,
+    };
+    let bucket = bucket.or(resolved_bucket.as_deref());
+    if let (ScoopOp::Install, Some(pkg)) = (op, package) {
+        crate::policy::check_install_allowed(pkg, bucket).map_err(reject)?;
+    }
+    let manifest_name = match (op, package) {
+        (ScoopOp::InstallManifest, Some(path)) => Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string()),
+        _ => None,
+    };
+    if let Some(name) = &manifest_name {
+        crate::policy::check_install_allowed(name, None).map_err(reject)?;
+    }
+    let cmd = build_scoop_cmd(op, package, bucket).map_err(reject)?;
 
     let op_name = match (op, package) {
         (ScoopOp::Install, Some(pkg)) => format!("Installing {}", pkg),
+        (ScoopOp::InstallManifest, Some(path)) => format!(
+            "Installing {} from manifest",
+            manifest_name.as_deref().unwrap_or(path)
+        ),
         (ScoopOp::Uninstall, Some(pkg)) => format!("Uninstalling {}", pkg),
         (ScoopOp::Update, Some(pkg)) => format!("Updating {}", pkg),
         (ScoopOp::UpdateForce, Some(pkg)) => format!("Force updating {}", pkg),
         (ScoopOp::ClearCache, Some(pkg)) => format!("Clearing cache for {}", pkg),
         (ScoopOp::UpdateAll, _) => "Updating all packages".to_string(),
         // This case should not be reached if `build_scoop_cmd` is correct.
-        _ => return Err("Invalid operation or missing package name.".to_string()),
+        _ => {
+            return Err(reject(
+                "Invalid operation or missing package name.".to_string(),
+            ))
+        }
     };
 
-    powershell::run_and_stream_command(
+    let app = window.app_handle().clone();
+    let state = app.state::<AppState>();
+    let tracked: Vec<String> = match (op.activity(), op, package) {
+        (None, ..) => Vec::new(),
+        (Some(_), ScoopOp::UpdateAll, _) => update_all_targets(&state.scoop_path()),
+        (Some(_), _, pkg) => pkg.map(String::from).into_iter().collect(),
+    };
+    let mark = |activity: PackageActivity, message: Option<&String>| {
+        for pkg in &tracked {
+            let operation_id = match activity {
+                PackageActivity::Idle => None,
+                _ => operation_id.clone(),
+            };
+            update_package_state(&app, pkg, activity, operation_id, message.cloned());
+        }
+    };
+    // A failed update of everything is reported on the operation, not on every package
+    let fail = |e: &String| match op {
+        ScoopOp::UpdateAll => mark(PackageActivity::Idle, None),
+        _ => mark(PackageActivity::Failed, Some(e)),
+    };
+    mark(PackageActivity::Queued, None);
+
+    // Hooks run outside the queue so a slow hook does not hold up other operations
+    let hook_events = op.hook_events();
+    let mut hook_context = HookContext {
+        package: manifest_name.as_deref().or(package).map(String::from),
+        bucket: bucket.map(String::from),
+        version: None,
+        operation_id: operation_id.clone(),
+    };
+    let mut hook_output = Vec::new();
+    if let Some((pre, _)) = hook_events {
+        hook_context.version = hook_context
+            .package
+            .as_deref()
+            .and_then(|pkg| get_installed_version(&state.scoop_path(), pkg));
+        match hooks::run_hooks(&app, pre, &hook_context).await {
+            Ok(output) => hook_output = output,
+            Err(e) => {
+                fail(&e);
+                return Err(reject(e));
+            }
+        }
+    }
+
+    // Scoop does not cope with concurrent runs, so operations wait for each other
+    let queue = state.operation_queue.lock().await;
+    if let Err(e) = guard_external_scoop(&app, &state.scoop_path()).await {
+        log::warn!("Not starting '{}': {}", op_name, e);
+        fail(&e);
+        return Err(reject(e));
+    }
+    if let Some(activity) = op.activity() {
+        mark(activity, None);
+    }
+
+    let timer = crate::metrics::Timer::start(op.metric_name());
+    let result = powershell::run_and_stream_command(
         window,
         cmd,
         op_name,
         EVENT_OUTPUT,
         EVENT_FINISHED,
         EVENT_CANCEL,
+        operation_id.clone(),
+        op.class(),
+    )
+    .await;
+    timer.finish(result.is_ok());
+    if let (ScoopOp::Install, Some(pkg), Err(CommandError::Failed(e))) = (op, package, &result) {
+        record_failed_install(&state, pkg, bucket, e).await;
+    }
+    drop(queue);
+    let result = result.map_err(String::from);
+
+    if let (Some((_, post)), true) = (hook_events, result.is_ok()) {
+        hook_context.version = hook_context
+            .package
+            .as_deref()
+            .and_then(|pkg| get_installed_version(&state.scoop_path(), pkg));
+        // Post hooks cannot fail the operation
+        if let Ok(output) = hooks::run_hooks(&app, post, &hook_context).await {
+            hook_output.extend(output);
+        }
+    }
+    hooks::record_hook_output(&hook_context, &hook_output);
+
+    match &result {
+        Ok(()) => mark(PackageActivity::Idle, None),
+        Err(e) => fail(e),
+    }
+    result
+}
+
+/// Runs a command that changes Scoop's state but does not go through [`execute_scoop`],
+/// such as cleanups or a raw command typed by the user, once no other operation runs.
+pub async fn run_queued_command(
+    window: Window,
+    command: String,
+    operation_name: String,
+    operation_id: Option<String>,
+    class: OperationClass,
+) -> Result<(), String> {
+    let app = window.app_handle().clone();
+    let state = app.state::<AppState>();
+    let _queue = state.operation_queue.lock().await;
+    if let Err(e) = guard_external_scoop(&app, &state.scoop_path()).await {
+        log::warn!("Not starting '{}': {}", operation_name, e);
+        return Err(e);
+    }
+    powershell::run_and_stream_command(
+        window,
+        command,
+        operation_name,
+        EVENT_OUTPUT,
+        EVENT_FINISHED,
+        EVENT_CANCEL,
         operation_id,
+        class,
     )
     .await
+    .map_err(String::from)
 }
@@ -0,0 +1,200 @@
+//! The branch and repository Scoop itself is updated from.
+//!
+//! Scoop follows `scoop_branch` and `scoop_repo` from its `config.json`, defaulting to
+//! the `master` branch of the official repository; the next `scoop update` switches
+//! to whatever is configured. The checkout in `apps/scoop/current` tells what is
+//! actually in use.
+use crate::commands::settings::{read_scoop_config, update_scoop_config};
+use crate::commands::{powershell, scoop};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::path::Path;
+use tauri::{State, Window};
+
+pub const DEFAULT_SCOOP_BRANCH: &str = "master";
+pub const DEFAULT_SCOOP_REPO: &str = "https://github.com/ScoopInstaller/Scoop";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoopCoreInfo {
+    pub installed: bool,
+    /// `scoop_branch` from the config, unset when Scoop uses its default.
+    pub configured_branch: Option<String>,
+    /// `scoop_repo` from the config, unset when Scoop uses its default.
+    pub configured_repo: Option<String>,
+    /// Branch checked out in `apps/scoop/current`.
+    pub branch: Option<String>,
+    pub commit: Option<String>,
+    /// Commit time of `commit` (RFC 3339).
+    pub commit_date: Option<String>,
+    /// URL of the checkout's `origin` remote.
+    pub remote_url: Option<String>,
+    /// The checkout differs from the configured branch or repository, which the next
+    /// `scoop update` will fix.
+    pub switch_pending: bool,
+}
+
+fn config_string(config: &Map<String, Value>, key: &str) -> Option<String> {
+    config
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+/// Compares repository URLs ignoring case, a trailing slash and a `.git` suffix.
+fn same_repo(a: &str, b: &str) -> bool {
+    let normalize = |url: &str| {
+        let url = url.trim().trim_end_matches('/').to_lowercase();
+        url.strip_suffix(".git").map(String::from).unwrap_or(url)
+    };
+    normalize(a) == normalize(b)
+}
+
+/// Reads the Scoop checkout under `scoop_path` and the given Scoop config.
+pub fn read_core_info(scoop_path: &Path, config: &Map<String, Value>) -> ScoopCoreInfo {
+    let current = scoop_path.join("apps").join("scoop").join("current");
+    let mut info = ScoopCoreInfo {
+        installed: current.is_dir(),
+        configured_branch: config_string(config, "scoop_branch"),
+        configured_repo: config_string(config, "scoop_repo"),
+        ..Default::default()
+    };
+
+    if let Ok(repo) = git2::Repository::open(&current) {
+        if let Ok(head) = repo.head() {
+            info.branch = head
+                .is_branch()
+                .then(|| head.shorthand().map(String::from))
+                .flatten();
+            if let Ok(commit) = head.peel_to_commit() {
+                info.commit = Some(commit.id().to_string());
+                info.commit_date = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+                    .map(|t| t.to_rfc3339());
+            }
+        }
+        info.remote_url = repo
+            .find_remote("origin")
+            .ok()
+            .and_then(|remote| remote.url().map(String::from));
+    }
+
+    let wanted_branch = info
+        .configured_branch
+        .as_deref()
+        .unwrap_or(DEFAULT_SCOOP_BRANCH);
+    let wanted_repo = info
+        .configured_repo
+        .as_deref()
+        .unwrap_or(DEFAULT_SCOOP_REPO);
+    info.switch_pending = info
+        .branch
+        .as_deref()
+        .is_some_and(|branch| branch != wanted_branch)
+        || info
+            .remote_url
+            .as_deref()
+            .is_some_and(|url| !same_repo(url, wanted_repo));
+    info
+}
+
+/// Reads the Scoop core state with the current `config.json`.
+pub fn core_info(scoop_path: &Path) -> ScoopCoreInfo {
+    let config = read_scoop_config().unwrap_or_default();
+    read_core_info(scoop_path, &config)
+}
+
+#[tauri::command]
+pub async fn get_scoop_core_info(state: State<'_, AppState>) -> Result<ScoopCoreInfo, String> {
+    let scoop_path = state.scoop_path();
+    tokio::task::spawn_blocking(move || core_info(&scoop_path))
+        .await
+        .map_err(|e| format!("Failed to read Scoop core info: {}", e))
+}
+
+/// Sets the branch and repository Scoop updates from; an empty value restores the
+/// default. Takes effect on the next `scoop update`, run right away when `update` is set.
+#[tauri::command]
+pub async fn set_scoop_core_source(
+    window: Window,
+    state: State<'_, AppState>,
+    branch: Option<String>,
+    repo: Option<String>,
+    update: Option<bool>,
+) -> Result<ScoopCoreInfo, String> {
+    let mut changes = Map::new();
+    if let Some(branch) = branch.map(|b| b.trim().to_string()) {
+        if branch.contains(char::is_whitespace) {
+            return Err(format!("Invalid branch name: {}", branch));
+        }
+        changes.insert(
+            "scoop_branch".to_string(),
+            if branch.is_empty() {
+                Value::Null
+            } else {
+                json!(branch)
+            },
+        );
+    }
+    if let Some(repo) = repo.map(|r| r.trim().to_string()) {
+        if !repo.is_empty() && url::Url::parse(&repo).is_err() && !Path::new(&repo).is_dir() {
+            return Err(format!("Invalid repository: {}", repo));
+        }
+        changes.insert(
+            "scoop_repo".to_string(),
+            if repo.is_empty() {
+                Value::Null
+            } else {
+                json!(repo)
+            },
+        );
+    }
+
+    if !changes.is_empty() {
+        log::info!("Changing Scoop core source: {:?}", changes);
+        update_scoop_config(Value::Object(changes), Some(true))?;
+    }
+    if update.unwrap_or(false) {
+        update_scoop_core(window).await?;
+    }
+    get_scoop_core_info(state).await
+}
+
+/// Runs `scoop update`, which updates Scoop itself and switches it to the configured
+/// branch or repository.
+#[tauri::command]
+pub async fn update_scoop_core(window: Window) -> Result<(), String> {
+    scoop::run_queued_command(
+        window,
+        "scoop update".to_string(),
+        "Update Scoop".to_string(),
+        None,
+        powershell::OperationClass::Update,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScoopFixture;
+
+    #[test]
+    fn reports_configured_source_without_checkout() {
+        let fixture = ScoopFixture::new();
+        let config = json!({ "scoop_branch": "develop", "scoop_repo": " " });
+        let info = read_core_info(fixture.root(), config.as_object().unwrap());
+
+        assert!(!info.installed);
+        assert_eq!(info.configured_branch.as_deref(), Some("develop"));
+        assert_eq!(info.configured_repo, None);
+        assert_eq!(info.commit, None);
+        assert!(!info.switch_pending);
+        assert!(same_repo(
+            "https://github.com/ScoopInstaller/Scoop.git/",
+            DEFAULT_SCOOP_REPO
+        ));
+    }
+}
@@ -0,0 +1,303 @@
+//! Interop with Scoop's SQLite search database.
+//!
+//! With `use_sqlite_cache` set, Scoop (0.5 and later) keeps `scoop.db` in its root: one
+//! row per manifest version with its name, version, bucket, binaries and the manifest.
+//! Searching it avoids reading thousands of manifest files. Scoop refreshes it on
+//! `scoop update`, while buckets updated here are pulled through git directly, so the
+//! database is only used while it is at least as new as every bucket checkout. Rows of
+//! buckets that have since been removed stay in it and are skipped.
+use crate::commands::search::{IndexedManifest, SearchField, SearchQuery};
+use crate::commands::settings;
+use crate::models::ScoopPackage;
+use crate::state::AppState;
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::State;
+
+const DATABASE_FILE: &str = "scoop.db";
+const SQLITE_CACHE_KEY: &str = "use_sqlite_cache";
+
+/// The newest row of every app in every bucket.
+const LATEST_APPS_QUERY: &str = "SELECT name, version, bucket, binary, description FROM app \
+     WHERE rowid IN (SELECT MAX(rowid) FROM app GROUP BY name, bucket)";
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoopDatabaseStatus {
+    /// `use_sqlite_cache` is set in Scoop's config.
+    pub enabled: bool,
+    /// The installed Scoop can maintain the database.
+    pub supported: bool,
+    pub path: String,
+    pub exists: bool,
+    /// Not older than any bucket checkout, so searches use it.
+    pub current: bool,
+    pub app_count: Option<u64>,
+}
+
+/// An app as Scoop stores it in the database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatabaseApp {
+    pub name: String,
+    pub version: String,
+    pub bucket: String,
+    pub binaries: Vec<String>,
+    pub description: String,
+}
+
+pub fn database_path(scoop_dir: &Path) -> PathBuf {
+    scoop_dir.join(DATABASE_FILE)
+}
+
+/// Whether `use_sqlite_cache` is set in Scoop's config.
+pub fn is_enabled() -> bool {
+    settings::read_scoop_config()
+        .ok()
+        .and_then(|config| config.get(SQLITE_CACHE_KEY).and_then(|v| v.as_bool()))
+        .unwrap_or(false)
+}
+
+/// Whether the installed Scoop ships the database support, added in 0.5.
+fn is_supported(scoop_dir: &Path) -> bool {
+    scoop_dir
+        .join("apps")
+        .join("scoop")
+        .join("current")
+        .join("lib")
+        .join("database.ps1")
+        .is_file()
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Whether the database at `db_path` is at least as new as the checkout of every bucket
+/// in `buckets_dir`; git rewrites a bucket's index whenever its checkout changes.
+pub fn is_current(db_path: &Path, buckets_dir: &Path) -> bool {
+    let Some(db_modified) = modified(db_path) else {
+        return false;
+    };
+    let Ok(entries) = fs::read_dir(buckets_dir) else {
+        return true;
+    };
+    entries.flatten().all(|entry| {
+        let bucket = entry.path();
+        modified(&bucket.join(".git").join("index"))
+            .or_else(|| modified(&bucket.join("bucket")))
+            .is_none_or(|changed| changed <= db_modified)
+    })
+}
+
+fn open(db_path: &Path) -> Result<Connection, String> {
+    Connection::open_with_flags(
+        db_path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| format!("Failed to open {}: {}", db_path.display(), e))
+}
+
+/// Names of the buckets in `buckets_dir`, lowercased.
+fn existing_buckets(buckets_dir: &Path) -> HashSet<String> {
+    fs::read_dir(buckets_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| entry.file_name().to_string_lossy().to_lowercase())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Reads the newest version of every app from the database, leaving out buckets that
+/// are no longer in `buckets_dir`.
+pub fn read_apps(db_path: &Path, buckets_dir: &Path) -> Result<Vec<DatabaseApp>, String> {
+    let buckets = existing_buckets(buckets_dir);
+    let connection = open(db_path)?;
+    let mut statement = connection
+        .prepare(LATEST_APPS_QUERY)
+        .map_err(|e| format!("Failed to query Scoop database: {}", e))?;
+    let rows = statement
+        .query_map([], |row| {
+            let binaries: Option<String> = row.get(3)?;
+            Ok(DatabaseApp {
+                name: row.get(0)?,
+                version: row.get(1)?,
+                bucket: row.get(2)?,
+                // Scoop joins the binaries with " | "
+                binaries: binaries
+                    .unwrap_or_default()
+                    .split('|')
+                    .map(|b| b.trim().to_string())
+                    .filter(|b| !b.is_empty())
+                    .collect(),
+                description: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+            })
+        })
+        .map_err(|e| format!("Failed to query Scoop database: {}", e))?;
+    let apps = rows
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read Scoop database: {}", e))?;
+    Ok(apps
+        .into_iter()
+        .filter(|app| buckets.contains(&app.bucket.to_lowercase()))
+        .collect())
+}
+
+/// The current version of every app by lowercased name and bucket, when Scoop maintains
+/// the database and it is current. Used to check for updates without reading manifests.
+pub fn latest_versions(scoop_dir: &Path) -> Option<HashMap<(String, String), String>> {
+    let db_path = database_path(scoop_dir);
+    let buckets_dir = scoop_dir.join("buckets");
+    if !is_enabled() || !is_current(&db_path, &buckets_dir) {
+        return None;
+    }
+    match read_apps(&db_path, &buckets_dir) {
+        Ok(apps) => Some(
+            apps.into_iter()
+                .map(|app| {
+                    (
+                        (app.name.to_lowercase(), app.bucket.to_lowercase()),
+                        app.version,
+                    )
+                })
+                .collect(),
+        ),
+        Err(e) => {
+            log::warn!("{}; reading manifests instead", e);
+            None
+        }
+    }
+}
+
+/// Matches the database apps against `query` the way the manifest index is matched.
+pub fn match_apps(apps: Vec<DatabaseApp>, query: &SearchQuery) -> Vec<ScoopPackage> {
+    apps.into_iter()
+        .filter_map(|app| {
+            let manifest = IndexedManifest {
+                name: app.name,
+                version: app.version,
+                bucket: app.bucket,
+                bins: app.binaries,
+                description: app.description,
+                ..Default::default()
+            };
+            let (match_source, score) = query.match_manifest(&manifest)?;
+            Some(manifest.to_package(match_source, score))
+        })
+        .collect()
+}
+
+/// Searches the database when Scoop maintains it and it is current; `None` when the
+/// manifests have to be scanned instead. The database has no homepages, licenses or
+/// architectures, so searches and filters of those always use the manifests.
+pub fn search(scoop_dir: &Path, query: &SearchQuery) -> Option<Vec<ScoopPackage>> {
+    if query.searches(SearchField::Homepage)
+        || query.searches(SearchField::License)
+        || query.filters().needs_manifest()
+    {
+        return None;
+    }
+    let db_path = database_path(scoop_dir);
+    let buckets_dir = scoop_dir.join("buckets");
+    if !is_enabled() || !is_current(&db_path, &buckets_dir) {
+        return None;
+    }
+    match read_apps(&db_path, &buckets_dir) {
+        Ok(apps) => Some(match_apps(apps, query)),
+        Err(e) => {
+            log::warn!("{}; scanning manifests instead", e);
+            None
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_scoop_database_status(
+    state: State<'_, AppState>,
+) -> Result<ScoopDatabaseStatus, String> {
+    let scoop_dir = state.scoop_path();
+    let db_path = database_path(&scoop_dir);
+    let exists = db_path.is_file();
+    let app_count = if exists {
+        read_apps(&db_path, &scoop_dir.join("buckets"))
+            .ok()
+            .map(|apps| {
+                apps.iter()
+                    .map(|app| app.name.to_lowercase())
+                    .collect::<HashSet<_>>()
+                    .len() as u64
+            })
+    } else {
+        None
+    };
+    Ok(ScoopDatabaseStatus {
+        enabled: is_enabled(),
+        supported: is_supported(&scoop_dir),
+        path: db_path.to_string_lossy().to_string(),
+        exists,
+        current: exists && is_current(&db_path, &scoop_dir.join("buckets")),
+        app_count,
+    })
+}
+
+/// Sets `use_sqlite_cache` in Scoop's config. Scoop builds the database on its next
+/// `scoop update`.
+#[tauri::command]
+pub fn enable_scoop_database(state: State<'_, AppState>) -> Result<(), String> {
+    if !is_supported(&state.scoop_path()) {
+        return Err("The installed Scoop has no SQLite cache; update Scoop first".to_string());
+    }
+    log::info!("Enabling Scoop's SQLite cache");
+    settings::update_scoop_config(serde_json::json!({ SQLITE_CACHE_KEY: true }), Some(true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MatchSource;
+
+    #[test]
+    fn reads_latest_rows_and_matches_binaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = database_path(dir.path());
+        Connection::open(&db_path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE app (name TEXT NOT NULL COLLATE NOCASE, description TEXT NOT NULL,
+                     version TEXT NOT NULL, bucket VARCHAR NOT NULL, manifest JSON NOT NULL UNIQUE,
+                     binary TEXT, shortcut TEXT, dependency TEXT, suggest TEXT,
+                     PRIMARY KEY (name, version, bucket));
+                 INSERT INTO app VALUES ('git', 'Version control', '2.44.0', 'main', '{\"a\":1}', 'git.exe | gitk.exe', NULL, NULL, NULL);
+                 INSERT INTO app VALUES ('git', 'Version control', '2.45.0', 'main', '{\"a\":2}', 'git.exe | gitk.exe', NULL, NULL, NULL);
+                 INSERT INTO app VALUES ('ripgrep', 'Fast grep', '14.1.0', 'main', '{\"a\":3}', 'rg.exe', NULL, NULL, NULL);
+                 INSERT INTO app VALUES ('vlc', 'Media player', '3.0.20', 'removed', '{\"a\":4}', 'vlc.exe', NULL, NULL, NULL);",
+            )
+            .unwrap();
+        let buckets_dir = dir.path().join("buckets");
+        fs::create_dir_all(buckets_dir.join("Main")).unwrap();
+
+        let apps = read_apps(&db_path, &buckets_dir).unwrap();
+        assert_eq!(apps.len(), 2);
+        let git = apps.iter().find(|a| a.name == "git").unwrap();
+        assert_eq!(git.version, "2.45.0");
+        assert_eq!(git.binaries, vec!["git.exe", "gitk.exe"]);
+
+        let found = match_apps(apps, &SearchQuery::new("rg", false).unwrap());
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "ripgrep");
+        assert_eq!(found[0].match_source, MatchSource::Name);
+        let found = match_apps(
+            read_apps(&db_path, &buckets_dir).unwrap(),
+            &SearchQuery::new("gitk", false).unwrap(),
+        );
+        assert_eq!(found[0].match_source, MatchSource::Binary);
+
+        assert!(is_current(&db_path, &buckets_dir));
+    }
+}
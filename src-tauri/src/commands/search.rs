@@ -1,19 +1,171 @@
 //! Commands for searching Scoop packages.
-use crate::commands::installed::get_installed_packages_full;
+use crate::commands::ignore_patterns::load_ignore_rules;
+use crate::commands::installed::get_installed_packages;
+use crate::commands::installed_search;
+use crate::commands::package_source::{bucket_rank, get_bucket_priority_list};
+use crate::commands::scoop_db;
+use crate::commands::search_index;
 use crate::models::{MatchSource, ScoopPackage, SearchResult};
 use crate::state::AppState;
 use once_cell::sync::Lazy;
 use rayon::prelude::*;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use tauri::Manager;
+use std::sync::Arc;
+use tauri::{Emitter, Manager};
 use tokio::sync::Mutex;
 
-// Global cache for manifest paths to avoid re-scanning the filesystem on every search.
-static MANIFEST_CACHE: Lazy<Mutex<Option<HashSet<PathBuf>>>> = Lazy::new(|| Mutex::new(None));
+/// Emitted with a [`SearchBatch`] while a slow search is still scanning manifests.
+pub const EVENT_SEARCH_BATCH: &str = "search-results-batch";
+/// Number of matches collected before a batch is emitted.
+const SEARCH_BATCH_SIZE: usize = 100;
+
+/// Matches found so far by a streaming search. They are unsorted; the command's
+/// result holds the complete, ranked list.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchBatch {
+    pub term: String,
+    pub packages: Vec<ScoopPackage>,
+}
+
+/// Every bucket manifest, parsed once so searches never re-read the JSON files.
+type ManifestIndex = Arc<Vec<IndexedManifest>>;
+
+// Global manifest index to avoid re-scanning the filesystem on every search.
+static MANIFEST_CACHE: Lazy<Mutex<Option<ManifestIndex>>> = Lazy::new(|| Mutex::new(None));
+
+/// The ranked results of the last search, which later pages of it are served from.
+struct RankedSearch {
+    key: String,
+    packages: Arc<Vec<ScoopPackage>>,
+}
+
+static LAST_SEARCH: Lazy<Mutex<Option<RankedSearch>>> = Lazy::new(|| Mutex::new(None));
+
+/// What a search looks through.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchScope {
+    /// The manifests of every bucket.
+    #[default]
+    All,
+    /// The installed packages, also matched by description and bucket.
+    Installed,
+}
+
+/// A field of the manifest index a search can match.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchField {
+    Name,
+    Binary,
+    Description,
+    Homepage,
+    License,
+}
+
+/// Fields searched when `search_fields` is not given.
+pub const DEFAULT_SEARCH_FIELDS: &[SearchField] = &[SearchField::Name, SearchField::Binary];
+
+/// Restrictions on search results beyond the search term. Empty lists allow anything.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFilters {
+    /// Bucket names, compared without case.
+    #[serde(default)]
+    pub buckets: Vec<String>,
+    /// SPDX identifiers, any of which the manifest's license has to mention.
+    #[serde(default)]
+    pub licenses: Vec<String>,
+    /// An architecture such as `64bit` the package has to support.
+    #[serde(default)]
+    pub architecture: Option<String>,
+}
+
+impl SearchFilters {
+    /// Whether the filters look at manifest fields Scoop's database lacks.
+    pub fn needs_manifest(&self) -> bool {
+        !self.licenses.is_empty() || self.architecture.is_some()
+    }
+
+    pub fn accepts(&self, manifest: &IndexedManifest) -> bool {
+        if !self.buckets.is_empty()
+            && !self
+                .buckets
+                .iter()
+                .any(|b| b.eq_ignore_ascii_case(&manifest.bucket))
+        {
+            return false;
+        }
+        if !self.licenses.is_empty() {
+            // `license` holds expressions such as `MIT|Apache-2.0` or `GPL-3.0-or-later`
+            let mut identifiers = manifest
+                .license
+                .split(|c: char| c.is_whitespace() || matches!(c, '|' | ',' | '(' | ')'));
+            if !identifiers.any(|id| self.licenses.iter().any(|l| l.eq_ignore_ascii_case(id))) {
+                return false;
+            }
+        }
+        match &self.architecture {
+            Some(arch) => {
+                manifest.architectures.is_empty()
+                    || manifest
+                        .architectures
+                        .iter()
+                        .any(|a| a.eq_ignore_ascii_case(arch))
+            }
+            None => true,
+        }
+    }
+}
+
+/// What search needs from a bucket manifest.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedManifest {
+    pub name: String,
+    pub version: String,
+    pub bucket: String,
+    /// Modification time of the manifest file, RFC 3339.
+    pub updated: String,
+    /// Declared binaries and their aliases.
+    pub bins: Vec<String>,
+    pub description: String,
+    pub homepage: String,
+    /// SPDX identifier(s), as in the manifest's `license` or `license.identifier`.
+    pub license: String,
+    /// Keys of the manifest's `architecture`; empty when it runs on any.
+    #[serde(default)]
+    pub architectures: Vec<String>,
+}
+
+impl IndexedManifest {
+    /// Whether the manifest has per-architecture downloads and none of them is 32-bit.
+    pub fn is_64bit_only(&self) -> bool {
+        !self.architectures.is_empty() && !self.architectures.iter().any(|a| a == "32bit")
+    }
+
+    pub fn to_package(&self, match_source: MatchSource, score: u32) -> ScoopPackage {
+        ScoopPackage {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            source: self.bucket.clone(),
+            updated: self.updated.clone(),
+            info: self.description.clone(),
+            match_source,
+            score: Some(score),
+            homepage: self.homepage.clone(),
+            license: self.license.clone(),
+            is_64bit_only: self.is_64bit_only(),
+            ..Default::default()
+        }
+    }
+}
 
 /// Finds all `.json` manifest files in a given bucket's `bucket` subdirectory.
 fn find_manifests_in_bucket(bucket_path: PathBuf) -> Vec<PathBuf> {
@@ -32,49 +184,108 @@ fn find_manifests_in_bucket(bucket_path: PathBuf) -> Vec<PathBuf> {
     }
 }
 
-/// Scans all bucket directories to find package manifests and populates the cache.
-async fn populate_manifest_cache(scoop_path: &Path) -> Result<HashSet<PathBuf>, String> {
+/// Scans all bucket directories to find package manifests.
+pub fn scan_manifest_paths(scoop_path: &Path) -> Result<HashSet<PathBuf>, String> {
     let buckets_path = scoop_path.join("buckets");
-    if !tokio::fs::try_exists(&buckets_path).await.unwrap_or(false) {
+    if !buckets_path.is_dir() {
         return Err("Scoop buckets directory not found".to_string());
     }
 
-    let mut read_dir = tokio::fs::read_dir(&buckets_path)
-        .await
+    let entries = fs::read_dir(&buckets_path)
         .map_err(|e| format!("Failed to read buckets directory: {}", e))?;
-    let mut manifest_paths = HashSet::new();
 
-    while let Ok(Some(entry)) = read_dir.next_entry().await {
-        if entry.path().is_dir() {
-            let bucket_manifests = find_manifests_in_bucket(entry.path());
-            manifest_paths.extend(bucket_manifests);
-        }
-    }
+    Ok(entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .flat_map(find_manifests_in_bucket)
+        .collect())
+}
 
-    Ok(manifest_paths)
+/// Scans all bucket directories and parses every manifest found into the index.
+pub fn build_manifest_index(scoop_path: &Path) -> Result<Vec<IndexedManifest>, String> {
+    let paths = scan_manifest_paths(scoop_path)?;
+    Ok(crate::scan_pool::install(|| {
+        paths
+            .par_iter()
+            .filter_map(|path| index_manifest(path))
+            .collect()
+    }))
+}
+
+/// Parses every manifest of one bucket into index entries.
+pub fn index_bucket(bucket_path: &Path) -> Vec<IndexedManifest> {
+    let paths = find_manifests_in_bucket(bucket_path.to_path_buf());
+    crate::scan_pool::install(|| {
+        paths
+            .par_iter()
+            .filter_map(|path| index_manifest(path))
+            .collect()
+    })
+}
+
+/// Populates the cache from the index kept on disk, parsing only the buckets that
+/// changed since it was saved.
+async fn populate_manifest_cache(scoop_path: &Path) -> Result<Vec<IndexedManifest>, String> {
+    let scoop_path = scoop_path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        search_index::load_or_build(&scoop_path, search_index::index_file().as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 /// Acquires a lock on the manifest cache and populates it if it's empty.
-async fn get_manifests<R: tauri::Runtime>(
+pub async fn get_manifests<R: tauri::Runtime>(
     app: tauri::AppHandle<R>,
-) -> Result<(HashSet<PathBuf>, bool), String> {
+) -> Result<(ManifestIndex, bool), String> {
     let mut guard = MANIFEST_CACHE.lock().await;
-    let is_cold = guard.is_none();
 
-    if is_cold {
-        log::info!("Cold search: Populating manifest cache.");
-        let state = app.state::<AppState>();
-        let scoop_path = state.scoop_path();
-        let paths = populate_manifest_cache(&scoop_path).await?;
-        *guard = Some(paths.clone());
-        Ok((paths, true))
-    } else {
-        Ok((guard.as_ref().unwrap().clone(), false))
+    match guard.as_ref() {
+        Some(index) => Ok((index.clone(), false)),
+        None => {
+            log::info!("Cold search: Populating manifest cache.");
+            let state = app.state::<AppState>();
+            let scoop_path = state.scoop_path();
+            let index = Arc::new(populate_manifest_cache(&scoop_path).await?);
+            *guard = Some(index.clone());
+            Ok((index, true))
+        }
     }
 }
 
-/// Parses a Scoop package manifest file to extract package information.
-fn parse_package_from_manifest(path: &Path) -> Option<ScoopPackage> {
+/// Collects the binaries and aliases declared by a manifest's `bin`.
+fn collect_bins(bin: &Value, bins: &mut Vec<String>) {
+    match bin {
+        Value::String(s) => bins.push(s.clone()),
+        Value::Array(arr) => {
+            for entry in arr {
+                match entry {
+                    // [path, alias, args...]
+                    Value::Array(parts) => bins.extend(
+                        parts
+                            .iter()
+                            .take(2)
+                            .filter_map(Value::as_str)
+                            .map(String::from),
+                    ),
+                    _ => collect_bins(entry, bins),
+                }
+            }
+        }
+        // Some manifests use object syntax { "alias": "path/to/file" }
+        Value::Object(obj) => {
+            for (key, value) in obj {
+                bins.push(key.clone());
+                bins.extend(value.as_str().map(String::from));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Parses a Scoop package manifest file into its index entry.
+fn index_manifest(path: &Path) -> Option<IndexedManifest> {
     let file_name = path.file_stem().and_then(|s| s.to_str())?.to_string();
 
     let content = std::fs::read_to_string(path).ok()?;
@@ -88,142 +299,503 @@ fn parse_package_from_manifest(path: &Path) -> Option<ScoopPackage> {
         .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
         .unwrap_or_default();
 
-    Some(ScoopPackage {
+    let text = |key: &str| {
+        json.get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let mut bins = Vec::new();
+    if let Some(bin) = json.get("bin") {
+        collect_bins(bin, &mut bins);
+    }
+    let architectures = json
+        .get("architecture")
+        .and_then(Value::as_object)
+        .map(|archs| archs.keys().cloned().collect())
+        .unwrap_or_default();
+    let license = match json.get("license") {
+        Some(Value::Object(obj)) => obj.get("identifier").and_then(Value::as_str),
+        Some(value) => value.as_str(),
+        None => None,
+    };
+
+    Some(IndexedManifest {
         name: file_name,
         version,
-        source: bucket,
+        bucket,
         updated,
-        match_source: MatchSource::Name,
-        ..Default::default()
+        bins,
+        description: text("description"),
+        homepage: text("homepage"),
+        license: license.unwrap_or_default().to_string(),
+        architectures,
     })
 }
 
+/// Score of a package found through one of its binaries rather than its name.
+pub const BINARY_MATCH_SCORE: u32 = 40;
+/// Scores of packages found through their manifest text.
+const DESCRIPTION_MATCH_SCORE: u32 = 30;
+const HOMEPAGE_MATCH_SCORE: u32 = 20;
+const LICENSE_MATCH_SCORE: u32 = 10;
+/// Shortest term that is matched fuzzily; shorter ones are all a typo away from too much.
+const MIN_FUZZY_TERM_LEN: usize = 4;
+
 /// Builds a regex pattern for searching, supporting exact and partial matches.
 fn build_search_regex(term: &str) -> Result<Regex, String> {
-    let trimmed = term.trim();
-    let pattern_str = if trimmed.starts_with('"') && trimmed.ends_with('"') && trimmed.len() > 1 {
+    let pattern_str = match exact_term(term) {
         // Exact match: "term"
-        let inner = &trimmed[1..trimmed.len() - 1];
-        let normalized = inner.trim().replace(' ', "-");
-        format!("(?i)^{}$", regex::escape(&normalized))
-    } else {
+        Some(inner) => format!("(?i)^{}$", regex::escape(&inner.replace(' ', "-"))),
         // Partial match: term
-        let normalized = trimmed.replace(' ', "-");
-        format!("(?i){}", regex::escape(&normalized))
+        None => format!("(?i){}", regex::escape(&term.trim().replace(' ', "-"))),
     };
 
     Regex::new(&pattern_str).map_err(|e| e.to_string())
 }
 
+/// Builds the pattern free text such as descriptions is searched with: the term as
+/// typed, and for an exact search as a whole phrase.
+fn build_text_regex(term: &str) -> Result<Regex, String> {
+    let pattern_str = match exact_term(term) {
+        Some(inner) => format!(r"(?i)\b{}\b", regex::escape(inner)),
+        None => format!("(?i){}", regex::escape(term.trim())),
+    };
+
+    Regex::new(&pattern_str).map_err(|e| e.to_string())
+}
+
+/// The inner term of an exact ("quoted") search.
+fn exact_term(term: &str) -> Option<&str> {
+    let trimmed = term.trim();
+    if trimmed.starts_with('"') && trimmed.ends_with('"') && trimmed.len() > 1 {
+        Some(trimmed[1..trimmed.len() - 1].trim())
+    } else {
+        None
+    }
+}
+
+/// Optimal string alignment distance: edits, counting a swap of neighbours as one.
+fn osa_distance(a: &[char], b: &[char]) -> usize {
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// A search term compiled for matching the fields of the manifest index.
+pub struct SearchQuery {
+    pattern: Regex,
+    /// Matches descriptions, homepages and licenses.
+    text_pattern: Regex,
+    fields: Vec<SearchField>,
+    filters: SearchFilters,
+    /// The lowercased term, with spaces as dashes like package names.
+    term: Vec<char>,
+    /// Tolerate typos in package names. Exact searches never do.
+    fuzzy: bool,
+}
+
+impl SearchQuery {
+    pub fn new(term: &str, fuzzy: bool) -> Result<Self, String> {
+        let exact = exact_term(term);
+        Ok(Self {
+            pattern: build_search_regex(term)?,
+            text_pattern: build_text_regex(term)?,
+            fields: DEFAULT_SEARCH_FIELDS.to_vec(),
+            filters: SearchFilters::default(),
+            term: exact
+                .unwrap_or(term.trim())
+                .to_lowercase()
+                .replace(' ', "-")
+                .chars()
+                .collect(),
+            fuzzy: fuzzy && exact.is_none(),
+        })
+    }
+
+    /// Searches `fields` instead of the package name and binaries.
+    pub fn with_fields(mut self, fields: Vec<SearchField>) -> Self {
+        if !fields.is_empty() {
+            self.fields = fields;
+        }
+        self
+    }
+
+    /// Only matches manifests `filters` accepts.
+    pub fn with_filters(mut self, filters: SearchFilters) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    pub fn filters(&self) -> &SearchFilters {
+        &self.filters
+    }
+
+    pub fn searches(&self, field: SearchField) -> bool {
+        self.fields.contains(&field)
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.pattern.is_match(text)
+    }
+
+    pub fn is_text_match(&self, text: &str) -> bool {
+        self.text_pattern.is_match(text)
+    }
+
+    /// Scores a package name containing the term: 100 when it is the term, 80 when it
+    /// starts with it and 60 otherwise; `None` when it does not contain it.
+    pub fn name_score(&self, name: &str) -> Option<u32> {
+        if !self.pattern.is_match(name) {
+            return None;
+        }
+        let name: Vec<char> = name.to_lowercase().chars().collect();
+        Some(if name == self.term {
+            100
+        } else if name.starts_with(&self.term) {
+            80
+        } else {
+            60
+        })
+    }
+
+    /// Scores a package name that differs from the term, or from a part of the name as
+    /// long as the term, by a typo or two: 35 for one edit and 25 for two, 5 less when
+    /// only part of the name matched. `None` when fuzzy matching is off or it is further.
+    pub fn fuzzy_score(&self, name: &str) -> Option<u32> {
+        let len = self.term.len();
+        if !self.fuzzy || len < MIN_FUZZY_TERM_LEN {
+            return None;
+        }
+        let max_edits = if len < 8 { 1 } else { 2 };
+        let name: Vec<char> = name.to_lowercase().chars().collect();
+
+        let whole = osa_distance(&self.term, &name);
+        if whole <= max_edits {
+            return Some(45 - 10 * whole as u32);
+        }
+        // Windows one shorter to one longer than the term, so a missing or extra
+        // character still lines up
+        (len - 1..=len + 1)
+            .filter(|&width| width < name.len())
+            .flat_map(|width| name.windows(width))
+            .map(|window| osa_distance(&self.term, window))
+            .min()
+            .filter(|&distance| distance <= max_edits)
+            .map(|distance| 40 - 10 * distance as u32)
+    }
+
+    /// Matches an index entry by name, then binaries, then fuzzily by name, then by
+    /// description, homepage and license, as far as those fields are searched.
+    pub fn match_manifest(&self, manifest: &IndexedManifest) -> Option<(MatchSource, u32)> {
+        if !self.filters.accepts(manifest) {
+            return None;
+        }
+        if self.searches(SearchField::Name) {
+            if let Some(score) = self.name_score(&manifest.name) {
+                return Some((MatchSource::Name, score));
+            }
+        }
+        if self.searches(SearchField::Binary) && manifest.bins.iter().any(|b| self.is_match(b)) {
+            return Some((MatchSource::Binary, BINARY_MATCH_SCORE));
+        }
+        if self.searches(SearchField::Name) {
+            if let Some(score) = self.fuzzy_score(&manifest.name) {
+                return Some((MatchSource::Fuzzy, score));
+            }
+        }
+        [
+            (
+                SearchField::Description,
+                &manifest.description,
+                MatchSource::Description,
+                DESCRIPTION_MATCH_SCORE,
+            ),
+            (
+                SearchField::Homepage,
+                &manifest.homepage,
+                MatchSource::Homepage,
+                HOMEPAGE_MATCH_SCORE,
+            ),
+            (
+                SearchField::License,
+                &manifest.license,
+                MatchSource::License,
+                LICENSE_MATCH_SCORE,
+            ),
+        ]
+        .into_iter()
+        .find(|(field, text, _, _)| self.searches(*field) && self.is_text_match(text))
+        .map(|(_, _, source, score)| (source, score))
+    }
+}
+
+/// Matches indexed manifests against a search term by package name, then by declared
+/// binaries.
+pub fn match_manifests(
+    manifests: &[IndexedManifest],
+    term: &str,
+) -> Result<Vec<ScoopPackage>, String> {
+    let query = SearchQuery::new(term, false)?;
+    Ok(match_manifests_in_batches(manifests, &query, &|_| {}))
+}
+
+/// Matches indexed manifests against `query`, also handing the matches to `on_batch` as
+/// they are found, [`SEARCH_BATCH_SIZE`] at a time.
+pub fn match_manifests_in_batches(
+    manifests: &[IndexedManifest],
+    query: &SearchQuery,
+    on_batch: &(dyn Fn(Vec<ScoopPackage>) + Sync),
+) -> Vec<ScoopPackage> {
+    let pending = std::sync::Mutex::new(Vec::new());
+    let collect_batch = |pkg: &ScoopPackage| {
+        let mut pending = pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.push(pkg.clone());
+        if pending.len() >= SEARCH_BATCH_SIZE {
+            let batch = std::mem::take(&mut *pending);
+            drop(pending);
+            on_batch(batch);
+        }
+    };
+
+    let packages: Vec<ScoopPackage> = crate::scan_pool::install(|| {
+        manifests
+            .par_iter()
+            .filter_map(|manifest| {
+                let (match_source, score) = query.match_manifest(manifest)?;
+                let pkg = manifest.to_package(match_source, score);
+                collect_batch(&pkg);
+                Some(pkg)
+            })
+            .collect()
+    });
+
+    let rest = pending.into_inner().unwrap_or_else(|e| e.into_inner());
+    if !rest.is_empty() {
+        on_batch(rest);
+    }
+    packages
+}
+
+/// The `limit` results from `offset` on, all of them without a limit.
+pub fn page_of(
+    packages: &[ScoopPackage],
+    offset: usize,
+    limit: Option<usize>,
+) -> Vec<ScoopPackage> {
+    let start = offset.min(packages.len());
+    let end = limit.map_or(packages.len(), |limit| {
+        start.saturating_add(limit).min(packages.len())
+    });
+    packages[start..end].to_vec()
+}
+
+/// Adds the homepage, license and architectures Scoop's database lacks to `packages`
+/// from the manifest index, when it is already loaded.
+async fn add_manifest_details(packages: &mut [ScoopPackage]) {
+    let Some(index) = MANIFEST_CACHE.lock().await.clone() else {
+        return;
+    };
+    let by_name: HashMap<(&str, &str), &IndexedManifest> = index
+        .iter()
+        .map(|m| ((m.bucket.as_str(), m.name.as_str()), m))
+        .collect();
+    for package in packages.iter_mut() {
+        if let Some(manifest) = by_name.get(&(package.source.as_str(), package.name.as_str())) {
+            package.homepage = manifest.homepage.clone();
+            package.license = manifest.license.clone();
+            package.is_64bit_only = manifest.is_64bit_only();
+        }
+    }
+}
+
 /// Searches for Scoop packages based on a search term.
+///
+/// When the manifest cache is cold, or `stream` is set, matches are also emitted in
+/// [`EVENT_SEARCH_BATCH`] events while the scan runs so results can be shown early.
+///
+/// Package names a typo or two away from the term are found too, unless `fuzzy` is
+/// `false`. `search_fields` picks the manifest fields searched, the name and binaries
+/// by default. Results are ranked by their `score`.
+///
+/// With `limit`, only that many results from `offset` on are returned, along with the
+/// `total`. Pages after the first are taken from the ranked results of the same search
+/// rather than scanning again.
+///
+/// With `scope` set to `installed`, only the installed packages are searched, by
+/// description and bucket as well unless `search_fields` says otherwise.
+///
+/// `filters` restrict the results to buckets, licenses or an architecture. Installed
+/// packages are only filtered by bucket.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn search_scoop<R: tauri::Runtime>(
     app: tauri::AppHandle<R>,
     term: String,
+    stream: Option<bool>,
+    fuzzy: Option<bool>,
+    search_fields: Option<Vec<SearchField>>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    scope: Option<SearchScope>,
+    filters: Option<SearchFilters>,
 ) -> Result<SearchResult, String> {
     if term.is_empty() {
         return Ok(SearchResult::default());
     }
+    let fuzzy = fuzzy.unwrap_or(true);
+    let scope = scope.unwrap_or_default();
+    let search_fields = match search_fields {
+        Some(fields) if !fields.is_empty() => fields,
+        _ if scope == SearchScope::Installed => vec![
+            SearchField::Name,
+            SearchField::Binary,
+            SearchField::Description,
+        ],
+        _ => Vec::new(),
+    };
+    let mut filters = filters.unwrap_or_default();
+    if scope == SearchScope::Installed {
+        // Installed packages are indexed without licenses and architectures
+        filters.licenses.clear();
+        filters.architecture = None;
+    }
+    let search_key = format!(
+        "{}\0{}\0{:?}\0{:?}\0{:?}",
+        term, fuzzy, search_fields, scope, filters
+    );
+    let offset = offset.unwrap_or(0);
+    if offset > 0 {
+        if let Some(last) = LAST_SEARCH.lock().await.as_ref() {
+            if last.key == search_key {
+                return Ok(SearchResult {
+                    packages: page_of(&last.packages, offset, limit),
+                    is_cold: false,
+                    total: last.packages.len(),
+                });
+            }
+        }
+    }
+    let query = Arc::new(
+        SearchQuery::new(&term, fuzzy)?
+            .with_fields(search_fields)
+            .with_filters(filters),
+    );
+
+    if scope == SearchScope::Installed {
+        let state = app.state::<AppState>();
+        let mut packages = installed_search::match_installed(&app, &state, &query).await?;
+        rank_results(&app, &mut packages);
+        return Ok(paged_result(packages, false, search_key, offset, limit).await);
+    }
 
     log::info!("search_scoop: Starting search for term: '{}'", term);
     let search_start = std::time::Instant::now();
 
-    let (manifest_paths, is_cold) = get_manifests(app.clone()).await?;
-    let cache_time = search_start.elapsed();
+    // Determine which packages are already installed, so streamed batches carry it too.
+    let state = app.state::<AppState>();
+    let scoop_path = state.scoop_path();
+    let installed_set: HashSet<String> = get_installed_packages(app.clone(), state)
+        .await
+        .map(|pkgs| pkgs.into_iter().map(|p| p.name.to_lowercase()).collect())
+        .unwrap_or_default();
+    let ignore = load_ignore_rules(&app);
+    let mark_installed = move |pkg: &mut ScoopPackage| {
+        if installed_set.contains(&pkg.name.to_lowercase()) {
+            pkg.is_installed = true;
+        }
+    };
 
-    if is_cold {
-        log::warn!(
-            "search_scoop: ⚠ Cache was cold! Had to populate manifest cache during search (took {:.2}s). This should not happen if cold-start completed.",
-            cache_time.as_secs_f64()
-        );
-    } else {
-        log::info!(
-            "search_scoop: ✓ Using pre-warmed manifest cache ({} manifests, retrieved in {:.2}ms)",
-            manifest_paths.len(),
-            cache_time.as_millis()
-        );
-    }
+    // Scoop's own search database, when it keeps one, spares reading the manifests
+    let db_query = query.clone();
+    let from_database =
+        tokio::task::spawn_blocking(move || scoop_db::search(&scoop_path, &db_query))
+            .await
+            .map_err(|e| e.to_string())?;
 
-    let pattern = build_search_regex(&term)?;
+    let (mut packages, ignore, mark_installed, is_cold) = match from_database {
+        Some(mut packages) => {
+            add_manifest_details(&mut packages).await;
+            log::info!(
+                "search_scoop: ✓ Searched Scoop's SQLite database in {:.2}ms",
+                search_start.elapsed().as_millis()
+            );
+            (packages, ignore, mark_installed, false)
+        }
+        None => {
+            let (manifests, is_cold) = get_manifests(app.clone()).await?;
+            let cache_time = search_start.elapsed();
 
-    let manifest_paths_clone = manifest_paths.clone();
+            if is_cold {
+                log::warn!(
+                    "search_scoop: ⚠ Cache was cold! Had to populate manifest cache during search (took {:.2}s). This should not happen if cold-start completed.",
+                    cache_time.as_secs_f64()
+                );
+            } else {
+                log::info!(
+                    "search_scoop: ✓ Using pre-warmed manifest cache ({} manifests, retrieved in {:.2}ms)",
+                    manifests.len(),
+                    cache_time.as_millis()
+                );
+            }
 
-    let mut packages: Vec<ScoopPackage> = tokio::task::spawn_blocking(move || {
-        manifest_paths_clone
-            .par_iter()
-            .filter_map(|path| {
-                // Check if the file name (package name) matches first
-                let file_name = path.file_stem().and_then(|s| s.to_str())?;
-                let name_matches = pattern.is_match(file_name);
-
-                // Determine if the search term matches one of the binaries declared in the manifest.
-                // We only do this expensive parse if the package name itself did **not** match.
-                let match_source = if name_matches {
-                    MatchSource::Name
-                } else {
-                    // Load and inspect the manifest's `bin` field
-                    let content = std::fs::read_to_string(path).ok()?;
-                    let json: Value = serde_json::from_str(&content).ok()?;
-
-                    let does_bin_match = json.get("bin").map_or(false, |bin_val| {
-                        match bin_val {
-                            Value::String(s) => pattern.is_match(s),
-                            Value::Array(arr) => arr.iter().any(|entry| match entry {
-                                Value::String(s) => pattern.is_match(s),
-                                Value::Object(obj) => {
-                                    // Some manifests use object syntax { "alias": "path/to/file" }
-                                    obj.keys().any(|k| pattern.is_match(k))
-                                        || obj.values().any(|v| {
-                                            v.as_str().map_or(false, |s| pattern.is_match(s))
-                                        })
-                                }
-                                _ => false,
-                            }),
-                            Value::Object(obj) => {
-                                // Very uncommon, but treat similarly to array/object case
-                                obj.keys().any(|k| pattern.is_match(k))
-                                    || obj
-                                        .values()
-                                        .any(|v| v.as_str().map_or(false, |s| pattern.is_match(s)))
-                            }
-                            _ => false,
-                        }
-                    });
-
-                    if does_bin_match {
-                        MatchSource::Binary
-                    } else {
-                        MatchSource::None
+            let streaming = is_cold || stream.unwrap_or(false);
+            let term_clone = term.clone();
+            let batch_app = app.clone();
+            let (packages, ignore, mark_installed) = tokio::task::spawn_blocking(move || {
+                let on_batch = |mut batch: Vec<ScoopPackage>| {
+                    if !streaming {
+                        return;
+                    }
+                    batch.retain(|pkg| !ignore.is_ignored(&pkg.name, &pkg.source));
+                    batch.iter_mut().for_each(&mark_installed);
+                    if batch.is_empty() {
+                        return;
+                    }
+                    let event = SearchBatch {
+                        term: term_clone.clone(),
+                        packages: batch,
+                    };
+                    if let Err(e) = batch_app.emit(EVENT_SEARCH_BATCH, &event) {
+                        log::warn!("Failed to emit {} event: {}", EVENT_SEARCH_BATCH, e);
                     }
                 };
-
-                if match_source == MatchSource::None {
-                    return None;
-                }
-
-                let mut pkg = parse_package_from_manifest(path)?;
-                pkg.match_source = match_source;
-                Some(pkg)
+                let packages = match_manifests_in_batches(&manifests, &query, &on_batch);
+                (packages, ignore, mark_installed)
             })
-            .collect()
-    })
-    .await
-    .map_err(|e| e.to_string())?;
-
-    // Determine which of the found packages are already installed.
-    let state = app.state::<AppState>();
-    if let Ok(installed_pkgs) = get_installed_packages_full(app.clone(), state).await {
-        let installed_set: HashSet<String> = installed_pkgs
-            .into_iter()
-            .map(|p| p.name.to_lowercase())
-            .collect();
-
-        for pkg in &mut packages {
-            if installed_set.contains(&pkg.name.to_lowercase()) {
-                pkg.is_installed = true;
-            }
+            .await
+            .map_err(|e| e.to_string())?;
+            (packages, ignore, mark_installed, is_cold)
         }
+    };
+
+    if !ignore.is_empty() {
+        let before = packages.len();
+        packages.retain(|pkg| !ignore.is_ignored(&pkg.name, &pkg.source));
+        log::debug!(
+            "search_scoop: Ignore patterns hid {} packages",
+            before - packages.len()
+        );
     }
+    packages.iter_mut().for_each(&mark_installed);
+
+    rank_results(&app, &mut packages);
 
     let total_time = search_start.elapsed();
     log::info!(
@@ -232,8 +804,50 @@ pub async fn search_scoop<R: tauri::Runtime>(
         term,
         total_time.as_secs_f64()
     );
+    crate::metrics::record(if is_cold { "searchCold" } else { "search" }, total_time, true);
+
+    Ok(paged_result(packages, is_cold, search_key, offset, limit).await)
+}
 
-    Ok(SearchResult { packages, is_cold })
+/// Ranks results by relevance, then by bucket priority so official buckets win over
+/// community buckets that shadow popular package names.
+fn rank_results<R: tauri::Runtime>(app: &tauri::AppHandle<R>, packages: &mut [ScoopPackage]) {
+    let priority = get_bucket_priority_list(app);
+    packages.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| bucket_rank(&priority, &a.source).cmp(&bucket_rank(&priority, &b.source)))
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+}
+
+/// The requested page of ranked results, keeping them for the search's later pages.
+async fn paged_result(
+    packages: Vec<ScoopPackage>,
+    is_cold: bool,
+    search_key: String,
+    offset: usize,
+    limit: Option<usize>,
+) -> SearchResult {
+    let total = packages.len();
+    if limit.is_none() && offset == 0 {
+        return SearchResult {
+            packages,
+            is_cold,
+            total,
+        };
+    }
+    let packages = Arc::new(packages);
+    let page = page_of(&packages, offset, limit);
+    *LAST_SEARCH.lock().await = Some(RankedSearch {
+        key: search_key,
+        packages,
+    });
+    SearchResult {
+        packages: page,
+        is_cold,
+        total,
+    }
 }
 
 /// Warms (populates) the global manifest cache if it is empty. Intended for use by the
@@ -249,11 +863,11 @@ pub async fn warm_manifest_cache<R: tauri::Runtime>(
     let elapsed = start_time.elapsed();
 
     match result {
-        Ok((paths, was_cold)) => {
+        Ok((manifests, was_cold)) => {
             log::info!(
                 "warm_manifest_cache: ✓ Cache warmed in {:.2}s - {} manifests loaded (was_cold: {})",
                 elapsed.as_secs_f64(),
-                paths.len(),
+                manifests.len(),
                 was_cold
             );
             Ok(())
@@ -275,5 +889,146 @@ pub async fn warm_manifest_cache<R: tauri::Runtime>(
 pub async fn invalidate_manifest_cache() {
     let mut guard = MANIFEST_CACHE.lock().await;
     *guard = None;
+    *LAST_SEARCH.lock().await = None;
     log::info!("Manifest cache invalidated.");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScoopFixture;
+    use serde_json::json;
+
+    #[test]
+    fn scores_names_and_tolerates_typos() {
+        let query = SearchQuery::new("firefox", true).unwrap();
+        assert_eq!(query.name_score("firefox"), Some(100));
+        assert_eq!(query.name_score("firefox-nightly"), Some(80));
+        assert_eq!(query.name_score("librewolf"), None);
+
+        let typo = SearchQuery::new("firefxo", true).unwrap();
+        assert_eq!(typo.name_score("firefox"), None);
+        assert_eq!(typo.fuzzy_score("firefox"), Some(35));
+        assert_eq!(typo.fuzzy_score("firefox-developer"), Some(30));
+        assert_eq!(typo.fuzzy_score("thunderbird"), None);
+
+        let regex_only = SearchQuery::new("firefxo", false).unwrap();
+        assert_eq!(regex_only.fuzzy_score("firefox"), None);
+        let exact = SearchQuery::new("\"firefxo\"", true).unwrap();
+        assert_eq!(exact.fuzzy_score("firefox"), None);
+    }
+
+    #[test]
+    fn indexes_manifest_text_for_field_searches() {
+        let fixture = ScoopFixture::new();
+        fixture.bucket_manifest_json(
+            "main",
+            "neovim",
+            json!({
+                "version": "0.10.0",
+                "description": "Vim-fork focused on extensibility and usability",
+                "homepage": "https://neovim.io",
+                "license": { "identifier": "Apache-2.0" },
+                "bin": [["bin\\nvim.exe", "nvim"], "bin\\nvim-qt.exe"],
+                "architecture": { "64bit": {}, "arm64": {} }
+            }),
+        );
+        let index = build_manifest_index(fixture.root()).unwrap();
+        assert_eq!(index.len(), 1);
+        assert_eq!(index[0].license, "Apache-2.0");
+        assert!(index[0].bins.contains(&"nvim".to_string()));
+
+        let query = |term: &str, fields: Vec<SearchField>| {
+            SearchQuery::new(term, false).unwrap().with_fields(fields)
+        };
+        assert!(
+            match_manifests_in_batches(&index, &query("focused on", vec![]), &|_| {}).is_empty()
+        );
+        let found = match_manifests_in_batches(
+            &index,
+            &query("focused on", vec![SearchField::Description]),
+            &|_| {},
+        );
+        assert_eq!(found[0].match_source, MatchSource::Description);
+        assert_eq!(found[0].info, index[0].description);
+        assert_eq!(found[0].homepage, "https://neovim.io");
+        assert_eq!(found[0].license, "Apache-2.0");
+        assert!(found[0].is_64bit_only);
+        let found = match_manifests_in_batches(
+            &index,
+            &query("apache", vec![SearchField::Name, SearchField::License]),
+            &|_| {},
+        );
+        assert_eq!(found[0].match_source, MatchSource::License);
+        assert_eq!(
+            match_manifests(&index, "nvim-qt").unwrap()[0].match_source,
+            MatchSource::Binary
+        );
+    }
+
+    #[test]
+    fn filters_by_bucket_license_and_architecture() {
+        let fixture = ScoopFixture::new();
+        fixture.bucket_manifest_json(
+            "main",
+            "tool-x86",
+            json!({
+                "version": "1.0",
+                "license": "MIT|Apache-2.0",
+                "architecture": { "32bit": { "url": "https://example.com/tool.zip" } }
+            }),
+        );
+        fixture.bucket_manifest_json(
+            "extras",
+            "tool-any",
+            json!({ "version": "1.0", "license": "GPL-3.0-only" }),
+        );
+        let index = build_manifest_index(fixture.root()).unwrap();
+        let names = |filters: SearchFilters| {
+            let query = SearchQuery::new("tool", false)
+                .unwrap()
+                .with_filters(filters);
+            let mut names: Vec<String> = match_manifests_in_batches(&index, &query, &|_| {})
+                .into_iter()
+                .map(|p| p.name)
+                .collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(names(SearchFilters::default()).len(), 2);
+        let by_bucket = SearchFilters {
+            buckets: vec!["Extras".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(names(by_bucket), vec!["tool-any"]);
+        let by_license = SearchFilters {
+            licenses: vec!["apache-2.0".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(names(by_license), vec!["tool-x86"]);
+        let by_arch = SearchFilters {
+            architecture: Some("64bit".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(names(by_arch), vec!["tool-any"]);
+    }
+
+    #[test]
+    fn pages_ranked_results() {
+        let packages: Vec<ScoopPackage> = (0..5)
+            .map(|i| ScoopPackage {
+                name: format!("tool{}", i),
+                ..Default::default()
+            })
+            .collect();
+        let names = |page: Vec<ScoopPackage>| page.into_iter().map(|p| p.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(page_of(&packages, 0, Some(2))),
+            vec!["tool0", "tool1"]
+        );
+        assert_eq!(names(page_of(&packages, 4, Some(2))), vec!["tool4"]);
+        assert!(page_of(&packages, 9, Some(2)).is_empty());
+        assert_eq!(page_of(&packages, 1, None).len(), 4);
+    }
+}
@@ -0,0 +1,246 @@
+//! Past search queries and the autocomplete suggestions built from them.
+//!
+//! Queries the UI records are kept in the settings store with how often and when they
+//! were last searched. Suggestions for a prefix list matching past queries first, most
+//! recent first, then package names from the manifest index, shortest first.
+use crate::commands::ignore_patterns::load_ignore_rules;
+use crate::commands::search::get_manifests;
+use crate::commands::settings;
+use crate::config_keys::SEARCH_HISTORY;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tauri::{AppHandle, Runtime};
+
+/// Queries kept; the least recently used are dropped beyond this.
+const MAX_HISTORY: usize = 200;
+const DEFAULT_LIMIT: usize = 10;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHistoryEntry {
+    pub query: String,
+    pub count: u32,
+    /// Unix seconds of the last search.
+    pub last_used: u64,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryOrder {
+    #[default]
+    Recent,
+    /// Most searched first.
+    Top,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SuggestionSource {
+    History,
+    Package,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSuggestion {
+    pub text: String,
+    pub source: SuggestionSource,
+}
+
+fn load_history<R: Runtime>(app: &AppHandle<R>) -> Vec<SearchHistoryEntry> {
+    settings::get_config_value(app.clone(), SEARCH_HISTORY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn save_history(app: &AppHandle, history: &[SearchHistoryEntry]) -> Result<(), String> {
+    let value = serde_json::to_value(history)
+        .map_err(|e| format!("Failed to serialize search history: {}", e))?;
+    settings::set_config_value(app.clone(), SEARCH_HISTORY.to_string(), value)
+}
+
+/// Adds a search of `query` at `now`. Queries differing only in case are one entry,
+/// spelled as last searched.
+pub fn add_query(history: &mut Vec<SearchHistoryEntry>, query: &str, now: u64) {
+    let query = query.trim();
+    if query.is_empty() {
+        return;
+    }
+    match history
+        .iter_mut()
+        .find(|entry| entry.query.eq_ignore_ascii_case(query))
+    {
+        Some(entry) => {
+            entry.query = query.to_string();
+            entry.count += 1;
+            entry.last_used = now;
+        }
+        None => history.push(SearchHistoryEntry {
+            query: query.to_string(),
+            count: 1,
+            last_used: now,
+        }),
+    }
+    if history.len() > MAX_HISTORY {
+        history.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+        history.truncate(MAX_HISTORY);
+    }
+}
+
+/// Sorts `history` in `order`, ties broken by the other criterion.
+pub fn sort_history(history: &mut [SearchHistoryEntry], order: HistoryOrder) {
+    match order {
+        HistoryOrder::Recent => {
+            history.sort_by(|a, b| (b.last_used, b.count).cmp(&(a.last_used, a.count)))
+        }
+        HistoryOrder::Top => {
+            history.sort_by(|a, b| (b.count, b.last_used).cmp(&(a.count, a.last_used)))
+        }
+    }
+}
+
+/// Up to `limit` completions of `prefix`: past queries, then package `names`. Matching
+/// ignores case, and the prefix itself is not suggested.
+pub fn suggest<'a>(
+    history: &[SearchHistoryEntry],
+    names: impl IntoIterator<Item = &'a str>,
+    prefix: &str,
+    limit: usize,
+) -> Vec<SearchSuggestion> {
+    let prefix = prefix.trim().to_lowercase();
+    if prefix.is_empty() {
+        return Vec::new();
+    }
+    let completes = |text: &str| {
+        let text = text.to_lowercase();
+        text.starts_with(&prefix) && text != prefix
+    };
+    let mut seen = HashSet::new();
+
+    let mut past: Vec<SearchHistoryEntry> = history
+        .iter()
+        .filter(|entry| completes(&entry.query))
+        .cloned()
+        .collect();
+    sort_history(&mut past, HistoryOrder::Recent);
+    let mut suggestions: Vec<SearchSuggestion> = past
+        .into_iter()
+        .filter(|entry| seen.insert(entry.query.to_lowercase()))
+        .map(|entry| SearchSuggestion {
+            text: entry.query,
+            source: SuggestionSource::History,
+        })
+        .collect();
+
+    let mut packages: Vec<&str> = names
+        .into_iter()
+        .filter(|name| completes(name) && seen.insert(name.to_lowercase()))
+        .collect();
+    packages.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+    suggestions.extend(packages.into_iter().map(|name| SearchSuggestion {
+        text: name.to_string(),
+        source: SuggestionSource::Package,
+    }));
+    suggestions.truncate(limit);
+    suggestions
+}
+
+/// Records a search the user ran.
+#[tauri::command]
+pub fn record_search_query(app: AppHandle, query: String) -> Result<(), String> {
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let mut history = load_history(&app);
+    add_query(&mut history, &query, now);
+    save_history(&app, &history)
+}
+
+/// Past queries, most recent or most searched first.
+#[tauri::command]
+pub fn get_search_history(
+    app: AppHandle,
+    order: Option<HistoryOrder>,
+    limit: Option<usize>,
+) -> Vec<SearchHistoryEntry> {
+    let mut history = load_history(&app);
+    sort_history(&mut history, order.unwrap_or_default());
+    history.truncate(limit.unwrap_or(DEFAULT_LIMIT));
+    history
+}
+
+#[tauri::command]
+pub fn clear_search_history(app: AppHandle) -> Result<(), String> {
+    save_history(&app, &[])?;
+    log::info!("Cleared search history");
+    Ok(())
+}
+
+/// Completions of `prefix` from the search history and the package names of every
+/// bucket, leaving out ignored packages.
+#[tauri::command]
+pub async fn get_search_suggestions(
+    app: AppHandle,
+    prefix: String,
+    limit: Option<usize>,
+) -> Result<Vec<SearchSuggestion>, String> {
+    let history = load_history(&app);
+    let ignore = load_ignore_rules(&app);
+    let (manifests, _) = get_manifests(app).await?;
+    let names = manifests
+        .iter()
+        .filter(|m| !ignore.is_ignored(&m.name, &m.bucket))
+        .map(|m| m.name.as_str());
+    Ok(suggest(
+        &history,
+        names,
+        &prefix,
+        limit.unwrap_or(DEFAULT_LIMIT),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_queries_and_suggests_completions() {
+        let mut history = Vec::new();
+        add_query(&mut history, "python", 10);
+        add_query(&mut history, "  git ", 20);
+        add_query(&mut history, "Python", 30);
+        add_query(&mut history, "pyenv", 40);
+        add_query(&mut history, "", 50);
+        assert_eq!(history.len(), 3);
+
+        sort_history(&mut history, HistoryOrder::Top);
+        assert_eq!(
+            history[0],
+            SearchHistoryEntry {
+                query: "Python".to_string(),
+                count: 2,
+                last_used: 30,
+            }
+        );
+        sort_history(&mut history, HistoryOrder::Recent);
+        assert_eq!(history[0].query, "pyenv");
+
+        let names = ["python", "python312", "pypy", "py", "git"];
+        let texts = |suggestions: Vec<SearchSuggestion>| {
+            suggestions
+                .into_iter()
+                .map(|s| (s.text, s.source))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            texts(suggest(&history, names, "Py", 4)),
+            vec![
+                ("pyenv".to_string(), SuggestionSource::History),
+                ("Python".to_string(), SuggestionSource::History),
+                ("pypy".to_string(), SuggestionSource::Package),
+                ("python312".to_string(), SuggestionSource::Package),
+            ]
+        );
+        assert!(suggest(&history, names, " ", 4).is_empty());
+    }
+}
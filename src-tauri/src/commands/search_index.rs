@@ -0,0 +1,161 @@
+//! The manifest search index kept on disk between runs.
+//!
+//! Parsing every manifest of every bucket takes seconds with many buckets, so the index
+//! is saved to `manifest-index.json` in the app data directory along with the commit
+//! each git bucket was at. When the cache is populated, buckets still at that commit
+//! are read from the file and only the others are parsed again. Buckets that are not
+//! git checkouts are always parsed.
+use crate::commands::bucket::get_head_commit;
+use crate::commands::search::{index_bucket, IndexedManifest};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const INDEX_FILE: &str = "manifest-index.json";
+/// Bumped when [`IndexedManifest`] changes, so older files are rebuilt.
+const INDEX_FORMAT: u32 = 2;
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct StoredIndex {
+    format: u32,
+    /// Index entries by bucket name.
+    buckets: BTreeMap<String, StoredBucket>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct StoredBucket {
+    /// The bucket's HEAD commit when it was indexed.
+    commit: String,
+    manifests: Vec<IndexedManifest>,
+}
+
+pub fn index_file() -> Option<PathBuf> {
+    crate::portable::app_data_dir().map(|dir| dir.join(INDEX_FILE))
+}
+
+fn read_index(path: &Path) -> StoredIndex {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<StoredIndex>(&content).ok())
+        .filter(|index| index.format == INDEX_FORMAT)
+        .unwrap_or_default()
+}
+
+fn write_index(path: &Path, index: &StoredIndex) -> Result<(), String> {
+    let content = serde_json::to_string(index)
+        .map_err(|e| format!("Failed to serialize manifest index: {}", e))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    // Written aside and renamed so a crash cannot leave half a file behind
+    let partial = path.with_extension("json.partial");
+    fs::write(&partial, content)
+        .and_then(|_| fs::rename(&partial, path))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Indexes the manifests of every bucket under `scoop_path`, reusing the entries saved
+/// in `store` for git buckets still at the same commit, and saves the result back.
+pub fn load_or_build(
+    scoop_path: &Path,
+    store: Option<&Path>,
+) -> Result<Vec<IndexedManifest>, String> {
+    let buckets_path = scoop_path.join("buckets");
+    if !buckets_path.is_dir() {
+        return Err("Scoop buckets directory not found".to_string());
+    }
+    let entries = fs::read_dir(&buckets_path)
+        .map_err(|e| format!("Failed to read buckets directory: {}", e))?;
+
+    let mut saved = store.map(read_index).unwrap_or_default();
+    let mut updated = StoredIndex {
+        format: INDEX_FORMAT,
+        ..Default::default()
+    };
+    let mut manifests = Vec::new();
+    let (mut reused, mut parsed) = (0, 0);
+    let mut changed = saved.format != INDEX_FORMAT;
+
+    for entry in entries.flatten() {
+        let bucket_path = entry.path();
+        if !bucket_path.is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        let commit = get_head_commit(&bucket_path);
+
+        let bucket_manifests = match (&commit, saved.buckets.remove(&name)) {
+            (Some(commit), Some(stored)) if &stored.commit == commit => {
+                reused += 1;
+                stored.manifests
+            }
+            _ => {
+                parsed += 1;
+                changed |= commit.is_some();
+                index_bucket(&bucket_path)
+            }
+        };
+        manifests.extend(bucket_manifests.iter().cloned());
+        if let Some(commit) = commit {
+            updated.buckets.insert(
+                name,
+                StoredBucket {
+                    commit,
+                    manifests: bucket_manifests,
+                },
+            );
+        }
+    }
+    // Buckets that were removed since
+    changed |= !saved.buckets.is_empty();
+
+    log::info!(
+        "Manifest index: {} buckets unchanged, {} parsed, {} manifests",
+        reused,
+        parsed,
+        manifests.len()
+    );
+    if let (Some(path), true) = (store, changed) {
+        if let Err(e) = write_index(path, &updated) {
+            log::warn!("{}", e);
+        }
+    }
+    Ok(manifests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScoopFixture;
+
+    #[test]
+    fn reuses_buckets_at_the_saved_commit() {
+        let fixture = ScoopFixture::new();
+        fixture
+            .bucket_manifest("main", "git", "2.44.0")
+            .bucket_manifest("local", "tool", "1.0");
+        fixture.git_bucket("main");
+        let store = fixture.root().join("index").join(INDEX_FILE);
+
+        let version_of = |manifests: &[IndexedManifest], name: &str| {
+            manifests
+                .iter()
+                .find(|m| m.name == name)
+                .map(|m| m.version.clone())
+        };
+        let first = load_or_build(fixture.root(), Some(&store)).unwrap();
+        assert_eq!(first.len(), 2);
+        assert!(read_index(&store).buckets.contains_key("main"));
+
+        // Uncommitted edits go unseen in git buckets, not in the others
+        fixture
+            .bucket_manifest("main", "git", "2.45.0")
+            .bucket_manifest("local", "tool", "2.0");
+        let second = load_or_build(fixture.root(), Some(&store)).unwrap();
+        assert_eq!(version_of(&second, "git").as_deref(), Some("2.44.0"));
+        assert_eq!(version_of(&second, "tool").as_deref(), Some("2.0"));
+    }
+}
@@ -0,0 +1,176 @@
+//! Removing Pailer's own data when the app is uninstalled.
+//!
+//! Uninstalling only deletes the install directory, which leaves the settings store,
+//! logs, caches, the WebView2 profile, the tray autostart entries and the `pailer:`
+//! protocol registration behind. When the user asks for app data to be deleted (the NSIS
+//! checkbox, or `DELETEAPPDATA=1` for the MSI), the uninstallers run
+//! `pailer.exe --self-cleanup` while the executable still exists; the settings page can
+//! call [`self_cleanup`] before the app is removed by hand. Nothing inside a Scoop root
+//! is removed: a Scoop-installed or portable copy may keep its data in `persist`, and
+//! that belongs to Scoop.
+use crate::commands::debug::OLD_APP_DIR;
+use crate::state::AppState;
+use crate::utils::{self, ScoopDirs};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Command-line flag the uninstallers start the app with.
+pub const CLI_FLAG: &str = "--self-cleanup";
+/// Registry keys under `HKEY_CURRENT_USER` written by the app.
+#[cfg(windows)]
+const APP_REGISTRY_KEYS: &[&str] = &[r"Software\com.pailer.ks", r"Software\Pailer"];
+#[cfg(windows)]
+const PROTOCOL_KEY: &str = r"Software\Classes\pailer";
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfCleanupReport {
+    /// Directories and registry keys that were removed.
+    pub removed: Vec<String>,
+    /// Data directories left alone because they are inside a Scoop root.
+    pub skipped: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// Directories holding the app's own data.
+fn data_dirs() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = crate::portable::app_data_dir().into_iter().collect();
+    // The WebView2 profile sits inside the data directory in portable mode
+    if !crate::portable::is_portable() {
+        if let Some(local) = dirs::data_local_dir() {
+            paths.push(local.join(crate::portable::APP_ID));
+            paths.push(local.join(OLD_APP_DIR));
+        }
+    }
+    paths
+}
+
+/// Scoop directories that must survive the cleanup: the configured roots and caches,
+/// and every directory that looks like a Scoop root.
+fn protected_roots(dirs: &ScoopDirs) -> Vec<PathBuf> {
+    let mut roots = vec![dirs.root.clone(), dirs.global.clone(), dirs.cache.clone()];
+    roots.extend(
+        utils::build_candidate_list(Vec::new())
+            .into_iter()
+            .filter(utils::is_valid_scoop_candidate),
+    );
+    roots
+}
+
+/// Whether `path` is one of `roots`, lies inside one, or contains one.
+pub fn overlaps_scoop_root(path: &Path, roots: &[PathBuf]) -> bool {
+    let normalize = |p: &Path| fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+    let path = normalize(path);
+    roots
+        .iter()
+        .map(|root| normalize(root))
+        .any(|root| path.starts_with(&root) || root.starts_with(&path))
+}
+
+/// Removes the data directories in `paths`, except those overlapping `roots`.
+pub fn remove_data_dirs(paths: &[PathBuf], roots: &[PathBuf], report: &mut SelfCleanupReport) {
+    for path in paths.iter().filter(|p| p.exists()) {
+        if overlaps_scoop_root(path, roots) {
+            log::warn!("Not removing {}: it is inside a Scoop root", path.display());
+            report.skipped.push(path.to_string_lossy().to_string());
+            continue;
+        }
+        match fs::remove_dir_all(path) {
+            Ok(()) => report.removed.push(path.to_string_lossy().to_string()),
+            Err(e) => report
+                .errors
+                .push(format!("Failed to remove {}: {}", path.display(), e)),
+        }
+    }
+}
+
+/// Removes the app's registry keys and its protocol handler. The protocol key is only
+/// removed when it still opens a Pailer executable.
+#[cfg(windows)]
+fn remove_registry_entries(report: &mut SelfCleanupReport) {
+    use winreg::{enums::HKEY_CURRENT_USER, RegKey};
+
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let opens_pailer = hkcu
+        .open_subkey(format!(r"{}\shell\open\command", PROTOCOL_KEY))
+        .and_then(|key| key.get_value::<String, _>(""))
+        .is_ok_and(|command| command.to_lowercase().contains("pailer"));
+    let keys = APP_REGISTRY_KEYS
+        .iter()
+        .chain(opens_pailer.then_some(&PROTOCOL_KEY));
+    for key in keys {
+        match hkcu.delete_subkey_all(key) {
+            Ok(()) => report.removed.push(format!(r"HKCU\{}", key)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => report
+                .errors
+                .push(format!(r"Failed to remove HKCU\{}: {}", key, e)),
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn remove_registry_entries(_report: &mut SelfCleanupReport) {}
+
+/// Removes the autostart entries, registry keys and data directories, never touching
+/// anything under `scoop_dirs`.
+pub fn clean_app_data(scoop_dirs: &ScoopDirs) -> SelfCleanupReport {
+    let mut report = SelfCleanupReport::default();
+    if let Err(e) = crate::commands::startup::cleanup_startup_entries() {
+        report
+            .errors
+            .push(format!("Failed to remove the autostart entry: {}", e));
+    }
+    remove_registry_entries(&mut report);
+    remove_data_dirs(&data_dirs(), &protected_roots(scoop_dirs), &mut report);
+    log::info!(
+        "Self cleanup removed {} item(s), skipped {}, {} error(s)",
+        report.removed.len(),
+        report.skipped.len(),
+        report.errors.len()
+    );
+    report
+}
+
+/// Entry point for `--self-cleanup`, run by the uninstallers before the app starts.
+pub fn run_from_uninstaller() {
+    let root = utils::get_scoop_root_fallback();
+    clean_app_data(&ScoopDirs::resolve(&root));
+}
+
+/// Removes the app's data, logs, caches, autostart entry and protocol registration.
+/// Files still held open by the running app, such as the current log, are reported as
+/// errors; the app should quit afterwards so it does not write its settings back.
+#[tauri::command]
+pub fn self_cleanup(state: State<'_, AppState>) -> Result<SelfCleanupReport, String> {
+    Ok(clean_app_data(&state.scoop_dirs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn never_removes_data_inside_a_scoop_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let scoop = dir.path().join("scoop");
+        let persisted = scoop.join("persist").join("pailer").join("data");
+        let app_data = dir.path().join("AppData").join("com.pailer.ks");
+        for path in [&persisted, &app_data.join("logs")] {
+            fs::create_dir_all(path).unwrap();
+        }
+        let roots = vec![scoop.clone()];
+        assert!(overlaps_scoop_root(&persisted, &roots));
+        assert!(overlaps_scoop_root(dir.path(), &roots));
+        assert!(!overlaps_scoop_root(&app_data, &roots));
+
+        let mut report = SelfCleanupReport::default();
+        remove_data_dirs(&[persisted.clone(), app_data.clone()], &roots, &mut report);
+        assert!(persisted.is_dir());
+        assert!(!app_data.exists());
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.removed, vec![app_data.to_string_lossy().to_string()]);
+    }
+}
@@ -10,9 +10,13 @@ use rand::random;
 use base64::{Engine as _, engine::general_purpose};
 
 /// Current store file name for unified settings (frontend + backend)
-const STORE_PATH: &str = "settings.json";
+pub const STORE_PATH: &str = "settings.json";
 /// Legacy store file name (for migration)
 const LEGACY_STORE_PATH: &str = "core.json";
+/// Directory (inside the app data dir) holding backups of Scoop's config.json
+const SCOOP_CONFIG_BACKUP_DIR: &str = "scoop_config_backups";
+/// Number of Scoop config backups kept before the oldest are pruned
+const MAX_SCOOP_CONFIG_BACKUPS: usize = 20;
 
 /// Fixed application-level encryption key (32 bytes for AES-256)
 // This is a simple approach following KISS principle - in production, consider using system keychain
@@ -56,9 +60,16 @@ fn decrypt_api_key(encrypted_key: &str) -> Result<String, String> {
 /// Migrates data from legacy store.json to core.json if needed.
 /// Returns true if migration was performed.
 fn migrate_from_legacy_store<R: Runtime>(app: &AppHandle<R>) -> bool {
-    let app_data_dir = match app.path().app_data_dir() {
-        Ok(dir) => dir,
-        Err(_) => return false,
+    let app_data_dir = if crate::portable::is_portable() {
+        match crate::portable::app_data_dir() {
+            Some(dir) => dir,
+            None => return false,
+        }
+    } else {
+        match app.path().app_data_dir() {
+            Ok(dir) => dir,
+            Err(_) => return false,
+        }
     };
 
     let legacy_path = app_data_dir.join(LEGACY_STORE_PATH);
@@ -96,7 +107,7 @@ where
     migrate_from_legacy_store(&app);
     
     let store = app
-        .store(PathBuf::from(STORE_PATH))
+        .store(crate::portable::store_path(STORE_PATH))
         .map_err(|e| e.to_string())?;
     let result = operation(&store);
     store.save().map_err(|e| e.to_string())?;
@@ -112,7 +123,7 @@ where
     migrate_from_legacy_store(&app);
     
     let store = app
-        .store(PathBuf::from(STORE_PATH))
+        .store(crate::portable::store_path(STORE_PATH))
         .map_err(|e| e.to_string())?;
     Ok(operation(&store))
 }
@@ -129,7 +140,7 @@ fn get_scoop_config_path() -> Result<PathBuf, String> {
 /// Reads the Scoop configuration file and returns its contents as a JSON map.
 ///
 /// If the file doesn't exist, it returns an empty map.
-fn read_scoop_config() -> Result<Map<String, Value>, String> {
+pub fn read_scoop_config() -> Result<Map<String, Value>, String> {
     let path = get_scoop_config_path()?;
     if !path.exists() {
         return Ok(Map::new());
@@ -140,18 +151,81 @@ fn read_scoop_config() -> Result<Map<String, Value>, String> {
         .map_err(|e| format!("Failed to parse Scoop config at {:?}: {}", path, e))
 }
 
+/// Returns the directory holding timestamped backups of Scoop's `config.json`.
+fn get_scoop_config_backup_dir() -> Result<PathBuf, String> {
+    crate::portable::app_data_dir()
+        .map(|dir| dir.join(SCOOP_CONFIG_BACKUP_DIR))
+        .ok_or_else(|| "Could not resolve application data directory".to_string())
+}
+
+/// Copies the current Scoop config to a timestamped backup and prunes old backups.
+fn backup_scoop_config(path: &PathBuf) -> Result<(), String> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let backup_dir = get_scoop_config_backup_dir()?;
+    fs::create_dir_all(&backup_dir)
+        .map_err(|e| format!("Failed to create config backup directory: {}", e))?;
+
+    let backup_path = backup_dir.join(format!(
+        "config-{}.json",
+        chrono::Local::now().format("%Y%m%d-%H%M%S-%3f")
+    ));
+    fs::copy(path, &backup_path)
+        .map_err(|e| format!("Failed to back up Scoop config to {:?}: {}", backup_path, e))?;
+    log::info!("Backed up Scoop config to {:?}", backup_path);
+
+    // Backup names sort chronologically, so the oldest come first
+    let mut backups = list_backup_files(&backup_dir);
+    if backups.len() > MAX_SCOOP_CONFIG_BACKUPS {
+        let excess = backups.len() - MAX_SCOOP_CONFIG_BACKUPS;
+        for old in backups.drain(..excess) {
+            let _ = fs::remove_file(old);
+        }
+    }
+
+    Ok(())
+}
+
+fn list_backup_files(backup_dir: &PathBuf) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(backup_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name()
+                        .map(|n| {
+                            let name = n.to_string_lossy();
+                            name.starts_with("config-") && name.ends_with(".json")
+                        })
+                        .unwrap_or(false)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
 /// Writes the given JSON map to the Scoop configuration file.
 ///
-/// This will create the directory and file if they don't exist.
+/// The existing file is backed up first. This will create the directory and file
+/// if they don't exist.
 fn write_scoop_config(config: &Map<String, Value>) -> Result<(), String> {
     let path = get_scoop_config_path()?;
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
             .map_err(|e| format!("Failed to create Scoop config directory: {}", e))?;
     }
+    backup_scoop_config(&path)?;
     let content = serde_json::to_string_pretty(config)
         .map_err(|e| format!("Failed to serialize Scoop config: {}", e))?;
-    fs::write(&path, content).map_err(|e| format!("Failed to write to {:?}: {}", path, e))
+    fs::write(&path, content).map_err(|e| format!("Failed to write to {:?}: {}", path, e))?;
+    // Scoop's proxy setting may have changed
+    crate::http::invalidate_client();
+    Ok(())
 }
 
 /// Gets the configured Scoop path from the store.
@@ -193,8 +267,10 @@ pub fn set_scoop_path<R: Runtime>(app: AppHandle<R>, path: String) -> Result<(),
     // We're only setting the scoop path synchronously and not clearing the cache
     // to avoid needing async context or blocking operations
     if let Some(state) = app.try_state::<crate::state::AppState>() {
-        state.set_scoop_path(std::path::PathBuf::from(path));
+        state.set_scoop_path(std::path::PathBuf::from(&path));
+        state.watch_scoop_dirs(&app);
     }
+    crate::utils::set_scoop_root_cache(std::path::PathBuf::from(path));
     
     Ok(())
 }
@@ -260,6 +336,9 @@ pub fn get_config_value<R: Runtime>(
     app: AppHandle<R>,
     key: String,
 ) -> Result<Option<Value>, String> {
+    if let Some(value) = crate::policy::locked_setting(&key) {
+        return Ok(Some(value));
+    }
     with_store_get(app, |store| {
         // First try direct access
         if let Some(value) = store.get(&key) {
@@ -300,6 +379,9 @@ pub fn set_config_value(
     key: String,
     value: Value,
 ) -> Result<(), String> {
+    if crate::policy::locked_setting(&key).is_some() {
+        return Err(format!("'{}' is set by the machine policy", key));
+    }
     let key_clone = key.clone();
     with_store_mut(app.clone(), move |store| store.set(key_clone, value))?;
 
@@ -313,6 +395,12 @@ pub fn set_config_value(
                 }
             });
         }
+        k if k.starts_with("network.") => crate::http::configure(&app),
+        k if k.starts_with("metrics.") => crate::metrics::configure(&app),
+        k if k.starts_with("crashReports.") => crate::crash::configure(&app),
+        k if k.starts_with("quietMode.") => crate::quiet::configure(&app),
+        k if k.starts_with("cleanup.") => crate::recycle::configure(&app),
+        k if k.starts_with("performance.") => crate::scan_pool::configure(&app),
         _ => {}
     }
 
@@ -338,15 +426,80 @@ pub fn get_scoop_config() -> Result<Option<serde_json::Map<String, serde_json::V
     }
 }
 
-/// Updates the Scoop configuration with a new JSON object
+/// Updates the Scoop configuration with a new JSON object.
+///
+/// By default the keys in `config` are merged into the existing file: keys it does not
+/// mention are preserved and keys set to `null` are removed. Only when `merge` is
+/// `false` is the file replaced with `config`.
 #[tauri::command]
-pub fn update_scoop_config(config: serde_json::Value) -> Result<(), String> {
+pub fn update_scoop_config(config: serde_json::Value, merge: Option<bool>) -> Result<(), String> {
     // Convert to Map for writing
-    if let serde_json::Value::Object(map) = config {
-        write_scoop_config(&map)
-    } else {
-        Err("Config must be a JSON object".to_string())
+    let serde_json::Value::Object(map) = config else {
+        return Err("Config must be a JSON object".to_string());
+    };
+
+    if !merge.unwrap_or(true) {
+        return write_scoop_config(&map);
+    }
+
+    let mut existing = read_scoop_config()?;
+    for (key, value) in map {
+        if value.is_null() {
+            existing.remove(&key);
+        } else {
+            existing.insert(key, value);
+        }
     }
+    write_scoop_config(&existing)
+}
+
+/// A backup of Scoop's `config.json` taken before it was modified.
+#[derive(serde::Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoopConfigBackup {
+    pub file_name: String,
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+/// Lists Scoop config backups, newest first.
+#[tauri::command]
+pub fn list_scoop_config_backups() -> Result<Vec<ScoopConfigBackup>, String> {
+    let backup_dir = get_scoop_config_backup_dir()?;
+    let mut backups: Vec<ScoopConfigBackup> = list_backup_files(&backup_dir)
+        .into_iter()
+        .filter_map(|path| {
+            let metadata = fs::metadata(&path).ok()?;
+            let created_at = metadata
+                .modified()
+                .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
+                .unwrap_or_default();
+            Some(ScoopConfigBackup {
+                file_name: path.file_name()?.to_string_lossy().to_string(),
+                created_at,
+                size_bytes: metadata.len(),
+            })
+        })
+        .collect();
+    backups.reverse();
+    Ok(backups)
+}
+
+/// Restores Scoop's `config.json` from a backup. The current file is backed up first.
+#[tauri::command]
+pub fn restore_scoop_config_backup(file_name: String) -> Result<(), String> {
+    if file_name.contains(['/', '\\']) || file_name.contains("..") {
+        return Err(format!("Invalid backup name: {}", file_name));
+    }
+
+    let backup_path = get_scoop_config_backup_dir()?.join(&file_name);
+    let content = fs::read_to_string(&backup_path)
+        .map_err(|e| format!("Failed to read backup {}: {}", file_name, e))?;
+    let config: Map<String, Value> = serde_json::from_str(&content)
+        .map_err(|e| format!("Backup {} is not a valid config: {}", file_name, e))?;
+
+    log::info!("Restoring Scoop config from backup {}", file_name);
+    write_scoop_config(&config)
 }
 
 /// Gets the VirusTotal API key from Scoop's `config.json`.
@@ -414,14 +567,12 @@ pub fn set_scoop_proxy(proxy: String) -> Result<(), String> {
 #[tauri::command]
 pub async fn run_scoop_command(window: tauri::Window, command: String) -> Result<(), String> {
     let full_command = format!("scoop {}", command);
-    crate::commands::powershell::run_and_stream_command(
+    crate::commands::scoop::run_queued_command(
         window,
         full_command,
         command.clone(),
-        crate::commands::powershell::EVENT_OUTPUT,
-        crate::commands::powershell::EVENT_FINISHED,
-        crate::commands::powershell::EVENT_CANCEL,
         None,
+        crate::commands::powershell::OperationClass::Custom,
     )
     .await
 }
@@ -439,14 +590,12 @@ pub fn get_scoop_config_directory() -> Result<String, String> {
 /// Executes an arbitrary PowerShell command directly without adding any prefix
 #[tauri::command]
 pub async fn run_powershell_command(window: tauri::Window, command: String) -> Result<(), String> {
-    crate::commands::powershell::run_and_stream_command(
+    crate::commands::scoop::run_queued_command(
         window,
         command.clone(),
         command.clone(),
-        crate::commands::powershell::EVENT_OUTPUT,
-        crate::commands::powershell::EVENT_FINISHED,
-        crate::commands::powershell::EVENT_CANCEL,
         None,
+        crate::commands::powershell::OperationClass::Custom,
     )
     .await
 }
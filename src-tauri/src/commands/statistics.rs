@@ -0,0 +1,175 @@
+//! Command for aggregating statistics shown on the home dashboard.
+use crate::commands::installed::get_installed_packages;
+use crate::commands::update_log::read_update_log;
+use crate::state::AppState;
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Runtime, State};
+use tokio::sync::Mutex;
+
+/// How long computed statistics are reused while the installed set is unchanged.
+const STATISTICS_CACHE_TTL: Duration = Duration::from_secs(300);
+const UPDATE_WINDOW_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// Aggregated statistics about the Scoop installation.
+#[derive(Serialize, Debug, Clone)]
+pub struct Statistics {
+    pub total_installed: usize,
+    pub packages_per_bucket: BTreeMap<String, usize>,
+    pub disk_usage_bytes: u64,
+    pub cache_size_bytes: u64,
+    pub updates_last_30_days: usize,
+    pub average_update_duration_ms: Option<u64>,
+    pub generated_at: String,
+}
+
+struct StatisticsCache {
+    fingerprint: String,
+    computed_at: Instant,
+    statistics: Statistics,
+}
+
+// Disk usage walks every installed app, so results are cached between calls.
+static STATISTICS_CACHE: Lazy<Mutex<Option<StatisticsCache>>> = Lazy::new(|| Mutex::new(None));
+
+/// Recursively sums file sizes, skipping links so `current` junctions are not counted twice.
+pub fn dir_size(path: &Path) -> u64 {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match fs::symlink_metadata(entry.path()) {
+            Ok(meta) if meta.file_type().is_symlink() => 0,
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Computes the size of every app directory in parallel.
+pub fn apps_disk_usage(apps_path: &Path) -> u64 {
+    let app_dirs: Vec<PathBuf> = match fs::read_dir(apps_path) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .collect(),
+        Err(_) => return 0,
+    };
+
+    app_dirs
+        .par_iter()
+        .map(|app_dir| {
+            fs::read_dir(app_dir)
+                .map(|entries| {
+                    entries
+                        .flatten()
+                        .filter(|e| {
+                            !e.file_name()
+                                .to_string_lossy()
+                                .eq_ignore_ascii_case("current")
+                        })
+                        .map(|e| match fs::symlink_metadata(e.path()) {
+                            Ok(meta) if meta.file_type().is_symlink() => 0,
+                            Ok(meta) if meta.is_dir() => dir_size(&e.path()),
+                            Ok(meta) => meta.len(),
+                            Err(_) => 0,
+                        })
+                        .sum::<u64>()
+                })
+                .unwrap_or(0)
+        })
+        .sum()
+}
+
+/// Returns aggregated statistics about installed packages, disk usage and update history.
+///
+/// Results are cached for a few minutes as long as the installed package set is unchanged;
+/// pass `force_refresh` to recompute immediately.
+#[tauri::command]
+pub async fn get_statistics<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+    force_refresh: Option<bool>,
+) -> Result<Statistics, String> {
+    let installed_packages = get_installed_packages(app, state.clone()).await?;
+    let fingerprint = {
+        let guard = state.installed_packages.lock().await;
+        guard
+            .as_ref()
+            .map(|cache| cache.fingerprint.clone())
+            .unwrap_or_default()
+    };
+
+    if !force_refresh.unwrap_or(false) {
+        let guard = STATISTICS_CACHE.lock().await;
+        if let Some(cache) = guard.as_ref() {
+            if cache.fingerprint == fingerprint
+                && cache.computed_at.elapsed() < STATISTICS_CACHE_TTL
+            {
+                log::debug!("Returning cached statistics");
+                return Ok(cache.statistics.clone());
+            }
+        }
+    }
+
+    log::info!(
+        "Computing statistics for {} installed packages",
+        installed_packages.len()
+    );
+    let dirs = state.scoop_dirs();
+
+    let statistics = tokio::task::spawn_blocking(move || {
+        let mut packages_per_bucket = BTreeMap::new();
+        for package in &installed_packages {
+            *packages_per_bucket
+                .entry(package.source.clone())
+                .or_insert(0) += 1;
+        }
+
+        let now = chrono::Utc::now().timestamp().max(0) as u64;
+        let recent_updates: Vec<_> = read_update_log()
+            .into_iter()
+            .filter(|entry| now.saturating_sub(entry.timestamp) <= UPDATE_WINDOW_SECS)
+            .collect();
+
+        let average_update_duration_ms = if recent_updates.is_empty() {
+            None
+        } else {
+            Some(
+                recent_updates.iter().map(|e| e.duration_ms).sum::<u64>()
+                    / recent_updates.len() as u64,
+            )
+        };
+
+        Statistics {
+            total_installed: installed_packages.len(),
+            packages_per_bucket,
+            disk_usage_bytes: apps_disk_usage(&dirs.apps),
+            cache_size_bytes: dir_size(&dirs.cache),
+            updates_last_30_days: recent_updates.len(),
+            average_update_duration_ms,
+            generated_at: chrono::Utc::now().to_rfc3339(),
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut guard = STATISTICS_CACHE.lock().await;
+    *guard = Some(StatisticsCache {
+        fingerprint,
+        computed_at: Instant::now(),
+        statistics: statistics.clone(),
+    });
+
+    Ok(statistics)
+}
@@ -1,12 +1,15 @@
 //! Command for checking the overall status of Scoop and installed packages.
 //! This implements the equivalent of `scoop status` command.
 
-use crate::commands::installed::get_installed_packages_full;
+use crate::commands::deprecation::{detect_deprecation, find_deprecated_manifest};
+use crate::commands::installed::get_installed_packages;
 use crate::models::{AppStatusInfo, ScoopPackage as InstalledPackage, ScoopStatus};
+use crate::engine::version::is_update_available;
 use crate::state::AppState;
 use crate::utils::locate_package_manifest;
 use git2::Repository;
 use serde::Deserialize;
+use serde_json::Value;
 use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -16,8 +19,6 @@ use tauri::{AppHandle, Runtime, State};
 #[derive(Deserialize, Debug)]
 struct Manifest {
     version: String,
-    #[serde(default)]
-    deprecated: Option<String>,
 }
 
 /// Represents the structure of an install.json file
@@ -101,6 +102,7 @@ fn get_app_status(
     let mut is_deprecated = false;
     let mut is_removed = false;
     let mut latest_version = None;
+    let mut replacement = None;
     let mut is_outdated = false;
     let is_held = held_packages.contains(&package.name);
 
@@ -108,24 +110,51 @@ fn get_app_status(
         info.push("Held package".to_string());
     }
 
+    if package.broken {
+        is_failed = true;
+        info.push("Install metadata missing".to_string());
+    }
+
     // Check if manifest exists and get latest version
-    match locate_package_manifest(scoop_path, &package.name, Some(package.source.clone())) {
+    let mut located = locate_package_manifest(scoop_path, &package.name, Some(package.source.clone()));
+    let bucket_missing = located
+        .as_ref()
+        .map(|(_, bucket)| bucket.ends_with("(missing)") || bucket.contains("Bucket missing"))
+        .unwrap_or(true);
+    if bucket_missing {
+        // Manifests moved to a bucket's `deprecated` directory are deprecated, not removed
+        if let Some(found) = find_deprecated_manifest(scoop_path, &package.name) {
+            located = Ok(found);
+        }
+    }
+
+    match located {
         Ok((manifest_path, _)) => {
-            match fs::read_to_string(manifest_path) {
+            match fs::read_to_string(&manifest_path) {
                 Ok(content) => {
-                    match serde_json::from_str::<Manifest>(&content) {
-                        Ok(manifest) => {
+                    let parsed = serde_json::from_str::<Value>(&content).ok().and_then(|json| {
+                        serde_json::from_value::<Manifest>(json.clone())
+                            .ok()
+                            .map(|manifest| (json, manifest))
+                    });
+                    match parsed {
+                        Some((json, manifest)) => {
                             latest_version = Some(manifest.version.clone());
                             // Check if package is outdated
-                            if package.version != manifest.version {
+                            if is_update_available(&package.version, &manifest.version) {
                                 is_outdated = true;
                             }
-                            if manifest.deprecated.is_some() {
+                            let deprecation = detect_deprecation(&json, &manifest_path, &package.name);
+                            if deprecation.is_deprecated {
                                 is_deprecated = true;
-                                info.push("Deprecated".to_string());
+                                match &deprecation.replacement {
+                                    Some(successor) => info.push(format!("Deprecated, replaced by {}", successor)),
+                                    None => info.push("Deprecated".to_string()),
+                                }
+                                replacement = deprecation.replacement;
                             }
                         }
-                        Err(_) => {
+                        None => {
                             is_failed = true;
                             info.push("Install failed".to_string());
                         }
@@ -176,23 +205,32 @@ fn get_app_status(
         is_held,
         is_deprecated,
         is_removed,
+        replacement,
     }))
 }
 
-/// Get all local bucket directories
+/// Get the local bucket directories that track a git remote
 fn get_local_buckets(scoop_path: &Path) -> Vec<PathBuf> {
-    let buckets_dir = scoop_path.join("buckets");
-    let mut buckets = Vec::new();
+    crate::commands::bucket::list_local_buckets(scoop_path)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|bucket| bucket.git_url.is_some())
+        .map(|bucket| PathBuf::from(bucket.path))
+        .collect()
+}
 
-    if let Ok(entries) = fs::read_dir(buckets_dir) {
-        for entry in entries.flatten() {
-            if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
-                buckets.push(entry.path());
-            }
+/// Counts the git buckets whose remote is ahead, and reports whether Scoop itself is
+/// behind. Fails when a remote cannot be reached.
+pub fn count_stale_repos(scoop_path: &Path) -> Result<(usize, bool), String> {
+    let scoop_current_dir = scoop_path.join("apps").join("scoop").join("current");
+    let scoop_behind = scoop_current_dir.exists() && test_update_status(&scoop_current_dir)?;
+    let mut buckets_behind = 0;
+    for bucket_path in get_local_buckets(scoop_path) {
+        if test_update_status(&bucket_path)? {
+            buckets_behind += 1;
         }
     }
-
-    buckets
+    Ok((buckets_behind, scoop_behind))
 }
 
 /// Main command to check scoop status
@@ -204,6 +242,7 @@ pub async fn check_scoop_status<R: Runtime>(
     log::info!("Checking scoop status");
 
     let scoop_path = state.scoop_path();
+    let app_version = app.package_info().version.to_string();
     let mut scoop_needs_update = false;
     let mut bucket_needs_update = false;
     let mut network_failure = false;
@@ -253,7 +292,7 @@ pub async fn check_scoop_status<R: Runtime>(
     }
 
     // Get installed packages and check their status
-    let installed_packages = get_installed_packages_full(app.clone(), state.clone()).await?;
+    let installed_packages = get_installed_packages(app.clone(), state.clone()).await?;
 
     // Get held packages for efficient lookup
     let held_packages: HashSet<String> =
@@ -280,11 +319,101 @@ pub async fn check_scoop_status<R: Runtime>(
         && !network_failure
         && apps_with_issues.is_empty();
 
+    let self_update = if crate::utils::is_scoop_installation() {
+        std::env::current_exe().ok().and_then(|exe| {
+            crate::commands::app_info::find_self_update(&exe, &scoop_path, &app_version)
+        })
+    } else {
+        None
+    };
+
     Ok(ScoopStatus {
         scoop_needs_update,
         bucket_needs_update,
         network_failure,
         apps_with_issues,
         is_everything_ok,
+        self_update,
+        scoop_core: Some(crate::commands::scoop_core::core_info(&scoop_path)),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScoopFixture;
+    use serde_json::json;
+
+    fn installed(name: &str, version: &str, source: &str) -> InstalledPackage {
+        InstalledPackage {
+            name: name.to_string(),
+            version: version.to_string(),
+            source: source.to_string(),
+            is_installed: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn up_to_date_packages_have_no_status() {
+        let fixture = ScoopFixture::new();
+        fixture
+            .bucket_manifest("main", "git", "2.44.0")
+            .install("git", "2.44.0", "main");
+
+        let package = installed("git", "2.44.0", "main");
+        let status = get_app_status(fixture.root(), &package, &HashSet::new());
+        assert!(status.unwrap().is_none());
+    }
+
+    #[test]
+    fn reports_outdated_and_held_packages() {
+        let fixture = ScoopFixture::new();
+        fixture
+            .bucket_manifest("main", "git", "2.45.0")
+            .install("git", "2.44.0", "main");
+        let held = HashSet::from(["git".to_string()]);
+
+        let status = get_app_status(fixture.root(), &installed("git", "2.44.0", "main"), &held)
+            .unwrap()
+            .unwrap();
+        assert!(status.is_outdated);
+        assert!(status.is_held);
+        assert_eq!(status.latest_version.as_deref(), Some("2.45.0"));
+    }
+
+    #[test]
+    fn reports_deprecated_manifests_with_replacement() {
+        let fixture = ScoopFixture::new();
+        fixture
+            .bucket_manifest_json(
+                "extras",
+                "oldtool",
+                json!({ "version": "1.0", "deprecated": "Use newtool instead" }),
+            )
+            .install("oldtool", "1.0", "extras");
+
+        let package = installed("oldtool", "1.0", "extras");
+        let status = get_app_status(fixture.root(), &package, &HashSet::new())
+            .unwrap()
+            .unwrap();
+        assert!(status.is_deprecated);
+        assert!(!status.is_outdated);
+        assert_eq!(status.replacement.as_deref(), Some("newtool"));
+    }
+
+    #[test]
+    fn skips_versioned_installs() {
+        let fixture = ScoopFixture::new();
+        fixture.bucket_manifest("main", "python", "3.12.1");
+        fixture.app_version("python", "3.10.11", None);
+        let package = InstalledPackage {
+            is_versioned_install: true,
+            ..installed("python", "3.10.11", "main")
+        };
+
+        assert!(get_app_status(fixture.root(), &package, &HashSet::new())
+            .unwrap()
+            .is_none());
+    }
+}
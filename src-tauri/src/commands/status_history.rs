@@ -0,0 +1,261 @@
+//! Periodic snapshots of how far behind the Scoop installation is.
+//!
+//! The scheduler records the number of outdated apps and stale buckets at a configurable
+//! interval. The dashboard reads the history as a trend and shows a reminder once
+//! updates have piled up or stayed pending for too long.
+use crate::commands::settings;
+use crate::commands::status::count_stale_repos;
+use crate::commands::updates::check_for_updates;
+use crate::config_keys::{STATUS_NAG_OUTDATED_COUNT, STATUS_NAG_PENDING_DAYS};
+use crate::state::AppState;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+const STATUS_HISTORY_FILE: &str = "status_history.json";
+/// Roughly a year of daily snapshots.
+const MAX_SNAPSHOTS: usize = 400;
+const DEFAULT_NAG_OUTDATED_COUNT: u64 = 10;
+const DEFAULT_NAG_PENDING_DAYS: u64 = 14;
+
+/// Emitted with the new [`StatusSnapshot`] after each snapshot.
+pub const EVENT_STATUS_SNAPSHOT: &str = "status-snapshot-taken";
+
+/// One point in the update history.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StatusSnapshot {
+    /// RFC 3339 time the snapshot was taken.
+    pub timestamp: String,
+    pub outdated_count: usize,
+    pub outdated: Vec<String>,
+    pub buckets_behind: usize,
+    pub scoop_behind: bool,
+    /// Set when a remote could not be reached, so the bucket counts are unknown.
+    pub network_failure: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct StatusTrend {
+    /// Snapshots in the requested window, oldest first.
+    pub snapshots: Vec<StatusSnapshot>,
+    pub latest: Option<StatusSnapshot>,
+    /// Change in outdated apps from the first to the last snapshot in the window.
+    pub outdated_delta: i64,
+    /// Start of the current run of snapshots with pending updates.
+    pub pending_since: Option<String>,
+    /// Set when a reminder threshold is exceeded.
+    pub nag: bool,
+    pub nag_reason: Option<String>,
+}
+
+fn history_path() -> Option<PathBuf> {
+    crate::portable::app_data_dir().map(|dir| dir.join(STATUS_HISTORY_FILE))
+}
+
+/// Reads all snapshots, oldest first.
+pub fn read_history() -> Vec<StatusSnapshot> {
+    history_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn append_snapshot(snapshot: StatusSnapshot) {
+    let Some(path) = history_path() else {
+        log::warn!("Could not resolve status history path");
+        return;
+    };
+    let mut history = read_history();
+    history.push(snapshot);
+    if history.len() > MAX_SNAPSHOTS {
+        let excess = history.len() - MAX_SNAPSHOTS;
+        history.drain(..excess);
+    }
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    match serde_json::to_string_pretty(&history) {
+        Ok(content) => {
+            if let Err(e) = fs::write(&path, content) {
+                log::warn!("Failed to write status history: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Failed to serialize status history: {}", e),
+    }
+}
+
+/// When the most recent snapshot was taken.
+pub fn last_snapshot_time() -> Option<DateTime<Utc>> {
+    read_history()
+        .last()
+        .and_then(|s| DateTime::parse_from_rfc3339(&s.timestamp).ok())
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Runs the update check and the bucket check, then records the result.
+pub async fn take_snapshot<R: Runtime>(app: &AppHandle<R>) -> Result<StatusSnapshot, String> {
+    let state = app.state::<AppState>();
+    let mut outdated: Vec<String> = check_for_updates(app.clone(), state.clone())
+        .await?
+        .into_iter()
+        .map(|p| p.name)
+        .collect();
+    outdated.sort();
+
+    let scoop_path = state.scoop_path();
+    let repos = tokio::task::spawn_blocking(move || count_stale_repos(&scoop_path))
+        .await
+        .map_err(|e| e.to_string())?;
+    let (buckets_behind, scoop_behind, network_failure) = match repos {
+        Ok((buckets, scoop)) => (buckets, scoop, false),
+        Err(e) => {
+            log::warn!("Could not check buckets for the status snapshot: {}", e);
+            (0, false, true)
+        }
+    };
+
+    let snapshot = StatusSnapshot {
+        timestamp: Utc::now().to_rfc3339(),
+        outdated_count: outdated.len(),
+        outdated,
+        buckets_behind,
+        scoop_behind,
+        network_failure,
+    };
+    log::info!(
+        "Status snapshot: {} outdated apps, {} buckets behind",
+        snapshot.outdated_count,
+        snapshot.buckets_behind
+    );
+    append_snapshot(snapshot.clone());
+    if let Err(e) = app.emit(EVENT_STATUS_SNAPSHOT, &snapshot) {
+        log::warn!("Failed to emit {} event: {}", EVENT_STATUS_SNAPSHOT, e);
+    }
+    Ok(snapshot)
+}
+
+fn build_trend(
+    history: Vec<StatusSnapshot>,
+    since: DateTime<Utc>,
+    nag_count: u64,
+    nag_days: u64,
+    now: DateTime<Utc>,
+) -> StatusTrend {
+    let parse = |s: &StatusSnapshot| DateTime::parse_from_rfc3339(&s.timestamp).ok();
+
+    // The pending streak can start before the requested window
+    let pending_since = history
+        .iter()
+        .rev()
+        .take_while(|s| s.outdated_count > 0)
+        .last()
+        .map(|s| s.timestamp.clone());
+
+    let snapshots: Vec<StatusSnapshot> = history
+        .into_iter()
+        .filter(|s| parse(s).is_some_and(|t| t >= since))
+        .collect();
+    let latest = snapshots.last().cloned();
+    let outdated_delta = match (snapshots.first(), &latest) {
+        (Some(first), Some(last)) => last.outdated_count as i64 - first.outdated_count as i64,
+        _ => 0,
+    };
+
+    let pending_days = pending_since
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| (now - t.with_timezone(&Utc)).num_days().max(0) as u64)
+        .unwrap_or(0);
+    let nag_reason = match &latest {
+        Some(s) if nag_count > 0 && s.outdated_count as u64 >= nag_count => {
+            Some(format!("{} apps have updates pending", s.outdated_count))
+        }
+        Some(_) if nag_days > 0 && pending_days >= nag_days => Some(format!(
+            "Updates have been pending for {} days",
+            pending_days
+        )),
+        _ => None,
+    };
+
+    StatusTrend {
+        snapshots,
+        latest,
+        outdated_delta,
+        pending_since,
+        nag: nag_reason.is_some(),
+        nag_reason,
+    }
+}
+
+fn config_u64<R: Runtime>(app: &AppHandle<R>, key: &str, default: u64) -> u64 {
+    settings::get_config_value(app.clone(), key.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_u64())
+        .unwrap_or(default)
+}
+
+/// Takes a snapshot now, outside the schedule.
+#[tauri::command]
+pub async fn take_status_snapshot<R: Runtime>(app: AppHandle<R>) -> Result<StatusSnapshot, String> {
+    take_snapshot(&app).await
+}
+
+/// Returns the snapshots of the last `days` days (30 by default) with reminder state.
+#[tauri::command]
+pub fn get_status_trend<R: Runtime>(app: AppHandle<R>, days: Option<u32>) -> StatusTrend {
+    let now = Utc::now();
+    build_trend(
+        read_history(),
+        now - Duration::days(days.unwrap_or(30) as i64),
+        config_u64(&app, STATUS_NAG_OUTDATED_COUNT, DEFAULT_NAG_OUTDATED_COUNT),
+        config_u64(&app, STATUS_NAG_PENDING_DAYS, DEFAULT_NAG_PENDING_DAYS),
+        now,
+    )
+}
+
+#[tauri::command]
+pub fn clear_status_history() -> Result<(), String> {
+    match history_path() {
+        Some(path) if path.exists() => fs::remove_file(path).map_err(|e| e.to_string()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(days_ago: i64, outdated_count: usize, now: DateTime<Utc>) -> StatusSnapshot {
+        StatusSnapshot {
+            timestamp: (now - Duration::days(days_ago)).to_rfc3339(),
+            outdated_count,
+            outdated: Vec::new(),
+            buckets_behind: 0,
+            scoop_behind: false,
+            network_failure: false,
+        }
+    }
+
+    #[test]
+    fn tracks_pending_streak_and_nags() {
+        let now = Utc::now();
+        let history = vec![
+            snapshot(40, 0, now),
+            snapshot(20, 2, now),
+            snapshot(5, 3, now),
+            snapshot(1, 4, now),
+        ];
+
+        let trend = build_trend(history.clone(), now - Duration::days(7), 10, 14, now);
+        assert_eq!(trend.snapshots.len(), 2);
+        assert_eq!(trend.outdated_delta, 1);
+        assert_eq!(trend.pending_since, Some(history[1].timestamp.clone()));
+        assert!(trend.nag);
+
+        let trend = build_trend(history, now - Duration::days(7), 10, 30, now);
+        assert!(!trend.nag);
+    }
+}
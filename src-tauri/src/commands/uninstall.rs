@@ -1,12 +1,20 @@
 //! Commands for uninstalling packages and clearing the cache.
 use crate::commands::auto_cleanup::trigger_auto_cleanup;
 use crate::commands::installed::invalidate_installed_cache;
+use crate::commands::resolver::resolve_installed_package;
 use crate::commands::scoop::{self, ScoopOp};
 use crate::commands::search::invalidate_manifest_cache;
+use crate::commands::uninstall_verification::verify_uninstall;
+use crate::engine::dependencies::{dependents_of, installed_dependency_graph, plan_uninstall};
+use crate::models::{BatchUninstallProgress, BatchUninstallResult, SkippedUninstall};
 use crate::state::AppState;
-use tauri::{AppHandle, State, Window};
+use std::collections::HashSet;
+use tauri::{AppHandle, Emitter, State, Window};
 
-/// Uninstalls a Scoop package.
+/// Emitted with a [`BatchUninstallProgress`] as `uninstall_packages` works through its list.
+pub const EVENT_BATCH_UNINSTALL_PROGRESS: &str = "batch-uninstall-progress";
+
+/// Uninstalls a Scoop package, then checks that it left nothing behind.
 ///
 /// Note: The `bucket` parameter is not used by the underlying `scoop uninstall` command
 /// but is included for API consistency and logging purposes.
@@ -23,7 +31,8 @@ pub async fn uninstall_package(
     package_name: String,
     bucket: String,
 ) -> Result<(), String> {
-    execute_package_operation(
+    let package_name = resolve_installed_package(&state.scoop_path(), &package_name)?;
+    let operation_id = execute_package_operation(
         window.clone(),
         ScoopOp::Uninstall,
         &package_name,
@@ -32,6 +41,7 @@ pub async fn uninstall_package(
     .await?;
     invalidate_manifest_cache().await;
     invalidate_installed_cache(state.clone()).await;
+    verify_uninstall(&app, &package_name, Some(operation_id)).await;
 
     // Trigger auto cleanup after uninstall
     trigger_auto_cleanup(app, state).await;
@@ -39,6 +49,103 @@ pub async fn uninstall_package(
     Ok(())
 }
 
+/// Uninstalls several packages, removing dependents before their dependencies.
+///
+/// Packages that an app staying installed still depends on are skipped, as are
+/// dependencies of a package whose uninstall failed. Progress is emitted as
+/// [`EVENT_BATCH_UNINSTALL_PROGRESS`]; each uninstall also streams its own output.
+#[tauri::command]
+pub async fn uninstall_packages(
+    window: Window,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    package_names: Vec<String>,
+) -> Result<BatchUninstallResult, String> {
+    let scoop_path = state.scoop_path();
+    let mut result = BatchUninstallResult::default();
+
+    let mut requested = Vec::new();
+    for name in &package_names {
+        match resolve_installed_package(&scoop_path, name) {
+            Ok(resolved) => requested.push(resolved),
+            Err(e) => result.failed.push((name.clone(), e)),
+        }
+    }
+
+    let graph = {
+        let scoop_path = scoop_path.clone();
+        tokio::task::spawn_blocking(move || installed_dependency_graph(&scoop_path))
+            .await
+            .map_err(|e| e.to_string())?
+    };
+    let plan = plan_uninstall(&graph, &requested);
+    log::info!(
+        "Batch uninstall of {} packages: order {:?}, skipping {}",
+        requested.len(),
+        plan.order,
+        plan.skipped.len()
+    );
+    result.skipped = plan.skipped;
+
+    let total = plan.order.len();
+    let emit = |package: &str, index: usize, status: &str, message: Option<String>| {
+        let progress = BatchUninstallProgress {
+            package: package.to_string(),
+            index,
+            total,
+            status: status.to_string(),
+            message,
+        };
+        if let Err(e) = window.emit(EVENT_BATCH_UNINSTALL_PROGRESS, progress) {
+            log::warn!("Failed to emit {} event: {}", EVENT_BATCH_UNINSTALL_PROGRESS, e);
+        }
+    };
+
+    // Packages left installed after a failure keep their dependencies in place
+    let mut kept: HashSet<String> = HashSet::new();
+    for (i, package) in plan.order.iter().enumerate() {
+        let index = i + 1;
+        let required_by = dependents_of(&graph, package, &kept);
+        if !required_by.is_empty() {
+            emit(
+                package,
+                index,
+                "skipped",
+                Some(format!("Still required by {}", required_by.join(", "))),
+            );
+            kept.insert(package.to_lowercase());
+            result.skipped.push(SkippedUninstall {
+                package: package.clone(),
+                required_by,
+            });
+            continue;
+        }
+
+        emit(package, index, "started", None);
+        match execute_package_operation(window.clone(), ScoopOp::Uninstall, package, None).await {
+            Ok(operation_id) => {
+                emit(package, index, "succeeded", None);
+                verify_uninstall(&app, package, Some(operation_id)).await;
+                result.uninstalled.push(package.clone());
+            }
+            Err(e) => {
+                log::warn!("Batch uninstall of '{}' failed: {}", package, e);
+                emit(package, index, "failed", Some(e.clone()));
+                kept.insert(package.to_lowercase());
+                result.failed.push((package.clone(), e));
+            }
+        }
+    }
+
+    if !result.uninstalled.is_empty() {
+        invalidate_manifest_cache().await;
+        invalidate_installed_cache(state.clone()).await;
+        trigger_auto_cleanup(app, state).await;
+    }
+
+    Ok(result)
+}
+
 /// Clears the cache for a Scoop package.
 ///
 /// Note: The `bucket` parameter is not used by the underlying `scoop cache rm` command
@@ -73,17 +180,18 @@ pub async fn clear_package_cache(
 /// A helper function to execute a Scoop operation on a package.
 ///
 /// This function handles the common logic for parsing the bucket, logging the operation,
-/// and calling the underlying `execute_scoop` function.
+/// and calling the underlying `execute_scoop` function. Returns the operation id.
 async fn execute_package_operation(
     window: Window,
     op: ScoopOp,
     package: &str,
     bucket: Option<&str>,
-) -> Result<(), String> {
+) -> Result<String, String> {
     log::info!(
         "Executing {} for package '{}' from bucket '{}'",
         match op {
             ScoopOp::Install => "installing",
+            ScoopOp::InstallManifest => "installing from manifest",
             ScoopOp::Uninstall => "uninstalling",
             ScoopOp::Update => "updating",
             ScoopOp::UpdateForce => "force updating",
@@ -94,15 +202,17 @@ async fn execute_package_operation(
         bucket.unwrap_or("default")
     );
 
-    let operation_id = Some(format!("{}-{}-{}", match op {
+    let operation_id = format!("{}-{}-{}", match op {
         ScoopOp::Install => "install",
+        ScoopOp::InstallManifest => "install-manifest",
         ScoopOp::Uninstall => "uninstall",
         ScoopOp::Update => "update",
         ScoopOp::UpdateForce => "force-update",
         ScoopOp::ClearCache => "clear-cache",
         ScoopOp::UpdateAll => "update-all",
-    }, package, std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs()));
+    }, package, std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs());
 
     // Pass the bucket option along; `execute_scoop` will handle whether it's used.
-    scoop::execute_scoop(window, op, Some(package), bucket, operation_id).await
+    scoop::execute_scoop(window, op, Some(package), bucket, Some(operation_id.clone())).await?;
+    Ok(operation_id)
 }
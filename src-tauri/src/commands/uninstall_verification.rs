@@ -0,0 +1,351 @@
+//! Checking that an uninstall left nothing behind.
+//!
+//! `scoop uninstall` regularly leaves residue: an app directory it could not delete
+//! because a file was in use, shims pointing into the removed app, Start Menu shortcuts,
+//! or `PATH` entries and variables added by `env_add_path`/`env_set`. After an uninstall
+//! these are looked for, the result is stored in the operation's history entry and
+//! [`EVENT_UNINSTALL_LEFTOVERS`] is emitted when something remains, which
+//! [`clean_uninstall_leftovers`] removes. The `persist` directory is kept by Scoop on
+//! purpose and is not checked.
+use crate::commands::env_changes::{self, broadcast_change, write_value};
+use crate::commands::installed::invalidate_installed_cache;
+use crate::commands::operation_log::record_uninstall_verification;
+use crate::state::AppState;
+use crate::utils::{get_scoop_app_shortcuts_with_path, ScoopAppShortcut, ShortcutScope};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Emitted with the [`UninstallVerification`] when an uninstall left something behind.
+pub const EVENT_UNINSTALL_LEFTOVERS: &str = "uninstall-leftovers";
+
+/// Shim files that can reference the app they launch; the `.exe` next to a `.shim` cannot.
+const SHIM_TEXT_EXTENSIONS: &[&str] = &["shim", "cmd", "ps1", ""];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UninstallVerification {
+    pub package: String,
+    /// The uninstall operation the verification belongs to.
+    pub operation_id: Option<String>,
+    pub checked_at: String,
+    pub clean: bool,
+    /// The app directory, when it still exists.
+    pub app_dir: Option<String>,
+    pub shims: Vec<String>,
+    pub shortcuts: Vec<String>,
+    /// User `PATH` entries pointing into the app directory.
+    pub path_entries: Vec<String>,
+    /// User variables whose value points into the app directory.
+    pub variables: Vec<String>,
+}
+
+/// Tells whether `text` mentions `dir` itself or a path inside it.
+fn references_dir(text: &str, dir: &Path) -> bool {
+    let normalize = |s: &str| s.replace('/', "\\").to_lowercase();
+    let text = normalize(text);
+    let needle = normalize(&dir.to_string_lossy());
+    let needle = needle.trim_end_matches('\\');
+    text.match_indices(needle).any(|(start, _)| {
+        text[start + needle.len()..]
+            .chars()
+            .next()
+            .is_none_or(|c| matches!(c, '\\' | '"' | '\'' | ';' | ' '))
+    })
+}
+
+/// Shim files in `shims` that launch something inside `app_dir`, with their `.exe`.
+fn find_leftover_shims(shims: &Path, app_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(shims) else {
+        return Vec::new();
+    };
+    let mut leftovers = Vec::new();
+    for path in entries.flatten().map(|e| e.path()).filter(|p| p.is_file()) {
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if !SHIM_TEXT_EXTENSIONS.contains(&ext.as_str()) {
+            continue;
+        }
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        if !references_dir(&content, app_dir) {
+            continue;
+        }
+        if ext == "shim" {
+            let exe = path.with_extension("exe");
+            if exe.is_file() {
+                leftovers.push(exe);
+            }
+        }
+        leftovers.push(path);
+    }
+    leftovers.sort();
+    leftovers
+}
+
+/// Looks for what remains of `package` after it was uninstalled.
+///
+/// Only per-user shortcuts and variables are considered, as the uninstall was not global.
+pub fn find_leftovers(
+    package: &str,
+    apps: &Path,
+    shims: &Path,
+    user_env: &BTreeMap<String, String>,
+    shortcuts: &[ScoopAppShortcut],
+) -> UninstallVerification {
+    let app_dir = apps.join(package);
+    let display = |paths: Vec<PathBuf>| -> Vec<String> {
+        paths.iter().map(|p| p.display().to_string()).collect()
+    };
+
+    let mut path_entries = Vec::new();
+    let mut variables = Vec::new();
+    for (name, value) in user_env {
+        if name.eq_ignore_ascii_case("Path") {
+            path_entries.extend(
+                value
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|entry| !entry.is_empty() && references_dir(entry, &app_dir))
+                    .map(String::from),
+            );
+        } else if references_dir(value, &app_dir) {
+            variables.push(name.clone());
+        }
+    }
+
+    let mut verification = UninstallVerification {
+        package: package.to_string(),
+        operation_id: None,
+        checked_at: chrono::Utc::now().to_rfc3339(),
+        clean: false,
+        app_dir: app_dir.exists().then(|| app_dir.display().to_string()),
+        shims: display(find_leftover_shims(shims, &app_dir)),
+        shortcuts: display(
+            shortcuts
+                .iter()
+                .filter(|s| s.scope == ShortcutScope::User)
+                .filter(|s| references_dir(&s.target_path, &app_dir))
+                .map(|s| s.path.clone())
+                .collect(),
+        ),
+        path_entries,
+        variables,
+    };
+    verification.clean = verification.app_dir.is_none()
+        && verification.shims.is_empty()
+        && verification.shortcuts.is_empty()
+        && verification.path_entries.is_empty()
+        && verification.variables.is_empty();
+    verification
+}
+
+async fn scan_leftovers<R: Runtime>(app: &AppHandle<R>, package: &str) -> UninstallVerification {
+    let state = app.state::<AppState>();
+    let scoop_path = state.scoop_path();
+    let dirs = state.scoop_dirs();
+    let package = package.to_string();
+    tokio::task::spawn_blocking(move || {
+        let shortcuts = get_scoop_app_shortcuts_with_path(&scoop_path).unwrap_or_default();
+        find_leftovers(
+            &package,
+            &dirs.apps,
+            &dirs.shims,
+            &env_changes::capture().user,
+            &shortcuts,
+        )
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Checks what an uninstall left behind and records it in the operation history.
+pub async fn verify_uninstall<R: Runtime>(
+    app: &AppHandle<R>,
+    package: &str,
+    operation_id: Option<String>,
+) -> UninstallVerification {
+    let mut verification = scan_leftovers(app, package).await;
+    verification.operation_id = operation_id;
+
+    if let Some(id) = &verification.operation_id {
+        record_uninstall_verification(id, &verification);
+    }
+    if verification.clean {
+        log::info!("Uninstall of '{}' left nothing behind", package);
+    } else {
+        log::warn!(
+            "Uninstall of '{}' left leftovers: app dir {}, {} shims, {} shortcuts, {} PATH entries, {} variables",
+            package,
+            verification.app_dir.is_some(),
+            verification.shims.len(),
+            verification.shortcuts.len(),
+            verification.path_entries.len(),
+            verification.variables.len()
+        );
+        if let Err(e) = app.emit(EVENT_UNINSTALL_LEFTOVERS, &verification) {
+            log::warn!("Failed to emit {} event: {}", EVENT_UNINSTALL_LEFTOVERS, e);
+        }
+    }
+    verification
+}
+
+/// Whether `app_dir` still holds an installation: a `current` link or a version with an
+/// `install.json`, as a reinstall since the verification would leave.
+fn is_installed(app_dir: &Path) -> bool {
+    fs::symlink_metadata(app_dir.join("current")).is_ok()
+        || fs::read_dir(app_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .any(|entry| entry.path().join("install.json").is_file())
+}
+
+/// Deletes the leftovers, returning an error message for each one that could not be.
+fn remove_leftovers(verification: &UninstallVerification) -> Vec<String> {
+    let mut errors = Vec::new();
+    if let Some(app_dir) = &verification.app_dir {
+        if let Err(e) = fs::remove_dir_all(app_dir) {
+            errors.push(format!("{}: {}", app_dir, e));
+        }
+    }
+    for file in verification.shims.iter().chain(&verification.shortcuts) {
+        if let Err(e) = fs::remove_file(file) {
+            errors.push(format!("{}: {}", file, e));
+        }
+    }
+
+    let mut env_changed = false;
+    if !verification.path_entries.is_empty() {
+        let user = env_changes::capture().user;
+        if let Some((name, value)) = user.iter().find(|(n, _)| n.eq_ignore_ascii_case("Path")) {
+            let kept: Vec<&str> = value
+                .split(';')
+                .filter(|entry| !verification.path_entries.iter().any(|e| e == entry.trim()))
+                .collect();
+            match write_value("user", name, Some(&kept.join(";"))) {
+                Ok(()) => env_changed = true,
+                Err(e) => errors.push(format!("PATH: {}", e)),
+            }
+        }
+    }
+    for name in &verification.variables {
+        match write_value("user", name, None) {
+            Ok(()) => env_changed = true,
+            Err(e) => errors.push(format!("{}: {}", name, e)),
+        }
+    }
+    if env_changed {
+        broadcast_change();
+    }
+    errors
+}
+
+/// Force-removes what the uninstall of `package_name` left behind.
+///
+/// The leftovers are looked up again rather than taken from the frontend, and the new
+/// verification replaces the one in the history entry of `operation_id`.
+#[tauri::command]
+pub async fn clean_uninstall_leftovers(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    package_name: String,
+    operation_id: Option<String>,
+) -> Result<UninstallVerification, String> {
+    crate::utils::validate_dir_name("package", &package_name)?;
+    if package_name.eq_ignore_ascii_case("scoop") {
+        return Err(format!("Invalid package name '{}'", package_name));
+    }
+
+    let leftovers = {
+        // Don't remove files while a Scoop operation may be reinstalling the package
+        let _queue = state.operation_queue.lock().await;
+        if is_installed(&state.scoop_dirs().apps.join(&package_name)) {
+            return Err(format!(
+                "Package '{}' is installed again, its files are not leftovers",
+                package_name
+            ));
+        }
+        let leftovers = scan_leftovers(&app, &package_name).await;
+        let to_remove = leftovers.clone();
+        let errors = tokio::task::spawn_blocking(move || remove_leftovers(&to_remove))
+            .await
+            .map_err(|e| format!("Failed to remove leftovers: {}", e))?;
+        for error in &errors {
+            log::warn!("Could not remove leftover of '{}': {}", package_name, error);
+        }
+        leftovers
+    };
+    log::info!("Cleaned leftovers of '{}'", package_name);
+    if leftovers.app_dir.is_some() {
+        invalidate_installed_cache(state).await;
+    }
+
+    let verification = verify_uninstall(&app, &package_name, operation_id).await;
+    Ok(verification)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, ScoopFixture};
+
+    #[test]
+    fn finds_what_an_uninstall_left_behind() {
+        let fixture = ScoopFixture::new();
+        let apps = fixture.apps_dir();
+        let shims = fixture.shims_dir();
+        fixture.install("git", "2.44.0", "main");
+        fixture.install("jdk", "21.0.2", "java");
+        fixture.shim("git", &apps.join("git").join("current").join("git.exe"));
+        fixture.shim(
+            "java",
+            &apps
+                .join("jdk")
+                .join("current")
+                .join("bin")
+                .join("java.exe"),
+        );
+        fixture.shim("jdkx", &apps.join("jdkx").join("current").join("jdkx.exe"));
+
+        let jdk_dir = apps.join("jdk").join("current");
+        let user_env = BTreeMap::from([
+            (
+                "Path".to_string(),
+                format!(r"C:\Windows;{}\bin", jdk_dir.display()),
+            ),
+            ("JAVA_HOME".to_string(), jdk_dir.display().to_string()),
+            ("EDITOR".to_string(), "notepad".to_string()),
+        ]);
+
+        let git = find_leftovers("git", &apps, &shims, &BTreeMap::new(), &[]);
+        assert!(git.app_dir.is_some());
+        assert_eq!(git.shims.len(), 2);
+        assert!(!git.clean);
+
+        fs::remove_dir_all(apps.join("jdk")).unwrap();
+        let jdk = find_leftovers("jdk", &apps, &shims, &user_env, &[]);
+        assert!(jdk.app_dir.is_none());
+        assert_eq!(jdk.shims.len(), 2);
+        assert!(jdk.shims.iter().all(|s| s.contains("java.")));
+        assert_eq!(jdk.path_entries.len(), 1);
+        assert_eq!(jdk.variables, vec!["JAVA_HOME"]);
+
+        let vlc = find_leftovers("vlc", &apps, &shims, &user_env, &[]);
+        assert!(vlc.clean);
+    }
+
+    #[test]
+    fn reinstalled_packages_are_not_leftovers() {
+        let fixture = ScoopFixture::new();
+        fixture.install("git", "2.44.0", "main");
+        assert!(is_installed(&fixture.app_dir("git")));
+
+        write_file(&fixture.app_dir("vlc").join("3.0.20").join("vlc.exe"), b"");
+        assert!(!is_installed(&fixture.app_dir("vlc")));
+        assert!(!is_installed(&fixture.app_dir("missing")));
+    }
+}
@@ -1,9 +1,26 @@
 use crate::commands::auto_cleanup::trigger_auto_cleanup;
+use crate::commands::delta_update::prepare_delta_updates;
+use crate::commands::external_scoop::guard_external_scoop;
+use crate::commands::hooks::{self, HookContext, HookEvent};
+use crate::commands::info::get_installed_version;
+use crate::commands::install_preflight::check_system_installer;
+use crate::commands::package_source::{get_installed_source, get_pinned_sources};
+use crate::commands::package_state::{update_all_targets, update_package_state};
+use crate::commands::powershell::fail_unstarted_operation;
 use crate::commands::scoop::{self, ScoopOp};
+use crate::commands::update_log;
+use crate::commands::version_pin::{hold_pinned_packages, release_pin_holds};
+use crate::models::PackageActivity;
 use crate::state::AppState;
+use std::collections::HashMap;
+use std::time::Instant;
 use tauri::{AppHandle, State, Window};
 
 /// Updates a specific Scoop package.
+///
+/// Like installs, updates that run a system installer are refused while Windows
+/// Installer is busy or a reboot is pending, unless `skip_installer_checks` is set.
+/// The operation runs under `operation_id` when given, for `wait_for_operation`.
 #[tauri::command]
 pub async fn update_package(
     window: Window,
@@ -11,6 +28,8 @@ pub async fn update_package(
     state: State<'_, AppState>,
     package_name: String,
     force: Option<bool>,
+    skip_installer_checks: Option<bool>,
+    operation_id: Option<String>,
 ) -> Result<(), String> {
     log::info!("Updating package '{}'", package_name);
     let op = if force.unwrap_or(false) {
@@ -20,14 +39,47 @@ pub async fn update_package(
         ScoopOp::Update
     };
     
-    let operation_id = if force.unwrap_or(false) {
+    let operation_id = operation_id.or_else(|| if force.unwrap_or(false) {
         Some(format!("force-update-{}-{}", package_name, std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs()))
     } else {
         Some(format!("update-{}-{}", package_name, std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs()))
-    };
-    
+    });
+    let reject = |e: String| fail_unstarted_operation(operation_id.as_deref(), e);
+
+    // `scoop update` always uses the bucket recorded at install time, so a pin to a
+    // different bucket has to be applied by switching the package's bucket first.
+    if let Some(pinned) = get_pinned_sources(&app).get(&package_name) {
+        let installed = get_installed_source(&state.scoop_path(), &package_name);
+        if installed.as_deref().map(|b| !b.eq_ignore_ascii_case(pinned)).unwrap_or(false) {
+            return Err(reject(format!(
+                "Package '{}' is pinned to bucket '{}' but installed from '{}'. Change its bucket before updating.",
+                package_name,
+                pinned,
+                installed.unwrap_or_default()
+            )));
+        }
+    }
+
+    if !skip_installer_checks.unwrap_or(false) {
+        let installed = get_installed_source(&state.scoop_path(), &package_name);
+        check_system_installer(&state.scoop_path(), &package_name, installed.as_deref())
+            .map_err(reject)?;
+    }
+
+    let scoop_path = state.scoop_path();
+    let before: HashMap<String, String> = get_installed_version(&scoop_path, &package_name)
+        .map(|v| HashMap::from([(package_name.clone(), v)]))
+        .unwrap_or_default();
+    let started = Instant::now();
+
+    prepare_delta_updates(&app, &scoop_path, Some(vec![package_name.clone()])).await;
     scoop::execute_scoop(window, op, Some(&package_name), None, operation_id).await?;
 
+    let after: HashMap<String, String> = get_installed_version(&scoop_path, &package_name)
+        .map(|v| HashMap::from([(package_name.clone(), v)]))
+        .unwrap_or_default();
+    update_log::record_version_changes(&before, &after, started.elapsed().as_millis() as u64);
+
     // Trigger auto cleanup after update
     trigger_auto_cleanup(app, state).await;
 
@@ -45,8 +97,21 @@ pub async fn update_all_packages(
     
     let operation_id = Some(format!("update-all-{}", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs()));
     
+    let scoop_path = state.scoop_path();
+    let before = update_log::snapshot_installed_versions(&scoop_path);
+    let started = Instant::now();
+
+    // Pinned packages are held for the run so they stay at their pinned version
+    let pin_holds = hold_pinned_packages(&app, &scoop_path);
+    prepare_delta_updates(&app, &scoop_path, None).await;
+
     // Execute the update through window streaming
     let result = scoop::execute_scoop(window.clone(), ScoopOp::UpdateAll, None, None, operation_id).await;
+    release_pin_holds(&scoop_path, &pin_holds);
+
+    // Record whatever was updated, even if some packages failed
+    let after = update_log::snapshot_installed_versions(&scoop_path);
+    update_log::record_version_changes(&before, &after, started.elapsed().as_millis() as u64);
 
     // Return the original result (success or error)
     result?;
@@ -66,10 +131,41 @@ pub async fn update_all_packages_headless(
     use tokio::io::AsyncReadExt;
 
     log::info!("(Headless) Updating all packages");
+    let scoop_path = state.scoop_path();
+    let hook_context = HookContext::default();
+    hooks::run_hooks(&app, HookEvent::PreUpdateAll, &hook_context).await?;
+    let before = update_log::snapshot_installed_versions(&scoop_path);
+    let started = Instant::now();
+
+    let pin_holds = hold_pinned_packages(&app, &scoop_path);
+    prepare_delta_updates(&app, &scoop_path, None).await;
+
+    // Waits for operations started from the UI, as every Scoop run does
+    let queue = state.operation_queue.lock().await;
+    if let Err(e) = guard_external_scoop(&app, &scoop_path).await {
+        release_pin_holds(&scoop_path, &pin_holds);
+        return Err(e);
+    }
+    let targets = update_all_targets(&scoop_path);
+    for pkg in &targets {
+        update_package_state(&app, pkg, PackageActivity::Updating, None, None);
+    }
+    let finish = |queue| {
+        release_pin_holds(&scoop_path, &pin_holds);
+        drop(queue);
+        for pkg in &targets {
+            update_package_state(&app, pkg, PackageActivity::Idle, None, None);
+        }
+    };
+
     let mut cmd = powershell::create_powershell_command("scoop update *");
-    let mut child = cmd
-        .spawn()
-        .map_err(|e| format!("Failed to spawn scoop update *: {}", e))?;
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            finish(queue);
+            return Err(format!("Failed to spawn scoop update *: {}", e));
+        }
+    };
 
     let mut stdout = String::new();
     let mut stderr = String::new();
@@ -91,10 +187,12 @@ pub async fn update_all_packages_headless(
         }
     }
     
-    let status = child
-        .wait()
-        .await
-        .map_err(|e| format!("Failed to execute scoop update *: {}", e))?;
+    let status = child.wait().await;
+    finish(queue);
+    let status = status.map_err(|e| format!("Failed to execute scoop update *: {}", e))?;
+
+    let after = update_log::snapshot_installed_versions(&scoop_path);
+    update_log::record_version_changes(&before, &after, started.elapsed().as_millis() as u64);
 
     if !status.success() {
         log::warn!(
@@ -159,6 +257,9 @@ pub async fn update_all_packages_headless(
         update_lines
     };
 
+    // Post hooks cannot fail the update
+    let _ = hooks::run_hooks(&app, HookEvent::PostUpdateAll, &hook_context).await;
+
     // Trigger auto cleanup after successful headless update
     trigger_auto_cleanup(app, state).await;
     log::info!("Headless package update completed successfully");
@@ -1,12 +1,11 @@
 use tauri::{command, AppHandle, Emitter};
-use std::path::PathBuf;
 use tauri_plugin_store::StoreExt;
 
 /// Get the current update channel from settings
 #[command]
 pub async fn get_update_channel(app_handle: AppHandle) -> Result<String, String> {
     // Use the same store that the frontend uses (settings.json)
-    let store = app_handle.store(PathBuf::from("settings.json"))
+    let store = app_handle.store(crate::portable::store_path("settings.json"))
         .map_err(|e| format!("Failed to load store: {}", e))?;
     
     // Try to get the channel from frontend settings
@@ -24,6 +23,30 @@ pub async fn get_update_channel(app_handle: AppHandle) -> Result<String, String>
     Ok("stable".to_string())
 }
 
+/// Saves the update channel in the frontend settings, where `get_update_channel` reads it
+pub fn set_update_channel(app_handle: &AppHandle, channel: &str) -> Result<(), String> {
+    let store = app_handle.store(crate::portable::store_path("settings.json"))
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+
+    let mut settings = store
+        .get("settings")
+        .and_then(|settings| settings.as_object().cloned())
+        .unwrap_or_default();
+    match settings.get_mut("update").and_then(|update| update.as_object_mut()) {
+        Some(update) => {
+            update.insert("channel".to_string(), serde_json::json!(channel));
+        }
+        None => {
+            settings.insert("update".to_string(), serde_json::json!({ "channel": channel }));
+        }
+    }
+    store.set("settings", serde_json::Value::Object(settings));
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+
+    log::info!("Update channel set to {}", channel);
+    Ok(())
+}
+
 /// Configure updater based on the current channel setting
 /// This function needs to be called before checking for updates
 #[cfg(windows)]
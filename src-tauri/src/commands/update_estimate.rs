@@ -0,0 +1,224 @@
+//! Estimating the size and duration of an update run before it starts.
+//!
+//! Download sizes come from the Scoop cache when a file is already there and from
+//! `HEAD` requests otherwise; servers that don't report a length leave the size
+//! unknown. Durations are averaged from past updates of the same package in the
+//! update log, falling back to the median of all recorded updates.
+use crate::commands::repair::host_architecture;
+use crate::commands::update_log::{read_update_log, UpdateLogEntry};
+use crate::commands::updates::{check_for_updates, UpdatablePackage};
+use crate::engine::cache_import::cache_file_name;
+use crate::state::AppState;
+use crate::utils::locate_package_manifest;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime, State};
+use tokio::sync::Semaphore;
+
+/// Used when the update log has no timings at all.
+const DEFAULT_UPDATE_SECS: f64 = 30.0;
+const HEAD_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_CONCURRENT_HEADS: usize = 8;
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageUpdateEstimate {
+    pub name: String,
+    pub current: String,
+    pub available: String,
+    /// Bytes still to download; `None` when a size could not be determined.
+    pub download_bytes: Option<u64>,
+    /// Every file is already in the Scoop cache.
+    pub cached: bool,
+    pub estimated_secs: f64,
+    /// Number of past updates of this package the duration is based on.
+    pub history_samples: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRunEstimate {
+    pub packages: Vec<PackageUpdateEstimate>,
+    /// Sum of the known download sizes.
+    pub download_bytes: u64,
+    /// Packages whose download size is unknown.
+    pub unknown_sizes: usize,
+    pub estimated_secs: f64,
+}
+
+/// Average past duration of each package, in seconds, with the number of samples.
+pub fn duration_history(entries: &[UpdateLogEntry]) -> HashMap<String, (f64, usize)> {
+    let mut totals: HashMap<String, (u64, usize)> = HashMap::new();
+    for entry in entries {
+        let total = totals.entry(entry.package.to_lowercase()).or_default();
+        total.0 += entry.duration_ms;
+        total.1 += 1;
+    }
+    totals
+        .into_iter()
+        .map(|(name, (ms, count))| (name, (ms as f64 / count as f64 / 1000.0, count)))
+        .collect()
+}
+
+/// Median of all recorded update durations, in seconds.
+fn median_duration(entries: &[UpdateLogEntry]) -> Option<f64> {
+    let mut durations: Vec<u64> = entries.iter().map(|e| e.duration_ms).collect();
+    if durations.is_empty() {
+        return None;
+    }
+    durations.sort_unstable();
+    Some(durations[durations.len() / 2] as f64 / 1000.0)
+}
+
+/// URLs the host architecture downloads: its `architecture` entry, else the top-level `url`.
+fn host_urls(manifest: &Value) -> Vec<String> {
+    let urls = manifest
+        .get("architecture")
+        .and_then(|archs| archs.get(host_architecture()))
+        .and_then(|arch| arch.get("url"))
+        .or_else(|| manifest.get("url"));
+    match urls {
+        Some(Value::String(url)) => vec![url.clone()],
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(String::from)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Reads the download URLs of the version `update` goes to, from its bucket manifest.
+fn update_urls(scoop_path: &Path, update: &UpdatablePackage) -> Vec<String> {
+    locate_package_manifest(scoop_path, &update.name, Some(update.bucket.clone()))
+        .ok()
+        .and_then(|(path, _)| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .map(|manifest| host_urls(&manifest))
+        .unwrap_or_default()
+}
+
+async fn content_length(url: String, limit: Arc<Semaphore>) -> Option<u64> {
+    let _permit = limit.acquire_owned().await.ok()?;
+    let client = crate::http::client().ok()?;
+    let url = crate::http::mirror_url(url.split("#/").next().unwrap_or(&url));
+    let response = client.head(url).timeout(HEAD_TIMEOUT).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// Estimates download size and duration of updating every package with an update.
+#[tauri::command]
+pub async fn estimate_update_run<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+) -> Result<UpdateRunEstimate, String> {
+    let updates = check_for_updates(app, state.clone()).await?;
+    let scoop_path = state.scoop_path();
+    let cache_dir = state.scoop_dirs().cache;
+
+    let log = read_update_log();
+    let history = duration_history(&log);
+    let fallback_secs = median_duration(&log).unwrap_or(DEFAULT_UPDATE_SECS);
+
+    let limit = Arc::new(Semaphore::new(MAX_CONCURRENT_HEADS));
+    let mut tasks = tokio::task::JoinSet::new();
+    for (index, update) in updates.iter().enumerate() {
+        for url in update_urls(&scoop_path, update) {
+            let cached = cache_dir.join(cache_file_name(&update.name, &update.available, &url));
+            if cached.is_file() {
+                continue;
+            }
+            let limit = limit.clone();
+            tasks.spawn(async move { (index, content_length(url, limit).await) });
+        }
+    }
+
+    let mut packages: Vec<PackageUpdateEstimate> = updates
+        .iter()
+        .map(|update| {
+            let (estimated_secs, history_samples) = history
+                .get(&update.name.to_lowercase())
+                .copied()
+                .unwrap_or((fallback_secs, 0));
+            PackageUpdateEstimate {
+                name: update.name.clone(),
+                current: update.current.clone(),
+                available: update.available.clone(),
+                download_bytes: Some(0),
+                cached: true,
+                estimated_secs,
+                history_samples,
+            }
+        })
+        .collect();
+
+    while let Some(result) = tasks.join_next().await {
+        let Ok((index, length)) = result else {
+            continue;
+        };
+        let package = &mut packages[index];
+        package.cached = false;
+        package.download_bytes = match (package.download_bytes, length) {
+            (Some(total), Some(length)) => Some(total + length),
+            _ => None,
+        };
+    }
+
+    let estimate = UpdateRunEstimate {
+        download_bytes: packages.iter().filter_map(|p| p.download_bytes).sum(),
+        unknown_sizes: packages
+            .iter()
+            .filter(|p| p.download_bytes.is_none())
+            .count(),
+        estimated_secs: packages.iter().map(|p| p.estimated_secs).sum(),
+        packages,
+    };
+    log::info!(
+        "Estimated update run: {} packages, {} bytes to download ({} unknown), ~{:.0}s",
+        estimate.packages.len(),
+        estimate.download_bytes,
+        estimate.unknown_sizes,
+        estimate.estimated_secs
+    );
+    Ok(estimate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn averages_durations_per_package() {
+        let entry = |package: &str, duration_ms: u64| UpdateLogEntry {
+            package: package.to_string(),
+            from_version: "1.0".to_string(),
+            to_version: "1.1".to_string(),
+            timestamp: 0,
+            duration_ms,
+        };
+        let log = [
+            entry("git", 20_000),
+            entry("Git", 40_000),
+            entry("vlc", 90_000),
+        ];
+
+        let history = duration_history(&log);
+        assert_eq!(history.get("git"), Some(&(30.0, 2)));
+        assert_eq!(history.get("vlc"), Some(&(90.0, 1)));
+        assert_eq!(median_duration(&log), Some(40.0));
+        assert_eq!(median_duration(&[]), None);
+    }
+}
@@ -0,0 +1,128 @@
+//! Persistent log of applied package updates.
+//!
+//! Entries are appended after update operations complete and are used by the
+//! statistics dashboard (updates in the last 30 days, average update duration).
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const UPDATE_LOG_FILE: &str = "update_log.json";
+/// Keep the log bounded so it never grows without limit.
+const MAX_UPDATE_LOG_ENTRIES: usize = 1000;
+
+/// A single applied update.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UpdateLogEntry {
+    pub package: String,
+    pub from_version: String,
+    pub to_version: String,
+    /// Unix timestamp (seconds) at which the update finished.
+    pub timestamp: u64,
+    /// Wall-clock duration of the update in milliseconds.
+    pub duration_ms: u64,
+}
+
+fn get_update_log_path() -> Option<PathBuf> {
+    crate::portable::app_data_dir().map(|dir| dir.join(UPDATE_LOG_FILE))
+}
+
+/// Reads all recorded update entries, oldest first.
+pub fn read_update_log() -> Vec<UpdateLogEntry> {
+    get_update_log_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Appends entries to the update log.
+pub fn append_update_log(entries: Vec<UpdateLogEntry>) {
+    if entries.is_empty() {
+        return;
+    }
+
+    let path = match get_update_log_path() {
+        Some(path) => path,
+        None => {
+            log::warn!("Could not resolve update log path");
+            return;
+        }
+    };
+
+    let mut log_entries = read_update_log();
+    log_entries.extend(entries);
+    if log_entries.len() > MAX_UPDATE_LOG_ENTRIES {
+        let excess = log_entries.len() - MAX_UPDATE_LOG_ENTRIES;
+        log_entries.drain(..excess);
+    }
+
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+
+    match serde_json::to_string_pretty(&log_entries) {
+        Ok(content) => {
+            if let Err(e) = fs::write(&path, content) {
+                log::warn!("Failed to write update log: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Failed to serialize update log: {}", e),
+    }
+}
+
+/// Reads the currently installed version of every package under `apps`.
+pub fn snapshot_installed_versions(scoop_path: &Path) -> HashMap<String, String> {
+    let mut versions = HashMap::new();
+
+    if let Ok(entries) = fs::read_dir(scoop_path.join("apps")) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(version) = crate::commands::info::get_installed_version(scoop_path, &name) {
+                versions.insert(name, version);
+            }
+        }
+    }
+
+    versions
+}
+
+/// Compares two version snapshots and records every package whose version changed.
+///
+/// When several packages are updated by a single run the total duration is split
+/// evenly between them, since scoop does not report per-package timings.
+pub fn record_version_changes(
+    before: &HashMap<String, String>,
+    after: &HashMap<String, String>,
+    duration_ms: u64,
+) {
+    let changed: Vec<(&String, &String, &String)> = after
+        .iter()
+        .filter_map(|(name, new_version)| {
+            before
+                .get(name)
+                .filter(|old_version| *old_version != new_version)
+                .map(|old_version| (name, old_version, new_version))
+        })
+        .collect();
+
+    if changed.is_empty() {
+        return;
+    }
+
+    let timestamp = chrono::Utc::now().timestamp().max(0) as u64;
+    let per_package_ms = duration_ms / changed.len() as u64;
+
+    let entries = changed
+        .into_iter()
+        .map(|(name, old_version, new_version)| UpdateLogEntry {
+            package: name.clone(),
+            from_version: old_version.clone(),
+            to_version: new_version.clone(),
+            timestamp,
+            duration_ms: per_package_ms,
+        })
+        .collect::<Vec<_>>();
+
+    log::info!("Recording {} applied updates in update log", entries.len());
+    append_update_log(entries);
+}
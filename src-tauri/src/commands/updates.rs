@@ -1,12 +1,18 @@
 //! Command for checking for available updates for installed Scoop packages.
-use crate::commands::installed::get_installed_packages_full;
+use crate::commands::ignore_patterns::load_ignore_rules;
+use crate::commands::installed::get_installed_packages;
+use crate::commands::package_source::{
+    get_bucket_priority_list, get_pinned_sources, prioritized_candidates,
+};
+use crate::commands::scoop_db;
+use crate::commands::version_pin::load_version_pins;
+use crate::engine::update_plan::{find_available_update, select_update_source};
+use crate::engine::version::is_update_available;
 use crate::models::ScoopPackage as InstalledPackage;
 use crate::state::AppState;
-use crate::utils::locate_package_manifest;
 use rayon::prelude::*;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use std::collections::HashSet;
-use std::fs;
 use std::path::Path;
 use tauri::{AppHandle, Runtime, State};
 
@@ -16,12 +22,8 @@ pub struct UpdatablePackage {
     pub name: String,
     pub current: String,
     pub available: String,
-}
-
-/// Represents the structure of a `manifest.json` file, used to extract the version.
-#[derive(Deserialize, Debug)]
-struct Manifest {
-    version: String,
+    /// Bucket the update comes from.
+    pub bucket: String,
 }
 
 /// Checks a single package to see if a newer version is available in its manifest.
@@ -31,35 +33,23 @@ struct Manifest {
 fn check_package_for_update(
     scoop_dir: &Path,
     package: &InstalledPackage,
+    source: &str,
 ) -> Result<Option<UpdatablePackage>, String> {
-    // Locate the manifest for the package in its source bucket.
-    let (manifest_path, _) =
-        locate_package_manifest(scoop_dir, &package.name, Some(package.source.clone()))
-            .map_err(|e| format!("Could not locate manifest for {}: {}", package.name, e))?;
-
-    // Read and parse the manifest to get the latest version.
-    let content = fs::read_to_string(manifest_path)
-        .map_err(|e| format!("Could not read manifest for {}: {}", package.name, e))?;
-    let manifest: Manifest = serde_json::from_str(&content)
-        .map_err(|e| format!("Could not parse manifest for {}: {}", package.name, e))?;
-
-    // Compare versions and return an UpdatablePackage if a new version is found.
-    if package.version != manifest.version {
-        Ok(Some(UpdatablePackage {
-            name: package.name.clone(),
-            current: package.version.clone(),
-            available: manifest.version,
-        }))
-    } else {
-        Ok(None)
-    }
+    let available = find_available_update(scoop_dir, &package.name, &package.version, source)?;
+    Ok(available.map(|available| UpdatablePackage {
+        name: package.name.clone(),
+        current: package.version.clone(),
+        available,
+        bucket: source.to_string(),
+    }))
 }
 
 /// Checks all installed packages for available updates.
 ///
 /// This command scans the filesystem, compares installed versions with the latest
 /// available versions in the package manifests, and returns a list of packages
-/// that can be updated. It respects packages that are on hold.
+/// that can be updated. It respects packages that are on hold. When Scoop's SQLite
+/// database is current, versions are looked up there instead of in the manifests.
 #[tauri::command]
 pub async fn check_for_updates<R: Runtime>(
     app: AppHandle<R>,
@@ -67,9 +57,17 @@ pub async fn check_for_updates<R: Runtime>(
 ) -> Result<Vec<UpdatablePackage>, String> {
     log::info!("Checking for updates using filesystem");
 
-    let installed_packages = get_installed_packages_full(app.clone(), state.clone()).await?;
+    let installed_packages = get_installed_packages(app.clone(), state.clone()).await?;
     let scoop_path = state.scoop_path();
 
+    // Pinned sources take precedence over the bucket recorded at install time. When
+    // that bucket no longer provides the package, the highest-priority bucket is used.
+    let pinned_sources = get_pinned_sources(&app);
+    let priority = get_bucket_priority_list(&app);
+    let ignore = load_ignore_rules(&app);
+    // Pinned packages are switched back after every update, so offering one is pointless
+    let pins = load_version_pins(&app);
+
     // Get a set of held packages for efficient lookup.
     let held_packages: HashSet<String> =
         crate::commands::hold::list_held_packages(app, state.clone())
@@ -83,11 +81,35 @@ pub async fn check_for_updates<R: Runtime>(
     let held_packages_clone = held_packages.clone();
 
     let updatable_packages = tokio::task::spawn_blocking(move || {
+        let database_versions = scoop_db::latest_versions(&scoop_path_clone);
         installed_packages_clone
             .par_iter()
             .filter(|p| !held_packages_clone.contains(&p.name)) // Exclude held packages
+            .filter(|p| !p.broken) // Broken installs have no reliable version to compare
+            .filter(|p| !ignore.is_ignored(&p.name, &p.source))
+            .filter(|p| !pins.contains_key(&p.name))
             .filter_map(|package| {
-                match check_package_for_update(&scoop_path_clone, package) {
+                let candidates =
+                    prioritized_candidates(&scoop_path_clone, &package.name, &priority);
+                let source = select_update_source(
+                    &package.source,
+                    pinned_sources.get(&package.name),
+                    candidates,
+                );
+                let known = database_versions.as_ref().and_then(|versions| {
+                    versions.get(&(package.name.to_lowercase(), source.to_lowercase()))
+                });
+                if let Some(latest) = known {
+                    return is_update_available(&package.version, latest).then(|| {
+                        UpdatablePackage {
+                            name: package.name.clone(),
+                            current: package.version.clone(),
+                            available: latest.clone(),
+                            bucket: source.clone(),
+                        }
+                    });
+                }
+                match check_package_for_update(&scoop_path_clone, package, &source) {
                     Ok(Some(updatable)) => Some(updatable),
                     Ok(None) => None, // Package is up-to-date
                     Err(e) => {
@@ -8,9 +8,8 @@ pub fn check_and_update_version(app: AppHandle) -> Result<bool, String> {
     let current_version = app.package_info().version.to_string();
     
     // Always try to use the new Roaming app data directory first
-    let new_data_dir = dirs::data_dir()
-        .ok_or_else(|| "Could not resolve roaming app data directory".to_string())?
-        .join("com.pailer.ks");
+    let new_data_dir = crate::portable::app_data_dir()
+        .ok_or_else(|| "Could not resolve roaming app data directory".to_string())?;
 
     // Ensure the new directory exists
     if !new_data_dir.exists() {
@@ -27,6 +26,14 @@ pub fn check_and_update_version(app: AppHandle) -> Result<bool, String> {
         return Ok(false);
     }
 
+    // Portable installs have no legacy location to migrate from
+    if crate::portable::is_portable() {
+        fs::write(&version_path, &current_version)
+            .map_err(|e| format!("Failed to write version file: {}", e))?;
+        log::info!("Detected new version {} (portable mode).", current_version);
+        return Ok(true);
+    }
+
     // Check if there's an old version file in the legacy location and migrate it if needed
     let old_data_dir = dirs::data_local_dir()
         .ok_or_else(|| "Could not resolve local app data directory".to_string())?
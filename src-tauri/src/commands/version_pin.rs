@@ -0,0 +1,287 @@
+//! Pinning packages to an installed version.
+//!
+//! Unlike a hold, which only stops Scoop from updating a package, a pin is enforced
+//! by the app: whenever `current` ends up pointing at another version, because of an
+//! update run here, in a terminal or by a bucket change, it is switched back through
+//! the linker and [`EVENT_VERSION_PINS_RESTORED`] reports what was done.
+use crate::commands::hold::{is_package_held, modify_hold_status};
+use crate::commands::installed::EVENT_INSTALLED_CHANGED;
+use crate::commands::linker::switch_to_version;
+use crate::commands::powershell::EVENT_FINISHED;
+use crate::commands::settings;
+use crate::config_keys::VERSION_PINS;
+use crate::state::AppState;
+use crate::utils::validate_dir_name;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Listener, Manager, Runtime, State};
+
+/// Emitted with the [`PinRestore`] results whenever pinned packages had moved.
+pub const EVENT_VERSION_PINS_RESTORED: &str = "version-pins-restored";
+/// How often pins are checked to catch updates made outside the app.
+const PIN_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// A pinned package whose `current` version had moved.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PinRestore {
+    pub package: String,
+    pub pinned_version: String,
+    pub found_version: Option<String>,
+    pub restored: bool,
+    pub error: Option<String>,
+}
+
+/// Reads the pinned version of every pinned package.
+pub fn load_version_pins<R: Runtime>(app: &AppHandle<R>) -> BTreeMap<String, String> {
+    settings::get_config_value(app.clone(), VERSION_PINS.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+fn save_version_pins(app: &AppHandle, pins: &BTreeMap<String, String>) -> Result<(), String> {
+    let value = serde_json::to_value(pins)
+        .map_err(|e| format!("Failed to serialize version pins: {}", e))?;
+    settings::set_config_value(app.clone(), VERSION_PINS.to_string(), value)
+}
+
+/// The version `current` points at: its target directory, or its manifest's version.
+fn current_version(package_dir: &Path) -> Option<String> {
+    let current = package_dir.join("current");
+    fs::canonicalize(&current)
+        .ok()
+        .and_then(|target| target.file_name().map(|n| n.to_string_lossy().to_string()))
+        .filter(|name| !name.eq_ignore_ascii_case("current"))
+        .or_else(|| {
+            let manifest = fs::read_to_string(current.join("manifest.json")).ok()?;
+            let json: serde_json::Value = serde_json::from_str(&manifest).ok()?;
+            json.get("version")?.as_str().map(String::from)
+        })
+}
+
+/// Checks that a pin names a package and one of its version directories, never the
+/// `current` link, which would be switched onto itself.
+fn validate_pin(package: &str, version: &str) -> Result<(), String> {
+    validate_dir_name("package", package)?;
+    validate_dir_name("version", version)?;
+    if version.eq_ignore_ascii_case("current") {
+        return Err(format!("Cannot pin '{}' to its current link", package));
+    }
+    Ok(())
+}
+
+/// Lists the installed pinned packages whose `current` is not the pinned version.
+/// Invalid pins are skipped.
+pub fn find_pin_drift(apps_dir: &Path, pins: &BTreeMap<String, String>) -> Vec<PinRestore> {
+    pins.iter()
+        .filter(|(package, pinned)| validate_pin(package, pinned).is_ok())
+        .filter(|(package, _)| apps_dir.join(package).is_dir())
+        .filter_map(|(package, pinned)| {
+            let found = current_version(&apps_dir.join(package));
+            (found.as_deref() != Some(pinned.as_str())).then(|| PinRestore {
+                package: package.clone(),
+                pinned_version: pinned.clone(),
+                found_version: found,
+                restored: false,
+                error: None,
+            })
+        })
+        .collect()
+}
+
+/// Switches every drifted pinned package back to its pinned version.
+pub async fn enforce_version_pins<R: Runtime>(app: &AppHandle<R>) -> Vec<PinRestore> {
+    let pins = load_version_pins(app);
+    if pins.is_empty() || crate::policy::is_read_only() {
+        return Vec::new();
+    }
+    let state = app.state::<AppState>();
+    // Wait for a running Scoop operation, switching mid-update would break it
+    let _queue = state.operation_queue.lock().await;
+    let apps_dir = state.scoop_dirs().apps;
+    let mut drift = find_pin_drift(&apps_dir, &pins);
+
+    for restore in &mut drift {
+        let package_dir = apps_dir.join(&restore.package);
+        match switch_to_version(&package_dir, &restore.package, &restore.pinned_version).await {
+            Ok(()) => {
+                log::info!(
+                    "Switched pinned package '{}' back from {} to {}",
+                    restore.package,
+                    restore.found_version.as_deref().unwrap_or("?"),
+                    restore.pinned_version
+                );
+                restore.restored = true;
+            }
+            Err(e) => {
+                log::warn!(
+                    "Could not restore pinned version of '{}': {}",
+                    restore.package,
+                    e
+                );
+                restore.error = Some(e);
+            }
+        }
+    }
+
+    if !drift.is_empty() {
+        if let Err(e) = app.emit(EVENT_VERSION_PINS_RESTORED, &drift) {
+            log::warn!(
+                "Failed to emit {} event: {}",
+                EVENT_VERSION_PINS_RESTORED,
+                e
+            );
+        }
+    }
+    drift
+}
+
+/// Holds the installed pinned packages that are not held already, so `scoop update *`
+/// leaves them alone. Returns the packages to pass to [`release_pin_holds`] afterwards.
+pub fn hold_pinned_packages<R: Runtime>(app: &AppHandle<R>, scoop_dir: &Path) -> Vec<String> {
+    load_version_pins(app)
+        .into_keys()
+        .filter(|package| is_package_held(scoop_dir, package) == Ok(false))
+        .filter(
+            |package| match modify_hold_status(scoop_dir, package, true) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("Could not hold pinned package '{}': {}", package, e);
+                    false
+                }
+            },
+        )
+        .collect()
+}
+
+/// Removes the holds placed by [`hold_pinned_packages`].
+pub fn release_pin_holds(scoop_dir: &Path, packages: &[String]) {
+    for package in packages {
+        if let Err(e) = modify_hold_status(scoop_dir, package, false) {
+            log::warn!(
+                "Could not release the hold on pinned package '{}': {}",
+                package,
+                e
+            );
+        }
+    }
+}
+
+/// Enforces pins after operations, detected package changes and periodically.
+pub fn watch_version_pins(app: AppHandle) {
+    for event in [EVENT_INSTALLED_CHANGED, EVENT_FINISHED] {
+        let app_handle = app.clone();
+        app.listen(event, move |_| {
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                enforce_version_pins(&app_handle).await;
+            });
+        });
+    }
+    crate::supervisor::spawn_supervised("versionPins", move || {
+        let app = app.clone();
+        async move {
+            loop {
+                enforce_version_pins(&app).await;
+                tokio::time::sleep(PIN_CHECK_INTERVAL).await;
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_version_pins(app: AppHandle) -> BTreeMap<String, String> {
+    load_version_pins(&app)
+}
+
+/// Pins a package to an installed version, switching to it right away if needed.
+#[tauri::command]
+pub async fn pin_package_version(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    package_name: String,
+    version: String,
+) -> Result<(), String> {
+    validate_pin(&package_name, &version)?;
+    let package_dir = state.scoop_dirs().apps.join(&package_name);
+    if !package_dir.join(&version).is_dir() {
+        return Err(format!(
+            "Version '{}' of package '{}' is not installed",
+            version, package_name
+        ));
+    }
+
+    let mut pins = load_version_pins(&app);
+    pins.insert(package_name.clone(), version.clone());
+    save_version_pins(&app, &pins)?;
+    log::info!("Pinned '{}' to version {}", package_name, version);
+
+    // Like the enforcer, never switch while a Scoop operation runs
+    let _queue = state.operation_queue.lock().await;
+    if current_version(&package_dir).as_deref() != Some(version.as_str()) {
+        switch_to_version(&package_dir, &package_name, &version).await?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn unpin_package_version(app: AppHandle, package_name: String) -> Result<(), String> {
+    let mut pins = load_version_pins(&app);
+    if pins.remove(&package_name).is_some() {
+        save_version_pins(&app, &pins)?;
+        log::info!("Unpinned '{}'", package_name);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScoopFixture;
+
+    #[test]
+    fn detects_pinned_packages_that_moved() {
+        let fixture = ScoopFixture::new();
+        fixture.install("node", "20.11.0", "main");
+        fixture.app_version("node", "18.19.0", Some("main"));
+        fixture.install("git", "2.44.0", "main");
+
+        let pins = BTreeMap::from([
+            ("node".to_string(), "18.19.0".to_string()),
+            ("git".to_string(), "2.44.0".to_string()),
+            ("removed".to_string(), "1.0.0".to_string()),
+        ]);
+        let drift = find_pin_drift(&fixture.apps_dir(), &pins);
+        assert_eq!(drift.len(), 1);
+        assert_eq!(drift[0].package, "node");
+        assert_eq!(drift[0].found_version.as_deref(), Some("20.11.0"));
+    }
+
+    #[test]
+    fn pins_name_a_version_directory() {
+        assert!(validate_pin("node", "18.19.0").is_ok());
+        for (package, version) in [
+            ("node", "current"),
+            ("node", ".."),
+            ("node", "a/b"),
+            ("..", "1.0"),
+        ] {
+            assert!(
+                validate_pin(package, version).is_err(),
+                "{}/{}",
+                package,
+                version
+            );
+        }
+
+        let fixture = ScoopFixture::new();
+        fixture.install("git", "2.44.0", "main");
+        let pins = BTreeMap::from([("git".to_string(), "current".to_string())]);
+        assert!(find_pin_drift(&fixture.apps_dir(), &pins).is_empty());
+    }
+}
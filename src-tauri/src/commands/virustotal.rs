@@ -1,6 +1,8 @@
-use crate::commands::powershell;
+use crate::commands::health::VIRUSTOTAL_DETECTIONS_KEY;
+use crate::commands::{info, powershell, settings};
+use crate::state::AppState;
 use serde::Serialize;
-use tauri::{Emitter, Window};
+use tauri::{Emitter, Manager, Window};
 use tokio::io::{AsyncBufReadExt, BufReader};
 
 /// Represents the result of a VirusTotal scan.
@@ -127,9 +129,49 @@ pub async fn scan_package(
 
     log::info!("VirusTotal scan finished: {:?}", result);
 
+    record_scan_outcome(&window, &package_name, exit_code);
+
     window
         .emit("virustotal-scan-finished", result)
         .map_err(|e| format!("Failed to emit scan result: {}", e))?;
 
     Ok(())
 }
+
+/// Remembers which installed version produced detections so package health can flag it.
+/// A clean scan clears any previous record; inconclusive scans leave it untouched.
+fn record_scan_outcome(window: &Window, package_name: &str, exit_code: i32) {
+    if exit_code != 0 && exit_code != 2 {
+        return;
+    }
+
+    let app = window.app_handle().clone();
+    let mut detections = settings::get_config_value(app.clone(), VIRUSTOTAL_DETECTIONS_KEY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_object().cloned())
+        .unwrap_or_default();
+
+    if exit_code == 2 {
+        let version = app
+            .try_state::<AppState>()
+            .and_then(|state| info::get_installed_version(&state.scoop_path(), package_name));
+        detections.insert(
+            package_name.to_string(),
+            serde_json::json!({
+                "version": version,
+                "scannedAt": chrono::Utc::now().to_rfc3339(),
+            }),
+        );
+    } else {
+        detections.remove(package_name);
+    }
+
+    if let Err(e) = settings::set_config_value(
+        app,
+        VIRUSTOTAL_DETECTIONS_KEY.to_string(),
+        serde_json::Value::Object(detections),
+    ) {
+        log::warn!("Failed to record VirusTotal result for {}: {}", package_name, e);
+    }
+}
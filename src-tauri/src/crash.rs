@@ -0,0 +1,244 @@
+//! Crash reports.
+//!
+//! Panics that end the app, and on Windows unhandled native exceptions, are written as
+//! JSON reports to `crashes` in the app data directory, optionally along with a
+//! minidump. On the next launch the newest report the user has not acknowledged is
+//! offered for viewing or attaching to an issue.
+//!
+//! Only panics on the main thread end the app. Elsewhere they are caught by the async
+//! runtime, `spawn_blocking` or a task supervisor and turned into errors or restarts, so
+//! they are only logged.
+use crate::commands::settings;
+use crate::config_keys::CRASH_MINIDUMPS_ENABLED;
+use crate::models::CrashReport;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use tauri::{AppHandle, Runtime};
+
+const CRASH_DIR: &str = "crashes";
+/// Reports kept before the oldest are deleted, together with their minidumps.
+const MAX_REPORTS: usize = 20;
+
+static MINIDUMPS_ENABLED: AtomicBool = AtomicBool::new(false);
+/// Tells apart reports written by this process in the same millisecond.
+static REPORT_SEQUENCE: AtomicU32 = AtomicU32::new(0);
+
+pub fn crash_dir() -> Option<PathBuf> {
+    crate::portable::app_data_dir().map(|dir| dir.join(CRASH_DIR))
+}
+
+/// Reads the minidump setting from the app store.
+pub fn configure<R: Runtime>(app: &AppHandle<R>) {
+    let enabled = settings::get_config_value(app.clone(), CRASH_MINIDUMPS_ENABLED.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    MINIDUMPS_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+fn new_report(kind: &str, message: String, location: Option<String>) -> CrashReport {
+    let now = chrono::Utc::now();
+    CrashReport {
+        id: format!(
+            "{}-{}-{}",
+            now.format("%Y%m%d-%H%M%S%3f"),
+            std::process::id(),
+            REPORT_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+        ),
+        timestamp: now.to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        kind: kind.to_string(),
+        message,
+        location,
+        thread: std::thread::current().name().map(String::from),
+        backtrace: None,
+        minidump_path: None,
+        acknowledged: false,
+    }
+}
+
+fn write_report(report: &CrashReport) {
+    let Some(dir) = crash_dir() else {
+        return;
+    };
+    if fs::create_dir_all(&dir).is_err() {
+        return;
+    }
+    if let Ok(content) = serde_json::to_string_pretty(report) {
+        let _ = fs::write(dir.join(format!("{}.json", report.id)), content);
+    }
+}
+
+/// Whether a panic on the current thread ends the app; see the module docs.
+fn is_fatal_panic() -> bool {
+    std::thread::current().name() == Some("main")
+}
+
+/// Writes a report for a panic that ends the app. Called from the panic hook, so it
+/// must not panic.
+pub fn record_panic(message: &str, location: &str) {
+    if !is_fatal_panic() {
+        return;
+    }
+    let mut report = new_report("panic", message.to_string(), Some(location.to_string()));
+    report.backtrace = Some(std::backtrace::Backtrace::force_capture().to_string());
+    if MINIDUMPS_ENABLED.load(Ordering::Relaxed) {
+        report.minidump_path = native::write_minidump(&report.id, std::ptr::null());
+    }
+    write_report(&report);
+}
+
+/// Installs the handler for native crashes that bypass the panic hook.
+pub fn install_native_handler() {
+    native::install();
+}
+
+/// Lists the stored reports, newest first.
+pub fn list_reports() -> Vec<CrashReport> {
+    let Some(entries) = crash_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| serde_json::from_str(&fs::read_to_string(entry.path()).ok()?).ok())
+        .collect();
+    reports.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    reports
+}
+
+/// Marks a report as seen so it is no longer offered on launch.
+pub fn acknowledge(id: &str) -> Result<(), String> {
+    let mut report = list_reports()
+        .into_iter()
+        .find(|r| r.id == id)
+        .ok_or_else(|| format!("Crash report '{}' not found", id))?;
+    report.acknowledged = true;
+    write_report(&report);
+    Ok(())
+}
+
+fn remove_report(report: &CrashReport) {
+    if let Some(dir) = crash_dir() {
+        let _ = fs::remove_file(dir.join(format!("{}.json", report.id)));
+    }
+    if let Some(dump) = &report.minidump_path {
+        let _ = fs::remove_file(dump);
+    }
+}
+
+/// Deletes every report and minidump.
+pub fn clear_reports() {
+    for report in list_reports() {
+        remove_report(&report);
+    }
+}
+
+/// Deletes the oldest reports beyond [`MAX_REPORTS`].
+pub fn prune_reports() {
+    for report in list_reports().iter().skip(MAX_REPORTS) {
+        remove_report(report);
+    }
+}
+
+#[cfg(windows)]
+mod native {
+    use super::{crash_dir, new_report, write_report, MINIDUMPS_ENABLED};
+    use std::os::windows::io::AsRawHandle;
+    use std::sync::atomic::Ordering;
+    use windows_sys::Win32::System::Diagnostics::Debug::{
+        MiniDumpWithThreadInfo, MiniDumpWriteDump, SetUnhandledExceptionFilter, EXCEPTION_POINTERS,
+        MINIDUMP_EXCEPTION_INFORMATION,
+    };
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentProcess, GetCurrentProcessId, GetCurrentThreadId,
+    };
+
+    /// Lets Windows continue with its default handling (WER) after the report is written.
+    const EXCEPTION_CONTINUE_SEARCH: i32 = 0;
+
+    /// Writes a minidump of this process, returning its path.
+    pub fn write_minidump(id: &str, exception: *const EXCEPTION_POINTERS) -> Option<String> {
+        let dir = crash_dir()?;
+        std::fs::create_dir_all(&dir).ok()?;
+        let path = dir.join(format!("{}.dmp", id));
+        let file = std::fs::File::create(&path).ok()?;
+
+        let info = MINIDUMP_EXCEPTION_INFORMATION {
+            ThreadId: unsafe { GetCurrentThreadId() },
+            ExceptionPointers: exception as *mut _,
+            ClientPointers: 0,
+        };
+        let info_ptr = if exception.is_null() {
+            std::ptr::null()
+        } else {
+            &info as *const _
+        };
+        let written = unsafe {
+            MiniDumpWriteDump(
+                GetCurrentProcess(),
+                GetCurrentProcessId(),
+                file.as_raw_handle() as _,
+                MiniDumpWithThreadInfo,
+                info_ptr,
+                std::ptr::null(),
+                std::ptr::null(),
+            )
+        };
+        if written == 0 {
+            drop(file);
+            let _ = std::fs::remove_file(&path);
+            return None;
+        }
+        Some(path.to_string_lossy().to_string())
+    }
+
+    unsafe extern "system" fn on_unhandled_exception(info: *const EXCEPTION_POINTERS) -> i32 {
+        let code = if info.is_null() || (*info).ExceptionRecord.is_null() {
+            0
+        } else {
+            (*(*info).ExceptionRecord).ExceptionCode as u32
+        };
+        let mut report = new_report(
+            "exception",
+            format!("Unhandled exception 0x{:08X}", code),
+            None,
+        );
+        if MINIDUMPS_ENABLED.load(Ordering::Relaxed) {
+            report.minidump_path = write_minidump(&report.id, info);
+        }
+        write_report(&report);
+        EXCEPTION_CONTINUE_SEARCH
+    }
+
+    pub fn install() {
+        unsafe {
+            SetUnhandledExceptionFilter(Some(on_unhandled_exception));
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod native {
+    pub fn write_minidump(_id: &str, _exception: *const ()) -> Option<String> {
+        None
+    }
+
+    pub fn install() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_ids_are_unique_within_a_second() {
+        let first = new_report("panic", String::new(), None);
+        let second = new_report("panic", String::new(), None);
+        assert_ne!(first.id, second.id);
+    }
+}
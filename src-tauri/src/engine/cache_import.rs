@@ -0,0 +1,267 @@
+//! Matching downloaded files to manifests so they can be placed in the Scoop cache.
+//!
+//! Scoop names cache files `app#version#<first 7 hex of sha256(url)><extension>`;
+//! older versions used the URL with every run of non-word characters replaced by `_`.
+//! Both forms are recognised when importing, and files are always written in the
+//! current form.
+use serde_json::Value;
+use sha2::{Digest, Sha256, Sha512};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// One downloadable file of a manifest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestDownload {
+    pub app: String,
+    pub version: String,
+    pub url: String,
+    pub hash: Option<String>,
+}
+
+/// Outcome of checking a file against a manifest hash.
+#[derive(Debug, PartialEq)]
+pub enum HashCheck {
+    Match,
+    Mismatch,
+    /// The manifest has no hash, or one in an algorithm we cannot compute.
+    Unverifiable,
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Extension of the URL as `[System.IO.Path]::GetExtension` reports it, dot included.
+pub fn url_extension(url: &str) -> &str {
+    let name_start = url.rfind(['/', '\\', ':']).map(|i| i + 1).unwrap_or(0);
+    match url[name_start..].rfind('.') {
+        Some(dot) if dot + 1 < url.len() - name_start => &url[name_start + dot..],
+        _ => "",
+    }
+}
+
+/// The cache file name Scoop uses for `url` of `app` at `version`.
+pub fn cache_file_name(app: &str, version: &str, url: &str) -> String {
+    format!(
+        "{}#{}#{}{}",
+        app,
+        version,
+        &sha256_hex(url.as_bytes())[..7],
+        url_extension(url)
+    )
+}
+
+/// The URL part of a cache file name from Scoop versions before hashed names.
+pub fn legacy_url_suffix(url: &str) -> String {
+    let mut out = String::with_capacity(url.len());
+    let mut in_run = false;
+    for c in url.chars() {
+        if c.is_alphanumeric() || matches!(c, '_' | '.' | '-') {
+            out.push(c);
+            in_run = false;
+        } else if !in_run {
+            out.push('_');
+            in_run = true;
+        }
+    }
+    out
+}
+
+/// Splits a Scoop cache file name into app, version and URL part.
+pub fn parse_cache_name(file_name: &str) -> Option<(&str, &str, &str)> {
+    let mut parts = file_name.splitn(3, '#');
+    let app = parts.next().filter(|s| !s.is_empty())?;
+    let version = parts.next().filter(|s| !s.is_empty())?;
+    let rest = parts.next().filter(|s| !s.is_empty())?;
+    Some((app, version, rest))
+}
+
+/// Whether a cache file's URL part was produced from `url`.
+pub fn suffix_matches(rest: &str, app: &str, version: &str, url: &str) -> bool {
+    cache_file_name(app, version, url).ends_with(&format!("#{}", rest))
+        || legacy_url_suffix(url) == rest
+}
+
+/// File name a download is saved as: the `#/name` fragment if present, else the last
+/// path segment.
+pub fn url_file_name(url: &str) -> &str {
+    if let Some((_, fragment)) = url.split_once("#/") {
+        return fragment;
+    }
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn string_or_array(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(s)) => vec![s.clone()],
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(String::from)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Lists every URL of a manifest, for all architectures, with its hash.
+pub fn manifest_downloads(app: &str, manifest: &Value) -> Vec<ManifestDownload> {
+    let version = manifest
+        .get("version")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+
+    let mut sections = vec![manifest];
+    if let Some(Value::Object(archs)) = manifest.get("architecture") {
+        sections.extend(archs.values());
+    }
+
+    let mut downloads = Vec::new();
+    for section in sections {
+        let urls = string_or_array(section.get("url"));
+        let hashes = string_or_array(section.get("hash"));
+        for (i, url) in urls.into_iter().enumerate() {
+            if downloads.iter().any(|d: &ManifestDownload| d.url == url) {
+                continue;
+            }
+            downloads.push(ManifestDownload {
+                app: app.to_string(),
+                version: version.clone(),
+                url,
+                hash: hashes.get(i).cloned(),
+            });
+        }
+    }
+    downloads
+}
+
+/// Lists the URLs a manifest downloads on `architecture`, with their hashes. The
+/// architecture's own `url` and `hash` take precedence over the top-level ones.
+pub fn architecture_downloads(
+    app: &str,
+    manifest: &Value,
+    architecture: &str,
+) -> Vec<ManifestDownload> {
+    let version = manifest
+        .get("version")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let arch = manifest
+        .get("architecture")
+        .and_then(|archs| archs.get(architecture));
+    let field = |key: &str| arch.and_then(|a| a.get(key)).or_else(|| manifest.get(key));
+
+    let hashes = string_or_array(field("hash"));
+    string_or_array(field("url"))
+        .into_iter()
+        .enumerate()
+        .map(|(i, url)| ManifestDownload {
+            app: app.to_string(),
+            version: version.clone(),
+            url,
+            hash: hashes.get(i).cloned(),
+        })
+        .collect()
+}
+
+pub fn file_digest<D: Digest>(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = D::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// Checks a file against a manifest hash such as `abc…` or `sha512:abc…`.
+pub fn check_hash(path: &Path, expected: Option<&str>) -> Result<HashCheck, String> {
+    let Some(expected) = expected else {
+        return Ok(HashCheck::Unverifiable);
+    };
+    let (algorithm, expected) = expected.split_once(':').unwrap_or(("sha256", expected));
+    let actual = match algorithm.to_lowercase().as_str() {
+        "sha256" => file_digest::<Sha256>(path)?,
+        "sha512" => file_digest::<Sha512>(path)?,
+        _ => return Ok(HashCheck::Unverifiable),
+    };
+    Ok(if actual.eq_ignore_ascii_case(expected.trim()) {
+        HashCheck::Match
+    } else {
+        HashCheck::Mismatch
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_files_like_scoop() {
+        let url = "https://www.7-zip.org/a/7z2301-x64.msi";
+        assert_eq!(
+            cache_file_name("7zip", "23.01", url),
+            "7zip#23.01#4227d2f.msi"
+        );
+        assert_eq!(url_extension("https://example.com/download?id=1"), "");
+        assert_eq!(url_extension("https://example.com/tool.exe#/dl.7z"), ".7z");
+        assert_eq!(
+            url_file_name("https://example.com/tool.exe#/dl.7z"),
+            "dl.7z"
+        );
+
+        assert!(suffix_matches("4227d2f.msi", "7zip", "23.01", url));
+        assert!(suffix_matches(
+            "https_www.7-zip.org_a_7z2301-x64.msi",
+            "7zip",
+            "23.01",
+            url
+        ));
+        assert_eq!(
+            parse_cache_name("7zip#23.01#4227d2f.msi"),
+            Some(("7zip", "23.01", "4227d2f.msi"))
+        );
+    }
+
+    #[test]
+    fn checks_hashes_and_lists_arch_downloads() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.zip");
+        std::fs::write(&file, b"hello").unwrap();
+        let sha = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+        assert_eq!(check_hash(&file, Some(sha)).unwrap(), HashCheck::Match);
+        assert_eq!(
+            check_hash(&file, Some("sha256:00")).unwrap(),
+            HashCheck::Mismatch
+        );
+        assert_eq!(
+            check_hash(&file, Some("md5:00")).unwrap(),
+            HashCheck::Unverifiable
+        );
+
+        let manifest = serde_json::json!({
+            "version": "1.0",
+            "architecture": {
+                "64bit": { "url": "https://e.com/a64.zip", "hash": sha },
+                "32bit": { "url": ["https://e.com/a32.zip", "https://e.com/x.zip"], "hash": ["1", "2"] }
+            }
+        });
+        let downloads = manifest_downloads("a", &manifest);
+        assert_eq!(downloads.len(), 3);
+        assert!(downloads
+            .iter()
+            .any(|d| d.url.ends_with("x.zip") && d.hash.as_deref() == Some("2")));
+    }
+}
@@ -0,0 +1,177 @@
+//! Dependency graph of installed packages and safe removal ordering.
+//!
+//! Edges come from the `depends` field of each installed manifest. Names are compared
+//! case-insensitively, and a `bucket/app` dependency refers to `app`.
+use crate::models::{SkippedUninstall, UninstallPlan};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Installed package (lowercase) -> packages it depends on (lowercase).
+pub type DependencyGraph = HashMap<String, Vec<String>>;
+
+/// Reads the `depends` field of a manifest, which is a string or an array.
+pub fn manifest_dependencies(manifest: &Value) -> Vec<String> {
+    let names: Vec<&str> = match manifest.get("depends") {
+        Some(Value::String(name)) => vec![name.as_str()],
+        Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    names
+        .into_iter()
+        .map(|name| {
+            name.rsplit('/')
+                .next()
+                .unwrap_or(name)
+                .trim()
+                .to_lowercase()
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Builds the graph from the `current` manifest of every installed package.
+pub fn installed_dependency_graph(scoop_dir: &Path) -> DependencyGraph {
+    let Ok(entries) = fs::read_dir(scoop_dir.join("apps")) else {
+        return DependencyGraph::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| {
+            let manifest = fs::read_to_string(entry.path().join("current").join("manifest.json"))
+                .ok()
+                .and_then(|content| serde_json::from_str::<Value>(&content).ok());
+            (
+                entry.file_name().to_string_lossy().to_lowercase(),
+                manifest
+                    .as_ref()
+                    .map(manifest_dependencies)
+                    .unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
+/// Packages among `among` that depend on `package`, sorted.
+pub fn dependents_of(
+    graph: &DependencyGraph,
+    package: &str,
+    among: &HashSet<String>,
+) -> Vec<String> {
+    let package = package.to_lowercase();
+    let mut dependents: Vec<String> = graph
+        .iter()
+        .filter(|(name, deps)| among.contains(*name) && deps.contains(&package))
+        .map(|(name, _)| name.clone())
+        .collect();
+    dependents.sort();
+    dependents
+}
+
+/// Orders `requested` so dependents are removed before their dependencies.
+///
+/// A requested package that an app staying installed still depends on is skipped, and
+/// that in turn keeps its own dependencies installed. Otherwise the requested order is
+/// kept; packages in a dependency cycle are removed in that order too.
+pub fn plan_uninstall(graph: &DependencyGraph, requested: &[String]) -> UninstallPlan {
+    let mut remaining: Vec<&String> = Vec::new();
+    let mut seen = HashSet::new();
+    for name in requested {
+        if seen.insert(name.to_lowercase()) {
+            remaining.push(name);
+        }
+    }
+
+    // Everything installed that was not requested stays; staying packages can pin more.
+    let mut staying: HashSet<String> = graph
+        .keys()
+        .filter(|name| !seen.contains(*name))
+        .cloned()
+        .collect();
+    let mut skipped = Vec::new();
+    loop {
+        let before = remaining.len();
+        remaining.retain(|name| {
+            let required_by = dependents_of(graph, name, &staying);
+            if required_by.is_empty() {
+                return true;
+            }
+            staying.insert(name.to_lowercase());
+            skipped.push(SkippedUninstall {
+                package: (*name).clone(),
+                required_by,
+            });
+            false
+        });
+        if remaining.len() == before {
+            break;
+        }
+    }
+
+    let mut order = Vec::new();
+    while !remaining.is_empty() {
+        let pending: HashSet<String> = remaining.iter().map(|n| n.to_lowercase()).collect();
+        let next = remaining
+            .iter()
+            .position(|name| {
+                dependents_of(graph, name, &pending)
+                    .iter()
+                    .all(|dependent| *dependent == name.to_lowercase())
+            })
+            .unwrap_or(0);
+        order.push(remaining.remove(next).clone());
+    }
+
+    UninstallPlan { order, skipped }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &[&str])]) -> DependencyGraph {
+        edges
+            .iter()
+            .map(|(name, deps)| {
+                (
+                    name.to_string(),
+                    deps.iter().map(|d| d.to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn removes_dependents_first_and_keeps_shared_dependencies() {
+        let graph = graph(&[
+            ("app", &["lib", "runtime"]),
+            ("lib", &["runtime"]),
+            ("runtime", &[]),
+            ("other", &["runtime"]),
+        ]);
+
+        let plan = plan_uninstall(&graph, &names(&["runtime", "lib", "app"]));
+        assert_eq!(plan.order, names(&["app", "lib"]));
+        assert_eq!(plan.skipped.len(), 1);
+        assert_eq!(plan.skipped[0].package, "runtime");
+        assert_eq!(plan.skipped[0].required_by, names(&["other"]));
+
+        let plan = plan_uninstall(&graph, &names(&["lib", "other"]));
+        assert_eq!(plan.order, names(&["other"]));
+        assert_eq!(plan.skipped[0].required_by, names(&["app"]));
+    }
+
+    #[test]
+    fn reads_depends_in_both_forms() {
+        let single = serde_json::json!({ "depends": "extras/Lessmsi" });
+        let many = serde_json::json!({ "depends": ["7zip", "main/dark"] });
+        assert_eq!(manifest_dependencies(&single), names(&["lessmsi"]));
+        assert_eq!(manifest_dependencies(&many), names(&["7zip", "dark"]));
+    }
+}
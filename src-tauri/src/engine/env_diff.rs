@@ -0,0 +1,180 @@
+//! Differences between two snapshots of the persistent environment variables.
+//!
+//! Scoop adds to `PATH` and sets variables such as `JAVA_HOME` when installing apps.
+//! List variables are compared entry by entry, so a change can later be reverted
+//! without discarding entries other programs added in the meantime.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Variables holding `;`-separated lists.
+const LIST_VARS: &[&str] = &["PATH", "PSMODULEPATH", "PATHEXT"];
+
+/// Persistent variables of the user and the machine, by name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnvSnapshot {
+    pub user: BTreeMap<String, String>,
+    pub machine: BTreeMap<String, String>,
+}
+
+/// A variable an operation added, removed or changed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvChange {
+    /// `user` or `machine`.
+    pub scope: String,
+    pub name: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    /// For list variables such as `PATH`, the entries that were added.
+    #[serde(default)]
+    pub added_entries: Vec<String>,
+    #[serde(default)]
+    pub removed_entries: Vec<String>,
+}
+
+fn is_list_var(name: &str) -> bool {
+    LIST_VARS.iter().any(|v| v.eq_ignore_ascii_case(name))
+}
+
+fn entries(value: Option<&str>) -> Vec<&str> {
+    value
+        .unwrap_or_default()
+        .split(';')
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .collect()
+}
+
+fn same_entry(a: &str, b: &str) -> bool {
+    a.trim_end_matches(['\\', '/'])
+        .eq_ignore_ascii_case(b.trim_end_matches(['\\', '/']))
+}
+
+fn contains_entry(list: &[&str], entry: &str) -> bool {
+    list.iter().any(|e| same_entry(e, entry))
+}
+
+fn diff_scope(
+    scope: &str,
+    before: &BTreeMap<String, String>,
+    after: &BTreeMap<String, String>,
+    changes: &mut Vec<EnvChange>,
+) {
+    let mut names: Vec<&String> = before.keys().chain(after.keys()).collect();
+    names.sort();
+    names.dedup();
+    for name in names {
+        let (old, new) = (before.get(name), after.get(name));
+        if old == new {
+            continue;
+        }
+        let (added_entries, removed_entries) = if is_list_var(name) {
+            let (old_entries, new_entries) = (
+                entries(old.map(String::as_str)),
+                entries(new.map(String::as_str)),
+            );
+            (
+                new_entries
+                    .iter()
+                    .filter(|e| !contains_entry(&old_entries, e))
+                    .map(|e| e.to_string())
+                    .collect(),
+                old_entries
+                    .iter()
+                    .filter(|e| !contains_entry(&new_entries, e))
+                    .map(|e| e.to_string())
+                    .collect(),
+            )
+        } else {
+            (Vec::new(), Vec::new())
+        };
+        changes.push(EnvChange {
+            scope: scope.to_string(),
+            name: name.clone(),
+            before: old.cloned(),
+            after: new.cloned(),
+            added_entries,
+            removed_entries,
+        });
+    }
+}
+
+/// Lists every variable that differs between the snapshots.
+pub fn diff(before: &EnvSnapshot, after: &EnvSnapshot) -> Vec<EnvChange> {
+    let mut changes = Vec::new();
+    diff_scope("user", &before.user, &after.user, &mut changes);
+    diff_scope("machine", &before.machine, &after.machine, &mut changes);
+    changes
+}
+
+/// Computes the value that undoes `change` given the variable's current value.
+///
+/// `Ok(None)` means the variable should be deleted. List variables only lose the
+/// entries the operation added and regain the ones it removed; other variables are
+/// only restored while they still hold the value the operation left.
+pub fn revert_value(change: &EnvChange, current: Option<&str>) -> Result<Option<String>, String> {
+    if is_list_var(&change.name) {
+        let current_entries = entries(current);
+        let mut reverted: Vec<&str> = current_entries
+            .iter()
+            .copied()
+            .filter(|e| !change.added_entries.iter().any(|a| same_entry(a, e)))
+            .collect();
+        for removed in &change.removed_entries {
+            if !contains_entry(&reverted, removed) {
+                reverted.push(removed);
+            }
+        }
+        if reverted == current_entries {
+            return Err("already reverted".to_string());
+        }
+        return Ok((!reverted.is_empty() || change.before.is_some()).then(|| reverted.join(";")));
+    }
+
+    if current == change.before.as_deref() {
+        Err("already reverted".to_string())
+    } else if current == change.after.as_deref() {
+        Ok(change.before.clone())
+    } else {
+        Err("changed since the operation".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(vars: &[(&str, &str)]) -> EnvSnapshot {
+        EnvSnapshot {
+            user: vars
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            machine: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn diffs_and_reverts_path_entries() {
+        let before = snapshot(&[("Path", r"C:\a;C:\scoop\shims")]);
+        let after = snapshot(&[
+            ("Path", r"C:\a;C:\scoop\shims;C:\scoop\apps\jdk\current\bin"),
+            ("JAVA_HOME", r"C:\scoop\apps\jdk\current"),
+        ]);
+        let changes = diff(&before, &after);
+        assert_eq!(changes.len(), 2);
+        let java = changes.iter().find(|c| c.name == "JAVA_HOME").unwrap();
+        let path = changes.iter().find(|c| c.name == "Path").unwrap();
+        assert_eq!(path.added_entries, vec![r"C:\scoop\apps\jdk\current\bin"]);
+
+        // Another entry was added after the install and must survive the revert
+        let current = r"C:\a;C:\scoop\shims;C:\scoop\apps\jdk\current\bin\;C:\other";
+        assert_eq!(
+            revert_value(path, Some(current)).unwrap().as_deref(),
+            Some(r"C:\a;C:\scoop\shims;C:\other")
+        );
+        assert_eq!(revert_value(java, java.after.as_deref()).unwrap(), None);
+        assert!(revert_value(java, None).is_err());
+        assert!(revert_value(java, Some(r"D:\jdk")).is_err());
+    }
+}
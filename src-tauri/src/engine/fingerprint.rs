@@ -0,0 +1,203 @@
+//! Fingerprinting of installed apps, used to tell whether cached scans are stale.
+use crate::commands::quarantine::is_quarantined;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Helper to get modification time of a path (file or directory) in milliseconds.
+pub fn get_path_modification_time(path: &Path) -> u128 {
+    fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis())
+        .unwrap_or(0)
+}
+
+/// Helper to get modification time of an installation directory.
+/// Checks install.json, then manifest.json, then the directory itself.
+pub fn get_install_modification_time(install_dir: &Path) -> u128 {
+    let install_manifest = install_dir.join("install.json");
+    let manifest_path = install_dir.join("manifest.json");
+
+    fs::metadata(&install_manifest)
+        .or_else(|_| fs::metadata(&manifest_path))
+        .or_else(|_| fs::metadata(install_dir))
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis())
+        .unwrap_or(0)
+}
+
+/// Returns the most recently updated version directory for a package when the
+/// `current` link is missing.
+pub fn find_latest_version_dir(package_path: &Path) -> Option<PathBuf> {
+    let mut candidates: Vec<(u128, PathBuf)> = Vec::new();
+
+    log::info!(
+        "Finding latest version directory for package: {}",
+        package_path.display()
+    );
+
+    if let Ok(entries) = fs::read_dir(package_path) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+
+            if !path.is_dir() || is_quarantined(&path) {
+                continue;
+            }
+
+            if path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.eq_ignore_ascii_case("current"))
+                .unwrap_or(false)
+            {
+                continue;
+            }
+
+            let install_manifest = path.join("install.json");
+            let manifest_path = path.join("manifest.json");
+
+            if !install_manifest.exists() && !manifest_path.exists() {
+                continue;
+            }
+
+            let modified = fs::metadata(&install_manifest)
+                .or_else(|_| fs::metadata(&manifest_path))
+                .or_else(|_| fs::metadata(&path))
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_millis())
+                .unwrap_or(0);
+
+            candidates.push((modified, path));
+        }
+    }
+
+    candidates.sort_by(|a, b| b.0.cmp(&a.0));
+    let result = candidates.into_iter().map(|(_, path)| path).next();
+    log::info!(
+        "Latest version directory found: {:?}",
+        result.as_ref().map(|p| p.display().to_string())
+    );
+    result
+}
+
+/// Returns the `current` directory of an app, or its newest version directory.
+pub fn locate_install_dir(package_path: &Path) -> Option<PathBuf> {
+    let current_path = package_path.join("current");
+    log::debug!(
+        "Locating install directory for package: {}, checking current path: {}",
+        package_path.display(),
+        current_path.display()
+    );
+
+    if current_path.is_dir() {
+        log::debug!("Found current directory: {}", current_path.display());
+        Some(current_path)
+    } else {
+        log::debug!("Current directory not found, searching for latest version directory");
+        find_latest_version_dir(package_path)
+    }
+}
+
+/// Builds a fingerprint of the app directories that changes whenever an app is
+/// installed, removed or updated.
+pub fn compute_apps_fingerprint(app_dirs: &[PathBuf]) -> String {
+    log::debug!(
+        "Computing apps fingerprint for {} app directories",
+        app_dirs.len()
+    );
+    let entries: Vec<String> = app_dirs
+        .iter()
+        .filter_map(|path| {
+            path.file_name().and_then(|n| n.to_str()).map(|name| {
+                let modified_stamp = locate_install_dir(path)
+                    .map(|install_dir| get_install_modification_time(&install_dir))
+                    .unwrap_or_else(|| get_path_modification_time(path));
+
+                format!("{}:{}", name.to_ascii_lowercase(), modified_stamp)
+            })
+        })
+        .collect();
+
+    let mut sorted_entries = entries;
+    sorted_entries.sort();
+    let fingerprint = format!("{}|{}", app_dirs.len(), sorted_entries.join(";"));
+    log::debug!("Computed apps fingerprint: {}", fingerprint);
+    fingerprint
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    fn make_version(app_dir: &Path, version: &str) -> PathBuf {
+        let dir = app_dir.join(version);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("manifest.json"),
+            format!(r#"{{"version": "{}"}}"#, version),
+        )
+        .unwrap();
+        fs::write(dir.join("install.json"), r#"{"bucket": "main"}"#).unwrap();
+        dir
+    }
+
+    fn set_mtime(path: &Path, secs_ago: u64) {
+        let file = fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(secs_ago))
+            .unwrap();
+    }
+
+    #[test]
+    fn latest_version_dir_uses_install_time() {
+        let tmp = tempfile::tempdir().unwrap();
+        let old = make_version(tmp.path(), "1.0");
+        let new = make_version(tmp.path(), "2.0");
+        set_mtime(&old.join("install.json"), 10);
+        set_mtime(&new.join("install.json"), 1000);
+
+        assert_eq!(find_latest_version_dir(tmp.path()), Some(old));
+    }
+
+    #[test]
+    fn latest_version_dir_skips_incomplete_and_quarantined_dirs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let good = make_version(tmp.path(), "1.0");
+        fs::create_dir_all(tmp.path().join("2.0")).unwrap();
+        make_version(tmp.path(), "3.0.quarantined-1");
+
+        assert_eq!(find_latest_version_dir(tmp.path()), Some(good));
+    }
+
+    #[test]
+    fn install_dir_prefers_current() {
+        let tmp = tempfile::tempdir().unwrap();
+        make_version(tmp.path(), "1.0");
+        let current = make_version(tmp.path(), "current");
+
+        assert_eq!(locate_install_dir(tmp.path()), Some(current));
+    }
+
+    #[test]
+    fn fingerprint_ignores_order_and_tracks_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let git = tmp.path().join("git");
+        let node = tmp.path().join("nodejs");
+        make_version(&git, "current");
+        make_version(&node, "current");
+
+        let forward = compute_apps_fingerprint(&[git.clone(), node.clone()]);
+        let backward = compute_apps_fingerprint(&[node.clone(), git.clone()]);
+        assert_eq!(forward, backward);
+        assert!(forward.starts_with("2|"));
+
+        set_mtime(&git.join("current").join("install.json"), 5000);
+        assert_ne!(compute_apps_fingerprint(&[git, node]), forward);
+    }
+}
@@ -0,0 +1,94 @@
+//! Matching packages against the user's ignore patterns.
+//!
+//! Patterns use `*` and `?` wildcards and match package names case-insensitively,
+//! optionally only within one bucket.
+use crate::models::IgnorePattern;
+use regex::Regex;
+
+/// Ignore patterns with their wildcards compiled.
+#[derive(Debug, Default)]
+pub struct IgnoreRules {
+    rules: Vec<(Regex, Option<String>)>,
+}
+
+/// Converts a wildcard pattern into an anchored, case-insensitive regex.
+fn wildcard_regex(pattern: &str) -> Result<Regex, String> {
+    let mut regex = String::from("(?i)^");
+    for c in pattern.trim().chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).map_err(|e| format!("Invalid ignore pattern '{}': {}", pattern, e))
+}
+
+impl IgnoreRules {
+    /// Compiles the enabled patterns, failing on the first invalid one.
+    pub fn compile(patterns: &[IgnorePattern]) -> Result<Self, String> {
+        let rules = patterns
+            .iter()
+            .filter(|p| p.enabled)
+            .map(|p| {
+                if p.package.trim().is_empty() {
+                    return Err("Ignore pattern has no package pattern".to_string());
+                }
+                let bucket = p
+                    .bucket
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|b| !b.is_empty())
+                    .map(String::from);
+                Ok((wildcard_regex(&p.package)?, bucket))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns `true` if `package` from `bucket` is hidden by any pattern.
+    pub fn is_ignored(&self, package: &str, bucket: &str) -> bool {
+        self.rules.iter().any(|(regex, only_bucket)| {
+            only_bucket
+                .as_deref()
+                .is_none_or(|b| b.eq_ignore_ascii_case(bucket))
+                && regex.is_match(package)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(package: &str, bucket: Option<&str>) -> IgnorePattern {
+        IgnorePattern {
+            package: package.to_string(),
+            bucket: bucket.map(String::from),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn matches_wildcards_and_buckets() {
+        let rules = IgnoreRules::compile(&[
+            pattern("*-np", None),
+            pattern("*", Some("Noisy")),
+            pattern("node?", Some("versions")),
+        ])
+        .unwrap();
+
+        assert!(rules.is_ignored("vlc-np", "extras"));
+        assert!(!rules.is_ignored("vlc", "extras"));
+        assert!(rules.is_ignored("anything", "noisy"));
+        assert!(rules.is_ignored("Node8", "versions"));
+        assert!(!rules.is_ignored("node18", "versions"));
+        assert!(!rules.is_ignored("node8", "main"));
+        assert!(IgnoreRules::compile(&[pattern(" ", None)]).is_err());
+    }
+}
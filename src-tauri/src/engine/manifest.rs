@@ -0,0 +1,228 @@
+//! Locating package manifests inside Scoop buckets.
+use std::path::{Path, PathBuf};
+
+/// Find every bucket that provides a manifest for `package_name`.
+///
+/// Results are sorted by bucket name so callers get a deterministic order.
+pub fn find_manifest_candidates(scoop_dir: &Path, package_name: &str) -> Vec<(PathBuf, String)> {
+    let manifest_filename = format!("{}.json", package_name);
+
+    let mut candidates: Vec<(PathBuf, String)> = std::fs::read_dir(scoop_dir.join("buckets"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| {
+                    let bucket_path = entry.path();
+                    let bucket_name = entry.file_name().to_string_lossy().to_string();
+                    [
+                        bucket_path.join(&manifest_filename),
+                        bucket_path.join("bucket").join(&manifest_filename),
+                    ]
+                    .into_iter()
+                    .find(|path| path.exists())
+                    .map(|path| (path, bucket_name))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    candidates.sort_by(|a, b| a.1.to_lowercase().cmp(&b.1.to_lowercase()));
+    candidates
+}
+
+/// Locate a manifest file for `package_name` within the Scoop buckets.
+///
+/// If `package_source` is supplied it will be treated as an exact bucket name
+/// and that bucket is inspected first. Otherwise all buckets are searched and
+/// the first match in bucket name order is returned, so the result does not
+/// depend on directory iteration order.
+///
+/// The returned tuple contains the fully qualified path to the manifest file
+/// and the bucket name the manifest originated from.
+///
+/// # Errors
+/// Propagates any I/O failure and returns a domain-specific error when the
+/// manifest cannot be located.
+pub fn locate_package_manifest(
+    scoop_dir: &Path,
+    package_name: &str,
+    package_source: Option<String>,
+) -> Result<(PathBuf, String), String> {
+    locate_package_manifest_impl(scoop_dir, package_name, package_source)
+}
+
+// Internal implementation that contains the previous logic. This avoids code
+// duplication while giving us the opportunity to phase out the old API.
+fn locate_package_manifest_impl(
+    scoop_dir: &Path,
+    package_name: &str,
+    package_source: Option<String>,
+) -> Result<(PathBuf, String), String> {
+    let buckets_dir = scoop_dir.join("buckets");
+
+    let search_buckets = |bucket_path: PathBuf| -> Result<(PathBuf, String), String> {
+        if bucket_path.is_dir() {
+            let bucket_name = bucket_path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .to_string();
+
+            let manifest_filename = format!("{}.json", package_name);
+
+            let manifest_path = bucket_path.join(&manifest_filename);
+            if manifest_path.exists() {
+                return Ok((manifest_path, bucket_name));
+            }
+
+            let nested_manifest_path = bucket_path.join("bucket").join(&manifest_filename);
+            if nested_manifest_path.exists() {
+                return Ok((nested_manifest_path, bucket_name));
+            }
+        }
+        Err(format!("Package '{}' not found.", package_name))
+    };
+
+    // 1. Try to find in specific bucket if provided
+    if let Some(source) = &package_source {
+        if !source.is_empty() && source != "None" && buckets_dir.is_dir() {
+            let specific_bucket_path = buckets_dir.join(source);
+            if let Ok(found) = search_buckets(specific_bucket_path) {
+                return Ok(found);
+            }
+        }
+    }
+
+    // 2. Search all buckets
+    if let Some(found) = find_manifest_candidates(scoop_dir, package_name)
+        .into_iter()
+        .next()
+    {
+        return Ok(found);
+    }
+
+    // 3. Check installed apps if not found in buckets
+    let installed_manifest_path = scoop_dir
+        .join("apps")
+        .join(package_name)
+        .join("current")
+        .join("manifest.json");
+
+    if installed_manifest_path.exists() {
+        // Try to read install.json to get the original bucket name if possible
+        let install_json_path = scoop_dir
+            .join("apps")
+            .join(package_name)
+            .join("current")
+            .join("install.json");
+
+        let mut bucket_name = "Installed (Bucket missing)".to_string();
+
+        if install_json_path.exists() {
+            if let Ok(content) = std::fs::read_to_string(install_json_path) {
+                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                    if let Some(bucket) = json.get("bucket").and_then(|b| b.as_str()) {
+                        bucket_name = format!("{} (missing)", bucket);
+                    }
+                }
+            }
+        }
+
+        return Ok((installed_manifest_path, bucket_name));
+    }
+
+    if let Some(source) = package_source {
+        if !source.is_empty() && source != "None" {
+            return Err(format!(
+                "Package '{}' not found in bucket '{}'.",
+                package_name, source
+            ));
+        }
+    }
+
+    Err(format!(
+        "Package '{}' not found in any bucket.",
+        package_name
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write_manifest(path: PathBuf, version: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, format!(r#"{{"version": "{}"}}"#, version)).unwrap();
+    }
+
+    #[test]
+    fn candidates_are_sorted_by_bucket_name() {
+        let tmp = tempfile::tempdir().unwrap();
+        let buckets = tmp.path().join("buckets");
+        write_manifest(buckets.join("zeta").join("bucket").join("git.json"), "1.0");
+        write_manifest(buckets.join("Extras").join("git.json"), "1.0");
+        write_manifest(buckets.join("main").join("bucket").join("git.json"), "1.0");
+
+        let names: Vec<String> = find_manifest_candidates(tmp.path(), "git")
+            .into_iter()
+            .map(|(_, bucket)| bucket)
+            .collect();
+        assert_eq!(names, vec!["Extras", "main", "zeta"]);
+    }
+
+    #[test]
+    fn root_manifest_is_preferred_over_nested_one() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bucket = tmp.path().join("buckets").join("main");
+        write_manifest(bucket.join("git.json"), "1.0");
+        write_manifest(bucket.join("bucket").join("git.json"), "2.0");
+
+        let candidates = find_manifest_candidates(tmp.path(), "git");
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].0, bucket.join("git.json"));
+    }
+
+    #[test]
+    fn locate_honors_requested_bucket() {
+        let tmp = tempfile::tempdir().unwrap();
+        let buckets = tmp.path().join("buckets");
+        write_manifest(
+            buckets.join("extras").join("bucket").join("git.json"),
+            "1.0",
+        );
+        write_manifest(buckets.join("main").join("bucket").join("git.json"), "1.0");
+
+        let (_, bucket) =
+            locate_package_manifest(tmp.path(), "git", Some("main".to_string())).unwrap();
+        assert_eq!(bucket, "main");
+
+        let (_, bucket) = locate_package_manifest(tmp.path(), "git", None).unwrap();
+        assert_eq!(bucket, "extras");
+    }
+
+    #[test]
+    fn locate_falls_back_to_installed_manifest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let current = tmp.path().join("apps").join("git").join("current");
+        write_manifest(current.join("manifest.json"), "1.0");
+        fs::write(current.join("install.json"), r#"{"bucket": "gone"}"#).unwrap();
+
+        let (path, bucket) = locate_package_manifest(tmp.path(), "git", None).unwrap();
+        assert_eq!(path, current.join("manifest.json"));
+        assert_eq!(bucket, "gone (missing)");
+    }
+
+    #[test]
+    fn locate_reports_missing_packages() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(tmp.path().join("buckets").join("main")).unwrap();
+
+        let err =
+            locate_package_manifest(tmp.path(), "nope", Some("main".to_string())).unwrap_err();
+        assert!(err.contains("not found in bucket 'main'"));
+        let err = locate_package_manifest(tmp.path(), "nope", None).unwrap_err();
+        assert!(err.contains("not found in any bucket"));
+    }
+}
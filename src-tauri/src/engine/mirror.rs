@@ -0,0 +1,112 @@
+//! Download URL rewriting for mirrors.
+//!
+//! Rules are tried in order and the first enabled rule whose pattern matches rewrites
+//! the URL; later rules are not applied to the result.
+use crate::models::MirrorRule;
+use regex::Regex;
+use serde_json::Value;
+
+/// Mirror rules with their patterns compiled.
+#[derive(Debug, Default)]
+pub struct MirrorRules {
+    rules: Vec<(Regex, String)>,
+}
+
+impl MirrorRules {
+    /// Compiles the enabled rules, failing on the first invalid pattern.
+    pub fn compile(rules: &[MirrorRule]) -> Result<Self, String> {
+        let rules = rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .map(|rule| {
+                Regex::new(&rule.pattern)
+                    .map(|regex| (regex, rule.replacement.clone()))
+                    .map_err(|e| format!("Invalid mirror pattern '{}': {}", rule.pattern, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns the rewritten URL, or `None` if no rule matches.
+    pub fn rewrite(&self, url: &str) -> Option<String> {
+        self.rules
+            .iter()
+            .find(|(regex, _)| regex.is_match(url))
+            .map(|(regex, replacement)| regex.replace(url, replacement.as_str()).into_owned())
+    }
+}
+
+/// Lists the URLs Scoop would download for `arch`, following its rule that an
+/// architecture-specific `url` replaces the top-level one.
+pub fn download_urls(manifest: &Value, arch: &str) -> Vec<String> {
+    let url_value = manifest
+        .get("architecture")
+        .and_then(|arches| arches.get(arch))
+        .and_then(|entry| entry.get("url"))
+        .or_else(|| manifest.get("url"));
+
+    match url_value {
+        Some(Value::String(url)) => vec![url.clone()],
+        Some(Value::Array(list)) => list
+            .iter()
+            .filter_map(Value::as_str)
+            .map(String::from)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, replacement: &str, enabled: bool) -> MirrorRule {
+        MirrorRule {
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            enabled,
+        }
+    }
+
+    #[test]
+    fn first_enabled_matching_rule_wins() {
+        let rules = MirrorRules::compile(&[
+            rule("^https://github\\.com/", "https://disabled.example/", false),
+            rule(
+                "^https://github\\.com/(.+/releases/download/.+)$",
+                "https://ghproxy.example/https://github.com/$1",
+                true,
+            ),
+            rule("^https://github\\.com/", "https://other.example/", true),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            rules
+                .rewrite("https://github.com/o/r/releases/download/v1/app.zip")
+                .as_deref(),
+            Some("https://ghproxy.example/https://github.com/o/r/releases/download/v1/app.zip")
+        );
+        assert_eq!(rules.rewrite("https://example.com/app.zip"), None);
+        assert!(MirrorRules::compile(&[rule("(", "", true)]).is_err());
+    }
+
+    #[test]
+    fn architecture_url_replaces_top_level_url() {
+        let manifest = serde_json::json!({
+            "url": "https://example.com/generic.zip",
+            "architecture": {
+                "64bit": { "url": ["https://example.com/a.zip", "https://example.com/b.zip"] }
+            }
+        });
+        assert_eq!(download_urls(&manifest, "64bit").len(), 2);
+        assert_eq!(
+            download_urls(&manifest, "32bit"),
+            vec!["https://example.com/generic.zip"]
+        );
+    }
+}
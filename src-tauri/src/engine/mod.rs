@@ -0,0 +1,16 @@
+//! Tauri-independent core logic.
+//!
+//! Functions here take paths and configuration as plain arguments instead of reading
+//! `AppState` or the settings store, so they can be unit tested against fixture Scoop
+//! trees. Tauri commands are thin wrappers that gather inputs and call into this module.
+pub mod cache_import;
+pub mod dependencies;
+pub mod env_diff;
+pub mod fingerprint;
+pub mod ignore;
+pub mod manifest;
+pub mod mirror;
+pub mod root;
+pub mod update_plan;
+pub mod version;
+pub mod zsync;
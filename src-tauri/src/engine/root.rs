@@ -0,0 +1,214 @@
+//! Scoring of candidate Scoop root directories.
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A directory that looks like a Scoop root, with the evidence used to score it.
+#[derive(Debug, Clone)]
+pub struct RootCandidate {
+    pub path: PathBuf,
+    pub score: u32,
+    pub installed_count: usize,
+    pub has_apps_dir: bool,
+    pub has_buckets_dir: bool,
+}
+
+/// Scores a directory as a potential Scoop root.
+///
+/// Returns `None` when the directory has neither an `apps` nor a `buckets` directory.
+pub fn evaluate_candidate(path: &Path) -> Option<RootCandidate> {
+    log::info!("Evaluating Scoop candidate: {}", path.display());
+
+    if !path.is_dir() {
+        log::info!("Candidate path is not a directory");
+        return None;
+    }
+
+    let apps_dir = path.join("apps");
+    let buckets_dir = path.join("buckets");
+    let has_apps_dir = apps_dir.is_dir();
+    let has_buckets_dir = buckets_dir.is_dir();
+
+    log::info!(
+        "Candidate evaluation - apps_dir: {} ({}), buckets_dir: {} ({})",
+        apps_dir.display(),
+        has_apps_dir,
+        buckets_dir.display(),
+        has_buckets_dir
+    );
+
+    if !has_apps_dir && !has_buckets_dir {
+        log::info!("Candidate rejected - missing both apps and buckets directories");
+        return None;
+    }
+
+    let installed_count = if has_apps_dir {
+        match fs::read_dir(&apps_dir) {
+            Ok(entries) => {
+                let count = entries
+                    .filter_map(Result::ok)
+                    .filter(|entry| entry.path().is_dir())
+                    .take(200)
+                    .count();
+                log::info!("Found {} installed apps in apps directory", count);
+                count
+            }
+            Err(e) => {
+                log::warn!("Failed to read apps directory: {}", e);
+                0
+            }
+        }
+    } else {
+        log::info!("No apps directory found");
+        0
+    };
+
+    let mut score = 0;
+    if has_buckets_dir {
+        score += 10;
+        log::info!("+10 points for having buckets directory");
+    }
+    if has_apps_dir {
+        score += 30;
+        log::info!("+30 points for having apps directory");
+    }
+    score += installed_count.min(50) as u32;
+    log::info!(
+        "+{} points for installed apps (capped at 50)",
+        installed_count.min(50)
+    );
+
+    log::info!("Total score for candidate {}: {}", path.display(), score);
+
+    Some(RootCandidate {
+        path: path.to_path_buf(),
+        score,
+        installed_count,
+        has_apps_dir,
+        has_buckets_dir,
+    })
+}
+
+/// Picks the highest scoring candidate. The preferred path gets a small bonus and ties
+/// are broken by the number of installed apps.
+pub fn select_best_root(
+    candidates: Vec<PathBuf>,
+    preferred: Option<&PathBuf>,
+) -> Option<RootCandidate> {
+    log::info!(
+        "Selecting best Scoop root from {} candidates",
+        candidates.len()
+    );
+    let mut best: Option<RootCandidate> = None;
+
+    for candidate in candidates {
+        let candidate_display = candidate.display().to_string();
+        let evaluated_candidate = evaluate_candidate(&candidate);
+
+        if evaluated_candidate.is_some() {
+            let mut info = evaluated_candidate.unwrap();
+
+            if preferred.is_some() && preferred.unwrap().eq(&info.path) {
+                info.score += 5;
+                log::info!("+5 points for being the preferred path");
+            }
+
+            log::debug!(
+                "Scored potential Scoop root {} => score {} (apps_dir={}, buckets_dir={}, installed={})",
+                info.path.display(),
+                info.score,
+                info.has_apps_dir,
+                info.has_buckets_dir,
+                info.installed_count
+            );
+
+            let replace = if best.is_some() {
+                let current = best.as_ref().unwrap();
+                let should_replace = info.score > current.score
+                    || (info.score == current.score
+                        && info.installed_count > current.installed_count);
+                log::debug!("Comparing with current best - current score: {}, candidate score: {}, replace: {}", 
+                           current.score, info.score, should_replace);
+                should_replace
+            } else {
+                true // No current best, always replace
+            };
+
+            if replace {
+                log::info!(
+                    "Setting new best candidate: {} with score {}",
+                    info.path.display(),
+                    info.score
+                );
+                best = Some(info);
+            }
+        } else {
+            log::debug!("Skipping invalid candidate: {}", candidate_display);
+        }
+    }
+
+    log::info!(
+        "Best Scoop root selection completed. Best candidate: {:?}",
+        best.as_ref().map(|b| b.path.display().to_string())
+    );
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_root(dir: &Path, apps: &[&str], with_buckets: bool) -> PathBuf {
+        fs::create_dir_all(dir.join("apps")).unwrap();
+        for app in apps {
+            fs::create_dir_all(dir.join("apps").join(app)).unwrap();
+        }
+        if with_buckets {
+            fs::create_dir_all(dir.join("buckets").join("main")).unwrap();
+        }
+        dir.to_path_buf()
+    }
+
+    #[test]
+    fn rejects_directories_without_apps_or_buckets() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(evaluate_candidate(tmp.path()).is_none());
+        assert!(evaluate_candidate(&tmp.path().join("missing")).is_none());
+    }
+
+    #[test]
+    fn scores_apps_buckets_and_installs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = make_root(tmp.path(), &["git", "7zip"], true);
+
+        let candidate = evaluate_candidate(&root).unwrap();
+        assert!(candidate.has_apps_dir && candidate.has_buckets_dir);
+        assert_eq!(candidate.installed_count, 2);
+        assert_eq!(candidate.score, 10 + 30 + 2);
+    }
+
+    #[test]
+    fn prefers_root_with_more_installs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let small = make_root(&tmp.path().join("small"), &["git"], true);
+        let large = make_root(&tmp.path().join("large"), &["git", "7zip", "nodejs"], true);
+
+        let best = select_best_root(vec![small, large.clone()], None).unwrap();
+        assert_eq!(best.path, large);
+    }
+
+    #[test]
+    fn preferred_root_wins_close_scores() {
+        let tmp = tempfile::tempdir().unwrap();
+        let preferred = make_root(&tmp.path().join("preferred"), &["git"], true);
+        let other = make_root(&tmp.path().join("other"), &["git", "7zip"], true);
+
+        let best = select_best_root(vec![other, preferred.clone()], Some(&preferred)).unwrap();
+        assert_eq!(best.path, preferred);
+    }
+
+    #[test]
+    fn no_valid_candidates_yields_none() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(select_best_root(vec![tmp.path().join("nope")], None).is_none());
+    }
+}
@@ -0,0 +1,116 @@
+//! Deciding which bucket an installed package updates from and whether an update exists.
+use crate::engine::manifest::locate_package_manifest;
+use crate::engine::version::is_update_available;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Deserialize)]
+struct VersionOnly {
+    version: String,
+}
+
+/// Picks the bucket an installed package should update from.
+///
+/// A pinned source always wins. Otherwise the bucket recorded at install time is kept
+/// while it still provides the package, falling back to the first of the
+/// (already prioritized) `candidates`.
+pub fn select_update_source(
+    installed_source: &str,
+    pinned: Option<&String>,
+    candidates: Vec<(PathBuf, String)>,
+) -> String {
+    if let Some(pinned) = pinned {
+        return pinned.clone();
+    }
+    if candidates
+        .iter()
+        .any(|(_, bucket)| bucket.eq_ignore_ascii_case(installed_source))
+    {
+        return installed_source.to_string();
+    }
+    candidates
+        .into_iter()
+        .next()
+        .map(|(_, bucket)| bucket)
+        .unwrap_or_else(|| installed_source.to_string())
+}
+
+/// Reads the manifest version of `package_name` from `source`.
+pub fn read_manifest_version(
+    scoop_dir: &Path,
+    package_name: &str,
+    source: &str,
+) -> Result<String, String> {
+    let (manifest_path, _) =
+        locate_package_manifest(scoop_dir, package_name, Some(source.to_string()))
+            .map_err(|e| format!("Could not locate manifest for {}: {}", package_name, e))?;
+    let content = fs::read_to_string(manifest_path)
+        .map_err(|e| format!("Could not read manifest for {}: {}", package_name, e))?;
+    let manifest: VersionOnly = serde_json::from_str(&content)
+        .map_err(|e| format!("Could not parse manifest for {}: {}", package_name, e))?;
+    Ok(manifest.version)
+}
+
+/// Returns the available version if the manifest in `source` differs from `installed_version`.
+pub fn find_available_update(
+    scoop_dir: &Path,
+    package_name: &str,
+    installed_version: &str,
+    source: &str,
+) -> Result<Option<String>, String> {
+    let latest = read_manifest_version(scoop_dir, package_name, source)?;
+    Ok(is_update_available(installed_version, &latest).then_some(latest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(bucket: &str) -> (PathBuf, String) {
+        (
+            PathBuf::from(format!("{}/pkg.json", bucket)),
+            bucket.to_string(),
+        )
+    }
+
+    #[test]
+    fn pinned_source_wins() {
+        let pinned = "extras".to_string();
+        let source = select_update_source("main", Some(&pinned), vec![candidate("main")]);
+        assert_eq!(source, "extras");
+    }
+
+    #[test]
+    fn keeps_install_bucket_while_it_provides_the_package() {
+        let source =
+            select_update_source("Main", None, vec![candidate("extras"), candidate("main")]);
+        assert_eq!(source, "Main");
+    }
+
+    #[test]
+    fn falls_back_to_first_candidate() {
+        let source =
+            select_update_source("gone", None, vec![candidate("extras"), candidate("main")]);
+        assert_eq!(source, "extras");
+        assert_eq!(select_update_source("gone", None, Vec::new()), "gone");
+    }
+
+    #[test]
+    fn reports_update_from_bucket_manifest() {
+        let root = tempfile::tempdir().unwrap();
+        let bucket = root.path().join("buckets").join("main").join("bucket");
+        fs::create_dir_all(&bucket).unwrap();
+        fs::write(bucket.join("pkg.json"), r#"{"version": "2.0"}"#).unwrap();
+
+        assert_eq!(
+            find_available_update(root.path(), "pkg", "1.0", "main").unwrap(),
+            Some("2.0".to_string())
+        );
+        assert_eq!(
+            find_available_update(root.path(), "pkg", "2.0.0", "main").unwrap(),
+            None
+        );
+        assert!(find_available_update(root.path(), "missing", "1.0", "main").is_err());
+    }
+}
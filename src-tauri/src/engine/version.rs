@@ -0,0 +1,110 @@
+//! Version comparison for Scoop manifest versions.
+//!
+//! Scoop versions are free-form (`1.2.3`, `2024.01.05`, `1.0-beta2`, `nightly`), so they
+//! are split into numeric and textual segments that are compared pairwise.
+use std::cmp::Ordering;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Segment<'a> {
+    Number(u64),
+    Text(&'a str),
+}
+
+/// Splits a version on separators and at digit/letter boundaries.
+fn segments(version: &str) -> Vec<Segment<'_>> {
+    let mut parts = Vec::new();
+    for chunk in version
+        .trim()
+        .split(|c: char| matches!(c, '.' | '-' | '_' | '+'))
+        .filter(|c| !c.is_empty())
+    {
+        let mut start = 0;
+        let bytes = chunk.as_bytes();
+        for i in 1..=bytes.len() {
+            let boundary =
+                i == bytes.len() || bytes[i].is_ascii_digit() != bytes[i - 1].is_ascii_digit();
+            if boundary {
+                let piece = &chunk[start..i];
+                parts.push(match piece.parse::<u64>() {
+                    Ok(n) => Segment::Number(n),
+                    Err(_) => Segment::Text(piece),
+                });
+                start = i;
+            }
+        }
+    }
+    parts
+}
+
+/// Compares two Scoop versions.
+///
+/// Numeric segments compare numerically, so `1.10` is newer than `1.9`. Missing trailing
+/// segments count as zero (`1.2` equals `1.2.0`), while a trailing text segment marks a
+/// pre-release (`1.0-beta` is older than `1.0`).
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let left = segments(a);
+    let right = segments(b);
+
+    for i in 0..left.len().max(right.len()) {
+        let ordering = match (left.get(i), right.get(i)) {
+            (Some(Segment::Number(x)), Some(Segment::Number(y))) => x.cmp(y),
+            (Some(Segment::Text(x)), Some(Segment::Text(y))) => {
+                x.to_lowercase().cmp(&y.to_lowercase())
+            }
+            // Numbers rank above text at the same position (`1.0.1` > `1.0.beta`)
+            (Some(Segment::Number(_)), Some(Segment::Text(_))) => Ordering::Greater,
+            (Some(Segment::Text(_)), Some(Segment::Number(_))) => Ordering::Less,
+            (Some(Segment::Number(x)), None) => x.cmp(&0),
+            (None, Some(Segment::Number(y))) => 0.cmp(y),
+            (Some(Segment::Text(_)), None) => Ordering::Less,
+            (None, Some(Segment::Text(_))) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Returns `true` when the manifest version differs from the installed one.
+///
+/// Like `scoop status`, any difference counts, so downgraded manifests are reported too.
+pub fn is_update_available(installed: &str, latest: &str) -> bool {
+    compare_versions(installed, latest) != Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_segments_compare_numerically() {
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("2.0.0", "10.0.0"), Ordering::Less);
+        assert_eq!(compare_versions("2024.01.05", "2024.1.5"), Ordering::Equal);
+    }
+
+    #[test]
+    fn missing_segments_count_as_zero() {
+        assert_eq!(compare_versions("1.2", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.2", "1.2.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn prereleases_are_older_than_releases() {
+        assert_eq!(compare_versions("1.0-beta", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0-beta2", "1.0-beta10"), Ordering::Less);
+        assert_eq!(compare_versions("1.0-alpha", "1.0-beta"), Ordering::Less);
+        assert_eq!(compare_versions("1.0rc1", "1.0.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn update_detection_matches_scoop_status() {
+        assert!(is_update_available("1.0", "1.1"));
+        assert!(is_update_available("1.1", "1.0"));
+        assert!(!is_update_available("1.0", "1.0.0"));
+        assert!(!is_update_available("nightly", "Nightly"));
+    }
+}
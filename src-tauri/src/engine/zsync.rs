@@ -0,0 +1,363 @@
+//! Reading zsync control files and rebuilding a file from an older local copy.
+//!
+//! A `.zsync` file lists a weak rolling checksum and a truncated MD4 for every block of
+//! the target. Blocks found in the seed (the previous version's archive) are copied
+//! locally and only the remaining byte ranges need to be downloaded. Compressed
+//! targets (`Z-Map2`) are not supported.
+use std::collections::HashMap;
+use std::io::{self, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+/// Parsed `.zsync` control file.
+#[derive(Debug)]
+pub struct ControlFile {
+    pub block_size: usize,
+    pub length: u64,
+    pub rsum_bytes: usize,
+    pub checksum_bytes: usize,
+    blocks: Vec<BlockSum>,
+}
+
+#[derive(Debug)]
+struct BlockSum {
+    weak: u32,
+    strong: Vec<u8>,
+}
+
+impl ControlFile {
+    pub fn parse(data: &[u8]) -> Result<Self, String> {
+        let header_end = data
+            .windows(2)
+            .position(|w| w == b"\n\n")
+            .ok_or("zsync header is not terminated")?;
+        let header = String::from_utf8_lossy(&data[..header_end]);
+
+        let mut block_size = None;
+        let mut length = None;
+        let mut hash_lengths = None;
+        for line in header.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "Blocksize" => block_size = value.parse::<usize>().ok(),
+                "Length" => length = value.parse::<u64>().ok(),
+                "Hash-Lengths" => {
+                    let parts: Vec<usize> = value
+                        .split(',')
+                        .filter_map(|p| p.trim().parse().ok())
+                        .collect();
+                    if let [seq, rsum, checksum] = parts[..] {
+                        hash_lengths = Some((seq, rsum, checksum));
+                    }
+                }
+                "Z-Map2" | "Recompress" => {
+                    return Err("Compressed zsync targets are not supported".to_string())
+                }
+                _ => {}
+            }
+        }
+
+        let block_size = block_size
+            .filter(|&b| b > 0)
+            .ok_or("zsync header has no valid Blocksize")?;
+        let length = length.ok_or("zsync header has no Length")?;
+        let (_, rsum_bytes, checksum_bytes) = hash_lengths.unwrap_or((1, 4, 16));
+        if !(1..=4).contains(&rsum_bytes) || !(1..=16).contains(&checksum_bytes) {
+            return Err(format!(
+                "Unsupported zsync hash lengths {},{}",
+                rsum_bytes, checksum_bytes
+            ));
+        }
+
+        let block_count = length.div_ceil(block_size as u64) as usize;
+        let entry = rsum_bytes + checksum_bytes;
+        let table = &data[header_end + 2..];
+        if block_count
+            .checked_mul(entry)
+            .is_none_or(|needed| table.len() < needed)
+        {
+            return Err("zsync block table is truncated".to_string());
+        }
+        let blocks = table
+            .chunks_exact(entry)
+            .take(block_count)
+            .map(|chunk| BlockSum {
+                weak: chunk[..rsum_bytes]
+                    .iter()
+                    .fold(0u32, |acc, &b| (acc << 8) | b as u32),
+                strong: chunk[rsum_bytes..].to_vec(),
+            })
+            .collect();
+
+        Ok(Self {
+            block_size,
+            length,
+            rsum_bytes,
+            checksum_bytes,
+            blocks,
+        })
+    }
+
+    fn weak_mask(&self) -> u32 {
+        match self.rsum_bytes {
+            4 => u32::MAX,
+            n => (1u32 << (n * 8)) - 1,
+        }
+    }
+
+    /// Finds each target block in `seed`, returning its offset there if present.
+    pub fn match_seed(&self, seed: &[u8]) -> Vec<Option<usize>> {
+        let mut matches = vec![None; self.blocks.len()];
+        let bs = self.block_size;
+        if seed.len() < bs {
+            return matches;
+        }
+
+        let mask = self.weak_mask();
+        let mut by_weak: HashMap<u32, Vec<usize>> = HashMap::new();
+        for (i, block) in self.blocks.iter().enumerate() {
+            by_weak.entry(block.weak).or_default().push(i);
+        }
+
+        let mut pos = 0;
+        let (mut a, mut b) = weak_sum(&seed[..bs]);
+        loop {
+            let mut found = false;
+            if let Some(candidates) = by_weak.get(&((((a as u32) << 16) | b as u32) & mask)) {
+                let strong = md4(&seed[pos..pos + bs]);
+                for &i in candidates {
+                    if matches[i].is_none()
+                        && self.blocks[i].strong[..] == strong[..self.checksum_bytes]
+                    {
+                        matches[i] = Some(pos);
+                        found = true;
+                    }
+                }
+            }
+
+            if found {
+                pos += bs;
+                if pos + bs > seed.len() {
+                    break;
+                }
+                (a, b) = weak_sum(&seed[pos..pos + bs]);
+            } else {
+                if pos + bs >= seed.len() {
+                    break;
+                }
+                let (old, new) = (seed[pos] as u16, seed[pos + bs] as u16);
+                a = a.wrapping_sub(old).wrapping_add(new);
+                b = b
+                    .wrapping_sub((bs as u16).wrapping_mul(old))
+                    .wrapping_add(a);
+                pos += 1;
+            }
+        }
+        matches
+    }
+
+    /// Byte ranges of the target not covered by `matches`, with adjacent blocks merged.
+    pub fn missing_ranges(&self, matches: &[Option<usize>]) -> Vec<Range<u64>> {
+        let mut ranges: Vec<Range<u64>> = Vec::new();
+        for (i, _) in matches.iter().enumerate().filter(|(_, m)| m.is_none()) {
+            let start = (i * self.block_size) as u64;
+            let end = (start + self.block_size as u64).min(self.length);
+            match ranges.last_mut() {
+                Some(last) if last.end == start => last.end = end,
+                _ => ranges.push(start..end),
+            }
+        }
+        ranges
+    }
+
+    /// Writes the blocks matched in `seed` to their places in `out`, leaving the rest of
+    /// the target for the caller to fill in.
+    pub fn write_matched<W: Write + Seek>(
+        &self,
+        seed: &[u8],
+        matches: &[Option<usize>],
+        out: &mut W,
+    ) -> io::Result<()> {
+        for (i, offset) in matches.iter().enumerate() {
+            if let Some(offset) = offset {
+                let start = (i * self.block_size) as u64;
+                let len = (self.block_size as u64).min(self.length - start) as usize;
+                out.seek(SeekFrom::Start(start))?;
+                out.write_all(&seed[*offset..*offset + len])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The rsync-style rolling checksum zsync uses, as its `a` and `b` halves.
+fn weak_sum(block: &[u8]) -> (u16, u16) {
+    let len = block.len();
+    block
+        .iter()
+        .enumerate()
+        .fold((0u16, 0u16), |(a, b), (i, &c)| {
+            (
+                a.wrapping_add(c as u16),
+                b.wrapping_add(((len - i) as u16).wrapping_mul(c as u16)),
+            )
+        })
+}
+
+/// MD4 digest (RFC 1320), the block checksum zsync uses.
+pub fn md4(data: &[u8]) -> [u8; 16] {
+    let mut state: [u32; 4] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64).wrapping_mul(8)).to_le_bytes());
+
+    for chunk in message.chunks_exact(64) {
+        let x: Vec<u32> = chunk
+            .chunks_exact(4)
+            .map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]]))
+            .collect();
+        let [mut a, mut b, mut c, mut d] = state;
+
+        let f = |x: u32, y: u32, z: u32| (x & y) | (!x & z);
+        for &i in &[0, 4, 8, 12] {
+            a = a.wrapping_add(f(b, c, d)).wrapping_add(x[i]).rotate_left(3);
+            d = d
+                .wrapping_add(f(a, b, c))
+                .wrapping_add(x[i + 1])
+                .rotate_left(7);
+            c = c
+                .wrapping_add(f(d, a, b))
+                .wrapping_add(x[i + 2])
+                .rotate_left(11);
+            b = b
+                .wrapping_add(f(c, d, a))
+                .wrapping_add(x[i + 3])
+                .rotate_left(19);
+        }
+
+        let g = |x: u32, y: u32, z: u32| (x & y) | (x & z) | (y & z);
+        let k2 = 0x5a82_7999u32;
+        for &i in &[0, 1, 2, 3] {
+            a = a
+                .wrapping_add(g(b, c, d))
+                .wrapping_add(x[i])
+                .wrapping_add(k2)
+                .rotate_left(3);
+            d = d
+                .wrapping_add(g(a, b, c))
+                .wrapping_add(x[i + 4])
+                .wrapping_add(k2)
+                .rotate_left(5);
+            c = c
+                .wrapping_add(g(d, a, b))
+                .wrapping_add(x[i + 8])
+                .wrapping_add(k2)
+                .rotate_left(9);
+            b = b
+                .wrapping_add(g(c, d, a))
+                .wrapping_add(x[i + 12])
+                .wrapping_add(k2)
+                .rotate_left(13);
+        }
+
+        let h = |x: u32, y: u32, z: u32| x ^ y ^ z;
+        let k3 = 0x6ed9_eba1u32;
+        for &i in &[0, 2, 1, 3] {
+            a = a
+                .wrapping_add(h(b, c, d))
+                .wrapping_add(x[i])
+                .wrapping_add(k3)
+                .rotate_left(3);
+            d = d
+                .wrapping_add(h(a, b, c))
+                .wrapping_add(x[i + 8])
+                .wrapping_add(k3)
+                .rotate_left(9);
+            c = c
+                .wrapping_add(h(d, a, b))
+                .wrapping_add(x[i + 4])
+                .wrapping_add(k3)
+                .rotate_left(11);
+            b = b
+                .wrapping_add(h(c, d, a))
+                .wrapping_add(x[i + 12])
+                .wrapping_add(k3)
+                .rotate_left(15);
+        }
+
+        for (s, v) in state.iter_mut().zip([a, b, c, d]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+
+    let mut digest = [0u8; 16];
+    for (out, word) in digest.chunks_exact_mut(4).zip(state) {
+        out.copy_from_slice(&word.to_le_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn control_for(target: &[u8], block_size: usize) -> Vec<u8> {
+        let mut data = format!(
+            "zsync: 0.6.2\nBlocksize: {}\nLength: {}\nHash-Lengths: 1,4,16\n\n",
+            block_size,
+            target.len()
+        )
+        .into_bytes();
+        for block in target.chunks(block_size) {
+            let mut padded = block.to_vec();
+            padded.resize(block_size, 0);
+            let (a, b) = weak_sum(&padded);
+            data.extend_from_slice(&a.to_be_bytes());
+            data.extend_from_slice(&b.to_be_bytes());
+            data.extend_from_slice(&md4(&padded));
+        }
+        data
+    }
+
+    #[test]
+    fn computes_md4() {
+        let hex = |d: [u8; 16]| d.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+        assert_eq!(hex(md4(b"")), "31d6cfe0d16ae931b73c59d7e0c089c0");
+        assert_eq!(hex(md4(b"abc")), "a448017aaf21d8525fc10ae87aa6729d");
+    }
+
+    #[test]
+    fn rebuilds_target_from_shifted_seed() {
+        let old: Vec<u8> = (0..4096u32).map(|i| (i * 7 % 251) as u8).collect();
+        let mut new = b"new header".to_vec();
+        new.extend_from_slice(&old[..3000]);
+        new.extend_from_slice(b"changed tail");
+
+        let control = ControlFile::parse(&control_for(&new, 64)).unwrap();
+        let matches = control.match_seed(&old);
+        let missing = control.missing_ranges(&matches);
+        let missing_bytes: u64 = missing.iter().map(|r| r.end - r.start).sum();
+        assert!(missing_bytes < new.len() as u64 / 4);
+
+        let mut rebuilt = std::io::Cursor::new(vec![0u8; new.len()]);
+        control.write_matched(&old, &matches, &mut rebuilt).unwrap();
+        let mut rebuilt = rebuilt.into_inner();
+        for range in missing {
+            let (s, e) = (range.start as usize, range.end as usize);
+            rebuilt[s..e].copy_from_slice(&new[s..e]);
+        }
+        assert_eq!(rebuilt, new);
+    }
+
+    #[test]
+    fn rejects_block_tables_that_overflow() {
+        let control = b"zsync: 0.6.2\nBlocksize: 1\nLength: 18446744073709551615\n\n";
+        assert!(ControlFile::parse(control).is_err());
+    }
+}
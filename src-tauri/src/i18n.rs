@@ -93,7 +93,11 @@ fn get_default_tray_strings() -> serde_json::Value {
         "notificationTitle": "Pailer - Minimized to Tray",
         "notificationMessage": "Pailer has been minimized to the system tray and will continue running in the background.\n\nYou can:\n• Click the tray icon to restore the window\n• Right-click the tray icon to access the context menu\n• Change this behavior in Settings > Window Behavior\n\nWhat would you like to do?",
         "closeAndDisable": "Close and Disable Tray",
-        "keepInTray": "Keep in Tray"
+        "keepInTray": "Keep in Tray",
+        "globalApp": "(global)",
+        "checkUpdates": "Check for Updates",
+        "updateAll": "Update All Packages",
+        "updatesAvailable": "{count} updates available"
     })
 }
 
@@ -1,12 +1,28 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+#[doc(hidden)]
+pub mod bench;
 mod cold_start;
 mod commands;
+mod crash;
+mod engine;
+mod env_report;
+mod http;
+mod metrics;
 mod models;
+mod policy;
+mod portable;
+mod quiet;
+mod recycle;
+mod scan_pool;
 mod scheduler;
 mod state;
+mod supervisor;
 mod tray;
 pub mod utils;
+mod window_state;
 mod i18n;
+#[cfg(test)]
+mod test_support;
 
 use crate::commands::settings::detect_scoop_path;
 use std::path::PathBuf;
@@ -17,7 +33,39 @@ use tauri_plugin_log::{Target, TargetKind};
 mod config_keys {
     pub const WINDOW_CLOSE_TO_TRAY: &str = "window.closeToTray";
     pub const WINDOW_FIRST_TRAY_NOTIFICATION_SHOWN: &str = "window.firstTrayNotificationShown";
+    pub const WINDOW_STATES: &str = "window.states";
     pub const TRAY_APPS_LIST: &str = "tray.appsList";
+    pub const TRAY_MAX_APPS: &str = "tray.maxApps";
+    pub const TRAY_SORT_ORDER: &str = "tray.sortOrder";
+    pub const TRAY_SHOW_UPDATE_ACTIONS: &str = "tray.showUpdateActions";
+    pub const TRAY_MINIMAL_MODE: &str = "tray.minimalMode";
+    pub const TRAY_RECENT_LAUNCHES: &str = "tray.recentLaunches";
+    pub const BUCKET_FILTER_PRESETS: &str = "bucketSearch.filterPresets";
+    pub const BUCKET_ACTIVE_FILTER_PRESET: &str = "bucketSearch.activeFilterPreset";
+    pub const NETWORK_PROXY: &str = "network.proxy";
+    pub const NETWORK_ACCEPT_INVALID_CERTS: &str = "network.acceptInvalidCerts";
+    pub const NETWORK_TIMEOUT_SECS: &str = "network.timeoutSecs";
+    pub const NETWORK_MIRROR_RULES: &str = "network.mirrorRules";
+    pub const ONBOARDING_STATE: &str = "onboarding.state";
+    pub const METRICS_ENABLED: &str = "metrics.enabled";
+    pub const CRASH_MINIDUMPS_ENABLED: &str = "crashReports.minidumpsEnabled";
+    pub const STATUS_SNAPSHOT_INTERVAL: &str = "statusHistory.interval";
+    pub const STATUS_NAG_OUTDATED_COUNT: &str = "statusHistory.nagOutdatedCount";
+    pub const STATUS_NAG_PENDING_DAYS: &str = "statusHistory.nagPendingDays";
+    pub const QUIET_MODE_ENABLED: &str = "quietMode.enabled";
+    pub const QUIET_MODE_AUTO_DETECT: &str = "quietMode.autoDetectFullscreen";
+    pub const UPDATE_INSTALLER_TYPE: &str = "update.installerType";
+    pub const UPDATE_DELTA_DOWNLOADS: &str = "update.deltaDownloads";
+    pub const INSTALLED_SCAN_EXCLUSIONS: &str = "installed.scanExclusions";
+    pub const OPERATIONS_EXTERNAL_SCOOP_MODE: &str = "operations.externalScoopMode";
+    pub const SEARCH_IGNORE_PATTERNS: &str = "search.ignorePatterns";
+    pub const SEARCH_HISTORY: &str = "search.history";
+    pub const VERSION_PINS: &str = "packages.versionPins";
+    pub const POLICY_READ_ONLY: &str = "policy.readOnly";
+    pub const CLEANUP_USE_RECYCLE_BIN: &str = "cleanup.useRecycleBin";
+    pub const PERFORMANCE_SCAN_THREADS: &str = "performance.scanThreads";
+    pub const PERFORMANCE_LOW_PRIORITY_IO: &str = "performance.lowPriorityIo";
+    pub const OPERATION_HOOKS: &str = "operations.hooks";
 }
 
 // Application constants
@@ -27,6 +75,12 @@ mod app_constants {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    if std::env::args().any(|arg| arg == commands::self_cleanup::CLI_FLAG) {
+        commands::self_cleanup::run_from_uninstaller();
+        return;
+    }
+    portable::init();
+
     // Set up panic handler for better crash reporting
     std::panic::set_hook(Box::new(|panic_info| {
         let location = panic_info
@@ -49,7 +103,7 @@ pub fn run() {
         );
 
         // Try to write to log file if possible
-        if let Some(log_dir) = dirs::data_dir().map(|dir| dir.join("com.pailer.ks").join("logs")) {
+        if let Some(log_dir) = portable::log_dir() {
             if let Ok(mut log_file) = std::fs::OpenOptions::new()
                 .create(true)
                 .append(true)
@@ -67,7 +121,13 @@ pub fn run() {
                 );
             }
         }
+
+        crate::crash::record_panic(
+            &message,
+            &format!("{}:{}:{}", location.file(), location.line(), location.column()),
+        );
     }));
+    crash::install_native_handler();
     let mut builder = tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
@@ -87,8 +147,7 @@ pub fn run() {
     }
 
     // Determine log directory path
-    let log_dir = dirs::data_dir()
-        .map(|dir| dir.join("com.pailer.ks").join("logs"))
+    let log_dir = portable::log_dir()
         .unwrap_or_else(|| PathBuf::from("./logs"));
 
     cleanup_old_logs(&log_dir);
@@ -143,6 +202,15 @@ pub fn run() {
             // Resolve Scoop path
             let scoop_path = resolve_scoop_path(app.handle().clone())?;
             app.manage(state::AppState::new(scoop_path));
+            app.state::<state::AppState>().watch_scoop_dirs(app.handle());
+            http::configure(app.handle());
+            metrics::configure(app.handle());
+            crash::configure(app.handle());
+            quiet::configure(app.handle());
+            recycle::configure(app.handle());
+            scan_pool::configure(app.handle());
+            policy::configure(app.handle());
+            crash::prune_reports();
 
             // Show the main application window
             show_main_window(app)?;
@@ -155,6 +223,7 @@ pub fn run() {
 
             // Start background tasks
             scheduler::start_background_tasks(app.handle().clone());
+            commands::version_pin::watch_version_pins(app.handle().clone());
 
             Ok(())
         })
@@ -162,20 +231,63 @@ pub fn run() {
         .on_page_load(|window, _| {
             cold_start::run_cold_start(window.app_handle().clone());
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(policy::guard(tauri::generate_handler![
             commands::search::search_scoop,
+            commands::search_history::record_search_query,
+            commands::search_history::get_search_history,
+            commands::search_history::clear_search_history,
+            commands::search_history::get_search_suggestions,
+            commands::scoop_db::get_scoop_database_status,
+            commands::scoop_db::enable_scoop_database,
             commands::installed::get_installed_packages_full,
+            commands::installed::get_scan_exclusions,
+            commands::installed::set_scan_exclusions,
+            commands::installed::get_installed_cache_info,
             commands::installed::refresh_installed_packages,
+            commands::installed::is_installed_scan_in_progress,
             commands::installed::get_package_path,
+            commands::installed_search::search_installed,
+            commands::package_groups::get_package_groups,
             commands::info::get_package_info,
             commands::install::install_package,
             commands::manifest::get_package_manifest,
+            commands::readme::get_package_readme,
+            commands::compare::compare_packages,
+            commands::metrics::get_metrics,
+            commands::metrics::export_metrics,
+            commands::metrics::clear_metrics,
+            commands::metrics::record_command_timings,
+            commands::mirror::get_mirror_rules,
+            commands::mirror::set_mirror_rules,
+            commands::ignore_patterns::get_ignore_patterns,
+            commands::ignore_patterns::set_ignore_patterns,
+            commands::hooks::get_operation_hooks,
+            commands::hooks::set_operation_hooks,
+            commands::mirror::preview_mirror_rewrites,
+            commands::onboarding::get_onboarding_state,
+            commands::onboarding::detect_scoop_candidates,
+            commands::onboarding::choose_scoop_root,
+            commands::onboarding::bootstrap_scoop_root,
+            commands::onboarding::pick_initial_buckets,
+            commands::onboarding::import_scoop_export,
+            commands::export::export_installed_packages,
+            commands::onboarding::set_onboarding_update_preferences,
+            commands::onboarding::skip_onboarding_step,
+            commands::onboarding::reset_onboarding,
             commands::updates::check_for_updates,
+            commands::update_estimate::estimate_update_run,
             commands::update::update_package,
             commands::update::update_all_packages,
             commands::uninstall::uninstall_package,
+            commands::uninstall::uninstall_packages,
             commands::uninstall::clear_package_cache,
+            commands::uninstall_verification::clean_uninstall_leftovers,
             commands::status::check_scoop_status,
+            commands::status_history::take_status_snapshot,
+            commands::status_history::get_status_trend,
+            commands::status_history::clear_status_history,
+            commands::health::get_package_health,
+            commands::statistics::get_statistics,
             commands::settings::get_config_value,
             commands::settings::set_config_value,
             commands::settings::get_scoop_path,
@@ -184,52 +296,117 @@ pub fn run() {
             commands::settings::set_virustotal_api_key,
             commands::settings::get_scoop_proxy,
             commands::settings::set_scoop_proxy,
+            commands::scoop_core::get_scoop_core_info,
+            commands::scoop_core::set_scoop_core_source,
+            commands::scoop_core::update_scoop_core,
             commands::settings::detect_scoop_path,
             commands::settings::validate_scoop_directory,
             commands::settings::run_scoop_command,
             commands::settings::run_powershell_command,
             commands::settings::get_scoop_config,
             commands::settings::update_scoop_config,
+            commands::settings::list_scoop_config_backups,
+            commands::settings::restore_scoop_config_backup,
             commands::settings::get_scoop_config_directory,
             commands::settings::set_powershell_exe,
             commands::settings::get_available_powershell_executables,
             commands::virustotal::scan_package,
             commands::auto_cleanup::run_auto_cleanup,
             commands::doctor::checkup::run_scoop_checkup,
+            commands::doctor::app_runtime::run_app_runtime_checkup,
             commands::doctor::cleanup::cleanup_all_apps,
             commands::doctor::cleanup::cleanup_all_apps_force,
             commands::doctor::cleanup::cleanup_outdated_cache,
+            commands::doctor::cleanup::preview_cleanup,
             commands::doctor::cache::list_cache_contents,
             commands::doctor::cache::clear_cache,
+            commands::doctor::cache::import_cache_files,
+            commands::doctor::cache_sharing::get_cache_dedup_report,
+            commands::doctor::cache_sharing::share_cache_files,
             commands::doctor::shim::list_shims,
             commands::doctor::shim::remove_shim,
             commands::doctor::shim::alter_shim,
             commands::doctor::shim::add_shim,
+            commands::doctor::shims_path::fix_shims_path,
             commands::hold::list_held_packages,
             commands::hold::hold_package,
             commands::hold::unhold_package,
+            commands::package_state::get_package_states,
             commands::bucket::get_buckets,
             commands::bucket::get_bucket_info,
+            commands::bucket::compact_bucket,
             commands::bucket::get_bucket_manifests,
+            commands::bucket::find_duplicate_buckets,
+            commands::resolver::resolve_package_name,
+            commands::package_source::get_package_candidates,
+            commands::package_source::set_package_source_pin,
+            commands::package_source::get_bucket_priority,
+            commands::package_source::set_bucket_priority,
+            commands::deprecation::migrate_package,
+            commands::quarantine::list_failed_installs,
+            commands::quarantine::retry_failed_install,
+            commands::operation_log::list_operation_logs,
+            commands::activity_report::generate_activity_report,
+            commands::external_scoop::check_external_scoop,
+            commands::operation_log::get_operation_log,
+            commands::env_changes::revert_env_changes,
+            commands::powershell::send_operation_input,
+            commands::powershell::wait_for_operation,
             commands::bucket_install::install_bucket,
             commands::bucket_install::validate_bucket_install,
             commands::bucket_install::update_bucket,
             commands::bucket_install::remove_bucket,
+            commands::bucket_install::update_buckets,
+            commands::bucket_install::remove_buckets,
+            commands::bucket_presets::get_bucket_presets,
+            commands::bucket_presets::install_bucket_preset,
+            commands::bucket_install::export_bucket_archive,
+            commands::bucket_install::add_local_bucket,
             commands::bucket_search::search_buckets,
+            commands::bucket_search::find_buckets_containing,
             // commands::bucket_search::get_expanded_search_info,
             commands::bucket_search::get_default_buckets,
             commands::bucket_search::clear_bucket_cache,
             commands::bucket_search::check_bucket_cache_exists,
+            commands::bucket_search::get_bucket_filter_presets,
+            commands::bucket_search::save_bucket_filter_preset,
+            commands::bucket_search::delete_bucket_filter_preset,
+            commands::bucket_search::set_active_bucket_filter_preset,
             commands::app_info::is_scoop_installation,
+            commands::app_info::check_self_manifest_update,
             commands::linker::get_package_versions,
             commands::linker::switch_package_version,
+            commands::version_pin::get_version_pins,
+            commands::version_pin::pin_package_version,
+            commands::version_pin::unpin_package_version,
             commands::linker::get_versioned_packages,
             commands::linker::debug_package_structure,
             commands::linker::change_package_bucket,
+            commands::linker::duplicate_version,
+            commands::repair::repair_install_metadata,
+            commands::repair::adopt_package,
+            commands::broken_installs::get_broken_installs,
+            commands::integrity::verify_package_integrity,
+            commands::broken_installs::repair_broken_installs,
+            commands::bucket_rehome::get_orphaned_installs,
+            commands::bucket_rehome::rehome_orphaned_installs,
             commands::debug::get_debug_info,
+            commands::debug::bench,
+            supervisor::get_background_tasks_status,
+            quiet::get_quiet_mode_status,
+            quiet::set_do_not_disturb,
+            window_state::reset_window_state,
+            policy::get_read_only_status,
+            policy::set_read_only,
+            policy::get_effective_policy,
             commands::debug::get_app_logs,
             commands::debug::read_app_log_file,
             commands::debug::get_app_data_dir,
+            commands::debug::get_portable_info,
+            commands::debug::get_pending_crash_report,
+            commands::debug::list_crash_reports,
+            commands::debug::acknowledge_crash_report,
+            commands::debug::clear_crash_reports,
             commands::debug::get_log_dir_cmd,
             commands::debug::get_log_retention_days,
             commands::debug::set_log_retention_days,
@@ -248,6 +425,7 @@ pub fn run() {
             commands::startup::is_silent_startup_enabled,
             commands::startup::set_silent_startup_enabled,
             commands::startup::cleanup_startup_entries,
+            commands::self_cleanup::self_cleanup,
             cold_start::is_cold_start_ready,
             tray::refresh_tray_apps_menu,
             tray::get_current_language,
@@ -261,8 +439,9 @@ pub fn run() {
             commands::test_update::get_current_update_channel,
             commands::custom_update::check_for_custom_update,
             commands::custom_update::download_and_install_custom_update,
+            commands::custom_update::return_to_stable,
             commands::custom_update::get_current_version
-        ])
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -357,6 +536,7 @@ fn show_main_window(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>>
     let should_start_silently = commands::startup::is_silent_startup_enabled().unwrap_or(false);
     
     if let Some(window) = app.get_webview_window("main") {
+        window_state::restore(&window.as_ref().window());
         if should_start_silently {
             // Start minimized - don't show the window initially
             window.hide()?;
@@ -372,6 +552,7 @@ fn show_main_window(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>>
 fn handle_window_event(window: &tauri::Window, event: &WindowEvent) {
     if let WindowEvent::CloseRequested { api, .. } = event {
         let app_handle = window.app_handle().clone();
+        window_state::save(window);
 
         // Check if "close to tray" is enabled in settings
         let close_to_tray = commands::settings::get_config_value(
@@ -0,0 +1,173 @@
+//! Opt-in, local-only operation metrics.
+//!
+//! When enabled, counts, failures and durations are aggregated per operation kind
+//! (e.g. `install`, `search`) and kept in `metrics.json` in the app data directory.
+//! Nothing identifying is recorded: no package names, paths or search terms. Users can
+//! export the file and attach it to a bug report.
+use crate::commands::settings;
+use crate::config_keys::METRICS_ENABLED;
+use crate::models::{MetricsSnapshot, OperationMetrics};
+use once_cell::sync::Lazy;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Runtime};
+
+const METRICS_FILE: &str = "metrics.json";
+/// Minimum time between writes of the metrics file.
+const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static METRICS: Lazy<Mutex<Metrics>> = Lazy::new(|| Mutex::new(Metrics::load()));
+
+struct Metrics {
+    snapshot: MetricsSnapshot,
+    last_saved: Option<Instant>,
+}
+
+fn metrics_file() -> Option<PathBuf> {
+    crate::portable::app_data_dir().map(|dir| dir.join(METRICS_FILE))
+}
+
+impl Metrics {
+    fn load() -> Self {
+        let snapshot = metrics_file()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<MetricsSnapshot>(&content).ok())
+            .unwrap_or_else(|| MetricsSnapshot {
+                since: chrono::Utc::now().to_rfc3339(),
+                ..Default::default()
+            });
+        Self {
+            snapshot,
+            last_saved: None,
+        }
+    }
+
+    fn save(&mut self) {
+        let Some(path) = metrics_file() else {
+            return;
+        };
+        match serde_json::to_string_pretty(&self.snapshot) {
+            Ok(content) => {
+                if let Err(e) = std::fs::write(&path, content) {
+                    log::warn!("Failed to save metrics to {}: {}", path.display(), e);
+                }
+            }
+            Err(e) => log::warn!("Failed to serialize metrics: {}", e),
+        }
+        self.last_saved = Some(Instant::now());
+    }
+}
+
+/// Reads the opt-in setting from the app store.
+pub fn configure<R: Runtime>(app: &AppHandle<R>) {
+    let enabled = settings::get_config_value(app.clone(), METRICS_ENABLED.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    ENABLED.store(enabled, Ordering::Relaxed);
+    log::info!(
+        "Local metrics {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Records one run of `operation`. Does nothing unless metrics are enabled.
+pub fn record(operation: &str, duration: Duration, success: bool) {
+    if !is_enabled() {
+        return;
+    }
+    let mut metrics = METRICS.lock().unwrap_or_else(|e| e.into_inner());
+    let entry = metrics
+        .snapshot
+        .operations
+        .entry(operation.to_string())
+        .or_default();
+    add_sample(entry, duration.as_millis() as u64, success);
+
+    if metrics
+        .last_saved
+        .map(|at| at.elapsed() >= SAVE_INTERVAL)
+        .unwrap_or(true)
+    {
+        metrics.save();
+    }
+}
+
+fn add_sample(entry: &mut OperationMetrics, millis: u64, success: bool) {
+    entry.min_ms = if entry.count == 0 {
+        millis
+    } else {
+        entry.min_ms.min(millis)
+    };
+    entry.max_ms = entry.max_ms.max(millis);
+    entry.count += 1;
+    entry.total_ms += millis;
+    if !success {
+        entry.failures += 1;
+    }
+}
+
+/// Measures an operation from creation until [`Timer::finish`].
+pub struct Timer {
+    operation: &'static str,
+    started: Instant,
+}
+
+impl Timer {
+    pub fn start(operation: &'static str) -> Self {
+        Self {
+            operation,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn finish(self, success: bool) {
+        record(self.operation, self.started.elapsed(), success);
+    }
+}
+
+/// Returns the collected metrics and writes them to disk.
+pub fn snapshot() -> MetricsSnapshot {
+    let mut metrics = METRICS.lock().unwrap_or_else(|e| e.into_inner());
+    metrics.save();
+    MetricsSnapshot {
+        enabled: is_enabled(),
+        ..metrics.snapshot.clone()
+    }
+}
+
+/// Discards all collected metrics.
+pub fn clear() {
+    let mut metrics = METRICS.lock().unwrap_or_else(|e| e.into_inner());
+    metrics.snapshot = MetricsSnapshot {
+        since: chrono::Utc::now().to_rfc3339(),
+        ..Default::default()
+    };
+    metrics.save();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_samples() {
+        let mut entry = OperationMetrics::default();
+        add_sample(&mut entry, 120, true);
+        add_sample(&mut entry, 40, false);
+        add_sample(&mut entry, 300, true);
+
+        assert_eq!(entry.count, 3);
+        assert_eq!(entry.failures, 1);
+        assert_eq!(entry.total_ms, 460);
+        assert_eq!((entry.min_ms, entry.max_ms), (40, 300));
+    }
+}
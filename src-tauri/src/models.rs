@@ -12,6 +12,13 @@ use serde::{Deserialize, Serialize};
 pub enum MatchSource {
     Name,
     Binary,
+    /// The name is close to the search term, give or take a typo.
+    Fuzzy,
+    Description,
+    Homepage,
+    License,
+    /// The bucket, for searches of the installed packages.
+    Bucket,
     None,
 }
 
@@ -21,6 +28,18 @@ impl Default for MatchSource {
     }
 }
 
+// -----------------------------------------------------------------------------
+// InstallScope
+// -----------------------------------------------------------------------------
+/// Whether a package is installed for the current user or machine-wide (`--global`).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallScope {
+    #[default]
+    User,
+    Global,
+}
+
 // -----------------------------------------------------------------------------
 // ScoopPackage
 // -----------------------------------------------------------------------------
@@ -36,6 +55,25 @@ pub struct ScoopPackage {
     pub match_source: MatchSource,
     #[serde(default)]
     pub is_versioned_install: bool,
+    /// Set when the install is missing or has unreadable `manifest.json`/`install.json`.
+    #[serde(default)]
+    pub broken: bool,
+    #[serde(default)]
+    pub broken_reason: Option<String>,
+    /// Global installs are listed read-only.
+    #[serde(default)]
+    pub scope: InstallScope,
+    /// Search relevance from 0 to 100; only set on search results.
+    #[serde(default)]
+    pub score: Option<u32>,
+    /// Manifest details shown on search results.
+    #[serde(default)]
+    pub homepage: String,
+    #[serde(default)]
+    pub license: String,
+    /// Only 64-bit downloads are offered.
+    #[serde(default)]
+    pub is_64bit_only: bool,
 }
 
 // -----------------------------------------------------------------------------
@@ -45,6 +83,27 @@ pub struct ScoopPackage {
 pub struct SearchResult {
     pub packages: Vec<ScoopPackage>,
     pub is_cold: bool,
+    /// Number of matches, of which `packages` may be one page.
+    #[serde(default)]
+    pub total: usize,
+}
+
+// -----------------------------------------------------------------------------
+// Installed Packages
+// -----------------------------------------------------------------------------
+/// Freshness of the installed packages cache.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct InstalledCacheInfo {
+    /// Incremented whenever the cached package list is replaced or invalidated.
+    pub generation: u64,
+    /// When the apps directory was last scanned, in milliseconds since the Unix epoch.
+    pub last_scan_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct InstalledPackagesResult {
+    pub packages: Vec<ScoopPackage>,
+    pub cache_info: InstalledCacheInfo,
 }
 
 // -----------------------------------------------------------------------------
@@ -58,7 +117,76 @@ pub struct BucketInfo {
     pub is_git_repo: bool,
     pub git_url: Option<String>,
     pub git_branch: Option<String>,
+    /// Commit id of HEAD for git buckets.
+    #[serde(default)]
+    pub head_commit: Option<String>,
     pub last_updated: Option<String>,
+    /// Commits on HEAD that the origin branch lacks, as of the last fetch.
+    #[serde(default)]
+    pub ahead: Option<usize>,
+    /// Commits on the origin branch that HEAD lacks, as of the last fetch.
+    #[serde(default)]
+    pub behind: Option<usize>,
+    #[serde(default)]
+    pub last_fetch: Option<BucketFetchStatus>,
+    #[serde(default)]
+    pub auth: Option<BucketAuth>,
+    /// Size of the checked out files, in bytes.
+    #[serde(default)]
+    pub working_tree_bytes: u64,
+    /// Size of the `.git` directory, in bytes.
+    #[serde(default)]
+    pub git_bytes: u64,
+}
+
+/// Outcome of compacting a bucket's git repository.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BucketCompactResult {
+    pub bucket_name: String,
+    pub git_bytes_before: u64,
+    pub git_bytes_after: u64,
+    /// Objects in the new pack.
+    pub objects: usize,
+    pub removed_packs: usize,
+    pub removed_loose_objects: usize,
+}
+
+/// How the origin remote of a git bucket authenticates.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BucketAuth {
+    /// Public HTTP(S) remote, no credentials needed.
+    Anonymous,
+    /// SSH remote, needs a key from the SSH agent.
+    Ssh,
+    /// Credentials are part of the remote URL.
+    UrlCredentials,
+    /// The last fetch was rejected for missing or wrong credentials.
+    Rejected,
+}
+
+/// Outcome of the most recent fetch of a git bucket.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BucketFetchStatus {
+    pub success: bool,
+    pub at: Option<String>,
+    pub error: Option<String>,
+    /// The fetch failed because the remote requires authentication.
+    #[serde(default)]
+    pub auth_failed: bool,
+}
+
+/// A group of local buckets that appear to mirror the same repository.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DuplicateBucketGroup {
+    pub buckets: Vec<String>,
+    /// The bucket suggested to keep; the others can be removed.
+    pub keep: String,
+    /// Either `same_remote` or `similar_manifests`.
+    pub reason: String,
+    /// Jaccard similarity of the manifest sets (1.0 for identical sets).
+    pub similarity: f64,
+    pub suggestion: String,
 }
 
 // -----------------------------------------------------------------------------
@@ -76,6 +204,9 @@ pub struct AppStatusInfo {
     pub is_held: bool,
     pub is_deprecated: bool,
     pub is_removed: bool,
+    /// Successor package suggested by a deprecated manifest.
+    #[serde(default)]
+    pub replacement: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -85,6 +216,12 @@ pub struct ScoopStatus {
     pub network_failure: bool,
     pub apps_with_issues: Vec<AppStatusInfo>,
     pub is_everything_ok: bool,
+    /// Set when this app was installed with Scoop and its bucket has a newer version.
+    #[serde(default)]
+    pub self_update: Option<SelfUpdateInfo>,
+    /// Branch and commit Scoop itself is at.
+    #[serde(default)]
+    pub scoop_core: Option<crate::commands::scoop_core::ScoopCoreInfo>,
 }
 
 // -----------------------------------------------------------------------------
@@ -100,3 +237,289 @@ pub struct PackageManifest {
 pub struct InstallManifest {
     pub bucket: Option<String>,
 }
+
+// -----------------------------------------------------------------------------
+// Failed Installs
+// -----------------------------------------------------------------------------
+/// An install that failed and whose leftover directories were quarantined.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FailedInstall {
+    pub package: String,
+    pub bucket: Option<String>,
+    pub error: String,
+    /// Paths the half-written install directories were moved to.
+    pub quarantined_paths: Vec<String>,
+    pub failed_at: String,
+}
+
+// -----------------------------------------------------------------------------
+// Download Mirrors
+// -----------------------------------------------------------------------------
+/// A download URL rewrite rule, e.g. to send GitHub release downloads to a mirror.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MirrorRule {
+    /// Regular expression matched against the full URL.
+    pub pattern: String,
+    /// Replacement text; may reference capture groups as `$1` or `${name}`.
+    pub replacement: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// -----------------------------------------------------------------------------
+// Ignore Patterns
+// -----------------------------------------------------------------------------
+/// Hides matching packages from search results and update checks.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IgnorePattern {
+    /// Package name pattern where `*` and `?` are wildcards, e.g. `*-np`.
+    #[serde(default = "match_all")]
+    pub package: String,
+    /// Limits the pattern to one bucket; with the default package pattern it hides
+    /// the whole bucket.
+    #[serde(default)]
+    pub bucket: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn match_all() -> String {
+    "*".to_string()
+}
+
+/// A package download URL and what the mirror rules turn it into.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MirrorRewrite {
+    pub original: String,
+    /// `None` when no rule matches and the original URL is used.
+    pub rewritten: Option<String>,
+}
+
+// -----------------------------------------------------------------------------
+// Portable Mode
+// -----------------------------------------------------------------------------
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PortableInfo {
+    pub portable: bool,
+    /// Directory holding the settings store, logs and caches.
+    pub data_dir: Option<String>,
+}
+
+// -----------------------------------------------------------------------------
+// Onboarding
+// -----------------------------------------------------------------------------
+/// Steps of the first-run setup, in the order they are presented.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    #[default]
+    DetectScoop,
+    ChooseRoot,
+    PickBuckets,
+    ImportExport,
+    UpdatePreferences,
+    Done,
+}
+
+/// Resumable onboarding progress, persisted in the settings store.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct OnboardingState {
+    pub current_step: OnboardingStep,
+    #[serde(default)]
+    pub completed_steps: Vec<OnboardingStep>,
+    #[serde(default)]
+    pub skipped_steps: Vec<OnboardingStep>,
+    #[serde(default)]
+    pub scoop_root: Option<String>,
+    #[serde(default)]
+    pub selected_buckets: Vec<String>,
+    #[serde(default)]
+    pub imported_from: Option<String>,
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub completed_at: Option<String>,
+}
+
+/// A possible Scoop root found during onboarding, with its score.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScoopRootCandidateInfo {
+    pub path: String,
+    pub score: u32,
+    pub installed_count: usize,
+    pub has_apps_dir: bool,
+    pub has_buckets_dir: bool,
+    /// Whether this is the root that would be picked automatically.
+    pub recommended: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OnboardingUpdatePreferences {
+    /// Bucket auto-update interval, e.g. `off`, `24h` or `7d`.
+    pub bucket_auto_update_interval: String,
+    pub auto_update_packages: bool,
+}
+
+/// Outcome of importing a `scoop export` file.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct OnboardingImportResult {
+    pub buckets_added: Vec<String>,
+    pub apps_installed: Vec<String>,
+    /// Apps installed from the manifest embedded in the export, at the exported version.
+    pub apps_pinned: Vec<String>,
+    /// Apps that were already installed.
+    pub apps_skipped: Vec<String>,
+    /// Bucket or app name with the error that stopped it.
+    pub failed: Vec<(String, String)>,
+}
+
+// -----------------------------------------------------------------------------
+// Self Update
+// -----------------------------------------------------------------------------
+/// A newer version of this app found in the Scoop bucket it was installed from.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SelfUpdateInfo {
+    pub package_name: String,
+    pub current_version: String,
+    pub latest_version: String,
+    pub bucket: String,
+}
+
+// -----------------------------------------------------------------------------
+// Metrics
+// -----------------------------------------------------------------------------
+/// Aggregated runs of one kind of operation.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct OperationMetrics {
+    pub count: u64,
+    pub failures: u64,
+    pub total_ms: u64,
+    pub min_ms: u64,
+    pub max_ms: u64,
+}
+
+/// Invocations of one IPC command during this session.
+///
+/// Durations run from the frontend's `invoke` call until its promise settles, as
+/// reported by `record_command_timings`.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandStats {
+    pub calls: u64,
+    /// Invocations that were rejected.
+    pub errors: u64,
+    pub last_ms: f64,
+    pub average_ms: f64,
+    pub max_ms: f64,
+}
+
+/// One settled IPC call, reported by the frontend.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandTiming {
+    pub command: String,
+    pub duration_ms: f64,
+    pub success: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MetricsSnapshot {
+    #[serde(default)]
+    pub enabled: bool,
+    /// When collection started or was last cleared (RFC 3339).
+    pub since: String,
+    /// Keyed by operation kind, e.g. `install` or `search`.
+    pub operations: std::collections::BTreeMap<String, OperationMetrics>,
+}
+
+// -----------------------------------------------------------------------------
+// Crash Reports
+// -----------------------------------------------------------------------------
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CrashReport {
+    pub id: String,
+    pub timestamp: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    /// `panic` or, on Windows, `exception` for native crashes.
+    pub kind: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub backtrace: Option<String>,
+    pub minidump_path: Option<String>,
+    /// Set once the user has seen the report after restarting.
+    #[serde(default)]
+    pub acknowledged: bool,
+}
+
+// -----------------------------------------------------------------------------
+// Package State
+// -----------------------------------------------------------------------------
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageActivity {
+    #[default]
+    Idle,
+    /// Waiting for another Scoop operation to finish.
+    Queued,
+    Installing,
+    Updating,
+    Uninstalling,
+    ClearingCache,
+    /// The last operation on the package failed.
+    Failed,
+    Held,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PackageState {
+    pub name: String,
+    pub state: PackageActivity,
+    pub operation_id: Option<String>,
+    /// Error of the failed operation.
+    pub message: Option<String>,
+    /// Milliseconds since the Unix epoch.
+    pub updated_at: u64,
+}
+
+// -----------------------------------------------------------------------------
+// Batch Uninstall
+// -----------------------------------------------------------------------------
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SkippedUninstall {
+    pub package: String,
+    /// Installed packages that still depend on it.
+    pub required_by: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct UninstallPlan {
+    /// Packages in removal order, dependents first.
+    pub order: Vec<String>,
+    pub skipped: Vec<SkippedUninstall>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchUninstallProgress {
+    pub package: String,
+    /// 1-based position in the removal order.
+    pub index: usize,
+    pub total: usize,
+    /// `started`, `succeeded`, `failed` or `skipped`.
+    pub status: String,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BatchUninstallResult {
+    pub uninstalled: Vec<String>,
+    /// Package name and error of each failed uninstall.
+    pub failed: Vec<(String, String)>,
+    pub skipped: Vec<SkippedUninstall>,
+}
@@ -0,0 +1,366 @@
+//! Read-only (kiosk) mode and the machine policy.
+//!
+//! In read-only mode the app is a dashboard: browsing, search and status keep working,
+//! but every command that changes the Scoop installation is rejected before it runs,
+//! and background updates and pin enforcement stay idle. Users can switch it on in the
+//! settings.
+//!
+//! Administrators can deploy `%ProgramData%\Pailer\policy.json` to fix read-only mode,
+//! restrict the buckets that can be added and installed from, block packages, and set
+//! the proxy and update schedule. Settings the policy fixes are returned in place of
+//! the user's values by [`settings::get_config_value`] and cannot be changed.
+use crate::commands::settings;
+use crate::config_keys::{NETWORK_PROXY, POLICY_READ_ONLY};
+use crate::engine::ignore::IgnoreRules;
+use crate::models::IgnorePattern;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::ipc::Invoke;
+use tauri::{AppHandle, Runtime};
+
+/// Directory under `%ProgramData%` holding the machine policy.
+const MACHINE_POLICY_DIR: &str = "Pailer";
+const MACHINE_POLICY_FILE: &str = "policy.json";
+const AUTO_UPDATE_INTERVAL_KEY: &str = "buckets.autoUpdateInterval";
+const AUTO_UPDATE_PACKAGES_KEY: &str = "buckets.autoUpdatePackagesEnabled";
+const READ_ONLY_ERROR: &str = "Pailer is in read-only mode; changes to Scoop are disabled";
+
+/// Commands that change the Scoop installation, rejected in read-only mode.
+const MUTATING_COMMANDS: &[&str] = &[
+    "install_package",
+    "update_package",
+    "update_all_packages",
+    "uninstall_package",
+    "uninstall_packages",
+    "clear_package_cache",
+    "clean_uninstall_leftovers",
+    "run_scoop_command",
+    "run_powershell_command",
+    "update_scoop_config",
+    "enable_scoop_database",
+    "restore_scoop_config_backup",
+    "set_scoop_proxy",
+    "set_scoop_core_source",
+    "update_scoop_core",
+    "run_auto_cleanup",
+    "cleanup_all_apps",
+    "cleanup_all_apps_force",
+    "cleanup_outdated_cache",
+    "clear_cache",
+    "import_cache_files",
+    "share_cache_files",
+    "remove_shim",
+    "alter_shim",
+    "add_shim",
+    "fix_shims_path",
+    "revert_env_changes",
+    "set_operation_hooks",
+    "hold_package",
+    "unhold_package",
+    "migrate_package",
+    "retry_failed_install",
+    "install_bucket",
+    "update_bucket",
+    "remove_bucket",
+    "update_buckets",
+    "remove_buckets",
+    "compact_bucket",
+    "install_bucket_preset",
+    "add_local_bucket",
+    "switch_package_version",
+    "pin_package_version",
+    "change_package_bucket",
+    "duplicate_version",
+    "repair_install_metadata",
+    "repair_broken_installs",
+    "rehome_orphaned_installs",
+    "adopt_package",
+    "bootstrap_scoop_root",
+    "pick_initial_buckets",
+    "import_scoop_export",
+];
+
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+static LOCKED: AtomicBool = AtomicBool::new(false);
+static MACHINE_POLICY: Lazy<MachinePolicy> = Lazy::new(load_machine_policy);
+static BLOCKED_PACKAGES: Lazy<IgnoreRules> = Lazy::new(|| {
+    let patterns: Vec<IgnorePattern> = MACHINE_POLICY
+        .blocked_packages
+        .iter()
+        .map(|entry| {
+            let (bucket, package) = match entry.split_once('/') {
+                Some((bucket, package)) => (Some(bucket.to_string()), package),
+                None => (None, entry.as_str()),
+            };
+            IgnorePattern {
+                package: package.to_string(),
+                bucket,
+                enabled: true,
+            }
+        })
+        .collect();
+    IgnoreRules::compile(&patterns).unwrap_or_else(|e| {
+        log::warn!("Ignoring blocked packages of the machine policy: {}", e);
+        IgnoreRules::default()
+    })
+});
+
+/// The policy an administrator deployed for every user of the machine.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MachinePolicy {
+    pub read_only: Option<bool>,
+    /// The only buckets that can be added, and installed from when one is named.
+    pub allowed_buckets: Option<Vec<String>>,
+    /// Package patterns that cannot be installed, with `*`/`?` wildcards and an
+    /// optional `bucket/` prefix.
+    #[serde(default)]
+    pub blocked_packages: Vec<String>,
+    /// Proxy for the app's own downloads.
+    pub proxy: Option<String>,
+    /// Bucket auto-update interval, e.g. `24h` or `off`.
+    pub auto_update_interval: Option<String>,
+    /// Whether packages are updated after the scheduled bucket update.
+    pub auto_update_packages: Option<bool>,
+}
+
+impl MachinePolicy {
+    /// The settings the policy fixes, by config key.
+    pub fn locked_settings(&self) -> BTreeMap<String, Value> {
+        [
+            (POLICY_READ_ONLY, self.read_only.map(Value::Bool)),
+            (NETWORK_PROXY, self.proxy.clone().map(Value::String)),
+            (
+                AUTO_UPDATE_INTERVAL_KEY,
+                self.auto_update_interval.clone().map(Value::String),
+            ),
+            (
+                AUTO_UPDATE_PACKAGES_KEY,
+                self.auto_update_packages.map(Value::Bool),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key.to_string(), value?)))
+        .collect()
+    }
+}
+
+/// The policy in effect, as shown in the settings.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectivePolicy {
+    /// The machine policy file, when one is deployed.
+    pub source: Option<String>,
+    pub read_only: bool,
+    pub read_only_locked: bool,
+    pub allowed_buckets: Option<Vec<String>>,
+    pub blocked_packages: Vec<String>,
+    /// Settings fixed by the machine policy, with their value.
+    pub locked_settings: BTreeMap<String, Value>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadOnlyStatus {
+    pub read_only: bool,
+    /// Set by the machine policy; the setting cannot be changed.
+    pub locked: bool,
+}
+
+fn machine_policy_path() -> Option<PathBuf> {
+    std::env::var_os("ProgramData").map(|dir| {
+        PathBuf::from(dir)
+            .join(MACHINE_POLICY_DIR)
+            .join(MACHINE_POLICY_FILE)
+    })
+}
+
+/// Reads the machine policy; a missing or invalid file means no policy.
+pub fn load_machine_policy() -> MachinePolicy {
+    let Some(path) = machine_policy_path().filter(|p| p.is_file()) else {
+        return MachinePolicy::default();
+    };
+    match std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+    {
+        Ok(policy) => {
+            log::info!("Loaded machine policy from {}", path.display());
+            policy
+        }
+        Err(e) => {
+            log::warn!("Ignoring invalid machine policy {}: {}", path.display(), e);
+            MachinePolicy::default()
+        }
+    }
+}
+
+/// Reads the read-only setting, letting the machine policy take precedence.
+pub fn configure<R: Runtime>(app: &AppHandle<R>) {
+    let (read_only, locked) = match MACHINE_POLICY.read_only {
+        Some(forced) => (forced, true),
+        None => {
+            let enabled = settings::get_config_value(app.clone(), POLICY_READ_ONLY.to_string())
+                .ok()
+                .flatten()
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            (enabled, false)
+        }
+    };
+    READ_ONLY.store(read_only, Ordering::Relaxed);
+    LOCKED.store(locked, Ordering::Relaxed);
+    if read_only {
+        log::info!(
+            "Read-only mode is on{}",
+            if locked { " (machine policy)" } else { "" }
+        );
+    }
+}
+
+pub fn is_read_only() -> bool {
+    READ_ONLY.load(Ordering::Relaxed)
+}
+
+/// Returns an error when read-only mode forbids changing the installation.
+pub fn ensure_writable() -> Result<(), String> {
+    if is_read_only() {
+        Err(READ_ONLY_ERROR.to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// The value the machine policy fixes for a config key, if any.
+pub fn locked_setting(key: &str) -> Option<Value> {
+    MACHINE_POLICY.locked_settings().remove(key)
+}
+
+fn bucket_allowed(policy: &MachinePolicy, bucket: &str) -> bool {
+    policy
+        .allowed_buckets
+        .as_ref()
+        .is_none_or(|allowed| allowed.iter().any(|b| b.eq_ignore_ascii_case(bucket)))
+}
+
+/// Returns an error when the machine policy does not allow adding `bucket`.
+pub fn check_bucket_allowed(bucket: &str) -> Result<(), String> {
+    if bucket_allowed(&MACHINE_POLICY, bucket) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Bucket '{}' is not allowed by the machine policy",
+            bucket
+        ))
+    }
+}
+
+/// Returns an error when the machine policy forbids installing `package`.
+///
+/// `package` may carry a `bucket/` prefix, which takes precedence over `bucket`. When
+/// the policy restricts buckets, an install whose bucket is unknown is refused.
+pub fn check_install_allowed(package: &str, bucket: Option<&str>) -> Result<(), String> {
+    let (bucket, name) = match package.split_once('/') {
+        Some((bucket, name)) => (Some(bucket), name),
+        None => (bucket, package),
+    };
+    match bucket {
+        Some(bucket) => check_bucket_allowed(bucket)?,
+        None if MACHINE_POLICY.allowed_buckets.is_some() => {
+            return Err(format!(
+                "Package '{}' does not come from a bucket allowed by the machine policy",
+                name
+            ))
+        }
+        None => {}
+    }
+    if BLOCKED_PACKAGES.is_ignored(name, bucket.unwrap_or_default()) {
+        return Err(format!(
+            "Package '{}' is blocked by the machine policy",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Wraps the invoke handler so mutating commands are rejected in read-only mode.
+pub fn guard<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let command = invoke.message.command();
+        if is_read_only() && MUTATING_COMMANDS.contains(&command) {
+            log::warn!("Rejected '{}' in read-only mode", command);
+            invoke.resolver.reject(READ_ONLY_ERROR);
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+#[tauri::command]
+pub fn get_read_only_status() -> ReadOnlyStatus {
+    ReadOnlyStatus {
+        read_only: is_read_only(),
+        locked: LOCKED.load(Ordering::Relaxed),
+    }
+}
+
+/// Switches read-only mode, unless the machine policy decides it.
+#[tauri::command]
+pub fn set_read_only(app: AppHandle, enabled: bool) -> Result<(), String> {
+    if LOCKED.load(Ordering::Relaxed) {
+        return Err("Read-only mode is set by the machine policy".to_string());
+    }
+    settings::set_config_value(
+        app,
+        POLICY_READ_ONLY.to_string(),
+        serde_json::Value::Bool(enabled),
+    )?;
+    READ_ONLY.store(enabled, Ordering::Relaxed);
+    log::info!(
+        "Read-only mode {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    Ok(())
+}
+
+/// Returns the machine policy merged with the user's read-only setting.
+#[tauri::command]
+pub fn get_effective_policy() -> EffectivePolicy {
+    EffectivePolicy {
+        source: machine_policy_path()
+            .filter(|p| p.is_file())
+            .map(|p| p.display().to_string()),
+        read_only: is_read_only(),
+        read_only_locked: LOCKED.load(Ordering::Relaxed),
+        allowed_buckets: MACHINE_POLICY.allowed_buckets.clone(),
+        blocked_packages: MACHINE_POLICY.blocked_packages.clone(),
+        locked_settings: MACHINE_POLICY.locked_settings(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_policy_file_and_locked_settings() {
+        let policy: MachinePolicy = serde_json::from_str(
+            r#"{ "allowedBuckets": ["main", "extras"], "proxy": "proxy.corp:8080", "autoUpdatePackages": false }"#,
+        )
+        .unwrap();
+        assert!(bucket_allowed(&policy, "Extras"));
+        assert!(!bucket_allowed(&policy, "games"));
+        assert!(bucket_allowed(&MachinePolicy::default(), "games"));
+
+        let locked = policy.locked_settings();
+        assert_eq!(locked.len(), 2);
+        assert_eq!(locked[NETWORK_PROXY], Value::from("proxy.corp:8080"));
+        assert_eq!(locked[AUTO_UPDATE_PACKAGES_KEY], Value::Bool(false));
+    }
+}
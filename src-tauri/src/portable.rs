@@ -0,0 +1,100 @@
+//! Portable mode.
+//!
+//! When a `portable.flag` file sits next to the executable, the settings store, logs
+//! and caches are kept in a `data` directory beside it instead of the user's profile,
+//! so the app can run from removable media without leaving files on the host. A Scoop
+//! root next to the executable (or one level up) is preferred in this mode.
+use once_cell::sync::Lazy;
+use std::path::{Path, PathBuf};
+
+/// Marker file that enables portable mode.
+pub const PORTABLE_FLAG_FILE: &str = "portable.flag";
+/// Directory next to the executable that holds the app data in portable mode.
+const PORTABLE_DATA_DIR: &str = "data";
+/// Tauri app identifier, used as the data directory name in normal mode.
+pub const APP_ID: &str = "com.pailer.ks";
+
+static PORTABLE_DATA: Lazy<Option<PathBuf>> = Lazy::new(|| {
+    let exe = std::env::current_exe().ok()?;
+    portable_data_dir_for(exe.parent()?)
+});
+
+/// Returns the portable data directory for an executable in `exe_dir`, if the flag
+/// file is present.
+fn portable_data_dir_for(exe_dir: &Path) -> Option<PathBuf> {
+    exe_dir
+        .join(PORTABLE_FLAG_FILE)
+        .is_file()
+        .then(|| exe_dir.join(PORTABLE_DATA_DIR))
+}
+
+/// Returns `true` if the app runs in portable mode.
+pub fn is_portable() -> bool {
+    PORTABLE_DATA.is_some()
+}
+
+/// Directory holding the app's own data: the portable data directory, or
+/// `%APPDATA%\com.pailer.ks`.
+pub fn app_data_dir() -> Option<PathBuf> {
+    match PORTABLE_DATA.as_ref() {
+        Some(dir) => Some(dir.clone()),
+        None => dirs::data_dir().map(|dir| dir.join(APP_ID)),
+    }
+}
+
+pub fn log_dir() -> Option<PathBuf> {
+    app_data_dir().map(|dir| dir.join("logs"))
+}
+
+/// Path to pass to the store plugin for `file_name`.
+///
+/// Relative paths resolve against Tauri's app data directory, which cannot be moved,
+/// so portable mode uses an absolute path instead.
+pub fn store_path(file_name: &str) -> PathBuf {
+    match PORTABLE_DATA.as_ref() {
+        Some(dir) => dir.join(file_name),
+        None => PathBuf::from(file_name),
+    }
+}
+
+/// Scoop roots that belong to a portable install, e.g. `E:\Pailer\scoop` or
+/// `E:\scoop` for an executable in `E:\Pailer`.
+pub fn scoop_root_candidates() -> Vec<PathBuf> {
+    let Some(exe_dir) = PORTABLE_DATA.as_ref().and_then(|dir| dir.parent()) else {
+        return Vec::new();
+    };
+    std::iter::once(exe_dir.join("scoop"))
+        .chain(exe_dir.parent().map(|parent| parent.join("scoop")))
+        .collect()
+}
+
+/// Prepares the portable data directory. Must run before the webview is created.
+pub fn init() {
+    let Some(dir) = PORTABLE_DATA.as_ref() else {
+        return;
+    };
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        eprintln!("Failed to create portable data directory {:?}: {}", dir, e);
+    }
+    // Keep the WebView2 profile (local storage, caches) out of %LOCALAPPDATA% as well
+    if std::env::var_os("WEBVIEW2_USER_DATA_FOLDER").is_none() {
+        std::env::set_var("WEBVIEW2_USER_DATA_FOLDER", dir.join("webview"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flag_file_enables_portable_data_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(portable_data_dir_for(dir.path()), None);
+
+        std::fs::write(dir.path().join(PORTABLE_FLAG_FILE), "").unwrap();
+        assert_eq!(
+            portable_data_dir_for(dir.path()),
+            Some(dir.path().join("data"))
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! Do-not-disturb handling for background work.
+//!
+//! While do-not-disturb is on, or (when auto-detection is enabled) Windows reports a
+//! fullscreen game, presentation or other busy foreground app, scheduled bucket
+//! fetches, Scoop runs and snapshots wait. [`EVENT_QUIET_MODE_CHANGED`] tells the UI
+//! to hold back its toasts meanwhile.
+use crate::commands::settings;
+use crate::config_keys::{QUIET_MODE_AUTO_DETECT, QUIET_MODE_ENABLED};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Emitted with `true` when background work pauses and `false` when it resumes.
+pub const EVENT_QUIET_MODE_CHANGED: &str = "quiet-mode-changed";
+/// How often paused work checks whether it may resume.
+const RECHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+static MANUAL: AtomicBool = AtomicBool::new(false);
+static AUTO_DETECT: AtomicBool = AtomicBool::new(true);
+
+#[derive(Serialize, Debug, Clone)]
+pub struct QuietModeStatus {
+    /// Do-not-disturb was switched on by the user.
+    pub manual: bool,
+    pub auto_detect: bool,
+    /// A fullscreen or busy foreground app was detected.
+    pub foreground_busy: bool,
+    /// Background work is currently paused.
+    pub active: bool,
+}
+
+/// Reads the do-not-disturb settings from the app store.
+pub fn configure<R: Runtime>(app: &AppHandle<R>) {
+    let read = |key: &str, default: bool| {
+        settings::get_config_value(app.clone(), key.to_string())
+            .ok()
+            .flatten()
+            .and_then(|v| v.as_bool())
+            .unwrap_or(default)
+    };
+    MANUAL.store(read(QUIET_MODE_ENABLED, false), Ordering::Relaxed);
+    AUTO_DETECT.store(read(QUIET_MODE_AUTO_DETECT, true), Ordering::Relaxed);
+}
+
+#[cfg(windows)]
+fn foreground_busy() -> bool {
+    use windows_sys::Win32::UI::Shell::{
+        SHQueryUserNotificationState, QUNS_BUSY, QUNS_PRESENTATION_MODE,
+        QUNS_RUNNING_D3D_FULL_SCREEN,
+    };
+
+    let mut state = 0;
+    let hr = unsafe { SHQueryUserNotificationState(&mut state) };
+    hr >= 0
+        && matches!(
+            state,
+            QUNS_BUSY | QUNS_RUNNING_D3D_FULL_SCREEN | QUNS_PRESENTATION_MODE
+        )
+}
+
+#[cfg(not(windows))]
+fn foreground_busy() -> bool {
+    false
+}
+
+pub fn status() -> QuietModeStatus {
+    let manual = MANUAL.load(Ordering::Relaxed);
+    let auto_detect = AUTO_DETECT.load(Ordering::Relaxed);
+    let foreground_busy = auto_detect && foreground_busy();
+    QuietModeStatus {
+        manual,
+        auto_detect,
+        foreground_busy,
+        active: manual || foreground_busy,
+    }
+}
+
+pub fn is_active() -> bool {
+    status().active
+}
+
+fn emit_change<R: Runtime>(app: &AppHandle<R>, active: bool) {
+    if let Err(e) = app.emit(EVENT_QUIET_MODE_CHANGED, active) {
+        log::warn!("Failed to emit {} event: {}", EVENT_QUIET_MODE_CHANGED, e);
+    }
+}
+
+/// Waits while quiet mode is active: until do-not-disturb is off and no fullscreen app
+/// is running. Returns at once when it is not active.
+pub async fn wait_while_active<R: Runtime>(app: &AppHandle<R>, task: &str) {
+    if !is_active() {
+        return;
+    }
+    log::info!("Do not disturb: pausing {}", task);
+    emit_change(app, true);
+    while is_active() {
+        tokio::time::sleep(RECHECK_INTERVAL).await;
+    }
+    log::info!("Do not disturb ended: resuming {}", task);
+    emit_change(app, false);
+}
+
+/// Gets whether background work is paused and why.
+#[tauri::command]
+pub fn get_quiet_mode_status() -> QuietModeStatus {
+    status()
+}
+
+/// Switches the manual do-not-disturb mode on or off.
+#[tauri::command]
+pub fn set_do_not_disturb(app: AppHandle, enabled: bool) -> Result<(), String> {
+    settings::set_config_value(
+        app.clone(),
+        QUIET_MODE_ENABLED.to_string(),
+        serde_json::Value::Bool(enabled),
+    )?;
+    emit_change(&app, is_active());
+    Ok(())
+}
@@ -0,0 +1,132 @@
+//! Deleting files through the Recycle Bin.
+//!
+//! With `cleanup.useRecycleBin` on, cache clears, old version cleanup and bucket
+//! removal move what they delete to the Recycle Bin so it can be restored. Commands
+//! taking a `permanent` flag still erase on request. Deletions Scoop makes itself,
+//! such as `scoop cleanup`, are not affected.
+use crate::commands::settings;
+use crate::config_keys::CLEANUP_USE_RECYCLE_BIN;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Runtime};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Reads the Recycle Bin setting from the app store.
+pub fn configure<R: Runtime>(app: &AppHandle<R>) {
+    let enabled = settings::get_config_value(app.clone(), CLEANUP_USE_RECYCLE_BIN.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Whether a deletion should go to the Recycle Bin; `permanent` overrides the setting.
+pub fn should_recycle(permanent: Option<bool>) -> bool {
+    permanent.map(|p| !p).unwrap_or_else(is_enabled)
+}
+
+#[cfg(windows)]
+fn move_to_recycle_bin(paths: &[PathBuf]) -> Result<(), String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::UI::Shell::{
+        SHFileOperationW, FOF_ALLOWUNDO, FOF_NOCONFIRMATION, FOF_NOERRORUI, FOF_SILENT, FO_DELETE,
+        SHFILEOPSTRUCTW,
+    };
+
+    // A list of absolute paths, each NUL terminated, ended by an extra NUL
+    let mut from: Vec<u16> = Vec::new();
+    for path in paths {
+        let absolute = std::path::absolute(path).unwrap_or_else(|_| path.clone());
+        from.extend(absolute.as_os_str().encode_wide());
+        from.push(0);
+    }
+    from.push(0);
+
+    let mut operation = SHFILEOPSTRUCTW {
+        wFunc: FO_DELETE,
+        pFrom: from.as_ptr(),
+        fFlags: (FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_NOERRORUI | FOF_SILENT) as u16,
+        ..Default::default()
+    };
+    let result = unsafe { SHFileOperationW(&mut operation) };
+    if result != 0 {
+        return Err(format!(
+            "Moving to the Recycle Bin failed with code {}",
+            result
+        ));
+    }
+    if operation.fAnyOperationsAborted != 0 {
+        return Err("Moving to the Recycle Bin was aborted".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn move_to_recycle_bin(paths: &[PathBuf]) -> Result<(), String> {
+    log::warn!("No Recycle Bin on this platform, deleting permanently");
+    paths.iter().try_for_each(|path| remove_permanently(path))
+}
+
+fn remove_permanently(path: &Path) -> Result<(), String> {
+    let result = match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => std::fs::remove_dir_all(path),
+        Ok(_) => std::fs::remove_file(path),
+        Err(_) => return Ok(()),
+    };
+    result.map_err(|e| format!("Failed to delete {}: {}", path.display(), e))
+}
+
+/// Deletes files and directories, through the Recycle Bin when `recycle` is set.
+///
+/// Paths that do not exist are skipped.
+pub fn remove_paths(paths: &[PathBuf], recycle: bool) -> Result<(), String> {
+    let existing: Vec<PathBuf> = paths
+        .iter()
+        .filter(|p| std::fs::symlink_metadata(p).is_ok())
+        .cloned()
+        .collect();
+    if existing.is_empty() {
+        return Ok(());
+    }
+    if recycle {
+        log::info!("Moving {} items to the Recycle Bin", existing.len());
+        move_to_recycle_bin(&existing)
+    } else {
+        existing
+            .iter()
+            .try_for_each(|path| remove_permanently(path))
+    }
+}
+
+/// Deletes one file or directory, through the Recycle Bin when `recycle` is set.
+pub fn remove_path(path: &Path, recycle: bool) -> Result<(), String> {
+    remove_paths(&[path.to_path_buf()], recycle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_files_and_directories_permanently() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("git#2.44.0#abc.zip");
+        let version = dir.path().join("2.44.0");
+        std::fs::write(&file, b"zip").unwrap();
+        std::fs::create_dir_all(version.join("bin")).unwrap();
+
+        let missing = dir.path().join("missing");
+        remove_paths(&[file.clone(), version.clone(), missing], false).unwrap();
+        assert!(!file.exists());
+        assert!(!version.exists());
+
+        assert!(!should_recycle(Some(true)));
+        assert!(should_recycle(Some(false)));
+    }
+}
@@ -0,0 +1,124 @@
+//! Thread pool for the parallel filesystem scans.
+//!
+//! The installed scan and search read hundreds of files at once, which on a hard disk
+//! can stall the whole machine. `performance.scanThreads` caps the number of threads
+//! (0 keeps rayon's default of one per core), and `performance.lowPriorityIo` runs the
+//! scan threads in Windows background mode, which lowers their I/O and memory priority.
+use crate::commands::settings;
+use crate::config_keys::{PERFORMANCE_LOW_PRIORITY_IO, PERFORMANCE_SCAN_THREADS};
+use once_cell::sync::Lazy;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::{Arc, RwLock};
+use tauri::{AppHandle, Runtime};
+
+/// Most threads a scan may use, whatever is configured.
+const MAX_SCAN_THREADS: usize = 64;
+
+/// The configured pool; `None` while the defaults apply and rayon's global pool is used.
+static POOL: Lazy<RwLock<Option<Arc<ThreadPool>>>> = Lazy::new(|| RwLock::new(None));
+
+/// Turns the thread count setting into a pool size; `None` for the default.
+pub fn thread_count(setting: Option<u64>) -> Option<usize> {
+    match setting {
+        Some(0) | None => None,
+        Some(n) => Some((n as usize).min(MAX_SCAN_THREADS)),
+    }
+}
+
+#[cfg(windows)]
+fn enter_background_mode() {
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentThread, SetThreadPriority, THREAD_MODE_BACKGROUND_BEGIN,
+    };
+    if unsafe { SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_BEGIN) } == 0 {
+        log::warn!(
+            "Failed to put scan thread in background mode: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
+#[cfg(not(windows))]
+fn enter_background_mode() {}
+
+fn build_pool(threads: Option<usize>, low_priority: bool) -> Result<ThreadPool, String> {
+    let mut builder = ThreadPoolBuilder::new().thread_name(|i| format!("scan-{}", i));
+    if let Some(threads) = threads {
+        builder = builder.num_threads(threads);
+    }
+    if low_priority {
+        builder = builder.start_handler(|_| enter_background_mode());
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to build scan thread pool: {}", e))
+}
+
+/// Reads the scan settings from the app store and rebuilds the pool.
+pub fn configure<R: Runtime>(app: &AppHandle<R>) {
+    let read = |key: &str| {
+        settings::get_config_value(app.clone(), key.to_string())
+            .ok()
+            .flatten()
+    };
+    let threads = thread_count(read(PERFORMANCE_SCAN_THREADS).and_then(|v| v.as_u64()));
+    let low_priority = read(PERFORMANCE_LOW_PRIORITY_IO)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let pool = if threads.is_none() && !low_priority {
+        None
+    } else {
+        match build_pool(threads, low_priority) {
+            Ok(pool) => {
+                log::info!(
+                    "Scans use {} threads{}",
+                    pool.current_num_threads(),
+                    if low_priority {
+                        " in background mode"
+                    } else {
+                        ""
+                    }
+                );
+                Some(Arc::new(pool))
+            }
+            Err(e) => {
+                log::warn!("{}", e);
+                None
+            }
+        }
+    };
+    // Scans running in the previous pool keep it alive until they finish
+    *POOL.write().unwrap_or_else(|e| e.into_inner()) = pool;
+}
+
+/// Runs `op` in the scan pool, so the parallel iterators inside it use the configured
+/// threads; in rayon's global pool when nothing is configured.
+pub fn install<T: Send>(op: impl FnOnce() -> T + Send) -> T {
+    let pool = POOL.read().unwrap_or_else(|e| e.into_inner()).clone();
+    match pool {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rayon::prelude::*;
+
+    #[test]
+    fn limits_threads_of_the_pool() {
+        assert_eq!(thread_count(None), None);
+        assert_eq!(thread_count(Some(0)), None);
+        assert_eq!(thread_count(Some(2)), Some(2));
+        assert_eq!(thread_count(Some(1000)), Some(MAX_SCAN_THREADS));
+
+        let pool = build_pool(Some(2), true).unwrap();
+        let sum: u32 = pool.install(|| {
+            assert_eq!(rayon::current_num_threads(), 2);
+            (1..=100u32).into_par_iter().sum()
+        });
+        assert_eq!(sum, 5050);
+    }
+}
@@ -1,78 +1,136 @@
 use tauri::{AppHandle, Emitter, Manager};
 
+/// How often the maintenance task prunes old log files.
+const MAINTENANCE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(6 * 3600);
+/// How often the snapshot task checks whether a status snapshot is due.
+const SNAPSHOT_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(600);
+
 pub fn start_background_tasks(app: AppHandle) {
+    let snapshot_app = app.clone();
+    crate::supervisor::spawn_supervised("autoUpdate", move || auto_update_loop(app.clone()));
+    crate::supervisor::spawn_supervised("statusSnapshots", move || {
+        status_snapshot_loop(snapshot_app.clone())
+    });
+    crate::supervisor::spawn_supervised("maintenance", maintenance_loop);
+}
+
+/// Periodically prunes old log files so long-running sessions don't accumulate them.
+async fn maintenance_loop() {
+    loop {
+        tokio::time::sleep(MAINTENANCE_INTERVAL).await;
+        if let Some(log_dir) = crate::portable::log_dir() {
+            log::debug!("Running periodic log cleanup");
+            crate::cleanup_old_logs(&log_dir);
+        }
+    }
+}
+
+/// Records a status snapshot whenever the configured interval (daily by default) has
+/// passed since the last one.
+async fn status_snapshot_loop(app: AppHandle) {
+    use crate::commands::status_history::{last_snapshot_time, take_snapshot};
+
+    loop {
+        tokio::time::sleep(SNAPSHOT_CHECK_INTERVAL).await;
+        let interval_raw = crate::commands::settings::get_config_value(
+            app.clone(),
+            crate::config_keys::STATUS_SNAPSHOT_INTERVAL.to_string(),
+        )
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_else(|| "24h".to_string());
+        let Some(interval_secs) = parse_update_interval(&interval_raw) else {
+            continue;
+        };
+
+        let due = last_snapshot_time()
+            .map(|last| (chrono::Utc::now() - last).num_seconds().max(0) as u64 >= interval_secs)
+            .unwrap_or(true);
+        if due && !crate::quiet::is_active() {
+            if let Err(e) = take_snapshot(&app).await {
+                log::warn!("Scheduled status snapshot failed: {}", e);
+            }
+        }
+    }
+}
+
+async fn auto_update_loop(app: AppHandle) {
     use std::time::{Duration, SystemTime, UNIX_EPOCH};
     use tokio::time::sleep;
 
-    tauri::async_runtime::spawn(async move {
-        log::info!("Background tasks started");
+    log::info!("Auto-update scheduler started");
 
-        loop {
-            // Parse auto-update interval from settings with better error handling
-            let interval_raw = crate::commands::settings::get_config_value(
-                app.clone(),
-                "buckets.autoUpdateInterval".to_string(),
-            )
-            .ok()
-            .flatten()
-            .and_then(|v| v.as_str().map(|s| s.to_string()))
-            .unwrap_or_else(|| "off".to_string());
+    loop {
+        // Parse auto-update interval from settings with better error handling
+        let interval_raw = crate::commands::settings::get_config_value(
+            app.clone(),
+            "buckets.autoUpdateInterval".to_string(),
+        )
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_else(|| "off".to_string());
 
-            let interval_secs = parse_update_interval(&interval_raw);
+        let interval_secs = parse_update_interval(&interval_raw);
 
-            if interval_secs.is_none() {
-                // Auto-update is disabled, check again later
-                sleep(Duration::from_secs(300)).await; // 5 minutes when auto-update is disabled
-                continue;
-            }
-            let interval_secs = interval_secs.unwrap();
-
-            // Check if an update is needed
-            let now = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
-            let last_ts = crate::commands::settings::get_config_value(
-                app.clone(),
-                "buckets.lastAutoUpdateTs".to_string(),
-            )
-            .ok()
-            .flatten()
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0);
-
-            let elapsed = if last_ts == 0 {
-                interval_secs
-            } else {
-                now.saturating_sub(last_ts)
-            };
-
-            if elapsed >= interval_secs {
-                log::debug!(
-                    "Auto-update interval elapsed ({}s), starting update check",
-                    elapsed
-                );
-                run_auto_update(&app, now).await;
-                continue;
-            }
+        if interval_secs.is_none() {
+            // Auto-update is disabled, check again later
+            sleep(Duration::from_secs(300)).await; // 5 minutes when auto-update is disabled
+            continue;
+        }
+        let interval_secs = interval_secs.unwrap();
 
-            // Calculate sleep duration (check at most every 60 seconds)
-            let remaining = interval_secs - elapsed;
-            let sleep_duration =
-                Duration::from_secs(remaining.min(60)); // Check every minute at most
+        // Check if an update is needed
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let last_ts = crate::commands::settings::get_config_value(
+            app.clone(),
+            "buckets.lastAutoUpdateTs".to_string(),
+        )
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
 
+        let elapsed = if last_ts == 0 {
+            interval_secs
+        } else {
+            now.saturating_sub(last_ts)
+        };
+
+        if elapsed >= interval_secs {
             log::debug!(
-                "Next scheduler check in {} seconds (auto-update interval: {}s, remaining: {}s)",
-                sleep_duration.as_secs(),
-                interval_secs,
-                remaining
+                "Auto-update interval elapsed ({}s), starting update check",
+                elapsed
             );
-            sleep(sleep_duration).await;
+            crate::quiet::wait_while_active(&app, "scheduled bucket update").await;
+            run_auto_update(&app, now).await;
+            continue;
         }
-    });
+
+        // Calculate sleep duration (check at most every 60 seconds)
+        let remaining = interval_secs - elapsed;
+        let sleep_duration =
+            Duration::from_secs(remaining.min(60)); // Check every minute at most
+
+        log::debug!(
+            "Next scheduler check in {} seconds (auto-update interval: {}s, remaining: {}s)",
+            sleep_duration.as_secs(),
+            interval_secs,
+            remaining
+        );
+        sleep(sleep_duration).await;
+    }
 }
 
 async fn run_auto_update(app_handle: &tauri::AppHandle, run_started_at: u64) {
+    if crate::policy::is_read_only() {
+        log::info!("Skipping auto bucket update in read-only mode");
+        return;
+    }
     log::info!("Starting auto bucket update task");
 
     // Check if silent update is enabled
@@ -259,7 +317,7 @@ async fn update_packages_after_buckets(app_handle: &tauri::AppHandle, silent_upd
     }
 }
 
-fn parse_update_interval(interval_raw: &str) -> Option<u64> {
+pub fn parse_update_interval(interval_raw: &str) -> Option<u64> {
     match interval_raw {
         "24h" | "1d" => Some(86400), // 24 hours
         "7d" | "1w" => Some(604800), // 7 days
@@ -1,10 +1,30 @@
-use crate::models::ScoopPackage;
-use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::RwLock;
-use std::time::{SystemTime, UNIX_EPOCH};
-use tokio::sync::Mutex;
+use crate::models::{
+    CommandStats, FailedInstall, InstalledCacheInfo, PackageActivity, PackageState, ScoopPackage,
+};
+use crate::utils::ScoopDirs;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex as StdMutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::sync::{mpsc, Mutex};
+
+/// Emitted with [`CacheInvalidation`] when changes on disk dropped cached data.
+pub const EVENT_CACHE_INVALIDATED: &str = "cache-invalidated";
+/// How long the watched directories must stay quiet before caches are dropped; Scoop
+/// extracting an archive or git checking out a bucket changes many files at once.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// Current time in milliseconds since the Unix epoch.
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
 
 #[derive(Clone)]
 pub struct InstalledPackagesCache {
@@ -18,6 +38,59 @@ pub struct PackageVersionsCache {
     pub versions_map: HashMap<String, Vec<String>>, // package_name -> list of version dirs
 }
 
+/// Which caches changes on disk made stale.
+#[derive(Serialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheInvalidation {
+    /// Installed packages and their versions.
+    pub installed: bool,
+    /// Bucket manifests used by searches.
+    pub manifests: bool,
+}
+
+impl CacheInvalidation {
+    /// Classifies a changed path. Only app and version directories and their
+    /// `install.json` or `manifest.json` affect the installed packages, so apps writing
+    /// into their own directories are ignored, as are bucket files other than manifests
+    /// and anything under `.git`.
+    pub fn for_path(dirs: &ScoopDirs, path: &Path) -> Self {
+        let depth_below = |base: &Path| {
+            let relative = path.strip_prefix(base).ok()?;
+            let components: Vec<_> = relative.components().collect();
+            let in_git = components
+                .iter()
+                .any(|c| matches!(c, Component::Normal(name) if *name == ".git"));
+            (!in_git).then_some(components.len())
+        };
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+
+        let installed = [&dirs.apps, &dirs.global_apps].iter().any(|apps| {
+            depth_below(apps).is_some_and(|depth| {
+                (1..=2).contains(&depth)
+                    || (depth == 3 && matches!(file_name, "install.json" | "manifest.json"))
+            })
+        });
+        let manifests = depth_below(&dirs.buckets)
+            .is_some_and(|depth| depth == 1 || (depth > 1 && file_name.ends_with(".json")));
+        Self {
+            installed,
+            manifests,
+        }
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.installed |= other.installed;
+        self.manifests |= other.manifests;
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.installed && !self.manifests
+    }
+}
+
 /// Shared application state managed by Tauri.
 pub struct AppState {
     /// The resolved path to the Scoop installation directory.
@@ -28,6 +101,32 @@ pub struct AppState {
     pub package_versions: Mutex<Option<PackageVersionsCache>>,
     /// Timestamp (ms) of the last installed packages refresh to prevent rapid consecutive calls
     last_refresh_time: AtomicU64,
+    /// Installs that failed during this session, keyed by package name
+    pub failed_installs: Mutex<HashMap<String, FailedInstall>>,
+    /// Held while an installed packages scan runs so overlapping scans are serialized
+    pub installed_scan_lock: Mutex<()>,
+    /// Whether an installed packages scan is currently running
+    installed_scan_in_progress: AtomicBool,
+    /// Incremented every time an installed packages scan finishes
+    installed_scan_generation: AtomicU64,
+    /// Fingerprint seen by the most recent scan; unlike the cache it survives invalidation
+    pub last_scan_fingerprint: Mutex<Option<String>>,
+    /// Set while the app changes installs itself, so the next scan does not report them as external
+    own_changes_pending: AtomicBool,
+    /// Installed version by package name as of the last parsed scan
+    pub last_scan_versions: Mutex<Option<HashMap<String, String>>>,
+    /// Incremented whenever the installed packages cache is replaced or invalidated
+    installed_cache_generation: AtomicU64,
+    /// Timestamp (ms) of the last completed installed packages scan, 0 if none yet
+    last_scan_time: AtomicU64,
+    /// Packages with an operation queued, running or failed; absent packages are idle
+    package_states: StdMutex<HashMap<String, PackageState>>,
+    /// Held while a Scoop operation runs so operations run one at a time
+    pub operation_queue: Mutex<()>,
+    /// Per-command invoke timings, keyed by command name
+    command_stats: StdMutex<HashMap<String, CommandStats>>,
+    /// Watches the Scoop directories; replaced when the Scoop path changes
+    cache_watcher: StdMutex<Option<RecommendedWatcher>>,
 }
 
 impl AppState {
@@ -38,6 +137,19 @@ impl AppState {
             installed_packages: Mutex::new(None),
             package_versions: Mutex::new(None),
             last_refresh_time: AtomicU64::new(0),
+            failed_installs: Mutex::new(HashMap::new()),
+            installed_scan_lock: Mutex::new(()),
+            installed_scan_in_progress: AtomicBool::new(false),
+            installed_scan_generation: AtomicU64::new(0),
+            last_scan_fingerprint: Mutex::new(None),
+            own_changes_pending: AtomicBool::new(false),
+            last_scan_versions: Mutex::new(None),
+            installed_cache_generation: AtomicU64::new(0),
+            last_scan_time: AtomicU64::new(0),
+            package_states: StdMutex::new(HashMap::new()),
+            operation_queue: Mutex::new(()),
+            command_stats: StdMutex::new(HashMap::new()),
+            cache_watcher: StdMutex::new(None),
         }
     }
 
@@ -46,11 +158,65 @@ impl AppState {
         self.scoop_path.read().unwrap().clone()
     }
 
+    /// Returns the special Scoop directories, honoring relocations in Scoop's config.
+    pub fn scoop_dirs(&self) -> ScoopDirs {
+        ScoopDirs::resolve(&self.scoop_path())
+    }
+
     /// Updates the Scoop root path stored in the application state.
     pub fn set_scoop_path(&self, new_path: PathBuf) {
         *self.scoop_path.write().unwrap() = new_path;
     }
 
+    /// Returns `true` while an installed packages scan is running
+    pub fn is_installed_scan_in_progress(&self) -> bool {
+        self.installed_scan_in_progress.load(Ordering::SeqCst)
+    }
+
+    /// Marks an installed packages scan as started or finished
+    pub fn set_installed_scan_in_progress(&self, in_progress: bool) {
+        self.installed_scan_in_progress.store(in_progress, Ordering::SeqCst);
+    }
+
+    /// Gets the number of installed packages scans completed so far
+    pub fn installed_scan_generation(&self) -> u64 {
+        self.installed_scan_generation.load(Ordering::SeqCst)
+    }
+
+    /// Records that an installed packages scan has completed
+    pub fn finish_installed_scan(&self) {
+        self.installed_scan_generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Marks the installed packages cache as changed, returning the new generation
+    pub fn bump_installed_cache_generation(&self) -> u64 {
+        self.installed_cache_generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Records that the app itself is changing installed packages
+    pub fn note_own_changes(&self) {
+        self.own_changes_pending.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns whether the app changed installed packages since the last call
+    pub fn take_own_changes(&self) -> bool {
+        self.own_changes_pending.swap(false, Ordering::SeqCst)
+    }
+
+    /// Records that the apps directory has just been scanned
+    pub fn update_last_scan_time(&self) {
+        self.last_scan_time.store(now_millis(), Ordering::SeqCst);
+    }
+
+    /// Gets the installed packages cache generation and last scan time
+    pub fn installed_cache_info(&self) -> InstalledCacheInfo {
+        let last_scan = self.last_scan_time.load(Ordering::SeqCst);
+        InstalledCacheInfo {
+            generation: self.installed_cache_generation.load(Ordering::SeqCst),
+            last_scan_at: (last_scan != 0).then_some(last_scan),
+        }
+    }
+
     /// Gets the timestamp of the last installed packages refresh in milliseconds
     pub fn last_refresh_time(&self) -> u64 {
         self.last_refresh_time.load(Ordering::Relaxed)
@@ -58,19 +224,13 @@ impl AppState {
 
     /// Updates the timestamp of the last installed packages refresh
     pub fn update_refresh_time(&self) {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_millis() as u64)
-            .unwrap_or(0);
+        let now = now_millis();
         self.last_refresh_time.store(now, Ordering::Relaxed);
     }
 
     /// Checks if a refresh should be debounced (less than 1 second since last refresh)
     pub fn should_debounce_refresh(&self) -> bool {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_millis() as u64)
-            .unwrap_or(0);
+        let now = now_millis();
         let last_refresh = self.last_refresh_time();
         
         // If last_refresh is 0, it's the first run, so don't debounce
@@ -80,4 +240,173 @@ impl AppState {
         
         now.saturating_sub(last_refresh) < 1000 // Debounce within 1 second
     }
+
+    /// Records the state of a package and returns it; `Idle` removes the entry
+    pub fn set_package_state(
+        &self,
+        name: &str,
+        activity: PackageActivity,
+        operation_id: Option<String>,
+        message: Option<String>,
+    ) -> PackageState {
+        let entry = PackageState {
+            name: name.to_string(),
+            state: activity,
+            operation_id,
+            message,
+            updated_at: now_millis(),
+        };
+        let mut states = self.package_states.lock().unwrap_or_else(|e| e.into_inner());
+        if activity == PackageActivity::Idle {
+            states.remove(&name.to_lowercase());
+        } else {
+            states.insert(name.to_lowercase(), entry.clone());
+        }
+        entry
+    }
+
+    /// Gets the state of a package that is not idle
+    pub fn package_state(&self, name: &str) -> Option<PackageState> {
+        self.package_states
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&name.to_lowercase())
+            .cloned()
+    }
+
+    /// Gets every package that is not idle
+    pub fn package_states(&self) -> Vec<PackageState> {
+        self.package_states
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Records one invocation of an IPC command
+    pub fn record_command(&self, command: &str, duration: Duration, success: bool) {
+        let millis = duration.as_secs_f64() * 1000.0;
+        let mut stats = self.command_stats.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = stats.entry(command.to_string()).or_default();
+        entry.calls += 1;
+        if !success {
+            entry.errors += 1;
+        }
+        entry.last_ms = millis;
+        entry.max_ms = entry.max_ms.max(millis);
+        entry.average_ms += (millis - entry.average_ms) / entry.calls as f64;
+    }
+
+    /// Gets the invoke timings of every command called so far
+    pub fn command_stats(&self) -> BTreeMap<String, CommandStats> {
+        self.command_stats
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(name, stats)| (name.clone(), stats.clone()))
+            .collect()
+    }
+
+    /// Watches `apps` and `buckets` of the current Scoop path, replacing any previous
+    /// watcher, and drops the caches that changes made outside the app leave stale.
+    pub fn watch_scoop_dirs<R: Runtime>(&self, app: &AppHandle<R>) {
+        let dirs = self.scoop_dirs();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let watched_dirs = dirs.clone();
+        let handler = move |result: notify::Result<notify::Event>| {
+            let Ok(event) = result else {
+                return;
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            for path in &event.paths {
+                let invalidation = CacheInvalidation::for_path(&watched_dirs, path);
+                if !invalidation.is_empty() {
+                    let _ = sender.send(invalidation);
+                }
+            }
+        };
+
+        let mut watcher = match notify::recommended_watcher(handler) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                log::warn!("Failed to create file watcher: {}", e);
+                return;
+            }
+        };
+        for dir in [&dirs.apps, &dirs.global_apps, &dirs.buckets] {
+            if !dir.is_dir() {
+                continue;
+            }
+            match watcher.watch(dir, RecursiveMode::Recursive) {
+                Ok(()) => log::info!("Watching {} for changes", dir.display()),
+                Err(e) => log::warn!("Failed to watch {}: {}", dir.display(), e),
+            }
+        }
+        // Dropping the previous watcher closes its channel, which ends its task
+        *self.cache_watcher.lock().unwrap_or_else(|e| e.into_inner()) = Some(watcher);
+        tauri::async_runtime::spawn(invalidate_on_changes(app.clone(), receiver));
+    }
+}
+
+/// Drops the caches reported by the watcher once a burst of changes has settled and no
+/// operation is running.
+async fn invalidate_on_changes<R: Runtime>(
+    app: AppHandle<R>,
+    mut receiver: mpsc::UnboundedReceiver<CacheInvalidation>,
+) {
+    while let Some(first) = receiver.recv().await {
+        let mut invalidation = first;
+        while let Ok(Some(next)) = tokio::time::timeout(WATCH_DEBOUNCE, receiver.recv()).await {
+            invalidation.merge(next);
+        }
+
+        let state = app.state::<AppState>();
+        // Changes seen during an operation are applied once it has finished, so they
+        // are not mistaken for a half-done install. Later changes queue up meanwhile.
+        drop(state.operation_queue.lock().await);
+        while let Ok(next) = receiver.try_recv() {
+            invalidation.merge(next);
+        }
+        log::info!(
+            "Scoop directories changed on disk (installed: {}, manifests: {})",
+            invalidation.installed,
+            invalidation.manifests
+        );
+        if invalidation.installed {
+            crate::commands::installed::clear_installed_cache(&state).await;
+        }
+        if invalidation.manifests {
+            crate::commands::search::invalidate_manifest_cache().await;
+        }
+        if let Err(e) = app.emit(EVENT_CACHE_INVALIDATED, invalidation) {
+            log::warn!("Failed to emit {} event: {}", EVENT_CACHE_INVALIDATED, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_changed_paths() {
+        let dirs = ScoopDirs::resolve(Path::new("scoop"));
+        let apps = dirs.apps.join("git");
+        let bucket = dirs.buckets.join("main");
+        let classify = |path: PathBuf| CacheInvalidation::for_path(&dirs, &path);
+
+        assert!(classify(apps.clone()).installed);
+        assert!(classify(apps.join("current")).installed);
+        assert!(classify(apps.join("2.45.0").join("install.json")).installed);
+        assert!(classify(apps.join("current").join("logs").join("git.log")).is_empty());
+
+        assert!(classify(bucket.clone()).manifests);
+        assert!(classify(bucket.join("bucket").join("git.json")).manifests);
+        assert!(classify(bucket.join(".git").join("refs").join("main.json")).is_empty());
+        assert!(classify(bucket.join("README.md")).is_empty());
+        assert!(classify(dirs.cache.join("git.7z")).is_empty());
+    }
 }
@@ -0,0 +1,121 @@
+//! Supervision for long-running background tasks.
+//!
+//! Tasks started through [`spawn_supervised`] are tracked by name. If a task panics it is
+//! restarted with exponential backoff; the current state of every task can be queried
+//! with the `get_background_tasks_status` command.
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// First restart delay after a crash.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// Upper bound for the restart delay.
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+/// A task that ran at least this long before crashing restarts with the initial backoff.
+const STABLE_RUN: Duration = Duration::from_secs(600);
+
+static TASKS: Lazy<Mutex<HashMap<String, BackgroundTaskStatus>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TaskState {
+    Running,
+    /// Crashed and waiting for its backoff to elapse.
+    Restarting,
+    /// Returned normally; one-shot tasks end up here.
+    Completed,
+}
+
+/// Status of a supervised background task.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BackgroundTaskStatus {
+    pub name: String,
+    pub state: TaskState,
+    pub restarts: u32,
+    pub started_at: String,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<String>,
+}
+
+fn update_task(name: &str, update: impl FnOnce(&mut BackgroundTaskStatus)) {
+    if let Ok(mut tasks) = TASKS.lock() {
+        let status = tasks
+            .entry(name.to_string())
+            .or_insert_with(|| BackgroundTaskStatus {
+                name: name.to_string(),
+                state: TaskState::Running,
+                restarts: 0,
+                started_at: chrono::Local::now().to_rfc3339(),
+                last_error: None,
+                last_error_at: None,
+            });
+        update(status);
+    }
+}
+
+/// Spawns `make_task` under supervision.
+///
+/// The factory is called again to restart the task whenever it panics. A task that
+/// returns normally is marked completed and not restarted.
+pub fn spawn_supervised<F, Fut>(name: &'static str, make_task: F)
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            update_task(name, |status| {
+                status.state = TaskState::Running;
+                status.started_at = chrono::Local::now().to_rfc3339();
+            });
+
+            let run_started = Instant::now();
+            let result = tauri::async_runtime::spawn(make_task()).await;
+
+            let error = match result {
+                Ok(()) => {
+                    log::info!("Background task '{}' completed", name);
+                    update_task(name, |status| status.state = TaskState::Completed);
+                    return;
+                }
+                Err(e) => e.to_string(),
+            };
+
+            if run_started.elapsed() >= STABLE_RUN {
+                backoff = INITIAL_BACKOFF;
+            }
+            log::error!(
+                "Background task '{}' crashed: {}. Restarting in {}s",
+                name,
+                error,
+                backoff.as_secs()
+            );
+            update_task(name, |status| {
+                status.state = TaskState::Restarting;
+                status.restarts += 1;
+                status.last_error = Some(error);
+                status.last_error_at = Some(chrono::Local::now().to_rfc3339());
+            });
+
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    });
+}
+
+/// Lists every supervised background task, sorted by name.
+#[tauri::command]
+pub fn get_background_tasks_status() -> Vec<BackgroundTaskStatus> {
+    let mut tasks: Vec<BackgroundTaskStatus> = TASKS
+        .lock()
+        .map(|tasks| tasks.values().cloned().collect())
+        .unwrap_or_default();
+    tasks.sort_by(|a, b| a.name.cmp(&b.name));
+    tasks
+}
@@ -0,0 +1,286 @@
+//! Synthetic Scoop directory trees for tests.
+//!
+//! `ScoopFixture` creates a throwaway Scoop root (apps, buckets, shims, cache, persist)
+//! in a temporary directory that is removed when the fixture is dropped. The `current`
+//! link is a junction on Windows and a symlink elsewhere; when the platform refuses to
+//! create it, [`ScoopFixture::link_current`] reports `false` so tests can skip checks
+//! that depend on it. Helpers overwrite the metadata Scoop writes, turn a bucket into a
+//! git repository and read files back, so tests only spell out what they check.
+use git2::{Oid, Repository};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+pub struct ScoopFixture {
+    dir: TempDir,
+}
+
+impl ScoopFixture {
+    /// Creates an empty Scoop root with the standard top-level directories.
+    pub fn new() -> Self {
+        let dir = tempfile::tempdir().expect("failed to create fixture directory");
+        for sub in ["apps", "buckets", "cache", "persist", "shims"] {
+            fs::create_dir_all(dir.path().join(sub)).unwrap();
+        }
+        Self { dir }
+    }
+
+    pub fn root(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub fn apps_dir(&self) -> PathBuf {
+        self.root().join("apps")
+    }
+
+    pub fn app_dir(&self, package: &str) -> PathBuf {
+        self.apps_dir().join(package)
+    }
+
+    pub fn cache_dir(&self) -> PathBuf {
+        self.root().join("cache")
+    }
+
+    pub fn shims_dir(&self) -> PathBuf {
+        self.root().join("shims")
+    }
+
+    pub fn persist_dir(&self, package: &str) -> PathBuf {
+        self.root().join("persist").join(package)
+    }
+
+    pub fn bucket_dir(&self, name: &str) -> PathBuf {
+        self.root().join("buckets").join(name)
+    }
+
+    /// Creates `buckets/<name>/bucket`.
+    pub fn bucket(&self, name: &str) -> &Self {
+        fs::create_dir_all(self.bucket_dir(name).join("bucket")).unwrap();
+        self
+    }
+
+    /// Makes `buckets/<name>` a git repository with its current contents committed.
+    pub fn git_bucket(&self, name: &str) -> Repository {
+        self.bucket(name);
+        let repo = Repository::init(self.bucket_dir(name)).unwrap();
+        commit_all(&repo, "base");
+        repo
+    }
+
+    /// Writes a minimal bucket manifest for `package`.
+    pub fn bucket_manifest(&self, bucket: &str, package: &str, version: &str) -> &Self {
+        self.bucket_manifest_json(
+            bucket,
+            package,
+            json!({ "version": version, "description": format!("{} fixture", package) }),
+        )
+    }
+
+    /// Writes an arbitrary bucket manifest for `package`.
+    pub fn bucket_manifest_json(&self, bucket: &str, package: &str, manifest: Value) -> &Self {
+        self.bucket(bucket);
+        write_json(
+            &self
+                .bucket_dir(bucket)
+                .join("bucket")
+                .join(format!("{}.json", package)),
+            &manifest,
+        );
+        self
+    }
+
+    /// Creates `apps/<package>/<version>` with `manifest.json` and `install.json`.
+    ///
+    /// A `bucket` of `None` produces an install.json without a bucket, which is how
+    /// versioned installs look on disk.
+    pub fn app_version(&self, package: &str, version: &str, bucket: Option<&str>) -> PathBuf {
+        let version_dir = self.app_dir(package).join(version);
+        fs::create_dir_all(&version_dir).unwrap();
+        write_json(
+            &version_dir.join("manifest.json"),
+            &json!({ "version": version, "description": format!("{} fixture", package) }),
+        );
+        let install = match bucket {
+            Some(bucket) => json!({ "bucket": bucket, "architecture": "64bit" }),
+            None => json!({ "architecture": "64bit" }),
+        };
+        write_json(&version_dir.join("install.json"), &install);
+        version_dir
+    }
+
+    /// Replaces the `manifest.json` of an installed version and returns its directory.
+    pub fn installed_manifest(&self, package: &str, version: &str, manifest: Value) -> PathBuf {
+        let version_dir = self.app_dir(package).join(version);
+        write_json(&version_dir.join("manifest.json"), &manifest);
+        version_dir
+    }
+
+    /// Replaces the `install.json` of an installed version and returns its directory.
+    pub fn install_info(&self, package: &str, version: &str, info: Value) -> PathBuf {
+        let version_dir = self.app_dir(package).join(version);
+        write_json(&version_dir.join("install.json"), &info);
+        version_dir
+    }
+
+    /// Overwrites the `install.json` of an installed version with invalid JSON.
+    pub fn corrupt_install_info(&self, package: &str, version: &str) -> PathBuf {
+        let version_dir = self.app_dir(package).join(version);
+        fs::write(version_dir.join("install.json"), "{ broken").unwrap();
+        version_dir
+    }
+
+    /// Points `apps/<package>/current` at `version`, replacing any existing link.
+    ///
+    /// Returns `false` if the platform does not allow creating the link.
+    pub fn link_current(&self, package: &str, version: &str) -> bool {
+        let current = self.app_dir(package).join("current");
+        if fs::symlink_metadata(&current).is_ok() {
+            let _ = fs::remove_dir(&current).or_else(|_| fs::remove_file(&current));
+        }
+        create_dir_link(&current, &self.app_dir(package).join(version))
+    }
+
+    /// Installs `package` at `version` from `bucket` and links it as current.
+    pub fn install(&self, package: &str, version: &str, bucket: &str) -> &Self {
+        self.app_version(package, version, Some(bucket));
+        self.link_current(package, version);
+        self
+    }
+
+    /// Writes a Scoop shim (`<name>.shim` plus a placeholder `<name>.exe`).
+    pub fn shim(&self, name: &str, target: &Path) -> &Self {
+        let shims = self.shims_dir();
+        fs::write(
+            shims.join(format!("{}.shim", name)),
+            format!("path = \"{}\"\n", target.display()),
+        )
+        .unwrap();
+        fs::write(shims.join(format!("{}.exe", name)), b"MZ").unwrap();
+        self
+    }
+
+    /// Writes a download cache entry named the way Scoop names them.
+    pub fn cache_file(&self, package: &str, version: &str, file_name: &str) -> PathBuf {
+        let path = self
+            .cache_dir()
+            .join(format!("{}#{}#{}", package, version, file_name));
+        fs::write(&path, b"cached").unwrap();
+        path
+    }
+}
+
+impl Default for ScoopFixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn write_json(path: &Path, value: &Value) {
+    fs::write(path, serde_json::to_string_pretty(value).unwrap()).unwrap();
+}
+
+/// Writes `contents` to `path`, creating its parent directories.
+pub fn write_file(path: &Path, contents: impl AsRef<[u8]>) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, contents).unwrap();
+}
+
+/// Reads a JSON file, panicking if it is missing or invalid.
+pub fn read_json(path: &Path) -> Value {
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+/// Stages everything in the work tree and commits it on `HEAD`.
+pub fn commit_all(repo: &Repository, message: &str) -> Oid {
+    let signature = git2::Signature::now("test", "test@example.com").unwrap();
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )
+    .unwrap()
+}
+
+/// Creates a directory junction (a symlink on Unix), returning whether it worked.
+#[cfg(windows)]
+pub fn create_dir_link(link: &Path, target: &Path) -> bool {
+    std::process::Command::new("cmd")
+        .args(["/c", "mklink", "/J"])
+        .arg(link)
+        .arg(target)
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(unix)]
+pub fn create_dir_link(link: &Path, target: &Path) -> bool {
+    std::os::unix::fs::symlink(target, link).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_a_scoop_layout() {
+        let fixture = ScoopFixture::new();
+        fixture
+            .bucket_manifest("main", "git", "2.45.0")
+            .install("git", "2.44.0", "main")
+            .shim(
+                "git",
+                &fixture.app_dir("git").join("current").join("git.exe"),
+            );
+        let cached = fixture.cache_file("git", "2.44.0", "PortableGit.7z");
+
+        assert!(fixture
+            .root()
+            .join("buckets/main/bucket/git.json")
+            .is_file());
+        assert!(fixture.app_dir("git").join("2.44.0/install.json").is_file());
+        assert!(fixture.root().join("shims/git.shim").is_file());
+        assert!(cached.is_file());
+        if fs::symlink_metadata(fixture.app_dir("git").join("current")).is_ok() {
+            assert!(fixture
+                .app_dir("git")
+                .join("current/manifest.json")
+                .is_file());
+        }
+    }
+
+    #[test]
+    fn overwrites_metadata_and_commits_buckets() {
+        let fixture = ScoopFixture::new();
+        fixture.bucket_manifest("main", "git", "2.45.0");
+        let repo = fixture.git_bucket("main");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert!(head
+            .tree()
+            .unwrap()
+            .get_path(Path::new("bucket/git.json"))
+            .is_ok());
+
+        fixture.install("git", "2.44.0", "main");
+        let version_dir = fixture.install_info("git", "2.44.0", json!({ "hold": true }));
+        assert_eq!(read_json(&version_dir.join("install.json"))["hold"], true);
+        fixture.corrupt_install_info("git", "2.44.0");
+        assert!(fs::read_to_string(version_dir.join("install.json"))
+            .map(|content| serde_json::from_str::<Value>(&content).is_err())
+            .unwrap());
+    }
+}
@@ -1,15 +1,193 @@
+use crate::commands::installed::EVENT_INSTALLED_CHANGED;
+use crate::commands::powershell::EVENT_FINISHED;
 use crate::commands::settings;
 use crate::state::AppState;
-use crate::utils::{get_scoop_app_shortcuts_with_path, launch_scoop_app, ScoopAppShortcut};
+use crate::utils::{
+    get_scoop_app_shortcuts_with_path, launch_scoop_app, ScoopAppShortcut, ShortcutScope,
+};
 use crate::i18n;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tauri::{
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Manager,
+    Emitter, Listener, Manager,
 };
 use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 
+/// How Scoop app entries are ordered in the tray menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TraySortOrder {
+    Alphabetical,
+    /// Most recently launched from the tray first.
+    Recent,
+    /// The order in which apps were picked in the tray apps settings.
+    Favorites,
+}
+
+/// User settings that shape the tray menu.
+struct TraySettings {
+    /// Maximum number of app entries; `0` shows all configured apps.
+    max_apps: usize,
+    sort_order: TraySortOrder,
+    show_update_actions: bool,
+    /// Only show window controls and Quit.
+    minimal_mode: bool,
+}
+
+impl TraySettings {
+    fn load(app: &tauri::AppHandle<tauri::Wry>) -> Self {
+        let get = |key: &str| {
+            settings::get_config_value(app.clone(), key.to_string())
+                .ok()
+                .flatten()
+        };
+
+        let sort_order = match get(crate::config_keys::TRAY_SORT_ORDER)
+            .as_ref()
+            .and_then(|v| v.as_str())
+        {
+            Some("recent") => TraySortOrder::Recent,
+            Some("favorites") => TraySortOrder::Favorites,
+            _ => TraySortOrder::Alphabetical,
+        };
+
+        Self {
+            max_apps: get(crate::config_keys::TRAY_MAX_APPS)
+                .and_then(|v| v.as_u64())
+                .unwrap_or(20) as usize,
+            sort_order,
+            show_update_actions: get(crate::config_keys::TRAY_SHOW_UPDATE_ACTIONS)
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            minimal_mode: get(crate::config_keys::TRAY_MINIMAL_MODE)
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Reads the last launch time (unix seconds) of apps started from the tray.
+fn get_recent_launches(app: &tauri::AppHandle<tauri::Wry>) -> HashMap<String, u64> {
+    settings::get_config_value(app.clone(), crate::config_keys::TRAY_RECENT_LAUNCHES.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+fn record_app_launch(app: &tauri::AppHandle<tauri::Wry>, name: &str) {
+    let mut launches = get_recent_launches(app);
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    launches.insert(name.to_string(), now);
+    if let Err(e) = settings::set_config_value(
+        app.clone(),
+        crate::config_keys::TRAY_RECENT_LAUNCHES.to_string(),
+        serde_json::json!(launches),
+    ) {
+        log::warn!("Failed to record tray app launch: {}", e);
+    }
+}
+
+/// Orders tray app entries according to the configured sort order and applies the limit.
+fn arrange_tray_apps(
+    app: &tauri::AppHandle<tauri::Wry>,
+    mut shortcuts: Vec<ScoopAppShortcut>,
+    configured_order: &[String],
+    tray_settings: &TraySettings,
+) -> Vec<ScoopAppShortcut> {
+    shortcuts.sort_by(|a, b| {
+        a.display_name
+            .to_lowercase()
+            .cmp(&b.display_name.to_lowercase())
+    });
+
+    match tray_settings.sort_order {
+        TraySortOrder::Alphabetical => {}
+        TraySortOrder::Recent => {
+            let launches = get_recent_launches(app);
+            // Stable sort keeps never-launched apps alphabetical at the end
+            shortcuts.sort_by_key(|s| {
+                std::cmp::Reverse(launches.get(&s.name).copied().unwrap_or(0))
+            });
+        }
+        TraySortOrder::Favorites => {
+            shortcuts.sort_by_key(|s| {
+                configured_order
+                    .iter()
+                    .position(|name| name == &s.name)
+                    .unwrap_or(usize::MAX)
+            });
+        }
+    }
+
+    if tray_settings.max_apps > 0 {
+        shortcuts.truncate(tray_settings.max_apps);
+    }
+    shortcuts
+}
+
+/// Checks for updates in the background and shows the count in the tray tooltip.
+async fn check_updates_from_tray(app: tauri::AppHandle<tauri::Wry>) {
+    let state = app.state::<AppState>();
+    match crate::commands::updates::check_for_updates(app.clone(), state).await {
+        Ok(updates) => {
+            log::info!("Tray update check found {} updates", updates.len());
+            let tooltip = if updates.is_empty() {
+                "Pailer - Scoop Package Manager".to_string()
+            } else {
+                let language = get_language(&app);
+                let template = i18n::load_tray_locale_strings(&app, &language)
+                    .ok()
+                    .and_then(|s| {
+                        s.get("updatesAvailable")
+                            .and_then(|v| v.as_str())
+                            .map(String::from)
+                    })
+                    .unwrap_or_else(|| "{count} updates available".to_string());
+                format!(
+                    "Pailer - {}",
+                    template.replace("{count}", &updates.len().to_string())
+                )
+            };
+            if let Some(tray) = app.tray_by_id("main") {
+                let _ = tray.set_tooltip(Some(tooltip));
+            }
+            let _ = app.emit("tray-updates-checked", updates.len());
+        }
+        Err(e) => log::warn!("Tray update check failed: {}", e),
+    }
+}
+
+/// Updates all packages, streaming progress to the main window.
+async fn update_all_from_tray(app: tauri::AppHandle<tauri::Wry>) {
+    let Some(webview_window) = app.get_webview_window("main") else {
+        log::warn!("Main window not found, cannot update packages from tray");
+        return;
+    };
+    let window = webview_window.as_ref().window();
+    let _ = window.show();
+    let _ = window.set_focus();
+    let _ = window.emit("auto-operation-start", "Updating packages...");
+
+    let state = app.state::<AppState>();
+    if let Err(e) = crate::commands::update::update_all_packages(window, app.clone(), state).await {
+        log::warn!("Tray update of all packages failed: {}", e);
+    }
+}
+
+fn get_language(app: &tauri::AppHandle<tauri::Wry>) -> String {
+    settings::get_config_value(app.clone(), "settings.language".to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_else(|| "en".to_string())
+}
+
 pub fn setup_system_tray(app: &tauri::AppHandle) -> tauri::Result<()> {
     // Create a shared map to store app shortcuts for menu events
     let shortcuts_map: Arc<Mutex<HashMap<String, ScoopAppShortcut>>> =
@@ -48,6 +226,7 @@ pub fn setup_system_tray(app: &tauri::AppHandle) -> tauri::Result<()> {
             let event_id = event.id().as_ref();
             match event_id {
                 "quit" => {
+                    crate::window_state::save_main(app);
                     app.exit(0);
                 }
                 "show" => {
@@ -61,6 +240,12 @@ pub fn setup_system_tray(app: &tauri::AppHandle) -> tauri::Result<()> {
                         let _ = window.hide();
                     }
                 }
+                "checkUpdates" => {
+                    tauri::async_runtime::spawn(check_updates_from_tray(app.clone()));
+                }
+                "updateAll" => {
+                    tauri::async_runtime::spawn(update_all_from_tray(app.clone()));
+                }
                 "refreshApps" => {
                     // Refresh the tray menu
                     let app_handle = app.clone();
@@ -84,6 +269,8 @@ pub fn setup_system_tray(app: &tauri::AppHandle) -> tauri::Result<()> {
                                     shortcut.display_name,
                                     e
                                 );
+                            } else {
+                                record_app_launch(app, &shortcut.name);
                             }
                         }
                     }
@@ -93,22 +280,43 @@ pub fn setup_system_tray(app: &tauri::AppHandle) -> tauri::Result<()> {
         })
         .build(app)?;
 
+    // Keep the app list in sync when packages are installed, removed or updated
+    for event in [EVENT_INSTALLED_CHANGED, EVENT_FINISHED] {
+        let app_handle = app.clone();
+        app.listen(event, move |_| schedule_tray_refresh(&app_handle));
+    }
+
     Ok(())
 }
 
+/// Delay before rebuilding the tray menu after a package change.
+const TRAY_REFRESH_DEBOUNCE: Duration = Duration::from_millis(1500);
+/// Incremented on every scheduled refresh; only the latest one rebuilds the menu.
+static TRAY_REFRESH_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Schedules a debounced tray menu rebuild, coalescing bursts of change events.
+pub fn schedule_tray_refresh(app: &tauri::AppHandle<tauri::Wry>) {
+    let generation = TRAY_REFRESH_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let app_handle = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(TRAY_REFRESH_DEBOUNCE).await;
+        if TRAY_REFRESH_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        if let Err(e) = refresh_tray_menu(&app_handle).await {
+            log::error!("Failed to refresh tray menu: {}", e);
+        }
+    });
+}
+
 fn build_tray_menu(
     app: &tauri::AppHandle<tauri::Wry>,
     shortcuts_map: Arc<Mutex<HashMap<String, ScoopAppShortcut>>>,
 ) -> tauri::Result<tauri::menu::Menu<tauri::Wry>> {
     // Get the current language setting
-    let language = settings::get_config_value(
-        app.clone(),
-        "settings.language".to_string(),
-    )
-    .ok()
-    .flatten()
-    .and_then(|v| v.as_str().map(|s| s.to_string()))
-    .unwrap_or_else(|| "en".to_string());
+    let language = get_language(app);
+    let tray_settings = TraySettings::load(app);
 
     // Get localized menu strings
     let menu_strings = i18n::load_tray_locale_strings(app, &language)?;
@@ -129,6 +337,15 @@ fn build_tray_menu(
     let quit_text = menu_strings.get("quit")
         .and_then(|v| v.as_str())
         .unwrap_or("Quit");
+    let global_app_text = menu_strings.get("globalApp")
+        .and_then(|v| v.as_str())
+        .unwrap_or("(global)");
+    let check_updates_text = menu_strings.get("checkUpdates")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Check for Updates");
+    let update_all_text = menu_strings.get("updateAll")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Update All Packages");
 
     // Basic menu items
     let show = tauri::menu::MenuItemBuilder::with_id("show", show_text).build(app)?;
@@ -136,6 +353,18 @@ fn build_tray_menu(
     let refresh_apps =
         tauri::menu::MenuItemBuilder::with_id("refreshApps", refresh_apps_text).build(app)?;
 
+    // Minimal mode only keeps the window controls and Quit
+    if tray_settings.minimal_mode {
+        let separator = tauri::menu::PredefinedMenuItem::separator(app)?;
+        let quit = tauri::menu::MenuItemBuilder::with_id("quit", quit_text).build(app)?;
+        return tauri::menu::MenuBuilder::new(app)
+            .item(&show)
+            .item(&hide)
+            .item(&separator)
+            .item(&quit)
+            .build();
+    }
+
     let mut menu_items: Vec<Box<dyn tauri::menu::IsMenuItem<tauri::Wry>>> = Vec::new();
     menu_items.push(Box::new(show));
     menu_items.push(Box::new(hide));
@@ -170,21 +399,26 @@ fn build_tray_menu(
                 .and_then(|v| v.as_array().cloned())
                 .unwrap_or_default();
 
-                // Convert configured apps to a HashSet for fast lookup
-                let configured_app_names: std::collections::HashSet<String> = configured_apps
+                // Keep the configured order for the favorites sort
+                let configured_order: Vec<String> = configured_apps
                     .iter()
                     .filter_map(|v| v.as_str().map(|s| s.to_string()))
                     .collect();
 
+                // Convert configured apps to a HashSet for fast lookup
+                let configured_app_names: std::collections::HashSet<String> =
+                    configured_order.iter().cloned().collect();
+
                 // Filter shortcuts based on configuration
                 // If no apps configured, show none (user can add them in settings)
                 let filtered_shortcuts: Vec<_> = if configured_app_names.is_empty() {
                     Vec::new()  // Show no apps by default
                 } else {
-                    shortcuts
+                    let configured: Vec<_> = shortcuts
                         .into_iter()
                         .filter(|shortcut| configured_app_names.contains(&shortcut.name))
-                        .collect()
+                        .collect();
+                    arrange_tray_apps(app, configured, &configured_order, &tray_settings)
                 };
 
                 if !filtered_shortcuts.is_empty() {
@@ -204,8 +438,15 @@ fn build_tray_menu(
                         let menu_id = format!("app_{}", shortcut.name);
                         new_shortcuts_map.insert(menu_id.clone(), shortcut.clone());
 
+                        // Tag machine-wide shortcuts so global installs are recognizable
+                        let label = match shortcut.scope {
+                            ShortcutScope::User => shortcut.display_name.clone(),
+                            ShortcutScope::Machine => {
+                                format!("{} {}", shortcut.display_name, global_app_text)
+                            }
+                        };
                         let menu_item =
-                            tauri::menu::MenuItemBuilder::with_id(&menu_id, &shortcut.display_name)
+                            tauri::menu::MenuItemBuilder::with_id(&menu_id, &label)
                                 .build(app)?;
                         menu_items.push(Box::new(menu_item));
                     }
@@ -228,6 +469,14 @@ fn build_tray_menu(
     // Add separator and refresh option
     let separator = tauri::menu::PredefinedMenuItem::separator(app)?;
     menu_items.push(Box::new(separator));
+    if tray_settings.show_update_actions {
+        let check_updates =
+            tauri::menu::MenuItemBuilder::with_id("checkUpdates", check_updates_text).build(app)?;
+        let update_all =
+            tauri::menu::MenuItemBuilder::with_id("updateAll", update_all_text).build(app)?;
+        menu_items.push(Box::new(check_updates));
+        menu_items.push(Box::new(update_all));
+    }
     menu_items.push(Box::new(refresh_apps));
 
     // Add quit option
@@ -370,6 +619,7 @@ pub fn show_system_notification_blocking(app: &tauri::AppHandle) {
         );
 
         log::info!("User chose to disable tray functionality. Exiting application.");
+        crate::window_state::save_main(app);
         app.exit(0);
     }
 }
@@ -407,7 +657,8 @@ pub fn get_scoop_app_shortcuts() -> Result<Vec<serde_json::Value>, String> {
                 .map(|shortcut| {
                     serde_json::json!({
                         "name": shortcut.name,
-                        "display_name": shortcut.display_name
+                        "display_name": shortcut.display_name,
+                        "scope": shortcut.scope.as_str()
                     })
                 })
                 .collect();
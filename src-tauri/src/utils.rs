@@ -1,4 +1,5 @@
 use crate::commands::settings;
+use crate::engine::root::{evaluate_candidate, select_best_root};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashSet;
@@ -8,6 +9,24 @@ use std::path::PathBuf;
 use tauri::{AppHandle, Runtime};
 use url::Url;
 
+/// Where a Start Menu shortcut was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutScope {
+    /// `%AppData%`, created by per-user installs.
+    User,
+    /// `%ProgramData%`, created by global (`--global`) installs.
+    Machine,
+}
+
+impl ShortcutScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShortcutScope::User => "user",
+            ShortcutScope::Machine => "machine",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ScoopAppShortcut {
     pub name: String,
@@ -15,6 +34,9 @@ pub struct ScoopAppShortcut {
     pub target_path: String,
     pub working_directory: String,
     pub icon_path: Option<String>,
+    pub scope: ShortcutScope,
+    /// The `.lnk` file itself.
+    pub path: PathBuf,
 }
 
 /// Checks if the application is installed via Scoop
@@ -29,15 +51,6 @@ pub fn is_scoop_installation() -> bool {
     }
 }
 
-#[derive(Debug, Clone)]
-struct ScoopRootCandidateInfo {
-    path: PathBuf,
-    score: u32,
-    installed_count: usize,
-    has_apps_dir: bool,
-    has_buckets_dir: bool,
-}
-
 fn push_candidate(seen: &mut HashSet<String>, candidates: &mut Vec<PathBuf>, path: PathBuf) {
     if path.as_os_str().is_empty() {
         return;
@@ -109,6 +122,10 @@ where
         push_candidate(&mut seen, &mut candidates, path);
     }
 
+    for path in crate::portable::scoop_root_candidates() {
+        push_candidate(&mut seen, &mut candidates, path);
+    }
+
     collect_common_candidates(&mut seen, &mut candidates);
 
     log::info!("Built candidate list with {} paths", candidates.len());
@@ -119,124 +136,6 @@ where
     candidates
 }
 
-fn evaluate_scoop_candidate(path: PathBuf) -> Option<ScoopRootCandidateInfo> {
-    log::info!("Evaluating Scoop candidate: {}", path.display());
-    
-    if !path.is_dir() {
-        log::info!("Candidate path is not a directory");
-        return None;
-    }
-
-    let apps_dir = path.join("apps");
-    let buckets_dir = path.join("buckets");
-    let has_apps_dir = apps_dir.is_dir();
-    let has_buckets_dir = buckets_dir.is_dir();
-
-    log::info!("Candidate evaluation - apps_dir: {} ({}), buckets_dir: {} ({})", 
-               apps_dir.display(), has_apps_dir, buckets_dir.display(), has_buckets_dir);
-
-    if !has_apps_dir && !has_buckets_dir {
-        log::info!("Candidate rejected - missing both apps and buckets directories");
-        return None;
-    }
-
-    let installed_count = if has_apps_dir {
-        match fs::read_dir(&apps_dir) {
-            Ok(entries) => {
-                let count = entries
-                    .filter_map(Result::ok)
-                    .filter(|entry| entry.path().is_dir())
-                    .take(200)
-                    .count();
-                log::info!("Found {} installed apps in apps directory", count);
-                count
-            },
-            Err(e) => {
-                log::warn!("Failed to read apps directory: {}", e);
-                0
-            }
-        }
-    } else {
-        log::info!("No apps directory found");
-        0
-    };
-
-    let mut score = 0;
-    if has_buckets_dir {
-        score += 10;
-        log::info!("+10 points for having buckets directory");
-    }
-    if has_apps_dir {
-        score += 30;
-        log::info!("+30 points for having apps directory");
-    }
-    score += installed_count.min(50) as u32;
-    log::info!("+{} points for installed apps (capped at 50)", installed_count.min(50));
-
-    log::info!("Total score for candidate {}: {}", path.display(), score);
-
-    Some(ScoopRootCandidateInfo {
-        path,
-        score,
-        installed_count,
-        has_apps_dir,
-        has_buckets_dir,
-    })
-}
-
-fn select_best_scoop_root(
-    candidates: Vec<PathBuf>,
-    preferred: Option<&PathBuf>,
-) -> Option<ScoopRootCandidateInfo> {
-    log::info!("Selecting best Scoop root from {} candidates", candidates.len());
-    let mut best: Option<ScoopRootCandidateInfo> = None;
-
-    for candidate in candidates {
-        let candidate_display = candidate.display().to_string();
-        let evaluated_candidate = evaluate_scoop_candidate(candidate);
-        
-        if evaluated_candidate.is_some() {
-            let mut info = evaluated_candidate.unwrap();
-            
-            if preferred.is_some() && preferred.unwrap().eq(&info.path) {
-                info.score += 5;
-                log::info!("+5 points for being the preferred path");
-            }
-
-            log::debug!(
-                "Scored potential Scoop root {} => score {} (apps_dir={}, buckets_dir={}, installed={})",
-                info.path.display(),
-                info.score,
-                info.has_apps_dir,
-                info.has_buckets_dir,
-                info.installed_count
-            );
-
-            let replace = if best.is_some() {
-                let current = best.as_ref().unwrap();
-                let should_replace = info.score > current.score
-                    || (info.score == current.score
-                        && info.installed_count > current.installed_count);
-                log::debug!("Comparing with current best - current score: {}, candidate score: {}, replace: {}", 
-                           current.score, info.score, should_replace);
-                should_replace
-            } else { 
-                true // No current best, always replace
-            };
-
-            if replace {
-                log::info!("Setting new best candidate: {} with score {}", info.path.display(), info.score);
-                best = Some(info);
-            }
-        } else {
-            log::debug!("Skipping invalid candidate: {}", candidate_display);
-        }
-    }
-
-    log::info!("Best Scoop root selection completed. Best candidate: {:?}", best.as_ref().map(|b| b.path.display().to_string()));
-    best
-}
-
 /// Resolve the root directory of Scoop on the host machine.
 ///
 /// The resolver inspects the persisted setting first, then scores a set of
@@ -257,7 +156,7 @@ pub fn resolve_scoop_root<R: Runtime>(app: AppHandle<R>) -> Result<PathBuf, Stri
 
     if let Some(path) = stored_path.as_ref() {
         log::info!("Found stored Scoop path: {}", path.display());
-        if evaluate_scoop_candidate(path.clone()).is_none() {
+        if evaluate_candidate(path).is_none() {
             log::warn!(
                 "Stored scoop path is invalid or inaccessible: {}",
                 path.display()
@@ -270,7 +169,12 @@ pub fn resolve_scoop_root<R: Runtime>(app: AppHandle<R>) -> Result<PathBuf, Stri
     let candidates = build_candidate_list(stored_path.clone().into_iter());
     log::info!("Built {} candidates for Scoop root", candidates.len());
 
-    if let Some(best) = select_best_scoop_root(candidates, stored_path.as_ref()) {
+    // A portable install prefers the Scoop root it was shipped with
+    let preferred = stored_path
+        .clone()
+        .or_else(|| crate::portable::scoop_root_candidates().into_iter().find(|p| p.exists()));
+
+    if let Some(best) = select_best_root(candidates, preferred.as_ref()) {
         let best_path = best.path.clone();
         let stored_matches = stored_path
             .as_ref()
@@ -309,146 +213,129 @@ pub fn resolve_scoop_root<R: Runtime>(app: AppHandle<R>) -> Result<PathBuf, Stri
 }
 
 // -----------------------------------------------------------------------------
-// Manifest helpers
+// Scoop directory helpers
 // -----------------------------------------------------------------------------
 
-/// Locate a manifest file for `package_name` within the Scoop buckets.
-///
-/// If `package_source` is supplied it will be treated as an exact bucket name
-/// and only that bucket will be inspected. Otherwise all buckets are searched
-/// in parallel and the first match is returned.
+/// The special directories of a Scoop installation.
 ///
-/// The returned tuple contains the fully qualified path to the manifest file
-/// and the bucket name the manifest originated from.
-///
-/// # Errors
-/// Propagates any I/O failure and returns a domain-specific error when the
-/// manifest cannot be located.
-pub fn locate_package_manifest(
-    scoop_dir: &std::path::Path,
-    package_name: &str,
-    package_source: Option<String>,
-) -> Result<(PathBuf, String), String> {
-    locate_package_manifest_impl(scoop_dir, package_name, package_source)
+/// Scoop lets users relocate the download cache and the global install directory
+/// through `config.json` (`cache_path`, `global_path`) or the `SCOOP_CACHE` and
+/// `SCOOP_GLOBAL` environment variables, so these must not be derived from the root.
+#[derive(Debug, Clone)]
+pub struct ScoopDirs {
+    pub root: PathBuf,
+    pub apps: PathBuf,
+    pub buckets: PathBuf,
+    pub cache: PathBuf,
+    pub persist: PathBuf,
+    pub shims: PathBuf,
+    pub global: PathBuf,
+    pub global_apps: PathBuf,
+    pub global_shims: PathBuf,
 }
 
-// Internal implementation that contains the previous logic. This avoids code
-// duplication while giving us the opportunity to phase out the old API.
-fn locate_package_manifest_impl(
-    scoop_dir: &std::path::Path,
-    package_name: &str,
-    package_source: Option<String>,
-) -> Result<(PathBuf, String), String> {
-    let buckets_dir = scoop_dir.join("buckets");
-
-    let search_buckets = |bucket_path: PathBuf| -> Result<(PathBuf, String), String> {
-        if bucket_path.is_dir() {
-            let bucket_name = bucket_path
-                .file_name()
-                .unwrap()
-                .to_string_lossy()
-                .to_string();
+impl ScoopDirs {
+    /// Resolves all special directories for the Scoop root at `root`.
+    pub fn resolve(root: &std::path::Path) -> Self {
+        let config = settings::read_scoop_config().unwrap_or_default();
+        let configured = |env_var: &str, key: &str| -> Option<PathBuf> {
+            env::var(env_var)
+                .ok()
+                .or_else(|| config.get(key).and_then(|v| v.as_str()).map(String::from))
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .map(PathBuf::from)
+        };
+
+        let cache = configured("SCOOP_CACHE", "cache_path").unwrap_or_else(|| root.join("cache"));
+        let global = configured("SCOOP_GLOBAL", "global_path").unwrap_or_else(|| {
+            env::var("ProgramData")
+                .map(|dir| PathBuf::from(dir).join("scoop"))
+                .unwrap_or_else(|_| root.join("global"))
+        });
+
+        Self {
+            root: root.to_path_buf(),
+            apps: root.join("apps"),
+            buckets: root.join("buckets"),
+            cache,
+            persist: root.join("persist"),
+            shims: root.join("shims"),
+            global_apps: global.join("apps"),
+            global_shims: global.join("shims"),
+            global,
+        }
+    }
+}
 
-            let manifest_filename = format!("{}.json", package_name);
+// -----------------------------------------------------------------------------
+// Manifest helpers
+// -----------------------------------------------------------------------------
 
-            let manifest_path = bucket_path.join(&manifest_filename);
-            if manifest_path.exists() {
-                return Ok((manifest_path, bucket_name));
-            }
+pub use crate::engine::manifest::{find_manifest_candidates, locate_package_manifest};
 
-            let nested_manifest_path = bucket_path.join("bucket").join(&manifest_filename);
-            if nested_manifest_path.exists() {
-                return Ok((nested_manifest_path, bucket_name));
-            }
-        }
-        Err(format!("Package '{}' not found.", package_name))
-    };
+// -----------------------------------------------------------------------------
+// Scoop Apps Shortcuts helpers
+// -----------------------------------------------------------------------------
 
-    // 1. Try to find in specific bucket if provided
-    if let Some(source) = &package_source {
-        if !source.is_empty() && source != "None" && buckets_dir.is_dir() {
-            let specific_bucket_path = buckets_dir.join(source);
-            if let Ok(found) = search_buckets(specific_bucket_path) {
-                return Ok(found);
-            }
-        }
-    }
+/// Scans the Windows Start Menu for Scoop Apps shortcuts
+///
+/// Looks in both the per-user (`%AppData%`) and machine-wide (`%ProgramData%`)
+/// `Start Menu\Programs\Scoop Apps` folders. When the same app has a shortcut in both,
+/// the per-user one wins.
+pub fn get_scoop_app_shortcuts_with_path(
+    scoop_path: &std::path::Path,
+) -> Result<Vec<ScoopAppShortcut>, String> {
+    let locations: Vec<(PathBuf, ShortcutScope)> = [
+        ("APPDATA", ShortcutScope::User),
+        ("ProgramData", ShortcutScope::Machine),
+    ]
+    .into_iter()
+    .filter_map(|(var, scope)| {
+        env::var(var).ok().map(|root| {
+            let dir = PathBuf::from(root)
+                .join("Microsoft")
+                .join("Windows")
+                .join("Start Menu")
+                .join("Programs")
+                .join("Scoop Apps");
+            (dir, scope)
+        })
+    })
+    .collect();
 
-    // 2. Search all buckets
-    if buckets_dir.is_dir() {
-        if let Ok(entries) = std::fs::read_dir(&buckets_dir) {
-            for entry in entries.flatten() {
-                if let Ok(found) = search_buckets(entry.path()) {
-                    return Ok(found);
-                }
-            }
-        }
+    if locations.is_empty() {
+        return Err("Could not find APPDATA or ProgramData environment variable".to_string());
     }
 
-    // 3. Check installed apps if not found in buckets
-    let installed_manifest_path = scoop_dir
-        .join("apps")
-        .join(package_name)
-        .join("current")
-        .join("manifest.json");
-
-    if installed_manifest_path.exists() {
-        // Try to read install.json to get the original bucket name if possible
-        let install_json_path = scoop_dir
-            .join("apps")
-            .join(package_name)
-            .join("current")
-            .join("install.json");
-
-        let mut bucket_name = "Installed (Bucket missing)".to_string();
-
-        if install_json_path.exists() {
-            if let Ok(content) = std::fs::read_to_string(install_json_path) {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-                    if let Some(bucket) = json.get("bucket").and_then(|b| b.as_str()) {
-                        bucket_name = format!("{} (missing)", bucket);
-                    }
-                }
+    let mut shortcuts: Vec<ScoopAppShortcut> = Vec::new();
+    let mut seen_names = HashSet::new();
+    let mut seen_targets = HashSet::new();
+
+    for (scoop_apps_path, scope) in locations {
+        for shortcut in scan_shortcut_dir(&scoop_apps_path, scope, scoop_path)? {
+            let target_key = shortcut.target_path.to_lowercase();
+            if !seen_names.insert(shortcut.name.to_lowercase())
+                || (!target_key.is_empty() && !seen_targets.insert(target_key))
+            {
+                continue;
             }
+            shortcuts.push(shortcut);
         }
-
-        return Ok((installed_manifest_path, bucket_name));
     }
 
-    if let Some(source) = package_source {
-        if !source.is_empty() && source != "None" {
-            return Err(format!(
-                "Package '{}' not found in bucket '{}'.",
-                package_name, source
-            ));
-        }
+    if !shortcuts.is_empty() {
+        log::info!("Scoop Apps shortcuts detected: {}", shortcuts.len());
     }
-
-    Err(format!(
-        "Package '{}' not found in any bucket.",
-        package_name
-    ))
+    Ok(shortcuts)
 }
 
-// -----------------------------------------------------------------------------
-// Scoop Apps Shortcuts helpers
-// -----------------------------------------------------------------------------
-
-/// Scans the Windows Start Menu for Scoop Apps shortcuts
-///
-/// Returns a list of shortcuts found in %AppData%\Microsoft\Windows\Start Menu\Programs\Scoop Apps
-pub fn get_scoop_app_shortcuts_with_path(
+/// Reads the `.lnk` files of a single Scoop Apps Start Menu folder.
+fn scan_shortcut_dir(
+    scoop_apps_path: &std::path::Path,
+    scope: ShortcutScope,
     scoop_path: &std::path::Path,
 ) -> Result<Vec<ScoopAppShortcut>, String> {
-    let app_data =
-        env::var("APPDATA").map_err(|_| "Could not find APPDATA environment variable")?;
-    let scoop_apps_path = PathBuf::from(app_data)
-        .join("Microsoft")
-        .join("Windows")
-        .join("Start Menu")
-        .join("Programs")
-        .join("Scoop Apps");
-
     if !scoop_apps_path.exists() {
         log::debug!(
             "Scoop Apps directory not found: {}",
@@ -459,7 +346,7 @@ pub fn get_scoop_app_shortcuts_with_path(
 
     let mut shortcuts = Vec::new();
 
-    for entry in fs::read_dir(&scoop_apps_path)
+    for entry in fs::read_dir(scoop_apps_path)
         .map_err(|e| format!("Failed to read Scoop Apps directory: {}", e))?
     {
         let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
@@ -474,6 +361,8 @@ pub fn get_scoop_app_shortcuts_with_path(
                         target_path: shortcut_info.target_path,
                         working_directory: shortcut_info.working_directory,
                         icon_path: shortcut_info.icon_path,
+                        scope,
+                        path: path.clone(),
                     });
                 } else {
                     log::trace!("Failed to parse shortcut: {}", path.display());
@@ -482,9 +371,6 @@ pub fn get_scoop_app_shortcuts_with_path(
         }
     }
 
-    if !shortcuts.is_empty() {
-        log::info!("Scoop Apps shortcuts detected: {}", shortcuts.len());
-    }
     Ok(shortcuts)
 }
 
@@ -566,7 +452,7 @@ pub fn get_scoop_root_fallback() -> PathBuf {
     // No cached value, perform detection
     let candidates = build_candidate_list(Vec::<PathBuf>::new());
 
-    if let Some(best) = select_best_scoop_root(candidates, None) {
+    if let Some(best) = select_best_root(candidates, None) {
         log::info!(
             "Using Scoop root fallback: {} (apps_dir={}, buckets_dir={}, installs={})",
             best.path.display(),
@@ -596,6 +482,12 @@ pub fn get_scoop_root_fallback() -> PathBuf {
     default_path
 }
 
+/// Replaces the cached Scoop root, e.g. after the user picked a different root.
+pub fn set_scoop_root_cache(path: PathBuf) {
+    let cache = SCOOP_ROOT_CACHE.get_or_init(|| Mutex::new(None));
+    *cache.lock().unwrap() = Some(path);
+}
+
 /// Clear the Scoop root cache (useful when Scoop configuration changes)
 pub fn clear_scoop_root_cache() {
     if let Some(cache) = SCOOP_ROOT_CACHE.get() {
@@ -752,6 +644,20 @@ pub fn launch_scoop_app(target_path: &str, working_directory: &str) -> Result<()
     }
 }
 
+/// Copies a directory tree, creating `dst` as needed.
+pub fn copy_dir_recursive(src: &std::path::Path, dst: &std::path::Path) -> std::io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)?.flatten() {
+        let target = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_recursive(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
 /// Counts the number of manifest (.json) files in a bucket directory.
 /// Handles both flat structure and bucket/ subdirectory structure.
 pub fn count_manifests(bucket_path: &std::path::Path) -> u32 {
@@ -788,6 +694,20 @@ pub fn count_manifests(bucket_path: &std::path::Path) -> u32 {
     count
 }
 
+/// Checks that `name`, a package, bucket or version name from the caller, is a single
+/// plain path component, so joining it onto a Scoop directory stays inside that
+/// directory. `kind` names it in the error.
+pub fn validate_dir_name(kind: &str, name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || name.starts_with('.')
+        || name.contains(['/', '\\', ':'])
+        || name.chars().any(char::is_control)
+    {
+        return Err(format!("Invalid {} name '{}'", kind, name));
+    }
+    Ok(())
+}
+
 // -----------------------------------------------------------------------------
 // URL and Bucket Helpers
 // -----------------------------------------------------------------------------
@@ -854,6 +774,28 @@ pub fn validate_and_normalize_url(url: &str) -> Result<String, String> {
     }
 }
 
+/// Reduces a repository URL to a comparable `host/owner/repo` key.
+///
+/// HTTPS, SSH and `.git`-suffixed forms of the same repository produce the same key.
+pub fn canonical_repo_key(url: &str) -> String {
+    let trimmed = url.trim().to_lowercase();
+    let without_scheme = trimmed
+        .strip_prefix("https://")
+        .or_else(|| trimmed.strip_prefix("http://"))
+        .or_else(|| trimmed.strip_prefix("ssh://"))
+        .unwrap_or(&trimmed);
+    let without_user = without_scheme
+        .strip_prefix("git@")
+        .map(|rest| rest.replacen(':', "/", 1))
+        .unwrap_or_else(|| without_scheme.to_string());
+
+    without_user
+        .trim_start_matches("www.")
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .to_string()
+}
+
 /// Extract bucket name from URL or use provided name
 pub fn extract_bucket_name_from_url(
     url: &str,
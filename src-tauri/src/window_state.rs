@@ -0,0 +1,272 @@
+//! Remembering the main window's size, position and maximized state.
+//!
+//! The geometry is saved in the store per monitor layout, so a laptop that is docked
+//! and undocked gets back the placement it had on each setup. A saved position is only
+//! applied while enough of the title bar lands on a connected display's work area;
+//! otherwise the saved size is kept and the window centered.
+use crate::commands::settings;
+use crate::config_keys::WINDOW_STATES;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, Window};
+
+/// Layouts remembered at most; the least recently saved ones are dropped.
+const MAX_LAYOUTS: usize = 8;
+/// Height of the strip along the window top that has to be on screen to grab it.
+const TITLE_BAR_HEIGHT: i64 = 32;
+/// Width of that strip that has to be visible on a single display.
+const MIN_VISIBLE_WIDTH: i64 = 100;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowGeometry {
+    /// Outer position, in physical pixels.
+    pub x: i32,
+    pub y: i32,
+    /// Inner size, in physical pixels.
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+    /// Unix timestamp (seconds) of the save, used to prune old layouts.
+    #[serde(default)]
+    pub saved_at: u64,
+}
+
+/// A display rectangle in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+fn display_rect(monitor: &Monitor) -> DisplayRect {
+    DisplayRect {
+        x: monitor.position().x,
+        y: monitor.position().y,
+        width: monitor.size().width,
+        height: monitor.size().height,
+    }
+}
+
+fn work_area_rect(monitor: &Monitor) -> DisplayRect {
+    let area = monitor.work_area();
+    DisplayRect {
+        x: area.position.x,
+        y: area.position.y,
+        width: area.size.width,
+        height: area.size.height,
+    }
+}
+
+/// Identifies a monitor layout by the position, size and scale of every display.
+pub fn layout_key(displays: &[(DisplayRect, f64)]) -> String {
+    let mut parts: Vec<String> = displays
+        .iter()
+        .map(|(rect, scale)| {
+            format!(
+                "{},{},{}x{}@{}",
+                rect.x,
+                rect.y,
+                rect.width,
+                rect.height,
+                (scale * 100.0).round()
+            )
+        })
+        .collect();
+    parts.sort();
+    parts.join("|")
+}
+
+/// Whether the title bar of a window at `geometry` can be reached on one of `work_areas`.
+pub fn is_reachable(geometry: &WindowGeometry, work_areas: &[DisplayRect]) -> bool {
+    let left = geometry.x as i64;
+    let right = left + geometry.width as i64;
+    let top = geometry.y as i64;
+    let bottom = top + TITLE_BAR_HEIGHT;
+    work_areas.iter().any(|area| {
+        let visible_width = right.min(area.x as i64 + area.width as i64) - left.max(area.x as i64);
+        let visible_height =
+            bottom.min(area.y as i64 + area.height as i64) - top.max(area.y as i64);
+        visible_width >= MIN_VISIBLE_WIDTH && visible_height > 0
+    })
+}
+
+fn current_layout(window: &Window) -> Option<(String, Vec<Monitor>)> {
+    let monitors = window.available_monitors().ok()?;
+    if monitors.is_empty() {
+        return None;
+    }
+    let displays: Vec<(DisplayRect, f64)> = monitors
+        .iter()
+        .map(|m| (display_rect(m), m.scale_factor()))
+        .collect();
+    Some((layout_key(&displays), monitors))
+}
+
+fn read_states(app: &AppHandle) -> Map<String, Value> {
+    settings::get_config_value(app.clone(), WINDOW_STATES.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_object().cloned())
+        .unwrap_or_default()
+}
+
+/// Applies the geometry saved for the current monitor layout; run before the window shows.
+pub fn restore(window: &Window) {
+    let Some((layout, monitors)) = current_layout(window) else {
+        return;
+    };
+    let Some(geometry) = read_states(window.app_handle())
+        .remove(&layout)
+        .and_then(|v| serde_json::from_value::<WindowGeometry>(v).ok())
+    else {
+        return;
+    };
+
+    let work_areas: Vec<DisplayRect> = monitors.iter().map(work_area_rect).collect();
+    let largest = work_areas
+        .iter()
+        .map(|a| (a.width, a.height))
+        .max()
+        .unwrap_or((geometry.width, geometry.height));
+    let size = PhysicalSize::new(
+        geometry.width.min(largest.0),
+        geometry.height.min(largest.1),
+    );
+    if let Err(e) = window.set_size(size) {
+        log::warn!("Failed to restore window size: {}", e);
+    }
+
+    if is_reachable(&geometry, &work_areas) {
+        if let Err(e) = window.set_position(PhysicalPosition::new(geometry.x, geometry.y)) {
+            log::warn!("Failed to restore window position: {}", e);
+        }
+    } else {
+        log::info!("Saved window position is off screen, centering the window");
+        let _ = window.center();
+    }
+    if geometry.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Saves the window geometry for the current monitor layout.
+///
+/// A minimized window is skipped, and a maximized one keeps the size and position it
+/// had before it was maximized, since those are what it returns to.
+pub fn save(window: &Window) {
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let Some((layout, _)) = current_layout(window) else {
+        return;
+    };
+    let app = window.app_handle();
+    let mut states = read_states(app);
+    let previous = states
+        .get(&layout)
+        .and_then(|v| serde_json::from_value::<WindowGeometry>(v.clone()).ok());
+
+    let maximized = window.is_maximized().unwrap_or(false);
+    let geometry = match (maximized, previous) {
+        (true, Some(previous)) => WindowGeometry {
+            maximized: true,
+            ..previous
+        },
+        _ => {
+            let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+                return;
+            };
+            WindowGeometry {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                maximized,
+                saved_at: 0,
+            }
+        }
+    };
+    let geometry = WindowGeometry {
+        saved_at: chrono::Utc::now().timestamp().max(0) as u64,
+        ..geometry
+    };
+
+    states.insert(layout, serde_json::json!(geometry));
+    while states.len() > MAX_LAYOUTS {
+        let oldest = states
+            .iter()
+            .min_by_key(|(_, v)| v.get("savedAt").and_then(Value::as_u64).unwrap_or(0))
+            .map(|(k, _)| k.clone());
+        match oldest {
+            Some(key) => states.remove(&key),
+            None => break,
+        };
+    }
+    if let Err(e) = settings::set_config_value(
+        app.clone(),
+        WINDOW_STATES.to_string(),
+        Value::Object(states),
+    ) {
+        log::warn!("Failed to save window state: {}", e);
+    }
+}
+
+/// Saves the main window's geometry; used before the app exits from the tray.
+pub fn save_main(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        save(&window.as_ref().window());
+    }
+}
+
+/// Forgets every saved window geometry, so the next launch uses the default placement.
+#[tauri::command]
+pub fn reset_window_state(app: AppHandle) -> Result<(), String> {
+    log::info!("Resetting saved window state");
+    settings::set_config_value(app, WINDOW_STATES.to_string(), Value::Object(Map::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_title_bar_against_work_areas() {
+        let laptop = DisplayRect {
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1040,
+        };
+        let external = DisplayRect {
+            x: 1920,
+            y: 0,
+            width: 2560,
+            height: 1400,
+        };
+        let window = |x: i32, y: i32| WindowGeometry {
+            x,
+            y,
+            width: 800,
+            height: 600,
+            maximized: false,
+            saved_at: 0,
+        };
+
+        assert!(is_reachable(&window(100, 100), &[laptop]));
+        // Placed on the external display, which is no longer connected
+        assert!(!is_reachable(&window(2400, 200), &[laptop]));
+        assert!(is_reachable(&window(2400, 200), &[laptop, external]));
+        // Only 50 pixels of the title bar stick out on the right edge
+        assert!(!is_reachable(&window(1870, 10), &[laptop]));
+        // Title bar above the top of the screen
+        assert!(!is_reachable(&window(100, -100), &[laptop]));
+
+        assert_eq!(
+            layout_key(&[(external, 1.0), (laptop, 1.5)]),
+            layout_key(&[(laptop, 1.5), (external, 1.0)])
+        );
+    }
+}
@@ -13,6 +13,9 @@ import DoctorPage from "./pages/DoctorPage.tsx";
 import DebugModal from "./components/DebugModal.tsx";
 import MinimizedIndicatorManager from "./components/MinimizedIndicatorManager.tsx";
 import MultiInstanceWarning from "./components/MultiInstanceWarning.tsx";
+import CrashReportNotice from "./components/CrashReportNotice.tsx";
+import ExternalChangesNotice from "./components/ExternalChangesNotice.tsx";
+import quietMode from "./stores/quietMode";
 import OperationModal from "./components/OperationModal.tsx";
 import { listen } from "@tauri-apps/api/event";
 import { info, error as logError } from "@tauri-apps/plugin-log";
@@ -100,8 +103,8 @@ function App() {
             try {
                 const unlisten = await listen<string>("auto-operation-start", (event) => {
                     info(`Auto-operation started: ${event.payload}`);
-                    // 检查是否启用了静默自动更新
-                    if (!settings.buckets.silentUpdateEnabled) {
+                    // 检查是否启用了静默自动更新；免打扰期间也不弹出
+                    if (!settings.buckets.silentUpdateEnabled && !quietMode.active()) {
                         setAutoUpdateTitle(event.payload);
                     }
                 });
@@ -387,7 +390,11 @@ function App() {
                 </div>
                 <DebugModal />
                 <MinimizedIndicatorManager />
-                <MultiInstanceWarning />
+                <Show when={!quietMode.active()}>
+                    <MultiInstanceWarning />
+                    <CrashReportNotice />
+                </Show>
+                <ExternalChangesNotice />
             </Show>
             {/* Render all active operation modals */}
             <For each={Object.values(operations())}>
@@ -0,0 +1,93 @@
+import { createSignal, Show, onMount } from "solid-js";
+import { invoke } from "@tauri-apps/api/core";
+import { openUrl } from "@tauri-apps/plugin-opener";
+import { TriangleAlert, X } from "lucide-solid";
+import { t } from "../i18n";
+
+const ISSUES_URL = "https://github.com/Kwensiu/Pailer/issues/new";
+
+export interface CrashReport {
+  id: string;
+  timestamp: string;
+  app_version: string;
+  os: string;
+  arch: string;
+  kind: string;
+  message: string;
+  location: string | null;
+  thread: string | null;
+  backtrace: string | null;
+  minidump_path: string | null;
+  acknowledged: boolean;
+}
+
+// Offers the report of a crash in the previous session until it is dismissed
+const CrashReportNotice = () => {
+  const [report, setReport] = createSignal<CrashReport | null>(null);
+
+  onMount(async () => {
+    try {
+      setReport(await invoke<CrashReport | null>("get_pending_crash_report"));
+    } catch (e) {
+      console.error("Failed to load the pending crash report:", e);
+    }
+  });
+
+  const dismiss = async () => {
+    const current = report();
+    setReport(null);
+    if (current) {
+      await invoke("acknowledge_crash_report", { id: current.id }).catch(console.error);
+    }
+  };
+
+  const copyReport = async () => {
+    const current = report();
+    if (current) {
+      await navigator.clipboard.writeText(JSON.stringify(current, null, 2)).catch(console.error);
+    }
+  };
+
+  return (
+    <Show when={report()}>
+      {(current) => (
+        <div class="fixed bottom-4 left-4 z-50 max-w-sm">
+          <div class="alert alert-error shadow-lg">
+            <div class="flex items-start gap-3">
+              <TriangleAlert class="w-5 h-5 shrink-0 mt-0.5" />
+              <div class="flex-1 min-w-0">
+                <div class="font-bold mb-1">{t('warnings.crashReport.title')}</div>
+                <div class="text-sm mb-3 break-words">
+                  {t('warnings.crashReport.message', { message: current().message })}
+                </div>
+                <div class="flex flex-wrap gap-2">
+                  <button class="btn btn-sm btn-outline" onClick={copyReport}>
+                    {t('warnings.crashReport.copyReport')}
+                  </button>
+                  <button
+                    class="btn btn-sm btn-outline"
+                    onClick={() => openUrl(ISSUES_URL).catch(console.error)}
+                  >
+                    {t('warnings.crashReport.reportIssue')}
+                  </button>
+                  <button class="btn btn-sm btn-ghost" onClick={dismiss}>
+                    {t('warnings.crashReport.dismiss')}
+                  </button>
+                </div>
+              </div>
+              <button
+                class="btn btn-sm btn-circle btn-ghost ml-2"
+                onClick={dismiss}
+                aria-label={t('buttons.close')}
+              >
+                <X class="w-4 h-4" />
+              </button>
+            </div>
+          </div>
+        </div>
+      )}
+    </Show>
+  );
+};
+
+export default CrashReportNotice;
@@ -3,6 +3,7 @@ import { invoke } from "@tauri-apps/api/core";
 import { info, warn, error } from "@tauri-apps/plugin-log";
 import settingsStore from "../stores/settings";
 import Modal from "./common/Modal";
+import { CrashReport } from "./CrashReportNotice";
 
 interface DebugInfo {
     timestamp: string;
@@ -13,13 +14,44 @@ interface DebugInfo {
         cached_count: number;
         fingerprint: string | null;
     };
+    bench: {
+        searchTerm: string;
+        ranAt: string;
+        timings: {
+            name: string;
+            iterations: number;
+            items: number;
+            minMs: number;
+            meanMs: number;
+            maxMs: number;
+        }[];
+    } | null;
+    crash_reports: {
+        id: string;
+        timestamp: string;
+        kind: string;
+        message: string;
+        location: string | null;
+        has_minidump: boolean;
+    }[];
+}
+
+interface BackgroundTaskStatus {
+    name: string;
+    state: "running" | "restarting" | "completed";
+    restarts: number;
+    startedAt: string;
+    lastError: string | null;
+    lastErrorAt: string | null;
 }
 
 const DebugModal = () => {
     const [isOpen, setIsOpen] = createSignal(false);
     const [debugInfo, setDebugInfo] = createSignal<DebugInfo | null>(null);
+    const [backgroundTasks, setBackgroundTasks] = createSignal<BackgroundTaskStatus[]>([]);
     const [appLogs, setAppLogs] = createSignal<string>("");
     const [logFileContent, setLogFileContent] = createSignal<string>("");
+    const [crashReports, setCrashReports] = createSignal<CrashReport[]>([]);
     const [activeTab, setActiveTab] = createSignal<"info" | "logs">("info");
     const [isLoading, setIsLoading] = createSignal(false);
 
@@ -33,11 +65,16 @@ const DebugModal = () => {
             const debugData = await invoke<DebugInfo>("get_debug_info");
             setDebugInfo(debugData);
 
+            const tasks = await invoke<BackgroundTaskStatus[]>("get_background_tasks_status");
+            setBackgroundTasks(tasks);
+
             const logs = await invoke<string>("get_app_logs");
             setAppLogs(logs);
 
             const logFile = await invoke<string>("read_app_log_file");
             setLogFileContent(logFile);
+
+            setCrashReports(await invoke<CrashReport[]>("list_crash_reports"));
         } catch (e) {
             error(`Failed to fetch debug info: ${e}`);
         } finally {
@@ -65,6 +102,7 @@ const DebugModal = () => {
             debugInfo: debugInfo(),
             appLogs: appLogs(),
             logFileContent: logFileContent(),
+            crashReports: crashReports(),
         };
 
         await copyToClipboard(JSON.stringify(data, null, 2));
@@ -186,6 +224,51 @@ const DebugModal = () => {
                                             </div>
                                         </div>
                                     </div>
+                                    <div class="bg-base-200 p-2 rounded">
+                                        <strong>Background Tasks:</strong>
+                                        <div class="ml-4 mt-2">
+                                            {backgroundTasks().map((task) => (
+                                                <div>
+                                                    {task.name}: {task.state}, started {task.startedAt}
+                                                    {task.restarts > 0 && ` (${task.restarts} restarts)`}
+                                                    {task.lastError && (
+                                                        <div class="text-xs text-error break-all">
+                                                            Last error at {task.lastErrorAt}: {task.lastError}
+                                                        </div>
+                                                    )}
+                                                </div>
+                                            ))}
+                                        </div>
+                                    </div>
+                                    <Show when={info().bench}>
+                                        {(bench) => (
+                                            <div class="bg-base-200 p-2 rounded">
+                                                <strong>Benchmarks:</strong> {bench().ranAt} (search "{bench().searchTerm}")
+                                                <div class="ml-4 mt-2">
+                                                    {bench().timings.map((timing) => (
+                                                        <div>
+                                                            {timing.name}: {timing.meanMs.toFixed(1)}ms mean, {timing.minMs.toFixed(1)}–{timing.maxMs.toFixed(1)}ms over {timing.iterations} runs ({timing.items} items)
+                                                        </div>
+                                                    ))}
+                                                </div>
+                                            </div>
+                                        )}
+                                    </Show>
+
+                                    <Show when={info().crash_reports.length > 0}>
+                                        <div class="bg-base-200 p-2 rounded">
+                                            <strong>Crash Reports:</strong> (included in "Copy All Data")
+                                            <div class="ml-4 mt-2">
+                                                {info().crash_reports.map((report) => (
+                                                    <div class="break-all">
+                                                        {report.timestamp} [{report.kind}] {report.message}
+                                                        {report.location && ` at ${report.location}`}
+                                                        {report.has_minidump && " (minidump)"}
+                                                    </div>
+                                                ))}
+                                            </div>
+                                        </div>
+                                    </Show>
 
                                     {info().app_count === 0 && info().apps_dir_exists && (
                                         <div class="bg-warning p-3 rounded text-warning-content">
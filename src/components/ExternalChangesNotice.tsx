@@ -0,0 +1,67 @@
+import { createSignal, Show, onMount, onCleanup } from "solid-js";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
+import { Info, X } from "lucide-solid";
+import { ExternalChanges } from "../types/scoop";
+import quietMode from "../stores/quietMode";
+import { t } from "../i18n";
+
+// Tells the user about packages installed, removed or updated outside the app, e.g.
+// from a terminal. Changes arriving while the notice is open are merged into it.
+const ExternalChangesNotice = () => {
+  const [changes, setChanges] = createSignal<ExternalChanges | null>(null);
+  let unlisten: UnlistenFn | undefined;
+
+  onMount(async () => {
+    unlisten = await listen<ExternalChanges>("external-changes", (event) => {
+      const current = changes();
+      setChanges(current
+        ? {
+          installed: [...current.installed, ...event.payload.installed],
+          removed: [...current.removed, ...event.payload.removed],
+          updated: [...current.updated, ...event.payload.updated],
+        }
+        : event.payload);
+    });
+  });
+
+  onCleanup(() => unlisten?.());
+
+  return (
+    <Show when={!quietMode.active() && changes()}>
+      {(current) => (
+        <div class="fixed top-20 right-4 z-50 max-w-sm">
+          <div class="alert alert-info shadow-lg">
+            <div class="flex items-start gap-3">
+              <Info class="w-5 h-5 shrink-0 mt-0.5" />
+              <div class="flex-1 min-w-0 text-sm break-words">
+                <div class="font-bold mb-1">{t('warnings.externalChanges.title')}</div>
+                <Show when={current().installed.length > 0}>
+                  <div>{t('warnings.externalChanges.installed', { names: current().installed.join(", ") })}</div>
+                </Show>
+                <Show when={current().removed.length > 0}>
+                  <div>{t('warnings.externalChanges.removed', { names: current().removed.join(", ") })}</div>
+                </Show>
+                <Show when={current().updated.length > 0}>
+                  <div>
+                    {t('warnings.externalChanges.updated', {
+                      names: current().updated.map((change) => `${change.name} ${change.from} → ${change.to}`).join(", ")
+                    })}
+                  </div>
+                </Show>
+              </div>
+              <button
+                class="btn btn-sm btn-circle btn-ghost ml-2"
+                onClick={() => setChanges(null)}
+                aria-label={t('warnings.externalChanges.dismiss')}
+              >
+                <X class="w-4 h-4" />
+              </button>
+            </div>
+          </div>
+        </div>
+      )}
+    </Show>
+  );
+};
+
+export default ExternalChangesNotice;
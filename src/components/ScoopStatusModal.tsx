@@ -19,6 +19,12 @@ interface ScoopStatus {
   bucket_needs_update?: boolean;
   network_failure?: boolean;
   apps_with_issues?: AppWithIssue[];
+  self_update?: {
+    package_name: string;
+    current_version: string;
+    latest_version: string;
+    bucket: string;
+  } | null;
 }
 
 interface ScoopStatusModalProps {
@@ -141,6 +147,22 @@ function ScoopStatusModal(props: ScoopStatusModalProps) {
             </div>
           </Show>
 
+          {/* Newer Pailer in its Scoop bucket */}
+          <Show when={props.status!.self_update}>
+            {(update) => (
+              <div class="alert alert-info alert-outline">
+                <TriangleAlert class="w-4 h-4" />
+                <span>
+                  {t('scoopStatus.selfUpdateAvailable', {
+                    version: update().latest_version,
+                    bucket: update().bucket,
+                    command: `scoop update ${update().package_name}`,
+                  })}
+                </span>
+              </div>
+            )}
+          </Show>
+
           {/* Bucket Updates */}
           <Show when={props.status!.bucket_needs_update}>
             <div class="alert alert-warning alert-outline">
@@ -0,0 +1,43 @@
+import { createSignal, onMount } from "solid-js";
+import { invoke } from "@tauri-apps/api/core";
+import Checkup, { CheckupItem } from "./Checkup";
+import { t } from "../../../i18n";
+
+// Checks of Pailer itself: the web view, plugins and persisted files. None of the
+// items can be fixed from here, so there are no install or fix actions.
+function AppRuntimeCheckup() {
+    const [result, setResult] = createSignal<CheckupItem[]>([]);
+    const [isLoading, setIsLoading] = createSignal(true);
+    const [error, setError] = createSignal<string | null>(null);
+
+    const runCheckup = async () => {
+        setIsLoading(true);
+        setError(null);
+        try {
+            setResult(await invoke<CheckupItem[]>("run_app_runtime_checkup"));
+        } catch (err) {
+            console.error("Failed to run app runtime checkup:", err);
+            setError(t("doctor.appRuntime.error"));
+            setResult([]);
+        } finally {
+            setIsLoading(false);
+        }
+    };
+
+    onMount(runCheckup);
+
+    return (
+        <Checkup
+            title={t("doctor.appRuntime.title")}
+            description={t("doctor.appRuntime.description")}
+            checkupResult={result()}
+            isLoading={isLoading()}
+            error={error()}
+            onRerun={runCheckup}
+            onInstallHelper={() => {}}
+            installingHelper={null}
+        />
+    );
+}
+
+export default AppRuntimeCheckup;
@@ -1,8 +1,11 @@
 import { For, Show } from "solid-js";
-import { CircleCheckBig, CircleX, TriangleAlert, RefreshCw, Download } from "lucide-solid";
+import { CircleCheckBig, CircleX, TriangleAlert, RefreshCw, Download, Wrench } from "lucide-solid";
 import Card from "../../common/Card";
 import { t } from "../../../i18n";
 
+// Checkup item ids fixed by a backend action instead of installing a package.
+export const SHIMS_PATH_FIX_ID = "shimsPath";
+
 export interface CheckupItem {
     id: string | null;
     status: boolean;
@@ -12,6 +15,8 @@ export interface CheckupItem {
 }
 
 interface CheckupProps {
+    title?: string;
+    description?: string;
     checkupResult: CheckupItem[];
     isLoading: boolean;
     isRetrying?: boolean;
@@ -24,14 +29,14 @@ interface CheckupProps {
 function Checkup(props: CheckupProps) {
     return (
         <Card
-            title={t('doctor.checkup.title')}
+            title={props.title ?? t('doctor.checkup.title')}
             icon={CircleCheckBig}
             headerAction={
                 <button class="btn btn-ghost btn-sm" onClick={props.onRerun} disabled={props.isLoading}>
                     <RefreshCw classList={{ "animate-spin": props.isLoading }} />
                 </button>
             }
-            description={t('doctor.checkup.description')}
+            description={props.description ?? t('doctor.checkup.description')}
         >
             <Show when={props.isLoading}>
                 <div class="flex justify-center p-8">
@@ -62,14 +67,26 @@ function Checkup(props: CheckupProps) {
                                             onClick={() => props.onInstallHelper(item.id!)}
                                             disabled={!!props.installingHelper}
                                         >
-                                            <Show when={props.installingHelper === item.id} fallback={
-                                                <>
-                                                    <Download class="w-3 h-3 mr-1" />
-                                                    {t('doctor.checkup.install')}
-                                                </>
+                                            <Show when={item.id === SHIMS_PATH_FIX_ID} fallback={
+                                                <Show when={props.installingHelper === item.id} fallback={
+                                                    <>
+                                                        <Download class="w-3 h-3 mr-1" />
+                                                        {t('doctor.checkup.install')}
+                                                    </>
+                                                }>
+                                                    <span class="loading loading-spinner loading-xs"></span>
+                                                    {t('doctor.checkup.installing')}
+                                                </Show>
                                             }>
-                                                <span class="loading loading-spinner loading-xs"></span>
-                                                {t('doctor.checkup.installing')}
+                                                <Show when={props.installingHelper === item.id} fallback={
+                                                    <>
+                                                        <Wrench class="w-3 h-3 mr-1" />
+                                                        {t('doctor.checkup.fix')}
+                                                    </>
+                                                }>
+                                                    <span class="loading loading-spinner loading-xs"></span>
+                                                    {t('doctor.checkup.fixing')}
+                                                </Show>
                                             </Show>
                                         </button>
                                     </Show>
@@ -87,7 +87,16 @@ function ScoopInfo(props: ScoopInfoProps) {
 
         try {
             const config = JSON.parse(editConfig());
-            await invoke("update_scoop_config", { config });
+            if (config === null || typeof config !== "object" || Array.isArray(config)) {
+                throw new Error("The configuration must be a JSON object");
+            }
+            // Saving merges into the file, so keys removed in the editor are cleared explicitly
+            for (const key of Object.keys(scoopConfig() ?? {})) {
+                if (!(key in config)) {
+                    config[key] = null;
+                }
+            }
+            await invoke("update_scoop_config", { config, merge: true });
 
             // Refresh the config after saving
             await fetchScoopInfo(true); // Silent refresh
@@ -1,5 +1,5 @@
 import { For, Show, Accessor } from "solid-js";
-import { Ellipsis, ArrowUpCircle, Trash2, Lock, RefreshCw, ArrowLeftRight, LockOpen } from 'lucide-solid';
+import { Ellipsis, ArrowUpCircle, Trash2, Lock, Globe, RefreshCw, ArrowLeftRight, LockOpen } from 'lucide-solid';
 import type { DisplayPackage } from "../../../stores/installedPackagesStore";
 import type { ScoopPackage } from "../../../types/scoop";
 import heldStore from "../../../stores/held";
@@ -52,7 +52,7 @@ const PackageCard = (props: {
                   {pkg.name}
                 </div>
               </button>
-              <Show when={pkg.available_version && !heldStore.isHeld(pkg.name) && !pkg.is_versioned_install}>
+              <Show when={pkg.available_version && !heldStore.isHeld(pkg.name) && !pkg.is_versioned_install && pkg.scope !== 'global'}>
                 <div class="tooltip" data-tip={t("installed.list.updateAvailableTooltip", { version: pkg.available_version }) + (isCiVersion(pkg.available_version || '') ? t("installed.list.ciVersionNote") : '')}>
                   <ArrowUpCircle class="w-4 h-4 text-primary cursor-pointer transition-transform hover:scale-125 mr-1" onClick={(e) => { e.stopPropagation(); props.onUpdate(pkg); }} />
                 </div>
@@ -67,43 +67,50 @@ const PackageCard = (props: {
                   <Lock class="w-4 h-4 text-warning" />
                 </div>
               </Show>
+              <Show when={pkg.scope === 'global'}>
+                <div class="tooltip" data-tip={t("installed.list.globalTooltip")}>
+                  <Globe class="w-4 h-4 text-info" />
+                </div>
+              </Show>
             </h2>
           </div>
-          <div class="dropdown dropdown-end shrink-0" onClick={(e) => e.stopPropagation()}>
-            <label tabindex="0" class="btn btn-ghost btn-xs btn-circle bg-base-400">
-              <Ellipsis class="w-4 h-4" />
-            </label>
-            <ul tabindex="0" class="dropdown-content menu p-2 shadow bg-base-400 rounded-box w-44 z-1">
-              <li>
-                <HoldToggleButton
+          <Show when={pkg.scope !== 'global'}>
+            <div class="dropdown dropdown-end shrink-0" onClick={(e) => e.stopPropagation()}>
+              <label tabindex="0" class="btn btn-ghost btn-xs btn-circle bg-base-400">
+                <Ellipsis class="w-4 h-4" />
+              </label>
+              <ul tabindex="0" class="dropdown-content menu p-2 shadow bg-base-400 rounded-box w-44 z-1">
+                <li>
+                  <HoldToggleButton
+                    pkgName={pkg.name}
+                    isHeld={heldStore.isHeld(pkg.name)}
+                    isVersioned={pkg.is_versioned_install ?? false}
+                    operatingOn={props.operatingOn}
+                    onHold={props.onHold}
+                    onUnhold={props.onUnhold}
+                  />
+                </li>
+                <SwitchVersionButton
                   pkgName={pkg.name}
-                  isHeld={heldStore.isHeld(pkg.name)}
-                  isVersioned={pkg.is_versioned_install ?? false}
-                  operatingOn={props.operatingOn}
-                  onHold={props.onHold}
-                  onUnhold={props.onUnhold}
+                  isPackageVersioned={props.isPackageVersioned}
+                  onViewInfoForVersions={props.onViewInfoForVersions}
+                  pkg={pkg}
                 />
-              </li>
-              <SwitchVersionButton
-                pkgName={pkg.name}
-                isPackageVersioned={props.isPackageVersioned}
-                onViewInfoForVersions={props.onViewInfoForVersions}
-                pkg={pkg}
-              />
-              <li>
-                <a onClick={() => props.onChangeBucket(pkg)}>
-                  <ArrowLeftRight class="w-4 h-4 mr-2" />
-                  {t("installed.list.changeBucket")}
-                </a>
-              </li>
-              <li>
-                <a class="text-error" onClick={() => props.onUninstall(pkg)}>
-                  <Trash2 class="w-4 h-4 mr-2" />
-                  {t("installed.list.uninstall")}
-                </a>
-              </li>
-            </ul>
-          </div>
+                <li>
+                  <a onClick={() => props.onChangeBucket(pkg)}>
+                    <ArrowLeftRight class="w-4 h-4 mr-2" />
+                    {t("installed.list.changeBucket")}
+                  </a>
+                </li>
+                <li>
+                  <a class="text-error" onClick={() => props.onUninstall(pkg)}>
+                    <Trash2 class="w-4 h-4 mr-2" />
+                    {t("installed.list.uninstall")}
+                  </a>
+                </li>
+              </ul>
+            </div>
+          </Show>
         </div>
         <p class="text-sm text-base-content/70">
           {t("installed.grid.version")} {pkg.version}
@@ -1,6 +1,6 @@
 import { For, Show, Accessor } from "solid-js";
 import {
-  Ellipsis, CircleArrowUp, Trash2, ArrowUp, ArrowDown, Lock, Unlock, RefreshCw, ArrowLeftRight,
+  Ellipsis, CircleArrowUp, Trash2, ArrowUp, ArrowDown, Lock, Globe, Unlock, RefreshCw, ArrowLeftRight,
 } from 'lucide-solid';
 import type { DisplayPackage } from "../../../stores/installedPackagesStore";
 import type { ScoopPackage } from "../../../types/scoop";
@@ -139,7 +139,7 @@ function PackageListView(props: PackageListViewProps) {
                         {pkg.name}
                       </div>
                     </button>
-                    <Show when={pkg.available_version && !heldStore.isHeld(pkg.name) && !pkg.is_versioned_install}>
+                    <Show when={pkg.available_version && !heldStore.isHeld(pkg.name) && !pkg.is_versioned_install && pkg.scope !== 'global'}>
                       <div class="tooltip" data-tip={`Update available: ${pkg.available_version}${isCiVersion(pkg.available_version || '') ? ' (CI 版本，Scoop 可能无法自动更新)' : ''}`}>
                         <CircleArrowUp class="w-4 h-4 text-primary cursor-pointer transition-transform hover:scale-125 mr-1" onClick={() => props.onUpdate(pkg)} />
                       </div>
@@ -154,54 +154,61 @@ function PackageListView(props: PackageListViewProps) {
                         <Lock class="w-4 h-4 text-warning" />
                       </div>
                     </Show>
+                    <Show when={pkg.scope === 'global'}>
+                      <div class="tooltip" data-tip={t("installed.list.globalTooltip")}>
+                        <Globe class="w-4 h-4 text-info" />
+                      </div>
+                    </Show>
                   </div>
                 </td>
                 <td class="whitespace-nowrap">{pkg.version}</td>
                 <td class="whitespace-nowrap">{pkg.source}</td>
                 <td class="whitespace-nowrap" title={pkg.updated}>{formatIsoDate(pkg.updated)}</td>
                 <td class="text-center">
-                  <div
-                    class="dropdown dropdown-end"
-                    classList={{
-                      'dropdown-top': index() * 2 >= props.packages().length - 1,
-                    }}
-                    onClick={(e) => e.stopPropagation()}
-                  >
-                    <label tabindex="0" class="btn btn-ghost btn-xs btn-circle bg-base-400">
-                      <Ellipsis class="w-4 h-4" />
-                    </label>
-                    <ul tabindex="0" class="dropdown-content menu p-2 shadow bg-base-400 rounded-box w-44 z-100">
-                      <li>
-                        <HoldToggleButton
+                  <Show when={pkg.scope !== 'global'}>
+                    <div
+                      class="dropdown dropdown-end"
+                      classList={{
+                        'dropdown-top': index() * 2 >= props.packages().length - 1,
+                      }}
+                      onClick={(e) => e.stopPropagation()}
+                    >
+                      <label tabindex="0" class="btn btn-ghost btn-xs btn-circle bg-base-400">
+                        <Ellipsis class="w-4 h-4" />
+                      </label>
+                      <ul tabindex="0" class="dropdown-content menu p-2 shadow bg-base-400 rounded-box w-44 z-100">
+                        <li>
+                          <HoldToggleButton
+                            pkgName={pkg.name}
+                            isHeld={heldStore.isHeld(pkg.name)}
+                            isVersioned={!!pkg.is_versioned_install}
+                            operatingOn={props.operatingOn()}
+                            onHold={props.onHold}
+                            onUnhold={props.onUnhold}
+                          />
+                        </li>
+                        <SwitchVersionButton
                           pkgName={pkg.name}
-                          isHeld={heldStore.isHeld(pkg.name)}
-                          isVersioned={!!pkg.is_versioned_install}
-                          operatingOn={props.operatingOn()}
-                          onHold={props.onHold}
-                          onUnhold={props.onUnhold}
+                          isPackageVersioned={props.isPackageVersioned}
+                          onViewInfoForVersions={props.onViewInfoForVersions}
+                          pkg={pkg}
                         />
-                      </li>
-                      <SwitchVersionButton
-                        pkgName={pkg.name}
-                        isPackageVersioned={props.isPackageVersioned}
-                        onViewInfoForVersions={props.onViewInfoForVersions}
-                        pkg={pkg}
-                      />
-                      <li>
-                        <a onClick={() => props.onChangeBucket(pkg)}>
-                          <ArrowLeftRight class="w-4 h-4 mr-2" />
-                          {t("installed.list.changeBucket")}
-                        </a>
-                      </li>
-                      <li>
-                        <a class="text-error" onClick={() => props.onUninstall(pkg)}>
-                          <Trash2 class="w-4 h-4 mr-2" />
-                          {t("installed.list.uninstall")}
-                        </a>
-                      </li>
-                    </ul>
+                        <li>
+                          <a onClick={() => props.onChangeBucket(pkg)}>
+                            <ArrowLeftRight class="w-4 h-4 mr-2" />
+                            {t("installed.list.changeBucket")}
+                          </a>
+                        </li>
+                        <li>
+                          <a class="text-error" onClick={() => props.onUninstall(pkg)}>
+                            <Trash2 class="w-4 h-4 mr-2" />
+                            {t("installed.list.uninstall")}
+                          </a>
+                        </li>
+                      </ul>
 
-                  </div>
+                    </div>
+                  </Show>
                 </td>
               </tr>
             )}
@@ -81,6 +81,16 @@ function SearchResultsList(props: SearchResultsListProps) {
                                         {pkg.info}
                                     </p>
                                 </Show>
+                                <Show when={pkg.license || pkg.is_64bit_only}>
+                                    <div class="flex flex-wrap gap-2 mt-2">
+                                        <Show when={pkg.license}>
+                                            <span class="badge badge-ghost badge-sm">{pkg.license}</span>
+                                        </Show>
+                                        <Show when={pkg.is_64bit_only}>
+                                            <span class="badge badge-ghost badge-sm">{t("search.results.only64bit")}</span>
+                                        </Show>
+                                    </div>
+                                </Show>
                             </div>
                         </div>
                     )}
@@ -15,6 +15,19 @@ export interface SearchableBucket {
   is_verified: boolean;
 }
 
+export interface BucketFilterRule {
+  field: "name" | "description" | "owner" | "any";
+  pattern: string;
+  action: "include" | "exclude";
+}
+
+export interface BucketFilterOptions {
+  disable_chinese_buckets?: boolean;
+  minimum_stars?: number;
+  excluded_scripts?: string[];
+  rules?: BucketFilterRule[];
+}
+
 export interface BucketSearchRequest {
   query?: string;
   include_expanded: boolean;
@@ -22,6 +35,9 @@ export interface BucketSearchRequest {
   sort_by?: string;
   disable_chinese_buckets?: boolean;
   minimum_stars?: number;
+  excluded_scripts?: string[];
+  rules?: BucketFilterRule[];
+  filter_preset?: string;
 }
 
 export interface BucketSearchResponse {
@@ -8,6 +8,7 @@ export interface BucketInfo {
   is_git_repo: boolean;
   git_url?: string;
   git_branch?: string;
+  head_commit?: string;
   last_updated?: string;
 }
 
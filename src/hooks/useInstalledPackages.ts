@@ -30,6 +30,12 @@ interface ScoopStatus {
   network_failure: boolean;
   apps_with_issues: AppStatusInfo[];
   is_everything_ok: boolean;
+  self_update?: {
+    package_name: string;
+    current_version: string;
+    latest_version: string;
+    bucket: string;
+  } | null;
 }
 
 export function useInstalledPackages() {
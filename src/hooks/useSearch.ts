@@ -1,7 +1,7 @@
 import { createSignal, createEffect, on, Setter, onMount, createMemo } from "solid-js";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { ScoopPackage, ScoopInfo } from "../types/scoop";
+import { ScoopPackage, ScoopInfo, CacheInvalidation } from "../types/scoop";
 import { usePackageOperations } from "./usePackageOperations";
 import { usePackageInfo } from "./usePackageInfo";
 import { OperationNextStep } from "../types/operations";
@@ -83,9 +83,15 @@ export function useSearch(): UseSearchReturn {
         restoreSearchResults();
         const unlistenBuckets = await listen("buckets-changed", () => setCacheVersion(v => v + 1));
         const unlistenPackages = await listen("packages-refreshed", () => setCacheVersion(v => v + 1));
+        const unlistenInvalidated = await listen<CacheInvalidation>("cache-invalidated", (event) => {
+            if (event.payload.manifests) {
+                setCacheVersion(v => v + 1);
+            }
+        });
         return () => {
             unlistenBuckets();
             unlistenPackages();
+            unlistenInvalidated();
         };
     });
 
@@ -217,7 +223,7 @@ export function useSearch(): UseSearchReturn {
         }
     };
 
-    const packageResults = () => results().filter((p) => p.match_source === "name");
+    const packageResults = () => results().filter((p) => p.match_source === "name" || p.match_source === "fuzzy");
     const binaryResults = () => results().filter((p) => p.match_source === "binary");
     const resultsToShow = () => {
         const filteredResults = activeTab() === "packages" ? packageResults() : binaryResults();
@@ -1,5 +1,8 @@
 /* @refresh reload */
 import { render } from "solid-js/web";
 import App from "./App";
+import { installCommandTiming } from "./utils/commandTiming";
+
+installCommandTiming();
 
 render(() => <App />, document.getElementById("root")!);
@@ -1,7 +1,8 @@
 import { createSignal, onMount, createMemo, Show, onCleanup } from "solid-js";
 import { invoke } from "@tauri-apps/api/core";
 import { openPath } from "@tauri-apps/plugin-opener";
-import Checkup, { CheckupItem } from "../components/page/doctor/Checkup";
+import Checkup, { CheckupItem, SHIMS_PATH_FIX_ID } from "../components/page/doctor/Checkup";
+import AppRuntimeCheckup from "../components/page/doctor/AppRuntimeCheckup";
 import Cleanup from "../components/page/doctor/Cleanup";
 import CacheManager from "../components/page/doctor/CacheManager";
 import ShimManager from "../components/page/doctor/ShimManager";
@@ -102,6 +103,11 @@ function DoctorPage() {
             return;
         }
         try {
+            if (helperId === SHIMS_PATH_FIX_ID) {
+                await invoke("fix_shims_path");
+                await runCheckup();
+                return;
+            }
             await invoke("install_package", { packageName: helperId, bucket: '' });
             await runCheckup();
             installedPackagesStore.refetch();
@@ -249,6 +255,7 @@ function DoctorPage() {
                     <Show when={!needsAttention() && (isCheckupLoading() || checkupResult().length > 0 || checkupError())}>
                         {checkupComponent}
                     </Show>
+                    <AppRuntimeCheckup />
                 </div>
             </div>
             <OperationModal
@@ -1,6 +1,13 @@
 import { createSignal, createRoot } from "solid-js";
 import { invoke } from "@tauri-apps/api/core";
-import { ScoopPackage, UpdatablePackage } from "../types/scoop";
+import { listen } from "@tauri-apps/api/event";
+import {
+  CacheInvalidation,
+  InstalledCacheInfo,
+  InstalledPackagesResult,
+  ScoopPackage,
+  UpdatablePackage,
+} from "../types/scoop";
 import heldStore from "./held";
 
 export interface DisplayPackage extends ScoopPackage {
@@ -15,6 +22,7 @@ function createInstalledPackagesStore() {
   const [isLoaded, setIsLoaded] = createSignal(false);
   const [isCheckingForUpdates, setIsCheckingForUpdates] = createSignal(false);
   const [versionedPackages, setVersionedPackages] = createSignal<string[]>([]);
+  const [cacheInfo, setCacheInfo] = createSignal<InstalledCacheInfo | null>(null);
 
 const checkForUpdates = async () => {
   setIsCheckingForUpdates(true);
@@ -44,6 +52,29 @@ const fetchVersionedPackages = async () => {
   }
 };
 
+const fetchCacheInfo = async () => {
+  try {
+    setCacheInfo(await invoke<InstalledCacheInfo>("get_installed_cache_info"));
+  } catch (err) {
+    console.error("Failed to fetch installed cache info:", err);
+  }
+};
+
+// Returns true when the backend cache changed since this store last loaded it.
+const isStale = async () => {
+  const current = cacheInfo();
+  if (!current) {
+    return false;
+  }
+  try {
+    const latest = await invoke<InstalledCacheInfo>("get_installed_cache_info");
+    return latest.generation !== current.generation;
+  } catch (err) {
+    console.error("Failed to fetch installed cache info:", err);
+    return false;
+  }
+};
+
 const fetchInstalledPackages = async () => {
   if (isLoaded() || loading()) {
     return;
@@ -52,8 +83,10 @@ const fetchInstalledPackages = async () => {
   setLoading(true);
   setError(null);
   try {
-    const installedPackages = await invoke<ScoopPackage[]>("get_installed_packages_full");
+    const result = await invoke<InstalledPackagesResult>("get_installed_packages_full");
+    const installedPackages = result.packages;
     setPackages(installedPackages);
+    setCacheInfo(result.cache_info);
     const buckets = new Set<string>(installedPackages.map(p => p.source));
     setUniqueBuckets(['all', ...Array.from(buckets).sort()]);
     setIsLoaded(true);
@@ -79,6 +112,7 @@ const refetch = async () => {
   try {
     const installedPackages = await invoke<ScoopPackage[]>("refresh_installed_packages");
     setPackages(installedPackages);
+    await fetchCacheInfo();
     const buckets = new Set<string>(installedPackages.map(p => p.source));
     setUniqueBuckets(['all', ...Array.from(buckets).sort()]);
     setIsLoaded(true);
@@ -114,6 +148,7 @@ const silentRefetch = async () => {
 
     // 一次性设置完整数据
     setPackages(packagesWithUpdates);
+    await fetchCacheInfo();
     const buckets = new Set<string>(installedPackages.map(p => p.source));
     setUniqueBuckets(['all', ...Array.from(buckets).sort()]);
     setIsLoaded(true);
@@ -130,6 +165,13 @@ const silentRefetch = async () => {
   }
 }
 
+  // Installs changed outside the app, e.g. from a terminal
+  listen<CacheInvalidation>("cache-invalidated", (event) => {
+    if (event.payload.installed && isLoaded() && !loading()) {
+      silentRefetch();
+    }
+  });
+
   const isPackageVersioned = (packageName: string) => {
     return versionedPackages().includes(packageName);
   };
@@ -142,6 +184,8 @@ const silentRefetch = async () => {
     isLoaded,
     isCheckingForUpdates,
     versionedPackages,
+    cacheInfo,
+    isStale,
     isPackageVersioned,
     fetch: fetchInstalledPackages,
     refetch,
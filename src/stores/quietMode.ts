@@ -0,0 +1,27 @@
+import { createRoot, createSignal } from "solid-js";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+interface QuietModeStatus {
+  manual: boolean;
+  auto_detect: boolean;
+  foreground_busy: boolean;
+  active: boolean;
+}
+
+// Whether background work is paused for do-not-disturb; toasts wait meanwhile
+function createQuietModeStore() {
+  const [active, setActive] = createSignal(false);
+
+  invoke<QuietModeStatus>("get_quiet_mode_status")
+    .then((status) => setActive(status.active))
+    .catch((err) => console.error("Failed to get quiet mode status:", err));
+
+  listen<boolean>("quiet-mode-changed", (event) => setActive(event.payload)).catch((err) =>
+    console.error("Failed to listen for quiet mode changes:", err)
+  );
+
+  return { active };
+}
+
+export default createRoot(createQuietModeStore);
@@ -14,7 +14,13 @@ let storeInitialized = false;
 /// Get or initialize the shared store instance
 export async function getSettingsStore(): Promise<Store> {
   if (!globalStore) {
-    globalStore = await Store.load(STORE_NAME);
+    // In portable mode the store lives next to the executable, so load it by absolute path
+    const portable = await invoke<{ portable: boolean; data_dir: string | null }>("get_portable_info")
+      .catch(() => null);
+    const storePath = portable?.portable && portable.data_dir
+      ? `${portable.data_dir}\\${STORE_NAME}`
+      : STORE_NAME;
+    globalStore = await Store.load(storePath);
     console.log('Tauri store for frontend settings loaded successfully');
   }
   return globalStore;
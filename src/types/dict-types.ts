@@ -141,6 +141,11 @@ export interface Dict {
       shimName: string;
       title: string;
     };
+    appRuntime: {
+      description: string;
+      error: string;
+      title: string;
+    };
     cacheManager: {
       actionCannotBeUndone: string;
       cacheIsEmpty: string;
@@ -158,6 +163,8 @@ export interface Dict {
     };
     checkup: {
       description: string;
+      fix: string;
+      fixing: string;
       install: string;
       installing: string;
       items: {
@@ -165,7 +172,16 @@ export interface Dict {
         helperInstalled: string;
         longPathsEnabled: string;
         mainBucketInstalled: string;
+        persistedFilesValid: string;
+        pluginLog: string;
+        pluginStore: string;
+        pluginUpdater: string;
+        runtimeInstalled: string;
         scoopOnNtfs: string;
+        shimsOnPath: string;
+        shimsPathPriority: string;
+        webviewProfileWritable: string;
+        webviewRuntime: string;
         windowsDeveloperModeEnabled: string;
       };
       suggestion: string;
@@ -262,6 +278,7 @@ export interface Dict {
       cannotUnhold: string;
       changeBucket: string;
       ciVersionNote: string;
+      globalTooltip: string;
       heldTooltip: string;
       holdPackage: string;
       name: string;
@@ -347,6 +364,7 @@ export interface Dict {
     errorCheckingStatus: string;
     networkFailure: string;
     scoopOutOfDate: string;
+    selfUpdateAvailable: string;
     table: {
       installed: string;
       latest: string;
@@ -368,6 +386,7 @@ export interface Dict {
     results: {
       fromBucket: string;
       noPackagesFound: string;
+      only64bit: string;
       pageInfo: string;
     };
     tabs: {
@@ -611,6 +630,20 @@ export interface Dict {
   updateChannel: {
   };
   warnings: {
+    crashReport: {
+      copyReport: string;
+      dismiss: string;
+      message: string;
+      reportIssue: string;
+      title: string;
+    };
+    externalChanges: {
+      dismiss: string;
+      installed: string;
+      removed: string;
+      title: string;
+      updated: string;
+    };
     multiInstance: {
       dontShowAgain: string;
       message: string;
@@ -37,6 +37,29 @@ export interface OperationResult {
   timestamp: number;
 }
 
+// 操作进度里程碑
+export type MilestoneStage =
+  | 'started'
+  | 'loading'
+  | 'downloading'
+  | 'verifying'
+  | 'extracting'
+  | 'installing'
+  | 'linking'
+  | 'creating-shims'
+  | 'persisting'
+  | 'done';
+
+export interface OperationMilestone {
+  operationId?: string;
+  stage: MilestoneStage;
+  package?: string;
+  current?: number;
+  total?: number;
+  summary: string;
+  at: string;
+}
+
 // 操作状态
 export type OperationStatus = 'in-progress' | 'success' | 'error' | 'cancelled';
 
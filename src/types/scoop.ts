@@ -5,9 +5,69 @@ export interface ScoopPackage {
   updated: string;
   is_installed: boolean;
   info: string;
-  match_source: "name" | "binary" | "none" | "path";
+  match_source: "name" | "binary" | "fuzzy" | "description" | "homepage" | "license" | "bucket" | "none" | "path";
   available_version?: string;
   is_versioned_install?: boolean;
+  scope?: "user" | "global";
+  /** Search relevance from 0 to 100, set on search results. */
+  score?: number | null;
+  /** Manifest details, set on search results. */
+  homepage?: string;
+  license?: string;
+  is_64bit_only?: boolean;
+}
+
+/** Restrictions passed as `filters` to `search_scoop`; empty lists allow anything. */
+export interface SearchFilters {
+  buckets?: string[];
+  licenses?: string[];
+  /** e.g. "64bit"; packages without per-architecture downloads always qualify. */
+  architecture?: string | null;
+}
+
+/** A past query from `get_search_history`. */
+export interface SearchHistoryEntry {
+  query: string;
+  count: number;
+  /** Unix seconds. */
+  lastUsed: number;
+}
+
+/** An autocomplete entry from `get_search_suggestions`. */
+export interface SearchSuggestion {
+  text: string;
+  source: "history" | "package";
+}
+
+export interface InstalledCacheInfo {
+  generation: number;
+  /** Milliseconds since the Unix epoch, or null before the first scan. */
+  last_scan_at: number | null;
+}
+
+/** Payload of the `cache-invalidated` event, sent when Scoop's directories change on disk. */
+export interface CacheInvalidation {
+  installed: boolean;
+  manifests: boolean;
+}
+
+/** A package whose installed version changed between two scans. */
+export interface VersionChange {
+  name: string;
+  from: string;
+  to: string;
+}
+
+/** Payload of the `external-changes` event, sent when packages change outside the app. */
+export interface ExternalChanges {
+  installed: string[];
+  removed: string[];
+  updated: VersionChange[];
+}
+
+export interface InstalledPackagesResult {
+  packages: ScoopPackage[];
+  cache_info: InstalledCacheInfo;
 }
 
 export interface ScoopInfo {
@@ -39,4 +99,23 @@ export interface VersionedPackageInfo {
   available_versions: PackageVersion[];
 }
 
+export type PackageActivity =
+  | "idle"
+  | "queued"
+  | "installing"
+  | "updating"
+  | "uninstalling"
+  | "clearing_cache"
+  | "failed"
+  | "held";
+
+/** Payload of `get_package_states` and the `package-state-changed` event. */
+export interface PackageState {
+  name: string;
+  state: PackageActivity;
+  operation_id: string | null;
+  message: string | null;
+  updated_at: number;
+}
+
 export type View = "search" | "installed" | "bucket" | "settings" | "doctor"; 
\ No newline at end of file
@@ -0,0 +1,54 @@
+// Times every IPC call from `invoke` until its promise settles and reports the
+// results in batches, for the command timings shown by `get_debug_info`.
+
+interface CommandTiming {
+  command: string;
+  durationMs: number;
+  success: boolean;
+}
+
+const REPORT_COMMAND = "record_command_timings";
+const FLUSH_INTERVAL_MS = 5000;
+
+type InvokeFn = (cmd: string, args?: unknown, options?: unknown) => Promise<unknown>;
+
+let pending: CommandTiming[] = [];
+
+/** Wraps the Tauri IPC entry point; calls made through `@tauri-apps/api` all pass through it. */
+export function installCommandTiming() {
+  const internals = (window as any).__TAURI_INTERNALS__;
+  if (!internals?.invoke) {
+    return;
+  }
+  const original: InvokeFn = internals.invoke.bind(internals);
+
+  internals.invoke = (cmd: string, args?: unknown, options?: unknown) => {
+    if (cmd === REPORT_COMMAND) {
+      return original(cmd, args, options);
+    }
+    const started = performance.now();
+    const settle = (success: boolean) =>
+      pending.push({ command: cmd, durationMs: performance.now() - started, success });
+    return original(cmd, args, options).then(
+      (value) => {
+        settle(true);
+        return value;
+      },
+      (error) => {
+        settle(false);
+        throw error;
+      }
+    );
+  };
+
+  setInterval(() => {
+    if (pending.length === 0) {
+      return;
+    }
+    const timings = pending;
+    pending = [];
+    original(REPORT_COMMAND, { timings }).catch((error) =>
+      console.warn("Failed to report command timings:", error)
+    );
+  }, FLUSH_INTERVAL_MS);
+}